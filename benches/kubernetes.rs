@@ -0,0 +1,157 @@
+//! Compares enriching the events of a high-throughput Pod with owned copies of its metadata
+//! strings, and with interned ones.
+//!
+//! The allocations per event are counted by the allocator of the benchmark, which can't replace
+//! jemalloc, so they are only reported when built without it:
+//!
+//! ```sh
+//! cargo bench --no-default-features --features kubernetes-benches --bench kubernetes
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use vector::{event::LogEvent, kubernetes::interner::StringInterner};
+use vrl::event_path;
+
+/// The number of events the Pod writes per iteration.
+const EVENTS: usize = 10_000;
+
+/// The metadata of the Pod, as enriched onto each of its events.
+const POD_FIELDS: [(&str, &str); 10] = [
+    ("pod_name", "checkout-7d4b9c6f5-x2x9q"),
+    ("pod_namespace", "production"),
+    ("pod_uid", "4b1c2a3e-5f6d-4e7a-8b9c-0d1e2f3a4b5c"),
+    ("pod_node_name", "ip-10-0-12-34.ec2.internal"),
+    ("pod_ip", "10.0.12.57"),
+    ("container_name", "checkout"),
+    (
+        "container_id",
+        "containerd://9f3a1c0e5b7d2f4a6c8e0b1d3f5a7c9e1b3d5f7a9c1e3b5d7f9a1c3e5b7d9f1a",
+    ),
+    (
+        "container_image",
+        "registry.example.com/shop/checkout:2024.06.1",
+    ),
+    ("app", "checkout"),
+    ("team", "payments"),
+];
+
+#[cfg(not(feature = "tikv-jemallocator"))]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    pub struct CountingAllocator;
+
+    pub static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOC: CountingAllocator = CountingAllocator;
+
+    /// Returns the number of allocations made by `f`.
+    pub fn count(f: impl FnOnce()) -> Option<usize> {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        f();
+        Some(ALLOCATIONS.load(Ordering::Relaxed) - before)
+    }
+}
+
+#[cfg(feature = "tikv-jemallocator")]
+mod counting {
+    pub fn count(f: impl FnOnce()) -> Option<usize> {
+        f();
+        None
+    }
+}
+
+fn events() -> Vec<LogEvent> {
+    (0..EVENTS)
+        .map(|i| LogEvent::from(format!("handled request {}", i)))
+        .collect()
+}
+
+fn enrich_owned(events: &mut [LogEvent]) {
+    for log in events {
+        for (key, value) in POD_FIELDS {
+            log.insert(event_path!("kubernetes", key), value.to_owned());
+        }
+    }
+}
+
+fn enrich_interned(events: &mut [LogEvent], interner: &mut StringInterner) {
+    for log in events {
+        for (key, value) in POD_FIELDS {
+            log.insert(event_path!("kubernetes", key), interner.intern(value));
+        }
+    }
+}
+
+fn report_allocations() {
+    let mut owned = events();
+    let mut interned = events();
+    let mut interner = StringInterner::new(4096);
+    let owned = counting::count(|| enrich_owned(&mut owned));
+    let interned = counting::count(|| enrich_interned(&mut interned, &mut interner));
+
+    if let (Some(owned), Some(interned)) = (owned, interned) {
+        #[allow(clippy::print_stdout)]
+        {
+            println!(
+                "kubernetes/enrich allocations per event: owned {:.1}, interned {:.1}",
+                owned as f64 / EVENTS as f64,
+                interned as f64 / EVENTS as f64,
+            );
+        }
+    }
+}
+
+fn bench_enrich(c: &mut Criterion) {
+    report_allocations();
+
+    let mut group = c.benchmark_group("kubernetes/enrich");
+    group.sample_size(10);
+
+    group.bench_function(BenchmarkId::new("high_throughput_pod", "owned"), |b| {
+        b.iter_batched(
+            events,
+            |mut events| {
+                enrich_owned(&mut events);
+                events
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    let mut interner = StringInterner::new(4096);
+    group.bench_function(BenchmarkId::new("high_throughput_pod", "interned"), |b| {
+        b.iter_batched(
+            events,
+            |mut events| {
+                enrich_interned(&mut events, &mut interner);
+                events
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().noise_threshold(0.02);
+    targets = bench_enrich
+);
+criterion_main!(benches);
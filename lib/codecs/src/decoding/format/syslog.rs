@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use chrono::{DateTime, Datelike, Utc};
+use chrono::{DateTime, Datelike, Local, Utc};
 use derivative::Derivative;
 use lookup::{event_path, owned_value_path, OwnedTargetPath, OwnedValuePath};
 use smallvec::{smallvec, SmallVec};
@@ -12,6 +12,7 @@ use vector_core::{
     event::{Event, LogEvent, ObjectMap, Value},
     schema,
 };
+use vrl::compiler::TimeZone;
 use vrl::value::{kind::Collection, Kind};
 
 use super::{default_lossy, Deserializer};
@@ -23,6 +24,9 @@ pub struct SyslogDeserializerConfig {
     #[serde(skip)]
     source: Option<&'static str>,
 
+    #[serde(skip)]
+    timezone: Option<TimeZone>,
+
     /// Syslog-specific decoding options.
     #[serde(
         default,
@@ -36,6 +40,7 @@ impl SyslogDeserializerConfig {
     pub fn new(options: SyslogDeserializerOptions) -> Self {
         Self {
             source: None,
+            timezone: None,
             syslog: options,
         }
     }
@@ -48,10 +53,19 @@ impl SyslogDeserializerConfig {
         }
     }
 
+    /// Sets the timezone the timestamps without one, like the RFC 3164 ones, are in.
+    ///
+    /// Defaults to the local time.
+    pub fn with_timezone(mut self, timezone: TimeZone) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
     /// Build the `SyslogDeserializer` from this configuration.
     pub const fn build(&self) -> SyslogDeserializer {
         SyslogDeserializer {
             source: self.source,
+            timezone: self.timezone,
             lossy: self.syslog.lossy,
         }
     }
@@ -266,6 +280,7 @@ pub struct SyslogDeserializer {
     /// syslog codec since it needs to handle the structured of the decoded data
     /// differently when using the Vector lognamespace.
     pub source: Option<&'static str>,
+    timezone: Option<TimeZone>,
     #[derivative(Default(value = "default_lossy()"))]
     lossy: bool,
 }
@@ -281,8 +296,17 @@ impl Deserializer for SyslogDeserializer {
             false => Cow::from(std::str::from_utf8(&bytes)?),
         };
         let line = line.trim();
-        let parsed =
-            syslog_loose::parse_message_with_year_exact(line, resolve_year, Variant::Either)?;
+        let timezone = self.timezone.unwrap_or(TimeZone::Local);
+        let tz = match timezone {
+            TimeZone::Local => None,
+            TimeZone::Named(tz) => Some(tz),
+        };
+        let parsed = syslog_loose::parse_message_with_year_exact_tz(
+            line,
+            |date| resolve_year(date, timezone),
+            tz,
+            Variant::Either,
+        )?;
 
         let log = match (self.source, log_namespace) {
             (Some(source), LogNamespace::Vector) => {
@@ -304,16 +328,31 @@ impl Deserializer for SyslogDeserializer {
 /// Function used to resolve the year for syslog messages that don't include the
 /// year.
 ///
+/// The current year is taken in the timezone the messages are in, so that the
+/// messages sent around New Year are dated as the sender did.
+///
 /// If the current month is January, and the syslog message is for December, it
-/// will take the previous year.
+/// will take the previous year. If the current month is December, and the
+/// syslog message is for January, the sender's clock is ahead, and it will take
+/// the next year.
 ///
 /// Otherwise, take the current year.
-fn resolve_year((month, _date, _hour, _min, _sec): IncompleteDate) -> i32 {
+fn resolve_year(date: IncompleteDate, timezone: TimeZone) -> i32 {
     let now = Utc::now();
-    if now.month() == 1 && month == 12 {
-        now.year() - 1
-    } else {
-        now.year()
+    match timezone {
+        TimeZone::Local => resolve_year_at(date, now.with_timezone(&Local)),
+        TimeZone::Named(tz) => resolve_year_at(date, now.with_timezone(&tz)),
+    }
+}
+
+fn resolve_year_at<Tz: chrono::TimeZone>(
+    (month, _date, _hour, _min, _sec): IncompleteDate,
+    now: DateTime<Tz>,
+) -> i32 {
+    match (now.month(), month) {
+        (1, 12) => now.year() - 1,
+        (12, 1) => now.year() + 1,
+        _ => now.year(),
     }
 }
 
@@ -484,6 +523,7 @@ fn insert_fields_from_syslog(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{TimeZone as _, Timelike};
     use vector_core::config::{init_log_schema, log_schema, LogSchema};
 
     #[test]
@@ -519,6 +559,67 @@ mod tests {
         assert!(events[0].as_log()["timestamp"].is_timestamp());
     }
 
+    #[test]
+    fn deserialize_syslog_rfc3164_timezone() {
+        let input = Bytes::from("<34>Oct 11 22:14:15 mymachine su: MSG");
+        let deserializer = SyslogDeserializerConfig::from_source("syslog")
+            .with_timezone(TimeZone::Named(chrono_tz::America::Chicago))
+            .build();
+
+        let events = deserializer.parse(input, LogNamespace::Vector).unwrap();
+        let timestamp = *events[0]
+            .as_log()
+            .get("%syslog.timestamp")
+            .unwrap()
+            .as_timestamp()
+            .unwrap();
+        // Chicago is on CDT, UTC-5, in October.
+        assert_eq!(
+            (timestamp.month(), timestamp.day(), timestamp.hour()),
+            (10, 12, 3)
+        );
+    }
+
+    #[test]
+    fn deserialize_syslog_rfc5424_ignores_timezone() {
+        let input =
+            Bytes::from("<34>1 2003-10-11T22:14:15.003Z mymachine.example.com su - ID47 - MSG");
+        let deserializer = SyslogDeserializerConfig::from_source("syslog")
+            .with_timezone(TimeZone::Named(chrono_tz::America::Chicago))
+            .build();
+
+        let events = deserializer.parse(input, LogNamespace::Vector).unwrap();
+        assert_eq!(
+            events[0].as_log().get("%syslog.timestamp").unwrap(),
+            &Value::from(
+                DateTime::parse_from_rfc3339("2003-10-11T22:14:15.003Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+    }
+
+    #[test]
+    fn resolve_year_around_new_year() {
+        let tz = chrono_tz::America::Chicago;
+        let new_year = tz.with_ymd_and_hms(2024, 1, 1, 0, 5, 0).unwrap();
+        let new_years_eve = tz.with_ymd_and_hms(2023, 12, 31, 23, 55, 0).unwrap();
+
+        // A message from December 31 received on January 1.
+        assert_eq!(resolve_year_at((12, 31, 23, 59, 0), new_year), 2023);
+        // A message from January 1 received on December 31.
+        assert_eq!(resolve_year_at((1, 1, 0, 1, 0), new_years_eve), 2024);
+        assert_eq!(resolve_year_at((1, 1, 0, 1, 0), new_year), 2024);
+        assert_eq!(resolve_year_at((6, 1, 0, 0, 0), new_year), 2024);
+
+        // It's still December 31 in UTC, but already January 1 in Tokyo.
+        let now = Utc
+            .with_ymd_and_hms(2023, 12, 31, 20, 0, 0)
+            .unwrap()
+            .with_timezone(&chrono_tz::Asia::Tokyo);
+        assert_eq!(resolve_year_at((1, 1, 4, 59, 0), now), 2024);
+    }
+
     fn init() {
         let mut schema = LogSchema::default();
         schema.set_message_key(Some(OwnedTargetPath::event(owned_value_path!(
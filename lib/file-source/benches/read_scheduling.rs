@@ -0,0 +1,173 @@
+use std::{
+    fs,
+    io::Error,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use file_source::{
+    paths_provider::PathsProvider, Checkpointer, Compression, FileServer, FileSourceInternalEvents,
+    FingerprintStrategy, Fingerprinter, Line, ReadFrom,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    executor::block_on,
+    StreamExt,
+};
+use tempfile::{tempdir, TempDir};
+
+const BUSY_LINES: usize = 20_000;
+const QUIET_FILES: usize = 50;
+const QUIET_LINES: usize = 10;
+
+struct StaticPaths(Vec<PathBuf>);
+
+impl PathsProvider for StaticPaths {
+    type IntoIter = Vec<PathBuf>;
+
+    fn paths(&self) -> Self::IntoIter {
+        self.0.clone()
+    }
+}
+
+#[derive(Clone)]
+struct NoopEmitter;
+
+impl FileSourceInternalEvents for NoopEmitter {
+    fn emit_file_added(&self, _: &Path) {}
+
+    fn emit_file_resumed(&self, _: &Path, _: u64) {}
+
+    fn emit_file_watch_error(&self, _: &Path, _: Error) {}
+
+    fn emit_file_unwatched(&self, _: &Path) {}
+
+    fn emit_file_deleted(&self, _: &Path) {}
+
+    fn emit_file_delete_error(&self, _: &Path, _: Error) {}
+
+    fn emit_file_archived(&self, _: &Path, _: &Path) {}
+
+    fn emit_file_archive_error(&self, _: &Path, _: Error) {}
+
+    fn emit_file_fingerprint_read_error(&self, _: &Path, _: Error) {}
+
+    fn emit_file_checkpointed(&self, _: usize, _: Duration) {}
+
+    fn emit_file_checksum_failed(&self, _: &Path) {}
+
+    fn emit_file_checkpoint_write_error(&self, _: Error) {}
+
+    fn emit_files_open(&self, _: usize) {}
+
+    fn emit_files_throttled(&self, _: usize) {}
+
+    fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {}
+}
+
+fn write_lines(path: &Path, name: &str, count: usize) {
+    let contents: String = (0..count)
+        .map(|i| format!("{} line {:06} {}\n", name, i, "x".repeat(80)))
+        .collect();
+    fs::write(path, contents).unwrap();
+}
+
+/// Writes one busy file, followed by a number of quiet files.
+fn setup() -> (TempDir, Vec<PathBuf>) {
+    let dir = tempdir().unwrap();
+    let busy = dir.path().join("busy.log");
+    write_lines(&busy, "busy", BUSY_LINES);
+    let mut paths = vec![busy];
+    for i in 0..QUIET_FILES {
+        let quiet = dir.path().join(format!("quiet-{}.log", i));
+        write_lines(&quiet, &format!("quiet-{}", i), QUIET_LINES);
+        paths.push(quiet);
+    }
+    (dir, paths)
+}
+
+/// Reads the files until all the lines of the quiet files were read, which is how long the busy
+/// file holds them back.
+fn read_quiet_files(paths: Vec<PathBuf>, max_read_bytes_per_file: Option<usize>) {
+    let data_dir = tempdir().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let file_server = FileServer {
+        paths_provider: StaticPaths(paths),
+        max_read_bytes: 2048,
+        max_read_bytes_per_file,
+        max_read_bytes_per_second: None,
+        ignore_checkpoints: true,
+        read_from: ReadFrom::Beginning,
+        ignore_before: None,
+        max_line_bytes: 1024,
+        truncate_long_lines: false,
+        line_delimiter: "\n".into(),
+        compression: Compression::None,
+        data_dir: data_dir.path().to_path_buf(),
+        glob_minimum_cooldown: Duration::from_secs(60),
+        fingerprinter: Fingerprinter {
+            strategy: FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 0,
+                lines: 1,
+            },
+            max_line_length: 1024,
+            ignore_not_found: false,
+            compression: Compression::None,
+        },
+        oldest_first: true,
+        remove_after: None,
+        archive_to_dir: None,
+        emitter: NoopEmitter,
+        handle: runtime.handle().clone(),
+    };
+
+    let (tx, mut rx) = mpsc::unbounded::<Vec<Line>>();
+    let (shutdown_data, shutdown_data_rx) = oneshot::channel::<()>();
+    let (shutdown_checkpointer, shutdown_checkpointer_rx) = oneshot::channel::<()>();
+    let checkpointer = Checkpointer::new(data_dir.path());
+    let server = thread::spawn(move || {
+        file_server
+            .run(tx, shutdown_data_rx, shutdown_checkpointer_rx, checkpointer)
+            .unwrap();
+    });
+
+    let mut quiet_lines = 0;
+    while quiet_lines < QUIET_FILES * QUIET_LINES {
+        let batch = block_on(rx.next()).unwrap();
+        quiet_lines += batch
+            .iter()
+            .filter(|line| line.text.starts_with(b"quiet"))
+            .count();
+    }
+    shutdown_data.send(()).unwrap();
+    shutdown_checkpointer.send(()).unwrap();
+    server.join().unwrap();
+}
+
+fn read_scheduling_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("file-source/read_scheduling");
+    group.sample_size(10);
+
+    for (name, max_read_bytes_per_file) in [("oldest_first", None), ("fair", Some(2048))] {
+        group.bench_with_input(
+            BenchmarkId::new("quiet_files_latency", name),
+            &max_read_bytes_per_file,
+            |b, max_read_bytes_per_file| {
+                b.iter_batched(
+                    setup,
+                    |(_dir, paths)| read_quiet_files(paths, *max_read_bytes_per_file),
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(name = benches;
+                 config = Criterion::default();
+                 targets = read_scheduling_bench);
+criterion_main!(benches);
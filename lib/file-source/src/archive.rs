@@ -0,0 +1,153 @@
+use std::{
+    cmp, fs, io,
+    path::{Component, Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// The delay before the first retry of archiving a file.
+const MIN_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The maximum delay between the retries of archiving a file.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Returns the path the file is archived at, which is the path of the file relative to the
+/// root, within the archive directory.
+pub(crate) fn archive_path(path: &Path, archive_dir: &Path) -> PathBuf {
+    let relative_path = path
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect::<PathBuf>();
+    archive_dir.join(relative_path)
+}
+
+/// Whether the file is within the archive directory, so it was archived and mustn't be watched
+/// again, even if it matches the include patterns.
+pub(crate) fn is_archived(path: &Path, archive_dir: &Path) -> bool {
+    path.starts_with(archive_dir)
+}
+
+/// Moves the file into the archive directory, returning the path it was archived at.
+///
+/// The file is renamed if possible, and otherwise copied and removed, like when the archive
+/// directory is on another filesystem.
+pub(crate) fn archive_file(path: &Path, archive_dir: &Path) -> io::Result<PathBuf> {
+    let archive_path = archive_path(path, archive_dir);
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match fs::rename(path, &archive_path) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Err(error),
+        Err(_) => {
+            fs::copy(path, &archive_path)?;
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(archive_path)
+}
+
+/// Backs off the retries of archiving a file that failed to be archived.
+#[derive(Debug)]
+pub(crate) struct ArchiveRetry {
+    failures: u32,
+    next_attempt: Instant,
+}
+
+impl ArchiveRetry {
+    pub(crate) fn new() -> Self {
+        Self {
+            failures: 0,
+            next_attempt: Instant::now(),
+        }
+    }
+
+    /// Whether archiving the file should be attempted again.
+    pub(crate) fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_attempt
+    }
+
+    /// Records a failed attempt, doubling the delay before the next one.
+    pub(crate) fn failed(&mut self, now: Instant) {
+        let backoff = MIN_RETRY_BACKOFF.saturating_mul(2u32.saturating_pow(self.failures));
+        self.failures = self.failures.saturating_add(1);
+        self.next_attempt = now + cmp::min(backoff, MAX_RETRY_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_archive_path() {
+        let cases = vec![
+            ("/var/log/app.log", "/archive/var/log/app.log"),
+            ("var/log/app.log", "/archive/var/log/app.log"),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                archive_path(Path::new(path), Path::new("/archive")),
+                PathBuf::from(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_archived() {
+        let archive_dir = Path::new("/var/log/archive");
+        assert!(is_archived(
+            Path::new("/var/log/archive/var/log/app.log"),
+            archive_dir
+        ));
+        assert!(!is_archived(Path::new("/var/log/app.log"), archive_dir));
+        assert!(!is_archived(Path::new("/var/log/archive.log"), archive_dir));
+    }
+
+    #[test]
+    fn test_archive_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("logs").join("app.log");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "hello\n").unwrap();
+        let archive_dir = dir.path().join("archive");
+
+        let archived = archive_file(&path, &archive_dir).unwrap();
+
+        assert_eq!(archived, archive_path(&path, &archive_dir));
+        assert!(archived.starts_with(&archive_dir));
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(archived).unwrap(), "hello\n");
+
+        // A file that is gone can't be archived.
+        assert_eq!(
+            archive_file(&path, &archive_dir).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_archive_retry_backoff() {
+        let start = Instant::now();
+        let mut retry = ArchiveRetry::new();
+        assert!(retry.is_due(start));
+
+        retry.failed(start);
+        assert!(!retry.is_due(start));
+        assert!(retry.is_due(start + Duration::from_secs(1)));
+
+        retry.failed(start);
+        assert!(!retry.is_due(start + Duration::from_secs(1)));
+        assert!(retry.is_due(start + Duration::from_secs(2)));
+
+        for _ in 0..20 {
+            retry.failed(start);
+        }
+        assert!(retry.is_due(start + MAX_RETRY_BACKOFF));
+    }
+}
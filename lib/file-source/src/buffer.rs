@@ -92,6 +92,75 @@ pub fn read_until_with_max_size<R: BufRead + ?Sized>(
     }
 }
 
+/// Read up to `max_size` bytes from `reader`, splitting by `delim`, like
+/// [`read_until_with_max_size`] does, but truncate the lines longer than
+/// `max_size` instead of discarding them.
+///
+/// Once the line in `buf` exceeds `max_size` bytes, its first `max_size` bytes
+/// are returned right away, without waiting for `delim`, and the rest of the
+/// line is skipped, so a long line is never buffered in full. As the rest may
+/// not be available yet, `skipping` keeps track of it across the calls, and
+/// has to be kept along with `buf` by the caller.
+///
+/// In the Ok case, if the inner value is Some, a line was written into `buf`
+/// and the inner `bool` tells whether it was truncated. None has the same
+/// meaning as for [`read_until_with_max_size`].
+pub fn read_until_with_max_size_truncating<R: BufRead + ?Sized>(
+    reader: &mut R,
+    position: &mut FilePosition,
+    delim: &[u8],
+    buf: &mut BytesMut,
+    max_size: usize,
+    skipping: &mut bool,
+) -> io::Result<Option<bool>> {
+    let delim_finder = Finder::new(delim);
+    let delim_len = delim.len();
+    loop {
+        let available: &[u8] = match reader.fill_buf() {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok(None);
+        }
+
+        let found = delim_finder.find(available);
+        let (line_len, used) = match found {
+            Some(i) => (i, i + delim_len),
+            None => (available.len(), available.len()),
+        };
+
+        if *skipping {
+            // The rest of a truncated line is dropped, up to and including `delim`.
+            *skipping = found.is_none();
+            reader.consume(used);
+            *position += used as u64;
+            continue;
+        }
+
+        let remaining = max_size.saturating_sub(buf.len());
+        if line_len > remaining {
+            warn!(
+                message = "Found line that exceeds max_line_bytes; truncating.",
+                internal_log_rate_limit = true
+            );
+            buf.extend_from_slice(&available[..remaining]);
+            *skipping = found.is_none();
+            reader.consume(used);
+            *position += used as u64;
+            return Ok(Some(true));
+        }
+
+        buf.extend_from_slice(&available[..line_len]);
+        reader.consume(used);
+        *position += used as u64;
+        if found.is_some() {
+            return Ok(Some(false));
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{io::Cursor, num::NonZeroU8, ops::Range};
@@ -99,7 +168,7 @@ mod test {
     use bytes::{BufMut, BytesMut};
     use quickcheck::{QuickCheck, TestResult};
 
-    use super::read_until_with_max_size;
+    use super::{read_until_with_max_size, read_until_with_max_size_truncating};
 
     fn qc_inner(chunks: Vec<Vec<u8>>, delim: u8, max_size: NonZeroU8) -> TestResult {
         // The `global_data` is the view of `chunks` as a single contiguous
@@ -230,4 +299,44 @@ mod test {
             .max_tests(2_000)
             .quickcheck(qc_inner as fn(Vec<Vec<u8>>, u8, NonZeroU8) -> TestResult);
     }
+
+    #[test]
+    fn read_until_with_max_size_truncating_lines() {
+        // The input is fed in chunks, so that the line exceeding the max size
+        // is only terminated in a later chunk.
+        let chunks: [&[u8]; 4] = [b"short\nthis line is", b" way too long", b"\nok\n", b"tail"];
+
+        let mut position = 0;
+        let mut buffer = BytesMut::new();
+        let mut skipping = false;
+        let mut lines = Vec::new();
+        for chunk in chunks {
+            let mut reader = Cursor::new(chunk);
+            while let Some(truncated) = read_until_with_max_size_truncating(
+                &mut reader,
+                &mut position,
+                b"\n",
+                &mut buffer,
+                10,
+                &mut skipping,
+            )
+            .unwrap()
+            {
+                lines.push((buffer.split().freeze(), truncated));
+            }
+        }
+
+        assert_eq!(
+            lines,
+            vec![
+                (b"short".as_slice().into(), false),
+                (b"this line ".as_slice().into(), true),
+                (b"ok".as_slice().into(), false),
+            ]
+        );
+        // The incomplete last line is kept for the next read.
+        assert_eq!(&buffer[..], b"tail");
+        assert!(!skipping);
+        assert_eq!(position, 39);
+    }
 }
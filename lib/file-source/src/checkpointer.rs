@@ -19,15 +19,43 @@ use super::{
 const TMP_FILE_NAME: &str = "checkpoints.new.json";
 pub const CHECKPOINT_FILE_NAME: &str = "checkpoints.json";
 
-/// This enum represents the file format of checkpoints persisted to disk. Right
-/// now there is only one variant, but any incompatible changes will require and
-/// additional variant to be added here and handled anywhere that we transit
-/// this format.
+/// This enum represents the file format of checkpoints persisted to disk. Any
+/// incompatible changes require an additional variant to be added here, along
+/// with its upgrade from the previous variant in [`State::upgrade`]. Only the
+/// latest variant is ever written, older ones are upgraded once they are read.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "version", rename_all = "snake_case")]
 enum State {
     #[serde(rename = "1")]
-    V1 { checkpoints: BTreeSet<Checkpoint> },
+    V1 { checkpoints: BTreeSet<CheckpointV1> },
+    /// Adds the time the file was found to be removed, so that the checkpoints
+    /// of removed files still expire after a restart.
+    #[serde(rename = "2")]
+    V2 { checkpoints: BTreeSet<Checkpoint> },
+}
+
+impl State {
+    /// Upgrades the state to the latest version.
+    fn upgrade(self) -> State {
+        match self {
+            State::V1 { checkpoints } => State::V2 {
+                checkpoints: checkpoints
+                    .into_iter()
+                    .map(|checkpoint| Checkpoint {
+                        fingerprint: checkpoint.fingerprint,
+                        position: checkpoint.position,
+                        modified: checkpoint.modified,
+                        removed: None,
+                    })
+                    .collect(),
+            },
+            state @ State::V2 { .. } => state,
+        }
+    }
+
+    const fn is_latest(&self) -> bool {
+        matches!(self, State::V2 { .. })
+    }
 }
 
 /// A simple JSON-friendly struct of the fingerprint/position pair, since
@@ -38,6 +66,17 @@ struct Checkpoint {
     fingerprint: FileFingerprint,
     position: FilePosition,
     modified: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    removed: Option<DateTime<Utc>>,
+}
+
+/// The checkpoint of the version 1 format.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+struct CheckpointV1 {
+    fingerprint: FileFingerprint,
+    position: FilePosition,
+    modified: DateTime<Utc>,
 }
 
 pub struct Checkpointer {
@@ -122,11 +161,15 @@ impl CheckpointsView {
             .insert(checkpoint.fingerprint, checkpoint.position);
         self.modified_times
             .insert(checkpoint.fingerprint, checkpoint.modified);
+        if let Some(removed) = checkpoint.removed {
+            self.removed_times.insert(checkpoint.fingerprint, removed);
+        }
     }
 
     fn set_state(&self, state: State, ignore_before: Option<DateTime<Utc>>) {
-        match state {
-            State::V1 { checkpoints } => {
+        match state.upgrade() {
+            State::V1 { .. } => unreachable!("the state is upgraded to the latest version"),
+            State::V2 { checkpoints } => {
                 for checkpoint in checkpoints {
                     if let Some(ignore_before) = ignore_before {
                         if checkpoint.modified < ignore_before {
@@ -140,7 +183,7 @@ impl CheckpointsView {
     }
 
     fn get_state(&self) -> State {
-        State::V1 {
+        State::V2 {
             checkpoints: self
                 .checkpoints
                 .iter()
@@ -155,6 +198,7 @@ impl CheckpointsView {
                             .get(fingerprint)
                             .map(|r| *r.value())
                             .unwrap_or_else(Utc::now),
+                        removed: self.removed_times.get(fingerprint).map(|r| *r.value()),
                     }
                 })
                 .collect(),
@@ -194,6 +238,13 @@ impl CheckpointsView {
                     self.update(fng, pos);
                 }
             }
+            if let Ok(Some(fingerprint)) =
+                fingerprinter.get_raw_first_lines_checksum(path, fingerprint_buffer)
+            {
+                if let Some((_, pos)) = self.checkpoints.remove(&fingerprint) {
+                    self.update(fng, pos);
+                }
+            }
         }
     }
 }
@@ -232,6 +283,7 @@ impl Checkpointer {
             BytesChecksum(c) => format!("g{:x}.{}", c, pos),
             FirstLinesChecksum(c) => format!("h{:x}.{}", c, pos),
             DevInode(dev, ino) => format!("i{:x}.{:x}.{}", dev, ino, pos),
+            IdentityChecksum(c) => format!("j{:x}.{}", c, pos),
             Unknown(x) => format!("{:x}.{}", x, pos),
         };
         self.directory.join(path)
@@ -244,28 +296,33 @@ impl Checkpointer {
     /// format. Because hex encoding only allows [0-9a-f], we can use any
     /// character outside of that range as a magic byte identifier for the newer
     /// formats.
-    fn decode(&self, path: &Path) -> (FileFingerprint, FilePosition) {
+    ///
+    /// Returns `None` for the file names that are not valid encodings.
+    fn decode(&self, path: &Path) -> Option<(FileFingerprint, FilePosition)> {
         use FileFingerprint::*;
 
-        let file_name = &path.file_name().unwrap().to_string_lossy();
-        match file_name.chars().next().expect("empty file name") {
+        let file_name = &path.file_name()?.to_string_lossy();
+        match file_name.chars().next()? {
             'g' => {
-                let (c, pos) = scan_fmt!(file_name, "g{x}.{}", [hex u64], FilePosition).unwrap();
-                (BytesChecksum(c), pos)
+                let (c, pos) = scan_fmt!(file_name, "g{x}.{}", [hex u64], FilePosition).ok()?;
+                Some((BytesChecksum(c), pos))
             }
             'h' => {
-                let (c, pos) = scan_fmt!(file_name, "h{x}.{}", [hex u64], FilePosition).unwrap();
-                (FirstLinesChecksum(c), pos)
+                let (c, pos) = scan_fmt!(file_name, "h{x}.{}", [hex u64], FilePosition).ok()?;
+                Some((FirstLinesChecksum(c), pos))
             }
             'i' => {
                 let (dev, ino, pos) =
-                    scan_fmt!(file_name, "i{x}.{x}.{}", [hex u64], [hex u64], FilePosition)
-                        .unwrap();
-                (DevInode(dev, ino), pos)
+                    scan_fmt!(file_name, "i{x}.{x}.{}", [hex u64], [hex u64], FilePosition).ok()?;
+                Some((DevInode(dev, ino), pos))
+            }
+            'j' => {
+                let (c, pos) = scan_fmt!(file_name, "j{x}.{}", [hex u64], FilePosition).ok()?;
+                Some((IdentityChecksum(c), pos))
             }
             _ => {
-                let (c, pos) = scan_fmt!(file_name, "{x}.{}", [hex u64], FilePosition).unwrap();
-                (Unknown(c), pos)
+                let (c, pos) = scan_fmt!(file_name, "{x}.{}", [hex u64], FilePosition).ok()?;
+                Some((Unknown(c), pos))
             }
         }
     }
@@ -344,7 +401,11 @@ impl Checkpointer {
     /// Read persisted checkpoints from disk, preferring the new JSON file
     /// format but falling back to the legacy system when those files are found
     /// instead.
-    pub fn read_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) {
+    ///
+    /// Returns whether any persisted checkpoints were found, that is whether a
+    /// previous run checkpointed its files. The checkpoints that fail to load
+    /// are not counted as found.
+    pub fn read_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) -> bool {
         // First try reading from the tmp file location. If this works, it means
         // that the previous process was interrupted in the process of
         // checkpointing and the tmp file should contain more recent data that
@@ -359,7 +420,7 @@ impl Checkpointer {
                 if let Err(error) = fs::rename(&self.tmp_file_path, &self.stable_file_path) {
                     warn!(message = "Error persisting recovered checkpoint file.", %error);
                 }
-                return;
+                return true;
             }
             Err(error) if error.kind() == io::ErrorKind::NotFound => {
                 // This is expected, so no warning needed
@@ -372,29 +433,33 @@ impl Checkpointer {
         // Next, attempt to read checkpoints from the stable file location. This
         // is the expected location, so warn more aggressively if something goes
         // wrong.
-        match self.read_checkpoints_file(&self.stable_file_path) {
+        match self.upgrade_checkpoints_file(&self.stable_file_path) {
             Ok(state) => {
                 info!(message = "Loaded checkpoint data.");
                 self.checkpoints.set_state(state, ignore_before);
-                return;
+                return true;
             }
             Err(error) if error.kind() == io::ErrorKind::NotFound => {
                 // This is expected, so no warning needed
             }
             Err(error) => {
-                warn!(message = "Unable to load checkpoint data.", %error);
-                return;
+                // The corrupt checkpoints are discarded, and replaced by the
+                // next ones written. None of them can be trusted, so the files
+                // are read as if no previous run checkpointed them.
+                warn!(message = "Unable to load checkpoint data, discarding it.", %error);
+                return false;
             }
         }
 
         // If we haven't returned yet, go ahead and look for the legacy files
         // and try to read them.
         info!("Attempting to read legacy checkpoint files.");
-        self.read_legacy_checkpoints(ignore_before);
+        let found = self.read_legacy_checkpoints(ignore_before);
 
         if self.write_checkpoints().is_ok() {
             fs::remove_dir_all(&self.directory).ok();
         }
+        found
     }
 
     fn read_checkpoints_file(&self, path: &Path) -> Result<State, io::Error> {
@@ -402,8 +467,34 @@ impl Checkpointer {
         serde_json::from_reader(reader).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
 
-    fn read_legacy_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) {
+    /// Reads the checkpoints file, upgrading it in place if it is in an older
+    /// format.
+    ///
+    /// The upgraded file replaces the old one the same way new checkpoints are
+    /// persisted, so that a crash while upgrading leaves either of them intact.
+    fn upgrade_checkpoints_file(&self, path: &Path) -> Result<State, io::Error> {
+        let state = self.read_checkpoints_file(path)?;
+        if state.is_latest() {
+            return Ok(state);
+        }
+
+        let state = state.upgrade();
+        let mut f = io::BufWriter::new(fs::File::create(&self.tmp_file_path)?);
+        serde_json::to_writer(&mut f, &state)?;
+        f.into_inner()?.sync_all()?;
+        fs::rename(&self.tmp_file_path, path)?;
+        info!(
+            message = "Upgraded checkpoint data to the latest format.",
+            ?path
+        );
+
+        Ok(state)
+    }
+
+    fn read_legacy_checkpoints(&mut self, ignore_before: Option<DateTime<Utc>>) -> bool {
+        let mut found = false;
         for path in glob(&self.glob_string).unwrap().flatten() {
+            found = true;
             let mut mtime = None;
             if let Some(ignore_before) = ignore_before {
                 if let Ok(Ok(modified)) = fs::metadata(&path).map(|metadata| metadata.modified()) {
@@ -415,24 +506,37 @@ impl Checkpointer {
                     mtime = Some(modified);
                 }
             }
-            let (fng, pos) = self.decode(&path);
+            let (fng, pos) = match self.decode(&path) {
+                Some(decoded) => decoded,
+                None => {
+                    warn!(
+                        message = "Discarding invalid legacy checkpoint file.",
+                        ?path
+                    );
+                    continue;
+                }
+            };
             self.checkpoints.checkpoints.insert(fng, pos);
             if let Some(mtime) = mtime {
                 self.checkpoints.modified_times.insert(fng, mtime);
             }
         }
+        found
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::io::Write;
+
     use chrono::{Duration, Utc};
+    use flate2::{write::GzEncoder, Compression as GzCompression};
     use similar_asserts::assert_eq;
     use tempfile::tempdir;
 
     use super::{
-        super::{FingerprintStrategy, Fingerprinter},
-        Checkpoint, Checkpointer, FileFingerprint, FilePosition, CHECKPOINT_FILE_NAME,
+        super::{Compression, FingerprintStrategy, Fingerprinter},
+        Checkpoint, Checkpointer, FileFingerprint, FilePosition, State, CHECKPOINT_FILE_NAME,
         TMP_FILE_NAME,
     };
 
@@ -442,6 +546,7 @@ mod test {
             FileFingerprint::DevInode(1, 2),
             FileFingerprint::BytesChecksum(3456),
             FileFingerprint::FirstLinesChecksum(78910),
+            FileFingerprint::IdentityChecksum(111213),
             FileFingerprint::Unknown(1337),
         ];
         for fingerprint in fingerprints {
@@ -450,7 +555,7 @@ mod test {
             let mut chkptr = Checkpointer::new(data_dir.path());
             assert_eq!(
                 chkptr.decode(&chkptr.encode(fingerprint, position)),
-                (fingerprint, position)
+                Some((fingerprint, position))
             );
             chkptr.update_checkpoint(fingerprint, position);
             assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
@@ -489,6 +594,7 @@ mod test {
                     fingerprint: *fingerprint,
                     position,
                     modified: *modified,
+                    removed: None,
                 });
                 assert_eq!(chkptr.get_checkpoint(*fingerprint), Some(position));
                 chkptr.write_checkpoints().unwrap();
@@ -513,6 +619,7 @@ mod test {
             FileFingerprint::DevInode(1, 2),
             FileFingerprint::BytesChecksum(3456),
             FileFingerprint::FirstLinesChecksum(78910),
+            FileFingerprint::IdentityChecksum(111213),
             FileFingerprint::Unknown(1337),
         ];
         for fingerprint in fingerprints {
@@ -533,6 +640,132 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpointer_read_checkpoints_found() {
+        let data_dir = tempdir().unwrap();
+        {
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            assert!(!chkptr.read_checkpoints(None));
+            chkptr.write_checkpoints().unwrap();
+        }
+        {
+            // Even without any files to checkpoint, a previous run persisted its checkpoints.
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            assert!(chkptr.read_checkpoints(None));
+        }
+    }
+
+    #[test]
+    fn test_checkpointer_removed_times_restart() {
+        let removed = Utc::now() - Duration::seconds(90);
+        let data_dir = tempdir().unwrap();
+        {
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            chkptr.update_checkpoint(FileFingerprint::DevInode(1, 2), 1234);
+            chkptr.update_checkpoint(FileFingerprint::DevInode(3, 4), 1234);
+            chkptr
+                .checkpoints
+                .removed_times
+                .insert(FileFingerprint::DevInode(1, 2), removed);
+            chkptr.write_checkpoints().unwrap();
+        }
+        {
+            // The checkpoint of the file removed before the restart still expires.
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            chkptr.read_checkpoints(None);
+            chkptr.write_checkpoints().unwrap();
+            assert_eq!(chkptr.get_checkpoint(FileFingerprint::DevInode(1, 2)), None);
+            assert_eq!(
+                chkptr.get_checkpoint(FileFingerprint::DevInode(3, 4)),
+                Some(1234)
+            );
+        }
+    }
+
+    #[test]
+    fn test_checkpointer_upgrades_v1_file() {
+        let data_dir = tempdir().unwrap();
+        let stable_file_path = data_dir.path().join(CHECKPOINT_FILE_NAME);
+        std::fs::write(
+            &stable_file_path,
+            r#"{"version":"1","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":1234,"modified":"2021-07-12T18:19:11.769003Z"}]}"#,
+        )
+        .unwrap();
+
+        let mut chkptr = Checkpointer::new(data_dir.path());
+        assert!(chkptr.read_checkpoints(None));
+        assert_eq!(
+            chkptr.get_checkpoint(FileFingerprint::DevInode(1, 2)),
+            Some(1234)
+        );
+
+        // The file was upgraded in place.
+        assert!(!data_dir.path().join(TMP_FILE_NAME).exists());
+        let state: State =
+            serde_json::from_str(&std::fs::read_to_string(&stable_file_path).unwrap()).unwrap();
+        assert_eq!(
+            state,
+            State::V2 {
+                checkpoints: [Checkpoint {
+                    fingerprint: FileFingerprint::DevInode(1, 2),
+                    position: 1234,
+                    modified: "2021-07-12T18:19:11.769003Z".parse().unwrap(),
+                    removed: None,
+                }]
+                .into_iter()
+                .collect(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_checkpointer_discards_corrupt_checkpoints() {
+        let cases = vec![
+            "",
+            "{",
+            r#"{"version":"1"}"#,
+            r#"{"version":"99","checkpoints":[]}"#,
+            r#"{"version":"2","checkpoints":[{"fingerprint":{"dev_inode":[1,2]}}]}"#,
+        ];
+
+        for contents in cases {
+            let data_dir = tempdir().unwrap();
+            std::fs::write(data_dir.path().join(CHECKPOINT_FILE_NAME), contents).unwrap();
+
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            assert!(!chkptr.read_checkpoints(None), "{:?}", contents);
+            assert_eq!(chkptr.checkpoints.checkpoints.len(), 0, "{:?}", contents);
+
+            // The corrupt file is replaced by the next checkpoints written.
+            chkptr.update_checkpoint(FileFingerprint::DevInode(1, 2), 1234);
+            chkptr.write_checkpoints().unwrap();
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            chkptr.read_checkpoints(None);
+            assert_eq!(
+                chkptr.get_checkpoint(FileFingerprint::DevInode(1, 2)),
+                Some(1234)
+            );
+        }
+    }
+
+    #[test]
+    fn test_checkpointer_discards_invalid_legacy_checkpoints() {
+        let data_dir = tempdir().unwrap();
+        let mut chkptr = Checkpointer::new(data_dir.path());
+        chkptr.update_checkpoint(FileFingerprint::DevInode(1, 2), 1234);
+        chkptr.write_legacy_checkpoints().unwrap();
+        std::fs::File::create(data_dir.path().join("checkpoints").join("iinvalid")).unwrap();
+        std::fs::File::create(data_dir.path().join("checkpoints").join("zzz")).unwrap();
+
+        let mut chkptr = Checkpointer::new(data_dir.path());
+        assert!(chkptr.read_checkpoints(None));
+        assert_eq!(
+            chkptr.get_checkpoint(FileFingerprint::DevInode(1, 2)),
+            Some(1234)
+        );
+        assert_eq!(chkptr.checkpoints.checkpoints.len(), 1);
+    }
+
     #[test]
     fn test_checkpointer_fingerprint_upgrades_unknown() {
         let log_dir = tempdir().unwrap();
@@ -547,6 +780,7 @@ mod test {
             strategy: FingerprintStrategy::DevInode,
             max_line_length: 1000,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let mut buf = Vec::new();
@@ -588,6 +822,7 @@ mod test {
             },
             max_line_length: 102400,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let mut buf = Vec::new();
@@ -611,6 +846,60 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_checkpointer_fingerprint_upgrades_raw_compressed_checksum() {
+        let log_dir = tempdir().unwrap();
+        let path = log_dir.path().join("test.log.gz");
+        let data: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+        let mut gzipped = GzEncoder::new(Vec::new(), GzCompression::default());
+        gzipped.write_all(data.as_bytes()).unwrap();
+        std::fs::write(&path, gzipped.finish().unwrap()).unwrap();
+
+        let fingerprinter = |compression| Fingerprinter {
+            strategy: FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 0,
+                lines: 1,
+            },
+            max_line_length: 102400,
+            ignore_not_found: false,
+            compression,
+        };
+
+        let mut buf = Vec::new();
+        // Before the compressed files were decompressed for fingerprinting,
+        // they were fingerprinted by their raw bytes.
+        let old_fingerprint = fingerprinter(Compression::None)
+            .get_fingerprint_of_file(&path, &mut buf)
+            .unwrap();
+        let new_fingerprint = fingerprinter(Compression::Auto)
+            .get_fingerprint_of_file(&path, &mut buf)
+            .unwrap();
+        assert_ne!(old_fingerprint, new_fingerprint);
+        let position: FilePosition = 1234;
+
+        let data_dir = tempdir().unwrap();
+        {
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            chkptr.update_checkpoint(old_fingerprint, position);
+            chkptr.write_checkpoints().ok();
+        }
+        {
+            let mut chkptr = Checkpointer::new(data_dir.path());
+            chkptr.read_checkpoints(None);
+            assert_eq!(chkptr.get_checkpoint(new_fingerprint), None);
+
+            chkptr.maybe_upgrade(
+                &path,
+                new_fingerprint,
+                &fingerprinter(Compression::Auto),
+                &mut buf,
+            );
+
+            assert_eq!(chkptr.get_checkpoint(new_fingerprint), Some(position));
+            assert_eq!(chkptr.get_checkpoint(old_fingerprint), None);
+        }
+    }
+
     #[test]
     fn test_checkpointer_fingerprint_upgrades_legacy_first_lines_checksum() {
         let log_dir = tempdir().unwrap();
@@ -629,6 +918,7 @@ mod test {
             },
             max_line_length: 102400,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let mut buf = Vec::new();
@@ -744,6 +1034,7 @@ mod test {
             },
             max_line_length: 1024,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let log_path = data_dir.path().join("test.log");
@@ -788,19 +1079,19 @@ mod test {
         let fingerprints = vec![
             (
                 FileFingerprint::DevInode(1, 2),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":1234}]}"#,
+                r#"{"version":"2","checkpoints":[{"fingerprint":{"dev_inode":[1,2]},"position":1234}]}"#,
             ),
             (
                 FileFingerprint::BytesChecksum(3456),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"checksum":3456},"position":1234}]}"#,
+                r#"{"version":"2","checkpoints":[{"fingerprint":{"checksum":3456},"position":1234}]}"#,
             ),
             (
                 FileFingerprint::FirstLinesChecksum(78910),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"first_lines_checksum":78910},"position":1234}]}"#,
+                r#"{"version":"2","checkpoints":[{"fingerprint":{"first_lines_checksum":78910},"position":1234}]}"#,
             ),
             (
                 FileFingerprint::Unknown(1337),
-                r#"{"version":"1","checkpoints":[{"fingerprint":{"unknown":1337},"position":1234}]}"#,
+                r#"{"version":"2","checkpoints":[{"fingerprint":{"unknown":1337},"position":1234}]}"#,
             ),
         ];
         for (fingerprint, expected) in fingerprints {
@@ -868,6 +1159,7 @@ mod test {
             FileFingerprint::BytesChecksum(3456),
             FileFingerprint::FirstLinesChecksum(1234),
             FileFingerprint::FirstLinesChecksum(78910),
+            FileFingerprint::IdentityChecksum(111213),
             FileFingerprint::Unknown(1337),
         ];
 
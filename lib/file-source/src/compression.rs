@@ -0,0 +1,143 @@
+use std::io::{self, BufRead};
+
+use flate2::bufread::MultiGzDecoder;
+use vector_config::configurable_component;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression of the files to read.
+///
+/// Compressed files are read once, from the beginning of the uncompressed data to its end, and
+/// are not expected to be appended to. Their checkpoints refer to the offset in the uncompressed
+/// data.
+#[configurable_component]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// Detect the compression of each file from its first bytes.
+    ///
+    /// Files starting with the gzip or zstd magic bytes are decompressed, all other files are
+    /// read as-is.
+    #[default]
+    Auto,
+
+    /// Read all files as-is.
+    None,
+
+    /// Decompress all files as gzip.
+    Gzip,
+
+    /// Decompress all files as zstd.
+    Zstd,
+}
+
+/// The algorithm a file is decompressed with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Picks the codec to decompress the file with, if any.
+    ///
+    /// The first bytes of the file are peeked at, without being consumed from the reader.
+    pub(crate) fn codec(self, reader: &mut impl BufRead) -> io::Result<Option<Codec>> {
+        Ok(match self {
+            Compression::None => None,
+            Compression::Gzip => Some(Codec::Gzip),
+            Compression::Zstd => Some(Codec::Zstd),
+            Compression::Auto => {
+                // WARN: The paired `BufRead::consume` is not called intentionally. If we
+                // do we'll chop a decent part of the potential compressed stream off.
+                let header_bytes = reader.fill_buf()?;
+                if header_bytes.starts_with(GZIP_MAGIC) {
+                    Some(Codec::Gzip)
+                } else if header_bytes.starts_with(ZSTD_MAGIC) {
+                    Some(Codec::Zstd)
+                } else {
+                    None
+                }
+            }
+        })
+    }
+}
+
+impl Codec {
+    /// Wraps the reader, so that the uncompressed data is read from it.
+    pub(crate) fn decoder<R: BufRead + 'static>(self, reader: R) -> io::Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Codec::Gzip => Box::new(io::BufReader::new(MultiGzDecoder::new(reader))),
+            Codec::Zstd => Box::new(io::BufReader::new(
+                zstd::stream::read::Decoder::with_buffer(reader)?,
+            )),
+        })
+    }
+}
+
+/// Skips over the given number of bytes of the reader, returning the number of bytes skipped.
+///
+/// Compressed streams can't be seeked in, so the data up to the offset is decompressed and
+/// discarded instead.
+pub(crate) fn skip(reader: &mut dyn BufRead, bytes: u64) -> io::Result<u64> {
+    io::copy(&mut io::Read::take(reader, bytes), &mut io::sink())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+
+    use flate2::{write::GzEncoder, Compression as GzCompression};
+
+    use super::*;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_codec() {
+        let gzipped = gzip(b"hello\n");
+        let zstded = zstd::encode_all(&b"hello\n"[..], 0).unwrap();
+
+        let cases = vec![
+            (Compression::Auto, &gzipped[..], Some(Codec::Gzip)),
+            (Compression::Auto, &zstded[..], Some(Codec::Zstd)),
+            (Compression::Auto, &b"hello\n"[..], None),
+            (Compression::Auto, &b""[..], None),
+            (Compression::None, &gzipped[..], None),
+            (Compression::Gzip, &b"hello\n"[..], Some(Codec::Gzip)),
+            (Compression::Zstd, &b"hello\n"[..], Some(Codec::Zstd)),
+        ];
+
+        for (compression, data, expected) in cases {
+            let mut reader = io::BufReader::new(data);
+            assert_eq!(compression.codec(&mut reader).unwrap(), expected);
+            // The peeked bytes are left in the reader.
+            assert_eq!(reader.fill_buf().unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decoder_skip() {
+        let cases = vec![
+            (Codec::Gzip, gzip(b"first\nsecond\n")),
+            (
+                Codec::Zstd,
+                zstd::encode_all(&b"first\nsecond\n"[..], 0).unwrap(),
+            ),
+        ];
+
+        for (codec, data) in cases {
+            let mut reader = codec.decoder(io::Cursor::new(data)).unwrap();
+            assert_eq!(skip(reader.as_mut(), 6).unwrap(), 6);
+
+            let mut rest = String::new();
+            reader.read_to_string(&mut rest).unwrap();
+            assert_eq!(rest, "second\n");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     cmp,
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs::{self, remove_file},
     path::PathBuf,
     sync::Arc,
@@ -18,10 +18,14 @@ use tokio::time::sleep;
 use tracing::{debug, error, info, trace};
 
 use crate::{
+    archive::{archive_file, is_archived, ArchiveRetry},
     checkpointer::{Checkpointer, CheckpointsView},
+    compression::Compression,
     file_watcher::FileWatcher,
-    fingerprinter::{FileFingerprint, Fingerprinter},
+    fingerprinter::{FileFingerprint, FingerprintStrategy, Fingerprinter},
     paths_provider::PathsProvider,
+    read_scheduler::ReadScheduler,
+    token_bucket::TokenBucket,
     FileSourceInternalEvents, ReadFrom,
 };
 
@@ -40,16 +44,24 @@ where
 {
     pub paths_provider: PP,
     pub max_read_bytes: usize,
+    /// When set, the reads are scheduled fairly over the files, reading up to this many bytes
+    /// from each of them per pass of the read loop, regardless of `oldest_first`.
+    pub max_read_bytes_per_file: Option<usize>,
+    pub max_read_bytes_per_second: Option<usize>,
     pub ignore_checkpoints: bool,
     pub read_from: ReadFrom,
     pub ignore_before: Option<DateTime<Utc>>,
     pub max_line_bytes: usize,
+    /// Whether the lines exceeding `max_line_bytes` are truncated, rather than discarded.
+    pub truncate_long_lines: bool,
     pub line_delimiter: Bytes,
+    pub compression: Compression,
     pub data_dir: PathBuf,
     pub glob_minimum_cooldown: Duration,
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
+    pub archive_to_dir: Option<PathBuf>,
     pub emitter: E,
     pub handle: tokio::runtime::Handle,
 }
@@ -93,16 +105,31 @@ where
         let mut fingerprint_buffer = Vec::new();
 
         let mut fp_map: IndexMap<FileFingerprint, FileWatcher> = Default::default();
+        let mut rate_limiters: HashMap<FileFingerprint, TokenBucket> = HashMap::new();
+        let mut archive_retries: HashMap<FileFingerprint, ArchiveRetry> = HashMap::new();
 
         let mut backoff_cap: usize = 1;
         let mut lines = Vec::new();
-
-        checkpointer.read_checkpoints(self.ignore_before);
+        let mut scheduler = ReadScheduler::new(
+            self.max_read_bytes,
+            self.max_read_bytes_per_file,
+            self.oldest_first,
+        );
+
+        let checkpoints_found = checkpointer.read_checkpoints(self.ignore_before);
+
+        // With `checkpoint_or_end`, the files found at startup are only read from the end on the
+        // first startup. Once a previous run persisted checkpoints, a file without one is new to
+        // us, so it's read from the beginning to not lose its data.
+        let startup_read_from = match self.read_from {
+            ReadFrom::CheckpointOrEnd if checkpoints_found => ReadFrom::Beginning,
+            read_from => read_from,
+        };
 
         let mut known_small_files = HashSet::new();
 
         let mut existing_files = Vec::new();
-        for path in self.paths_provider.paths().into_iter() {
+        for path in self.paths().into_iter() {
             if let Some(file_id) = self.fingerprinter.get_fingerprint_or_log_error(
                 &path,
                 &mut fingerprint_buffer,
@@ -130,7 +157,13 @@ where
                 &mut fingerprint_buffer,
             );
 
-            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, true);
+            self.watch_new_file(
+                path,
+                file_id,
+                &mut fp_map,
+                &checkpoints,
+                Some(startup_read_from),
+            );
         }
         self.emitter.emit_files_open(fp_map.len());
 
@@ -174,7 +207,7 @@ where
                 for (_file_id, watcher) in &mut fp_map {
                     watcher.set_file_findable(false); // assume not findable until found
                 }
-                for path in self.paths_provider.paths().into_iter() {
+                for path in self.paths().into_iter() {
                     if let Some(file_id) = self.fingerprinter.get_fingerprint_or_log_error(
                         &path,
                         &mut fingerprint_buffer,
@@ -221,7 +254,7 @@ where
                             }
                         } else {
                             // untracked file fingerprint
-                            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, false);
+                            self.watch_new_file(path, file_id, &mut fp_map, &checkpoints, None);
                             self.emitter.emit_files_open(fp_map.len());
                         }
                     }
@@ -232,14 +265,40 @@ where
             // Collect lines by polling files.
             let mut global_bytes_read: usize = 0;
             let mut maxed_out_reading_single_file = false;
-            for (&file_id, watcher) in &mut fp_map {
+            let mut files_throttled: usize = 0;
+            let max_bytes_per_file = scheduler.max_bytes_per_file();
+            for index in scheduler.pass(fp_map.len()) {
+                let (&file_id, watcher) = fp_map
+                    .get_index_mut(index)
+                    .expect("index is within the watched files");
                 if !watcher.should_read() {
                     continue;
                 }
 
                 let start = time::Instant::now();
+                let mut rate_limiter = None;
+                if let Some(bytes_per_second) = self.max_read_bytes_per_second {
+                    rate_limiter = Some(
+                        rate_limiters
+                            .entry(file_id)
+                            .or_insert_with(|| TokenBucket::new(bytes_per_second, start)),
+                    );
+                }
+                let mut throttled = false;
                 let mut bytes_read: usize = 0;
-                while let Ok(Some(line)) = watcher.read_line() {
+                let first_line = lines.len();
+                loop {
+                    if let Some(rate_limiter) = rate_limiter.as_mut() {
+                        if !rate_limiter.has_tokens(time::Instant::now()) {
+                            throttled = true;
+                            break;
+                        }
+                    }
+
+                    let line = match watcher.read_line() {
+                        Ok(Some(line)) => line,
+                        _ => break,
+                    };
                     let sz = line.bytes.len();
                     trace!(
                         message = "Read bytes.",
@@ -249,6 +308,9 @@ where
                     stats.record_bytes(sz);
 
                     bytes_read += sz;
+                    if let Some(rate_limiter) = rate_limiter.as_mut() {
+                        rate_limiter.consume(sz);
+                    }
 
                     lines.push(Line {
                         text: line.bytes,
@@ -256,38 +318,82 @@ where
                         file_id,
                         start_offset: line.offset,
                         end_offset: watcher.get_file_position(),
+                        file_size: None,
+                        truncated: line.truncated,
                     });
 
-                    if bytes_read > self.max_read_bytes {
+                    // The lines read so far carry their end offset, so the checkpoints advance
+                    // correctly when the rest of the file is left for the next pass.
+                    if bytes_read > max_bytes_per_file {
                         maxed_out_reading_single_file = true;
                         break;
                     }
                 }
+                if lines.len() > first_line {
+                    // The size is taken once the lines were read, so that it covers them.
+                    let file_size = watcher.file_size();
+                    for line in &mut lines[first_line..] {
+                        line.file_size = file_size;
+                    }
+                }
                 stats.record("reading", start.elapsed());
 
-                if bytes_read > 0 {
+                if throttled {
+                    // The throttled files are read again once their tokens refill, so they
+                    // shouldn't keep the loop from backing off.
+                    files_throttled += 1;
+                } else if bytes_read > 0 {
                     global_bytes_read = global_bytes_read.saturating_add(bytes_read);
-                } else {
+                }
+
+                if bytes_read == 0 && !throttled {
                     // Should the file be removed
                     if let Some(grace_period) = self.remove_after {
                         if watcher.last_read_success().elapsed() >= grace_period {
-                            // Try to remove
-                            match remove_file(&watcher.path) {
-                                Ok(()) => {
-                                    self.emitter.emit_file_deleted(&watcher.path);
-                                    watcher.set_dead();
-                                }
-                                Err(error) => {
-                                    // We will try again after some time.
-                                    self.emitter.emit_file_delete_error(&watcher.path, error);
+                            match &self.archive_to_dir {
+                                // Try to archive
+                                Some(archive_dir) => {
+                                    let now = time::Instant::now();
+                                    let retry = archive_retries
+                                        .entry(file_id)
+                                        .or_insert_with(ArchiveRetry::new);
+                                    if retry.is_due(now) {
+                                        match archive_file(&watcher.path, archive_dir) {
+                                            Ok(archive_path) => {
+                                                self.emitter.emit_file_archived(
+                                                    &watcher.path,
+                                                    &archive_path,
+                                                );
+                                                watcher.set_dead();
+                                            }
+                                            Err(error) => {
+                                                // The error may be transient, so we will try
+                                                // again after backing off.
+                                                retry.failed(now);
+                                                self.emitter
+                                                    .emit_file_archive_error(&watcher.path, error);
+                                            }
+                                        }
+                                    }
                                 }
+                                // Try to remove
+                                None => match remove_file(&watcher.path) {
+                                    Ok(()) => {
+                                        self.emitter.emit_file_deleted(&watcher.path);
+                                        watcher.set_dead();
+                                    }
+                                    Err(error) => {
+                                        // We will try again after some time.
+                                        self.emitter.emit_file_delete_error(&watcher.path, error);
+                                    }
+                                },
                             }
                         }
                     }
                 }
 
                 // Do not move on to newer files if we are behind on an older file
-                if self.oldest_first && maxed_out_reading_single_file {
+                if scheduler.stops_when_maxed_out() && maxed_out_reading_single_file {
                     break;
                 }
             }
@@ -298,12 +404,17 @@ where
                 if watcher.dead() {
                     self.emitter.emit_file_unwatched(&watcher.path);
                     checkpoints.set_dead(*file_id);
+                    rate_limiters.remove(file_id);
+                    archive_retries.remove(file_id);
                     false
                 } else {
                     true
                 }
             });
             self.emitter.emit_files_open(fp_map.len());
+            if self.max_read_bytes_per_second.is_some() {
+                self.emitter.emit_files_throttled(files_throttled);
+            }
 
             let start = time::Instant::now();
             let to_send = std::mem::take(&mut lines);
@@ -322,8 +433,11 @@ where
             // limited by the hard-coded cap. Else, we set the backup_cap to its
             // minimum on the assumption that next time through there will be
             // more lines to read promptly.
+            // The backoff is kept below a second while files are throttled, as their
+            // tokens stop refilling after a second.
             backoff_cap = if global_bytes_read == 0 {
-                cmp::min(2_048, backoff_cap.saturating_mul(2))
+                let max_backoff_cap = if files_throttled > 0 { 1_000 } else { 2_048 };
+                cmp::min(max_backoff_cap, backoff_cap.saturating_mul(2))
             } else {
                 1
             };
@@ -360,25 +474,31 @@ where
         }
     }
 
+    /// Lists the paths of the files to watch, leaving out the archived files.
+    fn paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.paths_provider.paths();
+        if let Some(archive_dir) = &self.archive_to_dir {
+            paths.retain(|path| !is_archived(path, archive_dir));
+        }
+        paths
+    }
+
     fn watch_new_file(
         &self,
         path: PathBuf,
         file_id: FileFingerprint,
         fp_map: &mut IndexMap<FileFingerprint, FileWatcher>,
         checkpoints: &CheckpointsView,
-        startup: bool,
+        startup_read_from: Option<ReadFrom>,
     ) {
         // Determine the initial _requested_ starting point in the file. This can be overridden
         // once the file is actually opened and we determine it is compressed, older than we're
         // configured to read, etc.
-        let fallback = if startup {
-            self.read_from
-        } else {
-            // Always read new files that show up while we're running from the beginning. There's
-            // not a good way to determine if they were moved or just created and written very
-            // quickly, so just make sure we're not missing any data.
-            ReadFrom::Beginning
-        };
+        //
+        // Always read new files that show up while we're running from the beginning. There's
+        // not a good way to determine if they were moved or just created and written very
+        // quickly, so just make sure we're not missing any data.
+        let fallback = startup_read_from.unwrap_or(ReadFrom::Beginning);
 
         // Always prefer the stored checkpoint unless the user has opted out.  Previously, the
         // checkpoint was only loaded for new files when Vector was started up, but the
@@ -400,6 +520,7 @@ where
             self.ignore_before,
             self.max_line_bytes,
             self.line_delimiter.clone(),
+            self.compression,
         ) {
             Ok(mut watcher) => {
                 if let ReadFrom::Checkpoint(file_position) = read_from {
@@ -408,6 +529,19 @@ where
                     self.emitter.emit_file_added(&path);
                 }
                 watcher.set_file_findable(true);
+                // Files keep their device and inode when truncated in place, so their watcher
+                // has to notice the truncation itself.
+                watcher.set_reset_on_truncation(matches!(
+                    self.fingerprinter.strategy,
+                    FingerprintStrategy::DevInode
+                ));
+                // The identity checksum changes along with the contents of files truncated in
+                // place, so their new contents are picked up by a watcher of their own.
+                watcher.set_dead_on_truncation(matches!(
+                    self.fingerprinter.strategy,
+                    FingerprintStrategy::PathIdentityChecksum { .. }
+                ));
+                watcher.set_truncate_long_lines(self.truncate_long_lines);
                 fp_map.insert(file_id, watcher);
             }
             Err(error) => self.emitter.emit_file_watch_error(&path, error),
@@ -525,4 +659,200 @@ pub struct Line {
     pub file_id: FileFingerprint,
     pub start_offset: u64,
     pub end_offset: u64,
+    /// The size of the file once the line was read, unless the file is compressed.
+    pub file_size: Option<u64>,
+    /// Whether the line exceeded `max_line_bytes` and was truncated.
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        fs,
+        io::Error,
+        path::{Path, PathBuf},
+        thread,
+        time::Duration,
+    };
+
+    use futures::{
+        channel::{mpsc, oneshot},
+        executor::block_on,
+        StreamExt,
+    };
+    use tempfile::tempdir;
+
+    use super::{FileServer, Line};
+    use crate::{
+        paths_provider::PathsProvider, Checkpointer, Compression, FileSourceInternalEvents,
+        FingerprintStrategy, Fingerprinter, ReadFrom,
+    };
+
+    struct StaticPaths(Vec<PathBuf>);
+
+    impl PathsProvider for StaticPaths {
+        type IntoIter = Vec<PathBuf>;
+
+        fn paths(&self) -> Self::IntoIter {
+            self.0.clone()
+        }
+    }
+
+    #[derive(Clone)]
+    struct NoErrors;
+
+    impl FileSourceInternalEvents for NoErrors {
+        fn emit_file_added(&self, _: &Path) {}
+
+        fn emit_file_resumed(&self, _: &Path, _: u64) {}
+
+        fn emit_file_watch_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_unwatched(&self, _: &Path) {}
+
+        fn emit_file_deleted(&self, _: &Path) {}
+
+        fn emit_file_delete_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_archived(&self, _: &Path, _: &Path) {}
+
+        fn emit_file_archive_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_fingerprint_read_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_checkpointed(&self, _: usize, _: Duration) {}
+
+        fn emit_file_checksum_failed(&self, _: &Path) {
+            panic!();
+        }
+
+        fn emit_file_checkpoint_write_error(&self, _: Error) {
+            panic!();
+        }
+
+        fn emit_files_open(&self, _: usize) {}
+
+        fn emit_files_throttled(&self, _: usize) {}
+
+        fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {}
+    }
+
+    fn write_lines(path: &Path, name: &str, count: usize) {
+        let contents: String = (0..count)
+            .map(|i| format!("{} line {:04} {}\n", name, i, "x".repeat(80)))
+            .collect();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_fair_reads_advance_checkpoints() {
+        let dir = tempdir().unwrap();
+        let data_dir = tempdir().unwrap();
+        let busy = dir.path().join("busy.log");
+        let quiet = dir.path().join("quiet.log");
+        write_lines(&busy, "busy", 100);
+        write_lines(&quiet, "quiet", 2);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let file_server = FileServer {
+            paths_provider: StaticPaths(vec![busy.clone(), quiet.clone()]),
+            max_read_bytes: 2048,
+            max_read_bytes_per_file: Some(250),
+            max_read_bytes_per_second: None,
+            ignore_checkpoints: false,
+            read_from: ReadFrom::Beginning,
+            ignore_before: None,
+            max_line_bytes: 1024,
+            truncate_long_lines: false,
+            line_delimiter: "\n".into(),
+            compression: Compression::None,
+            data_dir: data_dir.path().to_path_buf(),
+            glob_minimum_cooldown: Duration::from_secs(60),
+            fingerprinter: Fingerprinter {
+                strategy: FingerprintStrategy::FirstLinesChecksum {
+                    ignored_header_bytes: 0,
+                    lines: 1,
+                },
+                max_line_length: 1024,
+                ignore_not_found: false,
+                compression: Compression::None,
+            },
+            // The busy file would otherwise hold back the quiet one until it's drained.
+            oldest_first: true,
+            remove_after: None,
+            archive_to_dir: None,
+            emitter: NoErrors,
+            handle: runtime.handle().clone(),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded::<Vec<Line>>();
+        let (shutdown_data, shutdown_data_rx) = oneshot::channel::<()>();
+        let (shutdown_checkpointer, shutdown_checkpointer_rx) = oneshot::channel::<()>();
+        let checkpointer = Checkpointer::new(data_dir.path());
+        let server = thread::spawn(move || {
+            file_server
+                .run(tx, shutdown_data_rx, shutdown_checkpointer_rx, checkpointer)
+                .unwrap();
+        });
+
+        let mut batches = Vec::new();
+        let mut lines_read = 0;
+        while lines_read < 102 {
+            let batch = block_on(rx.next()).unwrap();
+            lines_read += batch.len();
+            if !batch.is_empty() {
+                batches.push(batch);
+            }
+        }
+        shutdown_data.send(()).unwrap();
+        shutdown_checkpointer.send(()).unwrap();
+        server.join().unwrap();
+
+        // The busy file is read up to the end of the line crossing the limit, and the quiet file
+        // is read on the same pass.
+        let first_batch: Vec<&str> = batches[0]
+            .iter()
+            .map(|line| line.filename.as_str())
+            .collect();
+        let busy_name = busy.to_str().unwrap();
+        let quiet_name = quiet.to_str().unwrap();
+        assert_eq!(
+            first_batch
+                .iter()
+                .filter(|name| **name == busy_name)
+                .count(),
+            3
+        );
+        assert_eq!(
+            first_batch
+                .iter()
+                .filter(|name| **name == quiet_name)
+                .count(),
+            2
+        );
+
+        // Every line resumes where the previous line of its file ended, so the checkpoints taken
+        // from the end offsets never skip or repeat lines across the partial reads.
+        let mut positions = HashMap::new();
+        for line in batches.iter().flatten() {
+            let position = positions.entry(line.filename.clone()).or_insert(0);
+            assert_eq!(line.start_offset, *position);
+            assert_eq!(
+                line.end_offset,
+                line.start_offset + line.text.len() as u64 + 1
+            );
+            *position = line.end_offset;
+        }
+        assert_eq!(positions[busy_name], fs::metadata(&busy).unwrap().len());
+        assert_eq!(positions[quiet_name], fs::metadata(&quiet).unwrap().len());
+    }
 }
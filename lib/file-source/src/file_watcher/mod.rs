@@ -7,11 +7,13 @@ use std::{
 
 use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Utc};
-use flate2::bufread::MultiGzDecoder;
-use tracing::debug;
+use tracing::{debug, info};
 
 use crate::{
-    buffer::read_until_with_max_size, metadata_ext::PortableFileExt, FilePosition, ReadFrom,
+    buffer::{read_until_with_max_size, read_until_with_max_size_truncating},
+    compression::{self, Compression},
+    metadata_ext::PortableFileExt,
+    FilePosition, ReadFrom,
 };
 #[cfg(test)]
 mod tests;
@@ -25,6 +27,8 @@ mod tests;
 pub(super) struct RawLine {
     pub offset: u64,
     pub bytes: Bytes,
+    /// Whether the line exceeded the max line bytes and was truncated.
+    pub truncated: bool,
 }
 
 /// The `FileWatcher` struct defines the polling based state machine which reads
@@ -38,13 +42,22 @@ pub struct FileWatcher {
     pub path: PathBuf,
     findable: bool,
     reader: Box<dyn BufRead>,
+    /// A handle to the uncompressed file being read, used to detect it being truncated.
+    file: Option<File>,
     file_position: FilePosition,
     devno: u64,
     inode: u64,
+    compression: Compression,
+    compressed: bool,
+    reset_on_truncation: bool,
+    dead_on_truncation: bool,
     is_dead: bool,
     last_read_attempt: Instant,
     last_read_success: Instant,
     max_line_bytes: usize,
+    truncate_long_lines: bool,
+    /// Whether the rest of a truncated line is being skipped.
+    skipping_line: bool,
     line_delimiter: Bytes,
     buf: BytesMut,
 }
@@ -61,10 +74,12 @@ impl FileWatcher {
         ignore_before: Option<DateTime<Utc>>,
         max_line_bytes: usize,
         line_delimiter: Bytes,
+        compression: Compression,
     ) -> Result<FileWatcher, io::Error> {
         let f = fs::File::open(&path)?;
         let (devno, ino) = (f.portable_dev()?, f.portable_ino()?);
         let metadata = f.metadata()?;
+        let file = f.try_clone()?;
         let mut reader = io::BufReader::new(f);
 
         let too_old = if let (Some(ignore_before), Ok(modified_time)) = (
@@ -76,53 +91,67 @@ impl FileWatcher {
             false
         };
 
-        let gzipped = is_gzipped(&mut reader)?;
+        let codec = compression.codec(&mut reader)?;
 
         // Determine the actual position at which we should start reading
         let (reader, file_position): (Box<dyn BufRead>, FilePosition) =
-            match (gzipped, too_old, read_from) {
-                (true, true, _) => {
+            match (codec, too_old, read_from) {
+                (Some(_), true, _) => {
                     debug!(
-                        message = "Not reading gzipped file older than `ignore_older`.",
+                        message = "Not reading compressed file older than `ignore_older`.",
                         ?path,
                     );
                     (Box::new(null_reader()), 0)
                 }
-                (true, _, ReadFrom::Checkpoint(file_position)) => {
+                (Some(codec), _, ReadFrom::Checkpoint(file_position)) => {
                     debug!(
-                        message = "Not re-reading gzipped file with existing stored offset.",
+                        message = "Resuming compressed file from the uncompressed offset.",
                         ?path,
                         %file_position
                     );
-                    (Box::new(null_reader()), file_position)
+                    let mut reader = codec.decoder(reader)?;
+                    let pos = compression::skip(reader.as_mut(), file_position)?;
+                    (reader, pos)
                 }
                 // TODO: This may become the default, leading us to stop reading gzipped files that
                 // we were reading before. Should we merge this and the next branch to read
                 // compressed file from the beginning even when `read_from = "end"` (implicitly via
                 // default or explicitly via config)?
-                (true, _, ReadFrom::End) => {
+                (Some(_), _, ReadFrom::End | ReadFrom::CheckpointOrEnd) => {
                     debug!(
                         message = "Can't read from the end of already-compressed file.",
                         ?path,
                     );
                     (Box::new(null_reader()), 0)
                 }
-                (true, false, ReadFrom::Beginning) => {
-                    (Box::new(io::BufReader::new(MultiGzDecoder::new(reader))), 0)
-                }
-                (false, true, _) => {
+                (Some(codec), false, ReadFrom::Beginning) => (codec.decoder(reader)?, 0),
+                (None, true, _) => {
                     let pos = reader.seek(io::SeekFrom::End(0)).unwrap();
                     (Box::new(reader), pos)
                 }
-                (false, false, ReadFrom::Checkpoint(file_position)) => {
+                (None, false, ReadFrom::Checkpoint(file_position))
+                    if file_position > metadata.len() =>
+                {
+                    // The file is shorter than the checkpointed offset, so it was truncated, or
+                    // the checkpointed file was removed and its inode reused, while we weren't
+                    // running. Either way, it holds a new stream of lines.
+                    info!(
+                        message = "File is shorter than its checkpoint, reading from the start.",
+                        ?path,
+                        %file_position
+                    );
+                    let pos = reader.seek(io::SeekFrom::Start(0)).unwrap();
+                    (Box::new(reader), pos)
+                }
+                (None, false, ReadFrom::Checkpoint(file_position)) => {
                     let pos = reader.seek(io::SeekFrom::Start(file_position)).unwrap();
                     (Box::new(reader), pos)
                 }
-                (false, false, ReadFrom::Beginning) => {
+                (None, false, ReadFrom::Beginning) => {
                     let pos = reader.seek(io::SeekFrom::Start(0)).unwrap();
                     (Box::new(reader), pos)
                 }
-                (false, false, ReadFrom::End) => {
+                (None, false, ReadFrom::End | ReadFrom::CheckpointOrEnd) => {
                     let pos = reader.seek(io::SeekFrom::End(0)).unwrap();
                     (Box::new(reader), pos)
                 }
@@ -139,13 +168,20 @@ impl FileWatcher {
             path,
             findable: true,
             reader,
+            file: codec.is_none().then_some(file),
             file_position,
             devno,
             inode: ino,
+            compression,
+            compressed: codec.is_some(),
+            reset_on_truncation: false,
+            dead_on_truncation: false,
             is_dead: false,
             last_read_attempt: ts,
             last_read_success: ts,
             max_line_bytes,
+            truncate_long_lines: false,
+            skipping_line: false,
             line_delimiter,
             buf: BytesMut::new(),
         })
@@ -154,19 +190,25 @@ impl FileWatcher {
     pub fn update_path(&mut self, path: PathBuf) -> io::Result<()> {
         let file_handle = File::open(&path)?;
         if (file_handle.portable_dev()?, file_handle.portable_ino()?) != (self.devno, self.inode) {
-            let mut reader = io::BufReader::new(fs::File::open(&path)?);
-            let gzipped = is_gzipped(&mut reader)?;
-            let new_reader: Box<dyn BufRead> = if gzipped {
-                if self.file_position != 0 {
-                    Box::new(null_reader())
-                } else {
-                    Box::new(io::BufReader::new(MultiGzDecoder::new(reader)))
+            let file = fs::File::open(&path)?;
+            let mut reader = io::BufReader::new(file.try_clone()?);
+            let codec = self.compression.codec(&mut reader)?;
+            let new_reader: Box<dyn BufRead> = match codec {
+                // The file was compressed after being rotated, so the rest of it is read from the
+                // uncompressed data.
+                Some(codec) => {
+                    let mut reader = codec.decoder(reader)?;
+                    compression::skip(reader.as_mut(), self.file_position)?;
+                    reader
+                }
+                None => {
+                    reader.seek(io::SeekFrom::Start(self.file_position))?;
+                    Box::new(reader)
                 }
-            } else {
-                reader.seek(io::SeekFrom::Start(self.file_position))?;
-                Box::new(reader)
             };
             self.reader = new_reader;
+            self.file = codec.is_none().then_some(file);
+            self.compressed = codec.is_some();
             self.devno = file_handle.portable_dev()?;
             self.inode = file_handle.portable_ino()?;
         }
@@ -182,6 +224,28 @@ impl FileWatcher {
         self.findable
     }
 
+    /// Sets whether the file is read from the beginning again once it is truncated in place.
+    ///
+    /// This is only sound when the fingerprint of the file doesn't change along with its
+    /// contents, otherwise the new contents are also picked up as a new file.
+    pub fn set_reset_on_truncation(&mut self, reset_on_truncation: bool) {
+        self.reset_on_truncation = reset_on_truncation;
+    }
+
+    /// Sets whether the file stops being read once it is truncated in place.
+    ///
+    /// This is meant for the fingerprints that change along with the contents of the file, so
+    /// the new contents are picked up as a new file, and the rest of the old stream of lines is
+    /// discarded rather than read from the new contents.
+    pub fn set_dead_on_truncation(&mut self, dead_on_truncation: bool) {
+        self.dead_on_truncation = dead_on_truncation;
+    }
+
+    /// Sets whether the lines exceeding the max line bytes are truncated, rather than discarded.
+    pub fn set_truncate_long_lines(&mut self, truncate_long_lines: bool) {
+        self.truncate_long_lines = truncate_long_lines;
+    }
+
     pub fn set_dead(&mut self) {
         self.is_dead = true;
     }
@@ -194,6 +258,14 @@ impl FileWatcher {
         self.file_position
     }
 
+    /// The current size of the file, unless it's compressed.
+    pub fn file_size(&self) -> Option<u64> {
+        self.file
+            .as_ref()
+            .and_then(|file| file.metadata().ok())
+            .map(|metadata| metadata.len())
+    }
+
     /// Read a single line from the underlying file
     ///
     /// This function will attempt to read a new line from its file, blocking,
@@ -205,26 +277,74 @@ impl FileWatcher {
         let reader = &mut self.reader;
         let file_position = &mut self.file_position;
         let initial_position = *file_position;
-        match read_until_with_max_size(
-            reader,
-            file_position,
-            self.line_delimiter.as_ref(),
-            &mut self.buf,
-            self.max_line_bytes,
-        ) {
-            Ok(Some(_)) => {
+        let result = if self.truncate_long_lines {
+            read_until_with_max_size_truncating(
+                reader,
+                file_position,
+                self.line_delimiter.as_ref(),
+                &mut self.buf,
+                self.max_line_bytes,
+                &mut self.skipping_line,
+            )
+        } else {
+            read_until_with_max_size(
+                reader,
+                file_position,
+                self.line_delimiter.as_ref(),
+                &mut self.buf,
+                self.max_line_bytes,
+            )
+            .map(|read| read.map(|_| false))
+        };
+        match result {
+            Ok(Some(truncated)) => {
                 self.track_read_success();
                 Ok(Some(RawLine {
                     offset: initial_position,
                     bytes: self.buf.split().freeze(),
+                    truncated,
                 }))
             }
             Ok(None) => {
+                let finished = self.compressed;
+                if finished {
+                    // Compressed files aren't appended to, so once the end of the uncompressed
+                    // data is reached, the file is finished. Its watcher is kept around, so that
+                    // the file isn't decompressed again each time it's found.
+                    debug!(message = "Finished reading compressed file.", path = ?self.path);
+                    self.reader = Box::new(null_reader());
+                    self.compressed = false;
+                } else if self.truncated()? {
+                    if self.dead_on_truncation {
+                        // The new stream of lines has a fingerprint of its own, so it is read by
+                        // another watcher, and the rest of the old stream is discarded.
+                        info!(
+                            message = "File was truncated, stopped reading it.",
+                            path = ?self.path
+                        );
+                        self.buf.clear();
+                        self.skipping_line = false;
+                        self.set_dead();
+                        return Ok(None);
+                    }
+                    // The file was truncated in place, like by a copy-truncate rotation, so its
+                    // inode now holds a new stream of lines. Partial lines read before the
+                    // truncation are discarded along with the rest of the old stream.
+                    info!(
+                        message = "File was truncated, reading it from the beginning.",
+                        path = ?self.path
+                    );
+                    self.reset()?;
+                    return Ok(None);
+                }
                 if !self.file_findable() {
                     self.set_dead();
-                    // File has been deleted, so return what we have in the buffer, even though it
-                    // didn't end with a newline. This is not a perfect signal for when we should
-                    // give up waiting for a newline, but it's decent.
+                }
+
+                if finished || self.dead() {
+                    // File has been deleted or finished, so return what we have in the buffer,
+                    // even though it didn't end with a newline. This is not a perfect signal for
+                    // when we should give up waiting for a newline, but it's decent.
                     let buf = self.buf.split().freeze();
                     if buf.is_empty() {
                         // EOF
@@ -233,6 +353,7 @@ impl FileWatcher {
                         Ok(Some(RawLine {
                             offset: initial_position,
                             bytes: buf,
+                            truncated: false,
                         }))
                     }
                 } else {
@@ -248,6 +369,29 @@ impl FileWatcher {
         }
     }
 
+    /// Whether the file shrank below the position read up to.
+    fn truncated(&self) -> io::Result<bool> {
+        match &self.file {
+            Some(file) if self.reset_on_truncation || self.dead_on_truncation => {
+                Ok(file.metadata()?.len() < self.file_position)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Starts reading the file over from its beginning.
+    fn reset(&mut self) -> io::Result<()> {
+        if let Some(file) = &self.file {
+            let mut reader = io::BufReader::new(file.try_clone()?);
+            reader.seek(io::SeekFrom::Start(0))?;
+            self.reader = Box::new(reader);
+            self.file_position = 0;
+            self.buf.clear();
+            self.skipping_line = false;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn track_read_attempt(&mut self) {
         self.last_read_attempt = Instant::now();
@@ -270,13 +414,6 @@ impl FileWatcher {
     }
 }
 
-fn is_gzipped(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
-    let header_bytes = r.fill_buf()?;
-    // WARN: The paired `BufReader::consume` is not called intentionally. If we
-    // do we'll chop a decent part of the potential gzip stream off.
-    Ok(header_bytes.starts_with(&[0x1f, 0x8b]))
-}
-
 fn null_reader() -> impl BufRead {
     io::Cursor::new(Vec::new())
 }
@@ -9,7 +9,7 @@ use quickcheck::{QuickCheck, TestResult};
 
 use crate::{
     file_watcher::{tests::*, FileWatcher},
-    ReadFrom,
+    Compression, ReadFrom,
 };
 
 // Interpret all FWActions, including truncation
@@ -36,6 +36,7 @@ fn experiment(actions: Vec<FileWatcherAction>) {
         None,
         100_000,
         Bytes::from("\n"),
+        Compression::None,
     )
     .expect("must be able to create");
 
@@ -1,5 +1,6 @@
 mod experiment;
 mod experiment_no_truncations;
+mod truncation;
 
 use std::str;
 
@@ -0,0 +1,66 @@
+use std::{fs, io::Write};
+
+use bytes::Bytes;
+
+use crate::{file_watcher::FileWatcher, Compression, ReadFrom};
+
+fn watcher(path: &std::path::Path) -> FileWatcher {
+    FileWatcher::new(
+        path.to_path_buf(),
+        ReadFrom::Beginning,
+        None,
+        100_000,
+        Bytes::from("\n"),
+        Compression::None,
+    )
+    .expect("must be able to create")
+}
+
+fn read_lines(fw: &mut FileWatcher) -> Vec<Bytes> {
+    let mut lines = Vec::new();
+    while let Some(line) = fw.read_line().unwrap() {
+        lines.push(line.bytes);
+    }
+    lines
+}
+
+#[test]
+fn reset_on_truncation() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let path = dir.path().join("a_file.log");
+    let mut fp = fs::File::create(&path).expect("could not create");
+    let mut fw = watcher(&path);
+    fw.set_reset_on_truncation(true);
+
+    writeln!(fp, "first").unwrap();
+    writeln!(fp, "second").unwrap();
+    assert_eq!(read_lines(&mut fw), vec!["first", "second"]);
+
+    fp.set_len(0).unwrap();
+    let mut fp = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    assert!(read_lines(&mut fw).is_empty());
+    writeln!(fp, "third").unwrap();
+    assert_eq!(read_lines(&mut fw), vec!["third"]);
+    assert!(!fw.dead());
+}
+
+#[test]
+fn dead_on_truncation() {
+    let dir = tempfile::TempDir::new().expect("could not create tempdir");
+    let path = dir.path().join("a_file.log");
+    let mut fp = fs::File::create(&path).expect("could not create");
+    let mut fw = watcher(&path);
+    fw.set_dead_on_truncation(true);
+
+    writeln!(fp, "first").unwrap();
+    writeln!(fp, "second").unwrap();
+    write!(fp, "partial").unwrap();
+    assert_eq!(read_lines(&mut fw), vec!["first", "second"]);
+
+    // The new stream of lines is left to the watcher of its own fingerprint.
+    fp.set_len(0).unwrap();
+    let mut fp = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(fp, "third").unwrap();
+    assert!(read_lines(&mut fw).is_empty());
+    assert!(fw.dead());
+}
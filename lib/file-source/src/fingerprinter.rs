@@ -8,7 +8,11 @@ use std::{
 use crc::Crc;
 use serde::{Deserialize, Serialize};
 
-use crate::{metadata_ext::PortableFileExt, FileSourceInternalEvents};
+use crate::{
+    compression::{self, Compression},
+    metadata_ext::PortableFileExt,
+    FileSourceInternalEvents,
+};
 
 const FINGERPRINT_CRC: Crc<u64> = Crc::<u64>::new(&crc::CRC_64_ECMA_182);
 const LEGACY_FINGERPRINT_CRC: Crc<u64> = Crc::<u64>::new(&crc::CRC_64_XZ);
@@ -18,6 +22,7 @@ pub struct Fingerprinter {
     pub strategy: FingerprintStrategy,
     pub max_line_length: usize,
     pub ignore_not_found: bool,
+    pub compression: Compression,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +37,14 @@ pub enum FingerprintStrategy {
         lines: usize,
     },
     DevInode,
+    /// Combines an identity derived from the path of the file with a checksum of its first
+    /// `bytes` bytes.
+    ///
+    /// Files without an identity are fingerprinted by the checksum alone.
+    PathIdentityChecksum {
+        bytes: usize,
+        identity: fn(&Path) -> Option<String>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, Ord, PartialOrd)]
@@ -42,6 +55,7 @@ pub enum FileFingerprint {
     #[serde(alias = "first_line_checksum")]
     FirstLinesChecksum(u64),
     DevInode(u64, u64),
+    IdentityChecksum(u64),
     Unknown(u64),
 }
 
@@ -58,6 +72,7 @@ impl FileFingerprint {
                 buf.write_all(&ino.to_be_bytes()).expect("writing to array");
                 FINGERPRINT_CRC.checksum(&buf[..])
             }
+            IdentityChecksum(c) => *c,
             Unknown(c) => *c,
         }
     }
@@ -94,12 +109,40 @@ impl Fingerprinter {
                 lines,
             } => {
                 buffer.resize(self.max_line_length, 0u8);
-                let mut fp = fs::File::open(path)?;
-                fp.seek(SeekFrom::Start(ignored_header_bytes as u64))?;
-                let bytes_read = fingerprinter_read_until(fp, b'\n', lines, buffer)?;
+                let mut fp = io::BufReader::new(fs::File::open(path)?);
+                // Compressed files are fingerprinted by their uncompressed data, rather than by
+                // the header of the compressed stream, so that a file compressed after being
+                // rotated is still recognized.
+                let bytes_read = match self.compression.codec(&mut fp)? {
+                    Some(codec) => {
+                        let mut fp = codec.decoder(fp)?;
+                        compression::skip(fp.as_mut(), ignored_header_bytes as u64)?;
+                        fingerprinter_read_until(fp, b'\n', lines, buffer)?
+                    }
+                    None => {
+                        fp.seek(SeekFrom::Start(ignored_header_bytes as u64))?;
+                        fingerprinter_read_until(fp, b'\n', lines, buffer)?
+                    }
+                };
                 let fingerprint = FINGERPRINT_CRC.checksum(&buffer[..bytes_read]);
                 Ok(FirstLinesChecksum(fingerprint))
             }
+            FingerprintStrategy::PathIdentityChecksum { bytes, identity } => {
+                buffer.resize(bytes, 0u8);
+                let mut fp = io::BufReader::new(fs::File::open(path)?);
+                // Rotated files that were compressed keep their identity, so they are
+                // fingerprinted by their uncompressed data as well.
+                match self.compression.codec(&mut fp)? {
+                    Some(codec) => codec.decoder(fp)?.read_exact(&mut buffer[..])?,
+                    None => fp.read_exact(&mut buffer[..])?,
+                }
+                let mut digest = FINGERPRINT_CRC.digest();
+                if let Some(identity) = identity(path) {
+                    digest.update(identity.as_bytes());
+                }
+                digest.update(&buffer[..]);
+                Ok(IdentityChecksum(digest.finalize()))
+            }
         }
     }
 
@@ -187,6 +230,40 @@ impl Fingerprinter {
             _ => Ok(None),
         }
     }
+    /// For upgrades from the versions fingerprinting the compressed files by
+    /// their raw bytes, before they were decompressed for it.
+    ///
+    /// Returns `None` for the files that aren't compressed, which are
+    /// fingerprinted the same way as before.
+    pub fn get_raw_first_lines_checksum(
+        &self,
+        path: &Path,
+        buffer: &mut Vec<u8>,
+    ) -> Result<Option<FileFingerprint>, io::Error> {
+        match self.strategy {
+            FingerprintStrategy::Checksum {
+                ignored_header_bytes,
+                bytes: _,
+                lines,
+            }
+            | FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes,
+                lines,
+            } => {
+                buffer.resize(self.max_line_length, 0u8);
+                let mut fp = io::BufReader::new(fs::File::open(path)?);
+                if self.compression.codec(&mut fp)?.is_none() {
+                    return Ok(None);
+                }
+                fp.seek(SeekFrom::Start(ignored_header_bytes as u64))?;
+                let bytes_read = fingerprinter_read_until(fp, b'\n', lines, buffer)?;
+                let fingerprint = FINGERPRINT_CRC.checksum(&buffer[..bytes_read]);
+                Ok(Some(FileFingerprint::FirstLinesChecksum(fingerprint)))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// For upgrades from legacy strategy version
     /// <https://github.com/vectordotdev/vector/issues/15700>
     pub fn get_legacy_first_lines_checksum(
@@ -281,11 +358,18 @@ fn fingerprinter_read_until(
 
 #[cfg(test)]
 mod test {
-    use std::{collections::HashSet, fs, io::Error, path::Path, time::Duration};
-
+    use std::{
+        collections::HashSet,
+        fs,
+        io::{Error, Write},
+        path::Path,
+        time::Duration,
+    };
+
+    use flate2::{write::GzEncoder, Compression as GzCompression};
     use tempfile::tempdir;
 
-    use super::{FileSourceInternalEvents, FingerprintStrategy, Fingerprinter};
+    use super::{Compression, FileSourceInternalEvents, FingerprintStrategy, Fingerprinter};
 
     #[test]
     fn test_checksum_fingerprint() {
@@ -297,6 +381,7 @@ mod test {
             },
             max_line_length: 1024,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let target_dir = tempdir().unwrap();
@@ -342,6 +427,7 @@ mod test {
             },
             max_line_length,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let target_dir = tempdir().unwrap();
@@ -406,6 +492,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_compressed_first_line_checksum_fingerprint() {
+        let fingerprinter = |compression| Fingerprinter {
+            strategy: FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 0,
+                lines: 1,
+            },
+            max_line_length: 64,
+            ignore_not_found: false,
+            compression,
+        };
+
+        let contents = b"hello world\nthe next line\n";
+        let mut gzipped = GzEncoder::new(Vec::new(), GzCompression::default());
+        gzipped.write_all(contents).unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let plain = target_dir.path().join("app.log");
+        let gzipped_path = target_dir.path().join("app.log.1.gz");
+        let zstded_path = target_dir.path().join("app.log.2.zst");
+        fs::write(&plain, contents).unwrap();
+        fs::write(&gzipped_path, gzipped.finish().unwrap()).unwrap();
+        fs::write(&zstded_path, zstd::encode_all(&contents[..], 0).unwrap()).unwrap();
+
+        let mut buf = Vec::new();
+        let mut run = |compression: Compression, path: &Path| {
+            fingerprinter(compression)
+                .get_fingerprint_of_file(path, &mut buf)
+                .unwrap()
+        };
+
+        let expected = run(Compression::Auto, &plain);
+        assert_eq!(run(Compression::Auto, &gzipped_path), expected);
+        assert_eq!(run(Compression::Auto, &zstded_path), expected);
+        assert_eq!(run(Compression::Gzip, &gzipped_path), expected);
+        assert_eq!(run(Compression::Zstd, &zstded_path), expected);
+        assert!(fingerprinter(Compression::None)
+            .get_fingerprint_of_file(&gzipped_path, &mut buf)
+            .map_or(true, |fingerprint| fingerprint != expected));
+    }
+
     #[test]
     fn test_first_two_lines_checksum_fingerprint() {
         let max_line_length = 64;
@@ -416,6 +543,7 @@ mod test {
             },
             max_line_length,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let target_dir = tempdir().unwrap();
@@ -466,6 +594,7 @@ mod test {
             strategy: FingerprintStrategy::DevInode,
             max_line_length: 42,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let target_dir = tempdir().unwrap();
@@ -497,6 +626,65 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_path_identity_checksum_fingerprint() {
+        fn identity(path: &Path) -> Option<String> {
+            let parent = path.parent()?.file_name()?.to_str()?;
+            (parent != "anonymous").then(|| parent.to_owned())
+        }
+
+        let fingerprinter = Fingerprinter {
+            strategy: FingerprintStrategy::PathIdentityChecksum { bytes: 8, identity },
+            max_line_length: 1024,
+            ignore_not_found: false,
+            compression: Compression::Auto,
+        };
+
+        let target_dir = tempdir().unwrap();
+        for dir in ["a", "b", "anonymous"] {
+            fs::create_dir(target_dir.path().join(dir)).unwrap();
+        }
+        let data = b"same first bytes\n";
+        let a_path = target_dir.path().join("a/0.log");
+        let b_path = target_dir.path().join("b/0.log");
+        let a_rotated_path = target_dir.path().join("a/0.log.1.gz");
+        let anonymous_path = target_dir.path().join("anonymous/0.log");
+        let short_path = target_dir.path().join("a/1.log");
+        fs::write(&a_path, data).unwrap();
+        fs::write(&b_path, data).unwrap();
+        fs::write(&anonymous_path, data).unwrap();
+        fs::write(&short_path, b"short").unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+        encoder.write_all(data).unwrap();
+        fs::write(&a_rotated_path, encoder.finish().unwrap()).unwrap();
+
+        let mut buf = Vec::new();
+        let a = fingerprinter
+            .get_fingerprint_of_file(&a_path, &mut buf)
+            .unwrap();
+        assert_ne!(
+            a,
+            fingerprinter
+                .get_fingerprint_of_file(&b_path, &mut buf)
+                .unwrap()
+        );
+        assert_ne!(
+            a,
+            fingerprinter
+                .get_fingerprint_of_file(&anonymous_path, &mut buf)
+                .unwrap()
+        );
+        assert_eq!(
+            a,
+            fingerprinter
+                .get_fingerprint_of_file(&a_rotated_path, &mut buf)
+                .unwrap()
+        );
+        assert!(fingerprinter
+            .get_fingerprint_of_file(&short_path, &mut buf)
+            .is_err());
+    }
+
     #[test]
     fn no_error_on_dir() {
         let target_dir = tempdir().unwrap();
@@ -508,6 +696,7 @@ mod test {
             },
             max_line_length: 1024,
             ignore_not_found: false,
+            compression: Compression::Auto,
         };
 
         let mut buf = Vec::new();
@@ -537,6 +726,12 @@ mod test {
             panic!();
         }
 
+        fn emit_file_archived(&self, _: &Path, _: &Path) {}
+
+        fn emit_file_archive_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
         fn emit_file_fingerprint_read_error(&self, _: &Path, _: Error) {
             panic!();
         }
@@ -553,6 +748,8 @@ mod test {
 
         fn emit_files_open(&self, _: usize) {}
 
+        fn emit_files_throttled(&self, _: usize) {}
+
         fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {}
     }
 }
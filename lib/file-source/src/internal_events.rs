@@ -15,6 +15,10 @@ pub trait FileSourceInternalEvents: Send + Sync + Clone + 'static {
 
     fn emit_file_delete_error(&self, path: &Path, error: Error);
 
+    fn emit_file_archived(&self, path: &Path, archive_path: &Path);
+
+    fn emit_file_archive_error(&self, path: &Path, error: Error);
+
     fn emit_file_fingerprint_read_error(&self, path: &Path, error: Error);
 
     fn emit_file_checkpointed(&self, count: usize, duration: Duration);
@@ -25,5 +29,7 @@ pub trait FileSourceInternalEvents: Send + Sync + Clone + 'static {
 
     fn emit_files_open(&self, count: usize);
 
+    fn emit_files_throttled(&self, count: usize);
+
     fn emit_path_globbing_failed(&self, path: &Path, error: &Error);
 }
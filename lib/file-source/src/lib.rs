@@ -4,17 +4,22 @@
 #[macro_use]
 extern crate scan_fmt;
 
+mod archive;
 pub mod buffer;
 mod checkpointer;
+mod compression;
 mod file_server;
 mod file_watcher;
 mod fingerprinter;
 mod internal_events;
 mod metadata_ext;
 pub mod paths_provider;
+mod read_scheduler;
+mod token_bucket;
 
 pub use self::{
     checkpointer::{Checkpointer, CheckpointsView, CHECKPOINT_FILE_NAME},
+    compression::Compression,
     file_server::{calculate_ignore_before, FileServer, Line, Shutdown as FileServerShutdown},
     fingerprinter::{FileFingerprint, FingerprintStrategy, Fingerprinter},
     internal_events::FileSourceInternalEvents,
@@ -29,6 +34,9 @@ pub enum ReadFrom {
     Beginning,
     End,
     Checkpoint(FilePosition),
+    /// Like `End`, except that once checkpoints were persisted by a previous run, the files
+    /// without a checkpoint are read from the beginning.
+    CheckpointOrEnd,
 }
 
 /// File position to use when reading a new file.
@@ -41,6 +49,12 @@ pub enum ReadFromConfig {
 
     /// Start reading from the current end of the file.
     End,
+
+    /// Start reading from the current end of the file on the first startup only.
+    ///
+    /// Files with a checkpoint resume from it. Once checkpoints were persisted by a previous run,
+    /// files without a checkpoint are new to Vector, so they are read from the beginning.
+    CheckpointOrEnd,
 }
 
 impl From<ReadFromConfig> for ReadFrom {
@@ -48,6 +62,7 @@ impl From<ReadFromConfig> for ReadFrom {
         match rfc {
             ReadFromConfig::Beginning => ReadFrom::Beginning,
             ReadFromConfig::End => ReadFrom::End,
+            ReadFromConfig::CheckpointOrEnd => ReadFrom::CheckpointOrEnd,
         }
     }
 }
@@ -0,0 +1,93 @@
+/// Schedules the reads over the watched files on each pass of the read loop.
+///
+/// By default the files are read in the order they were found, each until `max_read_bytes` were
+/// read from it. With `oldest_first`, the pass ends at the first file with more to read than
+/// that, so that the newer files aren't read before the older ones caught up.
+///
+/// With `max_read_bytes_per_file`, the reads are scheduled fairly instead: every file gets to read
+/// up to that many bytes per pass, and each pass starts one file further than the previous one, so
+/// the busy files can neither keep the quiet ones from being read nor always get to read first.
+/// Lines are never split, so reading stops at the end of the line crossing the limit, and the
+/// rest of the file is read on the next passes.
+#[derive(Debug)]
+pub(crate) struct ReadScheduler {
+    max_read_bytes: usize,
+    max_read_bytes_per_file: Option<usize>,
+    oldest_first: bool,
+    next_start: usize,
+}
+
+impl ReadScheduler {
+    pub(crate) const fn new(
+        max_read_bytes: usize,
+        max_read_bytes_per_file: Option<usize>,
+        oldest_first: bool,
+    ) -> Self {
+        Self {
+            max_read_bytes,
+            max_read_bytes_per_file,
+            oldest_first,
+            next_start: 0,
+        }
+    }
+
+    /// Returns the indexes of the `file_count` watched files, in the order they are read on this
+    /// pass.
+    pub(crate) fn pass(&mut self, file_count: usize) -> impl Iterator<Item = usize> {
+        let start = if self.max_read_bytes_per_file.is_some() && file_count > 0 {
+            let start = self.next_start % file_count;
+            self.next_start = start + 1;
+            start
+        } else {
+            0
+        };
+        (0..file_count).map(move |offset| (start + offset) % file_count)
+    }
+
+    /// The number of bytes after which reading from a single file stops for this pass.
+    pub(crate) fn max_bytes_per_file(&self) -> usize {
+        self.max_read_bytes_per_file.unwrap_or(self.max_read_bytes)
+    }
+
+    /// Whether the pass ends once a file had more to read than [`Self::max_bytes_per_file`].
+    pub(crate) const fn stops_when_maxed_out(&self) -> bool {
+        self.oldest_first && self.max_read_bytes_per_file.is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pass(scheduler: &mut ReadScheduler, file_count: usize) -> Vec<usize> {
+        scheduler.pass(file_count).collect()
+    }
+
+    #[test]
+    fn test_sequential_passes() {
+        let mut scheduler = ReadScheduler::new(2048, None, true);
+        assert_eq!(pass(&mut scheduler, 3), vec![0, 1, 2]);
+        assert_eq!(pass(&mut scheduler, 3), vec![0, 1, 2]);
+        assert_eq!(scheduler.max_bytes_per_file(), 2048);
+        assert!(scheduler.stops_when_maxed_out());
+
+        assert!(!ReadScheduler::new(2048, None, false).stops_when_maxed_out());
+    }
+
+    #[test]
+    fn test_fair_passes() {
+        let mut scheduler = ReadScheduler::new(2048, Some(512), true);
+        assert_eq!(pass(&mut scheduler, 3), vec![0, 1, 2]);
+        assert_eq!(pass(&mut scheduler, 3), vec![1, 2, 0]);
+        assert_eq!(pass(&mut scheduler, 3), vec![2, 0, 1]);
+        assert_eq!(pass(&mut scheduler, 3), vec![0, 1, 2]);
+        assert_eq!(scheduler.max_bytes_per_file(), 512);
+        assert!(!scheduler.stops_when_maxed_out());
+
+        // Files stop being watched between the passes.
+        assert_eq!(pass(&mut scheduler, 3), vec![1, 2, 0]);
+        assert_eq!(pass(&mut scheduler, 2), vec![0, 1]);
+        assert_eq!(pass(&mut scheduler, 0), Vec::<usize>::new());
+        assert_eq!(pass(&mut scheduler, 2), vec![1, 0]);
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+/// A token bucket limiting the rate at which a single file is read.
+///
+/// The bucket holds up to a second worth of bytes. Lines are never split to fit into the
+/// remaining tokens, so reading a line may overdraw the bucket, which is paid back before the
+/// file is read again.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    bytes_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket refilling at the given rate.
+    pub(crate) fn new(bytes_per_second: usize, now: Instant) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second as f64,
+            tokens: bytes_per_second as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Whether the file may be read from, after refilling the tokens accrued until `now`.
+    pub(crate) fn has_tokens(&mut self, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.bytes_per_second).min(self.bytes_per_second);
+        self.last_refill = now;
+        self.tokens > 0.0
+    }
+
+    /// Takes the tokens for the bytes read.
+    pub(crate) fn consume(&mut self, bytes: usize) {
+        self.tokens -= bytes as f64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(100, start);
+
+        assert!(bucket.has_tokens(start));
+        bucket.consume(60);
+        assert!(bucket.has_tokens(start));
+        // Lines aren't split, so the bucket is overdrawn.
+        bucket.consume(60);
+        assert!(!bucket.has_tokens(start));
+
+        // The overdrawn 20 bytes take 200ms to be paid back.
+        assert!(!bucket.has_tokens(start + Duration::from_millis(150)));
+        assert!(bucket.has_tokens(start + Duration::from_millis(250)));
+
+        // The bucket doesn't fill up beyond a second worth of bytes.
+        bucket.consume(5);
+        assert!(bucket.has_tokens(start + Duration::from_secs(10)));
+        bucket.consume(100);
+        assert!(!bucket.has_tokens(start + Duration::from_secs(10)));
+    }
+}
@@ -0,0 +1,677 @@
+//! Parsing of the paths of the pod log files written by the kubelet.
+//!
+//! The components of the paths are extracted the same way by the `kubernetes_logs` source and
+//! the `parse_k8s_log_path` VRL function, which both build on this crate.
+//!
+//! Loosely based on <https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go>.
+
+#![deny(missing_docs)]
+
+use std::borrow::Cow;
+
+use snafu::Snafu;
+
+/// The separators that can delimit the components of the log path.
+pub const PATH_SEPARATORS: &[char] = &['/', '\\'];
+
+/// The delimiter the kubelet uses between the components of the pod logs
+/// directory name, and of the container log symlink name.
+pub const DEFAULT_POD_DIR_DELIMITER: char = '_';
+
+/// The URL scheme prefix the paths reported by some file watchers carry.
+const FILE_URL_PREFIX: &str = "file://";
+
+/// An error parsing a pod log file path, telling why the path doesn't follow the pod log path
+/// layout.
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
+pub enum PathParseError {
+    /// The path has no container directory above the log file.
+    #[snafu(display("Path has no container directory above the log file."))]
+    MissingContainer,
+    /// The path has no pod logs directory above the container directory.
+    #[snafu(display("Path has no pod logs directory above the container directory."))]
+    MissingPodDir,
+    /// The pod logs directory name isn't made of the namespace, the name and the UID of the pod.
+    #[snafu(display(
+        "Pod logs directory {:?} has {} segment(s), expected at least 3.",
+        pod_dir,
+        found_segments
+    ))]
+    MalformedPodDir {
+        /// The name of the pod logs directory.
+        pod_dir: String,
+        /// The number of segments the pod logs directory name is split into by the delimiter.
+        found_segments: usize,
+    },
+}
+
+/// Parses pod log file path and returns the log file info.
+///
+/// Assumes the input is a valid pod log file name. The components are
+/// extracted from the end of the path, so the pod logs root directory can be
+/// located anywhere, and both `/` and `\\` are accepted as separators.
+/// A `file://` prefix and a single trailing separator are ignored.
+///
+/// Inspired by <https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go#L186>
+pub fn parse_log_file_path(path: &str) -> Option<LogFileInfo<'_>> {
+    try_parse_log_file_path(path).ok()
+}
+
+/// Parses pod log file path like [`parse_log_file_path`] does, but tells why
+/// the path doesn't parse.
+pub fn try_parse_log_file_path(path: &str) -> Result<LogFileInfo<'_>, PathParseError> {
+    try_parse_log_file_path_with_delimiter(path, DEFAULT_POD_DIR_DELIMITER)
+}
+
+/// Parses pod log file path like [`parse_log_file_path`] does, but with the
+/// components of the pod logs directory name delimited by `delimiter`.
+///
+/// The delimiter is only allowed in the pod name, so the other components must
+/// be guaranteed not to contain it. Kubernetes names may contain `-`, so a
+/// `-` delimiter only parses correctly if the kubelet writing the paths
+/// guarantees that the namespaces and the UIDs don't contain one.
+pub fn parse_log_file_path_with_delimiter(path: &str, delimiter: char) -> Option<LogFileInfo<'_>> {
+    try_parse_log_file_path_with_delimiter(path, delimiter).ok()
+}
+
+/// Parses pod log file path like [`parse_log_file_path_with_delimiter`] does,
+/// but tells why the path doesn't parse.
+pub fn try_parse_log_file_path_with_delimiter(
+    path: &str,
+    delimiter: char,
+) -> Result<LogFileInfo<'_>, PathParseError> {
+    let info = try_parse_log_file_path_bytes_with_delimiter(path.as_bytes(), delimiter)?;
+    // The path is only split where the separators and the encoded delimiter
+    // are, which are char boundaries of a string, so the components are valid
+    // UTF-8 too.
+    Ok(info
+        .to_log_file_info()
+        .expect("components of a string are valid UTF-8"))
+}
+
+/// Parses pod log file path like [`parse_log_file_path`] does, but without
+/// requiring the path to be valid UTF-8.
+///
+/// Container runtimes occasionally write file names that aren't valid UTF-8
+/// on Linux. Only the separators and the delimiters structure the path, so
+/// the components are still extracted, and only the ones that aren't valid
+/// UTF-8 themselves are returned as bytes.
+pub fn parse_log_file_path_bytes(path: &[u8]) -> Option<RawLogFileInfo<'_>> {
+    try_parse_log_file_path_bytes_with_delimiter(path, DEFAULT_POD_DIR_DELIMITER).ok()
+}
+
+/// Parses pod log file path like [`parse_log_file_path_bytes`] does, but with
+/// the components of the pod logs directory name delimited by `delimiter`,
+/// and tells why the path doesn't parse.
+pub fn try_parse_log_file_path_bytes_with_delimiter(
+    path: &[u8],
+    delimiter: char,
+) -> Result<RawLogFileInfo<'_>, PathParseError> {
+    let mut encoded_delimiter = [0; 4];
+    let delimiter = delimiter.encode_utf8(&mut encoded_delimiter).as_bytes();
+
+    let mut components = normalize_path_bytes(path).rsplit(|&byte| is_path_separator(byte));
+
+    // Splitting always yields at least one, possibly empty, component.
+    let log_file_name = components.next().unwrap_or_default();
+    let container_name = components.next().ok_or(PathParseError::MissingContainer)?;
+    let pod_dir = components.next().ok_or(PathParseError::MissingPodDir)?;
+
+    let malformed_pod_dir = || PathParseError::MalformedPodDir {
+        pod_dir: String::from_utf8_lossy(pod_dir).into_owned(),
+        found_segments: split_bytes(pod_dir, delimiter).len(),
+    };
+
+    // Neither namespaces nor UIDs can contain the delimiter, but pod names
+    // can, so the name is everything between the first and the last one,
+    // after the optional sandbox attempt and extra segments are split off.
+    let (pod_namespace, pod_dir_rest) =
+        split_once_bytes(pod_dir, delimiter).ok_or_else(malformed_pod_dir)?;
+    let (pod_dir_rest, extra_segments) = split_extra_segments(pod_dir_rest, delimiter);
+    let (pod_dir_rest, sandbox_attempt) = split_sandbox_attempt(pod_dir_rest, delimiter);
+    let (pod_name, pod_uid) =
+        rsplit_once_bytes(pod_dir_rest, delimiter).ok_or_else(malformed_pod_dir)?;
+
+    Ok(RawLogFileInfo {
+        pod_namespace: PathSegment::new(pod_namespace),
+        pod_name: PathSegment::new(pod_name),
+        pod_uid: PathSegment::new(pod_uid),
+        container_name: PathSegment::new(container_name),
+        file_name: PathSegment::new(log_file_name),
+        restart_count: parse_restart_count_bytes(log_file_name),
+        sandbox_attempt,
+        extra_segments: extra_segments.into_iter().map(PathSegment::new).collect(),
+    })
+}
+
+/// Parses the path like [`parse_log_file_path_bytes`] does, for the paths
+/// read from the file system.
+#[cfg(unix)]
+pub fn parse_log_file_os_path(path: &std::path::Path) -> Option<RawLogFileInfo<'_>> {
+    use std::os::unix::ffi::OsStrExt;
+
+    parse_log_file_path_bytes(path.as_os_str().as_bytes())
+}
+
+/// Splits the segments some distributions append to the pod logs directory
+/// name off the `<name>_<uid>[_<attempt>][_<extra>...]` rest of it.
+///
+/// The extra segments can't be told apart from the segments of a pod name
+/// containing the delimiter by their position alone, so they are only split
+/// off after a well-formed pod UID. The rest is returned whole, with no extra
+/// segments, if none of its segments after the first one is a pod UID.
+fn split_extra_segments<'a>(pod_dir_rest: &'a [u8], delimiter: &[u8]) -> (&'a [u8], Vec<&'a [u8]>) {
+    let mut uid_end = None;
+    let mut offset = 0;
+    for (index, segment) in split_bytes(pod_dir_rest, delimiter).into_iter().enumerate() {
+        offset += segment.len();
+        if index > 0 && is_valid_pod_uid(segment) {
+            uid_end = Some(offset);
+            break;
+        }
+        offset += delimiter.len();
+    }
+    let Some(uid_end) = uid_end else {
+        return (pod_dir_rest, Vec::new());
+    };
+
+    let mut extra_segments = match pod_dir_rest[uid_end..].strip_prefix(delimiter) {
+        Some(extra) => split_bytes(extra, delimiter),
+        None => return (pod_dir_rest, Vec::new()),
+    };
+    // The sandbox attempt directly follows the UID.
+    let attempt_end = match extra_segments.first() {
+        Some(attempt) if parse_u32(attempt).is_some() => {
+            let attempt_end = uid_end + delimiter.len() + attempt.len();
+            extra_segments.remove(0);
+            attempt_end
+        }
+        _ => uid_end,
+    };
+    (&pod_dir_rest[..attempt_end], extra_segments)
+}
+
+/// Strips a `file://` prefix and a single trailing separator off the path.
+///
+/// Paths reported by file system notifications may carry either, which would
+/// otherwise shift the components extracted from the end of the path.
+pub fn normalize_path(path: &str) -> &str {
+    let path = path.strip_prefix(FILE_URL_PREFIX).unwrap_or(path);
+    path.strip_suffix(PATH_SEPARATORS).unwrap_or(path)
+}
+
+/// Strips a `file://` prefix and a single trailing separator off the path,
+/// like [`normalize_path`] does.
+fn normalize_path_bytes(path: &[u8]) -> &[u8] {
+    let path = path
+        .strip_prefix(FILE_URL_PREFIX.as_bytes())
+        .unwrap_or(path);
+    match path.split_last() {
+        Some((&last, rest)) if is_path_separator(last) => rest,
+        _ => path,
+    }
+}
+
+/// Checks whether the byte is one of the [`PATH_SEPARATORS`].
+const fn is_path_separator(byte: u8) -> bool {
+    matches!(byte, b'/' | b'\\')
+}
+
+/// Splits the bytes on every occurrence of the non-empty `delimiter`, like
+/// [`str::split`] does.
+fn split_bytes<'a>(mut bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut segments = Vec::new();
+    while let Some((segment, rest)) = split_once_bytes(bytes, delimiter) {
+        segments.push(segment);
+        bytes = rest;
+    }
+    segments.push(bytes);
+    segments
+}
+
+/// Splits the bytes on the first occurrence of the non-empty `delimiter`,
+/// like [`str::split_once`] does.
+fn split_once_bytes<'a>(bytes: &'a [u8], delimiter: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let index = bytes
+        .windows(delimiter.len())
+        .position(|window| window == delimiter)?;
+    Some((&bytes[..index], &bytes[index + delimiter.len()..]))
+}
+
+/// Splits the bytes on the last occurrence of the non-empty `delimiter`,
+/// like [`str::rsplit_once`] does.
+fn rsplit_once_bytes<'a>(bytes: &'a [u8], delimiter: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let index = bytes
+        .windows(delimiter.len())
+        .rposition(|window| window == delimiter)?;
+    Some((&bytes[..index], &bytes[index + delimiter.len()..]))
+}
+
+/// Parses the bytes as a decimal number, if they are valid UTF-8.
+fn parse_u32(bytes: &[u8]) -> Option<u32> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Checks whether the pod UID segment of the pod logs directory is
+/// well-formed.
+///
+/// The API server assigns RFC 4122 UUIDs to the pods, but the directories
+/// of static pods are named after their config hashsum instead, which is an
+/// MD5 hex digest. Some
+/// distributions name the directories of their system pods with
+/// `system-<suffix>` UIDs instead, the suffix being made of alphanumerics and
+/// `-`.
+pub fn is_valid_pod_uid(uid: &[u8]) -> bool {
+    const UUID_GROUP_LENGTHS: [usize; 5] = [8, 4, 4, 4, 12];
+    const CONFIG_HASHSUM_LENGTH: usize = 32;
+    const SYSTEM_UID_PREFIX: &[u8] = b"system-";
+
+    let is_hex = |s: &[u8]| s.iter().all(u8::is_ascii_hexdigit);
+    let is_system_uid = |s: &[u8]| {
+        s.strip_prefix(SYSTEM_UID_PREFIX).map_or(false, |suffix| {
+            !suffix.is_empty()
+                && suffix
+                    .iter()
+                    .all(|&byte| byte.is_ascii_alphanumeric() || byte == b'-')
+        })
+    };
+
+    let mut groups = uid.split(|&byte| byte == b'-');
+    let is_uuid = UUID_GROUP_LENGTHS.iter().all(|&len| {
+        groups
+            .next()
+            .map_or(false, |group| group.len() == len && is_hex(group))
+    }) && groups.next().is_none();
+
+    is_uuid || (uid.len() == CONFIG_HASHSUM_LENGTH && is_hex(uid)) || is_system_uid(uid)
+}
+
+/// Parses the path of a container log symlink and returns the log file info,
+/// like [`parse_log_file_path`] does for the pod log files.
+///
+/// The kubelet keeps a symlink to every pod log file at
+/// `<container_logs_root>/<pod_name>_<pod_namespace>_<container_name>-<container_id>.log`,
+/// with the pod name and the namespace in the reverse order of the pod logs
+/// directory name. Neither the pod name, the namespace nor the container name
+/// can contain the delimiter, and the container ID can't contain a `-`, so the
+/// components are unambiguous even though the container name may contain
+/// hyphens. The pod UID is not a part of the file name, so it is left empty,
+/// and has to be obtained from the pod metadata instead.
+pub fn parse_container_symlink_path(path: &str) -> Option<LogFileInfo<'_>> {
+    let log_file_name = path.rsplit(PATH_SEPARATORS).next()?;
+    let stem = log_file_name.strip_suffix(".log")?;
+
+    let mut components = stem.splitn(3, DEFAULT_POD_DIR_DELIMITER);
+    let pod_name = components.next().filter(|name| !name.is_empty())?;
+    let pod_namespace = components
+        .next()
+        .filter(|namespace| !namespace.is_empty())?;
+    let (container_name, container_id) = components.next()?.rsplit_once('-')?;
+    if container_name.is_empty() || container_id.is_empty() {
+        return None;
+    }
+
+    Some(LogFileInfo {
+        pod_namespace,
+        pod_name,
+        pod_uid: "",
+        container_name,
+        file_name: log_file_name,
+        restart_count: None,
+        sandbox_attempt: None,
+        extra_segments: Vec::new(),
+    })
+}
+
+/// Splits the sandbox attempt off the `<name>_<uid>[_<attempt>]` rest of the
+/// pod logs directory name.
+///
+/// CRI-O and some versions of containerd name the pod logs directory as
+/// `<namespace>_<name>_<uid>_<attempt>`. A fourth segment is only taken for
+/// the attempt if it is numeric, as the other segments never are.
+fn split_sandbox_attempt<'a>(pod_dir_rest: &'a [u8], delimiter: &[u8]) -> (&'a [u8], Option<u32>) {
+    match rsplit_once_bytes(pod_dir_rest, delimiter) {
+        Some((name_and_uid, attempt)) if split_once_bytes(name_and_uid, delimiter).is_some() => {
+            match parse_u32(attempt) {
+                Some(attempt) => (name_and_uid, Some(attempt)),
+                None => (pod_dir_rest, None),
+            }
+        }
+        _ => (pod_dir_rest, None),
+    }
+}
+
+/// Parses the container restart count from the log file name.
+///
+/// The kubelet names the log files as `<restart_count>.log`, so the restart
+/// count is the numeric stem of the file name. Rotated files keep the stem and
+/// get a suffix appended, like `<restart_count>.log.<timestamp>`.
+pub fn parse_restart_count(log_file_name: &str) -> Option<u32> {
+    parse_restart_count_bytes(log_file_name.as_bytes())
+}
+
+/// Parses the container restart count from the log file name, like
+/// [`parse_restart_count`] does.
+fn parse_restart_count_bytes(log_file_name: &[u8]) -> Option<u32> {
+    let (stem, rotation_suffix) = split_once_bytes(log_file_name, b".log")?;
+    if !rotation_suffix.is_empty() && !rotation_suffix.starts_with(b".") {
+        return None;
+    }
+    parse_u32(stem)
+}
+
+/// Contains the information extracted from the pod log file path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LogFileInfo<'a> {
+    /// The namespace of the pod.
+    pub pod_namespace: &'a str,
+    /// The name of the pod.
+    pub pod_name: &'a str,
+    /// The UID of the pod, or an empty string if the path doesn't carry one.
+    pub pod_uid: &'a str,
+    /// The name of the container.
+    pub container_name: &'a str,
+    /// The name of the log file, e.g. `0.log` or a rotated `0.log.20240101-120000`.
+    pub file_name: &'a str,
+    /// The container restart count, if the log file name carries one.
+    pub restart_count: Option<u32>,
+    /// The pod sandbox attempt, if the pod logs directory carries one.
+    pub sandbox_attempt: Option<u32>,
+    /// The segments following the pod UID and the sandbox attempt in the pod
+    /// logs directory name, in order, which some distributions append.
+    pub extra_segments: Vec<&'a str>,
+}
+
+impl LogFileInfo<'_> {
+    /// Copies the borrowed information into an [`OwnedLogFileInfo`].
+    pub fn to_owned(&self) -> OwnedLogFileInfo {
+        OwnedLogFileInfo::from(self.clone())
+    }
+
+    /// Returns a 64-bit key identifying the stream of logs of the container
+    /// run the file belongs to, built from the pod UID, the container name and
+    /// the restart count.
+    ///
+    /// The files of the same container run, like its rotated files, share the
+    /// key. The key is computed with SeaHash over a length-prefixed encoding of
+    /// the components rather than with [`std::hash::Hash`], so it is the same
+    /// across platforms, processes and Vector releases, and can be persisted.
+    pub fn stream_key(&self) -> u64 {
+        let mut bytes =
+            Vec::with_capacity(8 + self.pod_uid.len() + 8 + self.container_name.len() + 5);
+        for component in [self.pod_uid, self.container_name] {
+            bytes.extend_from_slice(&(component.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(component.as_bytes());
+        }
+        match self.restart_count {
+            Some(restart_count) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&restart_count.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+        seahash::hash(&bytes)
+    }
+}
+
+/// An owned version of [`LogFileInfo`], for when the information has to
+/// outlive the path it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct OwnedLogFileInfo {
+    /// The namespace of the pod.
+    pub pod_namespace: String,
+    /// The name of the pod.
+    pub pod_name: String,
+    /// The UID of the pod, or an empty string if the path doesn't carry one.
+    pub pod_uid: String,
+    /// The name of the container.
+    pub container_name: String,
+    /// The name of the log file.
+    pub file_name: String,
+    /// The container restart count, if the log file name carries one.
+    pub restart_count: Option<u32>,
+    /// The pod sandbox attempt, if the pod logs directory carries one.
+    pub sandbox_attempt: Option<u32>,
+    /// The segments following the pod UID and the sandbox attempt in the pod
+    /// logs directory name.
+    pub extra_segments: Vec<String>,
+}
+
+/// A component of a pod log file path, borrowed as a string if it is valid
+/// UTF-8, and as bytes otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// The component is valid UTF-8.
+    Str(&'a str),
+    /// The component isn't valid UTF-8.
+    Bytes(&'a [u8]),
+}
+
+impl<'a> PathSegment<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        std::str::from_utf8(bytes).map_or(Self::Bytes(bytes), Self::Str)
+    }
+
+    /// Returns the component as a string, if it is valid UTF-8.
+    pub const fn as_str(&self) -> Option<&'a str> {
+        match *self {
+            Self::Str(segment) => Some(segment),
+            Self::Bytes(_) => None,
+        }
+    }
+
+    /// Returns the bytes of the component.
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        match *self {
+            Self::Str(segment) => segment.as_bytes(),
+            Self::Bytes(segment) => segment,
+        }
+    }
+
+    /// Returns the component as a string, with the invalid UTF-8 sequences
+    /// replaced by `U+FFFD REPLACEMENT CHARACTER`.
+    pub fn to_string_lossy(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+}
+
+/// Contains the information extracted from a pod log file path that may not
+/// be valid UTF-8, see [`parse_log_file_path_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct RawLogFileInfo<'a> {
+    /// The namespace of the pod.
+    pub pod_namespace: PathSegment<'a>,
+    /// The name of the pod.
+    pub pod_name: PathSegment<'a>,
+    /// The UID of the pod.
+    pub pod_uid: PathSegment<'a>,
+    /// The name of the container.
+    pub container_name: PathSegment<'a>,
+    /// The name of the log file.
+    pub file_name: PathSegment<'a>,
+    /// The container restart count, if the log file name carries one.
+    pub restart_count: Option<u32>,
+    /// The pod sandbox attempt, if the pod logs directory carries one.
+    pub sandbox_attempt: Option<u32>,
+    /// The segments following the pod UID and the sandbox attempt in the pod
+    /// logs directory name.
+    pub extra_segments: Vec<PathSegment<'a>>,
+}
+
+impl<'a> RawLogFileInfo<'a> {
+    /// Borrows the components as strings in a [`LogFileInfo`], if all of
+    /// them are valid UTF-8.
+    pub fn to_log_file_info(&self) -> Option<LogFileInfo<'a>> {
+        Some(LogFileInfo {
+            pod_namespace: self.pod_namespace.as_str()?,
+            pod_name: self.pod_name.as_str()?,
+            pod_uid: self.pod_uid.as_str()?,
+            container_name: self.container_name.as_str()?,
+            file_name: self.file_name.as_str()?,
+            restart_count: self.restart_count,
+            sandbox_attempt: self.sandbox_attempt,
+            extra_segments: self
+                .extra_segments
+                .iter()
+                .map(PathSegment::as_str)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl<'a> From<LogFileInfo<'a>> for RawLogFileInfo<'a> {
+    fn from(info: LogFileInfo<'a>) -> Self {
+        Self {
+            pod_namespace: PathSegment::Str(info.pod_namespace),
+            pod_name: PathSegment::Str(info.pod_name),
+            pod_uid: PathSegment::Str(info.pod_uid),
+            container_name: PathSegment::Str(info.container_name),
+            file_name: PathSegment::Str(info.file_name),
+            restart_count: info.restart_count,
+            sandbox_attempt: info.sandbox_attempt,
+            extra_segments: info
+                .extra_segments
+                .into_iter()
+                .map(PathSegment::Str)
+                .collect(),
+        }
+    }
+}
+
+impl From<LogFileInfo<'_>> for OwnedLogFileInfo {
+    fn from(info: LogFileInfo<'_>) -> Self {
+        Self {
+            pod_namespace: info.pod_namespace.to_owned(),
+            pod_name: info.pod_name.to_owned(),
+            pod_uid: info.pod_uid.to_owned(),
+            container_name: info.container_name.to_owned(),
+            file_name: info.file_name.to_owned(),
+            restart_count: info.restart_count,
+            sandbox_attempt: info.sandbox_attempt,
+            extra_segments: info.extra_segments.into_iter().map(str::to_owned).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UID: &str = "8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b";
+
+    #[test]
+    fn test_is_valid_pod_uid() {
+        let cases = vec![
+            (UID, true),
+            ("8F7E2C1A-3B4D-4E5F-9A6B-7C8D9E0F1A2B", true),
+            // Static pods are keyed by the config hashsum.
+            ("0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6d", true),
+            ("system-7f9c2b1d", true),
+            ("system-node-0", true),
+            ("system-", false),
+            ("system-dns.0", false),
+            ("8f7e2c1a-3b4d-4e5f-9a6b", false),
+            ("8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b-00", false),
+            ("zf7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b", false),
+            ("0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6", false),
+            ("sandbox0-uid", false),
+            ("", false),
+        ];
+
+        for (uid, expected) in cases.into_iter() {
+            assert_eq!(is_valid_pod_uid(uid.as_bytes()), expected, "{}", uid);
+        }
+    }
+
+    #[test]
+    fn test_parse_restart_count() {
+        let cases = vec![
+            ("0.log", Some(0)),
+            ("12.log", Some(12)),
+            ("1.log.20240101-120000", Some(1)),
+            ("1.log.20240101-120000.gz", Some(1)),
+            ("1.logs", None),
+            ("app.log", None),
+            ("-1.log", None),
+            ("", None),
+        ];
+
+        for (file_name, expected) in cases.into_iter() {
+            assert_eq!(parse_restart_count(file_name), expected, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        let cases = vec![
+            (
+                "/var/log/pods/ns_name_uid/c/0.log",
+                "/var/log/pods/ns_name_uid/c/0.log",
+            ),
+            (
+                "file:///var/log/pods/ns_name_uid/c/0.log",
+                "/var/log/pods/ns_name_uid/c/0.log",
+            ),
+            (
+                "/var/log/pods/ns_name_uid/c/0.log/",
+                "/var/log/pods/ns_name_uid/c/0.log",
+            ),
+            ("C:\\var\\log\\pods\\", "C:\\var\\log\\pods"),
+            // Only a single trailing separator is stripped.
+            ("/var/log//", "/var/log/"),
+            ("", ""),
+        ];
+
+        for (path, expected) in cases.into_iter() {
+            assert_eq!(normalize_path(path), expected, "{}", path);
+            assert_eq!(
+                normalize_path_bytes(path.as_bytes()),
+                expected.as_bytes(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_log_file_path_with_delimiter() {
+        let path = format!(
+            "/var/log/pods/sandbox0-ns-sandbox0-name-{}-3/app/2.log",
+            UID
+        );
+        let info = parse_log_file_path_with_delimiter(&path, '-').unwrap();
+        // The pod name can't be told apart from the UID with a `-` delimiter,
+        // which the UIDs contain, so only the namespace and the attempt are.
+        assert_eq!(info.pod_namespace, "sandbox0");
+        assert_eq!(info.sandbox_attempt, Some(3));
+        assert_eq!(info.container_name, "app");
+        assert_eq!(info.restart_count, Some(2));
+
+        let path = format!(
+            "/var/log/pods/sandbox0-ns~sandbox0-name~{}~3/app/2.log",
+            UID
+        );
+        let info = parse_log_file_path_with_delimiter(&path, '~').unwrap();
+        assert_eq!(info.pod_namespace, "sandbox0-ns");
+        assert_eq!(info.pod_name, "sandbox0-name");
+        assert_eq!(info.pod_uid, UID);
+        assert_eq!(info.sandbox_attempt, Some(3));
+    }
+
+    #[test]
+    fn test_raw_log_file_info_from_log_file_info() {
+        let path = format!(
+            "/var/log/pods/sandbox0-ns_sandbox0-name_{}_1_zone-a/app/0.log",
+            UID
+        );
+        let info = try_parse_log_file_path(&path).unwrap();
+        let raw = RawLogFileInfo::from(info.clone());
+
+        assert_eq!(raw, parse_log_file_path_bytes(path.as_bytes()).unwrap());
+        assert_eq!(raw.to_log_file_info(), Some(info));
+    }
+}
@@ -1,6 +1,7 @@
 fn main() {
     println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
     println!("cargo:rerun-if-changed=proto/prometheus-types.proto");
+    println!("cargo:rerun-if-changed=proto/io/prometheus/write/v2/types.proto");
     let mut prost_build = prost_build::Config::new();
     prost_build.btree_map(["."]);
     // It would be nice to just add these derives to all the types, but
@@ -9,7 +10,10 @@ fn main() {
     prost_build.type_attribute("Label", "#[derive(Eq, Hash, Ord, PartialOrd)]");
     prost_build
         .compile_protos(
-            &["proto/prometheus-remote.proto"],
+            &[
+                "proto/prometheus-remote.proto",
+                "proto/io/prometheus/write/v2/types.proto",
+            ],
             &["proto", "../../proto"],
         )
         .unwrap();
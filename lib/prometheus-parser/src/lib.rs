@@ -32,6 +32,13 @@ pub mod proto {
             }
         }
     }
+
+    /// The Remote Write 2.0 request format.
+    pub mod v2 {
+        include!(concat!(env!("OUT_DIR"), "/io.prometheus.write.v2.rs"));
+
+        pub use metadata::MetricType;
+    }
 }
 
 #[derive(Debug, snafu::Snafu, PartialEq)]
@@ -60,6 +67,10 @@ pub enum ParserError {
     MultipleMetricKinds { name: String },
     #[snafu(display("request is missing metric name label"))]
     RequestNoNameLabel,
+    #[snafu(display("request references missing symbol {}", index))]
+    RequestMissingSymbol { index: u32 },
+    #[snafu(display("request has an unpaired label reference"))]
+    RequestUnpairedLabelRef,
 }
 
 vector_common::impl_event_data_eq!(ParserError);
@@ -232,10 +243,28 @@ impl GroupKind {
     }
 }
 
+/// The help and unit of a metric group, when given by a remote_write request.
+#[derive(Debug, Default, PartialEq)]
+pub struct GroupMetadata {
+    pub help: Option<String>,
+    pub unit: Option<String>,
+}
+
+impl GroupMetadata {
+    fn new(help: String, unit: String) -> Self {
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+        Self {
+            help: non_empty(help),
+            unit: non_empty(unit),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct MetricGroup {
     pub name: String,
     pub metrics: GroupKind,
+    pub metadata: GroupMetadata,
 }
 
 fn try_f64_to_u64(f: f64) -> Result<u64, ParserError> {
@@ -252,7 +281,11 @@ fn try_f64_to_u64(f: f64) -> Result<u64, ParserError> {
 impl MetricGroup {
     fn new(name: String, kind: MetricKind) -> Self {
         let metrics = GroupKind::new(kind);
-        MetricGroup { name, metrics }
+        MetricGroup {
+            name,
+            metrics,
+            metadata: GroupMetadata::default(),
+        }
     }
 
     // For cases where a metric group was not defined with `# TYPE ...`.
@@ -267,6 +300,7 @@ impl MetricGroup {
         MetricGroup {
             name,
             metrics: GroupKind::new_untyped(key, value),
+            metadata: GroupMetadata::default(),
         }
     }
 
@@ -316,40 +350,49 @@ pub fn parse_text(input: &str) -> Result<Vec<MetricGroup>, ParserError> {
 }
 
 #[derive(Default)]
-struct MetricGroupSet(IndexMap<String, GroupKind>);
+struct MetricGroupSet {
+    groups: IndexMap<String, GroupKind>,
+    metadata: BTreeMap<String, GroupMetadata>,
+}
 
 impl MetricGroupSet {
     fn get_group<'a>(&'a mut self, name: &str) -> (usize, &'a String, &'a mut GroupKind) {
         let len = name.len();
-        let name = if self.0.contains_key(name) {
+        let name = if self.groups.contains_key(name) {
             name
-        } else if name.ends_with("_bucket") && self.0.contains_key(&name[..len - 7]) {
+        } else if name.ends_with("_bucket") && self.groups.contains_key(&name[..len - 7]) {
             &name[..len - 7]
-        } else if name.ends_with("_sum") && self.0.contains_key(&name[..len - 4]) {
+        } else if name.ends_with("_sum") && self.groups.contains_key(&name[..len - 4]) {
             &name[..len - 4]
-        } else if name.ends_with("_count") && self.0.contains_key(&name[..len - 6]) {
+        } else if name.ends_with("_count") && self.groups.contains_key(&name[..len - 6]) {
             &name[..len - 6]
         } else {
-            self.0
+            self.groups
                 .insert(name.into(), GroupKind::new(MetricKind::Untyped));
             name
         };
-        self.0.get_full_mut(name).unwrap()
+        self.groups.get_full_mut(name).unwrap()
     }
 
     fn insert_metadata(&mut self, name: String, kind: MetricKind) -> Result<(), ParserError> {
-        match self.0.get(&name) {
+        match self.groups.get(&name) {
             Some(group) if !group.matches_kind(kind) => {
                 Err(ParserError::MultipleMetricKinds { name })
             }
             Some(_) => Ok(()), // metadata already exists and is the right type
             None => {
-                self.0.insert(name, GroupKind::new(kind));
+                self.groups.insert(name, GroupKind::new(kind));
                 Ok(())
             }
         }
     }
 
+    fn insert_help_and_unit(&mut self, name: &str, metadata: GroupMetadata) {
+        if metadata != GroupMetadata::default() && !self.metadata.contains_key(name) {
+            self.metadata.insert(name.into(), metadata);
+        }
+    }
+
     fn insert_sample(
         &mut self,
         name: &str,
@@ -371,15 +414,19 @@ impl MetricGroupSet {
                 labels: metric.labels,
             };
             let group = GroupKind::new_untyped(key, metric.value);
-            self.0.insert(metric.name, group);
+            self.groups.insert(metric.name, group);
         }
         Ok(())
     }
 
-    fn finish(self) -> Vec<MetricGroup> {
-        self.0
+    fn finish(mut self) -> Vec<MetricGroup> {
+        self.groups
             .into_iter()
-            .map(|(name, metrics)| MetricGroup { name, metrics })
+            .map(|(name, metrics)| MetricGroup {
+                metadata: self.metadata.remove(&name).unwrap_or_default(),
+                name,
+                metrics,
+            })
             .collect()
     }
 }
@@ -394,6 +441,7 @@ pub fn parse_request(request: proto::WriteRequest) -> Result<Vec<MetricGroup>, P
         let kind = proto::MetricType::try_from(metadata.r#type)
             .unwrap_or(proto::MetricType::Unknown)
             .into();
+        groups.insert_help_and_unit(&name, GroupMetadata::new(metadata.help, metadata.unit));
         groups.insert_metadata(name, kind)?;
     }
 
@@ -416,6 +464,89 @@ pub fn parse_request(request: proto::WriteRequest) -> Result<Vec<MetricGroup>, P
     Ok(groups.finish())
 }
 
+/// Parse the given Remote Write 2.0 request, grouping the metrics into
+/// higher-level metric types based on the metadata of each series.
+///
+/// The created timestamps of the series are added as the `<name>_created`
+/// metrics, in seconds, following the OpenMetrics exposition of the created
+/// timestamps.
+pub fn parse_request_v2(request: proto::v2::Request) -> Result<Vec<MetricGroup>, ParserError> {
+    let symbols = request.symbols;
+    let symbol = |index: u32| {
+        symbols
+            .get(index as usize)
+            .cloned()
+            .ok_or(ParserError::RequestMissingSymbol { index })
+    };
+    let mut groups = MetricGroupSet::default();
+
+    for timeseries in request.timeseries {
+        let refs = timeseries.labels_refs;
+        if refs.len() % 2 != 0 {
+            return Err(ParserError::RequestUnpairedLabelRef);
+        }
+        let mut labels = refs
+            .chunks_exact(2)
+            .map(|pair| Ok((symbol(pair[0])?, symbol(pair[1])?)))
+            .collect::<Result<BTreeMap<_, _>, ParserError>>()?;
+        let name = match labels.remove(METRIC_NAME_LABEL) {
+            Some(name) => name,
+            None => return Err(ParserError::RequestNoNameLabel),
+        };
+
+        let metadata = timeseries.metadata.unwrap_or_default();
+        let r#type = proto::v2::MetricType::try_from(metadata.r#type)
+            .unwrap_or(proto::v2::MetricType::Unspecified);
+        let family_name = family_name(&name, r#type);
+        groups.insert_help_and_unit(
+            family_name,
+            GroupMetadata::new(symbol(metadata.help_ref)?, symbol(metadata.unit_ref)?),
+        );
+        if r#type != proto::v2::MetricType::Unspecified {
+            groups.insert_metadata(family_name.into(), r#type.into())?;
+        }
+
+        if timeseries.created_timestamp != 0 {
+            let created_name = format!("{}_created", family_name.trim_end_matches("_total"));
+            let mut created_labels = labels.clone();
+            created_labels.remove("le");
+            created_labels.remove("quantile");
+            for sample in &timeseries.samples {
+                let created = proto::Sample {
+                    value: timeseries.created_timestamp as f64 / 1000.0,
+                    timestamp: sample.timestamp,
+                };
+                groups.insert_sample(&created_name, &created_labels, created)?;
+            }
+        }
+
+        for sample in timeseries.samples {
+            let sample = proto::Sample {
+                value: sample.value,
+                timestamp: sample.timestamp,
+            };
+            groups.insert_sample(&name, &labels, sample)?;
+        }
+    }
+
+    Ok(groups.finish())
+}
+
+/// The name of the family of the series, without the suffixes of the
+/// histogram and summary series.
+fn family_name(name: &str, r#type: proto::v2::MetricType) -> &str {
+    use proto::v2::MetricType::*;
+    let suffixes: &[&str] = match r#type {
+        Histogram | Gaugehistogram => &["_bucket", "_sum", "_count"],
+        Summary => &["_sum", "_count"],
+        _ => &[],
+    };
+    suffixes
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name)
+}
+
 impl From<proto::MetricType> for MetricKind {
     fn from(kind: proto::MetricType) -> Self {
         use proto::MetricType::*;
@@ -430,6 +561,20 @@ impl From<proto::MetricType> for MetricKind {
     }
 }
 
+impl From<proto::v2::MetricType> for MetricKind {
+    fn from(kind: proto::v2::MetricType) -> Self {
+        use proto::v2::MetricType::*;
+        match kind {
+            Counter => MetricKind::Counter,
+            Gauge => MetricKind::Gauge,
+            Histogram => MetricKind::Histogram,
+            Gaugehistogram => MetricKind::Histogram,
+            Summary => MetricKind::Summary,
+            _ => MetricKind::Untyped,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -886,4 +1031,202 @@ mod test {
             assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(Some(1395066367700), labels!(), 24.0));
         });
     }
+
+    type SeriesV2<'a> = (
+        &'a [(&'a str, &'a str)],
+        proto::v2::MetricType,
+        (&'a str, &'a str),
+        i64,
+        &'a [(f64, i64)],
+    );
+
+    /// Builds a Remote Write 2.0 request from the labels, type, help and
+    /// unit, created timestamp, and samples of each series, interning the
+    /// strings into its symbols.
+    fn request_v2(series: &[SeriesV2<'_>]) -> proto::v2::Request {
+        let mut symbols = vec![String::new()];
+        let mut symbol = |value: &str| match symbols.iter().position(|symbol| symbol == value) {
+            Some(index) => index as u32,
+            None => {
+                symbols.push(value.into());
+                symbols.len() as u32 - 1
+            }
+        };
+
+        let timeseries = series
+            .iter()
+            .map(
+                |(labels, r#type, (help, unit), created_timestamp, samples)| {
+                    proto::v2::TimeSeries {
+                        labels_refs: labels
+                            .iter()
+                            .flat_map(|(name, value)| [symbol(name), symbol(value)])
+                            .collect(),
+                        samples: samples
+                            .iter()
+                            .map(|(value, timestamp)| proto::v2::Sample {
+                                value: *value,
+                                timestamp: *timestamp,
+                            })
+                            .collect(),
+                        metadata: Some(proto::v2::Metadata {
+                            r#type: *r#type as i32,
+                            help_ref: symbol(help),
+                            unit_ref: symbol(unit),
+                        }),
+                        created_timestamp: *created_timestamp,
+                    }
+                },
+            )
+            .collect();
+        proto::v2::Request {
+            symbols,
+            timeseries,
+        }
+    }
+
+    #[test]
+    fn parse_request_v2_counter() {
+        let parsed = parse_request_v2(request_v2(&[
+            (
+                &[("__name__", "http_requests_total"), ("method", "post")],
+                proto::v2::MetricType::Counter,
+                ("The total number of HTTP requests.", ""),
+                1395066360000,
+                &[(1027.0, 1395066363000)],
+            ),
+            (
+                &[("__name__", "temperature")],
+                proto::v2::MetricType::Unspecified,
+                ("", ""),
+                0,
+                &[(21.5, 1395066363000)],
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.len(), 3);
+        match_group!(parsed[0], "http_requests_total", Counter => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(Some(1395066363000), labels!(method => "post"), 1027.0)
+            );
+        });
+        assert_eq!(
+            parsed[0].metadata,
+            GroupMetadata {
+                help: Some("The total number of HTTP requests.".into()),
+                unit: None,
+            }
+        );
+        match_group!(parsed[1], "http_requests_created", Untyped => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(Some(1395066363000), labels!(method => "post"), 1395066360.0)
+            );
+        });
+        match_group!(parsed[2], "temperature", Untyped => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+        });
+        assert_eq!(parsed[2].metadata, GroupMetadata::default());
+    }
+
+    #[test]
+    fn parse_request_v2_histogram() {
+        let histogram = proto::v2::MetricType::Histogram;
+        let metadata = ("A histogram of the request duration.", "seconds");
+        let parsed = parse_request_v2(request_v2(&[
+            (
+                &[("__name__", "one_bucket"), ("le", "1")],
+                histogram,
+                metadata,
+                1395066360000,
+                &[(15.0, 1395066367700)],
+            ),
+            (
+                &[("__name__", "one_bucket"), ("le", "+Inf")],
+                histogram,
+                metadata,
+                1395066360000,
+                &[(19.0, 1395066367700)],
+            ),
+            (
+                &[("__name__", "one_count")],
+                histogram,
+                metadata,
+                1395066360000,
+                &[(19.0, 1395066367700)],
+            ),
+            (
+                &[("__name__", "one_sum")],
+                histogram,
+                metadata,
+                1395066360000,
+                &[(12.0, 1395066367700)],
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        match_group!(parsed[0], "one", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            assert_eq!(
+                metrics.get_index(0).unwrap(), (
+                    &GroupKey {
+                        timestamp: Some(1395066367700),
+                        labels: labels!(),
+                    },
+                    &HistogramMetric {
+                        buckets: vec![
+                            HistogramBucket { bucket: 1.0, count: 15 },
+                            HistogramBucket { bucket: f64::INFINITY, count: 19 },
+                        ],
+                        count: 19,
+                        sum: 12.0,
+                    })
+            );
+        });
+        assert_eq!(parsed[0].metadata.unit.as_deref(), Some("seconds"));
+        // The created timestamp is shared by the series of the histogram.
+        match_group!(parsed[1], "one_created", Untyped => |metrics: &MetricMap<SimpleMetric>| {
+            assert_eq!(metrics.len(), 1);
+            assert_eq!(
+                metrics.get_index(0).unwrap(),
+                simple_metric!(Some(1395066367700), labels!(), 1395066360.0)
+            );
+        });
+    }
+
+    #[test]
+    fn parse_request_v2_invalid() {
+        let unnamed = request_v2(&[(
+            &[("job", "node")],
+            proto::v2::MetricType::Gauge,
+            ("", ""),
+            0,
+            &[(1.0, 1395066367700)],
+        )]);
+        assert_eq!(
+            parse_request_v2(unnamed).unwrap_err(),
+            ParserError::RequestNoNameLabel
+        );
+
+        let mut missing_symbol = request_v2(&[(
+            &[("__name__", "one")],
+            proto::v2::MetricType::Gauge,
+            ("", ""),
+            0,
+            &[(1.0, 1395066367700)],
+        )]);
+        missing_symbol.timeseries[0].labels_refs[1] = 42;
+        assert_eq!(
+            parse_request_v2(missing_symbol.clone()).unwrap_err(),
+            ParserError::RequestMissingSymbol { index: 42 }
+        );
+
+        missing_symbol.timeseries[0].labels_refs.pop();
+        assert_eq!(
+            parse_request_v2(missing_symbol).unwrap_err(),
+            ParserError::RequestUnpairedLabelRef
+        );
+    }
 }
@@ -4,6 +4,7 @@ use vrl::compiler::Function;
 use vrl::path::OwnedTargetPath;
 
 pub mod get_secret;
+pub mod parse_k8s_log_path;
 pub mod remove_secret;
 pub mod set_secret;
 pub mod set_semantic_meaning;
@@ -23,5 +24,6 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(get_secret::GetSecret) as _,
         Box::new(remove_secret::RemoveSecret) as _,
         Box::new(set_secret::SetSecret) as _,
+        Box::new(parse_k8s_log_path::ParseK8sLogPath) as _,
     ]
 }
@@ -0,0 +1,474 @@
+use std::collections::BTreeMap;
+
+use k8s_log_path_parser::{
+    parse_container_symlink_path, try_parse_log_file_path_bytes_with_delimiter, PathSegment,
+    RawLogFileInfo, DEFAULT_POD_DIR_DELIMITER,
+};
+use vrl::prelude::*;
+use vrl::value::{kind::Field, ObjectMap};
+
+/// The layout the path is parsed in, like the `log_layout` of the `kubernetes_logs` source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layout {
+    /// The `<pod_logs_root>/<namespace>_<name>_<uid>/<container>/<n>.log` pod log files.
+    Pods,
+    /// The `<container_logs_root>/<name>_<namespace>_<container>-<id>.log` container log symlinks.
+    Containers,
+    /// The pod log files, falling back to the container log symlinks.
+    Auto,
+}
+
+fn layouts() -> Vec<Value> {
+    vec!["pods".into(), "containers".into(), "auto".into()]
+}
+
+impl Layout {
+    fn from_value(value: &Value) -> Self {
+        match value.as_bytes().map(|bytes| bytes.as_ref()) {
+            Some(b"containers") => Self::Containers,
+            Some(b"auto") => Self::Auto,
+            _ => Self::Pods,
+        }
+    }
+}
+
+fn parse_k8s_log_path(value: Value, delimiter: Value, layout: Layout) -> Resolved {
+    let path = value.try_bytes()?;
+    let delimiter = delimiter.try_bytes_utf8_lossy()?;
+    let delimiter = match delimiter.chars().collect::<Vec<_>>()[..] {
+        [delimiter] => delimiter,
+        _ => {
+            return Err(format!("delimiter must be a single character, got {:?}", delimiter).into())
+        }
+    };
+
+    let parse_pod_log_path = || try_parse_log_file_path_bytes_with_delimiter(&path, delimiter);
+    let parse_symlink_path = || {
+        std::str::from_utf8(&path)
+            .ok()
+            .and_then(parse_container_symlink_path)
+            .map(RawLogFileInfo::from)
+    };
+    let info = match layout {
+        Layout::Pods => parse_pod_log_path().map_err(|error| error.to_string()),
+        Layout::Containers => parse_symlink_path()
+            .ok_or_else(|| "Path is not a container log symlink path.".to_owned()),
+        Layout::Auto => parse_pod_log_path()
+            .or_else(|error| parse_symlink_path().ok_or_else(|| error.to_string())),
+    }
+    .map_err(|error| {
+        format!(
+            "unable to parse Kubernetes log path {:?}: {}",
+            String::from_utf8_lossy(&path),
+            error
+        )
+    })?;
+
+    let segment = |segment: PathSegment<'_>| Value::Bytes(segment.as_bytes().to_vec().into());
+    let number =
+        |number: Option<u32>| number.map_or(Value::Null, |number| Value::Integer(number.into()));
+    Ok(Value::Object(ObjectMap::from([
+        ("pod_namespace".into(), segment(info.pod_namespace)),
+        ("pod_name".into(), segment(info.pod_name)),
+        ("pod_uid".into(), segment(info.pod_uid)),
+        ("container_name".into(), segment(info.container_name)),
+        ("file_name".into(), segment(info.file_name)),
+        ("restart_count".into(), number(info.restart_count)),
+        ("sandbox_attempt".into(), number(info.sandbox_attempt)),
+    ])))
+}
+
+fn inner_kind() -> BTreeMap<Field, Kind> {
+    BTreeMap::from([
+        ("pod_namespace".into(), Kind::bytes()),
+        ("pod_name".into(), Kind::bytes()),
+        ("pod_uid".into(), Kind::bytes()),
+        ("container_name".into(), Kind::bytes()),
+        ("file_name".into(), Kind::bytes()),
+        ("restart_count".into(), Kind::integer().or_null()),
+        ("sandbox_attempt".into(), Kind::integer().or_null()),
+    ])
+}
+
+/// Parses the path of a pod log file, as written by the kubelet below `/var/log/pods`, with the
+/// same parser as the `kubernetes_logs` source.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseK8sLogPath;
+
+impl Function for ParseK8sLogPath {
+    fn identifier(&self) -> &'static str {
+        "parse_k8s_log_path"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "layout",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "Parse a pod log file path",
+                source: r#"parse_k8s_log_path!("/var/log/pods/default_nginx_b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d/nginx/0.log")"#,
+                result: Ok(
+                    r#"{"container_name": "nginx", "file_name": "0.log", "pod_name": "nginx", "pod_namespace": "default", "pod_uid": "b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d", "restart_count": 0, "sandbox_attempt": null}"#,
+                ),
+            },
+            Example {
+                title: "Parse a pod log file path with a sandbox attempt",
+                source: r#"parse_k8s_log_path!("file:///var/log/pods/default_nginx_b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d_1/nginx/2.log")"#,
+                result: Ok(
+                    r#"{"container_name": "nginx", "file_name": "2.log", "pod_name": "nginx", "pod_namespace": "default", "pod_uid": "b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d", "restart_count": 2, "sandbox_attempt": 1}"#,
+                ),
+            },
+            Example {
+                title: "Parse a pod log file path with a custom delimiter",
+                source: r#"parse_k8s_log_path!("/var/log/pods/default~nginx~b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d/nginx/0.log", delimiter: "~")"#,
+                result: Ok(
+                    r#"{"container_name": "nginx", "file_name": "0.log", "pod_name": "nginx", "pod_namespace": "default", "pod_uid": "b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d", "restart_count": 0, "sandbox_attempt": null}"#,
+                ),
+            },
+            Example {
+                title: "Parse a container log symlink path",
+                source: r#"parse_k8s_log_path!("/var/log/containers/nginx_default_nginx-8a3c1e4f.log", layout: "containers")"#,
+                result: Ok(
+                    r#"{"container_name": "nginx", "file_name": "nginx_default_nginx-8a3c1e4f.log", "pod_name": "nginx", "pod_namespace": "default", "pod_uid": "", "restart_count": null, "sandbox_attempt": null}"#,
+                ),
+            },
+            Example {
+                title: "Parse an invalid path",
+                source: r#"parse_k8s_log_path!("/var/log/syslog")"#,
+                result: Err(
+                    r#"function call error for "parse_k8s_log_path" at (0:38): unable to parse Kubernetes log path "/var/log/syslog": Pod logs directory "var" has 1 segment(s), expected at least 3."#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter");
+        let layout = arguments
+            .optional_enum("layout", &layouts(), state)?
+            .map_or(Layout::Pods, |layout| Layout::from_value(&layout));
+
+        Ok(ParseK8sLogPathFn {
+            value,
+            delimiter,
+            layout,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseK8sLogPathFn {
+    value: Box<dyn Expression>,
+    delimiter: Option<Box<dyn Expression>>,
+    layout: Layout,
+}
+
+impl FunctionExpression for ParseK8sLogPathFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let delimiter = self
+            .delimiter
+            .as_ref()
+            .map(|delimiter| delimiter.resolve(ctx))
+            .transpose()?
+            .unwrap_or_else(|| DEFAULT_POD_DIR_DELIMITER.to_string().into());
+        parse_k8s_log_path(value, delimiter, self.layout)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UID: &str = "8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b";
+
+    fn parse(path: &str, delimiter: char, layout: Layout) -> Result<Value, String> {
+        parse_k8s_log_path(path.into(), delimiter.to_string().into(), layout)
+            .map_err(|error| error.to_string())
+    }
+
+    fn expected(
+        pod_namespace: &str,
+        pod_name: &str,
+        pod_uid: &str,
+        container_name: &str,
+        file_name: &str,
+        restart_count: Option<u32>,
+        sandbox_attempt: Option<u32>,
+    ) -> Value {
+        Value::Object(ObjectMap::from([
+            ("pod_namespace".into(), pod_namespace.into()),
+            ("pod_name".into(), pod_name.into()),
+            ("pod_uid".into(), pod_uid.into()),
+            ("container_name".into(), container_name.into()),
+            ("file_name".into(), file_name.into()),
+            (
+                "restart_count".into(),
+                restart_count.map_or(Value::Null, |count| Value::Integer(count.into())),
+            ),
+            (
+                "sandbox_attempt".into(),
+                sandbox_attempt.map_or(Value::Null, |attempt| Value::Integer(attempt.into())),
+            ),
+        ]))
+    }
+
+    /// The cases of the `path_helpers` tests of the `kubernetes_logs` source, which the function
+    /// parses the same way.
+    #[test]
+    fn parses_like_path_helpers() {
+        let cases = vec![
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid",
+                    "sandbox0-container0-name",
+                    "1.log",
+                    Some(1),
+                    None,
+                ),
+            ),
+            // The pod name may contain the delimiter.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0_name_sandbox0-uid/sandbox0-container0-name/1.log".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0_name",
+                    "sandbox0-uid",
+                    "sandbox0-container0-name",
+                    "1.log",
+                    Some(1),
+                    None,
+                ),
+            ),
+            // Rotated log files keep the restart count.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log.20240101-120000".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid",
+                    "sandbox0-container0-name",
+                    "1.log.20240101-120000",
+                    Some(1),
+                    None,
+                ),
+            ),
+            // The sandbox attempt and the extra segments after a well-formed UID.
+            (
+                format!("/var/log/pods/sandbox0-ns_sandbox0-name_{}_2_zone-a/sandbox0-container0-name/1.log", UID),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    UID,
+                    "sandbox0-container0-name",
+                    "1.log",
+                    Some(1),
+                    Some(2),
+                ),
+            ),
+            // Without a well-formed UID, the last segment is taken for the UID.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_zone-a_pool0/sandbox0-container0-name/1.log".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name_sandbox0-uid_zone-a",
+                    "pool0",
+                    "sandbox0-container0-name",
+                    "1.log",
+                    Some(1),
+                    None,
+                ),
+            ),
+            // Static pods are keyed by the config hashsum.
+            (
+                "/var/log/pods/kube-system_kube-apiserver-node0_0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6d/kube-apiserver/0.log".to_owned(),
+                expected(
+                    "kube-system",
+                    "kube-apiserver-node0",
+                    "0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6d",
+                    "kube-apiserver",
+                    "0.log",
+                    Some(0),
+                    None,
+                ),
+            ),
+            (
+                "/var/log/pods/kube-system_coredns-0_system-7f9c2b1d/coredns/0.log".to_owned(),
+                expected(
+                    "kube-system",
+                    "coredns-0",
+                    "system-7f9c2b1d",
+                    "coredns",
+                    "0.log",
+                    Some(0),
+                    None,
+                ),
+            ),
+            // The prefix, the separators and a trailing separator don't matter.
+            (
+                "file://C:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid\\sandbox0-container0-name\\1.log\\".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid",
+                    "sandbox0-container0-name",
+                    "1.log",
+                    Some(1),
+                    None,
+                ),
+            ),
+            (
+                "sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/app.log".to_owned(),
+                expected(
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid",
+                    "sandbox0-container0-name",
+                    "app.log",
+                    None,
+                    None,
+                ),
+            ),
+        ];
+
+        for (path, expected) in cases.into_iter() {
+            assert_eq!(parse(&path, '_', Layout::Pods), Ok(expected), "{}", path);
+        }
+    }
+
+    #[test]
+    fn rejects_like_path_helpers() {
+        let cases = vec![
+            ("", "Path has no container directory above the log file."),
+            (
+                "1.log",
+                "Path has no container directory above the log file.",
+            ),
+            (
+                "sandbox0-container0-name/1.log",
+                "Path has no pod logs directory above the container directory.",
+            ),
+            (
+                "/var/log/pods/sandbox0-ns/sandbox0-container0-name/1.log",
+                r#"Pod logs directory "sandbox0-ns" has 1 segment(s), expected at least 3."#,
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name/sandbox0-container0-name/1.log",
+                r#"Pod logs directory "sandbox0-ns_sandbox0-name" has 2 segment(s), expected at least 3."#,
+            ),
+        ];
+
+        for (path, error) in cases.into_iter() {
+            let message = parse(path, '_', Layout::Pods).unwrap_err().to_string();
+            assert!(message.ends_with(error), "{}: {}", path, message);
+        }
+    }
+
+    #[test]
+    fn parses_with_delimiter() {
+        let path = format!(
+            "/var/log/pods/sandbox0-ns~sandbox0_name~{}~3/sandbox0-container0-name/1.log",
+            UID
+        );
+        assert_eq!(
+            parse(&path, '~', Layout::Pods),
+            Ok(expected(
+                "sandbox0-ns",
+                "sandbox0_name",
+                UID,
+                "sandbox0-container0-name",
+                "1.log",
+                Some(1),
+                Some(3)
+            ))
+        );
+        assert!(parse(&path, '_', Layout::Pods).is_err());
+
+        let message = parse_k8s_log_path(path.into(), "~~".into(), Layout::Pods)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("delimiter must be a single character"));
+    }
+
+    #[test]
+    fn parses_in_layout() {
+        let pod_log_path =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log";
+        let symlink_path =
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log";
+        let pod_log_info = expected(
+            "sandbox0-ns",
+            "sandbox0-name",
+            "sandbox0-uid",
+            "sandbox0-container0-name",
+            "1.log",
+            Some(1),
+            None,
+        );
+        let symlink_info = expected(
+            "sandbox0-ns",
+            "sandbox0-name",
+            "",
+            "sandbox0-container0-name",
+            "sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+            None,
+            None,
+        );
+
+        assert_eq!(
+            parse(pod_log_path, '_', Layout::Pods),
+            Ok(pod_log_info.clone())
+        );
+        assert!(parse(symlink_path, '_', Layout::Pods).is_err());
+        assert!(parse(pod_log_path, '_', Layout::Containers).is_err());
+        assert_eq!(
+            parse(symlink_path, '_', Layout::Containers),
+            Ok(symlink_info.clone())
+        );
+        assert_eq!(parse(pod_log_path, '_', Layout::Auto), Ok(pod_log_info));
+        assert_eq!(parse(symlink_path, '_', Layout::Auto), Ok(symlink_info));
+    }
+
+    #[test]
+    fn parses_invalid_utf8() {
+        let path = b"/var/log/pods/sandbox0-ns_sandbox0-name\xff_sandbox0-uid/sandbox0-container0-name/1.log";
+        let info = parse_k8s_log_path(Value::Bytes(path.to_vec().into()), "_".into(), Layout::Pods)
+            .unwrap();
+        assert_eq!(
+            info.as_object().unwrap()["pod_name"],
+            Value::Bytes(b"sandbox0-name\xff".to_vec().into())
+        );
+    }
+}
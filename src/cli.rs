@@ -271,6 +271,13 @@ pub enum SubCommand {
     /// Output the topology as visual representation using the DOT language which can be rendered by GraphViz
     Graph(graph::Opts),
 
+    /// List the pod log files the `kubernetes_logs` source would collect from a pod logs
+    /// directory, without collecting them, then exit.
+    ///
+    /// Exits with an error if none of the log files would be collected.
+    #[cfg(feature = "sources-kubernetes_logs")]
+    K8sPaths(crate::sources::kubernetes_logs::discover_paths::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "api-client")]
     Top(top::Opts),
@@ -299,6 +306,8 @@ impl SubCommand {
             Self::Generate(g) => generate::cmd(g),
             Self::GenerateSchema => generate_schema::cmd(),
             Self::Graph(g) => graph::cmd(g),
+            #[cfg(feature = "sources-kubernetes_logs")]
+            Self::K8sPaths(k) => crate::sources::kubernetes_logs::discover_paths::cmd(k),
             Self::List(l) => list::cmd(l),
             #[cfg(windows)]
             Self::Service(s) => service::cmd(s),
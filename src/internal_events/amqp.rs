@@ -67,6 +67,27 @@ pub mod source {
         }
     }
 
+    #[derive(Debug)]
+    pub struct AmqpMessageDisposed {
+        pub disposition: &'static str,
+        pub reason: &'static str,
+    }
+
+    impl InternalEvent for AmqpMessageDisposed {
+        fn emit(self) {
+            debug!(
+                message = "Disposing of message.",
+                disposition = self.disposition,
+                reason = self.reason,
+            );
+            counter!(
+                "amqp_failed_messages_total", 1,
+                "disposition" => self.disposition,
+                "reason" => self.reason,
+            );
+        }
+    }
+
     #[derive(Debug)]
     pub struct AmqpRejectError {
         pub error: lapin::Error,
@@ -114,6 +114,138 @@ mod s3 {
             );
         }
     }
+
+    #[derive(Debug)]
+    pub struct S3ObjectListError<'a, E> {
+        pub bucket: &'a str,
+        pub error: &'a E,
+    }
+
+    impl<'a, E: std::fmt::Display> InternalEvent for S3ObjectListError<'a, E> {
+        fn emit(self) {
+            error!(
+                message = "Failed to list S3 objects.",
+                bucket = %self.bucket,
+                error = %self.error,
+                error_code = "failed_listing_s3_objects",
+                error_type = error_type::REQUEST_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_listing_s3_objects",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3ObjectProcessingError<'a> {
+        pub bucket: &'a str,
+        pub key: &'a str,
+        pub error: &'a ProcessingError,
+    }
+
+    impl<'a> InternalEvent for S3ObjectProcessingError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to process S3 object.",
+                bucket = %self.bucket,
+                key = %self.key,
+                error = %self.error,
+                error_code = "failed_processing_s3_object",
+                error_type = error_type::PARSER_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_processing_s3_object",
+                "error_type" => error_type::PARSER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3ObjectDecompressionError<'a> {
+        pub key: &'a str,
+        pub error: &'a std::io::Error,
+    }
+
+    impl<'a> InternalEvent for S3ObjectDecompressionError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to decompress S3 object, skipping the rest of it.",
+                key = %self.key,
+                error = %self.error,
+                error_code = "failed_decompressing_s3_object",
+                error_type = error_type::PARSER_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_decompressing_s3_object",
+                "error_type" => error_type::PARSER_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3ObjectDeleteError<'a, E> {
+        pub bucket: &'a str,
+        pub key: &'a str,
+        pub error: &'a E,
+    }
+
+    impl<'a, E: std::fmt::Display> InternalEvent for S3ObjectDeleteError<'a, E> {
+        fn emit(self) {
+            error!(
+                message = "Failed to delete S3 object.",
+                bucket = %self.bucket,
+                key = %self.key,
+                error = %self.error,
+                error_code = "failed_deleting_s3_object",
+                error_type = error_type::ACKNOWLEDGMENT_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "failed_deleting_s3_object",
+                "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct S3CheckpointWriteError {
+        pub error: std::io::Error,
+    }
+
+    impl InternalEvent for S3CheckpointWriteError {
+        fn emit(self) {
+            error!(
+                message = "Failed writing S3 checkpoint.",
+                error = %self.error,
+                error_code = "writing_checkpoint",
+                error_type = error_type::WRITER_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "writing_checkpoint",
+                "error_type" => error_type::WRITER_FAILED,
+                "stage" => error_stage::RECEIVING,
+            );
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -90,6 +90,25 @@ impl InternalEvent for ExecTimeoutError<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct ExecScheduledRunSkipped<'a> {
+    pub command: &'a str,
+}
+
+impl InternalEvent for ExecScheduledRunSkipped<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Skipping scheduled run, as the previous run of the command is still executing.",
+            command = %self.command,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "skipped_runs_total", 1,
+            "command" => self.command.to_owned(),
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct ExecCommandExecuted<'a> {
     pub command: &'a str,
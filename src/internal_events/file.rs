@@ -34,6 +34,17 @@ impl InternalEvent for FileOpen {
     }
 }
 
+#[derive(Debug)]
+pub struct FilesThrottled {
+    pub count: usize,
+}
+
+impl InternalEvent for FilesThrottled {
+    fn emit(self) {
+        gauge!("files_throttled", self.count as f64);
+    }
+}
+
 #[derive(Debug)]
 pub struct FileBytesSent<'a> {
     pub byte_size: usize,
@@ -107,7 +118,7 @@ mod source {
     use metrics::counter;
     use vector_lib::file_source::FileSourceInternalEvents;
 
-    use super::{FileOpen, InternalEvent};
+    use super::{FileOpen, FilesThrottled, InternalEvent};
     use vector_lib::emit;
     use vector_lib::{
         internal_event::{error_stage, error_type},
@@ -301,6 +312,75 @@ mod source {
         }
     }
 
+    const ARCHIVING_FAILED: &str = "archiving_failed";
+
+    #[derive(Debug)]
+    pub struct FileArchiveError<'a> {
+        pub file: &'a Path,
+        pub error: Error,
+        pub include_file_metric_tag: bool,
+    }
+
+    impl<'a> InternalEvent for FileArchiveError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed in archiving file.",
+                file = %self.file.display(),
+                error = %self.error,
+                error_code = ARCHIVING_FAILED,
+                error_type = error_type::COMMAND_FAILED,
+                stage = error_stage::RECEIVING,
+                internal_log_rate_limit = true,
+            );
+            if self.include_file_metric_tag {
+                counter!(
+                    "component_errors_total", 1,
+                    "file" => self.file.to_string_lossy().into_owned(),
+                    "error_code" => ARCHIVING_FAILED,
+                    "error_type" => error_type::COMMAND_FAILED,
+                    "stage" => error_stage::RECEIVING,
+                );
+                counter!(
+                    "file_archive_errors_total", 1,
+                    "file" => self.file.to_string_lossy().into_owned(),
+                );
+            } else {
+                counter!(
+                    "component_errors_total", 1,
+                    "error_code" => ARCHIVING_FAILED,
+                    "error_type" => error_type::COMMAND_FAILED,
+                    "stage" => error_stage::RECEIVING,
+                );
+                counter!("file_archive_errors_total", 1);
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct FileArchived<'a> {
+        pub file: &'a Path,
+        pub archive_path: &'a Path,
+        pub include_file_metric_tag: bool,
+    }
+
+    impl<'a> InternalEvent for FileArchived<'a> {
+        fn emit(self) {
+            info!(
+                message = "File archived.",
+                file = %self.file.display(),
+                archive_path = %self.archive_path.display(),
+            );
+            if self.include_file_metric_tag {
+                counter!(
+                    "files_archived_total", 1,
+                    "file" => self.file.to_string_lossy().into_owned(),
+                );
+            } else {
+                counter!("files_archived_total", 1);
+            }
+        }
+    }
+
     #[derive(Debug)]
     pub struct FileUnwatched<'a> {
         pub file: &'a Path,
@@ -527,6 +607,22 @@ mod source {
             });
         }
 
+        fn emit_file_archived(&self, file: &Path, archive_path: &Path) {
+            emit!(FileArchived {
+                file,
+                archive_path,
+                include_file_metric_tag: self.include_file_metric_tag
+            });
+        }
+
+        fn emit_file_archive_error(&self, file: &Path, error: Error) {
+            emit!(FileArchiveError {
+                file,
+                error,
+                include_file_metric_tag: self.include_file_metric_tag
+            });
+        }
+
         fn emit_file_fingerprint_read_error(&self, file: &Path, error: Error) {
             emit!(FileFingerprintReadError {
                 file,
@@ -554,6 +650,10 @@ mod source {
             emit!(FileOpen { count });
         }
 
+        fn emit_files_throttled(&self, count: usize) {
+            emit!(FilesThrottled { count });
+        }
+
         fn emit_path_globbing_failed(&self, path: &Path, error: &Error) {
             emit!(PathGlobbingError { path, error });
         }
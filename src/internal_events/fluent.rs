@@ -1,8 +1,10 @@
+use std::net::SocketAddr;
+
 use metrics::counter;
 use vector_lib::internal_event::InternalEvent;
 use vector_lib::internal_event::{error_stage, error_type};
 
-use crate::sources::fluent::DecodeError;
+use crate::sources::fluent::{DecodeError, HandshakeError};
 
 #[derive(Debug)]
 pub struct FluentMessageReceived {
@@ -39,3 +41,27 @@ impl<'a> InternalEvent for FluentMessageDecodeError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct FluentHandshakeError<'a> {
+    pub error: &'a HandshakeError,
+    pub peer_addr: SocketAddr,
+}
+
+impl<'a> InternalEvent for FluentHandshakeError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Fluent handshake failed, closing the connection.",
+            error = %self.error,
+            peer_addr = %self.peer_addr,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -73,3 +73,52 @@ impl InternalEvent for GcpPubsubReceiveError {
         );
     }
 }
+
+pub struct GcpPubsubGetSubscriptionError {
+    pub error: tonic::Status,
+}
+
+impl InternalEvent for GcpPubsubGetSubscriptionError {
+    fn emit(self) {
+        error!(
+            message = "Failed to get the subscription, assuming at-least-once delivery.",
+            error = %self.error,
+            error_code = "failed_getting_subscription",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_getting_subscription",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+pub struct GcpPubsubAckError {
+    pub count: usize,
+    pub error_code: &'static str,
+}
+
+impl InternalEvent for GcpPubsubAckError {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge messages, they may be redelivered.",
+            count = self.count,
+            error_code = self.error_code,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.error_code,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
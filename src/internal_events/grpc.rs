@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{net::SocketAddr, time::Duration};
 
 use http::response::Response;
 use metrics::{counter, histogram};
@@ -86,6 +86,49 @@ where
     }
 }
 
+#[derive(Debug)]
+pub struct GrpcConnectionRejected {
+    pub peer_addr: SocketAddr,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for GrpcConnectionRejected {
+    fn emit(self) {
+        warn!(
+            message = "Rejecting the gRPC connection.",
+            peer_addr = %self.peer_addr,
+            reason = %self.reason,
+            internal_log_rate_limit = true
+        );
+        counter!("rejected_connections_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GrpcOversizedEventsError {
+    pub count: usize,
+    pub max_event_size_bytes: usize,
+}
+
+impl InternalEvent for GrpcOversizedEventsError {
+    fn emit(self) {
+        error!(
+            message = "Rejecting the batch with events larger than the maximum size.",
+            count = %self.count,
+            max_event_size_bytes = %self.max_event_size_bytes,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true
+        );
+        counter!("oversized_events_total", self.count as u64);
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
 const fn grpc_code_to_name(code: Code) -> &'static str {
     match code {
         Code::Ok => "Ok",
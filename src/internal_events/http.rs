@@ -175,3 +175,22 @@ impl<'a> InternalEvent for HttpInternalError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct HttpSignatureRejected {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for HttpSignatureRejected {
+    fn emit(self) {
+        warn!(
+            message = "Rejected request with failed signature verification.",
+            reason = %self.reason,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "http_server_signature_rejections_total", 1,
+            "reason" => self.reason,
+        );
+    }
+}
@@ -5,7 +5,7 @@ use vector_lib::internal_event::{error_stage, error_type};
 
 #[derive(Debug)]
 pub struct JournaldInvalidRecordError {
-    pub error: serde_json::Error,
+    pub error: crate::Error,
     pub text: String,
 }
 
@@ -49,6 +49,28 @@ impl InternalEvent for JournaldStartJournalctlError {
     }
 }
 
+#[derive(Debug)]
+pub struct JournaldRemoteRequestError {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for JournaldRemoteRequestError {
+    fn emit(self) {
+        error!(
+            message = "Error requesting entries from the remote journal.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "stage" => error_stage::RECEIVING,
+            "error_type" => error_type::REQUEST_FAILED,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct JournaldReadError {
     pub error: BoxedFramingError,
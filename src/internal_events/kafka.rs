@@ -108,6 +108,41 @@ impl InternalEvent for KafkaReadError {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaPartitionPauseError {
+    pub error: rdkafka::error::KafkaError,
+}
+
+impl InternalEvent for KafkaPartitionPauseError {
+    fn emit(self) {
+        error!(
+            message = "Failed to pause or resume partition.",
+            error = %self.error,
+            error_code = "pausing_partition",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "pausing_partition",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaPausedPartitions {
+    pub count: usize,
+}
+
+impl InternalEvent for KafkaPausedPartitions {
+    fn emit(self) {
+        gauge!("kafka_paused_partitions", self.count as f64);
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaStatisticsReceived<'a> {
     pub statistics: &'a rdkafka::Statistics,
@@ -1,12 +1,55 @@
+use std::time::Duration;
+
 use metrics::counter;
 use vector_lib::internal_event::InternalEvent;
 use vector_lib::{
+    configurable::configurable_component,
     internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL},
     json_size::JsonSize,
 };
 
+use super::parser::truncate_string_at;
 use crate::event::Event;
 
+/// Configuration of internal metrics for the `kubernetes_logs` source.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct KubernetesLogsInternalMetricsConfig {
+    /// Whether or not to include the "file" tag on the component's corresponding internal metrics.
+    ///
+    /// This is useful for distinguishing between different files while monitoring. However, the tag's
+    /// cardinality is unbounded.
+    #[serde(default = "crate::serde::default_false")]
+    pub include_file_tag: bool,
+
+    /// Whether or not to include the "pod_namespace", "pod_name", and "container_name" tags on the
+    /// `k8s_logs_read_bytes_total` and `k8s_logs_read_lines_total` internal metrics.
+    ///
+    /// This is useful for finding the Pods that produce the most logs. The tags are dropped while
+    /// more than `max_tagged_pods` Pods are active on the Node, to bound their cardinality.
+    #[serde(default = "crate::serde::default_false")]
+    pub include_pod_tags: bool,
+
+    /// The maximum number of active Pods on the Node to include the Pod tags for.
+    #[serde(default = "default_max_tagged_pods")]
+    pub max_tagged_pods: usize,
+}
+
+impl Default for KubernetesLogsInternalMetricsConfig {
+    fn default() -> Self {
+        Self {
+            include_file_tag: false,
+            include_pod_tags: false,
+            max_tagged_pods: default_max_tagged_pods(),
+        }
+    }
+}
+
+const fn default_max_tagged_pods() -> usize {
+    250
+}
+
 #[derive(Debug)]
 pub struct KubernetesLogsEventsReceived<'a> {
     pub file: &'a str,
@@ -47,6 +90,38 @@ impl InternalEvent for KubernetesLogsEventsReceived<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct KubernetesLogsLineRead<'a> {
+    pub byte_size: usize,
+    pub pod_tags: Option<KubernetesLogsPodTags<'a>>,
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsPodTags<'a> {
+    pub pod_namespace: &'a str,
+    pub pod_name: &'a str,
+    pub container_name: &'a str,
+}
+
+impl InternalEvent for KubernetesLogsLineRead<'_> {
+    fn emit(self) {
+        match self.pod_tags {
+            Some(pod_tags) => {
+                let pod_namespace = pod_tags.pod_namespace.to_owned();
+                let pod_name = pod_tags.pod_name.to_owned();
+                let container_name = pod_tags.container_name.to_owned();
+
+                counter!("k8s_logs_read_bytes_total", self.byte_size as u64, "pod_namespace" => pod_namespace.clone(), "pod_name" => pod_name.clone(), "container_name" => container_name.clone());
+                counter!("k8s_logs_read_lines_total", 1, "pod_namespace" => pod_namespace, "pod_name" => pod_name, "container_name" => container_name);
+            }
+            None => {
+                counter!("k8s_logs_read_bytes_total", self.byte_size as u64);
+                counter!("k8s_logs_read_lines_total", 1);
+            }
+        }
+    }
+}
+
 const ANNOTATION_FAILED: &str = "annotation_failed";
 
 #[derive(Debug)]
@@ -123,6 +198,81 @@ impl InternalEvent for KubernetesLogsEventNodeAnnotationError<'_> {
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct KubernetesLogsKubeletMetadataFallback;
+
+impl InternalEvent for KubernetesLogsKubeletMetadataFallback {
+    fn emit(self) {
+        trace!(message = "Annotating event with pod metadata obtained from the kubelet.");
+        counter!("k8s_kubelet_metadata_fallback_lookups_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KubernetesLogsWatchReconnect {
+    pub resource: &'static str,
+    pub delay: Duration,
+}
+
+impl InternalEvent for KubernetesLogsWatchReconnect {
+    fn emit(self) {
+        debug!(
+            message = "Reconnecting the Kubernetes API watch.",
+            resource = self.resource,
+            delay_ms = self.delay.as_millis() as u64,
+        );
+        counter!("k8s_metadata_watch_reconnects_total", 1, "resource" => self.resource);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KubernetesLogsDelayedDeletionLookup;
+
+impl InternalEvent for KubernetesLogsDelayedDeletionLookup {
+    fn emit(self) {
+        trace!(message = "Annotating event with metadata of a deleted pod.");
+        counter!("k8s_delayed_deletion_lookups_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KubernetesLogsPartialLineOverflow {
+    pub max_merged_line_bytes: usize,
+}
+
+impl InternalEvent for KubernetesLogsPartialLineOverflow {
+    fn emit(self) {
+        warn!(
+            message = "Merged partial event exceeded the maximum size, emitting it truncated.",
+            max_merged_line_bytes = %self.max_merged_line_bytes,
+            internal_log_rate_limit = true,
+        );
+        counter!("k8s_partial_line_overflows_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct KubernetesLogsKubeletMetadataRequestError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for KubernetesLogsKubeletMetadataRequestError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to fetch pod metadata from the kubelet.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct KubernetesLogsFormatPickerEdgeCase {
     pub what: &'static str,
@@ -138,6 +288,66 @@ impl InternalEvent for KubernetesLogsFormatPickerEdgeCase {
     }
 }
 
+#[derive(Debug)]
+pub struct KubernetesLogsLogPathUnparseable<'a> {
+    pub path: &'a str,
+}
+
+impl InternalEvent for KubernetesLogsLogPathUnparseable<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Log file path does not match the expected layout.",
+            path = &truncate_string_at(self.path, 256)[..],
+            internal_log_rate_limit = true,
+        );
+        counter!("k8s_log_paths_unparseable_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsTerminatedContainerFileClosed<'a> {
+    pub path: &'a str,
+}
+
+impl InternalEvent for KubernetesLogsTerminatedContainerFileClosed<'_> {
+    fn emit(self) {
+        debug!(
+            message = "Closing log file of terminated container.",
+            path = &truncate_string_at(self.path, 256)[..],
+        );
+        counter!("k8s_terminated_container_files_closed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsNodeMismatchFileSkipped<'a> {
+    pub path: &'a str,
+}
+
+impl InternalEvent for KubernetesLogsNodeMismatchFileSkipped<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Skipping log file of a Pod not on the Node.",
+            path = &truncate_string_at(self.path, 256)[..],
+            internal_log_rate_limit = true,
+        );
+        counter!("k8s_node_mismatch_files_skipped_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesLogsFormatDetectionFallback;
+
+impl InternalEvent for KubernetesLogsFormatDetectionFallback {
+    fn emit(self) {
+        warn!(
+            message = "Could not detect the log format of the file, falling back to CRI.",
+            internal_log_rate_limit = true,
+        );
+        counter!("k8s_format_detection_fallbacks_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct KubernetesLogsDockerFormatParseError<'a> {
     pub error: &'a dyn std::error::Error,
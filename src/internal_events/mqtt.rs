@@ -0,0 +1,89 @@
+use metrics::{counter, gauge};
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct MqttConnectionState {
+    pub connected: bool,
+}
+
+impl InternalEvent for MqttConnectionState {
+    fn emit(self) {
+        if self.connected {
+            debug!(message = "Connected to the MQTT broker.");
+        } else {
+            debug!(message = "Disconnected from the MQTT broker.");
+        }
+        gauge!("mqtt_connected", if self.connected { 1.0 } else { 0.0 });
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttConnectionError {
+    pub error: rumqttc::ConnectionError,
+}
+
+impl InternalEvent for MqttConnectionError {
+    fn emit(self) {
+        error!(
+            message = "MQTT connection failed.",
+            error = %self.error,
+            error_code = "mqtt_connection_error",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "mqtt_connection_error",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttSubscribeError {
+    pub error: String,
+}
+
+impl InternalEvent for MqttSubscribeError {
+    fn emit(self) {
+        error!(
+            message = "Failed to subscribe to the MQTT topics.",
+            error = %self.error,
+            error_code = "mqtt_subscribe_error",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "mqtt_subscribe_error",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct MqttAckError {
+    pub error: rumqttc::ClientError,
+}
+
+impl InternalEvent for MqttAckError {
+    fn emit(self) {
+        error!(
+            message = "Unable to ack.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct NatsJetStreamReceiveError {
+    pub error: async_nats::Error,
+}
+
+impl InternalEvent for NatsJetStreamReceiveError {
+    fn emit(self) {
+        error!(
+            message = "Failed to receive JetStream messages.",
+            error = %self.error,
+            error_code = "failed_receiving_messages",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_receiving_messages",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NatsJetStreamAckError {
+    pub error: async_nats::Error,
+}
+
+impl InternalEvent for NatsJetStreamAckError {
+    fn emit(self) {
+        error!(
+            message = "Unable to ack.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NatsJetStreamNakError {
+    pub error: async_nats::Error,
+}
+
+impl InternalEvent for NatsJetStreamNakError {
+    fn emit(self) {
+        error!(
+            message = "Unable to nak.",
+            error = %self.error,
+            error_type = error_type::COMMAND_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::COMMAND_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -4,7 +4,7 @@ use metrics::counter;
 use vector_lib::internal_event::InternalEvent;
 use vector_lib::internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL};
 
-fn truncate_string_at(s: &str, maxlen: usize) -> Cow<str> {
+pub(super) fn truncate_string_at(s: &str, maxlen: usize) -> Cow<str> {
     let ellipsis: &str = "[...]";
     if s.len() >= maxlen {
         let mut len = maxlen - ellipsis.len();
@@ -41,6 +41,35 @@ impl<'a> InternalEvent for PrometheusParseError<'a> {
     }
 }
 
+#[cfg(feature = "sources-prometheus-scrape")]
+#[derive(Debug)]
+pub struct PrometheusServiceDiscoveryError {
+    pub error: crate::Error,
+    pub url: http::Uri,
+}
+
+#[cfg(feature = "sources-prometheus-scrape")]
+impl InternalEvent for PrometheusServiceDiscoveryError {
+    fn emit(self) {
+        error!(
+            message = "Service discovery failed, keeping the last discovered targets.",
+            url = %self.url,
+            error = %self.error,
+            error_code = "service_discovery_failed",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "service_discovery_failed",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "url" => self.url.to_string(),
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct PrometheusRemoteWriteParseError {
     pub error: prost::DecodeError,
@@ -33,3 +33,25 @@ impl InternalEvent for RedisReceiveEventError {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct RedisStreamAckError {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisStreamAckError {
+    fn emit(self) {
+        error!(
+            message = "Unable to acknowledge stream entries.",
+            error = %self.error,
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
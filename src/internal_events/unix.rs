@@ -136,3 +136,19 @@ impl<'a> InternalEvent for UnixSocketFileDeleteError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct UnixSocketDatagramTruncated {
+    pub max_length: usize,
+}
+
+impl InternalEvent for UnixSocketDatagramTruncated {
+    fn emit(self) {
+        warn!(
+            message = "Received datagram longer than the maximum length, truncating it.",
+            max_length = %self.max_length,
+            internal_log_rate_limit = true,
+        );
+        counter!("unix_socket_datagrams_truncated_total", 1);
+    }
+}
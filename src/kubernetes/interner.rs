@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+/// Interns strings as shared [`Bytes`], so the values repeated across many events, like the names
+/// of the Pods and of their containers, share a single allocation instead of being copied onto
+/// each event.
+///
+/// The interned strings are dropped all at once when `capacity` of them are held, so the strings
+/// of the Pods that are gone don't accumulate.
+#[derive(Debug)]
+pub struct StringInterner {
+    strings: HashSet<Bytes>,
+    capacity: usize,
+}
+
+impl StringInterner {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            strings: HashSet::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the shared copy of the string, interning it if it isn't already.
+    pub fn intern(&mut self, value: &str) -> Bytes {
+        if let Some(bytes) = self.strings.get(value.as_bytes()) {
+            return bytes.clone();
+        }
+
+        if self.strings.len() >= self.capacity {
+            self.strings.clear();
+        }
+        let bytes = Bytes::copy_from_slice(value.as_bytes());
+        self.strings.insert(bytes.clone());
+        bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StringInterner;
+
+    #[test]
+    fn interned_strings_share_storage() {
+        let mut interner = StringInterner::new(16);
+
+        let first = interner.intern("sandbox0-name");
+        let second = interner.intern(&String::from("sandbox0-name"));
+        assert_eq!(first, second);
+        assert_eq!(first.as_ptr(), second.as_ptr());
+
+        let other = interner.intern("sandbox0-ns");
+        assert_ne!(first.as_ptr(), other.as_ptr());
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn interner_is_cleared_when_full() {
+        let mut interner = StringInterner::new(2);
+        interner.intern("a");
+        interner.intern("b");
+        assert_eq!(interner.len(), 2);
+
+        // Interning an already interned string doesn't count against the capacity.
+        interner.intern("a");
+        assert_eq!(interner.len(), 2);
+
+        let c = interner.intern("c");
+        assert_eq!(interner.len(), 1);
+        assert_eq!(interner.intern("c").as_ptr(), c.as_ptr());
+    }
+}
@@ -7,7 +7,9 @@
 
 #![cfg(feature = "kubernetes")]
 
+pub mod interner;
 pub mod meta_cache;
+pub mod path_helpers;
 pub mod pod_manager_logic;
 pub mod reflector;
 
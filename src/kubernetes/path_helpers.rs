@@ -0,0 +1,1332 @@
+//! Simple helpers for building and parsing k8s paths.
+//!
+//! Loosely based on <https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go>.
+//!
+//! # Stability
+//!
+//! [`build_pod_logs_directory`], [`build_pod_logs_directory_with_delimiter`],
+//! [`build_pod_log_file_path`], [`parse_log_file_path`], [`parse_log_file_path_with_delimiter`],
+//! [`try_parse_log_file_path`], [`try_parse_log_file_path_with_delimiter`],
+//! [`parse_log_file_path_bytes`], [`try_parse_log_file_path_bytes_with_delimiter`],
+//! [`parse_log_file_os_path`] (on Unix), [`DEFAULT_POD_DIR_DELIMITER`], [`PathComponentError`],
+//! [`PathParseError`], [`LogFileInfo`], [`OwnedLogFileInfo`], [`RawLogFileInfo`] and
+//! [`PathSegment`] are public for tooling built on top of Vector, and are kept stable across
+//! minor releases:
+//!
+//! - The signatures of the functions don't change, and the paths they build and accept only
+//!   change to follow the layout the kubelet uses.
+//! - The builders reject the components that would let the built path escape the pod logs
+//!   root with a [`PathComponentError`].
+//! - [`LogFileInfo`], [`OwnedLogFileInfo`] and [`RawLogFileInfo`] are `#[non_exhaustive]`, so
+//!   fields may be added to them, but the existing fields are neither removed nor change their
+//!   meaning.
+//! - [`LogFileInfo::stream_key`] keeps returning the same key for the same pod UID, container
+//!   name and restart count, so the keys can be persisted.
+//! - [`PathParseError`] is `#[non_exhaustive]`, so variants may be added to it as the parser
+//!   tells more failures apart.
+//!
+//! The rest of this module is internal to the `kubernetes_logs` source, and may change at any
+//! time.
+//!
+//! The pod log file paths are parsed by the `k8s-log-path-parser` crate, which the
+//! `parse_k8s_log_path` VRL function is built on as well, and its parser is re-exported here.
+
+#![deny(missing_docs)]
+
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+pub use k8s_log_path_parser::parse_log_file_os_path;
+pub(crate) use k8s_log_path_parser::parse_restart_count;
+use k8s_log_path_parser::{is_valid_pod_uid, normalize_path, PATH_SEPARATORS};
+pub use k8s_log_path_parser::{
+    parse_container_symlink_path, parse_log_file_path, parse_log_file_path_bytes,
+    parse_log_file_path_with_delimiter, try_parse_log_file_path,
+    try_parse_log_file_path_bytes_with_delimiter, try_parse_log_file_path_with_delimiter,
+    LogFileInfo, OwnedLogFileInfo, PathParseError, PathSegment, RawLogFileInfo,
+    DEFAULT_POD_DIR_DELIMITER,
+};
+use snafu::Snafu;
+use vector_lib::configurable::configurable_component;
+
+/// The default root directory for pod logs.
+#[cfg(not(windows))]
+pub(crate) const K8S_LOGS_DIR: &str = "/var/log/pods";
+
+/// The default root directory for pod logs.
+#[cfg(windows)]
+pub(crate) const K8S_LOGS_DIR: &str = "C:\\var\\log\\pods";
+
+/// The default directory of the legacy container log symlinks.
+#[cfg(not(windows))]
+pub(crate) const K8S_CONTAINER_LOGS_DIR: &str = "/var/log/containers";
+
+/// The default directory of the legacy container log symlinks.
+#[cfg(windows)]
+pub(crate) const K8S_CONTAINER_LOGS_DIR: &str = "C:\\var\\log\\containers";
+
+/// An error building a log path out of a component that could escape the pod logs root.
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+pub enum PathComponentError {
+    /// The component contains a path separator.
+    #[snafu(display("Path component {:?} contains a path separator.", component))]
+    ContainsSeparator {
+        /// The rejected component.
+        component: String,
+    },
+    /// The component is a `.` or `..` segment.
+    #[snafu(display("Path component {:?} is a relative segment.", component))]
+    RelativeSegment {
+        /// The rejected component.
+        component: String,
+    },
+}
+
+/// Checks that the component is joined into the path as a single, literal segment.
+fn check_path_component(component: &str) -> Result<(), PathComponentError> {
+    if component.contains(PATH_SEPARATORS) {
+        return ContainsSeparatorSnafu { component }.fail();
+    }
+    if component == "." || component == ".." {
+        return RelativeSegmentSnafu { component }.fail();
+    }
+    Ok(())
+}
+
+/// Builds absolute log directory path for a pod sandbox.
+///
+/// The components usually come from the pod metadata, so the ones that
+/// contain a path separator or are a `.` or `..` segment are rejected.
+///
+/// Based on <https://github.com/kubernetes/kubernetes/blob/31305966789525fca49ec26c289e565467d1f1c4/pkg/kubelet/kuberuntime/helpers.go#L178>
+pub fn build_pod_logs_directory(
+    pod_logs_root: &Path,
+    pod_namespace: &str,
+    pod_name: &str,
+    pod_uid: &str,
+) -> Result<PathBuf, PathComponentError> {
+    build_pod_logs_directory_with_delimiter(
+        pod_logs_root,
+        pod_namespace,
+        pod_name,
+        pod_uid,
+        DEFAULT_POD_DIR_DELIMITER,
+    )
+}
+
+/// Builds absolute log directory path for a pod sandbox like
+/// [`build_pod_logs_directory`] does, but with the components of the directory
+/// name delimited by `delimiter`.
+///
+/// This is for the modified kubelets that don't use the default `_`
+/// delimiter. The components aren't checked for the delimiter, so the
+/// directory names only parse back into the same components if the kubelet
+/// guarantees that they don't contain it.
+pub fn build_pod_logs_directory_with_delimiter(
+    pod_logs_root: &Path,
+    pod_namespace: &str,
+    pod_name: &str,
+    pod_uid: &str,
+    delimiter: char,
+) -> Result<PathBuf, PathComponentError> {
+    for component in [pod_namespace, pod_name, pod_uid] {
+        check_path_component(component)?;
+    }
+    Ok(build_pod_logs_directory_unchecked(
+        pod_logs_root,
+        pod_namespace,
+        pod_name,
+        pod_uid,
+        delimiter,
+    ))
+}
+
+/// Builds absolute log directory path for a pod sandbox like
+/// [`build_pod_logs_directory_with_delimiter`] does, but without checking the
+/// components.
+///
+/// Only for the components that are known to be well-formed.
+pub(crate) fn build_pod_logs_directory_unchecked(
+    pod_logs_root: &Path,
+    pod_namespace: &str,
+    pod_name: &str,
+    pod_uid: &str,
+    delimiter: char,
+) -> PathBuf {
+    pod_logs_root.join(format!(
+        "{}{}{}{}{}",
+        pod_namespace, delimiter, pod_name, delimiter, pod_uid
+    ))
+}
+
+/// Builds absolute log file path for a container of a pod sandbox.
+///
+/// The components are checked like [`build_pod_logs_directory`] does.
+///
+/// This is the inverse of [`parse_log_file_path`].
+pub fn build_pod_log_file_path(
+    pod_logs_root: &Path,
+    pod_namespace: &str,
+    pod_name: &str,
+    pod_uid: &str,
+    container_name: &str,
+    restart_count: u32,
+) -> Result<PathBuf, PathComponentError> {
+    check_path_component(container_name)?;
+    Ok(
+        build_pod_logs_directory(pod_logs_root, pod_namespace, pod_name, pod_uid)?
+            .join(container_name)
+            .join(format!("{}.log", restart_count)),
+    )
+}
+
+/// Parses pod log file path like [`parse_log_file_path_with_delimiter`] does,
+/// but only returns the log file info if the pod UID is well-formed.
+///
+/// This rejects the stray files placed below the pod logs root by other
+/// tooling, which [`parse_log_file_path`] would otherwise accept with an
+/// arbitrary string as the UID.
+pub(crate) fn parse_log_file_path_strict(path: &str, delimiter: char) -> Option<LogFileInfo<'_>> {
+    parse_log_file_path_with_delimiter(path, delimiter)
+        .filter(|info| is_valid_pod_uid(info.pod_uid.as_bytes()))
+}
+
+/// The layout the log files are discovered in.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLayout {
+    /// The `<pod_logs_root>/<namespace>_<name>_<uid>/<container>/<n>.log`
+    /// layout of the log files managed by the kubelet.
+    Pods,
+
+    /// The legacy `<container_logs_root>/<name>_<namespace>_<container>-<id>.log`
+    /// layout of symlinks to the log files.
+    Containers,
+
+    /// The `pods` layout is used for the Pods that have log files in it, and the `containers`
+    /// layout for the rest.
+    #[default]
+    Auto,
+}
+
+impl LogLayout {
+    /// Parses the log file path according to the layout, with the components
+    /// of the pod logs directory names delimited by `pod_dir_delimiter`.
+    ///
+    /// The paths of one layout don't parse as the paths of the other, so with
+    /// [`LogLayout::Auto`] the layout is effectively picked by where the file
+    /// is located.
+    pub(crate) fn parse_log_file_path(
+        self,
+        path: &str,
+        pod_dir_delimiter: char,
+    ) -> Option<LogFileInfo<'_>> {
+        self.parse_with(path, || {
+            parse_log_file_path_with_delimiter(path, pod_dir_delimiter)
+        })
+    }
+
+    /// Parses the log file path like [`LogLayout::parse_log_file_path`] does,
+    /// but only returns the info of the pod log files whose pod UID is
+    /// well-formed, see [`parse_log_file_path_strict`].
+    ///
+    /// The container log symlinks don't carry the pod UID, so they are parsed
+    /// as is.
+    pub(crate) fn parse_log_file_path_strict(
+        self,
+        path: &str,
+        pod_dir_delimiter: char,
+    ) -> Option<LogFileInfo<'_>> {
+        self.parse_with(path, || parse_log_file_path_strict(path, pod_dir_delimiter))
+    }
+
+    fn parse_with<'a>(
+        self,
+        path: &'a str,
+        parse_pod_log_file_path: impl FnOnce() -> Option<LogFileInfo<'a>>,
+    ) -> Option<LogFileInfo<'a>> {
+        match self {
+            Self::Pods => parse_pod_log_file_path(),
+            Self::Containers => parse_container_symlink_path(path),
+            Self::Auto => parse_pod_log_file_path().or_else(|| parse_container_symlink_path(path)),
+        }
+    }
+}
+
+/// Checks whether the path is of a log file that is still written to, rather
+/// than one of its rotations.
+///
+/// The kubelet rotates `<n>.log` by renaming it to `<n>.log.<timestamp>`, and
+/// compresses the older rotations to `<n>.log.<timestamp>.gz`. The rotations
+/// may be compressed, and their paths removed, at any time after they are
+/// discovered, so they are skipped instead of read. The files already open
+/// when they are rotated are still read to the end.
+pub fn is_active_log_file(path: &str) -> bool {
+    normalize_path(path)
+        .rsplit(PATH_SEPARATORS)
+        .next()
+        .map_or(false, |log_file_name| log_file_name.ends_with(".log"))
+}
+
+/// Returns the path of the log file the path is a rotation of, like
+/// `<dir>/0.log` for `<dir>/0.log.20240101-120000` or
+/// `<dir>/0.log.20240101-120000.gz`.
+///
+/// The information extracted from the path of a rotation is the same as from
+/// the path of its log file, besides the file name.
+pub fn rotated_log_file_base(path: &str) -> Option<&str> {
+    let file_name = path.rsplit(PATH_SEPARATORS).next()?;
+    let base_name_len = file_name.find(".log.")? + ".log".len();
+    Some(&path[..path.len() - file_name.len() + base_name_len])
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_build_pod_logs_directory() {
+        let cases = vec![
+            // Valid inputs.
+            (
+                ("sandbox0-ns", "sandbox0-name", "sandbox0-uid"),
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
+            // Invalid inputs.
+            (("", "", ""), "/var/log/pods/__"),
+        ];
+
+        for ((in_namespace, in_name, in_uid), expected) in cases.into_iter() {
+            assert_eq!(
+                build_pod_logs_directory(Path::new(K8S_LOGS_DIR), in_namespace, in_name, in_uid),
+                Ok(PathBuf::from(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_pod_logs_directory_rejects_traversal() {
+        let cases = vec![
+            (("../../etc", "name", "uid"), "../../etc"),
+            (("ns", "..", "uid"), ".."),
+            (("ns", "name", "."), "."),
+            (("ns", "a\\b", "uid"), "a\\b"),
+        ];
+
+        for ((in_namespace, in_name, in_uid), component) in cases.into_iter() {
+            let error =
+                build_pod_logs_directory(Path::new(K8S_LOGS_DIR), in_namespace, in_name, in_uid)
+                    .unwrap_err();
+            assert!(error.to_string().contains(&format!("{:?}", component)));
+        }
+
+        // The other components containing dots are a single segment.
+        assert_eq!(
+            build_pod_logs_directory(Path::new(K8S_LOGS_DIR), "...", "a..b", "uid"),
+            Ok(Path::new(K8S_LOGS_DIR).join("..._a..b_uid"))
+        );
+    }
+
+    #[test]
+    fn test_build_pod_logs_directory_custom_root() {
+        #[cfg(not(windows))]
+        let cases = vec![
+            (
+                "/data/kubelet/log/pods",
+                "/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
+            (
+                "/data/kubelet/log/pods/",
+                "/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
+        ];
+        #[cfg(windows)]
+        let cases = vec![
+            (
+                "D:\\kubelet\\log\\pods",
+                "D:\\kubelet\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
+            (
+                "D:\\kubelet\\log\\pods\\",
+                "D:\\kubelet\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid",
+            ),
+        ];
+
+        for (in_root, expected) in cases.into_iter() {
+            assert_eq!(
+                build_pod_logs_directory(
+                    Path::new(in_root),
+                    "sandbox0-ns",
+                    "sandbox0-name",
+                    "sandbox0-uid"
+                ),
+                Ok(PathBuf::from(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_pod_log_file_path() {
+        assert_eq!(
+            build_pod_log_file_path(
+                Path::new("/var/log/pods"),
+                "sandbox0-ns",
+                "sandbox0-name",
+                "sandbox0-uid",
+                "sandbox0-container0-name",
+                1
+            ),
+            Ok(PathBuf::from(
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log"
+            ))
+        );
+        assert_eq!(
+            build_pod_log_file_path(
+                Path::new("/var/log/pods"),
+                "sandbox0-ns",
+                "sandbox0-name",
+                "sandbox0-uid",
+                "..",
+                1
+            ),
+            Err(PathComponentError::RelativeSegment {
+                component: "..".to_owned()
+            })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn parse_log_file_path_inverts_build_pod_log_file_path(
+            pod_namespace in "[a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?",
+            pod_name in "[a-z0-9]([a-z0-9.-]{0,251}[a-z0-9])?",
+            pod_uid in "[0-9a-f-]{1,36}",
+            container_name in "[a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?",
+            restart_count: u32,
+        ) {
+            let path = build_pod_log_file_path(
+                Path::new(K8S_LOGS_DIR),
+                &pod_namespace,
+                &pod_name,
+                &pod_uid,
+                &container_name,
+                restart_count,
+            )
+            .unwrap();
+            let info = parse_log_file_path(path.to_str().unwrap()).unwrap();
+
+            prop_assert_eq!(info.pod_namespace, pod_namespace.as_str());
+            prop_assert_eq!(info.pod_name, pod_name.as_str());
+            prop_assert_eq!(info.pod_uid, pod_uid.as_str());
+            prop_assert_eq!(info.container_name, container_name.as_str());
+            prop_assert_eq!(info.restart_count, Some(restart_count));
+            prop_assert_eq!(info.sandbox_attempt, None);
+        }
+
+        #[test]
+        fn parse_log_file_path_with_delimiter_inverts_build(
+            delimiter in prop::sample::select(vec![DEFAULT_POD_DIR_DELIMITER, '-', '+']),
+            pod_namespace in "[a-z0-9]{1,63}",
+            pod_name in "[a-z0-9]([a-z0-9.+_-]{0,251}[a-z0-9])?",
+            pod_uid in "[a-f][0-9a-f]{0,31}",
+        ) {
+            let dir = build_pod_logs_directory_with_delimiter(
+                Path::new(K8S_LOGS_DIR),
+                &pod_namespace,
+                &pod_name,
+                &pod_uid,
+                delimiter,
+            )
+            .unwrap();
+            let path = dir.join("container").join("0.log");
+            let info = parse_log_file_path_with_delimiter(path.to_str().unwrap(), delimiter).unwrap();
+
+            prop_assert_eq!(info.pod_namespace, pod_namespace.as_str());
+            prop_assert_eq!(info.pod_name, pod_name.as_str());
+            prop_assert_eq!(info.pod_uid, pod_uid.as_str());
+        }
+    }
+
+    #[test]
+    fn test_parse_log_file_path() {
+        let cases = vec![
+            // Valid inputs.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/12.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "12.log",
+                    restart_count: Some(12),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with a file name that doesn't carry a restart count.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/current.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "current.log",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with a custom pod logs root.
+            (
+                "/data/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "D:\\kubelet\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid\\sandbox0-container0-name\\1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with mixed separators, like containerd on Windows
+            // sometimes produces.
+            (
+                "D:\\var\\log\\pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name\\1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with a rotated log file name.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/2.log.20240101-120000",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "2.log.20240101-120000",
+                    restart_count: Some(2),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log.20240101-000000.gz",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "0.log.20240101-000000.gz",
+                    restart_count: Some(0),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.logs",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "0.logs",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with a pod name that contains the delimiter.
+            (
+                "/var/log/pods/ns_my_app_uid/container/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "ns",
+                    pod_name: "my_app",
+                    pod_uid: "uid",
+                    container_name: "container",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0_name_with_underscores_sandbox0-uid/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0_name_with_underscores",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Valid inputs with the sandbox attempt in the pod logs directory.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_0/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: Some(0),
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_3/sandbox0-container0-name/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: Some(3),
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0_name_sandbox0-uid_1/sandbox0-container0-name/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0_name",
+                    pod_uid: "sandbox0-uid",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: Some(1),
+                    extra_segments: vec![],
+                }),
+            ),
+            // A numeric third segment is still the UID.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_0/sandbox0-container0-name/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "0",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Invalid inputs.
+            ("/var/log/pods/other", None),
+            ("/var/log/pods/sandbox0-ns_sandbox0-uid/sandbox0-container0-name/1.log", None),
+            ("qwe", None),
+            ("", None),
+            ("qwe/", None),
+            ("/", None),
+            ("file://", None),
+            ("file://qwe", None),
+        ];
+
+        for (input, expected) in cases.into_iter() {
+            assert_eq!(parse_log_file_path(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_log_file_path_normalization() {
+        let expected = Some(LogFileInfo {
+            pod_namespace: "ns",
+            pod_name: "n",
+            pod_uid: "u",
+            container_name: "c",
+            file_name: "0.log",
+            restart_count: Some(0),
+            sandbox_attempt: None,
+            extra_segments: vec![],
+        });
+
+        let cases = vec![
+            "/var/log/pods/ns_n_u/c/0.log",
+            "file:///var/log/pods/ns_n_u/c/0.log",
+            "/var/log/pods/ns_n_u/c/0.log/",
+            "file:///var/log/pods/ns_n_u/c/0.log/",
+            "C:\\var\\log\\pods\\ns_n_u\\c\\0.log\\",
+        ];
+
+        for input in cases.into_iter() {
+            assert_eq!(parse_log_file_path(input), expected, "{}", input);
+        }
+
+        // Only a single trailing separator is stripped.
+        assert_eq!(parse_log_file_path("/var/log/pods/ns_n_u/c/0.log//"), None);
+    }
+
+    #[test]
+    fn test_parse_log_file_path_any_prefix() {
+        let expected = LogFileInfo {
+            pod_namespace: "sandbox0-ns",
+            pod_name: "sandbox0-name",
+            pod_uid: "sandbox0-uid",
+            container_name: "sandbox0-container0-name",
+            file_name: "1.log",
+            restart_count: Some(1),
+            sandbox_attempt: None,
+            extra_segments: vec![],
+        };
+        let suffix = "sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log";
+
+        // The components are extracted from the end of the path, so neither the
+        // depth nor the names of the directories above the pod logs directory
+        // matter, like for a bind-mounted copy of the pod logs root.
+        let prefixes = [
+            "",
+            "/",
+            "./",
+            "pods/",
+            "/var/log/pods/",
+            "/mnt/nodelogs/var/log/pods/",
+            "/mnt/nodelogs/var/log/pods/../pods/",
+            "/a/b/c/d/e/f/g/h/i/j/",
+            "/mnt/var_log_pods/",
+            "file:///mnt/nodelogs/var/log/pods/",
+        ];
+        for prefix in prefixes {
+            let path = format!("{}{}", prefix, suffix);
+            assert_eq!(
+                parse_log_file_path(&path),
+                Some(expected.clone()),
+                "{}",
+                path
+            );
+            assert_eq!(
+                parse_log_file_path_bytes(path.as_bytes()).and_then(|info| info.to_log_file_info()),
+                Some(expected.clone()),
+                "{}",
+                path
+            );
+        }
+
+        let windows_path = format!(
+            "D:\\mnt\\nodelogs\\var\\log\\pods\\{}",
+            suffix.replace('/', "\\")
+        );
+        assert_eq!(parse_log_file_path(&windows_path), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_log_file_path_extra_segments() {
+        const UID: &str = "8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b";
+
+        let cases = vec![
+            (
+                format!("sandbox0-ns_sandbox0-name_{}_zone-a_pool0", UID),
+                "sandbox0-name",
+                None,
+                vec!["zone-a", "pool0"],
+            ),
+            // The sandbox attempt directly follows the UID.
+            (
+                format!("sandbox0-ns_sandbox0-name_{}_2_zone-a", UID),
+                "sandbox0-name",
+                Some(2),
+                vec!["zone-a"],
+            ),
+            (
+                format!("sandbox0-ns_sandbox0_name_{}_zone-a_pool0", UID),
+                "sandbox0_name",
+                None,
+                vec!["zone-a", "pool0"],
+            ),
+            // Empty and numeric segments past the attempt are kept, in order.
+            (
+                format!("sandbox0-ns_sandbox0-name_{}_0_1_", UID),
+                "sandbox0-name",
+                Some(0),
+                vec!["1", ""],
+            ),
+            (
+                format!("sandbox0-ns_sandbox0-name_{}", UID),
+                "sandbox0-name",
+                None,
+                vec![],
+            ),
+        ];
+
+        for (pod_dir, pod_name, sandbox_attempt, extra_segments) in cases.into_iter() {
+            let path = format!("/var/log/pods/{}/sandbox0-container0-name/1.log", pod_dir);
+            let info = parse_log_file_path(&path).unwrap();
+            assert_eq!(info.pod_namespace, "sandbox0-ns", "{}", path);
+            assert_eq!(info.pod_name, pod_name, "{}", path);
+            assert_eq!(info.pod_uid, UID, "{}", path);
+            assert_eq!(info.sandbox_attempt, sandbox_attempt, "{}", path);
+            assert_eq!(info.extra_segments, extra_segments, "{}", path);
+        }
+
+        // Without a well-formed UID, the extra segments can't be told apart
+        // from the pod name, and the last segment is taken for the UID.
+        let info = parse_log_file_path(
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_zone-a_pool0/sandbox0-container0-name/1.log",
+        )
+        .unwrap();
+        assert_eq!(info.pod_name, "sandbox0-name_sandbox0-uid_zone-a");
+        assert_eq!(info.pod_uid, "pool0");
+        assert!(info.extra_segments.is_empty());
+    }
+
+    #[test]
+    fn test_log_file_info_stream_key() {
+        let key = |path: &str| parse_log_file_path(path).unwrap().stream_key();
+
+        // The key is pinned, as it may be persisted.
+        assert_eq!(
+            key("/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log"),
+            8891416448038615252
+        );
+        assert_eq!(
+            key("/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/2.log"),
+            13747669295616412838
+        );
+
+        // Only the pod UID, the container name and the restart count are keyed.
+        assert_eq!(
+            key("/mnt/pods/other-ns_other-name_sandbox0-uid_2/sandbox0-container0-name/1.log.20240101-120000"),
+            8891416448038615252
+        );
+
+        let info = LogFileInfo {
+            restart_count: None,
+            ..parse_log_file_path(
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+            )
+            .unwrap()
+        };
+        assert_eq!(info.stream_key(), 20479255930664118);
+    }
+
+    #[test]
+    fn test_log_file_info_to_owned() {
+        let path = String::from(
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+        );
+        let owned = parse_log_file_path(&path).unwrap().to_owned();
+        drop(path);
+
+        assert_eq!(
+            owned,
+            OwnedLogFileInfo {
+                pod_namespace: "sandbox0-ns".to_owned(),
+                pod_name: "sandbox0-name".to_owned(),
+                pod_uid: "sandbox0-uid".to_owned(),
+                container_name: "sandbox0-container0-name".to_owned(),
+                file_name: "1.log".to_owned(),
+                restart_count: Some(1),
+                sandbox_attempt: None,
+                extra_segments: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_log_file_path_strict() {
+        let cases = vec![
+            // Valid inputs.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/sandbox0-container0-name/1.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "1.log",
+                    restart_count: Some(1),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Static pods are keyed by the config hashsum.
+            (
+                "/var/log/pods/kube-system_kube-apiserver-node0_0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6d/kube-apiserver/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "kube-system",
+                    pod_name: "kube-apiserver-node0",
+                    pod_uid: "0c4fa8bbf1de5c7f3e1e2d9f1b0a7c6d",
+                    container_name: "kube-apiserver",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Some distributions name the system pods with `system-` UIDs.
+            (
+                "/var/log/pods/kube-system_coredns-0_system-7f9c2b1d/coredns/0.log",
+                Some(LogFileInfo {
+                    pod_namespace: "kube-system",
+                    pod_name: "coredns-0",
+                    pod_uid: "system-7f9c2b1d",
+                    container_name: "coredns",
+                    file_name: "0.log",
+                    restart_count: Some(0),
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Invalid inputs.
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                None,
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b/sandbox0-container0-name/1.log",
+                None,
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b-00/sandbox0-container0-name/1.log",
+                None,
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_zf7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/sandbox0-container0-name/1.log",
+                None,
+            ),
+            (
+                "/var/log/pods/kube-system_coredns-0_system-/coredns/0.log",
+                None,
+            ),
+            (
+                "/var/log/pods/kube-system_coredns-0_system-dns.0/coredns/0.log",
+                None,
+            ),
+            ("/var/log/pods/other_tool_state/data/1.log", None),
+            ("", None),
+        ];
+
+        for (input, expected) in cases.into_iter() {
+            assert_eq!(
+                parse_log_file_path_strict(input, DEFAULT_POD_DIR_DELIMITER),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_container_symlink_path() {
+        let cases = vec![
+            // Valid inputs.
+            (
+                "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            (
+                "C:\\var\\log\\containers\\sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "",
+                    container_name: "sandbox0-container0-name",
+                    file_name: "sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // The container ID is split off the last hyphen, the container
+            // name keeps the others.
+            (
+                "/var/log/containers/sandbox0-name_sandbox0-ns_istio-proxy-init-0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef.log",
+                Some(LogFileInfo {
+                    pod_namespace: "sandbox0-ns",
+                    pod_name: "sandbox0-name",
+                    pod_uid: "",
+                    container_name: "istio-proxy-init",
+                    file_name: "sandbox0-name_sandbox0-ns_istio-proxy-init-0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef.log",
+                    restart_count: None,
+                    sandbox_attempt: None,
+                    extra_segments: vec![],
+                }),
+            ),
+            // Invalid inputs.
+            ("/var/log/containers/sandbox0-name_sandbox0-ns_container.log", None),
+            ("/var/log/containers/sandbox0-name_sandbox0-ns_container-.log", None),
+            ("/var/log/containers/sandbox0-name_sandbox0-ns_-8a3c1e4f.log", None),
+            ("/var/log/containers/_sandbox0-ns_container-8a3c1e4f.log", None),
+            ("/var/log/containers/sandbox0-name__container-8a3c1e4f.log", None),
+            ("/var/log/containers/sandbox0-name_sandbox0-ns.log", None),
+            ("/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f", None),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                None,
+            ),
+            ("", None),
+        ];
+
+        for (input, expected) in cases.into_iter() {
+            assert_eq!(parse_container_symlink_path(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_container_symlink_path_matches_pod_log_file() {
+        let symlink = parse_container_symlink_path(
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log",
+        )
+        .unwrap();
+        let pod_log_file = parse_log_file_path(
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log",
+        )
+        .unwrap();
+
+        assert_eq!(symlink.pod_namespace, pod_log_file.pod_namespace);
+        assert_eq!(symlink.pod_name, pod_log_file.pod_name);
+        assert_eq!(symlink.container_name, pod_log_file.container_name);
+    }
+
+    #[test]
+    fn test_rotated_log_file_base() {
+        let dir = "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_2/sandbox0-container0-name";
+        let base = format!("{}/3.log", dir);
+        let cases = vec![
+            ("3.log", None),
+            ("3.logs", None),
+            ("3.log.20240101-120000", Some(base.as_str())),
+            ("3.log.20240101-120000.gz", Some(base.as_str())),
+            ("3.log.gz", Some(base.as_str())),
+        ];
+
+        for (file_name, expected) in cases {
+            let path = format!("{}/{}", dir, file_name);
+            assert_eq!(rotated_log_file_base(&path), expected, "{}", path);
+        }
+
+        // The metadata of the rotations is the one of their log file.
+        let rotation = format!("{}/3.log.20240101-120000.gz", dir);
+        let info = parse_log_file_path(&rotation).unwrap();
+        let base_info = parse_log_file_path(rotated_log_file_base(&rotation).unwrap()).unwrap();
+        assert_eq!(info.file_name, "3.log.20240101-120000.gz");
+        assert_eq!(base_info.file_name, "3.log");
+        assert_eq!(
+            LogFileInfo {
+                file_name: base_info.file_name,
+                ..info
+            },
+            base_info
+        );
+    }
+
+    #[test]
+    fn test_is_active_log_file() {
+        let dir = "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name";
+        let cases = vec![
+            ("0.log", true),
+            ("1.log", true),
+            ("0.log.20240101", false),
+            ("0.log.20240101-120000", false),
+            ("0.log.gz", false),
+            ("0.log.20240101-120000.gz", false),
+            ("0.log.tmp", false),
+        ];
+
+        for (file_name, expected) in cases {
+            let path = format!("{}/{}", dir, file_name);
+            assert_eq!(is_active_log_file(&path), expected, "{}", path);
+        }
+
+        assert!(is_active_log_file(
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log"
+        ));
+        assert!(is_active_log_file(&format!("file://{}/0.log", dir)));
+        assert!(!is_active_log_file(""));
+    }
+
+    #[test]
+    fn test_log_layout_parse_log_file_path() {
+        let pods_path =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log";
+        let containers_path =
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log";
+
+        let cases = vec![
+            (LogLayout::Pods, pods_path, Some("sandbox0-uid")),
+            (LogLayout::Pods, containers_path, None),
+            (LogLayout::Containers, pods_path, None),
+            (LogLayout::Containers, containers_path, Some("")),
+            (LogLayout::Auto, pods_path, Some("sandbox0-uid")),
+            (LogLayout::Auto, containers_path, Some("")),
+        ];
+
+        for (layout, input, expected_uid) in cases.into_iter() {
+            let info = layout.parse_log_file_path(input, DEFAULT_POD_DIR_DELIMITER);
+            assert_eq!(info.as_ref().map(|info| info.pod_uid), expected_uid);
+            if let Some(info) = info {
+                assert_eq!(info.pod_namespace, "sandbox0-ns");
+                assert_eq!(info.container_name, "sandbox0-container0-name");
+            }
+        }
+    }
+
+    #[test]
+    fn test_log_layout_parse_log_file_path_strict() {
+        let uid = "8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b";
+        let pods_path = format!(
+            "/var/log/pods/sandbox0-ns+sandbox0-name+{}/sandbox0-container0-name/1.log",
+            uid
+        );
+        let malformed_uid_path =
+            "/var/log/pods/sandbox0-ns+sandbox0-name+sandbox0-uid/sandbox0-container0-name/1.log";
+        let containers_path =
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e4f.log";
+
+        let cases = vec![
+            (LogLayout::Pods, pods_path.as_str(), Some(uid)),
+            (LogLayout::Pods, malformed_uid_path, None),
+            (LogLayout::Containers, containers_path, Some("")),
+            (LogLayout::Auto, pods_path.as_str(), Some(uid)),
+            (LogLayout::Auto, malformed_uid_path, None),
+            (LogLayout::Auto, containers_path, Some("")),
+        ];
+
+        for (layout, input, expected_uid) in cases.into_iter() {
+            let info = layout.parse_log_file_path_strict(input, '+');
+            assert_eq!(info.map(|info| info.pod_uid), expected_uid);
+        }
+    }
+
+    #[test]
+    fn test_pod_logs_directory_delimiters() {
+        for delimiter in [DEFAULT_POD_DIR_DELIMITER, '-', '+'] {
+            let dir = build_pod_logs_directory_with_delimiter(
+                Path::new("/var/log/pods"),
+                "ns",
+                "name",
+                "uid",
+                delimiter,
+            )
+            .unwrap();
+            assert_eq!(
+                dir,
+                PathBuf::from(format!("/var/log/pods/ns{0}name{0}uid", delimiter))
+            );
+
+            let path = format!(
+                "/var/log/pods/ns{0}app{0}name{0}uid{0}3/container/1.log",
+                delimiter
+            );
+            let info = parse_log_file_path_with_delimiter(&path, delimiter).unwrap();
+            assert_eq!(info.pod_namespace, "ns", "{}", delimiter);
+            assert_eq!(
+                info.pod_name,
+                format!("app{}name", delimiter),
+                "{}",
+                delimiter
+            );
+            assert_eq!(info.pod_uid, "uid", "{}", delimiter);
+            assert_eq!(info.sandbox_attempt, Some(3), "{}", delimiter);
+            assert_eq!(info.restart_count, Some(1), "{}", delimiter);
+
+            let other = if delimiter == '+' { '-' } else { '+' };
+            assert_eq!(parse_log_file_path_with_delimiter(&path, other), None);
+        }
+    }
+
+    #[test]
+    fn test_try_parse_log_file_path() {
+        let cases = vec![
+            ("", Err(PathParseError::MissingContainer)),
+            ("0.log", Err(PathParseError::MissingContainer)),
+            ("container/0.log", Err(PathParseError::MissingPodDir)),
+            (
+                "/var/log/syslog",
+                Err(PathParseError::MalformedPodDir {
+                    pod_dir: "var".to_owned(),
+                    found_segments: 1,
+                }),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name/sandbox0-container0-name/1.log",
+                Err(PathParseError::MalformedPodDir {
+                    pod_dir: "sandbox0-ns_sandbox0-name".to_owned(),
+                    found_segments: 2,
+                }),
+            ),
+        ];
+
+        for (input, expected) in cases.into_iter() {
+            assert_eq!(try_parse_log_file_path(input), expected, "{}", input);
+            assert_eq!(parse_log_file_path(input), None, "{}", input);
+        }
+
+        let path =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log";
+        assert_eq!(
+            try_parse_log_file_path(path).ok(),
+            parse_log_file_path(path)
+        );
+        assert_eq!(
+            try_parse_log_file_path_with_delimiter(path, '+'),
+            Err(PathParseError::MalformedPodDir {
+                pod_dir: "sandbox0-ns_sandbox0-name_sandbox0-uid".to_owned(),
+                found_segments: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_log_file_path_bytes() {
+        // A container name with bytes that aren't valid UTF-8.
+        let path =
+            b"/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-\xff\xfe-name/1.log";
+        let info = parse_log_file_path_bytes(path).unwrap();
+        assert_eq!(info.pod_namespace, PathSegment::Str("sandbox0-ns"));
+        assert_eq!(info.pod_name, PathSegment::Str("sandbox0-name"));
+        assert_eq!(info.pod_uid, PathSegment::Str("sandbox0-uid"));
+        assert_eq!(
+            info.container_name,
+            PathSegment::Bytes(b"sandbox0-\xff\xfe-name")
+        );
+        assert_eq!(info.container_name.as_str(), None);
+        assert_eq!(
+            info.container_name.to_string_lossy(),
+            "sandbox0-\u{fffd}\u{fffd}-name"
+        );
+        assert_eq!(info.file_name, PathSegment::Str("1.log"));
+        assert_eq!(info.restart_count, Some(1));
+        assert_eq!(info.to_log_file_info(), None);
+
+        // A pod name with bytes that aren't valid UTF-8, followed by a sandbox
+        // attempt and an extra segment.
+        let path = b"/var/log/pods/ns_name-\xc3_3bd1e6e8-1d4c-4a0e-9b4f-3a1d2c4b5e6f_2_extra/container/0.log.20240101-120000";
+        let info = parse_log_file_path_bytes(path).unwrap();
+        assert_eq!(info.pod_namespace, PathSegment::Str("ns"));
+        assert_eq!(info.pod_name, PathSegment::Bytes(b"name-\xc3"));
+        assert_eq!(
+            info.pod_uid,
+            PathSegment::Str("3bd1e6e8-1d4c-4a0e-9b4f-3a1d2c4b5e6f")
+        );
+        assert_eq!(info.container_name, PathSegment::Str("container"));
+        assert_eq!(info.restart_count, Some(0));
+        assert_eq!(info.sandbox_attempt, Some(2));
+        assert_eq!(info.extra_segments, vec![PathSegment::Str("extra")]);
+
+        // A pod logs directory with bytes that aren't valid UTF-8, but no UID.
+        assert_eq!(
+            try_parse_log_file_path_bytes_with_delimiter(
+                b"/var/log/pods/ns_\xff/container/0.log",
+                DEFAULT_POD_DIR_DELIMITER
+            ),
+            Err(PathParseError::MalformedPodDir {
+                pod_dir: "ns_\u{fffd}".to_owned(),
+                found_segments: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_log_file_path_bytes_matches_str() {
+        let paths = [
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+            "file:///var/log/pods/ns_name_uid_4/container/2.log/",
+            "C:\\var\\log\\pods\\ns_app_name_uid\\container\\0.log",
+            "/var/log/pods/ns_name/container/0.log",
+        ];
+        for path in paths {
+            for delimiter in [DEFAULT_POD_DIR_DELIMITER, '+', '\u{2063}'] {
+                let path = path.replace('_', &delimiter.to_string());
+                assert_eq!(
+                    try_parse_log_file_path_bytes_with_delimiter(path.as_bytes(), delimiter)
+                        .map(|info| info.to_log_file_info().unwrap()),
+                    try_parse_log_file_path_with_delimiter(&path, delimiter),
+                    "{}",
+                    path
+                );
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_log_file_os_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let path = Path::new(OsStr::from_bytes(
+            b"/var/log/pods/ns_name_uid/\x80container/0.log",
+        ));
+        let info = parse_log_file_os_path(path).unwrap();
+        assert_eq!(info.container_name, PathSegment::Bytes(b"\x80container"));
+        assert_eq!(info.pod_uid, PathSegment::Str("uid"));
+    }
+}
@@ -1,6 +1,11 @@
 //! Intercept [`watcher::Event`]'s.
 
-use std::{hash::Hash, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use futures::StreamExt;
 use futures_util::Stream;
@@ -8,17 +13,109 @@ use kube::{
     runtime::{reflector::store, watcher},
     Resource,
 };
-use tokio::pin;
+use tokio::{pin, sync::watch};
 use tokio_util::time::DelayQueue;
 
 use super::meta_cache::{MetaCache, MetaDescribe};
 
+/// The objects whose deletion is currently delayed by a [`custom_reflector`],
+/// by UID.
+///
+/// Allows telling apart the lookups that only succeed because the deletion
+/// of the object is delayed. The UIDs are tracked rather than the names, so
+/// that an object recreated under the same name isn't mistaken for the
+/// deleted one, and the deleted object can still be looked up by its UID
+/// once the store holds its successor.
+pub struct DelayedDeletions<K>(Arc<RwLock<HashMap<String, Arc<K>>>>);
+
+impl<K> Clone for DelayedDeletions<K> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<K> Default for DelayedDeletions<K> {
+    fn default() -> Self {
+        Self(Arc::default())
+    }
+}
+
+impl<K> DelayedDeletions<K> {
+    /// Whether the deletion of the object with the given UID is delayed.
+    pub fn contains(&self, uid: &str) -> bool {
+        self.0.read().expect("lock poisoned").contains_key(uid)
+    }
+
+    /// Gets the object with the given UID, while its deletion is delayed.
+    pub fn get(&self, uid: &str) -> Option<Arc<K>> {
+        self.0.read().expect("lock poisoned").get(uid).cloned()
+    }
+
+    fn insert(&self, uid: String, obj: K) {
+        self.0
+            .write()
+            .expect("lock poisoned")
+            .insert(uid, Arc::new(obj));
+    }
+
+    fn remove(&self, uid: &str) {
+        self.0.write().expect("lock poisoned").remove(uid);
+    }
+
+    fn clear(&self) {
+        self.0.write().expect("lock poisoned").clear();
+    }
+}
+
+/// Tracks whether a [`custom_reflector`] applied the initial list of the
+/// objects to its store.
+///
+/// The watcher starts with listing the objects, and only watches them from
+/// there, so the store is empty until the list is complete.
+#[derive(Clone)]
+pub struct InitialList(Arc<watch::Sender<bool>>);
+
+impl Default for InitialList {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl InitialList {
+    /// Whether the initial list was applied to the store.
+    pub fn is_complete(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until the initial list is applied to the store.
+    pub async fn wait(&self) {
+        let mut complete = self.0.subscribe();
+        // The sender is kept alive by `self`, so this can't fail.
+        _ = complete.wait_for(|complete| *complete).await;
+    }
+
+    fn complete(&self) {
+        self.0.send_replace(true);
+    }
+}
+
 /// Handles events from a [`kube::runtime::watcher()`] to delay the application of Deletion events.
+///
+/// At most `max_delayed_deletions` deletions are delayed at a time, once
+/// there are more, the oldest ones are applied early. This bounds the
+/// memory used by the deleted objects when many of them are deleted at once,
+/// like when a Node is drained.
+///
+/// `initial_list` is completed once the first list of the objects is applied
+/// to the store.
 pub async fn custom_reflector<K, W>(
     mut store: store::Writer<K>,
     mut meta_cache: MetaCache,
     stream: W,
     delay_deletion: Duration,
+    max_delayed_deletions: usize,
+    delayed_deletions: DelayedDeletions<K>,
+    initial_list: InitialList,
 ) where
     K: Resource + Clone + std::fmt::Debug,
     K::DynamicType: Eq + Hash + Clone,
@@ -26,6 +123,8 @@ pub async fn custom_reflector<K, W>(
 {
     pin!(stream);
     let mut delay_queue = DelayQueue::default();
+    // The keys of the delayed events, oldest first.
+    let mut delayed_keys = VecDeque::new();
     loop {
         tokio::select! {
             result = stream.next() => {
@@ -41,16 +140,41 @@ pub async fn custom_reflector<K, W>(
                             }
                             // Delay reconciling any `Deleted` events
                             watcher::Event::Deleted(ref obj) => {
-                                delay_queue.insert(event.to_owned(), delay_deletion);
+                                if let Some(uid) = obj.meta().uid.clone() {
+                                    delayed_deletions.insert(uid, obj.clone());
+                                }
+                                let key = delay_queue.insert(event.to_owned(), delay_deletion);
+                                delayed_keys.push_back(key);
                                 let meta_descr = MetaDescribe::from_meta(obj.meta());
                                 meta_cache.delete(&meta_descr);
+
+                                while delayed_keys.len() > max_delayed_deletions {
+                                    warn!(
+                                        message = "Too many deletions are delayed, applying the oldest one early.",
+                                        max_delayed_deletions,
+                                        internal_log_rate_limit = true,
+                                    );
+                                    let key = delayed_keys
+                                        .pop_front()
+                                        .expect("the queue is not empty");
+                                    let event = delay_queue.remove(&key).into_inner();
+                                    apply_delayed_event(
+                                        &mut store,
+                                        &meta_cache,
+                                        &delayed_deletions,
+                                        event,
+                                    );
+                                }
                             }
                             // Clear all delayed events on `Restarted` events
                             watcher::Event::Restarted(_) => {
                                 trace!(message = "Processing Restarted event.", ?event);
                                 delay_queue.clear();
+                                delayed_keys.clear();
+                                delayed_deletions.clear();
                                 store.apply_watcher_event(&event);
                                 meta_cache.clear();
+                                initial_list.complete();
                             }
                         }
                     },
@@ -66,18 +190,17 @@ pub async fn custom_reflector<K, W>(
             }
             result = delay_queue.next(), if !delay_queue.is_empty() => {
                 match result {
-                    Some(event) => {
-                        let event = event.into_inner();
-                        match event {
-                            watcher::Event::Deleted(ref obj) => {
-                                let meta_desc = MetaDescribe::from_meta(obj.meta());
-                                if !meta_cache.contains(&meta_desc) {
-                                    trace!(message = "Processing Deleted event.", ?event);
-                                    store.apply_watcher_event(&event);
-                                }
-                            },
-                            _ => store.apply_watcher_event(&event),
+                    Some(expired) => {
+                        let expired_key = expired.key();
+                        if let Some(position) = delayed_keys.iter().position(|key| *key == expired_key) {
+                            delayed_keys.remove(position);
                         }
+                        apply_delayed_event(
+                            &mut store,
+                            &meta_cache,
+                            &delayed_deletions,
+                            expired.into_inner(),
+                        );
                     },
                     // DelayQueue returns None if the queue is exhausted,
                     // however we disable the DelayQueue branch if there are
@@ -91,6 +214,30 @@ pub async fn custom_reflector<K, W>(
     }
 }
 
+fn apply_delayed_event<K>(
+    store: &mut store::Writer<K>,
+    meta_cache: &MetaCache,
+    delayed_deletions: &DelayedDeletions<K>,
+    event: watcher::Event<K>,
+) where
+    K: Resource + Clone + std::fmt::Debug,
+    K::DynamicType: Eq + Hash + Clone,
+{
+    match event {
+        watcher::Event::Deleted(ref obj) => {
+            if let Some(uid) = obj.meta().uid.as_deref() {
+                delayed_deletions.remove(uid);
+            }
+            let meta_desc = MetaDescribe::from_meta(obj.meta());
+            if !meta_cache.contains(&meta_desc) {
+                trace!(message = "Processing Deleted event.", ?event);
+                store.apply_watcher_event(&event);
+            }
+        }
+        _ => store.apply_watcher_event(&event),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -103,8 +250,8 @@ mod tests {
         watcher,
     };
 
-    use super::custom_reflector;
     use super::MetaCache;
+    use super::{custom_reflector, DelayedDeletions, InitialList};
 
     #[tokio::test]
     async fn applied_should_add_object() {
@@ -127,8 +274,48 @@ mod tests {
             meta_cache,
             rx,
             Duration::from_secs(1),
+            usize::MAX,
+            DelayedDeletions::default(),
+            InitialList::default(),
+        ));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(store.get(&ObjectRef::from_obj(&cm)).as_deref(), Some(&cm));
+    }
+
+    #[tokio::test]
+    async fn restarted_should_complete_initial_list() {
+        let store_w = store::Writer::default();
+        let store = store_w.as_reader();
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("a".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let (mut tx, rx) = mpsc::channel::<_>(5);
+        let initial_list = InitialList::default();
+        tokio::spawn(custom_reflector(
+            store_w,
+            MetaCache::new(),
+            rx,
+            Duration::from_secs(1),
+            usize::MAX,
+            DelayedDeletions::default(),
+            initial_list.clone(),
         ));
+        tx.send(Ok(watcher::Event::Applied(cm.clone())))
+            .await
+            .unwrap();
         tokio::time::sleep(Duration::from_secs(1)).await;
+        // Only the list completes it, not the watch events.
+        assert!(!initial_list.is_complete());
+
+        tx.send(Ok(watcher::Event::Restarted(vec![cm.clone()])))
+            .await
+            .unwrap();
+        initial_list.wait().await;
+        assert!(initial_list.is_complete());
         assert_eq!(store.get(&ObjectRef::from_obj(&cm)).as_deref(), Some(&cm));
     }
 
@@ -156,6 +343,9 @@ mod tests {
             meta_cache,
             rx,
             Duration::from_secs(2),
+            usize::MAX,
+            DelayedDeletions::default(),
+            InitialList::default(),
         ));
         // Ensure the Resource is still available after deletion
         tokio::time::sleep(Duration::from_secs(1)).await;
@@ -193,6 +383,9 @@ mod tests {
             meta_cache,
             rx,
             Duration::from_secs(2),
+            usize::MAX,
+            DelayedDeletions::default(),
+            InitialList::default(),
         ));
         tokio::time::sleep(Duration::from_secs(1)).await;
         // Ensure the Resource is still available after deletion
@@ -201,4 +394,91 @@ mod tests {
         // Ensure the Resource is still available after Applied event
         assert_eq!(store.get(&ObjectRef::from_obj(&cm)).as_deref(), Some(&cm));
     }
+
+    #[tokio::test]
+    async fn deleted_should_track_delayed_deletion() {
+        let store_w = store::Writer::default();
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("a".to_string()),
+                uid: Some("a-uid".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let (mut tx, rx) = mpsc::channel::<_>(5);
+        tx.send(Ok(watcher::Event::Applied(cm.clone())))
+            .await
+            .unwrap();
+        tx.send(Ok(watcher::Event::Deleted(cm.clone())))
+            .await
+            .unwrap();
+        let meta_cache = MetaCache::new();
+        let delayed_deletions = DelayedDeletions::default();
+        tokio::spawn(custom_reflector(
+            store_w,
+            meta_cache,
+            rx,
+            Duration::from_secs(2),
+            usize::MAX,
+            delayed_deletions.clone(),
+            InitialList::default(),
+        ));
+        // Ensure the deletion is tracked while it's delayed
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(delayed_deletions.contains("a-uid"));
+        assert_eq!(delayed_deletions.get("a-uid").as_deref(), Some(&cm));
+        // Ensure the deletion is no longer tracked once it's applied
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(!delayed_deletions.contains("a-uid"));
+        assert_eq!(delayed_deletions.get("a-uid"), None);
+    }
+
+    #[tokio::test]
+    async fn deleted_should_bound_delayed_deletions() {
+        let store_w = store::Writer::default();
+        let store = store_w.as_reader();
+        let cms: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| ConfigMap {
+                metadata: ObjectMeta {
+                    name: Some(name.to_string()),
+                    ..ObjectMeta::default()
+                },
+                ..ConfigMap::default()
+            })
+            .collect();
+        let (mut tx, rx) = mpsc::channel::<_>(10);
+        for cm in &cms {
+            tx.send(Ok(watcher::Event::Applied(cm.clone())))
+                .await
+                .unwrap();
+        }
+        for cm in &cms {
+            tx.send(Ok(watcher::Event::Deleted(cm.clone())))
+                .await
+                .unwrap();
+        }
+        let meta_cache = MetaCache::new();
+        tokio::spawn(custom_reflector(
+            store_w,
+            meta_cache,
+            rx,
+            Duration::from_secs(2),
+            2,
+            DelayedDeletions::default(),
+            InitialList::default(),
+        ));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        // Ensure the oldest deletion is applied early
+        assert_eq!(store.get(&ObjectRef::from_obj(&cms[0])), None);
+        assert_eq!(
+            store.get(&ObjectRef::from_obj(&cms[1])).as_deref(),
+            Some(&cms[1])
+        );
+        assert_eq!(
+            store.get(&ObjectRef::from_obj(&cms[2])).as_deref(),
+            Some(&cms[2])
+        );
+    }
 }
@@ -16,6 +16,12 @@ use pin_project::pin_project;
 use regex::bytes::Regex;
 use tokio_util::time::delay_queue::{DelayQueue, Key};
 use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::event_path;
+
+use crate::{
+    conditions,
+    event::{Event, LogEvent},
+};
 
 /// Mode of operation of the line aggregator.
 #[configurable_component]
@@ -53,14 +59,14 @@ pub struct Config {
     /// Regular expression pattern that is used to match the start of a new message.
     pub start_pattern: Regex,
 
-    /// Regular expression pattern that is used to determine whether or not more lines should be read.
+    /// Condition that is used to determine whether or not more lines should be read.
     ///
     /// This setting must be configured in conjunction with `mode`.
-    pub condition_pattern: Regex,
+    pub condition: LineCondition,
 
     /// Aggregation mode.
     ///
-    /// This setting must be configured in conjunction with `condition_pattern`.
+    /// This setting must be configured in conjunction with `condition`.
     pub mode: Mode,
 
     /// The maximum amount of time to wait for the next additional line, in milliseconds.
@@ -74,19 +80,49 @@ impl Config {
     /// params.
     pub fn for_legacy(marker: Regex, timeout_ms: u64) -> Self {
         let start_pattern = marker;
-        let condition_pattern = start_pattern.clone();
+        let condition = LineCondition::Pattern(start_pattern.clone());
         let mode = Mode::HaltBefore;
         let timeout = Duration::from_millis(timeout_ms);
 
         Self {
             start_pattern,
-            condition_pattern,
+            condition,
             mode,
             timeout,
         }
     }
 }
 
+/// Condition that is used to determine whether or not more lines should be read.
+#[derive(Clone, Debug)]
+pub enum LineCondition {
+    /// Regular expression pattern matched against the line.
+    Pattern(Regex),
+
+    /// VRL boolean expression evaluated against a log event holding the line in its `message`
+    /// field.
+    Vrl(conditions::Condition),
+}
+
+impl LineCondition {
+    fn is_match(&self, line: &Bytes) -> bool {
+        match self {
+            LineCondition::Pattern(pattern) => pattern.is_match(line.as_ref()),
+            LineCondition::Vrl(condition) => {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("message"), line.clone());
+                condition.check(Event::Log(log)).0
+            }
+        }
+    }
+}
+
+impl From<Regex> for LineCondition {
+    fn from(pattern: Regex) -> Self {
+        LineCondition::Pattern(pattern)
+    }
+}
+
 /// Line aggregator.
 ///
 /// Provides a `Stream` implementation that reads lines from the `inner` stream
@@ -125,15 +161,29 @@ pub struct Logic<K, C> {
 
     /// A queue of key timeouts.
     timeouts: DelayQueue<K>,
+
+    /// Combines the context of the aggregated lines with the context of the
+    /// next line.
+    merge_context: fn(&mut C, C),
 }
 
 impl<K, C> Logic<K, C> {
     /// Create a new `Logic` using the specified `Config`.
+    ///
+    /// The aggregated lines carry the context of the last line.
     pub fn new(config: Config) -> Self {
+        Self::with_merge_context(config, |context, next| *context = next)
+    }
+
+    /// Create a new `Logic` using the specified `Config`, combining the
+    /// context of the aggregated lines with the context of each next line
+    /// using `merge_context`.
+    pub fn with_merge_context(config: Config, merge_context: fn(&mut C, C)) -> Self {
         Self {
             config,
             buffers: HashMap::new(),
             timeouts: DelayQueue::new(),
+            merge_context,
         }
     }
 }
@@ -298,7 +348,7 @@ where
         // Check if we already have the buffered data for the source.
         match self.buffers.entry(src) {
             Entry::Occupied(mut entry) => {
-                let condition_matched = self.config.condition_pattern.is_match(line.as_ref());
+                let condition_matched = self.config.condition.is_match(&line);
                 let decision = match (self.config.mode, condition_matched) {
                     // All consecutive lines matching this pattern are included in
                     // the group.
@@ -322,13 +372,13 @@ where
                     Decision::Continue => {
                         let buffered = entry.get_mut();
                         self.timeouts.reset(&buffered.0, self.config.timeout);
-                        buffered.1.add_next_line(line, context);
+                        buffered.1.add_next_line(line, context, self.merge_context);
                         None
                     }
                     Decision::EndInclude => {
                         let (src, (key, mut buffered)) = entry.remove_entry();
                         self.timeouts.remove(&key);
-                        buffered.add_next_line(line, context);
+                        buffered.add_next_line(line, context, self.merge_context);
                         Some((src, Emit::One(buffered.merge())))
                     }
                     Decision::EndExclude => {
@@ -370,8 +420,8 @@ impl<C> Aggregate<C> {
         }
     }
 
-    fn add_next_line(&mut self, line: Bytes, context: C) {
-        self.context = context;
+    fn add_next_line(&mut self, line: Bytes, context: C, merge_context: fn(&mut C, C)) {
+        merge_context(&mut self.context, context);
         self.lines.push(line);
     }
 
@@ -399,6 +449,7 @@ mod tests {
     use std::fmt::Write as _;
 
     use super::*;
+    use crate::conditions::ConditionalConfig;
 
     #[tokio::test]
     async fn mode_continue_through_1() {
@@ -414,7 +465,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+").unwrap(),
+            condition: Regex::new("^[\\s]+").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
         };
@@ -448,7 +499,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("\\\\$").unwrap(),
-            condition_pattern: Regex::new("\\\\$").unwrap(),
+            condition: Regex::new("\\\\$").unwrap().into(),
             mode: Mode::ContinuePast,
             timeout: Duration::from_millis(10),
         };
@@ -485,7 +536,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^(INFO|ERROR) ").unwrap(),
+            condition: Regex::new("^(INFO|ERROR) ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
         };
@@ -522,7 +573,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("[^;]$").unwrap(),
-            condition_pattern: Regex::new(";$").unwrap(),
+            condition: Regex::new(";$").unwrap().into(),
             mode: Mode::HaltWith,
             timeout: Duration::from_millis(10),
         };
@@ -551,7 +602,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+at").unwrap(),
+            condition: Regex::new("^[\\s]+at").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
         };
@@ -576,7 +627,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("^[^\\s]").unwrap(),
-            condition_pattern: Regex::new("^[\\s]+from").unwrap(),
+            condition: Regex::new("^[\\s]+from").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
         };
@@ -613,7 +664,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("^\\s").unwrap(),
-            condition_pattern: Regex::new("^\\s").unwrap(),
+            condition: Regex::new("^\\s").unwrap().into(),
             mode: Mode::ContinueThrough,
             timeout: Duration::from_millis(10),
         };
@@ -651,7 +702,7 @@ mod tests {
         ];
         let config = Config {
             start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^START ").unwrap(),
+            condition: Regex::new("^START ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
         };
@@ -666,6 +717,80 @@ mod tests {
         run_and_assert(&lines, config, &expected).await;
     }
 
+    #[tokio::test]
+    async fn vrl_condition() {
+        let lines = vec![
+            r#"{"timestamp":"2024-01-01T00:00:00Z","message":"first"}"#,
+            r#"{"detail":"first continued"}"#,
+            r#"{"detail":"first continued again"}"#,
+            r#"{"timestamp":"2024-01-01T00:00:01Z","message":"second"}"#,
+            "not json",
+        ];
+        let condition = conditions::VrlConfig {
+            source: "(parse_json(.message) ?? {}).timestamp == null".to_owned(),
+            runtime: Default::default(),
+        }
+        .build(&Default::default())
+        .unwrap();
+        let config = Config {
+            start_pattern: Regex::new("").unwrap(),
+            condition: LineCondition::Vrl(condition),
+            mode: Mode::ContinueThrough,
+            timeout: Duration::from_millis(10),
+        };
+        let expected = vec![
+            (
+                concat!(
+                    r#"{"timestamp":"2024-01-01T00:00:00Z","message":"first"}"#,
+                    "\n",
+                    r#"{"detail":"first continued"}"#,
+                    "\n",
+                    r#"{"detail":"first continued again"}"#,
+                ),
+                2,
+            ),
+            (
+                concat!(
+                    r#"{"timestamp":"2024-01-01T00:00:01Z","message":"second"}"#,
+                    "\n",
+                    "not json",
+                ),
+                4,
+            ),
+        ];
+        run_and_assert(&lines, config, &expected).await;
+    }
+
+    #[tokio::test]
+    async fn merge_context() {
+        let lines = vec!["first part", " second part", " last part", "usual line"];
+        let config = Config {
+            start_pattern: Regex::new("^[^\\s]").unwrap(),
+            condition: Regex::new("^[\\s]+").unwrap().into(),
+            mode: Mode::ContinueThrough,
+            timeout: Duration::from_millis(10),
+        };
+
+        // Keep the index of the first line along with the index of the last one.
+        let logic = Logic::with_merge_context(config, |context: &mut (usize, usize), next| {
+            context.1 = next.1;
+        });
+        let stream =
+            stream_from_lines(&lines).map(|(src, line, index)| (src, line, (index, index)));
+        let results: Vec<_> = LineAgg::new(stream, logic)
+            .map(|(_src, line, context)| (line, context))
+            .collect()
+            .await;
+
+        assert_eq!(
+            results,
+            vec![
+                (Bytes::from("first part\n second part\n last part"), (0, 2)),
+                (Bytes::from("usual line"), (3, 3)),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn legacy() {
         let lines = vec![
@@ -724,7 +849,7 @@ mod tests {
         }
         let config = Config {
             start_pattern: Regex::new("").unwrap(),
-            condition_pattern: Regex::new("^START ").unwrap(),
+            condition: Regex::new("^START ").unwrap().into(),
             mode: Mode::HaltBefore,
             timeout: Duration::from_millis(10),
         };
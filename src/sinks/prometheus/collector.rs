@@ -1,12 +1,16 @@
-use std::{collections::BTreeMap, fmt::Write as _};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Write as _};
 
 use chrono::Utc;
 use indexmap::map::IndexMap;
 use vector_lib::event::metric::{samples_to_buckets, MetricSketch, MetricTags, Quantile};
+use vector_lib::lookup::path;
 use vector_lib::prometheus::parser::{proto, METRIC_NAME_LABEL};
 
 use crate::{
-    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Value,
+    },
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
 };
 
@@ -15,7 +19,14 @@ pub(super) trait MetricCollector {
 
     fn new() -> Self;
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue);
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        help: Option<&str>,
+        unit: Option<&str>,
+    );
 
     fn emit_value(
         &mut self,
@@ -42,7 +53,15 @@ pub(super) trait MetricCollector {
 
         if metric.kind() == MetricKind::Absolute {
             let tags = metric.tags();
-            self.emit_metadata(metric.name(), name, metric.value());
+            let help = prometheus_metadata(metric, "help");
+            let unit = prometheus_metadata(metric, "unit");
+            self.emit_metadata(
+                metric.name(),
+                name,
+                metric.value(),
+                help.as_deref(),
+                unit.as_deref(),
+            );
 
             match metric.value() {
                 MetricValue::Counter { value } => {
@@ -231,9 +250,16 @@ impl MetricCollector for StringCollector {
         Self { processed }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        help: Option<&str>,
+        _unit: Option<&str>,
+    ) {
         if !self.processed.contains_key(fullname) {
-            let header = Self::encode_header(name, fullname, value);
+            let header = Self::encode_header(help.unwrap_or(name), fullname, value);
             self.processed.insert(fullname.into(), header);
         }
     }
@@ -288,11 +314,12 @@ impl StringCollector {
         .ok();
     }
 
-    fn encode_header(name: &str, fullname: &str, value: &MetricValue) -> String {
+    fn encode_header(help: &str, fullname: &str, value: &MetricValue) -> String {
         let r#type = prometheus_metric_type(value).as_str();
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
         format!(
             "# HELP {} {}\n# TYPE {} {}\n",
-            fullname, name, fullname, r#type
+            fullname, help, fullname, r#type
         )
     }
 
@@ -367,14 +394,21 @@ impl MetricCollector for TimeSeries {
         }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        help: Option<&str>,
+        unit: Option<&str>,
+    ) {
         if !self.metadata.contains_key(name) {
             let r#type = prometheus_metric_type(value);
             let metadata = proto::MetricMetadata {
                 r#type: r#type as i32,
                 metric_family_name: fullname.into(),
-                help: name.into(),
-                unit: String::new(),
+                help: help.unwrap_or(name).into(),
+                unit: unit.unwrap_or_default().into(),
             };
             self.metadata.insert(name.into(), metadata);
         }
@@ -414,6 +448,16 @@ impl MetricCollector for TimeSeries {
     }
 }
 
+/// The help or unit of the metric, kept in the `prometheus` object of its metadata by the
+/// Prometheus sources.
+fn prometheus_metadata<'a>(metric: &'a Metric, field: &str) -> Option<Cow<'a, str>> {
+    metric
+        .metadata()
+        .value()
+        .get(path!("prometheus", field))
+        .and_then(Value::as_str)
+}
+
 const fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType {
     use proto::MetricType;
     match metric_value {
@@ -523,6 +567,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encodes_help_and_unit() {
+        let mut metric = Metric::new(
+            "hits".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 10.0 },
+        )
+        .with_timestamp(Some(timestamp()));
+        let metadata = metric.metadata_mut().value_mut();
+        metadata.insert(path!("prometheus", "help"), "The hits,\nby code.");
+        metadata.insert(path!("prometheus", "unit"), "requests");
+
+        assert_eq!(
+            encode_one::<StringCollector>(Some("vector"), &[], &[], &metric),
+            indoc! { r#"
+                # HELP vector_hits The hits,\nby code.
+                # TYPE vector_hits counter
+                vector_hits 10 1612325106789
+            "#}
+        );
+
+        let request = encode_one::<TimeSeries>(Some("vector"), &[], &[], &metric);
+        assert_eq!(request.metadata[0].help, "The hits,\nby code.");
+        assert_eq!(request.metadata[0].unit, "requests");
+    }
+
     fn encode_counter<T: MetricCollector>() -> T::Output {
         let metric = Metric::new(
             "hits".to_owned(),
@@ -6,7 +6,9 @@ use crate::{
     config::{SourceConfig, SourceContext, SourceOutput},
     event::{BatchNotifier, BatchStatus},
     internal_events::{
-        source::{AmqpAckError, AmqpBytesReceived, AmqpEventError, AmqpRejectError},
+        source::{
+            AmqpAckError, AmqpBytesReceived, AmqpEventError, AmqpMessageDisposed, AmqpRejectError,
+        },
         StreamClosedError,
     },
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
@@ -20,7 +22,11 @@ use futures::{FutureExt, StreamExt};
 use futures_util::Stream;
 use lapin::{acker::Acker, message::Delivery, Channel};
 use snafu::Snafu;
-use std::{io::Cursor, pin::Pin};
+use std::{
+    io::Cursor,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use tokio_util::codec::FramedRead;
 use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
 use vector_lib::configurable::configurable_component;
@@ -102,6 +108,71 @@ pub struct AmqpSourceConfig {
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     pub(crate) acknowledgements: SourceAcknowledgementsConfig,
+
+    /// What to do with the messages that fail to be decoded, and with the messages whose events
+    /// fail to be delivered when end-to-end acknowledgements are enabled.
+    ///
+    /// Rejecting the messages without requeueing them lets a [dead letter exchange][dlx] capture
+    /// them for inspection.
+    ///
+    /// When unset, the messages that fail to be decoded are acknowledged, and the messages whose
+    /// events fail to be delivered are rejected without being requeued.
+    ///
+    /// [dlx]: https://www.rabbitmq.com/docs/dlx
+    #[configurable(derived)]
+    pub(crate) on_decode_error: Option<MessageDisposition>,
+}
+
+/// The disposition of a message that failed to be decoded or delivered.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageDisposition {
+    /// Acknowledge the message, removing it from the queue.
+    Ack,
+
+    /// Reject the message without requeueing it, dead lettering it if the queue has a dead letter
+    /// exchange.
+    Reject,
+
+    /// Reject the message and requeue it, so it is delivered again.
+    RejectRequeue,
+}
+
+impl MessageDisposition {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ack => "ack",
+            Self::Reject => "reject",
+            Self::RejectRequeue => "reject_requeue",
+        }
+    }
+}
+
+/// Why a message is disposed of by its [`MessageDisposition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DispositionReason {
+    DecodeError,
+    DeliveryFailure,
+}
+
+impl DispositionReason {
+    /// The disposition configured with `on_decode_error`, or the default one
+    /// for the reason.
+    const fn disposition(self, on_decode_error: Option<MessageDisposition>) -> MessageDisposition {
+        match (on_decode_error, self) {
+            (Some(disposition), _) => disposition,
+            (None, Self::DecodeError) => MessageDisposition::Ack,
+            (None, Self::DeliveryFailure) => MessageDisposition::Reject,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::DecodeError => "decode_error",
+            Self::DeliveryFailure => "delivery_failure",
+        }
+    }
 }
 
 fn default_queue() -> String {
@@ -194,14 +265,9 @@ impl SourceConfig for AmqpSourceConfig {
 #[derive(Debug)]
 struct FinalizerEntry {
     acker: Acker,
-}
-
-impl From<Delivery> for FinalizerEntry {
-    fn from(delivery: Delivery) -> Self {
-        Self {
-            acker: delivery.acker,
-        }
-    }
+    /// Whether the message failed to be decoded, in which case it is disposed
+    /// of even if the events decoded from it are delivered.
+    decode_failed: bool,
 }
 
 pub(crate) async fn amqp_source(
@@ -334,6 +400,8 @@ async fn receive_event(
         delivery_tag: msg.delivery_tag as i64,
     };
     let events_received = register!(EventsReceived);
+    let decode_failed = AtomicBool::new(false);
+    let decode_failed_flag = &decode_failed;
 
     let stream = stream! {
         while let Some(result) = stream.next().await {
@@ -361,8 +429,9 @@ async fn receive_event(
                 Err(error) => {
                     use vector_lib::codecs::StreamDecodingError as _;
 
-                    // Error is logged by `codecs::Decoder`, no further handling
-                    // is needed here.
+                    // Error is logged by `codecs::Decoder`, the message is
+                    // disposed of once the stream is sent.
+                    decode_failed_flag.store(true, Ordering::Relaxed);
                     if !error.can_continue() {
                         break;
                     }
@@ -372,17 +441,30 @@ async fn receive_event(
     }
     .boxed();
 
-    finalize_event_stream(finalizer, out, stream, msg).await;
+    finalize_event_stream(
+        finalizer,
+        out,
+        stream,
+        msg,
+        &decode_failed,
+        config.on_decode_error,
+    )
+    .await;
 
     Ok(())
 }
 
 /// Send the event stream created by the framed read to the `out` stream.
+///
+/// `decode_failed` is only read once the stream is sent, as it is set while
+/// the stream is decoded.
 async fn finalize_event_stream(
     finalizer: Option<&UnorderedFinalizer<FinalizerEntry>>,
     out: &mut SourceSender,
     mut stream: Pin<Box<dyn Stream<Item = Event> + Send + '_>>,
     msg: Delivery,
+    decode_failed: &AtomicBool,
+    on_decode_error: Option<MessageDisposition>,
 ) {
     match finalizer {
         Some(finalizer) => {
@@ -394,7 +476,11 @@ async fn finalize_event_stream(
                     emit!(StreamClosedError { count: 1 });
                 }
                 Ok(_) => {
-                    finalizer.add(msg.into(), receiver);
+                    let entry = FinalizerEntry {
+                        acker: msg.acker,
+                        decode_failed: decode_failed.load(Ordering::Relaxed),
+                    };
+                    finalizer.add(entry, receiver);
                 }
             }
         }
@@ -403,9 +489,13 @@ async fn finalize_event_stream(
                 emit!(StreamClosedError { count: 1 });
             }
             Ok(_) => {
-                let ack_options = lapin::options::BasicAckOptions::default();
-                if let Err(error) = msg.acker.ack(ack_options).await {
-                    emit!(AmqpAckError { error });
+                if decode_failed.load(Ordering::Relaxed) {
+                    dispose(&msg.acker, DispositionReason::DecodeError, on_decode_error).await;
+                } else {
+                    let ack_options = lapin::options::BasicAckOptions::default();
+                    if let Err(error) = msg.acker.ack(ack_options).await {
+                        emit!(AmqpAckError { error });
+                    }
                 }
             }
         },
@@ -443,7 +533,7 @@ async fn run_amqp_source(
             _ = &mut shutdown => break,
             entry = ack_stream.next() => {
                 if let Some((status, entry)) = entry {
-                    handle_ack(status, entry).await;
+                    handle_ack(status, entry, config.on_decode_error).await;
                 }
             },
             opt_m = consumer.next() => {
@@ -467,23 +557,62 @@ async fn run_amqp_source(
     Ok(())
 }
 
-async fn handle_ack(status: BatchStatus, entry: FinalizerEntry) {
+async fn handle_ack(
+    status: BatchStatus,
+    entry: FinalizerEntry,
+    on_decode_error: Option<MessageDisposition>,
+) {
     match status {
+        BatchStatus::Delivered if entry.decode_failed => {
+            dispose(
+                &entry.acker,
+                DispositionReason::DecodeError,
+                on_decode_error,
+            )
+            .await;
+        }
         BatchStatus::Delivered => {
             let ack_options = lapin::options::BasicAckOptions::default();
             if let Err(error) = entry.acker.ack(ack_options).await {
                 emit!(AmqpAckError { error });
             }
         }
-        BatchStatus::Errored => {
-            let ack_options = lapin::options::BasicRejectOptions::default();
-            if let Err(error) = entry.acker.reject(ack_options).await {
-                emit!(AmqpRejectError { error });
+        BatchStatus::Errored | BatchStatus::Rejected => {
+            dispose(
+                &entry.acker,
+                DispositionReason::DeliveryFailure,
+                on_decode_error,
+            )
+            .await;
+        }
+    }
+}
+
+/// Acknowledges or rejects the message that failed to be decoded or
+/// delivered, as configured with `on_decode_error`.
+async fn dispose(
+    acker: &Acker,
+    reason: DispositionReason,
+    on_decode_error: Option<MessageDisposition>,
+) {
+    let disposition = reason.disposition(on_decode_error);
+    emit!(AmqpMessageDisposed {
+        disposition: disposition.as_str(),
+        reason: reason.as_str(),
+    });
+
+    match disposition {
+        MessageDisposition::Ack => {
+            let ack_options = lapin::options::BasicAckOptions::default();
+            if let Err(error) = acker.ack(ack_options).await {
+                emit!(AmqpAckError { error });
             }
         }
-        BatchStatus::Rejected => {
-            let ack_options = lapin::options::BasicRejectOptions::default();
-            if let Err(error) = entry.acker.reject(ack_options).await {
+        MessageDisposition::Reject | MessageDisposition::RejectRequeue => {
+            let reject_options = lapin::options::BasicRejectOptions {
+                requeue: disposition == MessageDisposition::RejectRequeue,
+            };
+            if let Err(error) = acker.reject(reject_options).await {
                 emit!(AmqpRejectError { error });
             }
         }
@@ -504,6 +633,44 @@ pub mod test {
         crate::test_util::test_generate_config::<AmqpSourceConfig>();
     }
 
+    #[test]
+    fn message_disposition() {
+        let config: AmqpSourceConfig = toml::from_str(
+            r#"
+            connection_string = "amqp://127.0.0.1:5672/%2f"
+            on_decode_error = "reject_requeue"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.on_decode_error,
+            Some(MessageDisposition::RejectRequeue)
+        );
+
+        // The configured disposition applies to both decode errors and
+        // delivery failures.
+        for reason in [
+            DispositionReason::DecodeError,
+            DispositionReason::DeliveryFailure,
+        ] {
+            assert_eq!(
+                reason.disposition(config.on_decode_error),
+                MessageDisposition::RejectRequeue
+            );
+        }
+
+        // Without it, undecodable messages are acknowledged, and failed
+        // deliveries are rejected.
+        assert_eq!(
+            DispositionReason::DecodeError.disposition(None),
+            MessageDisposition::Ack
+        );
+        assert_eq!(
+            DispositionReason::DeliveryFailure.disposition(None),
+            MessageDisposition::Reject
+        );
+    }
+
     pub fn make_config() -> AmqpSourceConfig {
         let mut config = AmqpSourceConfig {
             queue: "it".to_string(),
@@ -1,9 +1,16 @@
-use std::{convert::TryInto, io::ErrorKind};
+use std::{
+    convert::TryInto,
+    fmt,
+    io::ErrorKind,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 use async_compression::tokio::bufread;
 use aws_sdk_s3::types::ByteStream;
 use futures::{stream, stream::StreamExt, TryStreamExt};
 use snafu::Snafu;
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
 use tokio_util::io::StreamReader;
 use vector_lib::codecs::decoding::{
     DeserializerConfig, FramingConfig, NewlineDelimitedDecoderOptions,
@@ -22,11 +29,13 @@ use crate::{
     config::{
         ProxyConfig, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
     },
+    internal_events::S3ObjectDecompressionError,
     line_agg,
     serde::{bool_or_struct, default_decoding},
     tls::TlsConfig,
 };
 
+mod polling;
 pub mod sqs;
 
 /// Compression scheme for objects retrieved from S3.
@@ -66,6 +75,13 @@ enum Strategy {
     /// [aws_sqs]: https://aws.amazon.com/sqs/
     #[derivative(Default)]
     Sqs,
+
+    /// Consumes objects by listing the objects under a prefix of the bucket on an interval.
+    ///
+    /// This doesn't require bucket notifications. The objects are consumed in the lexicographic
+    /// order of their keys, and the last processed one is checkpointed, so that the objects are
+    /// not consumed again after a restart.
+    Polling,
 }
 
 /// Configuration for the `aws_s3` source.
@@ -83,15 +99,21 @@ pub struct AwsS3Config {
     region: RegionOrEndpoint,
 
     /// The compression scheme used for decompressing objects retrieved from S3.
+    ///
+    /// Objects made of multiple GZIP members or ZSTD frames are decompressed to their end. A
+    /// corrupt member ends the object: the events of the members before it are still sent, and
+    /// the error is reported.
     compression: Compression,
 
     /// The strategy to use to consume objects from S3.
-    #[configurable(metadata(docs::hidden))]
     strategy: Strategy,
 
     /// Configuration options for SQS.
     sqs: Option<sqs::Config>,
 
+    /// Configuration options for polling.
+    polling: Option<polling::Config>,
+
     /// The ARN of an [IAM role][iam_role] to assume at startup.
     ///
     /// [iam_role]: https://docs.aws.amazon.com/IAM/latest/UserGuide/id_roles.html
@@ -159,6 +181,11 @@ impl SourceConfig for AwsS3Config {
                     .await?
                     .run(cx, self.acknowledgements, log_namespace),
             )),
+            Strategy::Polling => Ok(Box::pin(
+                self.create_polling_ingestor(multiline_config, &cx, log_namespace)
+                    .await?
+                    .run(cx, self.acknowledgements, log_namespace),
+            )),
         }
     }
 
@@ -273,6 +300,47 @@ impl AwsS3Config {
             None => Err(CreateSqsIngestorError::ConfigMissing {}.into()),
         }
     }
+
+    async fn create_polling_ingestor(
+        &self,
+        multiline: Option<line_agg::Config>,
+        cx: &SourceContext,
+        log_namespace: LogNamespace,
+    ) -> crate::Result<polling::Ingestor> {
+        let polling = self
+            .polling
+            .as_ref()
+            .ok_or(CreatePollingIngestorError::ConfigMissing)?;
+
+        let (s3_client, region) = create_client_and_region::<S3ClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint(),
+            &cx.proxy,
+            &self.tls_options,
+            false,
+        )
+        .await?;
+
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(polling.data_dir.as_ref(), cx.key.id())?;
+
+        let ingestor = polling::Ingestor::new(
+            region,
+            s3_client.clone(),
+            sqs::ObjectReader::new(s3_client, self.compression, multiline, decoder),
+            polling.clone(),
+            &data_dir,
+        )
+        .await?;
+
+        Ok(ingestor)
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -289,6 +357,12 @@ enum CreateSqsIngestorError {
     InvalidEndpoint,
 }
 
+#[derive(Debug, Snafu)]
+enum CreatePollingIngestorError {
+    #[snafu(display("Configuration for `polling` required when strategy=polling"))]
+    ConfigMissing,
+}
+
 /// None if body is empty
 async fn s3_object_decoder(
     compression: Compression,
@@ -306,7 +380,7 @@ async fn s3_object_decoder(
     let r = tokio::io::BufReader::new(StreamReader::new(
         stream::iter(Some(first))
             .chain(body)
-            .map_err(|e| std::io::Error::new(ErrorKind::Other, e)),
+            .map_err(|e| std::io::Error::new(ErrorKind::Other, BodyError(e.into()))),
     ));
 
     let compression = match compression {
@@ -318,16 +392,125 @@ async fn s3_object_decoder(
     match compression {
         Auto => unreachable!(), // is mapped above
         None => Box::new(r),
-        Gzip => Box::new({
-            let mut decoder = bufread::GzipDecoder::new(r);
-            decoder.multiple_members(true);
-            decoder
-        }),
-        Zstd => Box::new({
-            let mut decoder = bufread::ZstdDecoder::new(r);
-            decoder.multiple_members(true);
-            decoder
-        }),
+        Gzip => Box::new(Members::new(
+            key,
+            r,
+            bufread::GzipDecoder::new,
+            bufread::GzipDecoder::into_inner,
+        )),
+        Zstd => Box::new(Members::new(
+            key,
+            r,
+            bufread::ZstdDecoder::new,
+            bufread::ZstdDecoder::into_inner,
+        )),
+    }
+}
+
+/// An error reading the body of the object, as opposed to an error decompressing it.
+#[derive(Debug)]
+struct BodyError(Box<dyn std::error::Error + Send + Sync>);
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+enum MembersState<R, D> {
+    Decoding(D),
+    Next(R),
+    Done,
+}
+
+/// Decompresses the members (or frames) of the object one after the other, ending the object
+/// at the first corrupt one.
+///
+/// Each member is read to its end before the next one is decoded, so the events of the members
+/// before the corrupt one are still sent, and the error is reported instead of failing the
+/// whole object. The errors reading the body are passed on, so that the object is retried.
+struct Members<R, D> {
+    key: String,
+    state: Option<MembersState<R, D>>,
+    new_decoder: fn(R) -> D,
+    into_reader: fn(D) -> R,
+}
+
+impl<R, D> Members<R, D> {
+    fn new(key: &str, reader: R, new_decoder: fn(R) -> D, into_reader: fn(D) -> R) -> Self {
+        Self {
+            key: key.to_owned(),
+            state: Some(MembersState::Decoding(new_decoder(reader))),
+            new_decoder,
+            into_reader,
+        }
+    }
+}
+
+impl<R, D> AsyncRead for Members<R, D>
+where
+    R: AsyncBufRead + Unpin,
+    D: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        loop {
+            let state = self.state.take().expect("the state is always put back");
+            match state {
+                MembersState::Decoding(mut decoder) => {
+                    let filled = buf.filled().len();
+                    match Pin::new(&mut decoder).poll_read(cx, buf) {
+                        // The end of the member.
+                        Poll::Ready(Ok(())) if buf.filled().len() == filled => {
+                            self.state = Some(MembersState::Next((self.into_reader)(decoder)));
+                        }
+                        Poll::Ready(Err(error))
+                            if !error
+                                .get_ref()
+                                .map_or(false, |inner| inner.is::<BodyError>()) =>
+                        {
+                            emit!(S3ObjectDecompressionError {
+                                key: &self.key,
+                                error: &error,
+                            });
+                            self.state = Some(MembersState::Done);
+                            return Poll::Ready(Ok(()));
+                        }
+                        poll => {
+                            self.state = Some(MembersState::Decoding(decoder));
+                            return poll;
+                        }
+                    }
+                }
+                MembersState::Next(mut reader) => {
+                    match Pin::new(&mut reader)
+                        .poll_fill_buf(cx)
+                        .map_ok(|input| input.is_empty())
+                    {
+                        Poll::Ready(Ok(true)) => self.state = Some(MembersState::Done),
+                        Poll::Ready(Ok(false)) => {
+                            self.state = Some(MembersState::Decoding((self.new_decoder)(reader)));
+                        }
+                        poll => {
+                            self.state = Some(MembersState::Next(reader));
+                            return poll.map_ok(|_| ());
+                        }
+                    }
+                }
+                MembersState::Done => {
+                    self.state = Some(MembersState::Done);
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
     }
 }
 
@@ -397,6 +580,14 @@ mod test {
                 Some(Compression::Gzip),
             ),
             ("out.log.gz", None, None, Some(Compression::Gzip)),
+            ("out.log", Some("zstd"), None, Some(Compression::Zstd)),
+            (
+                "out.log",
+                None,
+                Some("application/zstd"),
+                Some(Compression::Zstd),
+            ),
+            ("out.log.zst", None, None, Some(Compression::Zstd)),
             ("out.txt", None, None, None),
         ];
         for case in cases {
@@ -431,6 +622,48 @@ mod test {
 
         assert!(data.is_empty());
     }
+
+    async fn decode_fixture(path: &str) -> String {
+        let mut data = String::new();
+        s3_object_decoder(
+            Compression::Auto,
+            path,
+            None,
+            None,
+            ByteStream::from(std::fs::read(path).unwrap()),
+        )
+        .await
+        .read_to_string(&mut data)
+        .await
+        .unwrap();
+        data
+    }
+
+    #[tokio::test]
+    async fn decode_multipart() {
+        let expected = std::fs::read_to_string("tests/data/multipart-zst.log").unwrap();
+        assert_eq!(
+            decode_fixture("tests/data/multipart-zst.log.zst").await,
+            expected
+        );
+
+        let data = decode_fixture("tests/data/multipart-gzip.log.gz").await;
+        assert_eq!(
+            data.lines().collect::<Vec<_>>(),
+            crate::test_util::lines_from_gzip_file("tests/data/multipart-gzip.log.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_corrupt_trailing_member() {
+        let expected = std::fs::read_to_string("tests/data/corrupt-trailing-member.log").unwrap();
+        for path in [
+            "tests/data/corrupt-trailing-member.log.gz",
+            "tests/data/corrupt-trailing-member.log.zst",
+        ] {
+            assert_eq!(decode_fixture(path).await, expected, "{}", path);
+        }
+    }
 }
 
 #[cfg(feature = "aws-s3-integration-tests")]
@@ -673,6 +906,33 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn s3_process_message_corrupt_trailing_member() {
+        trace_init();
+
+        let logs = lines_from_plaintext("tests/data/corrupt-trailing-member.log");
+
+        for (path, content_encoding) in [
+            ("tests/data/corrupt-trailing-member.log.gz", "gzip"),
+            ("tests/data/corrupt-trailing-member.log.zst", "zstd"),
+        ] {
+            let buffer = std::fs::read(path).expect("file can be read");
+
+            test_event(
+                None,
+                Some(content_encoding),
+                None,
+                None,
+                buffer,
+                logs.clone(),
+                Delivered,
+                false,
+                DeserializerConfig::Bytes,
+            )
+            .await;
+        }
+    }
+
     #[tokio::test]
     async fn s3_process_message_multiline() {
         trace_init();
@@ -689,7 +949,8 @@ mod integration_tests {
             Some(MultilineConfig {
                 start_pattern: "abc".to_owned(),
                 mode: line_agg::Mode::HaltWith,
-                condition_pattern: "geh".to_owned(),
+                condition_pattern: Some("geh".to_owned()),
+                condition: None,
                 timeout_ms: Duration::from_millis(1000),
             }),
             logs.join("\n").into_bytes(),
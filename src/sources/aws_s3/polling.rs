@@ -0,0 +1,444 @@
+//! Consumes the objects by listing them under a prefix of the bucket, without bucket
+//! notifications.
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use aws_sdk_s3::{
+    error::{DeleteObjectError, ListObjectsV2Error},
+    model::Object,
+    output::ListObjectsV2Output,
+    Client as S3Client,
+};
+use aws_smithy_client::SdkError;
+use aws_types::region::Region;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::{fs, pin, select, time::sleep};
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{BytesReceived, Protocol, Registered};
+
+use super::sqs::{ObjectLocation, ObjectReader};
+use crate::{
+    config::{SourceAcknowledgementsConfig, SourceContext},
+    internal_events::{
+        EventsReceived, S3CheckpointWriteError, S3ObjectDeleteError, S3ObjectListError,
+        S3ObjectProcessingError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+const TMP_FILE_NAME: &str = "checkpoint.new.json";
+
+/// Polling configuration options.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Config {
+    /// The name of the bucket to list the objects from.
+    #[configurable(metadata(docs::examples = "my-bucket"))]
+    pub(super) bucket: String,
+
+    /// The prefix of the keys of the objects to consume.
+    ///
+    /// The objects are consumed in the lexicographic order of their keys, so they are expected to
+    /// be written under this prefix in that order, as with keys starting with a timestamp. Objects
+    /// written with keys sorting before the last processed one are never consumed.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "logs/"))]
+    pub(super) prefix: String,
+
+    /// How long to wait between the listings of the objects, in seconds.
+    ///
+    /// Each listing consumes all the new objects before waiting again.
+    #[serde(default = "default_poll_secs")]
+    #[derivative(Default(value = "default_poll_secs()"))]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    pub(super) poll_secs: u32,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) start_from: StartFrom,
+
+    /// Whether to delete the objects once they are processed.
+    ///
+    /// When acknowledgements are enabled, the objects are only deleted once their events are
+    /// delivered.
+    #[serde(default)]
+    pub(super) delete_object: bool,
+
+    /// The directory used to persist the checkpoint of the last processed object.
+    ///
+    /// By default, the global `data_dir` option is used. Make sure the running user has write
+    /// permissions to this directory.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/lib/vector"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub(super) data_dir: Option<PathBuf>,
+}
+
+const fn default_poll_secs() -> u32 {
+    60
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Snafu)]
+pub enum StartFromParseError {
+    #[snafu(display("Must be \"beginning\" or an RFC 3339 timestamp"))]
+    UnableToParse,
+}
+
+/// Where to start consuming the objects from.
+///
+/// Objects last modified before the timestamp are skipped.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(untagged)]
+#[serde(try_from = "String", into = "String")]
+#[configurable(metadata(docs::examples = "beginning"))]
+#[configurable(metadata(docs::examples = "2024-01-01T00:00:00Z"))]
+pub(super) enum StartFrom {
+    /// Consume all the objects under the prefix.
+    #[default]
+    Beginning,
+
+    /// Consume the objects last modified at or after this timestamp.
+    Timestamp(DateTime<Utc>),
+}
+
+impl StartFrom {
+    fn skips(self, last_modified: Option<DateTime<Utc>>) -> bool {
+        match (self, last_modified) {
+            (Self::Timestamp(start), Some(last_modified)) => last_modified < start,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for StartFrom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Beginning => write!(f, "beginning"),
+            Self::Timestamp(timestamp) => write!(f, "{}", timestamp.to_rfc3339()),
+        }
+    }
+}
+
+impl TryFrom<String> for StartFrom {
+    type Error = StartFromParseError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        if input == "beginning" {
+            return Ok(Self::Beginning);
+        }
+        DateTime::parse_from_rfc3339(&input)
+            .map(|timestamp| Self::Timestamp(timestamp.into()))
+            .map_err(|_| StartFromParseError::UnableToParse)
+    }
+}
+
+impl From<StartFrom> for String {
+    fn from(start_from: StartFrom) -> String {
+        start_from.to_string()
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum IngestorNewError {
+    #[snafu(display("Unable to read checkpoint {:?}: {}", path, source))]
+    ReadCheckpoint { source: io::Error, path: PathBuf },
+}
+
+/// The last processed object.
+///
+/// Listing the objects after its key resumes from where the previous listing stopped, without the
+/// listing having to go over the processed objects again.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+struct Checkpoint {
+    key: Option<String>,
+    etag: Option<String>,
+}
+
+struct Checkpointer {
+    path: PathBuf,
+    tmp_path: PathBuf,
+}
+
+impl Checkpointer {
+    fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(CHECKPOINT_FILE_NAME),
+            tmp_path: data_dir.join(TMP_FILE_NAME),
+        }
+    }
+
+    async fn read(&self) -> Result<Checkpoint, io::Error> {
+        match fs::read(&self.path).await {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Checkpoint::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes the checkpoint to a temporary file first, so that it's replaced atomically.
+    async fn write(&self, checkpoint: &Checkpoint) -> Result<(), io::Error> {
+        fs::write(&self.tmp_path, serde_json::to_vec(checkpoint)?).await?;
+        fs::rename(&self.tmp_path, &self.path).await
+    }
+}
+
+pub(super) struct Ingestor {
+    region: Region,
+    s3_client: S3Client,
+    reader: ObjectReader,
+    config: Config,
+    checkpointer: Checkpointer,
+    checkpoint: Checkpoint,
+}
+
+impl Ingestor {
+    pub(super) async fn new(
+        region: Region,
+        s3_client: S3Client,
+        reader: ObjectReader,
+        config: Config,
+        data_dir: &Path,
+    ) -> Result<Ingestor, IngestorNewError> {
+        let checkpointer = Checkpointer::new(data_dir);
+        let checkpoint = checkpointer.read().await.context(ReadCheckpointSnafu {
+            path: checkpointer.path.clone(),
+        })?;
+
+        Ok(Ingestor {
+            region,
+            s3_client,
+            reader,
+            config,
+            checkpointer,
+            checkpoint,
+        })
+    }
+
+    pub(super) async fn run(
+        self,
+        cx: SourceContext,
+        acknowledgements: SourceAcknowledgementsConfig,
+        log_namespace: LogNamespace,
+    ) -> Result<(), ()> {
+        let acknowledgements = cx.do_acknowledgements(acknowledgements);
+        IngestorProcess {
+            state: self,
+            out: cx.out,
+            shutdown: cx.shutdown,
+            acknowledgements,
+            log_namespace,
+            bytes_received: register!(BytesReceived::from(Protocol::HTTP)),
+            events_received: register!(EventsReceived),
+        }
+        .run()
+        .await;
+
+        Ok(())
+    }
+}
+
+struct IngestorProcess {
+    state: Ingestor,
+    out: SourceSender,
+    shutdown: ShutdownSignal,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    bytes_received: Registered<BytesReceived>,
+    events_received: Registered<EventsReceived>,
+}
+
+impl IngestorProcess {
+    async fn run(mut self) {
+        let poll_interval = Duration::from_secs(self.state.config.poll_secs.into());
+        let shutdown = self.shutdown.clone().fuse();
+        pin!(shutdown);
+
+        loop {
+            // Shutting down while an object is processed leaves the checkpoint before it, so its
+            // events are sent again on the next start.
+            select! {
+                _ = &mut shutdown => break,
+                _ = self.run_once() => {},
+            }
+            select! {
+                _ = &mut shutdown => break,
+                _ = sleep(poll_interval) => {},
+            }
+        }
+    }
+
+    /// Processes the objects listed after the checkpoint, one page after the other.
+    ///
+    /// Stops at the first object failing to be processed, so that the checkpoint never passes
+    /// over it, and it's retried on the next listing.
+    async fn run_once(&mut self) {
+        loop {
+            let output = match self.list_objects().await {
+                Ok(output) => output,
+                Err(error) => {
+                    emit!(S3ObjectListError {
+                        bucket: &self.state.config.bucket,
+                        error: &error,
+                    });
+                    return;
+                }
+            };
+
+            for object in output.contents.unwrap_or_default() {
+                if !self.handle_object(object).await {
+                    return;
+                }
+            }
+
+            if !output.is_truncated {
+                return;
+            }
+        }
+    }
+
+    /// Returns whether the listing can go on to the next object.
+    async fn handle_object(&mut self, object: Object) -> bool {
+        let Some(key) = object.key else {
+            return true;
+        };
+
+        let last_modified = object
+            .last_modified
+            .and_then(|ts| Utc.timestamp_opt(ts.secs(), ts.subsec_nanos()).single());
+        if !self.state.config.start_from.skips(last_modified) {
+            let location = ObjectLocation {
+                bucket: &self.state.config.bucket,
+                key: &key,
+                region: self.state.region.as_ref(),
+            };
+            let result = self
+                .state
+                .reader
+                .read(
+                    &location,
+                    &mut self.out,
+                    &self.bytes_received,
+                    &self.events_received,
+                    self.acknowledgements,
+                    self.log_namespace,
+                )
+                .await;
+            if let Err(error) = result {
+                emit!(S3ObjectProcessingError {
+                    bucket: &self.state.config.bucket,
+                    key: &key,
+                    error: &error,
+                });
+                return false;
+            }
+
+            if self.state.config.delete_object {
+                if let Err(error) = self.delete_object(&key).await {
+                    emit!(S3ObjectDeleteError {
+                        bucket: &self.state.config.bucket,
+                        key: &key,
+                        error: &error,
+                    });
+                }
+            }
+        }
+
+        self.state.checkpoint = Checkpoint {
+            key: Some(key),
+            etag: object.e_tag,
+        };
+        if let Err(error) = self.state.checkpointer.write(&self.state.checkpoint).await {
+            emit!(S3CheckpointWriteError { error });
+        }
+        true
+    }
+
+    /// Lists the objects after the checkpoint, in the lexicographic order of their keys.
+    ///
+    /// Starting after the checkpointed key, rather than continuing the previous page, lets the
+    /// listing skip over the processed objects even in buckets with millions of keys, and picks up
+    /// where it stopped after a restart.
+    async fn list_objects(&self) -> Result<ListObjectsV2Output, SdkError<ListObjectsV2Error>> {
+        self.state
+            .s3_client
+            .list_objects_v2()
+            .bucket(self.state.config.bucket.clone())
+            .prefix(self.state.config.prefix.clone())
+            .set_start_after(self.state.checkpoint.key.clone())
+            .send()
+            .await
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), SdkError<DeleteObjectError>> {
+        self.state
+            .s3_client
+            .delete_object()
+            .bucket(self.state.config.bucket.clone())
+            .key(key)
+            .send()
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_start_from() {
+        assert_eq!(
+            StartFrom::try_from("beginning".to_owned()),
+            Ok(StartFrom::Beginning)
+        );
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            StartFrom::try_from("2024-01-01T01:00:00+01:00".to_owned()),
+            Ok(StartFrom::Timestamp(timestamp))
+        );
+        assert_eq!(
+            String::from(StartFrom::Timestamp(timestamp)),
+            "2024-01-01T00:00:00+00:00"
+        );
+        assert!(StartFrom::try_from("end".to_owned()).is_err());
+        assert!(StartFrom::try_from("2024-01-01".to_owned()).is_err());
+    }
+
+    #[test]
+    fn start_from_skips_older_objects() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let start_from = StartFrom::Timestamp(timestamp);
+        assert!(start_from.skips(Some(timestamp - chrono::Duration::seconds(1))));
+        assert!(!start_from.skips(Some(timestamp)));
+        assert!(!start_from.skips(None));
+        assert!(!StartFrom::Beginning.skips(Some(timestamp)));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpointer = Checkpointer::new(dir.path());
+        assert_eq!(checkpointer.read().await.unwrap(), Checkpoint::default());
+
+        let checkpoint = Checkpoint {
+            key: Some("logs/2024-01-01T00:00:00Z.log.gz".to_owned()),
+            etag: Some("\"d41d8cd98f00b204e9800998ecf8427e\"".to_owned()),
+        };
+        checkpointer.write(&checkpoint).await.unwrap();
+        assert_eq!(checkpointer.read().await.unwrap(), checkpoint);
+        assert!(!dir.path().join(TMP_FILE_NAME).exists());
+    }
+}
@@ -190,18 +190,14 @@ pub enum ProcessingError {
 pub struct State {
     region: Region,
 
-    s3_client: S3Client,
     sqs_client: SqsClient,
-
-    multiline: Option<line_agg::Config>,
-    compression: super::Compression,
+    reader: ObjectReader,
 
     queue_url: String,
     poll_secs: i32,
     client_concurrency: usize,
     visibility_timeout_secs: i32,
     delete_message: bool,
-    decoder: Decoder,
 }
 
 pub(super) struct Ingestor {
@@ -221,11 +217,8 @@ impl Ingestor {
         let state = Arc::new(State {
             region,
 
-            s3_client,
             sqs_client,
-
-            compression,
-            multiline,
+            reader: ObjectReader::new(s3_client, compression, multiline, decoder),
 
             queue_url: config.queue_url,
             poll_secs: config.poll_secs as i32,
@@ -235,7 +228,6 @@ impl Ingestor {
                 .unwrap_or_else(crate::num_threads),
             visibility_timeout_secs: config.visibility_timeout_secs as i32,
             delete_message: config.delete_message,
-            decoder,
         });
 
         Ok(Ingestor { state })
@@ -429,8 +421,7 @@ impl IngestorProcess {
 
     async fn handle_s3_event(&mut self, s3_event: S3Event) -> Result<(), ProcessingError> {
         for record in s3_event.records {
-            self.handle_s3_event_record(record, self.log_namespace)
-                .await?
+            self.handle_s3_event_record(record).await?
         }
         Ok(())
     }
@@ -438,7 +429,6 @@ impl IngestorProcess {
     async fn handle_s3_event_record(
         &mut self,
         s3_event: S3EventRecord,
-        log_namespace: LogNamespace,
     ) -> Result<(), ProcessingError> {
         let event_version: semver::Version = s3_event.event_version.clone().into();
         if !SUPPORTED_S3_EVENT_VERSION.matches(&event_version) {
@@ -467,17 +457,103 @@ impl IngestorProcess {
             });
         }
 
+        let location = ObjectLocation {
+            bucket: &s3_event.s3.bucket.name,
+            key: &s3_event.s3.object.key,
+            region: &s3_event.aws_region,
+        };
+        self.state
+            .reader
+            .read(
+                &location,
+                &mut self.out,
+                &self.bytes_received,
+                &self.events_received,
+                self.acknowledgements,
+                self.log_namespace,
+            )
+            .await
+    }
+
+    async fn receive_messages(&mut self) -> Result<Vec<Message>, SdkError<ReceiveMessageError>> {
+        self.state
+            .sqs_client
+            .receive_message()
+            .queue_url(self.state.queue_url.clone())
+            .max_number_of_messages(10)
+            .visibility_timeout(self.state.visibility_timeout_secs)
+            .wait_time_seconds(self.state.poll_secs)
+            .send()
+            .map_ok(|res| res.messages.unwrap_or_default())
+            .await
+    }
+
+    async fn delete_messages(
+        &mut self,
+        entries: Vec<DeleteMessageBatchRequestEntry>,
+    ) -> Result<DeleteMessageBatchOutput, SdkError<DeleteMessageBatchError>> {
+        self.state
+            .sqs_client
+            .delete_message_batch()
+            .queue_url(self.state.queue_url.clone())
+            .set_entries(Some(entries))
+            .send()
+            .await
+    }
+}
+
+/// The location of an object to read events from.
+pub(super) struct ObjectLocation<'a> {
+    pub(super) bucket: &'a str,
+    pub(super) key: &'a str,
+    pub(super) region: &'a str,
+}
+
+/// Reads the events out of the objects, for all the strategies consuming them.
+pub(super) struct ObjectReader {
+    s3_client: S3Client,
+    compression: super::Compression,
+    multiline: Option<line_agg::Config>,
+    decoder: Decoder,
+}
+
+impl ObjectReader {
+    pub(super) const fn new(
+        s3_client: S3Client,
+        compression: super::Compression,
+        multiline: Option<line_agg::Config>,
+        decoder: Decoder,
+    ) -> Self {
+        Self {
+            s3_client,
+            compression,
+            multiline,
+            decoder,
+        }
+    }
+
+    /// Fetches the object, and sends the events decoded from it.
+    ///
+    /// With `acknowledgements`, this waits for the events to be acknowledged.
+    pub(super) async fn read(
+        &self,
+        location: &ObjectLocation<'_>,
+        out: &mut SourceSender,
+        bytes_received: &Registered<BytesReceived>,
+        events_received: &Registered<EventsReceived>,
+        acknowledgements: bool,
+        log_namespace: LogNamespace,
+    ) -> Result<(), ProcessingError> {
         let object_result = self
-            .state
             .s3_client
             .get_object()
-            .bucket(s3_event.s3.bucket.name.clone())
-            .key(s3_event.s3.object.key.clone())
+            .bucket(location.bucket)
+            .key(location.key)
             .send()
             .await
             .context(GetObjectSnafu {
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
+                bucket: location.bucket,
+                key: location.key,
             });
 
         let object = object_result?;
@@ -490,10 +566,10 @@ impl IngestorProcess {
                 .expect("invalid timestamp")
         });
 
-        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+        let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
         let object_reader = super::s3_object_decoder(
-            self.state.compression,
-            &s3_event.s3.object.key,
+            self.compression,
+            location.key,
             object.content_encoding.as_deref(),
             object.content_type.as_deref(),
             object.body,
@@ -513,10 +589,8 @@ impl IngestorProcess {
         // the offset of the object that has been read, but this would only be relevant in
         // the case that the same vector instance processes the same message.
         let mut read_error = None;
-        let bytes_received = self.bytes_received.clone();
-        let events_received = self.events_received.clone();
         let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = Box::new(
-            FramedRead::new(object_reader, self.state.decoder.framer.clone())
+            FramedRead::new(object_reader, self.decoder.framer.clone())
                 .map(|res| {
                     res.map(|bytes| {
                         bytes_received.emit(ByteSize(bytes.len()));
@@ -531,7 +605,7 @@ impl IngestorProcess {
                 .map(|r| r.expect("validated by take_while")),
         );
 
-        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = match &self.state.multiline {
+        let lines: Box<dyn Stream<Item = Bytes> + Send + Unpin> = match &self.multiline {
             Some(config) => Box::new(
                 LineAgg::new(
                     lines.map(|line| ((), line, ())),
@@ -543,7 +617,7 @@ impl IngestorProcess {
         };
 
         let mut stream = lines.flat_map(|line| {
-            let events = match self.state.decoder.deserializer_parse(line) {
+            let events = match self.decoder.deserializer_parse(line) {
                 Ok((events, _events_size)) => events,
                 Err(_error) => {
                     // Error is handled by `codecs::Decoder`, no further handling
@@ -557,13 +631,7 @@ impl IngestorProcess {
                 .map(|mut event: Event| {
                     event = event.with_batch_notifier_option(&batch);
                     if let Some(log_event) = event.maybe_as_log_mut() {
-                        handle_single_log(
-                            log_event,
-                            log_namespace,
-                            &s3_event,
-                            &metadata,
-                            timestamp,
-                        );
+                        handle_single_log(log_event, log_namespace, location, &metadata, timestamp);
                     }
                     events_received.emit(CountByteSize(1, event.estimated_json_encoded_size_of()));
                     event
@@ -572,7 +640,7 @@ impl IngestorProcess {
             futures::stream::iter(events)
         });
 
-        let send_error = match self.out.send_event_stream(&mut stream).await {
+        let send_error = match out.send_event_stream(&mut stream).await {
             Ok(_) => None,
             Err(_) => {
                 let (count, _) = stream.size_hint();
@@ -592,14 +660,14 @@ impl IngestorProcess {
         if let Some(error) = read_error {
             Err(ProcessingError::ReadObject {
                 source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
+                bucket: location.bucket.to_owned(),
+                key: location.key.to_owned(),
             })
         } else if let Some(error) = send_error {
             Err(ProcessingError::PipelineSend {
                 source: error,
-                bucket: s3_event.s3.bucket.name.clone(),
-                key: s3_event.s3.object.key.clone(),
+                bucket: location.bucket.to_owned(),
+                key: location.key.to_owned(),
             })
         } else {
             match receiver {
@@ -619,38 +687,12 @@ impl IngestorProcess {
             }
         }
     }
-
-    async fn receive_messages(&mut self) -> Result<Vec<Message>, SdkError<ReceiveMessageError>> {
-        self.state
-            .sqs_client
-            .receive_message()
-            .queue_url(self.state.queue_url.clone())
-            .max_number_of_messages(10)
-            .visibility_timeout(self.state.visibility_timeout_secs)
-            .wait_time_seconds(self.state.poll_secs)
-            .send()
-            .map_ok(|res| res.messages.unwrap_or_default())
-            .await
-    }
-
-    async fn delete_messages(
-        &mut self,
-        entries: Vec<DeleteMessageBatchRequestEntry>,
-    ) -> Result<DeleteMessageBatchOutput, SdkError<DeleteMessageBatchError>> {
-        self.state
-            .sqs_client
-            .delete_message_batch()
-            .queue_url(self.state.queue_url.clone())
-            .set_entries(Some(entries))
-            .send()
-            .await
-    }
 }
 
 fn handle_single_log(
     log: &mut LogEvent,
     log_namespace: LogNamespace,
-    s3_event: &S3EventRecord,
+    location: &ObjectLocation<'_>,
     metadata: &Option<HashMap<String, String>>,
     timestamp: Option<DateTime<Utc>>,
 ) {
@@ -659,7 +701,7 @@ fn handle_single_log(
         log,
         Some(LegacyKey::Overwrite(path!("bucket"))),
         path!("bucket"),
-        Bytes::from(s3_event.s3.bucket.name.as_bytes().to_vec()),
+        Bytes::from(location.bucket.as_bytes().to_vec()),
     );
 
     log_namespace.insert_source_metadata(
@@ -667,14 +709,14 @@ fn handle_single_log(
         log,
         Some(LegacyKey::Overwrite(path!("object"))),
         path!("object"),
-        Bytes::from(s3_event.s3.object.key.as_bytes().to_vec()),
+        Bytes::from(location.key.as_bytes().to_vec()),
     );
     log_namespace.insert_source_metadata(
         AwsS3Config::NAME,
         log,
         Some(LegacyKey::Overwrite(path!("region"))),
         path!("region"),
-        Bytes::from(s3_event.aws_region.as_bytes().to_vec()),
+        Bytes::from(location.region.as_bytes().to_vec()),
     );
 
     if let Some(metadata) = metadata {
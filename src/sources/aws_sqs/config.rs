@@ -3,7 +3,7 @@ use std::num::NonZeroUsize;
 use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
 use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::configurable::configurable_component;
-use vector_lib::lookup::owned_value_path;
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, OwnedValuePath};
 use vrl::value::Kind;
 
 use crate::aws::create_client;
@@ -64,6 +64,10 @@ pub struct AwsSqsConfig {
 
     /// Whether to delete the message once it is processed.
     ///
+    /// For FIFO queues, a message is only deleted once it and all the messages received before it
+    /// from its message group were processed, so that a message that fails to be processed is
+    /// received again before the messages following it in its message group.
+    ///
     /// It can be useful to set this to `false` for debugging or during the initial setup.
     #[serde(default = "default_true")]
     #[derivative(Default(value = "default_true()"))]
@@ -80,6 +84,39 @@ pub struct AwsSqsConfig {
     /// processing them.
     pub client_concurrency: Option<NonZeroUsize>,
 
+    /// Overrides the name of the log field used to add the message group ID to each event.
+    ///
+    /// The value is the `MessageGroupId` of the SQS message, only set for FIFO queues.
+    ///
+    /// By default, `"message_group_id"` is used.
+    #[serde(default = "default_message_group_id_key")]
+    #[derivative(Default(value = "default_message_group_id_key()"))]
+    #[configurable(metadata(docs::examples = "message_group_id"))]
+    pub(super) message_group_id_key: OptionalValuePath,
+
+    /// Overrides the name of the log field used to add the message deduplication ID to each
+    /// event.
+    ///
+    /// The value is the `MessageDeduplicationId` of the SQS message, only set for FIFO queues.
+    ///
+    /// By default, `"message_deduplication_id"` is used.
+    #[serde(default = "default_message_deduplication_id_key")]
+    #[derivative(Default(value = "default_message_deduplication_id_key()"))]
+    #[configurable(metadata(docs::examples = "message_deduplication_id"))]
+    pub(super) message_deduplication_id_key: OptionalValuePath,
+
+    /// Overrides the name of the log field used to add the receive count to each event.
+    ///
+    /// The value is the `ApproximateReceiveCount` of the SQS message, the number of times it was
+    /// received from the queue, which can be used to route the messages that repeatedly fail to
+    /// be processed.
+    ///
+    /// By default, `"receive_count"` is used.
+    #[serde(default = "default_receive_count_key")]
+    #[derivative(Default(value = "default_receive_count_key()"))]
+    #[configurable(metadata(docs::examples = "receive_count"))]
+    pub(super) receive_count_key: OptionalValuePath,
+
     #[configurable(derived)]
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
@@ -127,6 +164,8 @@ impl SourceConfig for AwsSqsConfig {
                     .unwrap_or_else(crate::num_threads),
                 visibility_timeout_secs: self.visibility_timeout_secs,
                 delete_message: self.delete_message,
+                fifo: self.is_fifo(),
+                keys: self.keys(),
                 acknowledgements,
                 log_namespace,
             }
@@ -135,6 +174,7 @@ impl SourceConfig for AwsSqsConfig {
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let keys = self.keys();
         let schema_definition = self
             .decoding
             .schema_definition(global_log_namespace.merge(self.log_namespace))
@@ -145,6 +185,27 @@ impl SourceConfig for AwsSqsConfig {
                 &owned_value_path!("timestamp"),
                 Kind::timestamp().or_undefined(),
                 Some("timestamp"),
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.message_group_id.map(LegacyKey::Overwrite),
+                &owned_value_path!("message_group_id"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.message_deduplication_id.map(LegacyKey::Overwrite),
+                &owned_value_path!("message_deduplication_id"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                keys.receive_count.map(LegacyKey::Overwrite),
+                &owned_value_path!("receive_count"),
+                Kind::integer().or_undefined(),
+                None,
             );
 
         vec![SourceOutput::new_logs(
@@ -159,6 +220,19 @@ impl SourceConfig for AwsSqsConfig {
 }
 
 impl AwsSqsConfig {
+    /// Whether the queue is a FIFO queue, the names of which always end with `.fifo`.
+    fn is_fifo(&self) -> bool {
+        self.queue_url.ends_with(".fifo")
+    }
+
+    pub(super) fn keys(&self) -> Keys {
+        Keys {
+            message_group_id: self.message_group_id_key.path.clone(),
+            message_deduplication_id: self.message_deduplication_id_key.path.clone(),
+            receive_count: self.receive_count_key.path.clone(),
+        }
+    }
+
     async fn build_client(&self, cx: &SourceContext) -> crate::Result<aws_sdk_sqs::Client> {
         create_client::<SqsClientBuilder>(
             &self.auth,
@@ -184,4 +258,24 @@ const fn default_true() -> bool {
     true
 }
 
+fn default_message_group_id_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("message_group_id"))
+}
+
+fn default_message_deduplication_id_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("message_deduplication_id"))
+}
+
+fn default_receive_count_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("receive_count"))
+}
+
+/// The legacy keys the attributes of the messages are inserted in.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Keys {
+    pub(super) message_group_id: Option<OwnedValuePath>,
+    pub(super) message_deduplication_id: Option<OwnedValuePath>,
+    pub(super) receive_count: Option<OwnedValuePath>,
+}
+
 impl_generate_config_from_default!(AwsSqsConfig);
@@ -1,20 +1,24 @@
 use std::{collections::HashMap, panic, str::FromStr, sync::Arc};
 
 use aws_sdk_sqs::{
-    model::{DeleteMessageBatchRequestEntry, MessageSystemAttributeName, QueueAttributeName},
+    model::{
+        DeleteMessageBatchRequestEntry, Message, MessageSystemAttributeName, QueueAttributeName,
+    },
     Client as SqsClient,
 };
 use chrono::{DateTime, TimeZone, Utc};
-use futures::{FutureExt, StreamExt};
+use futures::{future::join_all, FutureExt, StreamExt};
 use tokio::{pin, select};
 use tracing_futures::Instrument;
-use vector_lib::config::LogNamespace;
+use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::finalizer::UnorderedFinalizer;
 use vector_lib::internal_event::{EventsReceived, Registered};
+use vector_lib::lookup::path;
 
+use super::{config::Keys, AwsSqsConfig};
 use crate::{
     codecs::Decoder,
-    event::{BatchNotifier, BatchStatus},
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
     internal_events::{
         EndpointBytesReceived, SqsMessageDeleteError, SqsMessageReceiveError, StreamClosedError,
     },
@@ -37,6 +41,8 @@ pub struct SqsSource {
     pub visibility_timeout_secs: u32,
     pub delete_message: bool,
     pub concurrency: usize,
+    pub(super) fifo: bool,
+    pub(super) keys: Keys,
     pub(super) acknowledgements: bool,
     pub(super) log_namespace: LogNamespace,
 }
@@ -44,7 +50,8 @@ pub struct SqsSource {
 impl SqsSource {
     pub async fn run(self, out: SourceSender, shutdown: ShutdownSignal) -> Result<(), ()> {
         let mut task_handles = vec![];
-        let finalizer = self.acknowledgements.then(|| {
+        // The messages of FIFO queues are acknowledged in order by the tasks receiving them.
+        let finalizer = (self.acknowledgements && !self.fifo).then(|| {
             let (finalizer, mut ack_stream) = Finalizer::new(Some(shutdown.clone()));
             let client = self.client.clone();
             let queue_url = self.queue_url.clone();
@@ -111,6 +118,9 @@ impl SqsSource {
             // I think this should be a known attribute
             // https://github.com/awslabs/aws-sdk-rust/issues/411
             .attribute_names(QueueAttributeName::from("SentTimestamp"))
+            .attribute_names(QueueAttributeName::from("ApproximateReceiveCount"))
+            .attribute_names(QueueAttributeName::from("MessageGroupId"))
+            .attribute_names(QueueAttributeName::from("MessageDeduplicationId"))
             .send()
             .await;
 
@@ -133,57 +143,217 @@ impl SqsSource {
                 endpoint: &self.queue_url
             });
 
-            let mut receipts_to_ack = Vec::with_capacity(messages.len());
-            let mut events = Vec::with_capacity(messages.len());
+            if self.fifo {
+                self.process_fifo_messages(messages, out, &events_received)
+                    .await;
+            } else {
+                self.process_messages(messages, out, finalizer, &events_received)
+                    .await;
+            }
+        }
+    }
 
-            let (batch, batch_receiver) =
-                BatchNotifier::maybe_new_with_receiver(finalizer.is_some());
-            for message in messages {
-                if let Some(body) = message.body {
-                    // a receipt handle should always exist
-                    if let Some(receipt_handle) = message.receipt_handle {
-                        receipts_to_ack.push(receipt_handle);
-                    }
-                    let timestamp = get_timestamp(&message.attributes);
-                    // Error is logged by `crate::codecs::Decoder`, no further handling
-                    // is needed here.
-                    let decoded = util::decode_message(
-                        self.decoder.clone(),
-                        "aws_sqs",
-                        body.as_bytes(),
-                        timestamp,
-                        &batch,
-                        self.log_namespace,
-                        &events_received,
-                    );
-                    events.extend(decoded);
+    async fn process_messages(
+        &self,
+        messages: Vec<Message>,
+        out: &mut SourceSender,
+        finalizer: Option<&Arc<Finalizer>>,
+        events_received: &Registered<EventsReceived>,
+    ) {
+        let mut receipts_to_ack = Vec::with_capacity(messages.len());
+        let mut events = Vec::with_capacity(messages.len());
+
+        let (batch, batch_receiver) = BatchNotifier::maybe_new_with_receiver(finalizer.is_some());
+        for message in messages {
+            if message.body.is_some() {
+                // a receipt handle should always exist
+                if let Some(receipt_handle) = message.receipt_handle.clone() {
+                    receipts_to_ack.push(receipt_handle);
                 }
+                events.extend(self.decode_message(&message, &batch, events_received));
             }
-            drop(batch); // Drop last reference to batch acknowledgement finalizer
-            let count = events.len();
+        }
+        drop(batch); // Drop last reference to batch acknowledgement finalizer
+        let count = events.len();
 
-            match out.send_batch(events).await {
-                Ok(()) => {
-                    if self.delete_message {
-                        match batch_receiver {
-                            Some(receiver) => finalizer
-                                .expect("Finalizer must exist for the batch receiver to be created")
-                                .add(receipts_to_ack, receiver),
-                            None => {
-                                delete_messages(
-                                    self.client.clone(),
-                                    receipts_to_ack,
-                                    self.queue_url.clone(),
-                                )
-                                .await
-                            }
+        match out.send_batch(events).await {
+            Ok(()) => {
+                if self.delete_message {
+                    match batch_receiver {
+                        Some(receiver) => finalizer
+                            .expect("Finalizer must exist for the batch receiver to be created")
+                            .add(receipts_to_ack, receiver),
+                        None => {
+                            delete_messages(
+                                self.client.clone(),
+                                receipts_to_ack,
+                                self.queue_url.clone(),
+                            )
+                            .await
                         }
                     }
                 }
-                Err(_) => emit!(StreamClosedError { count }),
             }
+            Err(_) => emit!(StreamClosedError { count }),
+        }
+    }
+
+    /// Processes the messages of a FIFO queue, the message groups concurrently, and the messages
+    /// of each group in order.
+    ///
+    /// SQS doesn't return the messages of a group while messages received before them from the
+    /// same group are in flight, so the groups are never processed by multiple tasks at once.
+    async fn process_fifo_messages(
+        &self,
+        messages: Vec<Message>,
+        out: &SourceSender,
+        events_received: &Registered<EventsReceived>,
+    ) {
+        join_all(
+            group_messages(messages)
+                .into_iter()
+                .map(|group| self.process_message_group(group, out.clone(), events_received)),
+        )
+        .await;
+    }
+
+    /// Sends the events of the messages of a group one message after the other, waiting for the
+    /// events of each message to be acknowledged before sending the next ones.
+    ///
+    /// When the events of a message aren't delivered, neither it nor the following messages are
+    /// deleted, so that they are all received again, in order, once their visibility timeout
+    /// expires.
+    async fn process_message_group(
+        &self,
+        messages: Vec<Message>,
+        mut out: SourceSender,
+        events_received: &Registered<EventsReceived>,
+    ) {
+        let mut receipts_to_delete = Vec::with_capacity(messages.len());
+        for message in messages {
+            let (batch, batch_receiver) =
+                BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+            let events: Vec<_> = self
+                .decode_message(&message, &batch, events_received)
+                .collect();
+            drop(batch);
+            let count = events.len();
+
+            if out.send_batch(events).await.is_err() {
+                emit!(StreamClosedError { count });
+                return;
+            }
+            if let Some(receiver) = batch_receiver {
+                if receiver.await != BatchStatus::Delivered {
+                    break;
+                }
+            }
+            if let Some(receipt_handle) = message.receipt_handle {
+                receipts_to_delete.push(receipt_handle);
+            }
+        }
+
+        if self.delete_message {
+            delete_messages(
+                self.client.clone(),
+                receipts_to_delete,
+                self.queue_url.clone(),
+            )
+            .await;
+        }
+    }
+
+    fn decode_message<'a>(
+        &'a self,
+        message: &'a Message,
+        batch: &'a Option<BatchNotifier>,
+        events_received: &'a Registered<EventsReceived>,
+    ) -> impl Iterator<Item = Event> + 'a {
+        let timestamp = get_timestamp(&message.attributes);
+        // Error is logged by `crate::codecs::Decoder`, no further handling
+        // is needed here.
+        util::decode_message(
+            self.decoder.clone(),
+            "aws_sqs",
+            message.body().unwrap_or_default().as_bytes(),
+            timestamp,
+            batch,
+            self.log_namespace,
+            events_received,
+        )
+        .map(move |mut event| {
+            if let Event::Log(ref mut log) = event {
+                insert_attributes(log, &message.attributes, &self.keys, self.log_namespace);
+            }
+            event
+        })
+    }
+}
+
+/// Inserts the FIFO attributes and the receive count of the message into the event.
+fn insert_attributes(
+    log: &mut LogEvent,
+    attributes: &Option<HashMap<MessageSystemAttributeName, String>>,
+    keys: &Keys,
+    log_namespace: LogNamespace,
+) {
+    let Some(attributes) = attributes else {
+        return;
+    };
+
+    if let Some(id) = attributes.get(&MessageSystemAttributeName::MessageGroupId) {
+        log_namespace.insert_source_metadata(
+            AwsSqsConfig::NAME,
+            log,
+            keys.message_group_id.as_ref().map(LegacyKey::Overwrite),
+            path!("message_group_id"),
+            id.as_str(),
+        );
+    }
+    if let Some(id) = attributes.get(&MessageSystemAttributeName::MessageDeduplicationId) {
+        log_namespace.insert_source_metadata(
+            AwsSqsConfig::NAME,
+            log,
+            keys.message_deduplication_id
+                .as_ref()
+                .map(LegacyKey::Overwrite),
+            path!("message_deduplication_id"),
+            id.as_str(),
+        );
+    }
+    if let Some(count) = attributes
+        .get(&MessageSystemAttributeName::ApproximateReceiveCount)
+        .and_then(|count| i64::from_str(count).ok())
+    {
+        log_namespace.insert_source_metadata(
+            AwsSqsConfig::NAME,
+            log,
+            keys.receive_count.as_ref().map(LegacyKey::Overwrite),
+            path!("receive_count"),
+            count,
+        );
+    }
+}
+
+/// Splits the messages by their message group, keeping the order of the messages within each
+/// group, and of the groups by their first message.
+fn group_messages(messages: Vec<Message>) -> Vec<Vec<Message>> {
+    let mut groups: Vec<(Option<String>, Vec<Message>)> = Vec::new();
+    for message in messages {
+        if message.body.is_none() {
+            continue;
+        }
+        let group_id = message.attributes.as_ref().and_then(|attributes| {
+            attributes
+                .get(&MessageSystemAttributeName::MessageGroupId)
+                .cloned()
+        });
+        match groups.iter_mut().find(|(id, _)| *id == group_id) {
+            Some((_, group)) => group.push(message),
+            None => groups.push((group_id, vec![message])),
         }
     }
+    groups.into_iter().map(|(_, group)| group).collect()
 }
 
 fn get_timestamp(
@@ -346,4 +516,75 @@ mod tests {
             )
         );
     }
+
+    fn message(body: &str, group_id: &str) -> Message {
+        Message::builder()
+            .body(body)
+            .attributes(MessageSystemAttributeName::MessageGroupId, group_id)
+            .build()
+    }
+
+    #[test]
+    fn test_group_messages() {
+        let messages = vec![
+            message("a1", "a"),
+            message("b1", "b"),
+            message("a2", "a"),
+            Message::builder().build(),
+            message("b2", "b"),
+            message("c1", "c"),
+        ];
+
+        let groups: Vec<Vec<_>> = group_messages(messages)
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|message| message.body.unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(groups, vec![vec!["a1", "a2"], vec!["b1", "b2"], vec!["c1"]]);
+    }
+
+    #[test]
+    fn test_insert_attributes() {
+        let attributes = Some(HashMap::from([
+            (
+                MessageSystemAttributeName::MessageGroupId,
+                "group".to_string(),
+            ),
+            (
+                MessageSystemAttributeName::MessageDeduplicationId,
+                "dedup".to_string(),
+            ),
+            (
+                MessageSystemAttributeName::ApproximateReceiveCount,
+                "3".to_string(),
+            ),
+        ]));
+        let keys = AwsSqsConfig::default().keys();
+
+        let mut log = LogEvent::from("test");
+        insert_attributes(&mut log, &attributes, &keys, LogNamespace::Legacy);
+        assert_eq!(log["message_group_id"], "group".into());
+        assert_eq!(log["message_deduplication_id"], "dedup".into());
+        assert_eq!(log["receive_count"], 3.into());
+
+        let mut log = LogEvent::from(vrl::value!("test"));
+        insert_attributes(&mut log, &attributes, &keys, LogNamespace::Vector);
+        let metadata = log.metadata().value();
+        assert_eq!(
+            metadata.get(path!(AwsSqsConfig::NAME, "message_group_id")),
+            Some(&"group".into())
+        );
+        assert_eq!(
+            metadata.get(path!(AwsSqsConfig::NAME, "message_deduplication_id")),
+            Some(&"dedup".into())
+        );
+        assert_eq!(
+            metadata.get(path!(AwsSqsConfig::NAME, "receive_count")),
+            Some(&3.into())
+        );
+    }
 }
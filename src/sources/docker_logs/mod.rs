@@ -52,6 +52,9 @@ const CREATED_AT: &str = "container_created_at";
 const NAME: &str = "container_name";
 const STREAM: &str = "stream";
 const CONTAINER: &str = "container_id";
+const COMPOSE: &str = "compose";
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
 // Prevent short hostname from being wrongly recognized as a container's short ID.
 const MIN_HOSTNAME_LENGTH: usize = 6;
 
@@ -131,6 +134,20 @@ pub struct DockerLogsConfig {
     ))]
     include_labels: Option<Vec<String>>,
 
+    /// A list of container object labels to exclude containers by.
+    ///
+    /// Each label is either a `key`, matching the containers with the label whatever its value, or a
+    /// `key=value`, matching the containers with the label set to `value`. Containers matching any of
+    /// the labels are excluded even if they are also included with `include_labels`.
+    ///
+    /// The labels are evaluated whenever a container starts, so a container recreated with
+    /// different labels is included or excluded by its new labels.
+    #[configurable(metadata(
+        docs::examples = "logging=verbose",
+        docs::examples = "com.mycorp.internal.debug",
+    ))]
+    exclude_labels: Option<Vec<String>>,
+
     /// A list of image names to match against.
     ///
     /// If not provided, all images are included.
@@ -177,6 +194,7 @@ impl Default for DockerLogsConfig {
             exclude_containers: None,
             include_containers: None,
             include_labels: None,
+            exclude_labels: None,
             include_images: None,
             partial_event_marker_field: default_partial_event_marker_field(),
             auto_partial_merge: true,
@@ -218,6 +236,21 @@ impl DockerLogsConfig {
                 .unwrap_or(false))
     }
 
+    /// Returns whether the container is excluded by one of its labels.
+    fn labels_excluded(&self, labels: &HashMap<String, String>) -> bool {
+        self.exclude_labels
+            .as_ref()
+            .map(|exclude_labels| {
+                exclude_labels
+                    .iter()
+                    .any(|label| match label.split_once('=') {
+                        Some((key, value)) => labels.get(key).map_or(false, |v| v == value),
+                        None => labels.contains_key(label),
+                    })
+            })
+            .unwrap_or(false)
+    }
+
     fn name_or_id_matches(id: &str, names: &[String], items: &[String]) -> bool {
         items.iter().any(|flag| id.starts_with(flag))
             || names
@@ -319,6 +352,20 @@ impl SourceConfig for DockerLogsConfig {
                 Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(COMPOSE, "project"))),
+                &owned_value_path!(COMPOSE, "project"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(COMPOSE, "service"))),
+                &owned_value_path!(COMPOSE, "service"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 Some(LegacyKey::Overwrite(owned_value_path!(STREAM))),
@@ -541,6 +588,16 @@ impl DockerLogsSource {
                     return;
                 }
 
+                if self
+                    .esb
+                    .core
+                    .config
+                    .labels_excluded(&container.labels.unwrap_or_default())
+                {
+                    info!(message = "Excluded container by its labels.", id = %id);
+                    return;
+                }
+
                 if !self.esb.core.config.container_name_or_id_included(
                     id.as_str(),
                     names.iter().map(|s| {
@@ -629,9 +686,16 @@ impl DockerLogsSource {
                                                 attributes.get("name").map(|s| s.as_str()),
                                             );
 
+                                        // The attributes of container events hold the labels of
+                                        // the container. Labels can't change without recreating
+                                        // the container, which gives it a new ID, so they are
+                                        // only evaluated for the containers not seen yet.
+                                        let exclude_labels =
+                                            self.esb.core.config.labels_excluded(&attributes);
+
                                         let exclude_self = self.exclude_self(id.as_str());
 
-                                        if include_name && !exclude_self {
+                                        if include_name && !exclude_labels && !exclude_self {
                                             self.containers.insert(id.clone(), self.esb.start(id, None));
                                         }
                                     }
@@ -1119,6 +1183,25 @@ impl ContainerLogInfo {
                 )
             }
         }
+        // Compose project and service, for grouping the containers started by Docker Compose
+        if let Some(project) = self.metadata.labels.get(COMPOSE_PROJECT_LABEL) {
+            log_namespace.insert_source_metadata(
+                DockerLogsConfig::NAME,
+                &mut log,
+                Some(LegacyKey::Overwrite(path!(COMPOSE, "project"))),
+                path!(COMPOSE, "project"),
+                project.clone(),
+            );
+        }
+        if let Some(service) = self.metadata.labels.get(COMPOSE_SERVICE_LABEL) {
+            log_namespace.insert_source_metadata(
+                DockerLogsConfig::NAME,
+                &mut log,
+                Some(LegacyKey::Overwrite(path!(COMPOSE, "service"))),
+                path!(COMPOSE, "service"),
+                service.clone(),
+            );
+        }
         log_namespace.insert_source_metadata(
             DockerLogsConfig::NAME,
             &mut log,
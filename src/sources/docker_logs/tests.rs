@@ -23,6 +23,96 @@ fn exclude_self() {
     assert!(!source.exclude_self("a29d569bd46c"));
 }
 
+#[test]
+fn exclude_labels() {
+    let config = DockerLogsConfig {
+        exclude_labels: Some(vec!["logging=verbose".to_owned(), "debug".to_owned()]),
+        ..DockerLogsConfig::default()
+    };
+    let labels = |labels: &[(&str, &str)]| {
+        labels
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    assert!(config.labels_excluded(&labels(&[("logging", "verbose")])));
+    assert!(config.labels_excluded(&labels(&[("debug", "")])));
+    assert!(config.labels_excluded(&labels(&[("debug", "true"), ("logging", "quiet")])));
+    assert!(!config.labels_excluded(&labels(&[("logging", "quiet")])));
+    assert!(!config.labels_excluded(&labels(&[("logging.verbose", "")])));
+    assert!(!config.labels_excluded(&labels(&[])));
+
+    assert!(!DockerLogsConfig::default().labels_excluded(&labels(&[("logging", "verbose")])));
+}
+
+#[test]
+fn compose_fields() {
+    let created = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let metadata = |labels: &[(&str, &str)]| ContainerMetadata {
+        labels: labels
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect(),
+        name: "web-1".into(),
+        name_str: "/web-1".to_owned(),
+        image: "nginx".into(),
+        created_at: created,
+    };
+    let bytes_received = register!(BytesReceived::from(Protocol::HTTP));
+    let message = || LogOutput::StdOut {
+        message: Bytes::from_static(b"2024-01-01T00:00:01Z hello\n"),
+    };
+
+    let mut info = ContainerLogInfo::new(
+        ContainerId::new("id".to_owned()),
+        metadata(&[
+            (COMPOSE_PROJECT_LABEL, "shop"),
+            (COMPOSE_SERVICE_LABEL, "web"),
+        ]),
+        created,
+    );
+    let log = info
+        .new_event(
+            message(),
+            None,
+            false,
+            &mut None,
+            &bytes_received,
+            LogNamespace::Legacy,
+        )
+        .unwrap();
+    assert_eq!(log["compose.project"], "shop".into());
+    assert_eq!(log["compose.service"], "web".into());
+
+    let mut info = ContainerLogInfo::new(
+        ContainerId::new("id".to_owned()),
+        metadata(&[(COMPOSE_PROJECT_LABEL, "shop")]),
+        created,
+    );
+    let log = info
+        .new_event(
+            message(),
+            None,
+            false,
+            &mut None,
+            &bytes_received,
+            LogNamespace::Vector,
+        )
+        .unwrap();
+    let meta = log.metadata().value();
+    assert_eq!(
+        meta.get(path!(DockerLogsConfig::NAME, COMPOSE, "project")),
+        Some(&"shop".into())
+    );
+    assert_eq!(
+        meta.get(path!(DockerLogsConfig::NAME, COMPOSE, "service")),
+        None
+    );
+}
+
 #[cfg(all(test, feature = "docker-logs-integration-tests"))]
 mod integration_tests {
     use crate::sources::docker_logs::*;
@@ -145,7 +235,11 @@ mod integration_tests {
         let config = ContainerConfig {
             image: Some("busybox"),
             cmd: Some(cmd),
-            labels: label.map(|label| vec![(label, "")].into_iter().collect()),
+            labels: label.map(|label| {
+                vec![label.split_once('=').unwrap_or((label, ""))]
+                    .into_iter()
+                    .collect()
+            }),
             tty: Some(tty),
             ..Default::default()
         };
@@ -610,6 +704,139 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn exclude_labels_legacy_namespace() {
+        trace_init();
+
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let will_be_read = "12";
+
+            let prefix = "vector_test_exclude_labels";
+            let included = format!("{}_{}", prefix, "include");
+            let excluded = format!("{}_{}", prefix, "exclude");
+            let label = "vector_test_exclude_label=verbose";
+
+            let docker = docker(None, None).unwrap();
+
+            let out = source_with_config(DockerLogsConfig {
+                include_containers: Some(vec![prefix.to_owned()]),
+                exclude_labels: Some(vec![label.to_owned()]),
+                ..DockerLogsConfig::default()
+            })
+            .await;
+
+            let id0 = container_log_n(1, &excluded, Some(label), "will not be read", &docker).await;
+            let id1 = container_log_n(
+                1,
+                &included,
+                Some("vector_test_exclude_label=quiet"),
+                will_be_read,
+                &docker,
+            )
+            .await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let events = collect_ready(out).await;
+            container_remove(&id0, &docker).await;
+            container_remove(&id1, &docker).await;
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                *events[0].as_log().get_message().unwrap(),
+                will_be_read.into()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn exclude_labels_recreated_legacy_namespace() {
+        trace_init();
+
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let will_be_read = "13";
+            let name = "vector_test_exclude_labels_recreated";
+            let label = "vector_test_exclude_label_recreated";
+
+            let docker = docker(None, None).unwrap();
+
+            let out = source_with_config(DockerLogsConfig {
+                include_containers: Some(vec![name.to_owned()]),
+                exclude_labels: Some(vec![label.to_owned()]),
+                ..DockerLogsConfig::default()
+            })
+            .await;
+
+            // Started and stopped while excluded.
+            let id = container_log_n(1, name, Some(label), "will not be read", &docker).await;
+            container_remove(&id, &docker).await;
+
+            // Recreated under the same name, without the label.
+            let id = container_log_n(1, name, None, will_be_read, &docker).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let events = collect_ready(out).await;
+            container_remove(&id, &docker).await;
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                *events[0].as_log().get_message().unwrap(),
+                will_be_read.into()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn compose_labels_legacy_namespace() {
+        trace_init();
+        let schema_definitions = DockerLogsConfig::default()
+            .outputs(LogNamespace::Legacy)
+            .first()
+            .unwrap()
+            .schema_definition
+            .clone();
+
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let message = "21";
+            let name = "vector_test_compose_labels";
+
+            let docker = docker(None, None).unwrap();
+            let out = source_with(&[name], None, None).await;
+
+            pull_busybox(&docker).await;
+            let id = docker
+                .create_container(
+                    Some(CreateContainerOptions {
+                        name,
+                        platform: None,
+                    }),
+                    ContainerConfig {
+                        image: Some("busybox"),
+                        cmd: Some(vec!["echo", message]),
+                        labels: Some(HashMap::from([
+                            (COMPOSE_PROJECT_LABEL, "vector_test"),
+                            (COMPOSE_SERVICE_LABEL, "web"),
+                        ])),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap()
+                .id;
+            container_run(&id, &docker).await.unwrap();
+            let events = collect_n(out, 1).await;
+            container_remove(&id, &docker).await;
+
+            schema_definitions
+                .unwrap()
+                .assert_valid_for_event(&events[0]);
+            let log = events[0].as_log();
+            assert_eq!(*log.get_message().unwrap(), message.into());
+            assert_eq!(log["compose.project"], "vector_test".into());
+            assert_eq!(log["compose.service"], "web".into());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn currently_running_legacy_namespace() {
         trace_init();
@@ -854,7 +1081,8 @@ mod integration_tests {
                 include_images: Some(vec!["busybox".to_owned()]),
                 multiline: Some(MultilineConfig {
                     start_pattern: "^[^\\s]".to_owned(),
-                    condition_pattern: "^[\\s]+at".to_owned(),
+                    condition_pattern: Some("^[\\s]+at".to_owned()),
+                    condition: None,
                     mode: line_agg::Mode::ContinueThrough,
                     timeout_ms: Duration::from_millis(10),
                 }),
@@ -926,7 +1154,8 @@ mod integration_tests {
                 include_images: Some(vec!["busybox".to_owned()]),
                 multiline: Some(MultilineConfig {
                     start_pattern: "^[^\\s]".to_owned(),
-                    condition_pattern: "^[\\s]+at".to_owned(),
+                    condition_pattern: Some("^[\\s]+at".to_owned()),
+                    condition: None,
                     mode: line_agg::Mode::ContinueThrough,
                     timeout_ms: Duration::from_millis(10),
                 }),
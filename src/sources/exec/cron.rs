@@ -0,0 +1,360 @@
+//! Cron expressions, for running the scheduled commands at fixed instants.
+
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, Timelike, Utc,
+};
+use vector_lib::TimeZone;
+
+/// The number of years searched for a matching instant. The calendar repeats every 28 years, so
+/// an expression without a match within that span never matches.
+const SEARCH_YEARS: i32 = 28;
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const SECONDS: Field = Field {
+    name: "seconds",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const MINUTES: Field = Field {
+    name: "minutes",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOURS: Field = Field {
+    name: "hours",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAYS_OF_MONTH: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTHS: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+// Both 0 and 7 stand for Sunday.
+const DAYS_OF_WEEK: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+/// A parsed cron expression.
+///
+/// Both the standard five fields (minutes, hours, day of month, month, day of week) and six
+/// fields, with leading seconds, are accepted. Each field is a comma separated list of values,
+/// `a-b` ranges or `*`, optionally followed by a `/n` step. Months and days of week can also be
+/// given by their three letter English names.
+///
+/// As with the traditional cron, when both the day of month and the day of week are restricted, a
+/// day matching either of them matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_either: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (seconds, fields) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            count => return Err(format!("expected 5 or 6 fields, found {}", count)),
+        };
+
+        let mut days_of_week = parse_field(fields[4], &DAYS_OF_WEEK)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            seconds: parse_field(seconds, &SECONDS)?,
+            minutes: parse_field(fields[0], &MINUTES)?,
+            hours: parse_field(fields[1], &HOURS)?,
+            days_of_month: parse_field(fields[2], &DAYS_OF_MONTH)?,
+            months: parse_field(fields[3], &MONTHS)?,
+            days_of_week,
+            days_either: is_restricted(fields[2]) && is_restricted(fields[4]),
+        })
+    }
+}
+
+fn is_restricted(field: &str) -> bool {
+    !field.starts_with(['*', '?'])
+}
+
+fn parse_field(spec: &str, field: &Field) -> Result<u64, String> {
+    let mut bits = 0;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step `{}` in the {}", step, field.name)),
+            },
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" || range == "?" {
+            (field.min, field.max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, field)?, parse_value(end, field)?)
+        } else {
+            let value = parse_value(range, field)?;
+            // A single value with a step, like `5/15`, runs until the end of the range.
+            (value, if step.is_some() { field.max } else { value })
+        };
+        if start > end {
+            return Err(format!("invalid range `{}` in the {}", range, field.name));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, field: &Field) -> Result<u32, String> {
+    let parsed = match value.parse::<u32>() {
+        Ok(number) => Some(number),
+        Err(_) => field
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+            .map(|index| index as u32 + field.min),
+    };
+    parsed
+        .filter(|number| (field.min..=field.max).contains(number))
+        .ok_or_else(|| format!("invalid value `{}` in the {}", value, field.name))
+}
+
+const fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+impl CronSchedule {
+    /// Returns the first instant strictly after `after` matching the schedule in the time zone,
+    /// if any.
+    ///
+    /// Local times skipped by a daylight saving time transition don't match, and local times
+    /// repeated by one only match once.
+    pub(super) fn next_after(
+        &self,
+        after: DateTime<Utc>,
+        timezone: TimeZone,
+    ) -> Option<DateTime<Utc>> {
+        match timezone {
+            TimeZone::Local => self.next_in(&Local, after),
+            TimeZone::Named(tz) => self.next_in(&tz, after),
+        }
+    }
+
+    fn next_in<Z: chrono::TimeZone>(
+        &self,
+        zone: &Z,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let mut start =
+            after.with_timezone(zone).naive_local().with_nanosecond(0)? + Duration::seconds(1);
+        let limit = start.year() + SEARCH_YEARS;
+        while let Some(candidate) = self.next_local(start, limit) {
+            let time = match zone.from_local_datetime(&candidate) {
+                LocalResult::Single(time) => Some(time.with_timezone(&Utc)),
+                LocalResult::Ambiguous(earliest, latest) => {
+                    let earliest = earliest.with_timezone(&Utc);
+                    Some(if earliest > after {
+                        earliest
+                    } else {
+                        latest.with_timezone(&Utc)
+                    })
+                }
+                LocalResult::None => None,
+            };
+            match time {
+                Some(time) if time > after => return Some(time),
+                _ => start = candidate + Duration::seconds(1),
+            }
+        }
+        None
+    }
+
+    /// Returns the first local time from `start` on matching the schedule, up to the end of the
+    /// `limit` year.
+    fn next_local(&self, mut time: NaiveDateTime, limit: i32) -> Option<NaiveDateTime> {
+        while time.year() <= limit {
+            if !contains(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !contains(self.hours, time.hour()) {
+                time = time.date().and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time =
+                    time.date().and_hms_opt(time.hour(), time.minute(), 0)? + Duration::minutes(1);
+            } else if !contains(self.seconds, time.second()) {
+                time += Duration::seconds(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.days_either {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time).unwrap().into()
+    }
+
+    fn next(expression: &str, after: &str) -> Option<DateTime<Utc>> {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(utc(after), TimeZone::Named(chrono_tz::UTC))
+    }
+
+    #[test]
+    fn parses_expressions() {
+        for expression in [
+            "* * * * *",
+            "5 * * * *",
+            "*/15 0-6,18 1 jan,JUL ?",
+            "0 0 * * 7",
+            "30 */10 * * * MON-FRI",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_ok(), "{}", expression);
+        }
+        assert_eq!(
+            "0 0 * * 7".parse::<CronSchedule>(),
+            "0 0 * * 0".parse::<CronSchedule>()
+        );
+
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+            "* * * * 8",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn computes_next_instants() {
+        let cases = [
+            ("5 * * * *", "2024-01-01T10:07:00Z", "2024-01-01T11:05:00Z"),
+            ("5 * * * *", "2024-01-01T11:05:00Z", "2024-01-01T12:05:00Z"),
+            ("5 * * * *", "2024-12-31T23:59:30Z", "2025-01-01T00:05:00Z"),
+            (
+                "*/15 * * * * *",
+                "2024-01-01T10:00:07Z",
+                "2024-01-01T10:00:15Z",
+            ),
+            (
+                "0 9 * * MON-FRI",
+                "2024-01-06T12:00:00Z",
+                "2024-01-08T09:00:00Z",
+            ),
+            // Either the 13th or a Friday.
+            (
+                "0 0 13 * FRI",
+                "2024-01-01T00:00:00Z",
+                "2024-01-05T00:00:00Z",
+            ),
+            ("0 0 29 2 *", "2024-03-01T00:00:00Z", "2028-02-29T00:00:00Z"),
+        ];
+        for (expression, after, expected) in cases {
+            assert_eq!(
+                next(expression, after),
+                Some(utc(expected)),
+                "{} after {}",
+                expression,
+                after
+            );
+        }
+
+        assert_eq!(next("0 0 30 2 *", "2024-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn follows_time_zone() {
+        let schedule = "30 2 * * *".parse::<CronSchedule>().unwrap();
+        let new_york = TimeZone::Named(chrono_tz::America::New_York);
+
+        assert_eq!(
+            schedule.next_after(utc("2024-01-01T00:00:00Z"), new_york),
+            Some(utc("2024-01-01T07:30:00Z"))
+        );
+        // 02:30 is skipped when the clocks are put forward on March 10th.
+        assert_eq!(
+            schedule.next_after(utc("2024-03-10T05:00:00Z"), new_york),
+            Some(utc("2024-03-11T06:30:00Z"))
+        );
+
+        // 01:30 is repeated when the clocks are put back on November 3rd, and only matches once.
+        let schedule = "30 1 * * *".parse::<CronSchedule>().unwrap();
+        assert_eq!(
+            schedule.next_after(utc("2024-11-03T04:00:00Z"), new_york),
+            Some(utc("2024-11-03T05:30:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(utc("2024-11-03T05:30:00Z"), new_york),
+            Some(utc("2024-11-04T06:30:00Z"))
+        );
+    }
+}
@@ -7,12 +7,14 @@ use std::{
 
 use chrono::Utc;
 use futures::StreamExt;
+use ordered_float::NotNan;
 use smallvec::SmallVec;
 use snafu::Snafu;
 use tokio::{
     io::{AsyncRead, BufReader},
     process::Command,
     sync::mpsc::{channel, Sender},
+    task::JoinHandle,
     time::{self, sleep, Duration, Instant},
 };
 use tokio_stream::wrappers::IntervalStream;
@@ -23,17 +25,18 @@ use vector_lib::codecs::{
 };
 use vector_lib::configurable::configurable_component;
 use vector_lib::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
-use vector_lib::{config::LegacyKey, EstimatedJsonEncodedSizeOf};
+use vector_lib::{config::LegacyKey, EstimatedJsonEncodedSizeOf, TimeZone};
 use vrl::path::OwnedValuePath;
-use vrl::value::Kind;
+use vrl::value::{Kind, ObjectMap};
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::{SourceConfig, SourceContext, SourceOutput},
-    event::Event,
+    event::{Event, LogEvent, Value},
     internal_events::{
         ExecChannelClosedError, ExecCommandExecuted, ExecEventsReceived, ExecFailedError,
-        ExecFailedToSignalChild, ExecFailedToSignalChildError, ExecTimeoutError, StreamClosedError,
+        ExecFailedToSignalChild, ExecFailedToSignalChildError, ExecScheduledRunSkipped,
+        ExecTimeoutError, StreamClosedError,
     },
     serde::default_decoding,
     shutdown::ShutdownSignal,
@@ -42,9 +45,12 @@ use crate::{
 use vector_lib::config::{log_schema, LogNamespace};
 use vector_lib::lookup::{owned_value_path, path};
 
+mod cron;
 #[cfg(test)]
 mod tests;
 
+use cron::CronSchedule;
+
 /// Configuration for the `exec` source.
 #[configurable_component(source("exec", "Collect output from a process running on the host."))]
 #[derive(Clone, Debug)]
@@ -78,9 +84,21 @@ pub struct ExecConfig {
     pub working_directory: Option<PathBuf>,
 
     /// Whether or not the output from stderr should be included when generating events.
+    ///
+    /// The stream the output was read from, `stdout` or `stderr`, is set in the `stream` field of
+    /// the events.
     #[serde(default = "default_include_stderr")]
     pub include_stderr: bool,
 
+    /// Whether or not to emit an event with the exit status of the command when it exits.
+    ///
+    /// The event is emitted after all of the output of the command, and holds the `exit_status`
+    /// of the command, the `signal` it was killed by, if any, and its execution time in
+    /// `duration_seconds`. In scheduled mode this records the outcome of every run, and in
+    /// streaming mode it tells when the command exited.
+    #[serde(default)]
+    pub emit_exit_status: bool,
+
     /// The maximum buffer size allowed before a log event is generated.
     #[serde(default = "default_maximum_buffer_size")]
     pub maximum_buffer_size_bytes: usize,
@@ -118,8 +136,32 @@ pub struct ScheduledConfig {
     /// The interval, in seconds, between scheduled command runs.
     ///
     /// If the command takes longer than `exec_interval_secs` to run, it is killed.
-    #[serde(default = "default_exec_interval_secs")]
-    exec_interval_secs: u64,
+    ///
+    /// Defaults to 60 seconds, unless `schedule` is set. Can't be set together with `schedule`.
+    exec_interval_secs: Option<u64>,
+
+    #[configurable(derived)]
+    schedule: Option<CronScheduleConfig>,
+}
+
+/// Configuration for running scheduled commands at the instants matching a cron expression.
+///
+/// Unlike with `exec_interval_secs`, the runs don't drift over time. A command still running when
+/// the next run is due isn't killed, and the next run is skipped instead.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CronScheduleConfig {
+    /// The cron expression the command runs on.
+    ///
+    /// Both the standard five fields and six fields, with leading seconds, are supported.
+    #[configurable(metadata(docs::examples = "5 * * * *", docs::examples = "*/30 * * * * *"))]
+    cron: String,
+
+    /// The time zone the cron expression is evaluated in.
+    ///
+    /// Defaults to the global `timezone` option.
+    timezone: Option<TimeZone>,
 }
 
 /// Configuration options for streaming commands.
@@ -143,6 +185,10 @@ pub enum ExecConfigError {
     CommandEmpty,
     #[snafu(display("The maximum buffer size must be greater than zero"))]
     ZeroBuffer,
+    #[snafu(display("Only one of exec_interval_secs and schedule can be set"))]
+    IntervalAndSchedule,
+    #[snafu(display("Invalid cron expression {:?}: {}", expression, reason))]
+    InvalidCron { expression: String, reason: String },
 }
 
 impl Default for ExecConfig {
@@ -150,7 +196,8 @@ impl Default for ExecConfig {
         ExecConfig {
             mode: Mode::Scheduled,
             scheduled: Some(ScheduledConfig {
-                exec_interval_secs: default_exec_interval_secs(),
+                exec_interval_secs: Some(default_exec_interval_secs()),
+                schedule: None,
             }),
             streaming: None,
             command: vec!["echo".to_owned(), "Hello World!".to_owned()],
@@ -158,6 +205,7 @@ impl Default for ExecConfig {
             clear_environment: default_clear_environment(),
             working_directory: None,
             include_stderr: default_include_stderr(),
+            emit_exit_status: false,
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: None,
             decoding: default_decoding(),
@@ -208,6 +256,9 @@ const STDERR: &str = "stderr";
 const STREAM_KEY: &str = "stream";
 const PID_KEY: &str = "pid";
 const COMMAND_KEY: &str = "command";
+const EXIT_STATUS_KEY: &str = "exit_status";
+const SIGNAL_KEY: &str = "signal";
+const DURATION_KEY: &str = "duration_seconds";
 
 impl_generate_config_from_default!(ExecConfig);
 
@@ -217,19 +268,45 @@ impl ExecConfig {
             Err(ExecConfigError::CommandEmpty)
         } else if self.maximum_buffer_size_bytes == 0 {
             Err(ExecConfigError::ZeroBuffer)
+        } else if self.scheduled.as_ref().map_or(false, |config| {
+            config.exec_interval_secs.is_some() && config.schedule.is_some()
+        }) {
+            Err(ExecConfigError::IntervalAndSchedule)
         } else {
-            Ok(())
+            self.cron_schedule().map(|_| ())
         }
     }
 
+    /// Parses the cron expression of the schedule, if set, along with its time zone.
+    fn cron_schedule(&self) -> Result<Option<(CronSchedule, Option<TimeZone>)>, ExecConfigError> {
+        let Some(schedule) = self
+            .scheduled
+            .as_ref()
+            .and_then(|config| config.schedule.as_ref())
+        else {
+            return Ok(None);
+        };
+        schedule
+            .cron
+            .parse()
+            .map(|cron| Some((cron, schedule.timezone)))
+            .map_err(|reason| ExecConfigError::InvalidCron {
+                expression: schedule.cron.clone(),
+                reason,
+            })
+    }
+
     fn command_line(&self) -> String {
         self.command.join(" ")
     }
 
     const fn exec_interval_secs_or_default(&self) -> u64 {
         match &self.scheduled {
-            None => default_exec_interval_secs(),
-            Some(config) => config.exec_interval_secs,
+            Some(ScheduledConfig {
+                exec_interval_secs: Some(exec_interval_secs),
+                ..
+            }) => *exec_interval_secs,
+            _ => default_exec_interval_secs(),
         }
     }
 
@@ -265,6 +342,19 @@ impl SourceConfig for ExecConfig {
 
         match &self.mode {
             Mode::Scheduled => {
+                if let Some((schedule, timezone)) = self.cron_schedule()? {
+                    return Ok(Box::pin(run_cron_scheduled(
+                        self.clone(),
+                        hostname,
+                        schedule,
+                        timezone.unwrap_or_else(|| cx.globals.timezone()),
+                        decoder,
+                        cx.shutdown,
+                        cx.out,
+                        log_namespace,
+                    )));
+                }
+
                 let exec_interval_secs = self.exec_interval_secs_or_default();
 
                 Ok(Box::pin(run_scheduled(
@@ -333,6 +423,27 @@ impl SourceConfig for ExecConfig {
                 &owned_value_path!(COMMAND_KEY),
                 Kind::bytes(),
                 None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(EXIT_STATUS_KEY))),
+                &owned_value_path!(EXIT_STATUS_KEY),
+                Kind::integer().or_null().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(SIGNAL_KEY))),
+                &owned_value_path!(SIGNAL_KEY),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!(DURATION_KEY))),
+                &owned_value_path!(DURATION_KEY),
+                Kind::float().or_undefined(),
+                None,
             );
 
         vec![SourceOutput::new_logs(
@@ -398,6 +509,71 @@ async fn run_scheduled(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn run_cron_scheduled(
+    config: ExecConfig,
+    hostname: Option<String>,
+    schedule: CronSchedule,
+    timezone: TimeZone,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    out: SourceSender,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    debug!("Starting cron scheduled exec runs.");
+    let mut last_run = Utc::now();
+    let mut running: Option<JoinHandle<()>> = None;
+
+    // Runs missed while the host was suspended aren't caught up on.
+    while let Some(next_run) = schedule.next_after(last_run.max(Utc::now()), timezone) {
+        debug!(message = "Scheduled next command run.", next_run = %next_run);
+
+        let delay = (next_run - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = sleep(delay) => {},
+        }
+        // The wall clock may have been set back while sleeping.
+        if Utc::now() < next_run {
+            continue;
+        }
+        last_run = next_run;
+
+        if running.as_ref().map_or(false, |run| !run.is_finished()) {
+            emit!(ExecScheduledRunSkipped {
+                command: config.command_line().as_str(),
+            });
+            continue;
+        }
+
+        let command = config.command_line();
+        let run = run_command(
+            config.clone(),
+            hostname.clone(),
+            decoder.clone(),
+            shutdown.clone(),
+            out.clone(),
+            log_namespace,
+        );
+        running = Some(tokio::spawn(async move {
+            if let Err(command_error) = run.await {
+                emit!(ExecFailedError {
+                    command: command.as_str(),
+                    error: command_error,
+                });
+            }
+        }));
+    }
+
+    // The running command is signaled to shut down, wait for its remaining output.
+    if let Some(run) = running {
+        _ = run.await;
+    }
+
+    debug!("Finished cron scheduled exec runs.");
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_streaming(
     config: ExecConfig,
@@ -538,28 +714,90 @@ async fn run_command(
         }
     }
 
+    // The output may be closed before the process exits, wait for it if its exit status is
+    // emitted.
+    let exit_status = match child.try_wait() {
+        Ok(None) if config.emit_exit_status => child.wait().await.map(Some),
+        exit_status => exit_status,
+    };
+
     let elapsed = start.elapsed();
 
-    let result = match child.try_wait() {
-        Ok(Some(exit_status)) => {
-            handle_exit_status(&config, exit_status.code(), elapsed);
-            Ok(Some(exit_status))
-        }
-        Ok(None) => {
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
-        }
+    let exit_status = match exit_status {
+        Ok(exit_status) => exit_status,
         Err(error) => {
             error!(message = "Unable to obtain exit status.", %error);
-
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
+            None
         }
     };
+    handle_exit_status(
+        &config,
+        exit_status.and_then(|exit_status| exit_status.code()),
+        elapsed,
+    );
+
+    if config.emit_exit_status {
+        let mut event = exit_status_event(exit_status, elapsed, log_namespace);
+        handle_event(&config, &hostname, &None, pid, &mut event, log_namespace);
+        if out.send_event(event).await.is_err() {
+            emit!(StreamClosedError { count: 1 });
+        }
+    }
 
     debug!("Finished command run.");
 
-    result
+    Ok(exit_status)
+}
+
+/// Builds the event reporting how the command exited, after all of its output.
+fn exit_status_event(
+    exit_status: Option<ExitStatus>,
+    elapsed: Duration,
+    log_namespace: LogNamespace,
+) -> Event {
+    let mut log = LogEvent::from(ObjectMap::new());
+
+    log_namespace.insert_source_metadata(
+        ExecConfig::NAME,
+        &mut log,
+        Some(LegacyKey::InsertIfEmpty(path!(EXIT_STATUS_KEY))),
+        path!(EXIT_STATUS_KEY),
+        exit_status
+            .and_then(|exit_status| exit_status.code())
+            .map_or(Value::Null, |code| Value::from(code as i64)),
+    );
+
+    if let Some(signal) = exit_status.and_then(exit_signal) {
+        log_namespace.insert_source_metadata(
+            ExecConfig::NAME,
+            &mut log,
+            Some(LegacyKey::InsertIfEmpty(path!(SIGNAL_KEY))),
+            path!(SIGNAL_KEY),
+            signal as i64,
+        );
+    }
+
+    log_namespace.insert_source_metadata(
+        ExecConfig::NAME,
+        &mut log,
+        Some(LegacyKey::InsertIfEmpty(path!(DURATION_KEY))),
+        path!(DURATION_KEY),
+        Value::Float(NotNan::new(elapsed.as_secs_f64()).expect("duration cannot be NaN")),
+    );
+
+    log.into()
+}
+
+#[cfg(unix)]
+fn exit_signal(exit_status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    exit_status.signal()
+}
+
+#[cfg(windows)]
+const fn exit_signal(_exit_status: ExitStatus) -> Option<i32> {
+    None
 }
 
 fn handle_exit_status(config: &ExecConfig, exit_status: Option<i32>, exec_duration: Duration) {
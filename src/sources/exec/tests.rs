@@ -14,6 +14,54 @@ fn test_generate_config() {
     crate::test_util::test_generate_config::<ExecConfig>();
 }
 
+#[test]
+fn test_scheduled_cron_config() {
+    let config: ExecConfig = toml::from_str(
+        r#"
+        mode = "scheduled"
+        command = ["./billing-export.sh"]
+
+        [scheduled.schedule]
+        cron = "5 * * * *"
+        timezone = "America/New_York"
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    let (_, timezone) = config.cron_schedule().unwrap().unwrap();
+    assert_eq!(
+        timezone,
+        Some(TimeZone::Named(chrono_tz::America::New_York))
+    );
+
+    let config: ExecConfig = toml::from_str(
+        r#"
+        mode = "scheduled"
+        command = ["./billing-export.sh"]
+
+        [scheduled]
+        exec_interval_secs = 3600
+        schedule.cron = "5 * * * *"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.validate(), Err(ExecConfigError::IntervalAndSchedule));
+
+    let config: ExecConfig = toml::from_str(
+        r#"
+        mode = "scheduled"
+        command = ["./billing-export.sh"]
+        scheduled.schedule.cron = "5 * * *"
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        config.validate(),
+        Err(ExecConfigError::InvalidCron { .. })
+    ));
+    assert_eq!(config.exec_interval_secs_or_default(), 60);
+}
+
 #[test]
 fn test_scheduled_handle_event() {
     let config = standard_scheduled_test_config();
@@ -180,6 +228,7 @@ fn test_build_command() {
         clear_environment: default_clear_environment(),
         working_directory: Some(PathBuf::from("/tmp")),
         include_stderr: default_include_stderr(),
+        emit_exit_status: false,
         maximum_buffer_size_bytes: default_maximum_buffer_size(),
         framing: None,
         decoding: default_decoding(),
@@ -214,6 +263,7 @@ fn test_build_command_custom_environment() {
         clear_environment: default_clear_environment(),
         working_directory: Some(PathBuf::from("/tmp")),
         include_stderr: default_include_stderr(),
+        emit_exit_status: false,
         maximum_buffer_size_bytes: default_maximum_buffer_size(),
         framing: None,
         decoding: default_decoding(),
@@ -244,6 +294,7 @@ fn test_build_command_clear_environment() {
         clear_environment: true,
         working_directory: Some(PathBuf::from("/tmp")),
         include_stderr: default_include_stderr(),
+        emit_exit_status: false,
         maximum_buffer_size_bytes: default_maximum_buffer_size(),
         framing: None,
         decoding: default_decoding(),
@@ -379,6 +430,105 @@ async fn test_run_command_linux() {
     }
 }
 
+#[tokio::test]
+#[cfg(unix)]
+async fn test_run_command_exit_status() {
+    let mut config = standard_scheduled_test_config();
+    config.command = vec![
+        String::from("bash"),
+        String::from("-c"),
+        String::from("echo out ; echo err >&2 ; exit 3"),
+    ];
+    config.emit_exit_status = true;
+    let hostname = Some("Some.Machine".to_string());
+    let decoder = Default::default();
+    let shutdown = ShutdownSignal::noop();
+    let (tx, rx) = SourceSender::new_test();
+
+    let exit_status = tokio::time::timeout(
+        time::Duration::from_secs(5),
+        run_command(
+            config.clone(),
+            hostname,
+            decoder,
+            shutdown,
+            tx,
+            LogNamespace::Legacy,
+        ),
+    )
+    .await
+    .expect("command timed out")
+    .expect("command error");
+    assert_eq!(3_i32, exit_status.unwrap().code().unwrap());
+
+    let events: Vec<_> = rx.collect().await;
+    assert_eq!(events.len(), 3);
+
+    // The output of the streams may be interleaved, but the exit status comes last.
+    let mut lines: Vec<_> = events[..2]
+        .iter()
+        .map(|event| {
+            let log = event.as_log();
+            (log[STREAM_KEY].clone(), log.get_message().unwrap().clone())
+        })
+        .collect();
+    lines.sort_by_key(|(stream, _)| stream.to_string_lossy().into_owned());
+    assert_eq!(
+        lines,
+        vec![(STDERR.into(), "err".into()), (STDOUT.into(), "out".into())]
+    );
+
+    let log = events[2].as_log();
+    assert_eq!(log[EXIT_STATUS_KEY], 3_i64.into());
+    assert!(log.get(SIGNAL_KEY).is_none());
+    assert!(log[DURATION_KEY].is_float());
+    assert!(log.get(STREAM_KEY).is_none());
+    assert!(log.get_message().is_none());
+    assert_eq!(log[COMMAND_KEY], config.command.clone().into());
+    assert!(log.get(PID_KEY).is_some());
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_run_command_exit_signal() {
+    let mut config = standard_scheduled_test_config();
+    config.command = vec![
+        String::from("bash"),
+        String::from("-c"),
+        String::from("kill -KILL $$"),
+    ];
+    config.emit_exit_status = true;
+    let (tx, rx) = SourceSender::new_test();
+
+    let exit_status = tokio::time::timeout(
+        time::Duration::from_secs(5),
+        run_command(
+            config,
+            None,
+            Default::default(),
+            ShutdownSignal::noop(),
+            tx,
+            LogNamespace::Vector,
+        ),
+    )
+    .await
+    .expect("command timed out")
+    .expect("command error");
+    assert_eq!(exit_status.unwrap().code(), None);
+
+    let events: Vec<_> = rx.collect().await;
+    assert_eq!(events.len(), 1);
+    let meta = events[0].metadata().value();
+    assert_eq!(
+        meta.get(path!(ExecConfig::NAME, EXIT_STATUS_KEY)).unwrap(),
+        &value!(null)
+    );
+    assert_eq!(
+        meta.get(path!(ExecConfig::NAME, SIGNAL_KEY)).unwrap(),
+        &value!(9_i64)
+    );
+}
+
 #[tokio::test]
 #[cfg(unix)]
 async fn test_graceful_shutdown() {
@@ -452,6 +602,7 @@ fn standard_streaming_test_config() -> ExecConfig {
         clear_environment: default_clear_environment(),
         working_directory: None,
         include_stderr: default_include_stderr(),
+        emit_exit_status: false,
         maximum_buffer_size_bytes: default_maximum_buffer_size(),
         framing: None,
         decoding: default_decoding(),
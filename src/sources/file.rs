@@ -13,8 +13,8 @@ use vector_lib::configurable::configurable_component;
 use vector_lib::file_source::{
     calculate_ignore_before,
     paths_provider::glob::{Glob, MatchOptions},
-    Checkpointer, FileFingerprint, FileServer, FingerprintStrategy, Fingerprinter, Line, ReadFrom,
-    ReadFromConfig,
+    Checkpointer, Compression, FileFingerprint, FileServer, FingerprintStrategy, Fingerprinter,
+    Line, ReadFrom, ReadFromConfig,
 };
 use vector_lib::finalizer::OrderedFinalizer;
 use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path, OwnedValuePath};
@@ -117,6 +117,10 @@ pub struct FileConfig {
     #[configurable(derived)]
     pub read_from: ReadFromConfig,
 
+    #[serde(default)]
+    #[configurable(derived)]
+    pub compression: Compression,
+
     /// Ignore files with a data modification date older than the specified number of seconds.
     #[serde(alias = "ignore_older", default)]
     #[configurable(metadata(docs::type_unit = "seconds"))]
@@ -155,13 +159,24 @@ pub struct FileConfig {
 
     /// Enables adding the file offset to each event and sets the name of the log field used.
     ///
-    /// The value is the byte offset of the start of the line within the file.
+    /// The value is the byte offset of the start of the line within the file. For lines aggregated
+    /// by `multiline`, it's the offset of their first line.
     ///
     /// Off by default, the offset is only added to the event if this is set.
     #[serde(default)]
     #[configurable(metadata(docs::examples = "offset"))]
     pub offset_key: Option<OptionalValuePath>,
 
+    /// Enables adding the file size to each event and sets the name of the log field used.
+    ///
+    /// The value is the size of the file in bytes once the line was read. It's not added for
+    /// compressed files.
+    ///
+    /// Off by default, the file size is only added to the event if this is set.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "file_size"))]
+    pub file_size_key: Option<OptionalValuePath>,
+
     /// The delay between file discovery calls.
     ///
     /// This controls the interval at which files are searched. A higher value results in greater
@@ -214,6 +229,17 @@ pub struct FileConfig {
     #[configurable(metadata(docs::type_unit = "bytes"))]
     pub max_read_bytes: usize,
 
+    /// The maximum number of bytes to read from a single file per second.
+    ///
+    /// Each file gets its own budget, so that a file being written to very quickly can't starve
+    /// the reads of the other files. Lines are never split to stay within the budget, so a file
+    /// may exceed it by up to one line, which is made up for before the file is read again.
+    ///
+    /// By default, the reads are not rate limited.
+    #[serde(default)]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_read_bytes_per_second: Option<usize>,
+
     /// Instead of balancing read capacity fairly across all watched files, prioritize draining the oldest files before moving on to read data from more recent files.
     #[serde(default)]
     pub oldest_first: bool,
@@ -229,6 +255,17 @@ pub struct FileConfig {
     #[configurable(metadata(docs::human_name = "Wait Time Before Removing File"))]
     pub remove_after_secs: Option<u64>,
 
+    /// The directory to move files to instead of deleting them once `remove_after_secs` elapsed.
+    ///
+    /// Files keep their path relative to the root within the directory. They are renamed when
+    /// possible, and otherwise copied and removed, like when the directory is on another
+    /// filesystem. Files within the directory are never read, even if they match `include`.
+    ///
+    /// If not specified, files are deleted.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/log/archive"))]
+    pub archive_to_dir: Option<PathBuf>,
+
     /// String sequence used to separate one file line from another.
     #[serde(default = "default_line_delimiter")]
     #[configurable(metadata(docs::examples = "\r\n"))]
@@ -323,8 +360,17 @@ pub enum FingerprintConfig {
 
     /// Use the [device and inode][inode] as the identifier.
     ///
+    /// Files renamed by a rotation keep their device and inode, so they continue to be read from
+    /// their checkpoint under the new name. Files truncated in place, like by a copy-truncate
+    /// rotation, hold a new stream of lines on the same inode, so they are read from the
+    /// beginning again once they are found to be shorter than the offset read up to. The same
+    /// check catches an inode reused by a new file, as long as the new file is shorter than the
+    /// checkpoint of the removed one.
+    ///
+    /// This strategy can also be configured as `inode`.
+    ///
     /// [inode]: https://en.wikipedia.org/wiki/Inode
-    #[serde(rename = "device_and_inode")]
+    #[serde(rename = "device_and_inode", alias = "inode")]
     DevInode,
 }
 
@@ -383,20 +429,24 @@ impl Default for FileConfig {
             start_at_beginning: None,
             ignore_checkpoints: None,
             read_from: default_read_from(),
+            compression: Compression::default(),
             ignore_older_secs: None,
             max_line_bytes: default_max_line_bytes(),
             fingerprint: FingerprintConfig::default(),
             ignore_not_found: false,
             host_key: default_host_key(),
             offset_key: None,
+            file_size_key: None,
             data_dir: None,
             glob_minimum_cooldown_ms: default_glob_minimum_cooldown_ms(),
             message_start_indicator: None,
             multi_line_timeout: default_multi_line_timeout(), // millis
             multiline: None,
             max_read_bytes: default_max_read_bytes(),
+            max_read_bytes_per_second: None,
             oldest_first: false,
             remove_after_secs: None,
+            archive_to_dir: None,
             line_delimiter: default_line_delimiter(),
             encoding: None,
             acknowledgements: Default::default(),
@@ -458,6 +508,12 @@ impl SourceConfig for FileConfig {
             .and_then(|k| k.path)
             .map(LegacyKey::Overwrite);
 
+        let file_size_key = self
+            .file_size_key
+            .clone()
+            .and_then(|k| k.path)
+            .map(LegacyKey::Overwrite);
+
         let schema_definition = BytesDeserializerConfig
             .schema_definition(global_log_namespace.merge(self.log_namespace))
             .with_standard_vector_source_metadata()
@@ -475,6 +531,13 @@ impl SourceConfig for FileConfig {
                 Kind::integer(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                file_size_key,
+                &owned_value_path!("file_size"),
+                Kind::integer().or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 file_key,
@@ -538,20 +601,26 @@ pub fn file_source(
     let file_server = FileServer {
         paths_provider,
         max_read_bytes: config.max_read_bytes,
+        max_read_bytes_per_file: None,
+        max_read_bytes_per_second: config.max_read_bytes_per_second,
         ignore_checkpoints,
         read_from,
         ignore_before,
         max_line_bytes: config.max_line_bytes,
+        truncate_long_lines: false,
         line_delimiter: line_delimiter_as_bytes,
+        compression: config.compression,
         data_dir,
         glob_minimum_cooldown,
         fingerprinter: Fingerprinter {
             strategy: config.fingerprint.clone().into(),
             max_line_length: config.max_line_bytes,
             ignore_not_found: config.ignore_not_found,
+            compression: config.compression,
         },
         oldest_first: config.oldest_first,
         remove_after: config.remove_after_secs.map(Duration::from_secs),
+        archive_to_dir: config.archive_to_dir.clone(),
         emitter,
         handle: tokio::runtime::Handle::current(),
     };
@@ -561,11 +630,15 @@ pub fn file_source(
         hostname: crate::get_hostname().ok(),
         file_key: config.file_key.clone().path,
         offset_key: config.offset_key.clone().and_then(|k| k.path),
+        file_size_key: config.file_size_key.clone().and_then(|k| k.path),
     };
 
     let include = config.include.clone();
     let exclude = config.exclude.clone();
-    let multiline_config = config.multiline.clone();
+    let multiline_config: Option<line_agg::Config> = config
+        .multiline
+        .as_ref()
+        .map(|config| config.try_into().unwrap()); // validated in build
     let message_start_indicator = config.message_start_indicator.clone();
     let multi_line_timeout = config.multi_line_timeout;
 
@@ -623,11 +696,8 @@ pub fn file_source(
             });
 
         let messages: Box<dyn Stream<Item = Line> + Send + std::marker::Unpin> =
-            if let Some(ref multiline_config) = multiline_config {
-                wrap_with_line_agg(
-                    rx,
-                    multiline_config.try_into().unwrap(), // validated in build
-                )
+            if let Some(multiline_config) = multiline_config {
+                wrap_with_line_agg(rx, multiline_config)
             } else if let Some(msi) = message_start_indicator {
                 wrap_with_line_agg(
                     rx,
@@ -647,6 +717,7 @@ pub fn file_source(
             let mut event = create_event(
                 line.text,
                 line.start_offset,
+                line.file_size,
                 &line.filename,
                 &event_metadata,
                 log_namespace,
@@ -724,25 +795,40 @@ fn wrap_with_line_agg(
     rx: impl Stream<Item = Line> + Send + std::marker::Unpin + 'static,
     config: line_agg::Config,
 ) -> Box<dyn Stream<Item = Line> + Send + std::marker::Unpin + 'static> {
-    let logic = line_agg::Logic::new(config);
+    let logic = line_agg::Logic::with_merge_context(
+        config,
+        |context: &mut (FileFingerprint, u64, u64, Option<u64>), next| {
+            // The aggregated lines start where their first line starts.
+            let start_offset = context.1;
+            *context = next;
+            context.1 = start_offset;
+        },
+    );
     Box::new(
         LineAgg::new(
             rx.map(|line| {
                 (
                     line.filename,
                     line.text,
-                    (line.file_id, line.start_offset, line.end_offset),
+                    (
+                        line.file_id,
+                        line.start_offset,
+                        line.end_offset,
+                        line.file_size,
+                    ),
                 )
             }),
             logic,
         )
         .map(
-            |(filename, text, (file_id, start_offset, end_offset))| Line {
+            |(filename, text, (file_id, start_offset, end_offset, file_size))| Line {
                 text,
                 filename,
                 file_id,
                 start_offset,
                 end_offset,
+                file_size,
+                truncated: false,
             },
         ),
     )
@@ -753,11 +839,13 @@ struct EventMetadata {
     hostname: Option<String>,
     file_key: Option<OwnedValuePath>,
     offset_key: Option<OwnedValuePath>,
+    file_size_key: Option<OwnedValuePath>,
 }
 
 fn create_event(
     line: Bytes,
     offset: u64,
+    file_size: Option<u64>,
     file: &str,
     meta: &EventMetadata,
     log_namespace: LogNamespace,
@@ -800,6 +888,17 @@ fn create_event(
         offset,
     );
 
+    if let Some(file_size) = file_size {
+        let legacy_file_size_key = meta.file_size_key.as_ref().map(LegacyKey::Overwrite);
+        log_namespace.insert_source_metadata(
+            FileConfig::NAME,
+            &mut event,
+            legacy_file_size_key,
+            path!("file_size"),
+            file_size,
+        );
+    }
+
     let legacy_file_key = meta.file_key.as_ref().map(LegacyKey::Overwrite);
     log_namespace.insert_source_metadata(
         FileConfig::NAME,
@@ -903,6 +1002,16 @@ mod tests {
         .unwrap();
         assert_eq!(config.fingerprint, FingerprintConfig::DevInode);
 
+        let config: FileConfig = toml::from_str(
+            r#"
+        include = [ "/var/log/**/*.log" ]
+        [fingerprint]
+        strategy = "inode"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.fingerprint, FingerprintConfig::DevInode);
+
         let config: FileConfig = toml::from_str(
             r#"
         include = [ "/var/log/**/*.log" ]
@@ -949,6 +1058,15 @@ mod tests {
         )
         .unwrap();
         assert_eq!(config.read_from, ReadFromConfig::End);
+
+        let config: FileConfig = toml::from_str(
+            r#"
+        include = [ "/var/log/**/*.log" ]
+        read_from = "checkpoint_or_end"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.read_from, ReadFromConfig::CheckpointOrEnd);
     }
 
     #[test]
@@ -1051,12 +1169,22 @@ mod tests {
             hostname: Some("Some.Machine".to_string()),
             file_key: Some(owned_value_path!("file")),
             offset_key: Some(owned_value_path!("offset")),
+            file_size_key: Some(owned_value_path!("file_size")),
         };
-        let log = create_event(line, offset, file, &meta, LogNamespace::Legacy, false);
+        let log = create_event(
+            line,
+            offset,
+            Some(12),
+            file,
+            &meta,
+            LogNamespace::Legacy,
+            false,
+        );
 
         assert_eq!(log["file"], "some_file.rs".into());
         assert_eq!(log["host"], "Some.Machine".into());
         assert_eq!(log["offset"], 0.into());
+        assert_eq!(log["file_size"], 12.into());
         assert_eq!(*log.get_message().unwrap(), "hello world".into());
         assert_eq!(*log.get_source_type().unwrap(), "file".into());
         assert!(log[log_schema().timestamp_key().unwrap().to_string()].is_timestamp());
@@ -1073,12 +1201,22 @@ mod tests {
             hostname: Some("Some.Machine".to_string()),
             file_key: Some(owned_value_path!("file_path")),
             offset_key: Some(owned_value_path!("off")),
+            file_size_key: Some(owned_value_path!("size")),
         };
-        let log = create_event(line, offset, file, &meta, LogNamespace::Legacy, false);
+        let log = create_event(
+            line,
+            offset,
+            Some(12),
+            file,
+            &meta,
+            LogNamespace::Legacy,
+            false,
+        );
 
         assert_eq!(log["file_path"], "some_file.rs".into());
         assert_eq!(log["hostname"], "Some.Machine".into());
         assert_eq!(log["off"], 0.into());
+        assert_eq!(log["size"], 12.into());
         assert_eq!(*log.get_message().unwrap(), "hello world".into());
         assert_eq!(*log.get_source_type().unwrap(), "file".into());
         assert!(log[log_schema().timestamp_key().unwrap().to_string()].is_timestamp());
@@ -1095,8 +1233,17 @@ mod tests {
             hostname: Some("Some.Machine".to_string()),
             file_key: Some(owned_value_path!("ignored")),
             offset_key: Some(owned_value_path!("ignored")),
+            file_size_key: Some(owned_value_path!("ignored")),
         };
-        let log = create_event(line, offset, file, &meta, LogNamespace::Vector, false);
+        let log = create_event(
+            line,
+            offset,
+            Some(12),
+            file,
+            &meta,
+            LogNamespace::Vector,
+            false,
+        );
 
         assert_eq!(log.value(), &value!("hello world"));
 
@@ -1128,6 +1275,13 @@ mod tests {
                 .unwrap(),
             &value!(0)
         );
+        assert_eq!(
+            log.metadata()
+                .value()
+                .get(path!(FileConfig::NAME, "file_size"))
+                .unwrap(),
+            &value!(12)
+        );
         assert_eq!(
             log.metadata()
                 .value()
@@ -1343,6 +1497,96 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn file_rotate_device_and_inode() {
+        let n = 5;
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            fingerprint: FingerprintConfig::DevInode,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let archive_path = dir.path().join("file.1");
+        let received = run_file_source(&config, false, NoAcks, LogNamespace::Legacy, async {
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at its original length before writing to it
+
+            for i in 0..n {
+                writeln!(&mut file, "prerot {}", i).unwrap();
+            }
+
+            sleep_500_millis().await; // The writes must be observed before rotating
+
+            // The renamed file keeps its inode, so it isn't read again under its new name.
+            fs::rename(&path, &archive_path).expect("could not rename");
+            writeln!(&mut file, "prerot {}", n).unwrap();
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The rotation must be observed before writing again
+
+            for i in 0..n {
+                writeln!(&mut file, "postrot {}", i).unwrap();
+            }
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let expected = (0..=n)
+            .map(|i| format!("prerot {}", i))
+            .chain((0..n).map(|i| format!("postrot {}", i)))
+            .collect::<Vec<_>>();
+        assert_eq!(extract_messages_string(received), expected);
+    }
+
+    #[tokio::test]
+    async fn file_copy_truncate_device_and_inode() {
+        let n = 5;
+
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("file")],
+            fingerprint: FingerprintConfig::DevInode,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let received = run_file_source(&config, false, NoAcks, LogNamespace::Legacy, async {
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at its original length before writing to it
+
+            for i in 0..n {
+                writeln!(&mut file, "pretrunc {}", i).unwrap();
+            }
+
+            sleep_500_millis().await; // The writes must be observed before truncating
+
+            fs::copy(&path, dir.path().join("file.1")).unwrap();
+            file.set_len(0).unwrap();
+            file.seek(std::io::SeekFrom::Start(0)).unwrap();
+
+            sleep_500_millis().await; // The truncate must be observed before writing again
+
+            for i in 0..n {
+                writeln!(&mut file, "posttrunc {}", i).unwrap();
+            }
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let expected = (0..n)
+            .map(|i| format!("pretrunc {}", i))
+            .chain((0..n).map(|i| format!("posttrunc {}", i)))
+            .collect::<Vec<_>>();
+        assert_eq!(extract_messages_string(received), expected);
+    }
+
     #[tokio::test]
     async fn file_multiple_paths() {
         let n = 5;
@@ -1572,6 +1816,155 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn file_offset_and_size_server_restart() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            offset_key: Some(OptionalValuePath::from(owned_value_path!("offset"))),
+            file_size_key: Some(OptionalValuePath::from(owned_value_path!("file_size"))),
+            ..test_default_file_config(&dir)
+        };
+
+        let offsets_and_sizes = |received: Vec<Event>| {
+            received
+                .into_iter()
+                .map(|event| {
+                    let log = event.into_log();
+                    (log["offset"].clone(), log["file_size"].clone())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "zeroth line").unwrap();
+        writeln!(&mut file, "first line").unwrap();
+        sleep_500_millis().await;
+
+        let received = run_file_source(&config, true, Acks, LogNamespace::Legacy, async {
+            sleep_500_millis().await;
+        })
+        .await;
+        assert_eq!(
+            offsets_and_sizes(received),
+            vec![(0.into(), 23.into()), (12.into(), 23.into())]
+        );
+
+        // After a restart, the offsets carry on from the checkpoint.
+        let received = run_file_source(&config, true, Acks, LogNamespace::Legacy, async {
+            sleep_500_millis().await;
+            writeln!(&mut file, "second line").unwrap();
+            sleep_500_millis().await;
+        })
+        .await;
+        assert_eq!(offsets_and_sizes(received), vec![(23.into(), 35.into())]);
+    }
+
+    #[tokio::test]
+    async fn file_start_position_checkpoint_or_end() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            read_from: ReadFromConfig::CheckpointOrEnd,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "historical line").unwrap();
+        sleep_500_millis().await;
+
+        // On the first startup, the existing file is read from the end, and a file discovered
+        // while running is read from the beginning.
+        {
+            let received = run_file_source(&config, true, NoAcks, LogNamespace::Legacy, async {
+                sleep_500_millis().await;
+                writeln!(&mut file, "first line").unwrap();
+                let mut new_file = File::create(dir.path().join("new_file")).unwrap();
+                writeln!(&mut new_file, "new file line").unwrap();
+                sleep_500_millis().await;
+            })
+            .await;
+
+            let mut lines = extract_messages_string(received);
+            lines.sort();
+            assert_eq!(lines, vec!["first line", "new file line"]);
+        }
+
+        // A file created while Vector was stopped has no checkpoint yet.
+        let mut unseen_file = File::create(dir.path().join("unseen_file")).unwrap();
+        writeln!(&mut unseen_file, "unseen file line").unwrap();
+        writeln!(&mut file, "second line").unwrap();
+        sleep_500_millis().await;
+
+        // On restart, the existing file resumes from its checkpoint, and the file without one is
+        // read from the beginning, as checkpoints exist now.
+        {
+            let received = run_file_source(
+                &config,
+                true,
+                NoAcks,
+                LogNamespace::Legacy,
+                sleep_500_millis(),
+            )
+            .await;
+
+            let mut lines = extract_messages_string(received);
+            lines.sort();
+            assert_eq!(lines, vec!["second line", "unseen file line"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn file_start_position_checkpoint_or_end_corrupt_checkpoints() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            read_from: ReadFromConfig::CheckpointOrEnd,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "first line").unwrap();
+        sleep_500_millis().await;
+
+        // A previous run persisted its checkpoints.
+        run_file_source(
+            &config,
+            true,
+            NoAcks,
+            LogNamespace::Legacy,
+            sleep_500_millis(),
+        )
+        .await;
+
+        // The checkpoints are corrupted while Vector is stopped.
+        let checkpoints_pattern = dir
+            .path()
+            .join("*")
+            .join(vector_lib::file_source::CHECKPOINT_FILE_NAME);
+        let checkpoints_paths: Vec<_> = glob::glob(checkpoints_pattern.to_str().unwrap())
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(checkpoints_paths.len(), 1);
+        std::fs::write(&checkpoints_paths[0], "{").unwrap();
+        writeln!(&mut file, "second line").unwrap();
+        sleep_500_millis().await;
+
+        // Without usable checkpoints, the existing file is read from the end, like on the first
+        // startup.
+        let received = run_file_source(&config, true, NoAcks, LogNamespace::Legacy, async {
+            sleep_500_millis().await;
+            writeln!(&mut file, "third line").unwrap();
+            sleep_500_millis().await;
+        })
+        .await;
+        assert_eq!(extract_messages_string(received), vec!["third line"]);
+    }
+
     #[tokio::test]
     async fn file_start_position_server_restart_unfinalized() {
         let dir = tempdir().unwrap();
@@ -1883,6 +2276,51 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_multi_line_aggregation_vrl_condition() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            multiline: Some(MultilineConfig {
+                start_pattern: "".to_owned(),
+                condition_pattern: None,
+                condition: Some("(parse_json(.message) ?? {}).timestamp == null".to_owned()),
+                mode: line_agg::Mode::ContinueThrough,
+                timeout_ms: Duration::from_millis(25), // less than 50 in sleep()
+            }),
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let received = run_file_source(&config, false, NoAcks, LogNamespace::Legacy, async {
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at their original lengths before writing to them
+
+            writeln!(&mut file, r#"{{"timestamp":1,"message":"hello"}}"#).unwrap();
+            writeln!(&mut file, r#"{{"message":"part of hello"}}"#).unwrap();
+            writeln!(&mut file, r#"{{"timestamp":2,"message":"goodbye"}}"#).unwrap();
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let received = extract_messages_value(received);
+
+        assert_eq!(
+            received,
+            vec![
+                concat!(
+                    r#"{"timestamp":1,"message":"hello"}"#,
+                    "\n",
+                    r#"{"message":"part of hello"}"#
+                )
+                .into(),
+                r#"{"timestamp":2,"message":"goodbye"}"#.into(),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_multi_line_aggregation() {
         let dir = tempdir().unwrap();
@@ -1890,7 +2328,8 @@ mod tests {
             include: vec![dir.path().join("*")],
             multiline: Some(MultilineConfig {
                 start_pattern: "INFO".to_owned(),
-                condition_pattern: "INFO".to_owned(),
+                condition_pattern: Some("INFO".to_owned()),
+                condition: None,
                 mode: line_agg::Mode::HaltBefore,
                 timeout_ms: Duration::from_millis(25), // less than 50 in sleep()
             }),
@@ -1949,7 +2388,8 @@ mod tests {
             include: vec![dir.path().join("*")],
             multiline: Some(MultilineConfig {
                 start_pattern: "INFO".to_owned(),
-                condition_pattern: "INFO".to_owned(),
+                condition_pattern: Some("INFO".to_owned()),
+                condition: None,
                 mode: line_agg::Mode::HaltBefore,
                 timeout_ms: Duration::from_millis(25), // less than 50 in sleep()
             }),
@@ -1984,6 +2424,45 @@ mod tests {
         assert_eq!(lines, vec!["INFO goodbye"]);
     }
 
+    #[tokio::test]
+    async fn test_multi_line_aggregation_offset() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            offset_key: Some(OptionalValuePath::from(owned_value_path!("offset"))),
+            multiline: Some(MultilineConfig {
+                start_pattern: "INFO".to_owned(),
+                condition_pattern: Some("INFO".to_owned()),
+                condition: None,
+                mode: line_agg::Mode::HaltBefore,
+                timeout_ms: Duration::from_millis(25), // less than 50 in sleep()
+            }),
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let mut file = File::create(&path).unwrap();
+        writeln!(&mut file, "INFO hello").unwrap();
+        writeln!(&mut file, "part of hello").unwrap();
+        writeln!(&mut file, "INFO goodbye").unwrap();
+
+        let received = run_file_source(
+            &config,
+            false,
+            Acks,
+            LogNamespace::Legacy,
+            sleep_500_millis(),
+        )
+        .await;
+
+        // The aggregated lines carry the offset of their first line.
+        let offsets = received
+            .into_iter()
+            .map(|event| event.into_log()["offset"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0.into(), 25.into()]);
+    }
+
     #[tokio::test]
     async fn test_fair_reads() {
         let dir = tempdir().unwrap();
@@ -2036,6 +2515,71 @@ mod tests {
         );
     }
 
+    #[cfg(target_os = "linux")] // see #7988
+    #[tokio::test]
+    async fn test_read_rate_limit() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            max_read_bytes_per_second: Some(100),
+            ..test_default_file_config(&dir)
+        };
+
+        let hot_lines = (0..100)
+            .map(|i| format!("hot line {:03}", i))
+            .collect::<Vec<_>>();
+        let mut hot = File::create(dir.path().join("a_hot_file")).unwrap();
+        for line in &hot_lines {
+            writeln!(&mut hot, "{}", line).unwrap();
+        }
+        let mut quiet = File::create(dir.path().join("b_quiet_file")).unwrap();
+        writeln!(&mut quiet, "quiet line").unwrap();
+
+        sleep_500_millis().await;
+
+        // The hot file is read at about 100 bytes per second, which doesn't hold up the quiet one.
+        let received = run_file_source(
+            &config,
+            true,
+            NoAcks,
+            LogNamespace::Legacy,
+            sleep_500_millis(),
+        )
+        .await;
+        let first_lines = extract_messages_string(received);
+        assert!(first_lines.contains(&"quiet line".to_owned()));
+        let first_hot_lines = first_lines
+            .into_iter()
+            .filter(|line| line.starts_with("hot"))
+            .collect::<Vec<_>>();
+        assert!(!first_hot_lines.is_empty());
+        assert!(first_hot_lines.len() < 20);
+
+        // The checkpoint of the throttled file advanced, so the rest of it is read after a
+        // restart, without reading any of it again.
+        let config = file::FileConfig {
+            max_read_bytes_per_second: None,
+            ..config
+        };
+        let received = run_file_source(
+            &config,
+            true,
+            NoAcks,
+            LogNamespace::Legacy,
+            sleep_500_millis(),
+        )
+        .await;
+        let rest_hot_lines = extract_messages_string(received);
+
+        assert_eq!(
+            first_hot_lines
+                .into_iter()
+                .chain(rest_hot_lines)
+                .collect::<Vec<_>>(),
+            hot_lines
+        );
+    }
+
     #[tokio::test]
     async fn test_oldest_first() {
         let dir = tempdir().unwrap();
@@ -2136,13 +2680,6 @@ mod tests {
         let dir = tempdir().unwrap();
         let config = file::FileConfig {
             include: vec![PathBuf::from("tests/data/gzipped.log")],
-            // TODO: remove this once files are fingerprinted after decompression
-            //
-            // Currently, this needs to be smaller than the total size of the compressed file
-            // because the fingerprinter tries to read until a newline, which it's not going to see
-            // in the compressed data, or this number of bytes. If it hits EOF before that, it
-            // can't return a fingerprint because the value would change once more data is written.
-            max_line_bytes: 100,
             ..test_default_file_config(&dir)
         };
 
@@ -2169,6 +2706,40 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_zstd_compressed_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("file.log.zst");
+        let contents = "this is a simple file\ni have been compressed\nwith zstd\n";
+        std::fs::write(&path, zstd::encode_all(contents.as_bytes(), 0).unwrap()).unwrap();
+
+        let config = file::FileConfig {
+            include: vec![path],
+            compression: Compression::Zstd,
+            ..test_default_file_config(&dir)
+        };
+
+        let received = run_file_source(
+            &config,
+            false,
+            NoAcks,
+            LogNamespace::Legacy,
+            sleep_500_millis(),
+        )
+        .await;
+
+        let received = extract_messages_value(received);
+
+        assert_eq!(
+            received,
+            vec![
+                "this is a simple file".into(),
+                "i have been compressed".into(),
+                "with zstd".into(),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_non_utf8_encoded_file() {
         let dir = tempdir().unwrap();
@@ -2280,6 +2851,63 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn archive_file() {
+        let n = 5;
+        let remove_after_secs = 1;
+
+        let dir = tempdir().unwrap();
+        let archive_dir = dir.path().join("archive");
+        let config = file::FileConfig {
+            // The archive directory matches the include pattern too.
+            include: vec![dir.path().join("**/*")],
+            remove_after_secs: Some(remove_after_secs),
+            archive_to_dir: Some(archive_dir.clone()),
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file");
+        let received = run_file_source(&config, false, Acks, LogNamespace::Legacy, async {
+            let mut file = File::create(&path).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at their original lengths before writing to them
+
+            for i in 0..n {
+                writeln!(&mut file, "{}", i).unwrap();
+            }
+            drop(file);
+
+            for _ in 0..10 {
+                // Wait for remove grace period to end.
+                sleep(Duration::from_secs(remove_after_secs + 1)).await;
+
+                if File::open(&path).is_err() {
+                    break;
+                }
+            }
+
+            // Give the source a chance to discover the archived file.
+            sleep_500_millis().await;
+        })
+        .await;
+
+        // The archived file isn't read again.
+        assert_eq!(received.len(), n);
+
+        match File::open(&path) {
+            Ok(_) => panic!("File wasn't archived"),
+            Err(error) => assert_eq!(error.kind(), std::io::ErrorKind::NotFound),
+        }
+
+        let relative_path = path
+            .components()
+            .filter(|component| matches!(component, std::path::Component::Normal(_)))
+            .collect::<PathBuf>();
+        let archived = archive_dir.join(relative_path);
+        let lines = std::fs::read_to_string(archived).unwrap();
+        assert_eq!(lines.lines().count(), n);
+    }
+
     #[derive(Clone, Copy, Eq, PartialEq)]
     enum AckingMode {
         NoAcks,      // No acknowledgement handling and no finalization
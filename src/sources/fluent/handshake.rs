@@ -0,0 +1,412 @@
+//! The handshake of the forward protocol, authenticating the clients with a shared key, and
+//! optionally a username and password, before they send any message.
+//!
+//! <https://github.com/fluent/fluentd/wiki/Forward-Protocol-Specification-v1#handshake-messages>
+
+use std::io;
+
+use bytes::{Buf, BytesMut};
+use rand::{thread_rng, RngCore};
+use rmpv::Value;
+use sha2::{Digest, Sha512};
+use snafu::{ResultExt, Snafu};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use vector_lib::configurable::configurable_component;
+use vector_lib::sensitive_string::SensitiveString;
+
+/// The maximum size of the `PING` message.
+const MAX_PING_BYTES: usize = 64 * 1024;
+
+/// A user allowed to send to the source.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FluentUserConfig {
+    /// The name of the user.
+    #[configurable(metadata(docs::examples = "fluentd"))]
+    pub username: String,
+
+    /// The password of the user.
+    #[configurable(metadata(docs::examples = "${FLUENT_PASSWORD}"))]
+    pub password: SensitiveString,
+}
+
+#[derive(Debug, Snafu)]
+pub enum HandshakeError {
+    #[snafu(display("unable to write the handshake message: {}", source))]
+    Write { source: io::Error },
+    #[snafu(display("unable to read the handshake message: {}", source))]
+    Read { source: io::Error },
+    #[snafu(display("unable to decode the handshake message: {}", source))]
+    Decode { source: rmpv::decode::Error },
+    #[snafu(display("the connection was closed during the handshake"))]
+    Closed,
+    #[snafu(display("the handshake message is larger than {} bytes", MAX_PING_BYTES))]
+    TooLarge,
+    #[snafu(display("unexpected handshake message: {}", message))]
+    UnexpectedMessage { message: Value },
+    #[snafu(display("the client failed to authenticate: {}", reason))]
+    Unauthenticated { reason: &'static str },
+}
+
+/// The `PING` message sent by the client.
+struct Ping<'a> {
+    hostname: &'a [u8],
+    shared_key_salt: &'a [u8],
+    shared_key_digest: &'a [u8],
+    username: &'a [u8],
+    password_digest: &'a [u8],
+}
+
+impl<'a> Ping<'a> {
+    fn parse(message: &'a Value) -> Option<Self> {
+        let fields = message.as_array()?;
+        let [kind, hostname, shared_key_salt, shared_key_digest, username, password_digest] =
+            fields.as_slice()
+        else {
+            return None;
+        };
+        if kind.as_str() != Some("PING") {
+            return None;
+        }
+
+        Some(Self {
+            hostname: hostname.as_slice()?,
+            shared_key_salt: shared_key_salt.as_slice()?,
+            shared_key_digest: shared_key_digest.as_slice()?,
+            username: username.as_slice()?,
+            password_digest: password_digest.as_slice()?,
+        })
+    }
+}
+
+/// Authenticates the clients on the new connections.
+#[derive(Debug)]
+pub struct Handshake {
+    shared_key: SensitiveString,
+    users: Vec<FluentUserConfig>,
+    hostname: String,
+}
+
+impl Handshake {
+    pub const fn new(
+        shared_key: SensitiveString,
+        users: Vec<FluentUserConfig>,
+        hostname: String,
+    ) -> Self {
+        Self {
+            shared_key,
+            users,
+            hostname,
+        }
+    }
+
+    /// Sends the `HELO` message, and replies to the `PING` message of the client with a `PONG`
+    /// message, failing if the client didn't authenticate.
+    ///
+    /// The bytes read past the `PING` message are left in `buffer`.
+    pub async fn run<S>(&self, socket: &mut S, buffer: &mut BytesMut) -> Result<(), HandshakeError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let nonce = random_salt();
+        // The password salt is left empty when the users aren't authenticated.
+        let auth_salt = if self.users.is_empty() {
+            Vec::new()
+        } else {
+            random_salt()
+        };
+
+        let helo = Value::Array(vec![
+            "HELO".into(),
+            Value::Map(vec![
+                ("nonce".into(), Value::Binary(nonce.clone())),
+                ("auth".into(), Value::Binary(auth_salt.clone())),
+                ("keepalive".into(), true.into()),
+            ]),
+        ]);
+        write_message(socket, &helo).await?;
+
+        let message = read_message(socket, buffer).await?;
+        let Some(ping) = Ping::parse(&message) else {
+            return UnexpectedMessageSnafu { message }.fail();
+        };
+
+        if let Err(reason) = self.authenticate(&ping, &nonce, &auth_salt) {
+            let pong = Value::Array(vec![
+                "PONG".into(),
+                false.into(),
+                reason.into(),
+                self.hostname.as_str().into(),
+                "".into(),
+            ]);
+            // The client is told why it is rejected on a best effort basis.
+            _ = write_message(socket, &pong).await;
+            return UnauthenticatedSnafu { reason }.fail();
+        }
+
+        let shared_key_digest =
+            self.shared_key_digest(ping.shared_key_salt, self.hostname.as_bytes(), &nonce);
+        let pong = Value::Array(vec![
+            "PONG".into(),
+            true.into(),
+            "".into(),
+            self.hostname.as_str().into(),
+            shared_key_digest.into(),
+        ]);
+        write_message(socket, &pong).await
+    }
+
+    fn authenticate(
+        &self,
+        ping: &Ping<'_>,
+        nonce: &[u8],
+        auth_salt: &[u8],
+    ) -> Result<(), &'static str> {
+        let shared_key_digest = self.shared_key_digest(ping.shared_key_salt, ping.hostname, nonce);
+        if !digest_eq(shared_key_digest.as_bytes(), ping.shared_key_digest) {
+            return Err("shared key mismatch");
+        }
+
+        if self.users.is_empty() {
+            return Ok(());
+        }
+        let authenticated = self.users.iter().any(|user| {
+            user.username.as_bytes() == ping.username
+                && digest_eq(
+                    hex_digest(&[
+                        auth_salt,
+                        user.username.as_bytes(),
+                        user.password.inner().as_bytes(),
+                    ])
+                    .as_bytes(),
+                    ping.password_digest,
+                )
+        });
+        if authenticated {
+            Ok(())
+        } else {
+            Err("username/password mismatch")
+        }
+    }
+
+    fn shared_key_digest(&self, salt: &[u8], hostname: &[u8], nonce: &[u8]) -> String {
+        hex_digest(&[salt, hostname, nonce, self.shared_key.inner().as_bytes()])
+    }
+}
+
+fn random_salt() -> Vec<u8> {
+    let mut salt = vec![0; 16];
+    thread_rng().fill_bytes(&mut salt);
+    salt
+}
+
+fn hex_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Compares the digests in constant time, so that they can't be guessed from the time it takes.
+fn digest_eq(expected: &[u8], actual: &[u8]) -> bool {
+    expected.len() == actual.len() && openssl::memcmp::eq(expected, actual)
+}
+
+async fn write_message<S>(socket: &mut S, message: &Value) -> Result<(), HandshakeError>
+where
+    S: AsyncWrite + Unpin,
+{
+    let mut bytes = Vec::new();
+    rmpv::encode::write_value(&mut bytes, message).expect("writing to a Vec never fails");
+    socket.write_all(&bytes).await.context(WriteSnafu)?;
+    socket.flush().await.context(WriteSnafu)
+}
+
+async fn read_message<S>(socket: &mut S, buffer: &mut BytesMut) -> Result<Value, HandshakeError>
+where
+    S: AsyncRead + Unpin,
+{
+    loop {
+        let mut cursor = io::Cursor::new(&buffer[..]);
+        match rmpv::decode::read_value(&mut cursor) {
+            Ok(message) => {
+                let position = cursor.position() as usize;
+                buffer.advance(position);
+                return Ok(message);
+            }
+            // The message isn't complete yet.
+            Err(
+                rmpv::decode::Error::InvalidMarkerRead(ref error)
+                | rmpv::decode::Error::InvalidDataRead(ref error),
+            ) if error.kind() == io::ErrorKind::UnexpectedEof => {}
+            Err(source) => return Err(HandshakeError::Decode { source }),
+        }
+
+        if buffer.len() >= MAX_PING_BYTES {
+            return TooLargeSnafu.fail();
+        }
+        if socket.read_buf(buffer).await.context(ReadSnafu)? == 0 {
+            return ClosedSnafu.fail();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{duplex, DuplexStream};
+
+    use super::*;
+
+    const SHARED_KEY: &str = "secret";
+
+    fn handshake(users: Vec<FluentUserConfig>) -> Handshake {
+        Handshake::new(SHARED_KEY.to_owned().into(), users, "vector".to_owned())
+    }
+
+    fn user(username: &str, password: &str) -> FluentUserConfig {
+        FluentUserConfig {
+            username: username.to_owned(),
+            password: password.to_owned().into(),
+        }
+    }
+
+    fn field<'a>(message: &'a Value, key: &str) -> &'a [u8] {
+        message.as_array().unwrap()[1]
+            .as_map()
+            .unwrap()
+            .iter()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .and_then(|(_, v)| v.as_slice())
+            .unwrap()
+    }
+
+    /// Runs the client side of the handshake, returning the `PONG` message.
+    async fn client(
+        mut socket: DuplexStream,
+        shared_key: &str,
+        username: &str,
+        password: &str,
+        trailing: &[u8],
+    ) -> Value {
+        let mut buffer = BytesMut::new();
+        let helo = read_message(&mut socket, &mut buffer).await.unwrap();
+        assert_eq!(helo.as_array().unwrap()[0].as_str(), Some("HELO"));
+        let nonce = field(&helo, "nonce");
+        let auth_salt = field(&helo, "auth");
+
+        let salt: &[u8] = b"client-salt";
+        let ping = Value::Array(vec![
+            "PING".into(),
+            "client".into(),
+            Value::Binary(salt.to_vec()),
+            hex_digest(&[salt, b"client".as_slice(), nonce, shared_key.as_bytes()]).into(),
+            username.into(),
+            hex_digest(&[auth_salt, username.as_bytes(), password.as_bytes()]).into(),
+        ]);
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, &ping).unwrap();
+        bytes.extend_from_slice(trailing);
+        socket.write_all(&bytes).await.unwrap();
+
+        let pong = read_message(&mut socket, &mut buffer).await.unwrap();
+        if pong.as_array().unwrap()[1].as_bool() == Some(true) {
+            let expected = hex_digest(&[salt, b"vector".as_slice(), nonce, shared_key.as_bytes()]);
+            assert_eq!(
+                pong.as_array().unwrap()[4].as_str(),
+                Some(expected.as_str())
+            );
+        }
+        pong
+    }
+
+    #[tokio::test]
+    async fn handshake_with_shared_key() {
+        let (mut server, client_socket) = duplex(1024);
+        let client = tokio::spawn(client(client_socket, SHARED_KEY, "", "", b"rest"));
+
+        let mut buffer = BytesMut::new();
+        handshake(Vec::new())
+            .run(&mut server, &mut buffer)
+            .await
+            .unwrap();
+        let pong = client.await.unwrap();
+        assert_eq!(pong.as_array().unwrap()[1].as_bool(), Some(true));
+
+        // The bytes sent after the `PING` message are kept to be decoded.
+        while buffer.len() < 4 {
+            server.read_buf(&mut buffer).await.unwrap();
+        }
+        assert_eq!(&buffer[..], b"rest");
+    }
+
+    #[tokio::test]
+    async fn handshake_with_wrong_shared_key() {
+        let (mut server, client_socket) = duplex(1024);
+        let client = tokio::spawn(client(client_socket, "wrong", "", "", b""));
+
+        let error = handshake(Vec::new())
+            .run(&mut server, &mut BytesMut::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            HandshakeError::Unauthenticated {
+                reason: "shared key mismatch"
+            }
+        ));
+        let pong = client.await.unwrap();
+        assert_eq!(pong.as_array().unwrap()[1].as_bool(), Some(false));
+        assert_eq!(
+            pong.as_array().unwrap()[2].as_str(),
+            Some("shared key mismatch")
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_with_users() {
+        let users = vec![user("alice", "password1"), user("bob", "password2")];
+
+        let (mut server, client_socket) = duplex(1024);
+        let client = tokio::spawn(client(client_socket, SHARED_KEY, "bob", "password2", b""));
+        handshake(users.clone())
+            .run(&mut server, &mut BytesMut::new())
+            .await
+            .unwrap();
+        client.await.unwrap();
+
+        let (mut server, client_socket) = duplex(1024);
+        let client = tokio::spawn(client(client_socket, SHARED_KEY, "bob", "password1", b""));
+        let error = handshake(users)
+            .run(&mut server, &mut BytesMut::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            HandshakeError::Unauthenticated {
+                reason: "username/password mismatch"
+            }
+        ));
+        client.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn handshake_with_unexpected_message() {
+        let (mut server, mut client_socket) = duplex(1024);
+        let client = tokio::spawn(async move {
+            let mut bytes = Vec::new();
+            rmpv::encode::write_value(&mut bytes, &Value::Array(vec!["tag".into(), 0.into()]))
+                .unwrap();
+            client_socket.write_all(&bytes).await.unwrap();
+            client_socket
+        });
+
+        let error = handshake(Vec::new())
+            .run(&mut server, &mut BytesMut::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, HandshakeError::UnexpectedMessage { .. }));
+        drop(client.await.unwrap());
+    }
+}
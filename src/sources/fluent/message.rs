@@ -11,7 +11,7 @@ use vector_lib::event::{KeyString, ObjectMap, Value};
 /// The spec refers to 4 ways, but really CompressedPackedForward is encoded the
 /// same as PackedForward, it just has an additional decompression step.
 ///
-/// Not yet handled are the handshake messages.
+/// The handshake messages are handled before any of these, see the `handshake` module.
 ///
 /// <https://github.com/fluent/fluentd/wiki/Forward-Protocol-Specification-v1#event-modes>
 #[derive(Debug, Deserialize, Serialize)]
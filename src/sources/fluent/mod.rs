@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 use std::io::{self, Read};
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use base64::prelude::{Engine as _, BASE64_STANDARD};
 use bytes::{Buf, Bytes, BytesMut};
 use chrono::Utc;
 use flate2::read::MultiGzDecoder;
+use futures::future::BoxFuture;
 use rmp_serde::{decode, Deserializer, Serializer};
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
+use tokio::net::TcpStream;
 use tokio_util::codec::Decoder;
 use vector_lib::codecs::{BytesDeserializerConfig, StreamDecodingError};
 use vector_lib::config::{LegacyKey, LogNamespace};
@@ -17,6 +20,7 @@ use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::lookup_v2::parse_value_path;
 use vector_lib::lookup::{metadata_path, owned_value_path, path, OwnedValuePath};
 use vector_lib::schema::Definition;
+use vector_lib::sensitive_string::SensitiveString;
 use vrl::value::kind::Collection;
 use vrl::value::{Kind, Value};
 
@@ -27,12 +31,16 @@ use crate::{
         SourceContext, SourceOutput,
     },
     event::{Event, LogEvent},
-    internal_events::{FluentMessageDecodeError, FluentMessageReceived},
+    internal_events::{FluentHandshakeError, FluentMessageDecodeError, FluentMessageReceived},
     serde::bool_or_struct,
     tcp::TcpKeepaliveConfig,
-    tls::{MaybeTlsSettings, TlsSourceConfig},
+    tls::{MaybeTlsIncomingStream, MaybeTlsSettings, TlsSourceConfig},
 };
 
+mod handshake;
+pub use self::handshake::HandshakeError;
+use self::handshake::{FluentUserConfig, Handshake};
+
 mod message;
 use self::message::{FluentEntry, FluentMessage, FluentRecord, FluentTag, FluentTimestamp};
 
@@ -64,6 +72,21 @@ pub struct FluentConfig {
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
 
+    /// The key shared with the clients, to authenticate them.
+    ///
+    /// When set, the clients must perform the handshake of the forward protocol, proving they know
+    /// the key, before sending any message. This is the `shared_key` of the `<security>` section of
+    /// the Fluentd configuration.
+    #[configurable(metadata(docs::examples = "${FLUENT_SHARED_KEY}"))]
+    shared_key: Option<SensitiveString>,
+
+    /// The users allowed to send to the source.
+    ///
+    /// When set, the clients must also authenticate with the username and password of one of
+    /// them during the handshake. Requires `shared_key`.
+    #[serde(default)]
+    users: Vec<FluentUserConfig>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -79,6 +102,8 @@ impl GenerateConfig for FluentConfig {
             receive_buffer_bytes: None,
             acknowledgements: Default::default(),
             connection_limit: Some(2),
+            shared_key: None,
+            users: Vec::new(),
             log_namespace: None,
         })
         .unwrap()
@@ -90,7 +115,18 @@ impl GenerateConfig for FluentConfig {
 impl SourceConfig for FluentConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
-        let source = FluentSource::new(log_namespace);
+        let handshake = match &self.shared_key {
+            Some(shared_key) => Some(Arc::new(Handshake::new(
+                shared_key.clone(),
+                self.users.clone(),
+                crate::get_hostname()?,
+            ))),
+            None if !self.users.is_empty() => {
+                return Err("the `users` option requires a `shared_key`".into())
+            }
+            None => None,
+        };
+        let source = FluentSource::new(log_namespace, handshake);
         let shutdown_secs = Duration::from_secs(30);
         let tls_config = self.tls.as_ref().map(|tls| tls.tls_config.clone());
         let tls_client_metadata_key = self
@@ -204,13 +240,15 @@ impl FluentConfig {
 struct FluentSource {
     log_namespace: LogNamespace,
     legacy_host_key_path: Option<OwnedValuePath>,
+    handshake: Option<Arc<Handshake>>,
 }
 
 impl FluentSource {
-    fn new(log_namespace: LogNamespace) -> Self {
+    fn new(log_namespace: LogNamespace, handshake: Option<Arc<Handshake>>) -> Self {
         Self {
             log_namespace,
             legacy_host_key_path: log_schema().host_key().cloned(),
+            handshake,
         }
     }
 }
@@ -244,6 +282,26 @@ impl TcpSource for FluentSource {
         }
     }
 
+    fn handshake<'a>(
+        &'a self,
+        socket: &'a mut MaybeTlsIncomingStream<TcpStream>,
+        buffer: &'a mut BytesMut,
+        peer_addr: SocketAddr,
+    ) -> BoxFuture<'a, crate::Result<()>> {
+        Box::pin(async move {
+            let Some(handshake) = &self.handshake else {
+                return Ok(());
+            };
+            handshake.run(socket, buffer).await.map_err(|error| {
+                emit!(FluentHandshakeError {
+                    error: &error,
+                    peer_addr
+                });
+                error.into()
+            })
+        })
+    }
+
     fn build_acker(&self, frame: &[Self::Item]) -> Self::Acker {
         FluentAcker::new(frame)
     }
@@ -253,6 +311,7 @@ impl TcpSource for FluentSource {
 pub enum DecodeError {
     IO(io::Error),
     Decode(decode::Error),
+    Decompression(io::Error),
     UnknownCompression(String),
     UnexpectedValue(rmpv::Value),
 }
@@ -262,6 +321,7 @@ impl std::fmt::Display for DecodeError {
         match self {
             DecodeError::IO(err) => write!(f, "{}", err),
             DecodeError::Decode(err) => write!(f, "{}", err),
+            DecodeError::Decompression(err) => write!(f, "unable to decompress: {}", err),
             DecodeError::UnknownCompression(compression) => {
                 write!(f, "unknown compression: {}", compression)
             }
@@ -277,6 +337,7 @@ impl StreamDecodingError for DecodeError {
         match self {
             DecodeError::IO(_) => false,
             DecodeError::Decode(_) => true,
+            DecodeError::Decompression(_) => true,
             DecodeError::UnknownCompression(_) => true,
             DecodeError::UnexpectedValue(_) => true,
         }
@@ -402,7 +463,7 @@ impl FluentDecoder {
                         MultiGzDecoder::new(io::Cursor::new(bin.into_vec()))
                             .read_to_end(&mut buf)
                             .map(|_| buf)
-                            .map_err(Into::into)
+                            .map_err(DecodeError::Decompression)
                     }
                     Some("text") | None => Ok(bin.into_vec()),
                     Some(s) => Err(DecodeError::UnknownCompression(s.to_owned())),
@@ -808,7 +869,6 @@ mod tests {
         assert_event_data_eq!(got.0[2], expected[2]);
     }
 
-    //  TODO
     #[test]
     fn decode_compressed_packed_forward_mode() {
         //[
@@ -843,6 +903,70 @@ mod tests {
         assert_event_data_eq!(got.0[2], expected[2]);
     }
 
+    fn compressed_packed_forward(bin: Vec<u8>, chunk: &str) -> Vec<u8> {
+        let message = FluentMessage::PackedForwardWithOptions(
+            "tag.name".into(),
+            serde_bytes::ByteBuf::from(bin),
+            FluentMessageOptions {
+                chunk: Some(chunk.to_owned()),
+                compressed: Some("gzip".to_owned()),
+                ..Default::default()
+            },
+        );
+        let mut buf = Vec::new();
+        message.serialize(&mut Serializer::new(&mut buf)).unwrap();
+        buf
+    }
+
+    #[test]
+    fn decode_compressed_packed_forward_mode_with_chunk() {
+        // [1441588984, {"message": "foo"}]
+        let entry: Vec<u8> = vec![
+            146, 206, 85, 236, 230, 248, 129, 167, 109, 101, 115, 115, 97, 103, 101, 163, 102, 111,
+            111,
+        ];
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut encoder, &entry).unwrap();
+        let message = compressed_packed_forward(encoder.finish().unwrap(), "chunk-id");
+
+        let mut buf = BytesMut::from(&message[..]);
+        let (frame, byte_size) = FluentDecoder::new(LogNamespace::default())
+            .decode(&mut buf)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(byte_size, message.len());
+        assert_eq!(frame.chunk.as_deref(), Some("chunk-id"));
+        assert_event_data_eq!(frame.events[0], mock_event("foo", "2015-09-07T01:23:04Z"));
+    }
+
+    #[test]
+    fn decode_malformed_compressed_packed_forward_mode() {
+        //[
+        //  "tag.name",
+        //  1441588984,
+        //  {"message": "bar"},
+        //]
+        let next_message: Vec<u8> = vec![
+            147, 168, 116, 97, 103, 46, 110, 97, 109, 101, 206, 85, 236, 230, 248, 129, 167, 109,
+            101, 115, 115, 97, 103, 101, 163, 98, 97, 114,
+        ];
+        let mut message = compressed_packed_forward(b"not gzip".to_vec(), "chunk-id");
+        message.extend_from_slice(&next_message);
+
+        let mut buf = BytesMut::from(&message[..]);
+        let mut decoder = FluentDecoder::new(LogNamespace::default());
+
+        // The connection isn't closed, the next message is decoded.
+        let error = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(error, DecodeError::Decompression(_)));
+        assert!(error.can_continue());
+
+        let (frame, _) = decoder.decode(&mut buf).unwrap().unwrap();
+        assert_event_data_eq!(frame.events[0], mock_event("bar", "2015-09-07T01:23:04Z"));
+        assert!(buf.is_empty());
+    }
+
     fn decode_all(message: Vec<u8>) -> Result<(SmallVec<[Event; 1]>, usize), DecodeError> {
         let mut buf = BytesMut::from(&message[..]);
 
@@ -897,6 +1021,8 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: true.into(),
             connection_limit: None,
+            shared_key: None,
+            users: Vec::new(),
             log_namespace: None,
         }
         .build(SourceContext::new_test(sender, None))
@@ -952,6 +1078,25 @@ mod tests {
         buf
     }
 
+    #[tokio::test]
+    async fn users_require_shared_key() {
+        let config: FluentConfig = toml::from_str(
+            r#"
+            address = "0.0.0.0:24224"
+
+            [[users]]
+            username = "fluentd"
+            password = "password"
+            "#,
+        )
+        .unwrap();
+        let (sender, _) = SourceSender::new_test();
+        assert!(config
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .is_err());
+    }
+
     #[test]
     fn output_schema_definition_vector_namespace() {
         let config = FluentConfig {
@@ -961,6 +1106,8 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: false.into(),
             connection_limit: None,
+            shared_key: None,
+            users: Vec::new(),
             log_namespace: Some(true),
         };
 
@@ -1016,6 +1163,8 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: false.into(),
             connection_limit: None,
+            shared_key: None,
+            users: Vec::new(),
             log_namespace: None,
         };
 
@@ -1237,6 +1386,8 @@ mod integration_tests {
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
                 connection_limit: None,
+                shared_key: None,
+                users: Vec::new(),
                 log_namespace: None,
             }
             .build(SourceContext::new_test(sender, None))
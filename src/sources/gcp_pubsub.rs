@@ -1,6 +1,12 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::{
-    error::Error as _, future::Future, pin::Pin, sync::Arc, task::Context, task::Poll,
+    collections::{HashMap, HashSet},
+    error::Error as _,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::Context,
+    task::Poll,
     time::Duration,
 };
 
@@ -11,11 +17,15 @@ use http::uri::{InvalidUri, Scheme, Uri};
 use once_cell::sync::Lazy;
 use serde_with::serde_as;
 use snafu::{ResultExt, Snafu};
-use tokio::sync::{mpsc, watch};
+use tokio::{
+    sync::{mpsc, watch},
+    time::Instant,
+};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{
+    codegen::InterceptedService,
     metadata::{errors::InvalidMetadataValue, MetadataValue},
-    transport::{Certificate, ClientTlsConfig, Endpoint, Identity},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity},
     Code, Request, Status,
 };
 use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig};
@@ -35,8 +45,8 @@ use crate::{
     event::{BatchNotifier, BatchStatus, Event, MaybeAsLogMut, Value},
     gcp::{GcpAuthConfig, GcpAuthenticator, Scope, PUBSUB_URL},
     internal_events::{
-        GcpPubsubConnectError, GcpPubsubReceiveError, GcpPubsubStreamingPullError,
-        StreamClosedError,
+        GcpPubsubAckError, GcpPubsubConnectError, GcpPubsubGetSubscriptionError,
+        GcpPubsubReceiveError, GcpPubsubStreamingPullError, StreamClosedError,
     },
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
@@ -56,6 +66,10 @@ const MAX_ACK_DEADLINE_SECS: u64 = 600;
 // processing.
 const ACK_QUEUE_SIZE: usize = 8;
 
+// The delay before retrying an acknowledgement the server didn't confirm,
+// for exactly-once delivery subscriptions. It is doubled on each retry.
+const ACK_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
 type Finalizer = UnorderedFinalizer<Vec<String>>;
 
 // prost emits some generated code that includes clones on `Arc`
@@ -329,6 +343,8 @@ impl SourceConfig for PubsubConfig {
             log_namespace,
             bytes_received: register!(BytesReceived::from(protocol)),
             events_received: register!(EventsReceived),
+            exactly_once: false,
+            ack_tracker: AckTracker::default(),
         }
         .run_all(self.max_concurrency, self.poll_time_seconds)
         .map_err(|error| error!(message = "Source failed.", %error));
@@ -394,6 +410,10 @@ struct PubsubSource {
     log_namespace: LogNamespace,
     bytes_received: Registered<BytesReceived>,
     events_received: Registered<EventsReceived>,
+    // Whether exactly-once delivery is enabled for the subscription, in
+    // which case the server confirms the acknowledgements.
+    exactly_once: bool,
+    ack_tracker: AckTracker,
 }
 
 enum State {
@@ -404,6 +424,13 @@ enum State {
 
 impl PubsubSource {
     async fn run_all(mut self, max_concurrency: usize, poll_time: Duration) -> crate::Result<()> {
+        let mut shutdown = self.shutdown.clone();
+        self.exactly_once = tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            exactly_once = self.exactly_once_delivery() => exactly_once,
+        };
+        log_delivery_mode(self.exactly_once);
+
         let mut tasks = FuturesUnordered::new();
 
         loop {
@@ -437,6 +464,34 @@ impl PubsubSource {
         Ok(())
     }
 
+    /// Detects whether exactly-once delivery is enabled for the subscription,
+    /// falling back to at-least-once delivery if it can't be fetched. The
+    /// streaming pull responses also carry the subscription properties, so
+    /// the mode is corrected once the first of them are received.
+    async fn exactly_once_delivery(&self) -> bool {
+        let connection = match self.endpoint.connect().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                emit!(GcpPubsubConnectError { error });
+                return false;
+            }
+        };
+
+        let request = proto::GetSubscriptionRequest {
+            subscription: self.subscription.clone(),
+        };
+        match make_client(&self.auth, connection)
+            .get_subscription(request)
+            .await
+        {
+            Ok(subscription) => subscription.into_inner().enable_exactly_once_delivery,
+            Err(error) => {
+                emit!(GcpPubsubGetSubscriptionError { error });
+                false
+            }
+        }
+    }
+
     fn start_one(&self, tasks: &FuturesUnordered<Task>) {
         info!(message = "Starting stream.", concurrency = tasks.len() + 1);
         // The `busy_flag` is used to monitor the status of a
@@ -475,23 +530,12 @@ impl PubsubSource {
             }
         };
 
-        let mut client = proto::subscriber_client::SubscriberClient::with_interceptor(
-            connection,
-            |mut req: Request<()>| {
-                if let Some(token) = self.auth.make_token() {
-                    let authorization = MetadataValue::try_from(&token).map_err(|_| {
-                        Status::new(
-                            Code::FailedPrecondition,
-                            "Invalid token text returned by GCP",
-                        )
-                    })?;
-                    req.metadata_mut().insert("authorization", authorization);
-                }
-                Ok(req)
-            },
-        );
+        let mut client = make_client(&self.auth, connection);
 
         let (ack_ids_sender, ack_ids_receiver) = mpsc::channel(ACK_QUEUE_SIZE);
+        // The events of the messages in flight on the previous stream are
+        // never acknowledged, so they are redelivered.
+        self.ack_tracker.forget_in_flight();
 
         // Handle shutdown during startup, the streaming pull doesn't
         // start if there is no data in the subscription.
@@ -514,13 +558,40 @@ impl PubsubSource {
         let mut pending_acks = 0;
 
         loop {
+            let ack_due = if self.exactly_once {
+                self.ack_tracker.next_due(self.ack_deadline_secs)
+            } else {
+                None
+            };
             tokio::select! {
                 biased;
                 receipts = ack_stream.next() => if let Some((status, receipts)) = receipts {
                     pending_acks -= 1;
                     if status == BatchStatus::Delivered {
+                        if self.exactly_once {
+                            self.ack_tracker.acked(&receipts, Instant::now());
+                        }
+                        ack_ids_sender
+                            .send(StreamUpdate::Ack(receipts))
+                            .await
+                            .unwrap_or_else(|_| unreachable!("request stream never closes"));
+                    } else if self.exactly_once {
+                        self.ack_tracker.rejected(&receipts);
+                    }
+                },
+                _ = tokio::time::sleep_until(ack_due.unwrap_or_else(Instant::now)), if ack_due.is_some() => {
+                    let now = Instant::now();
+                    let extended = self.ack_tracker.extensions(now, self.ack_deadline_secs);
+                    if !extended.is_empty() {
+                        ack_ids_sender
+                            .send(StreamUpdate::ModifyAckDeadline(extended))
+                            .await
+                            .unwrap_or_else(|_| unreachable!("request stream never closes"));
+                    }
+                    let retried = self.ack_tracker.retries(now, self.ack_deadline_secs);
+                    if !retried.is_empty() {
                         ack_ids_sender
-                            .send(receipts)
+                            .send(StreamUpdate::Ack(retried))
                             .await
                             .unwrap_or_else(|_| unreachable!("request stream never closes"));
                     }
@@ -563,7 +634,7 @@ impl PubsubSource {
                     // in a new request with empty fields, effectively
                     // a keepalive.
                     ack_ids_sender
-                        .send(Vec::new())
+                        .send(StreamUpdate::Ack(Vec::new()))
                         .await
                         .unwrap_or_else(|_| unreachable!("request stream never closes"));
                 }
@@ -573,12 +644,12 @@ impl PubsubSource {
 
     fn request_stream(
         &self,
-        ack_ids: mpsc::Receiver<Vec<String>>,
+        updates: mpsc::Receiver<StreamUpdate>,
     ) -> impl Stream<Item = proto::StreamingPullRequest> + 'static {
         let subscription = self.subscription.clone();
         let client_id = CLIENT_ID.clone();
         let stream_ack_deadline_seconds = self.ack_deadline_secs.as_secs() as i32;
-        let ack_ids = ReceiverStream::new(ack_ids).ready_chunks(ACK_QUEUE_SIZE);
+        let updates = ReceiverStream::new(updates).ready_chunks(ACK_QUEUE_SIZE);
 
         stream::once(async move {
             // These fields are only valid on the first request in the
@@ -590,27 +661,44 @@ impl PubsubSource {
                 ..Default::default()
             }
         })
-        .chain(ack_ids.map(|chunks| {
+        .chain(updates.map(move |chunks| {
             // These "requests" serve only to send updates about
             // acknowledgements to the server. None of the above
             // fields need to be repeated and, in fact, will cause
             // an stream error and cancellation if they are
             // present.
-            proto::StreamingPullRequest {
-                ack_ids: chunks.into_iter().flatten().collect(),
-                ..Default::default()
-            }
+            update_request(chunks, stream_ack_deadline_seconds)
         }))
     }
 
     async fn handle_response(
         &mut self,
-        response: proto::StreamingPullResponse,
+        mut response: proto::StreamingPullResponse,
         finalizer: &Option<Finalizer>,
-        ack_ids: &mpsc::Sender<Vec<String>>,
+        ack_ids: &mpsc::Sender<StreamUpdate>,
         pending_acks: &mut usize,
         busy_flag: &Arc<AtomicBool>,
     ) {
+        if let Some(properties) = &response.subscription_properties {
+            if properties.exactly_once_delivery_enabled != self.exactly_once {
+                self.exactly_once = properties.exactly_once_delivery_enabled;
+                log_delivery_mode(self.exactly_once);
+            }
+        }
+        if self.exactly_once {
+            if let Some(confirmation) = response.acknowledge_confirmation.take() {
+                self.ack_tracker.confirm(confirmation);
+            }
+            if let Some(confirmation) = response.modify_ack_deadline_confirmation.take() {
+                self.ack_tracker.confirm_modify_ack_deadline(confirmation);
+            }
+        }
+        // Responses of exactly-once delivery subscriptions may only carry
+        // the confirmations.
+        if response.received_messages.is_empty() {
+            return;
+        }
+
         if response.received_messages.len() >= self.full_response_size {
             busy_flag.store(true, Ordering::Relaxed);
         }
@@ -623,11 +711,19 @@ impl PubsubSource {
         match self.out.send_batch(events).await {
             Err(_) => emit!(StreamClosedError { count }),
             Ok(()) => match notifier {
-                None => ack_ids
-                    .send(ids)
-                    .await
-                    .unwrap_or_else(|_| unreachable!("request stream never closes")),
+                None => {
+                    if self.exactly_once {
+                        self.ack_tracker.acked(&ids, Instant::now());
+                    }
+                    ack_ids
+                        .send(StreamUpdate::Ack(ids))
+                        .await
+                        .unwrap_or_else(|_| unreachable!("request stream never closes"))
+                }
                 Some(notifier) => {
+                    if self.exactly_once {
+                        self.ack_tracker.received(&ids, Instant::now());
+                    }
                     finalizer
                         .as_ref()
                         .expect("Finalizer must have been set up for acknowledgements")
@@ -705,6 +801,225 @@ impl PubsubSource {
     }
 }
 
+/// Creates a client for the connection, authenticating the requests.
+fn make_client(
+    auth: &GcpAuthenticator,
+    connection: Channel,
+) -> proto::subscriber_client::SubscriberClient<
+    InterceptedService<Channel, impl FnMut(Request<()>) -> Result<Request<()>, Status> + '_>,
+> {
+    proto::subscriber_client::SubscriberClient::with_interceptor(
+        connection,
+        |mut req: Request<()>| {
+            if let Some(token) = auth.make_token() {
+                let authorization = MetadataValue::try_from(&token).map_err(|_| {
+                    Status::new(
+                        Code::FailedPrecondition,
+                        "Invalid token text returned by GCP",
+                    )
+                })?;
+                req.metadata_mut().insert("authorization", authorization);
+            }
+            Ok(req)
+        },
+    )
+}
+
+fn log_delivery_mode(exactly_once: bool) {
+    if exactly_once {
+        info!(message = "Subscription has exactly-once delivery enabled, acknowledgements are confirmed and retried.");
+    } else {
+        info!(message = "Subscription has at-least-once delivery.");
+    }
+}
+
+/// The updates sent to the server on the request stream of a streaming pull.
+#[derive(Debug)]
+enum StreamUpdate {
+    /// Acknowledges the messages.
+    Ack(Vec<String>),
+    /// Extends the acknowledgement deadline of the messages.
+    ModifyAckDeadline(Vec<String>),
+}
+
+/// Combines the updates into a single request, extending the deadlines by
+/// the acknowledgement deadline of the stream.
+fn update_request(
+    updates: Vec<StreamUpdate>,
+    ack_deadline_seconds: i32,
+) -> proto::StreamingPullRequest {
+    let mut request = proto::StreamingPullRequest::default();
+    for update in updates {
+        match update {
+            StreamUpdate::Ack(ack_ids) => request.ack_ids.extend(ack_ids),
+            StreamUpdate::ModifyAckDeadline(ack_ids) => {
+                request
+                    .modify_deadline_seconds
+                    .extend(ack_ids.iter().map(|_| ack_deadline_seconds));
+                request.modify_deadline_ack_ids.extend(ack_ids);
+            }
+        }
+    }
+    request
+}
+
+/// Tracks the messages of an exactly-once delivery subscription, from their
+/// reception until the server confirms their acknowledgement.
+///
+/// The deadlines of the messages are extended while their events are being
+/// processed, so slow sinks don't lead to redeliveries. Acknowledgements the
+/// server doesn't confirm are sent again with an exponential backoff, until
+/// the deadline of the message expires. Acknowledgements the server reports
+/// as invalid are permanent failures, and aren't retried.
+#[derive(Clone, Debug, Default)]
+struct AckTracker {
+    in_flight: HashSet<String>,
+    last_extended: Option<Instant>,
+    unconfirmed: HashMap<String, UnconfirmedAck>,
+}
+
+#[derive(Clone, Debug)]
+struct UnconfirmedAck {
+    first_sent: Instant,
+    last_sent: Instant,
+    attempts: u32,
+}
+
+impl UnconfirmedAck {
+    /// When the acknowledgement is due to be sent again, or given up on.
+    fn next_due(&self, ack_deadline: Duration) -> Instant {
+        let backoff = ACK_RETRY_BACKOFF * 2u32.saturating_pow(self.attempts - 1);
+        (self.last_sent + backoff).min(self.first_sent + ack_deadline)
+    }
+}
+
+impl AckTracker {
+    fn received(&mut self, ack_ids: &[String], now: Instant) {
+        if self.in_flight.is_empty() {
+            self.last_extended = Some(now);
+        }
+        self.in_flight.extend(ack_ids.iter().cloned());
+    }
+
+    /// Stops extending the deadline of messages whose events weren't
+    /// delivered, so they are redelivered.
+    fn rejected(&mut self, ack_ids: &[String]) {
+        for ack_id in ack_ids {
+            self.in_flight.remove(ack_id);
+        }
+    }
+
+    fn forget_in_flight(&mut self) {
+        self.in_flight.clear();
+    }
+
+    fn acked(&mut self, ack_ids: &[String], now: Instant) {
+        for ack_id in ack_ids {
+            self.in_flight.remove(ack_id);
+            self.unconfirmed.insert(
+                ack_id.clone(),
+                UnconfirmedAck {
+                    first_sent: now,
+                    last_sent: now,
+                    attempts: 1,
+                },
+            );
+        }
+    }
+
+    fn confirm(&mut self, confirmation: proto::streaming_pull_response::AcknowledgeConfirmation) {
+        for ack_id in &confirmation.ack_ids {
+            self.unconfirmed.remove(ack_id);
+        }
+        for (ack_ids, error_code) in [
+            (confirmation.invalid_ack_ids, "invalid_ack_id"),
+            (confirmation.unordered_ack_ids, "unordered_ack_id"),
+        ] {
+            if !ack_ids.is_empty() {
+                for ack_id in &ack_ids {
+                    self.unconfirmed.remove(ack_id);
+                }
+                emit!(GcpPubsubAckError {
+                    count: ack_ids.len(),
+                    error_code,
+                });
+            }
+        }
+    }
+
+    fn confirm_modify_ack_deadline(
+        &mut self,
+        confirmation: proto::streaming_pull_response::ModifyAckDeadlineConfirmation,
+    ) {
+        if !confirmation.invalid_ack_ids.is_empty() {
+            // The messages are redelivered, so their acknowledgements would fail.
+            for ack_id in &confirmation.invalid_ack_ids {
+                self.in_flight.remove(ack_id);
+            }
+            emit!(GcpPubsubAckError {
+                count: confirmation.invalid_ack_ids.len(),
+                error_code: "invalid_modify_ack_deadline_id",
+            });
+        }
+    }
+
+    /// When the next deadlines are due to be extended or acknowledgements
+    /// retried, if any.
+    fn next_due(&self, ack_deadline: Duration) -> Option<Instant> {
+        let extension = self
+            .last_extended
+            .filter(|_| !self.in_flight.is_empty())
+            .map(|last_extended| last_extended + ack_deadline / 2);
+        let retry = self
+            .unconfirmed
+            .values()
+            .map(|ack| ack.next_due(ack_deadline))
+            .min();
+        extension.into_iter().chain(retry).min()
+    }
+
+    /// Returns the messages whose deadlines are due to be extended, halfway
+    /// through the deadline.
+    fn extensions(&mut self, now: Instant, ack_deadline: Duration) -> Vec<String> {
+        match self.last_extended {
+            Some(last_extended)
+                if !self.in_flight.is_empty() && now >= last_extended + ack_deadline / 2 =>
+            {
+                self.last_extended = Some(now);
+                self.in_flight.iter().cloned().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the acknowledgements due to be retried, giving up on the ones
+    /// whose deadline expired.
+    fn retries(&mut self, now: Instant, ack_deadline: Duration) -> Vec<String> {
+        let mut retries = Vec::new();
+        let mut expired = 0;
+        self.unconfirmed.retain(|ack_id, ack| {
+            if now >= ack.first_sent + ack_deadline {
+                expired += 1;
+                false
+            } else {
+                if now >= ack.next_due(ack_deadline) {
+                    ack.attempts += 1;
+                    ack.last_sent = now;
+                    retries.push(ack_id.clone());
+                }
+                true
+            }
+        });
+        if expired > 0 {
+            emit!(GcpPubsubAckError {
+                count: expired,
+                error_code: "ack_deadline_expired",
+            });
+        }
+        retries
+    }
+}
+
 fn translate_error(error: tonic::Status) -> State {
     // GCP occasionally issues a connection reset
     // in the middle of the streaming pull. This
@@ -833,6 +1148,91 @@ mod tests {
 
         assert_eq!(definitions, Some(expected_definition));
     }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn update_request_combines_updates() {
+        let request = update_request(
+            vec![
+                StreamUpdate::Ack(ids(&["a"])),
+                StreamUpdate::ModifyAckDeadline(ids(&["b", "c"])),
+                StreamUpdate::Ack(ids(&["d"])),
+            ],
+            600,
+        );
+        assert_eq!(request.ack_ids, ids(&["a", "d"]));
+        assert_eq!(request.modify_deadline_ack_ids, ids(&["b", "c"]));
+        assert_eq!(request.modify_deadline_seconds, vec![600, 600]);
+        assert!(request.subscription.is_empty());
+    }
+
+    #[test]
+    fn ack_tracker_extends_deadlines_in_flight() {
+        let deadline = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut tracker = AckTracker::default();
+        assert_eq!(tracker.next_due(deadline), None);
+
+        tracker.received(&ids(&["a", "b"]), start);
+        assert_eq!(tracker.next_due(deadline), Some(start + deadline / 2));
+        assert!(tracker.extensions(start, deadline).is_empty());
+
+        let halfway = start + deadline / 2;
+        assert_eq!(
+            sorted(tracker.extensions(halfway, deadline)),
+            ids(&["a", "b"])
+        );
+        assert_eq!(tracker.next_due(deadline), Some(halfway + deadline / 2));
+
+        // Messages whose events were rejected are left to be redelivered.
+        tracker.rejected(&ids(&["b"]));
+        tracker.acked(&ids(&["a"]), halfway);
+        assert!(tracker.extensions(halfway + deadline, deadline).is_empty());
+    }
+
+    #[test]
+    fn ack_tracker_retries_unconfirmed_acks() {
+        let deadline = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut tracker = AckTracker::default();
+        tracker.received(&ids(&["a", "b", "c"]), start);
+        tracker.acked(&ids(&["a", "b", "c"]), start);
+        assert_eq!(tracker.next_due(deadline), Some(start + ACK_RETRY_BACKOFF));
+
+        tracker.confirm(proto::streaming_pull_response::AcknowledgeConfirmation {
+            ack_ids: ids(&["a"]),
+            invalid_ack_ids: ids(&["b"]),
+            unordered_ack_ids: Vec::new(),
+        });
+        assert!(tracker.retries(start, deadline).is_empty());
+
+        // The retries back off exponentially.
+        let first_retry = start + ACK_RETRY_BACKOFF;
+        assert_eq!(tracker.retries(first_retry, deadline), ids(&["c"]));
+        assert_eq!(
+            tracker.next_due(deadline),
+            Some(first_retry + ACK_RETRY_BACKOFF * 2)
+        );
+        assert!(tracker
+            .retries(first_retry + ACK_RETRY_BACKOFF, deadline)
+            .is_empty());
+        assert_eq!(
+            tracker.retries(first_retry + ACK_RETRY_BACKOFF * 2, deadline),
+            ids(&["c"])
+        );
+
+        // They are given up on when the deadline expires.
+        assert!(tracker.retries(start + deadline, deadline).is_empty());
+        assert_eq!(tracker.next_due(deadline), None);
+    }
 }
 
 #[cfg(all(test, feature = "gcp-integration-tests"))]
@@ -30,6 +30,8 @@ enum CGroupsError {
         filename: PathBuf,
         source: ParseIntError,
     },
+    #[snafu(display("Could not parse cgroup pressure file {:?}.", filename))]
+    ParsingPressure { filename: PathBuf },
 }
 
 type CGroupsResult<T> = Result<T, CGroupsError>;
@@ -63,6 +65,7 @@ struct CGroupRecurser<'a> {
     buffer: String,
     load_cpu: bool,
     load_memory: bool,
+    load_pressure: bool,
     config: CGroupsConfig,
 }
 
@@ -75,6 +78,7 @@ impl<'a> CGroupRecurser<'a> {
             buffer: String::new(),
             load_cpu: true,
             load_memory: true,
+            load_pressure: true,
             config: cgroups,
         }
     }
@@ -86,6 +90,7 @@ impl<'a> CGroupRecurser<'a> {
         };
         self.load_cpu = true;
         self.load_memory = true;
+        self.load_pressure = true;
         self.recurse(cgroup, 1).await;
     }
 
@@ -97,6 +102,8 @@ impl<'a> CGroupRecurser<'a> {
         };
         self.load_cpu = false;
         self.load_memory = true;
+        // The pressure files are only found in the modern groups.
+        self.load_pressure = false;
         self.recurse(cgroup, 1).await;
 
         let cpu_base = join_path(base, "cpu");
@@ -119,6 +126,11 @@ impl<'a> CGroupRecurser<'a> {
             if self.load_memory && !cgroup.is_root() {
                 self.load_memory(&cgroup, &tags).await;
             }
+            // The pressure of the whole host is found under `/proc/pressure` rather than in the
+            // root group.
+            if self.load_pressure && !cgroup.is_root() {
+                self.load_pressure(&cgroup, &tags).await;
+            }
 
             if level < self.config.levels {
                 let groups = self.config.groups.clone();
@@ -179,6 +191,35 @@ impl<'a> CGroupRecurser<'a> {
                 .gauge("cgroup_memory_file_bytes", stat.file as f64, tags.clone());
         }
     }
+
+    /// Try to load the pressure stall information files and emit metrics for those found.
+    async fn load_pressure(&mut self, cgroup: &CGroup, tags: &MetricTags) {
+        for resource in PRESSURE_RESOURCES {
+            let Some(Some(pressure)) = filter_result_sync(
+                cgroup.load_pressure(resource, &mut self.buffer).await,
+                "Failed to load cgroups pressure stall information.",
+            ) else {
+                continue;
+            };
+            for (kind, stat) in [("some", pressure.some), ("full", pressure.full)] {
+                let Some(stat) = stat else {
+                    continue;
+                };
+                let prefix = format!("cgroup_{resource}_pressure_{kind}");
+                self.output
+                    .gauge(&format!("{prefix}_avg10"), stat.avg10, tags.clone());
+                self.output
+                    .gauge(&format!("{prefix}_avg60"), stat.avg60, tags.clone());
+                self.output
+                    .gauge(&format!("{prefix}_avg300"), stat.avg300, tags.clone());
+                self.output.counter(
+                    &format!("{prefix}_seconds_total"),
+                    stat.total as f64 * MICROSECONDS,
+                    tags.clone(),
+                );
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -197,6 +238,9 @@ enum Mode {
 
 const CGROUP_CONTROLLERS: &str = "cgroup.controllers";
 
+/// The resources with a `<resource>.pressure` file in the modern groups.
+const PRESSURE_RESOURCES: [&str; 3] = ["cpu", "memory", "io"];
+
 impl CGroupRoot {
     pub(super) fn new(config: &CGroupsConfig) -> Option<Self> {
         // There are three standard possibilities for cgroups setups
@@ -340,6 +384,28 @@ impl CGroup {
         self.open_read_parse("memory.stat", buffer).await
     }
 
+    /// Load the `<resource>.pressure` file. Returns `Ok(None)` if the file is missing or can't be
+    /// read because PSI is disabled, as with `psi=0` on the kernel command line.
+    async fn load_pressure(
+        &self,
+        resource: &str,
+        buffer: &mut String,
+    ) -> CGroupsResult<Option<Pressure>> {
+        match self.open_read(format!("{resource}.pressure"), buffer).await {
+            Ok(Some(filename)) => buffer
+                .parse()
+                .map(Some)
+                .map_err(|()| CGroupsError::ParsingPressure { filename }),
+            Ok(None) => Ok(None),
+            Err(CGroupsError::Opening { source, .. } | CGroupsError::Reading { source, .. })
+                if is_unsupported(&source) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     async fn children(&self) -> io::Result<Vec<CGroup>> {
         let mut result = Vec::new();
         let mut dir = fs::read_dir(&self.path).await?;
@@ -395,6 +461,60 @@ define_stat_struct! { MemoryStat(
     file,
 )}
 
+/// The pressure stall information of a resource, as found in the `<resource>.pressure` files.
+///
+/// See <https://docs.kernel.org/accounting/psi.html> for the format.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Pressure {
+    some: Option<PressureStat>,
+    full: Option<PressureStat>,
+}
+
+/// The share of time some or all tasks stalled on a resource, as percentages averaged over 10,
+/// 60 and 300 seconds, and the total stall time in microseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct PressureStat {
+    avg10: f64,
+    avg60: f64,
+    avg300: f64,
+    total: u64,
+}
+
+impl FromStr for Pressure {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::default();
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            let stat = match fields.next() {
+                Some("some") => &mut result.some,
+                Some("full") => &mut result.full,
+                _ => continue,
+            };
+            let mut parsed = PressureStat::default();
+            for field in fields {
+                let (key, value) = field.split_once('=').ok_or(())?;
+                match key {
+                    "avg10" => parsed.avg10 = value.parse().map_err(|_| ())?,
+                    "avg60" => parsed.avg60 = value.parse().map_err(|_| ())?,
+                    "avg300" => parsed.avg300 = value.parse().map_err(|_| ())?,
+                    "total" => parsed.total = value.parse().map_err(|_| ())?,
+                    _ => {}
+                }
+            }
+            *stat = Some(parsed);
+        }
+        Ok(result)
+    }
+}
+
+/// Whether the error is the one returned by the pressure files when PSI is disabled.
+fn is_unsupported(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::Unsupported
+        || error.raw_os_error() == Some(nix::libc::EOPNOTSUPP)
+}
+
 fn is_dir(path: impl AsRef<Path>) -> bool {
     std::fs::metadata(path.as_ref()).map_or(false, |metadata| metadata.is_dir())
 }
@@ -438,14 +558,14 @@ mod tests {
     use rand::{rngs::ThreadRng, Rng};
     use similar_asserts::assert_eq;
     use tempfile::TempDir;
-    use vector_lib::event::Metric;
+    use vector_lib::event::{Metric, MetricValue};
 
     use super::{
         super::{
             tests::{count_name, count_tag},
             HostMetrics, HostMetricsConfig,
         },
-        join_name, join_path, MetricsBuffer,
+        join_name, join_path, MetricsBuffer, Pressure, PressureStat,
     };
 
     #[test]
@@ -458,6 +578,42 @@ mod tests {
         assert_eq!(join_path("/sys", "/"), PathBuf::from("/sys"));
     }
 
+    #[test]
+    fn parses_pressure() {
+        let pressure: Pressure = "some avg10=1.50 avg60=0.75 avg300=0.10 total=123456\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=42\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            pressure,
+            Pressure {
+                some: Some(PressureStat {
+                    avg10: 1.5,
+                    avg60: 0.75,
+                    avg300: 0.1,
+                    total: 123456,
+                }),
+                full: Some(PressureStat {
+                    avg10: 0.0,
+                    avg60: 0.0,
+                    avg300: 0.0,
+                    total: 42,
+                }),
+            }
+        );
+
+        // Older kernels don't report the `full` line for the CPU.
+        let pressure: Pressure = "some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"
+            .parse()
+            .unwrap();
+        assert!(pressure.some.is_some());
+        assert_eq!(pressure.full, None);
+
+        assert!("some avg10=x avg60=0.00 avg300=0.00 total=0\n"
+            .parse::<Pressure>()
+            .is_err());
+        assert!("some total\n".parse::<Pressure>().is_err());
+    }
+
     #[tokio::test]
     async fn generates_cgroups_metrics() {
         let config: HostMetricsConfig = toml::from_str(r#"collectors = ["cgroups"]"#).unwrap();
@@ -495,6 +651,47 @@ mod tests {
         base.test().await;
     }
 
+    #[tokio::test]
+    async fn parses_modern_cgroups_pressure() {
+        // Fully v2 cgroups with PSI enabled, where the pressure files are found in all groups
+        // but the root one.
+        let mut base = Setup::new();
+        for subdir in SUBDIRS {
+            base.group(
+                subdir,
+                if subdir == "." {
+                    CPU_STAT | MEMORY_STAT
+                } else {
+                    CPU_STAT | MEMORY_STAT | PRESSURE
+                },
+                Some("cpu memory io\n"),
+            );
+        }
+        let metrics = base.test().await;
+
+        for name in [
+            "cgroup_cpu_pressure_some_avg10",
+            "cgroup_cpu_pressure_some_avg60",
+            "cgroup_cpu_pressure_some_avg300",
+            "cgroup_cpu_pressure_some_seconds_total",
+            "cgroup_cpu_pressure_full_avg10",
+            "cgroup_memory_pressure_some_avg10",
+            "cgroup_memory_pressure_full_seconds_total",
+            "cgroup_io_pressure_some_avg300",
+            "cgroup_io_pressure_full_seconds_total",
+        ] {
+            assert_eq!(count_name(&metrics, name), SUBDIRS.len() - 1, "{name}");
+        }
+        let total = metrics
+            .iter()
+            .find(|metric| metric.name() == "cgroup_io_pressure_some_seconds_total")
+            .unwrap();
+        assert!(matches!(
+            total.value(),
+            MetricValue::Counter { value } if (value - 1.234567).abs() < 1e-9
+        ));
+    }
+
     #[tokio::test]
     async fn parses_hybrid_cgroups_1() {
         // As found on Gentoo, hybrid v1/v2 cgroups:
@@ -561,13 +758,14 @@ mod tests {
     const NONE: usize = 0;
     const CPU_STAT: usize = 1 << 1;
     const MEMORY_STAT: usize = 1 << 2;
+    const PRESSURE: usize = 1 << 3;
 
     impl Setup {
         fn new() -> Self {
             Self(tempfile::tempdir().unwrap(), rand::thread_rng())
         }
 
-        async fn test(&self) {
+        async fn test(&self) -> Vec<Metric> {
             let path = self.0.path();
             let config: HostMetricsConfig = toml::from_str(&format!(
                 r#"
@@ -605,6 +803,8 @@ mod tests {
                 count_name(&metrics, "cgroup_memory_file_bytes"),
                 SUBDIRS.len() - 1
             );
+
+            metrics
         }
 
         fn group(&mut self, subdir: &str, flags: usize, controllers: Option<&str>) {
@@ -618,6 +818,9 @@ mod tests {
             if (flags & MEMORY_STAT) != 0 {
                 self.memory_stat(subdir);
             }
+            if (flags & PRESSURE) != 0 {
+                self.pressure(subdir);
+            }
         }
 
         fn cpu_stat(&mut self, subdir: &str) {
@@ -641,6 +844,16 @@ mod tests {
             );
         }
 
+        fn pressure(&mut self, subdir: &str) {
+            for resource in ["cpu", "memory", "io"] {
+                self.f(
+                    subdir,
+                    &format!("{resource}.pressure"),
+                    "some avg10=0.50 avg60=0.25 avg300=0.05 total=1234567\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n",
+                );
+            }
+        }
+
         fn d(&self, subdir: &str) {
             let path: PathBuf = [self.0.path(), subdir.as_ref()].iter().collect();
             fs::create_dir_all(path).unwrap();
@@ -1,3 +1,5 @@
+#[cfg(target_os = "linux")]
+use std::{collections::HashMap, sync::Mutex};
 use std::{
     path::{Path, PathBuf},
     time::Duration,
@@ -34,6 +36,7 @@ mod disk;
 mod filesystem;
 mod memory;
 mod network;
+mod process;
 
 /// Collector types.
 #[serde_as]
@@ -66,6 +69,11 @@ pub enum Collector {
 
     /// Metrics related to network utilization.
     Network,
+
+    /// Metrics related to the resource usage of the processes.
+    ///
+    /// Only available on Linux, and skipped on the other platforms. Not enabled by default.
+    Process,
 }
 
 /// Filtering configuration.
@@ -98,7 +106,7 @@ pub struct HostMetricsConfig {
 
     /// The list of host metric collector services to use.
     ///
-    /// Defaults to all collectors.
+    /// Defaults to all collectors, except `process`.
     #[configurable(metadata(docs::examples = "example_collectors()"))]
     #[derivative(Default(value = "default_collectors()"))]
     #[serde(default = "default_collectors")]
@@ -125,6 +133,10 @@ pub struct HostMetricsConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub network: network::NetworkConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub process: process::ProcessConfig,
 }
 
 /// Options for the cgroups (controller groups) metrics collector.
@@ -170,7 +182,7 @@ pub fn default_namespace() -> Option<String> {
     Some(String::from("host"))
 }
 
-const fn example_collectors() -> [&'static str; 8] {
+const fn example_collectors() -> [&'static str; 9] {
     [
         "cgroups",
         "cpu",
@@ -180,6 +192,7 @@ const fn example_collectors() -> [&'static str; 8] {
         "host",
         "memory",
         "network",
+        "process",
     ]
 }
 
@@ -317,6 +330,9 @@ pub struct HostMetrics {
     config: HostMetricsConfig,
     #[cfg(target_os = "linux")]
     root_cgroup: Option<cgroups::CGroupRoot>,
+    /// The CPU time of the processes at the previous scrape, by PID.
+    #[cfg(target_os = "linux")]
+    process_cpu_seconds: Mutex<HashMap<u32, f64>>,
     events_received: Registered<EventsReceived>,
 }
 
@@ -336,6 +352,7 @@ impl HostMetrics {
         Self {
             config,
             root_cgroup,
+            process_cpu_seconds: Mutex::default(),
             events_received: register!(EventsReceived),
         }
     }
@@ -373,6 +390,10 @@ impl HostMetrics {
         if self.config.has_collector(Collector::Network) {
             self.network_metrics(&mut buffer).await;
         }
+        #[cfg(target_os = "linux")]
+        if self.config.has_collector(Collector::Process) {
+            self.process_metrics(&mut buffer).await;
+        }
 
         let metrics = buffer.metrics;
         self.events_received.emit(CountByteSize(
@@ -0,0 +1,545 @@
+#[cfg(target_os = "linux")]
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use vector_lib::configurable::configurable_component;
+#[cfg(target_os = "linux")]
+use vector_lib::metric_tags;
+
+#[cfg(target_os = "linux")]
+use crate::internal_events::HostMetricsScrapeDetailError;
+
+use super::PatternWrapper;
+#[cfg(target_os = "linux")]
+use super::{HostMetrics, MetricsBuffer};
+
+/// The resource the processes are ranked by.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TopNBy {
+    /// The CPU time used since the previous scrape.
+    Cpu,
+
+    /// The resident memory.
+    Memory,
+}
+
+/// Options for the process metrics collector.
+///
+/// This collector is only available on Linux, and is skipped on the other platforms.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct ProcessConfig {
+    /// Patterns matched against the names of the processes.
+    ///
+    /// When set, only the processes with a matching name are reported. The name is the one the
+    /// kernel reports, truncated to 15 characters.
+    ///
+    /// The patterns are matched using globbing.
+    #[configurable(metadata(docs::examples = "vector"))]
+    #[configurable(metadata(docs::examples = "postgres*"))]
+    include_process_names: Vec<PatternWrapper>,
+
+    /// Patterns matched against the command lines of the processes, with the arguments
+    /// separated by spaces.
+    ///
+    /// When set, only the processes with a matching command line are reported.
+    ///
+    /// The patterns are matched using globbing.
+    #[configurable(metadata(docs::examples = "*--config /etc/vector/*"))]
+    include_cmdline_patterns: Vec<PatternWrapper>,
+
+    /// The resource the processes are ranked by, to only report the `top_n` first ones.
+    ///
+    /// All the included processes are reported when unset.
+    top_n_by: Option<TopNBy>,
+
+    /// The number of processes reported when `top_n_by` is set.
+    #[derivative(Default(value = "default_top_n()"))]
+    #[configurable(metadata(docs::examples = 5))]
+    top_n: usize,
+
+    /// Whether the metrics are tagged with the PID of the process.
+    ///
+    /// The PIDs change as the processes restart, so disabling the tag bounds the cardinality of
+    /// the metrics. The metrics of the processes with the same name are then summed.
+    #[derivative(Default(value = "true"))]
+    pid_tag: bool,
+}
+
+const fn default_top_n() -> usize {
+    10
+}
+
+#[cfg(target_os = "linux")]
+impl ProcessConfig {
+    fn includes(patterns: &[PatternWrapper], value: &str) -> bool {
+        patterns.is_empty() || patterns.iter().any(|pattern| pattern.matches_str(value))
+    }
+}
+
+/// The resource usage of a process, or of the processes with the same name once summed.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ProcessStats {
+    name: String,
+    pid: Option<u32>,
+    cpu_seconds: f64,
+    /// The CPU time used since the previous scrape, or since the process started on the first
+    /// one.
+    cpu_seconds_delta: f64,
+    resident_memory_bytes: f64,
+    /// The descriptors of the processes of the other users are only listed with enough
+    /// privileges.
+    open_fds: Option<f64>,
+    threads: f64,
+}
+
+#[cfg(target_os = "linux")]
+impl HostMetrics {
+    pub(super) async fn process_metrics(&self, output: &mut MetricsBuffer) {
+        output.name = "process";
+        let config = self.config.process.clone();
+        let proc_dir = procfs_root();
+        let mut processes =
+            match tokio::task::spawn_blocking(move || scan_processes(&proc_dir, &config)).await {
+                Ok(Ok(processes)) => processes,
+                Ok(Err(error)) => {
+                    emit!(HostMetricsScrapeDetailError {
+                        message: "Failed to list the processes.",
+                        error,
+                    });
+                    return;
+                }
+                Err(error) => {
+                    emit!(HostMetricsScrapeDetailError {
+                        message: "Failed to list the processes.",
+                        error,
+                    });
+                    return;
+                }
+            };
+
+        {
+            let mut previous = self
+                .process_cpu_seconds
+                .lock()
+                .expect("the lock is never poisoned");
+            set_cpu_seconds_deltas(&mut processes, &previous);
+            *previous = processes
+                .iter()
+                .filter_map(|process| Some((process.pid?, process.cpu_seconds)))
+                .collect();
+        }
+
+        let config = &self.config.process;
+        if !config.pid_tag {
+            processes = sum_by_name(processes);
+        }
+        if let Some(top_n_by) = config.top_n_by {
+            keep_top_n(&mut processes, top_n_by, config.top_n);
+        }
+
+        for process in processes {
+            let mut tags = metric_tags!("name" => process.name);
+            if let Some(pid) = process.pid {
+                tags.replace("pid".into(), pid.to_string());
+            }
+            output.counter(
+                "process_cpu_seconds_total",
+                process.cpu_seconds,
+                tags.clone(),
+            );
+            output.gauge(
+                "process_resident_memory_bytes",
+                process.resident_memory_bytes,
+                tags.clone(),
+            );
+            if let Some(open_fds) = process.open_fds {
+                output.gauge("process_open_fds", open_fds, tags.clone());
+            }
+            output.gauge("process_threads", process.threads, tags);
+        }
+    }
+}
+
+/// The procfs root, following the one set for `heim` by `init_roots`.
+#[cfg(target_os = "linux")]
+fn procfs_root() -> PathBuf {
+    std::env::var_os("PROCFS_ROOT").map_or_else(|| PathBuf::from("/proc"), PathBuf::from)
+}
+
+#[cfg(target_os = "linux")]
+fn clock_ticks_per_second() -> f64 {
+    // SAFETY: `sysconf` only reads a configuration value of the system.
+    let ticks = unsafe { nix::libc::sysconf(nix::libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as f64
+    } else {
+        // The `USER_HZ` the kernel reports the CPU times in on most architectures.
+        100.0
+    }
+}
+
+/// Reads the included processes from the procfs mounted at `proc_dir`.
+#[cfg(target_os = "linux")]
+fn scan_processes(proc_dir: &Path, config: &ProcessConfig) -> io::Result<Vec<ProcessStats>> {
+    let ticks_per_second = clock_ticks_per_second();
+    let mut processes = Vec::new();
+    for entry in fs::read_dir(proc_dir)? {
+        let Some(pid) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // The processes exiting while they are read are skipped.
+        if let Ok(Some(process)) = read_process(proc_dir, pid, ticks_per_second, config) {
+            processes.push(process);
+        }
+    }
+    Ok(processes)
+}
+
+#[cfg(target_os = "linux")]
+fn read_process(
+    proc_dir: &Path,
+    pid: u32,
+    ticks_per_second: f64,
+    config: &ProcessConfig,
+) -> io::Result<Option<ProcessStats>> {
+    let dir = proc_dir.join(pid.to_string());
+    let Some(process) = parse_stat(&fs::read_to_string(dir.join("stat"))?, ticks_per_second) else {
+        return Ok(None);
+    };
+    if !ProcessConfig::includes(&config.include_process_names, &process.name) {
+        return Ok(None);
+    }
+    if !config.include_cmdline_patterns.is_empty() {
+        let cmdline = parse_cmdline(&fs::read(dir.join("cmdline"))?);
+        if !ProcessConfig::includes(&config.include_cmdline_patterns, &cmdline) {
+            return Ok(None);
+        }
+    }
+
+    let status = fs::read_to_string(dir.join("status"))?;
+    Ok(Some(ProcessStats {
+        pid: Some(pid),
+        // The kernel threads have no resident memory.
+        resident_memory_bytes: parse_resident_memory(&status).unwrap_or_default(),
+        open_fds: fs::read_dir(dir.join("fd"))
+            .ok()
+            .map(|fds| fds.count() as f64),
+        ..process
+    }))
+}
+
+/// Parses the name, the CPU time, and the number of threads out of `/proc/<pid>/stat`.
+///
+/// The name is in parentheses, and may itself contain spaces and parentheses, so the other
+/// fields are split after its last closing parenthesis.
+#[cfg(target_os = "linux")]
+fn parse_stat(stat: &str, ticks_per_second: f64) -> Option<ProcessStats> {
+    let (head, fields) = stat.rsplit_once(')')?;
+    let (_, name) = head.split_once('(')?;
+    let fields: Vec<_> = fields.split_whitespace().collect();
+    // The fields are numbered from 1 in proc(5), the PID and the name being the first two.
+    let field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+    let (utime, stime, threads) = (field(14)?, field(15)?, field(20)?);
+
+    Some(ProcessStats {
+        name: name.to_owned(),
+        cpu_seconds: (utime + stime) as f64 / ticks_per_second,
+        threads: threads as f64,
+        ..Default::default()
+    })
+}
+
+/// Parses the resident memory out of `/proc/<pid>/status`.
+#[cfg(target_os = "linux")]
+fn parse_resident_memory(status: &str) -> Option<f64> {
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes as f64 * 1024.0)
+}
+
+/// Joins the NUL-separated arguments of `/proc/<pid>/cmdline` with spaces.
+#[cfg(target_os = "linux")]
+fn parse_cmdline(cmdline: &[u8]) -> String {
+    String::from_utf8_lossy(cmdline)
+        .split('\0')
+        .filter(|argument| !argument.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Sets the CPU time the processes used since the `previous` scrape.
+///
+/// A process whose CPU time went backwards is a new process reusing the PID.
+#[cfg(target_os = "linux")]
+fn set_cpu_seconds_deltas(processes: &mut [ProcessStats], previous: &HashMap<u32, f64>) {
+    for process in processes {
+        let previous = process
+            .pid
+            .and_then(|pid| previous.get(&pid).copied())
+            .filter(|&previous| previous <= process.cpu_seconds)
+            .unwrap_or_default();
+        process.cpu_seconds_delta = process.cpu_seconds - previous;
+    }
+}
+
+/// Sums the resource usage of the processes with the same name.
+#[cfg(target_os = "linux")]
+fn sum_by_name(processes: Vec<ProcessStats>) -> Vec<ProcessStats> {
+    let mut by_name = BTreeMap::<String, ProcessStats>::new();
+    for process in processes {
+        let sum = by_name
+            .entry(process.name.clone())
+            .or_insert_with(|| ProcessStats {
+                name: process.name,
+                ..Default::default()
+            });
+        sum.cpu_seconds += process.cpu_seconds;
+        sum.cpu_seconds_delta += process.cpu_seconds_delta;
+        sum.resident_memory_bytes += process.resident_memory_bytes;
+        if let Some(open_fds) = process.open_fds {
+            sum.open_fds = Some(sum.open_fds.unwrap_or_default() + open_fds);
+        }
+        sum.threads += process.threads;
+    }
+    by_name.into_values().collect()
+}
+
+/// Keeps the `top_n` processes using the most of the `top_n_by` resource.
+#[cfg(target_os = "linux")]
+fn keep_top_n(processes: &mut Vec<ProcessStats>, top_n_by: TopNBy, top_n: usize) {
+    let usage = |process: &ProcessStats| match top_n_by {
+        TopNBy::Cpu => process.cpu_seconds_delta,
+        TopNBy::Memory => process.resident_memory_bytes,
+    };
+    processes.sort_by(|a, b| usage(b).total_cmp(&usage(a)));
+    processes.truncate(top_n);
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::{
+        super::{tests::count_tag, HostMetricsConfig},
+        *,
+    };
+
+    fn process(name: &str, pid: u32, cpu_seconds_delta: f64, memory: f64) -> ProcessStats {
+        ProcessStats {
+            name: name.into(),
+            pid: Some(pid),
+            cpu_seconds: cpu_seconds_delta * 2.0,
+            cpu_seconds_delta,
+            resident_memory_bytes: memory,
+            open_fds: Some(4.0),
+            threads: 1.0,
+        }
+    }
+
+    fn write_process(proc_dir: &Path, pid: u32, name: &str, cmdline: &[u8]) {
+        let dir = proc_dir.join(pid.to_string());
+        fs::create_dir_all(dir.join("fd")).unwrap();
+        for fd in ["0", "1", "2"] {
+            fs::write(dir.join("fd").join(fd), "").unwrap();
+        }
+        fs::write(
+            dir.join("stat"),
+            format!(
+                "{} ({}) S 1 {} {} 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 3 0 100 1000 200",
+                pid, name, pid, pid
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("status"),
+            format!("Name:\t{}\nVmRSS:\t  2048 kB\n", name),
+        )
+        .unwrap();
+        fs::write(dir.join("cmdline"), cmdline).unwrap();
+    }
+
+    #[test]
+    fn parses_stat() {
+        let stat = "42 (tmux: server (1)) S 1 42 42 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 3 0 \
+                    100 1000 200";
+        let process = parse_stat(stat, 100.0).unwrap();
+        assert_eq!(process.name, "tmux: server (1)");
+        assert_eq!(process.cpu_seconds, 3.0);
+        assert_eq!(process.threads, 3.0);
+
+        assert_eq!(parse_stat("42 (truncated) S 1", 100.0), None);
+    }
+
+    #[test]
+    fn parses_status_and_cmdline() {
+        assert_eq!(
+            parse_resident_memory("Name:\tvector\nVmRSS:\t   1024 kB\nThreads:\t3\n"),
+            Some(1048576.0)
+        );
+        // The kernel threads have no resident memory.
+        assert_eq!(
+            parse_resident_memory("Name:\tkthreadd\nThreads:\t1\n"),
+            None
+        );
+
+        assert_eq!(
+            parse_cmdline(b"vector\0--config\0/etc/vector/vector.yaml\0"),
+            "vector --config /etc/vector/vector.yaml"
+        );
+    }
+
+    #[test]
+    fn computes_cpu_seconds_deltas() {
+        let mut processes = vec![process("a", 1, 0.0, 0.0), process("b", 2, 0.0, 0.0)];
+        processes[0].cpu_seconds = 10.0;
+        processes[1].cpu_seconds = 3.0;
+        // The second PID was reused by a new process.
+        let previous = HashMap::from([(1, 4.0), (2, 5.0)]);
+
+        set_cpu_seconds_deltas(&mut processes, &previous);
+        assert_eq!(processes[0].cpu_seconds_delta, 6.0);
+        assert_eq!(processes[1].cpu_seconds_delta, 3.0);
+    }
+
+    #[test]
+    fn sums_and_ranks_processes() {
+        let processes = vec![
+            process("postgres", 10, 1.0, 300.0),
+            process("vector", 20, 5.0, 100.0),
+            process("postgres", 11, 2.0, 200.0),
+            process("sshd", 30, 0.5, 50.0),
+        ];
+
+        let summed = sum_by_name(processes.clone());
+        assert_eq!(summed.len(), 3);
+        assert_eq!(
+            summed[0],
+            ProcessStats {
+                name: "postgres".into(),
+                pid: None,
+                cpu_seconds: 6.0,
+                cpu_seconds_delta: 3.0,
+                resident_memory_bytes: 500.0,
+                open_fds: Some(8.0),
+                threads: 2.0,
+            }
+        );
+
+        let mut by_cpu = processes;
+        keep_top_n(&mut by_cpu, TopNBy::Cpu, 2);
+        assert_eq!(
+            by_cpu.iter().map(|p| p.pid).collect::<Vec<_>>(),
+            [Some(20), Some(11)]
+        );
+
+        let mut by_memory = summed;
+        keep_top_n(&mut by_memory, TopNBy::Memory, 1);
+        assert_eq!(by_memory[0].name, "postgres");
+    }
+
+    #[test]
+    fn scans_included_processes() {
+        let proc_dir = tempfile::tempdir().unwrap();
+        write_process(proc_dir.path(), 1, "systemd", b"/sbin/init\0");
+        write_process(
+            proc_dir.path(),
+            20,
+            "vector",
+            b"vector\0--config\0/etc/vector/a.yaml\0",
+        );
+        write_process(
+            proc_dir.path(),
+            21,
+            "vector",
+            b"vector\0--config\0/tmp/b.yaml\0",
+        );
+        fs::create_dir(proc_dir.path().join("self")).unwrap();
+
+        let config = |config: &str| toml::from_str::<ProcessConfig>(config).unwrap();
+        let pids = |config: &ProcessConfig| {
+            let mut pids: Vec<_> = scan_processes(proc_dir.path(), config)
+                .unwrap()
+                .into_iter()
+                .filter_map(|process| process.pid)
+                .collect();
+            pids.sort_unstable();
+            pids
+        };
+
+        assert_eq!(pids(&ProcessConfig::default()), [1, 20, 21]);
+        assert_eq!(
+            pids(&config(r#"include_process_names = ["vec*"]"#)),
+            [20, 21]
+        );
+        assert_eq!(
+            pids(&config(
+                r#"include_cmdline_patterns = ["*--config /etc/vector/*"]"#
+            )),
+            [20]
+        );
+
+        let process = scan_processes(proc_dir.path(), &ProcessConfig::default())
+            .unwrap()
+            .into_iter()
+            .find(|process| process.pid == Some(20))
+            .unwrap();
+        assert_eq!(process.name, "vector");
+        assert_eq!(process.resident_memory_bytes, 2097152.0);
+        assert_eq!(process.open_fds, Some(3.0));
+        assert_eq!(process.threads, 3.0);
+    }
+
+    #[tokio::test]
+    async fn generates_process_metrics() {
+        let mut buffer = MetricsBuffer::new(None);
+        HostMetrics::new(HostMetricsConfig::default())
+            .process_metrics(&mut buffer)
+            .await;
+        let metrics = buffer.metrics;
+        assert!(!metrics.is_empty());
+        assert!(metrics
+            .iter()
+            .all(|metric| metric.name().starts_with("process_")));
+        // At least the process of the tests is found.
+        assert!(metrics
+            .iter()
+            .any(|metric| { metric.tag_value("pid") == Some(std::process::id().to_string()) }));
+        assert_eq!(count_tag(&metrics, "name"), metrics.len());
+    }
+
+    #[tokio::test]
+    async fn process_metrics_without_pid_tag() {
+        let mut buffer = MetricsBuffer::new(None);
+        HostMetrics::new(HostMetricsConfig {
+            process: toml::from_str("pid_tag = false\ntop_n_by = \"memory\"\ntop_n = 3").unwrap(),
+            ..Default::default()
+        })
+        .process_metrics(&mut buffer)
+        .await;
+        let metrics = buffer.metrics;
+        assert_eq!(count_tag(&metrics, "pid"), 0);
+        let threads = metrics
+            .iter()
+            .filter(|metric| metric.name() == "process_threads")
+            .count();
+        assert!(threads > 0 && threads <= 3);
+    }
+}
@@ -31,7 +31,10 @@ use crate::{
     register_validatable_component,
     serde::{bool_or_struct, default_decoding},
     sources::util::{
-        http::{add_query_parameters, HttpMethod},
+        http::{
+            add_query_parameters, decode_with_limit, HmacAuth, HmacAuthConfig, HttpMethod,
+            PathTemplate,
+        },
         Encoding, ErrorMessage, HttpSource, HttpSourceAuthConfig,
     },
     tls::TlsEnableableConfig,
@@ -97,13 +100,23 @@ pub struct SimpleHttpConfig {
     /// A list of URL query parameters to include in the log event.
     ///
     /// These override any values included in the body with conflicting names.
+    ///
+    /// The `*` entry includes all of the query parameters of the request as a map, in the
+    /// `query_parameters_key` field.
     #[serde(default)]
     #[configurable(metadata(docs::examples = "application"))]
     #[configurable(metadata(docs::examples = "source"))]
+    #[configurable(metadata(docs::examples = "*"))]
     query_parameters: Vec<String>,
 
+    /// The event key in which all of the query parameters of the request are stored, when
+    /// `query_parameters` contains `*`.
+    #[serde(default = "default_query_parameters_key")]
+    #[configurable(metadata(docs::examples = "query_parameters"))]
+    query_parameters_key: OptionalValuePath,
+
     #[configurable(derived)]
-    auth: Option<HttpSourceAuthConfig>,
+    auth: Option<HttpServerAuthConfig>,
 
     /// Whether or not to treat the configured `path` as an absolute path.
     ///
@@ -116,9 +129,14 @@ pub struct SimpleHttpConfig {
     strict_path: bool,
 
     /// The URL path on which log event POST requests are sent.
+    ///
+    /// Segments of the form `{name}` match any single path segment, which is included in the log
+    /// event in the `name` field. Requests to paths that don't match are rejected with a `404
+    /// Not Found` response.
     #[serde(default = "default_path")]
     #[configurable(metadata(docs::examples = "/event/path"))]
     #[configurable(metadata(docs::examples = "/logs"))]
+    #[configurable(metadata(docs::examples = "/ingest/{tenant}/{dataset}"))]
     path: String,
 
     /// The event key in which the requested URL path used to send the request is stored.
@@ -131,12 +149,27 @@ pub struct SimpleHttpConfig {
     method: HttpMethod,
 
     /// Specifies the HTTP response status code that will be returned on successful requests.
+    ///
+    /// If `response.status_code` is set, it takes precedence over this option.
     #[configurable(metadata(docs::examples = 202))]
     #[configurable(metadata(docs::numeric_type = "uint"))]
     #[serde(with = "http_serde::status_code")]
     #[serde(default = "default_http_response_code")]
     response_code: StatusCode,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    response: HttpServerResponseConfig,
+
+    /// The maximum size, in bytes, of a request body after decompressing it according to its
+    /// `Content-Encoding` header.
+    ///
+    /// Requests whose decompressed body exceeds this size are rejected with a `413 Payload Too
+    /// Large` response. By default, the size of decompressed bodies is not limited.
+    #[configurable(metadata(docs::examples = 10485760))]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_decompressed_size: Option<usize>,
+
     #[configurable(derived)]
     tls: Option<TlsEnableableConfig>,
 
@@ -156,9 +189,70 @@ pub struct SimpleHttpConfig {
     log_namespace: Option<bool>,
 }
 
+/// The response returned to successful requests.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HttpServerResponseConfig {
+    /// The HTTP status code returned on successful requests.
+    ///
+    /// Overrides `response_code` when set.
+    #[configurable(metadata(docs::examples = 204))]
+    status_code: Option<u16>,
+
+    /// The body returned with successful requests.
+    ///
+    /// By default, the response body is empty. It can't be set together with a status code that
+    /// doesn't allow a body, such as `204`.
+    #[configurable(metadata(docs::examples = "{\"status\":\"ok\"}"))]
+    body: Option<String>,
+}
+
+/// HTTP authentication configuration.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(untagged)]
+pub enum HttpServerAuthConfig {
+    /// HTTP Basic authentication.
+    Basic(HttpSourceAuthConfig),
+
+    /// Verification of the HMAC signature of requests.
+    ///
+    /// Requests with a missing or invalid signature are rejected with a `401` status code.
+    Hmac {
+        #[configurable(derived)]
+        hmac: HmacAuthConfig,
+    },
+}
+
+impl HttpServerAuthConfig {
+    fn basic(&self) -> Option<HttpSourceAuthConfig> {
+        match self {
+            Self::Basic(basic) => Some(basic.clone()),
+            Self::Hmac { .. } => None,
+        }
+    }
+
+    fn hmac(&self) -> Option<&HmacAuthConfig> {
+        match self {
+            Self::Basic(_) => None,
+            Self::Hmac { hmac } => Some(hmac),
+        }
+    }
+}
+
 impl SimpleHttpConfig {
     /// Builds the `schema::Definition` for this source using the provided `LogNamespace`.
     fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        let all_query_parameters_key = if self.captures_all_query_parameters() {
+            self.query_parameters_key
+                .path
+                .clone()
+                .map(LegacyKey::Overwrite)
+        } else {
+            None
+        };
+
         let mut schema_definition = self
             .decoding
             .as_ref()
@@ -182,13 +276,24 @@ impl SimpleHttpConfig {
             // for metadata that is added to the events dynamically from the self.query_parameters
             .with_source_metadata(
                 SimpleHttpConfig::NAME,
-                None,
+                all_query_parameters_key,
                 &owned_value_path!("query_parameters"),
                 Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
                 None,
             )
             .with_standard_vector_source_metadata();
 
+        // for metadata that is added to the events dynamically from the captures of self.path
+        if PathTemplate::parse(&self.path).map_or(false, |template| template.has_captures()) {
+            schema_definition = schema_definition.with_source_metadata(
+                SimpleHttpConfig::NAME,
+                None,
+                &owned_value_path!("path_parameters"),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())),
+                None,
+            );
+        }
+
         // for metadata that is added to the events dynamically from config options
         if log_namespace == LogNamespace::Legacy {
             schema_definition = schema_definition.unknown_fields(Kind::bytes());
@@ -197,6 +302,35 @@ impl SimpleHttpConfig {
         schema_definition
     }
 
+    fn captures_all_query_parameters(&self) -> bool {
+        self.query_parameters
+            .iter()
+            .any(|name| name == ALL_QUERY_PARAMETERS)
+    }
+
+    fn get_response(&self) -> crate::Result<(StatusCode, Option<Bytes>)> {
+        let status_code = match self.response.status_code {
+            Some(code) => StatusCode::from_u16(code)
+                .map_err(|_| format!("Invalid `response.status_code` {}.", code))?,
+            None => self.response_code,
+        };
+
+        let body = self.response.body.clone().map(Bytes::from);
+        if body.is_some()
+            && (status_code.is_informational()
+                || status_code == StatusCode::NO_CONTENT
+                || status_code == StatusCode::NOT_MODIFIED)
+        {
+            return Err(format!(
+                "`response.body` can't be set when responding with status code {}.",
+                status_code
+            )
+            .into());
+        }
+
+        Ok((status_code, body))
+    }
+
     fn get_decoding_config(&self) -> crate::Result<DecodingConfig> {
         if self.encoding.is_some() && (self.framing.is_some() || self.decoding.is_some()) {
             return Err("Using `encoding` is deprecated and does not have any effect when `decoding` or `framing` is provided. Configure `framing` and `decoding` instead.".into());
@@ -245,12 +379,15 @@ impl Default for SimpleHttpConfig {
             encoding: None,
             headers: Vec::new(),
             query_parameters: Vec::new(),
+            query_parameters_key: default_query_parameters_key(),
             tls: None,
             auth: None,
             path: default_path(),
             path_key: default_path_key(),
             method: default_http_method(),
             response_code: default_http_response_code(),
+            response: HttpServerResponseConfig::default(),
+            max_decompressed_size: None,
             strict_path: true,
             framing: None,
             decoding: Some(default_decoding()),
@@ -298,6 +435,13 @@ fn default_path_key() -> OptionalValuePath {
     OptionalValuePath::from(owned_value_path!("path"))
 }
 
+fn default_query_parameters_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("query_parameters"))
+}
+
+/// The `query_parameters` entry that includes all of the query parameters.
+const ALL_QUERY_PARAMETERS: &str = "*";
+
 const fn default_http_response_code() -> StatusCode {
     StatusCode::OK
 }
@@ -328,23 +472,42 @@ fn remove_duplicates(mut list: Vec<String>, list_name: &str) -> Vec<String> {
 impl SourceConfig for SimpleHttpConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let decoder = self.get_decoding_config()?.build()?;
+        let (response_code, response_body) = self.get_response()?;
         let log_namespace = cx.log_namespace(self.log_namespace);
 
+        let path_template = PathTemplate::parse(&self.path)?;
+        let hmac_auth = self
+            .auth
+            .as_ref()
+            .and_then(HttpServerAuthConfig::hmac)
+            .map(HmacAuth::try_from)
+            .transpose()?;
+
+        let mut query_parameters =
+            remove_duplicates(self.query_parameters.clone(), "query_parameters");
+        query_parameters.retain(|name| name != ALL_QUERY_PARAMETERS);
+
         let source = SimpleHttpSource {
             headers: remove_duplicates(self.headers.clone(), "headers"),
-            query_parameters: remove_duplicates(self.query_parameters.clone(), "query_parameters"),
+            query_parameters,
+            all_query_parameters: self.captures_all_query_parameters(),
+            query_parameters_key: self.query_parameters_key.clone(),
             path_key: self.path_key.clone(),
+            path_template: path_template.has_captures().then_some(path_template),
             decoder,
+            max_decompressed_size: self.max_decompressed_size,
+            response_body,
+            hmac_auth,
             log_namespace,
         };
         source.run(
             self.address,
             self.path.as_str(),
             self.method,
-            self.response_code,
+            response_code,
             self.strict_path,
             &self.tls,
-            &self.auth,
+            &self.auth.as_ref().and_then(HttpServerAuthConfig::basic),
             cx,
             self.acknowledgements,
         )
@@ -379,12 +542,33 @@ impl SourceConfig for SimpleHttpConfig {
 struct SimpleHttpSource {
     headers: Vec<String>,
     query_parameters: Vec<String>,
+    all_query_parameters: bool,
+    query_parameters_key: OptionalValuePath,
     path_key: OptionalValuePath,
+    path_template: Option<PathTemplate>,
     decoder: Decoder,
+    max_decompressed_size: Option<usize>,
+    response_body: Option<Bytes>,
+    hmac_auth: Option<HmacAuth>,
     log_namespace: LogNamespace,
 }
 
 impl HttpSource for SimpleHttpSource {
+    fn decode(&self, encoding_header: Option<&str>, body: Bytes) -> Result<Bytes, ErrorMessage> {
+        decode_with_limit(encoding_header, body, self.max_decompressed_size)
+    }
+
+    fn verify(&self, headers: &HeaderMap, body: &Bytes) -> Result<(), ErrorMessage> {
+        match &self.hmac_auth {
+            Some(hmac_auth) => hmac_auth.verify(headers, body),
+            None => Ok(()),
+        }
+    }
+
+    fn response_body(&self) -> Option<Bytes> {
+        self.response_body.clone()
+    }
+
     /// Enriches the log events with metadata for the `request_path` and for each of the headers.
     /// Non-log events are skipped.
     fn enrich_events(
@@ -395,6 +579,11 @@ impl HttpSource for SimpleHttpSource {
         query_parameters: &HashMap<String, String>,
     ) {
         let now = Utc::now();
+        let path_captures = self
+            .path_template
+            .as_ref()
+            .and_then(|template| template.captures(request_path))
+            .unwrap_or_default();
         for event in events.iter_mut() {
             match event {
                 Event::Log(log) => {
@@ -407,6 +596,17 @@ impl HttpSource for SimpleHttpSource {
                         request_path.to_owned(),
                     );
 
+                    // add each path capture to each event
+                    for (name, value) in &path_captures {
+                        self.log_namespace.insert_source_metadata(
+                            SimpleHttpConfig::NAME,
+                            log,
+                            Some(LegacyKey::InsertIfEmpty(path!(*name))),
+                            path!("path_parameters", *name),
+                            value.clone(),
+                        );
+                    }
+
                     // add each header to each event
                     for header_name in &self.headers {
                         let value = headers_config.get(header_name).map(HeaderValue::as_bytes);
@@ -439,6 +639,29 @@ impl HttpSource for SimpleHttpSource {
             self.log_namespace,
             SimpleHttpConfig::NAME,
         );
+
+        if self.all_query_parameters {
+            let value = Value::Object(
+                query_parameters
+                    .iter()
+                    .map(|(name, value)| (name.as_str().into(), Value::from(value.as_str())))
+                    .collect(),
+            );
+            for event in events.iter_mut() {
+                if let Event::Log(log) = event {
+                    self.log_namespace.insert_source_metadata(
+                        SimpleHttpConfig::NAME,
+                        log,
+                        self.query_parameters_key
+                            .path
+                            .as_ref()
+                            .map(LegacyKey::Overwrite),
+                        path!("query_parameters"),
+                        value.clone(),
+                    );
+                }
+            }
+        }
     }
 
     fn build_events(
@@ -485,6 +708,7 @@ mod tests {
     };
     use futures::Stream;
     use http::{HeaderMap, Method, StatusCode};
+    use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
     use similar_asserts::assert_eq;
     use vector_lib::codecs::{
         decoding::{DeserializerConfig, FramingConfig},
@@ -508,7 +732,10 @@ mod tests {
         SourceSender,
     };
 
-    use super::{remove_duplicates, SimpleHttpConfig};
+    use super::{
+        remove_duplicates, HmacAuthConfig, HttpServerAuthConfig, HttpServerResponseConfig,
+        SimpleHttpConfig,
+    };
 
     #[test]
     fn generate_config() {
@@ -546,7 +773,10 @@ mod tests {
                 headers,
                 encoding: None,
                 query_parameters,
+                query_parameters_key: super::default_query_parameters_key(),
                 response_code,
+                response: Default::default(),
+                max_decompressed_size: None,
                 tls: None,
                 auth: None,
                 strict_path,
@@ -568,6 +798,25 @@ mod tests {
         (recv, address)
     }
 
+    async fn source_with_config(
+        config: SimpleHttpConfig,
+    ) -> (impl Stream<Item = Event>, SocketAddr) {
+        let (sender, recv) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+
+        tokio::spawn(async move {
+            SimpleHttpConfig { address, ..config }
+                .build(context)
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
     async fn send(address: SocketAddr, body: &str) -> u16 {
         reqwest::Client::new()
             .post(&format!("http://{}/", address))
@@ -1057,6 +1306,107 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn http_all_query_parameters() {
+        let mut events = assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let (rx, addr) = source_with_config(SimpleHttpConfig {
+                query_parameters: vec!["*".to_string(), "source".to_string()],
+                decoding: Some(JsonDeserializerConfig::default().into()),
+                ..Default::default()
+            })
+            .await;
+
+            spawn_ok_collect_n(
+                send_with_query(addr, "{\"key1\":\"value1\"}", "source=staging&region=gb"),
+                rx,
+                1,
+            )
+            .await
+        })
+        .await;
+
+        {
+            let event = events.remove(0);
+            let log = event.as_log();
+            assert_eq!(log["key1"], "value1".into());
+            assert_eq!(log["source"], "staging".into());
+            assert_eq!(log["query_parameters.source"], "staging".into());
+            assert_eq!(log["query_parameters.region"], "gb".into());
+        }
+    }
+
+    #[tokio::test]
+    async fn http_path_captures() {
+        let mut events = assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let (rx, addr) = source_with_config(SimpleHttpConfig {
+                path: "/ingest/{tenant}/{dataset}".to_string(),
+                decoding: Some(JsonDeserializerConfig::default().into()),
+                ..Default::default()
+            })
+            .await;
+
+            spawn_ok_collect_n(
+                send_with_path(addr, "{\"key1\":\"value1\"}", "/ingest/acme/web%20logs"),
+                rx,
+                1,
+            )
+            .await
+        })
+        .await;
+
+        {
+            let event = events.remove(0);
+            let log = event.as_log();
+            assert_eq!(log["key1"], "value1".into());
+            assert_eq!(log["tenant"], "acme".into());
+            assert_eq!(log["dataset"], "web logs".into());
+            assert_eq!(log["path"], "/ingest/acme/web%20logs".into());
+        }
+    }
+
+    #[tokio::test]
+    async fn http_path_captures_not_found() {
+        components::init_test();
+        let (_rx, addr) = source_with_config(SimpleHttpConfig {
+            path: "/ingest/{tenant}/{dataset}".to_string(),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(404, send_with_path(addr, "test body", "/ingest/acme").await);
+        assert_eq!(
+            404,
+            send_with_path(addr, "test body", "/ingest/acme/web/extra").await
+        );
+        assert_eq!(
+            404,
+            send_with_path(addr, "test body", "/other/acme/web").await
+        );
+    }
+
+    #[test]
+    fn output_schema_definition_path_captures() {
+        let config = SimpleHttpConfig {
+            path: "/ingest/{tenant}".to_string(),
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definitions = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true)
+            .unwrap();
+
+        assert_eq!(
+            definitions.metadata_kind().at_path(&owned_value_path!(
+                SimpleHttpConfig::NAME,
+                "path_parameters"
+            )),
+            Kind::object(Collection::empty().with_unknown(Kind::bytes()))
+        );
+    }
+
     #[tokio::test]
     async fn http_gzip_deflate() {
         let mut events = assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
@@ -1257,6 +1607,173 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn http_response_body() {
+        components::init_test();
+        let (rx, addr) = source_with_config(SimpleHttpConfig {
+            response: HttpServerResponseConfig {
+                status_code: Some(202),
+                body: Some(r#"{"status":"ok"}"#.to_owned()),
+            },
+            ..Default::default()
+        })
+        .await;
+
+        spawn_collect_n(
+            async move {
+                let response = reqwest::Client::new()
+                    .post(&format!("http://{}/", addr))
+                    .body("test body")
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(202, response.status().as_u16());
+                assert_eq!(r#"{"status":"ok"}"#, response.text().await.unwrap());
+            },
+            rx,
+            1,
+        )
+        .await;
+    }
+
+    #[test]
+    fn rejects_response_body_without_content() {
+        let config = SimpleHttpConfig {
+            response: HttpServerResponseConfig {
+                status_code: Some(204),
+                body: Some("ok".to_owned()),
+            },
+            ..Default::default()
+        };
+        assert!(config.get_response().is_err());
+
+        let config = SimpleHttpConfig {
+            response: HttpServerResponseConfig {
+                status_code: Some(204),
+                body: None,
+            },
+            ..Default::default()
+        };
+        assert_eq!(config.get_response().unwrap().0, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn http_max_decompressed_size() {
+        components::init_test();
+        let (rx, addr) = source_with_config(SimpleHttpConfig {
+            max_decompressed_size: Some(16),
+            ..Default::default()
+        })
+        .await;
+
+        let gzip = |body: &str| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body.as_bytes()).unwrap();
+            encoder.finish().unwrap()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("Content-Encoding", "gzip".parse().unwrap());
+
+        assert_eq!(
+            413,
+            send_bytes(addr, gzip(&"x".repeat(17)), headers.clone()).await
+        );
+
+        let mut events =
+            spawn_ok_collect_n(send_bytes(addr, gzip("test body"), headers), rx, 1).await;
+        assert_eq!(
+            *events.remove(0).as_log().get_message().unwrap(),
+            "test body".into()
+        );
+    }
+
+    #[test]
+    fn parses_auth_config() {
+        let config = toml::from_str::<SimpleHttpConfig>(
+            r#"
+            auth.username = "user"
+            auth.password = "pass"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.auth, Some(HttpServerAuthConfig::Basic(_))));
+
+        let config = toml::from_str::<SimpleHttpConfig>(
+            r#"
+            auth.hmac.header = "X-Hub-Signature-256"
+            auth.hmac.secret = "secret"
+            auth.hmac.algorithm = "sha256"
+            auth.hmac.prefix = "sha256="
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.auth,
+            Some(HttpServerAuthConfig::Hmac { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn http_hmac_auth() {
+        components::init_test();
+        let (mut rx, addr) = source_with_config(SimpleHttpConfig {
+            auth: Some(HttpServerAuthConfig::Hmac {
+                hmac: HmacAuthConfig {
+                    header: "X-Hub-Signature-256".to_owned(),
+                    secret: "It's a Secret to Everybody".to_owned().into(),
+                    algorithm: Default::default(),
+                    prefix: Some("sha256=".to_owned()),
+                },
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        let signed = |signature: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("X-Hub-Signature-256", signature.parse().unwrap());
+            headers
+        };
+
+        // The example payload and signature of the GitHub webhooks documentation.
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        assert_eq!(401, send(addr, "Hello, World!").await);
+        assert_eq!(
+            401,
+            send_with_headers(addr, "Hello, World?", signed(signature)).await
+        );
+
+        let mut events = spawn_ok_collect_n(
+            send_with_headers(addr, "Hello, World!", signed(signature)),
+            &mut rx,
+            1,
+        )
+        .await;
+        assert_eq!(
+            *events.remove(0).as_log().get_message().unwrap(),
+            "Hello, World!".into()
+        );
+
+        // The signature covers the raw body, before it is decompressed.
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"compressed body").unwrap();
+        let body = encoder.finish().unwrap();
+        let key = PKey::hmac(b"It's a Secret to Everybody").unwrap();
+        let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        signer.update(&body).unwrap();
+        let mut headers = signed(&format!(
+            "sha256={}",
+            hex::encode(signer.sign_to_vec().unwrap())
+        ));
+        headers.insert("Content-Encoding", "gzip".parse().unwrap());
+
+        let mut events = spawn_ok_collect_n(send_bytes(addr, body, headers), &mut rx, 1).await;
+        assert_eq!(
+            *events.remove(0).as_log().get_message().unwrap(),
+            "compressed body".into()
+        );
+    }
+
     #[tokio::test]
     async fn http_delivery_failure() {
         assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
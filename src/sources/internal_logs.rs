@@ -1,10 +1,16 @@
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
+
 use chrono::Utc;
 use futures::{stream, StreamExt};
 use vector_lib::codecs::BytesDeserializerConfig;
 use vector_lib::config::log_schema;
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::lookup_v2::OptionalValuePath;
-use vector_lib::lookup::{owned_value_path, path, OwnedValuePath};
+use vector_lib::lookup::{event_path, owned_value_path, path, OwnedValuePath};
 use vector_lib::{
     config::{LegacyKey, LogNamespace},
     schema::Definition,
@@ -13,7 +19,7 @@ use vrl::value::Kind;
 
 use crate::{
     config::{DataType, SourceConfig, SourceContext, SourceOutput},
-    event::{EstimatedJsonEncodedSizeOf, Event},
+    event::{EstimatedJsonEncodedSizeOf, Event, LogEvent},
     internal_events::{InternalLogsBytesReceived, InternalLogsEventsReceived, StreamClosedError},
     shutdown::ShutdownSignal,
     trace::TraceSubscription,
@@ -46,12 +52,73 @@ pub struct InternalLogsConfig {
     #[serde(default = "default_pid_key")]
     pid_key: OptionalValuePath,
 
+    /// The minimum level of the internal logs to forward.
+    ///
+    /// The internal logs below this level are dropped by the source. Only the internal logs
+    /// enabled for Vector itself, as with `VECTOR_LOG`, can be forwarded.
+    #[serde(default)]
+    min_level: LogLevel,
+
+    /// The IDs of the components whose internal logs are dropped.
+    ///
+    /// Matched against the `vector.component_id` field of the internal logs.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "my_sink"))]
+    exclude_components: Vec<String>,
+
+    /// The maximum number of internal logs forwarded per second for each component.
+    ///
+    /// Once a component goes over the limit, the rest of its internal logs are dropped until the
+    /// second ends, and an event reporting how many of them were suppressed is sent then. The
+    /// internal logs emitted outside of any component share a single limit.
+    ///
+    /// By default, the internal logs aren't rate limited.
+    #[configurable(metadata(docs::type_unit = "events"))]
+    rate_limit_per_component: Option<NonZeroU32>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
     log_namespace: Option<bool>,
 }
 
+/// The level of an internal log.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// The `TRACE` level, and above.
+    #[default]
+    Trace,
+
+    /// The `DEBUG` level, and above.
+    Debug,
+
+    /// The `INFO` level, and above.
+    Info,
+
+    /// The `WARN` level, and above.
+    Warn,
+
+    /// The `ERROR` level.
+    Error,
+}
+
+impl LogLevel {
+    /// The level of the internal log, from its `metadata.level` field.
+    fn of(log: &LogEvent) -> Option<Self> {
+        let level = log.get(event_path!("metadata", "level"))?.as_bytes()?;
+        match level.as_ref() {
+            b"TRACE" => Some(Self::Trace),
+            b"DEBUG" => Some(Self::Debug),
+            b"INFO" => Some(Self::Info),
+            b"WARN" => Some(Self::Warn),
+            b"ERROR" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
 fn default_host_key() -> OptionalValuePath {
     log_schema().host_key().cloned().into()
 }
@@ -67,12 +134,23 @@ impl Default for InternalLogsConfig {
         InternalLogsConfig {
             host_key: default_host_key(),
             pid_key: default_pid_key(),
+            min_level: LogLevel::default(),
+            exclude_components: Vec::new(),
+            rate_limit_per_component: None,
             log_namespace: None,
         }
     }
 }
 
 impl InternalLogsConfig {
+    fn filter(&self) -> LogFilter {
+        LogFilter {
+            min_level: self.min_level,
+            exclude_components: self.exclude_components.iter().cloned().collect(),
+            rate_limiter: self.rate_limit_per_component.map(RateLimiter::new),
+        }
+    }
+
     /// Generates the `schema::Definition` for this component.
     fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
         let host_key = self.host_key.clone().path.map(LegacyKey::Overwrite);
@@ -114,6 +192,7 @@ impl SourceConfig for InternalLogsConfig {
         Ok(Box::pin(run(
             host_key,
             pid_key,
+            self.filter(),
             subscription,
             cx.out,
             cx.shutdown,
@@ -133,9 +212,142 @@ impl SourceConfig for InternalLogsConfig {
     }
 }
 
+/// The window the internal logs of a component are rate limited over.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Drops the internal logs before they are sent, so that they don't load the topology.
+struct LogFilter {
+    min_level: LogLevel,
+    exclude_components: HashSet<String>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl LogFilter {
+    /// Returns the logs to send for the received log: the log itself if it isn't dropped,
+    /// preceded by the suppression summary of its component if its rate limit window just ended.
+    fn process(&mut self, log: LogEvent, now: Instant) -> Vec<LogEvent> {
+        if LogLevel::of(&log).map_or(false, |level| level < self.min_level) {
+            return Vec::new();
+        }
+
+        let component_id = log
+            .get(event_path!("vector", "component_id"))
+            .map(|id| id.to_string_lossy().into_owned());
+        if component_id
+            .as_ref()
+            .map_or(false, |id| self.exclude_components.contains(id))
+        {
+            return Vec::new();
+        }
+
+        let Some(rate_limiter) = &mut self.rate_limiter else {
+            return vec![log];
+        };
+        let (allowed, summary) = rate_limiter.check(component_id, now);
+        summary.into_iter().chain(allowed.then_some(log)).collect()
+    }
+
+    /// Returns the suppression summaries of the rate limit windows that ended.
+    fn flush(&mut self, now: Instant) -> Vec<LogEvent> {
+        self.rate_limiter
+            .as_mut()
+            .map(|rate_limiter| rate_limiter.flush(now))
+            .unwrap_or_default()
+    }
+}
+
+/// Limits the number of internal logs of each component within a window.
+struct RateLimiter {
+    limit: u32,
+    windows: HashMap<Option<String>, RateLimitWindow>,
+}
+
+struct RateLimitWindow {
+    start: Instant,
+    count: u32,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    fn new(limit: NonZeroU32) -> Self {
+        Self {
+            limit: limit.get(),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Counts an internal log of the component, returning whether it is allowed, and the
+    /// suppression summary of the previous window of the component if it just ended.
+    fn check(&mut self, component_id: Option<String>, now: Instant) -> (bool, Option<LogEvent>) {
+        let window = self
+            .windows
+            .entry(component_id.clone())
+            .or_insert(RateLimitWindow {
+                start: now,
+                count: 0,
+                suppressed: 0,
+            });
+
+        let mut summary = None;
+        if now.duration_since(window.start) >= RATE_LIMIT_WINDOW {
+            if window.suppressed > 0 {
+                summary = Some(suppressed_event(component_id, window.suppressed));
+            }
+            *window = RateLimitWindow {
+                start: now,
+                count: 0,
+                suppressed: 0,
+            };
+        }
+
+        if window.count < self.limit {
+            window.count += 1;
+            (true, summary)
+        } else {
+            window.suppressed = window.suppressed.saturating_add(1);
+            (false, summary)
+        }
+    }
+
+    /// Forgets the windows that ended, returning the suppression summaries of those that
+    /// suppressed internal logs.
+    fn flush(&mut self, now: Instant) -> Vec<LogEvent> {
+        let mut summaries = Vec::new();
+        self.windows.retain(|component_id, window| {
+            if now.duration_since(window.start) < RATE_LIMIT_WINDOW {
+                return true;
+            }
+            if window.suppressed > 0 {
+                summaries.push(suppressed_event(component_id.clone(), window.suppressed));
+            }
+            false
+        });
+        summaries
+    }
+}
+
+/// Creates the event reporting the internal logs of the component suppressed by the rate limit.
+fn suppressed_event(component_id: Option<String>, suppressed: u32) -> LogEvent {
+    let mut log = LogEvent::from(format!("{} messages suppressed.", suppressed));
+    log.insert(event_path!("timestamp"), Utc::now());
+    log.insert(event_path!("metadata", "kind"), "event");
+    log.insert(event_path!("metadata", "level"), "WARN");
+    log.insert(
+        event_path!("metadata", "module_path"),
+        module_path!().to_owned(),
+    );
+    log.insert(event_path!("metadata", "target"), module_path!().to_owned());
+    log.insert(event_path!("suppressed"), suppressed);
+    if let Some(component_id) = component_id {
+        log.insert(event_path!("vector", "component_id"), component_id);
+    }
+    log
+}
+
 async fn run(
     host_key: Option<OwnedValuePath>,
     pid_key: Option<OwnedValuePath>,
+    mut filter: LogFilter,
     mut subscription: TraceSubscription,
     mut out: SourceSender,
     shutdown: ShutdownSignal,
@@ -151,50 +363,63 @@ async fn run(
         .chain(subscription.into_stream())
         .take_until(shutdown);
 
+    let rate_limited = filter.rate_limiter.is_some();
+    let mut flush_interval = tokio::time::interval(RATE_LIMIT_WINDOW);
+
     // Note: This loop, or anything called within it, MUST NOT generate
     // any logs that don't break the loop, as that could cause an
     // infinite loop since it receives all such logs.
-    while let Some(mut log) = rx.next().await {
-        // TODO: Should this actually be in memory size?
-        let byte_size = log.estimated_json_encoded_size_of().get();
-        let json_byte_size = log.estimated_json_encoded_size_of();
-        // This event doesn't emit any log
-        emit!(InternalLogsBytesReceived { byte_size });
-        emit!(InternalLogsEventsReceived {
-            count: 1,
-            byte_size: json_byte_size,
-        });
+    loop {
+        let logs = tokio::select! {
+            log = rx.next() => match log {
+                Some(log) => filter.process(log, Instant::now()),
+                None => break,
+            },
+            _ = flush_interval.tick(), if rate_limited => filter.flush(Instant::now()),
+        };
+
+        for mut log in logs {
+            // TODO: Should this actually be in memory size?
+            let byte_size = log.estimated_json_encoded_size_of().get();
+            let json_byte_size = log.estimated_json_encoded_size_of();
+            // This event doesn't emit any log
+            emit!(InternalLogsBytesReceived { byte_size });
+            emit!(InternalLogsEventsReceived {
+                count: 1,
+                byte_size: json_byte_size,
+            });
+
+            if let Ok(hostname) = &hostname {
+                let legacy_host_key = host_key.as_ref().map(LegacyKey::Overwrite);
+                log_namespace.insert_source_metadata(
+                    InternalLogsConfig::NAME,
+                    &mut log,
+                    legacy_host_key,
+                    path!("host"),
+                    hostname.to_owned(),
+                );
+            }
 
-        if let Ok(hostname) = &hostname {
-            let legacy_host_key = host_key.as_ref().map(LegacyKey::Overwrite);
+            let legacy_pid_key = pid_key.as_ref().map(LegacyKey::Overwrite);
             log_namespace.insert_source_metadata(
                 InternalLogsConfig::NAME,
                 &mut log,
-                legacy_host_key,
-                path!("host"),
-                hostname.to_owned(),
+                legacy_pid_key,
+                path!("pid"),
+                pid,
             );
-        }
-
-        let legacy_pid_key = pid_key.as_ref().map(LegacyKey::Overwrite);
-        log_namespace.insert_source_metadata(
-            InternalLogsConfig::NAME,
-            &mut log,
-            legacy_pid_key,
-            path!("pid"),
-            pid,
-        );
 
-        log_namespace.insert_standard_vector_source_metadata(
-            &mut log,
-            InternalLogsConfig::NAME,
-            Utc::now(),
-        );
+            log_namespace.insert_standard_vector_source_metadata(
+                &mut log,
+                InternalLogsConfig::NAME,
+                Utc::now(),
+            );
 
-        if (out.send_event(Event::from(log)).await).is_err() {
-            // this wont trigger any infinite loop considering it stops the component
-            emit!(StreamClosedError { count: 1 });
-            return Err(());
+            if (out.send_event(Event::from(log)).await).is_err() {
+                // this wont trigger any infinite loop considering it stops the component
+                emit!(StreamClosedError { count: 1 });
+                return Err(());
+            }
         }
     }
 
@@ -224,6 +449,75 @@ mod tests {
         crate::test_util::test_generate_config::<InternalLogsConfig>();
     }
 
+    fn log(level: &str, component_id: Option<&str>) -> LogEvent {
+        let mut log = LogEvent::from("message");
+        log.insert(event_path!("metadata", "level"), level);
+        if let Some(component_id) = component_id {
+            log.insert(event_path!("vector", "component_id"), component_id);
+        }
+        log
+    }
+
+    fn filter(config: &str) -> LogFilter {
+        toml::from_str::<InternalLogsConfig>(config)
+            .unwrap()
+            .filter()
+    }
+
+    #[test]
+    fn filters_by_level_and_component() {
+        let mut filter = filter(
+            r#"
+            min_level = "warn"
+            exclude_components = ["noisy_sink"]
+            "#,
+        );
+        let now = Instant::now();
+
+        assert!(filter
+            .process(log("DEBUG", Some("my_sink")), now)
+            .is_empty());
+        assert!(filter.process(log("INFO", None), now).is_empty());
+        assert_eq!(filter.process(log("WARN", Some("my_sink")), now).len(), 1);
+        assert_eq!(filter.process(log("ERROR", None), now).len(), 1);
+        assert!(filter
+            .process(log("ERROR", Some("noisy_sink")), now)
+            .is_empty());
+    }
+
+    #[test]
+    fn rate_limits_per_component() {
+        let mut filter = filter("rate_limit_per_component = 2");
+        let start = Instant::now();
+
+        let sent: Vec<_> = (0..5)
+            .map(|_| filter.process(log("DEBUG", Some("my_sink")), start).len())
+            .collect();
+        assert_eq!(sent, [1, 1, 0, 0, 0]);
+        // The other components, and the logs outside of any component, have their own limit.
+        assert_eq!(filter.process(log("DEBUG", Some("other")), start).len(), 1);
+        assert_eq!(filter.process(log("DEBUG", None), start).len(), 1);
+
+        // The first log of the next window comes with the summary of the previous one.
+        let logs = filter.process(log("DEBUG", Some("my_sink")), start + RATE_LIMIT_WINDOW);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0]["message"], "3 messages suppressed.".into());
+        assert_eq!(logs[0]["suppressed"], 3.into());
+        assert_eq!(logs[0]["metadata.level"], "WARN".into());
+        assert_eq!(logs[0]["vector.component_id"], "my_sink".into());
+        assert_eq!(logs[1]["message"], "message".into());
+
+        // The summaries are also sent once the window ends without any further logs.
+        for _ in 0..3 {
+            filter.process(log("DEBUG", Some("other")), start + RATE_LIMIT_WINDOW);
+        }
+        let summaries = filter.flush(start + RATE_LIMIT_WINDOW * 2);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0]["message"], "1 messages suppressed.".into());
+        assert_eq!(summaries[0]["vector.component_id"], "other".into());
+        assert!(filter.flush(start + RATE_LIMIT_WINDOW * 3).is_empty());
+    }
+
     // This test is fairly overloaded with different cases.
     //
     // Unfortunately, this can't be easily split out into separate test
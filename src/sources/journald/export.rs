@@ -0,0 +1,219 @@
+//! Decoding of the [journal export format][export-format], as served by
+//! `systemd-journal-gatewayd`.
+//!
+//! [export-format]: https://systemd.io/JOURNAL_EXPORT_FORMATS/#journal-export-format
+
+use std::{collections::HashMap, io};
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Decoder;
+use vector_lib::codecs::decoding::BoxedFramingError;
+
+use super::Record;
+
+/// The length of the little-endian length prefix of binary field values.
+const LENGTH_PREFIX_SIZE: usize = 8;
+
+/// The maximum length of a field value, matching `DATA_SIZE_MAX` of `systemd-journald`.
+const MAX_FIELD_LENGTH: u64 = 768 * 1024 * 1024;
+
+/// Frames the journal export format into one frame per journal entry.
+///
+/// Entries are terminated by an empty line. Field values that aren't printable
+/// are serialized in the binary form, `<name>\n<le64 length><data>\n`, and may
+/// contain empty lines themselves, so the fields have to be walked to find the
+/// end of the entry.
+#[derive(Debug, Default)]
+pub(super) struct ExportFramer {
+    /// The number of bytes of the current entry already walked.
+    scanned: usize,
+}
+
+impl Decoder for ExportFramer {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            let rest = &src[self.scanned..];
+            let Some(newline) = rest.iter().position(|&b| b == b'\n') else {
+                return Ok(None);
+            };
+
+            if newline == 0 {
+                let entry = src.split_to(self.scanned + 1).freeze();
+                self.scanned = 0;
+                // Skip the stray empty lines between entries.
+                if entry.len() > 1 {
+                    return Ok(Some(entry.slice(..entry.len() - 1)));
+                }
+                continue;
+            }
+
+            if rest[..newline].contains(&b'=') {
+                self.scanned += newline + 1;
+                continue;
+            }
+
+            match binary_field_length(&rest[newline + 1..])? {
+                Some(length) => {
+                    let field_length = newline + 1 + LENGTH_PREFIX_SIZE + length + 1;
+                    if rest.len() < field_length {
+                        return Ok(None);
+                    }
+                    if rest[field_length - 1] != b'\n' {
+                        return Err(
+                            invalid_data("binary field is not terminated by a newline").into()
+                        );
+                    }
+                    self.scanned += field_length;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Parses a journal entry framed by [`ExportFramer`] into a record.
+///
+/// Fields that appear more than once in the entry are collected into a JSON
+/// array, the same way they end up after decoding the output of
+/// `journalctl --output=json`, so the records don't depend on where they are
+/// read from.
+pub(super) fn decode_export_record(mut entry: &[u8]) -> io::Result<Record> {
+    let mut fields = HashMap::<String, Vec<String>>::new();
+
+    while !entry.is_empty() {
+        let newline = entry
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(entry.len());
+        let line = &entry[..newline];
+
+        let (name, value, field_length) = match line.iter().position(|&b| b == b'=') {
+            Some(equals) => (&line[..equals], &line[equals + 1..], newline + 1),
+            None => {
+                let length = binary_field_length(&entry[(newline + 1).min(entry.len())..])?
+                    .ok_or_else(|| invalid_data("truncated binary field"))?;
+                let start = newline + 1 + LENGTH_PREFIX_SIZE;
+                let value = entry
+                    .get(start..start + length)
+                    .ok_or_else(|| invalid_data("truncated binary field"))?;
+                (line, value, start + length + 1)
+            }
+        };
+
+        fields
+            .entry(String::from_utf8_lossy(name).into_owned())
+            .or_default()
+            .push(String::from_utf8_lossy(value).into_owned());
+
+        entry = entry.get(field_length..).unwrap_or_default();
+    }
+
+    Ok(fields
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                serde_json::to_string(&values).expect("strings always serialize")
+            };
+            (name, value)
+        })
+        .collect())
+}
+
+/// Reads the length prefix of a binary field value, if it is complete.
+fn binary_field_length(src: &[u8]) -> io::Result<Option<usize>> {
+    let Some(prefix) = src.get(..LENGTH_PREFIX_SIZE) else {
+        return Ok(None);
+    };
+    let length = u64::from_le_bytes(prefix.try_into().expect("prefix has the right size"));
+    if length > MAX_FIELD_LENGTH {
+        return Err(invalid_data("binary field is too long"));
+    }
+    Ok(Some(length as usize))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_field(name: &str, value: &[u8]) -> Vec<u8> {
+        let mut field = format!("{}\n", name).into_bytes();
+        field.extend((value.len() as u64).to_le_bytes());
+        field.extend(value);
+        field.push(b'\n');
+        field
+    }
+
+    fn frames(input: &[u8]) -> Vec<Bytes> {
+        let mut framer = ExportFramer::default();
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        // Feed the input byte by byte to exercise the partial reads.
+        for byte in input {
+            src.extend_from_slice(&[*byte]);
+            while let Some(frame) = framer.decode(&mut src).unwrap() {
+                frames.push(frame);
+            }
+        }
+        assert!(src.is_empty());
+        frames
+    }
+
+    #[test]
+    fn frames_entries() {
+        let mut input = b"__CURSOR=c1\nMESSAGE=one\n\n".to_vec();
+        input.extend(b"__CURSOR=c2\n");
+        input.extend(binary_field("MESSAGE", b"two\n\nlines"));
+        input.extend(b"_HOSTNAME=host\n\n\n");
+
+        let frames = frames(&input);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(&frames[0][..], b"__CURSOR=c1\nMESSAGE=one\n");
+
+        let record = decode_export_record(&frames[1]).unwrap();
+        assert_eq!(record["__CURSOR"], "c2");
+        assert_eq!(record["MESSAGE"], "two\n\nlines");
+        assert_eq!(record["_HOSTNAME"], "host");
+    }
+
+    #[test]
+    fn rejects_unterminated_binary_field() {
+        let mut input = binary_field("MESSAGE", b"value");
+        *input.last_mut().unwrap() = b'x';
+        input.extend(b"\n\n");
+
+        let mut framer = ExportFramer::default();
+        let mut src = BytesMut::from(&input[..]);
+        assert!(framer.decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn decodes_record() {
+        let record = decode_export_record(
+            b"__CURSOR=s=1;i=2\nMESSAGE=a=b\nPRIORITY=6\nTAG=x\nTAG=y\nEMPTY=\n",
+        )
+        .unwrap();
+
+        assert_eq!(record.len(), 5);
+        assert_eq!(record["__CURSOR"], "s=1;i=2");
+        assert_eq!(record["MESSAGE"], "a=b");
+        assert_eq!(record["PRIORITY"], "6");
+        assert_eq!(record["TAG"], r#"["x","y"]"#);
+        assert_eq!(record["EMPTY"], "");
+    }
+
+    #[test]
+    fn rejects_truncated_record() {
+        let input = binary_field("MESSAGE", b"value");
+        assert!(decode_export_record(&input[..input.len() - 3]).is_err());
+        assert!(decode_export_record(b"MESSAGE\n\x05").is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     io::SeekFrom,
     path::PathBuf,
     process::Stdio,
@@ -10,12 +10,19 @@ use std::{
 
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
-use futures::{poll, stream::BoxStream, task::Poll, StreamExt};
+use futures::{poll, stream::BoxStream, task::Poll, StreamExt, TryStreamExt};
+use http::{
+    header::{ACCEPT, RANGE},
+    uri::InvalidUri,
+    Request, StatusCode, Uri,
+};
+use hyper::Body;
 use nix::{
     sys::signal::{kill, Signal},
     unistd::Pid,
 };
 use once_cell::sync::Lazy;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde_json::{Error as JsonError, Value as JsonValue};
 use snafu::{ResultExt, Snafu};
 use tokio::{
@@ -25,7 +32,7 @@ use tokio::{
     sync::{Mutex, MutexGuard},
     time::sleep,
 };
-use tokio_util::codec::FramedRead;
+use tokio_util::{codec::FramedRead, io::StreamReader};
 use vector_lib::codecs::{decoding::BoxedFramingError, CharacterDelimitedDecoder};
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::{metadata_path, owned_value_path, path};
@@ -49,16 +56,22 @@ use crate::{
         SourceOutput,
     },
     event::{BatchNotifier, BatchStatus, BatchStatusReceiver, LogEvent},
+    http::HttpClient,
     internal_events::{
         EventsReceived, JournaldCheckpointFileOpenError, JournaldCheckpointSetError,
-        JournaldInvalidRecordError, JournaldReadError, JournaldStartJournalctlError,
-        StreamClosedError,
+        JournaldInvalidRecordError, JournaldReadError, JournaldRemoteRequestError,
+        JournaldStartJournalctlError, StreamClosedError,
     },
     serde::bool_or_struct,
     shutdown::ShutdownSignal,
+    tls::{TlsConfig, TlsSettings},
     SourceSender,
 };
 
+mod export;
+
+use export::{decode_export_record, ExportFramer};
+
 const BATCH_TIMEOUT: Duration = Duration::from_millis(10);
 
 const CHECKPOINT_FILENAME: &str = "checkpoint.txt";
@@ -71,6 +84,9 @@ const RECEIVED_TIMESTAMP: &str = "__REALTIME_TIMESTAMP";
 
 const BACKOFF_DURATION: Duration = Duration::from_secs(1);
 
+/// The content type of the journal export format served by `systemd-journal-gatewayd`.
+const EXPORT_CONTENT_TYPE: &str = "application/vnd.fdo.journal";
+
 static JOURNALCTL: Lazy<PathBuf> = Lazy::new(|| "journalctl".into());
 
 #[derive(Debug, Snafu)]
@@ -90,10 +106,89 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("Invalid remote_url {:?}: {}", url, source))]
+    InvalidRemoteUrl { url: String, source: InvalidUri },
+    #[snafu(display("Cannot use `{}` together with `remote_url`", option))]
+    IncompatibleRemoteOption { option: &'static str },
+    #[snafu(display("The remote journal responded with HTTP status {}", status))]
+    RemoteStatus { status: StatusCode },
 }
 
 type Matches = HashMap<String, HashSet<String>>;
 
+/// A syslog priority level of a journal entry.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// System is unusable.
+    Emerg,
+
+    /// Action must be taken immediately.
+    Alert,
+
+    /// Critical conditions.
+    Crit,
+
+    /// Error conditions.
+    Err,
+
+    /// Warning conditions.
+    Warning,
+
+    /// Normal but significant conditions.
+    Notice,
+
+    /// Informational messages.
+    Info,
+
+    /// Debug-level messages.
+    Debug,
+}
+
+impl Priority {
+    /// The name of the priority, as accepted by `journalctl --priority`.
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Emerg => "emerg",
+            Self::Alert => "alert",
+            Self::Crit => "crit",
+            Self::Err => "err",
+            Self::Warning => "warning",
+            Self::Notice => "notice",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+
+    /// The numeric value of the priority, as stored in the `PRIORITY` journal field.
+    const fn as_number(self) -> u8 {
+        match self {
+            Self::Emerg => 0,
+            Self::Alert => 1,
+            Self::Crit => 2,
+            Self::Err => 3,
+            Self::Warning => 4,
+            Self::Notice => 5,
+            Self::Info => 6,
+            Self::Debug => 7,
+        }
+    }
+}
+
+/// The priorities of the journal entries to include.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+#[configurable(metadata(docs::examples = "warning"))]
+pub enum IncludePriority {
+    /// Include entries at this priority or any more severe one.
+    Max(Priority),
+
+    /// Include entries at exactly these priorities.
+    List(Vec<Priority>),
+}
+
 /// Configuration for the `journald` source.
 #[configurable_component(source("journald", "Collect logs from JournalD."))]
 #[derive(Clone, Debug)]
@@ -147,6 +242,25 @@ pub struct JournaldConfig {
     #[configurable(metadata(docs::examples = "matches_examples()"))]
     pub exclude_matches: Matches,
 
+    /// The priorities of the journal entries to include.
+    ///
+    /// Either the name of the least severe priority to include, such as `warning` to include
+    /// entries at `warning` or worse, or an explicit list of priority names.
+    ///
+    /// This is passed to `journalctl`, so entries are filtered before they are read by Vector.
+    /// If not present, all priorities are accepted.
+    #[serde(default)]
+    pub include_priority: Option<IncludePriority>,
+
+    /// A list of syslog identifiers to monitor.
+    ///
+    /// If empty or not present, all syslog identifiers are accepted.
+    ///
+    /// This is passed to `journalctl`, so entries are filtered before they are read by Vector.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "sshd", docs::examples = "kernel"))]
+    pub include_syslog_identifiers: Vec<String>,
+
     /// The directory used to persist file checkpoint positions.
     ///
     /// By default, the global `data_dir` option is used. Make sure the running user has write
@@ -192,6 +306,22 @@ pub struct JournaldConfig {
     #[serde(default)]
     pub journal_namespace: Option<String>,
 
+    /// The URL of a [`systemd-journal-gatewayd`][gatewayd] instance to read the journal from.
+    ///
+    /// If set, the journal entries are streamed from the `/entries` endpoint of the gateway in the
+    /// journal export format, instead of being read from a local `journalctl`. The options that
+    /// only apply to `journalctl`, such as `journalctl_path`, `journal_directory`,
+    /// `journal_namespace`, `extra_args` and `since_now`, can't be used together with this option.
+    ///
+    /// [gatewayd]: https://www.freedesktop.org/software/systemd/man/systemd-journal-gatewayd.service.html
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "https://journal.example.com:19531"))]
+    pub remote_url: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
@@ -250,6 +380,107 @@ impl JournaldConfig {
         matches
     }
 
+    /// Builds the `journalctl` arguments for the `include_priority` and
+    /// `include_syslog_identifiers` options.
+    ///
+    /// `journalctl` combines matches on the same field with OR and matches on different fields
+    /// with AND, so an explicit list of priorities is passed as one `PRIORITY=` match per entry.
+    fn filter_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match &self.include_priority {
+            Some(IncludePriority::Max(priority)) => {
+                args.push(format!("--priority={}", priority.as_str()));
+            }
+            Some(IncludePriority::List(priorities)) => {
+                let priorities = priorities.iter().collect::<BTreeSet<_>>();
+                args.extend(
+                    priorities
+                        .into_iter()
+                        .map(|priority| format!("PRIORITY={}", priority.as_number())),
+                );
+            }
+            None => {}
+        }
+
+        args.extend(
+            self.include_syslog_identifiers
+                .iter()
+                .map(|identifier| format!("--identifier={}", identifier)),
+        );
+
+        args
+    }
+
+    /// Builds the journal matches the remote journal is queried with for the `include_priority`
+    /// and `include_syslog_identifiers` options.
+    ///
+    /// The gateway doesn't support priority ranges, so a maximum priority is expanded into a
+    /// match for each of the priorities up to it.
+    fn remote_matches(&self) -> Vec<(&'static str, String)> {
+        let priorities = match &self.include_priority {
+            Some(IncludePriority::Max(max)) => (0..=max.as_number()).collect(),
+            Some(IncludePriority::List(priorities)) => priorities
+                .iter()
+                .map(|priority| priority.as_number())
+                .collect(),
+            None => BTreeSet::new(),
+        };
+
+        priorities
+            .into_iter()
+            .map(|priority| ("PRIORITY", priority.to_string()))
+            .chain(
+                self.include_syslog_identifiers
+                    .iter()
+                    .map(|identifier| ("SYSLOG_IDENTIFIER", identifier.clone())),
+            )
+            .collect()
+    }
+
+    fn starter(&self, cx: &SourceContext) -> crate::Result<Starter> {
+        let Some(url) = &self.remote_url else {
+            let journalctl_path = self
+                .journalctl_path
+                .clone()
+                .unwrap_or_else(|| JOURNALCTL.clone());
+
+            return Ok(Starter::Journalctl(StartJournalctl::new(
+                journalctl_path,
+                self.journal_directory.clone(),
+                self.journal_namespace.clone(),
+                self.current_boot_only,
+                self.since_now,
+                self.filter_args(),
+                self.extra_args.clone(),
+            )));
+        };
+
+        let incompatible_options = [
+            ("since_now", self.since_now),
+            ("journalctl_path", self.journalctl_path.is_some()),
+            ("journal_directory", self.journal_directory.is_some()),
+            ("journal_namespace", self.journal_namespace.is_some()),
+            ("extra_args", !self.extra_args.is_empty()),
+        ];
+        if let Some((option, _)) = incompatible_options.into_iter().find(|(_, set)| *set) {
+            return Err(BuildError::IncompatibleRemoteOption { option }.into());
+        }
+
+        url.parse::<Uri>()
+            .context(InvalidRemoteUrlSnafu { url: url.clone() })?;
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls, &cx.proxy)?;
+
+        Ok(Starter::Remote(Box::new(StartRemote {
+            client,
+            url: url.clone(),
+            current_boot_only: self.current_boot_only,
+            matches: self.remote_matches(),
+        })))
+    }
+
     /// Builds the `schema::Definition` for this source using the provided `LogNamespace`.
     fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
         let schema_definition = match log_namespace {
@@ -306,11 +537,15 @@ impl Default for JournaldConfig {
             exclude_units: vec![],
             include_matches: Default::default(),
             exclude_matches: Default::default(),
+            include_priority: None,
+            include_syslog_identifiers: vec![],
             data_dir: None,
             batch_size: default_batch_size(),
             journalctl_path: None,
             journal_directory: None,
             journal_namespace: None,
+            remote_url: None,
+            tls: None,
             extra_args: vec![],
             acknowledgements: Default::default(),
             remap_priority: false,
@@ -356,19 +591,7 @@ impl SourceConfig for JournaldConfig {
         let mut checkpoint_path = data_dir;
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
-        let journalctl_path = self
-            .journalctl_path
-            .clone()
-            .unwrap_or_else(|| JOURNALCTL.clone());
-
-        let starter = StartJournalctl::new(
-            journalctl_path,
-            self.journal_directory.clone(),
-            self.journal_namespace.clone(),
-            self.current_boot_only,
-            self.since_now,
-            self.extra_args.clone(),
-        );
+        let starter = self.starter(&cx)?;
 
         let batch_size = self.batch_size;
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
@@ -411,7 +634,7 @@ struct JournaldSource {
     remap_priority: bool,
     out: SourceSender,
     acknowledgements: bool,
-    starter: StartJournalctl,
+    starter: Starter,
     log_namespace: LogNamespace,
     emit_cursor: bool,
 }
@@ -454,9 +677,8 @@ impl JournaldSource {
                 break;
             }
 
-            info!("Starting journalctl.");
             let cursor = checkpointer.lock().await.cursor.clone();
-            match self.starter.start(cursor.as_deref()) {
+            match self.starter.start(cursor.as_deref()).await {
                 Ok((stream, running)) => {
                     if !self.run_stream(stream, &finalizer, shutdown.clone()).await {
                         return;
@@ -464,13 +686,11 @@ impl JournaldSource {
                     // Explicit drop to ensure it isn't dropped earlier.
                     drop(running);
                 }
-                Err(error) => {
-                    emit!(JournaldStartJournalctlError { error });
-                }
+                Err(error) => self.starter.emit_start_error(error),
             }
 
-            // journalctl process should never stop,
-            // so it is an error if we reach here.
+            // Neither the journalctl process nor the remote journal stream
+            // should ever stop, so it is an error if we reach here.
             tokio::select! {
                 _ = &mut shutdown => break,
                 _ = sleep(BACKOFF_DURATION) => (),
@@ -553,7 +773,7 @@ impl<'a> Batch<'a> {
     fn handle_next(&mut self, result: Option<Result<Bytes, BoxedFramingError>>) -> bool {
         match result {
             None => {
-                warn!("Journalctl process stopped.");
+                warn!("Journal stream stopped.");
                 self.exiting = Some(true);
                 false
             }
@@ -562,7 +782,11 @@ impl<'a> Batch<'a> {
                 false
             }
             Some(Ok(bytes)) => {
-                match decode_record(&bytes, self.source.remap_priority) {
+                match self
+                    .source
+                    .starter
+                    .decode_record(&bytes, self.source.remap_priority)
+                {
                     Ok(mut record) => {
                         if self.source.emit_cursor {
                             if let Some(tmp) = record.get(CURSOR) {
@@ -638,12 +862,61 @@ impl<'a> Batch<'a> {
 
 type JournalStream = BoxStream<'static, Result<Bytes, BoxedFramingError>>;
 
+/// Starts reading the journal either from a local `journalctl` or from a remote journal.
+enum Starter {
+    Journalctl(StartJournalctl),
+    Remote(Box<StartRemote>),
+}
+
+impl Starter {
+    async fn start(
+        &mut self,
+        checkpoint: Option<&str>,
+    ) -> crate::Result<(JournalStream, Option<RunningJournalctl>)> {
+        match self {
+            Self::Journalctl(starter) => {
+                info!("Starting journalctl.");
+                let (stream, running) = starter.start(checkpoint)?;
+                Ok((stream, Some(running)))
+            }
+            Self::Remote(starter) => {
+                info!(message = "Connecting to remote journal.", url = %starter.url);
+                Ok((starter.start(checkpoint).await?, None))
+            }
+        }
+    }
+
+    fn emit_start_error(&self, error: crate::Error) {
+        match self {
+            Self::Journalctl(_) => emit!(JournaldStartJournalctlError { error }),
+            Self::Remote(_) => emit!(JournaldRemoteRequestError { error }),
+        }
+    }
+
+    /// Decodes a frame of the journal stream into a record.
+    fn decode_record(&self, frame: &[u8], remap: bool) -> crate::Result<Record> {
+        match self {
+            Self::Journalctl(_) => Ok(decode_record(frame, remap)?),
+            Self::Remote(_) => {
+                let mut record = decode_export_record(frame)?;
+                if remap {
+                    if let Some(text) = record.get("PRIORITY").and_then(|p| priority_name(p)) {
+                        record.insert("PRIORITY".into(), text.into());
+                    }
+                }
+                Ok(record)
+            }
+        }
+    }
+}
+
 struct StartJournalctl {
     path: PathBuf,
     journal_dir: Option<PathBuf>,
     journal_namespace: Option<String>,
     current_boot_only: bool,
     since_now: bool,
+    filter_args: Vec<String>,
     extra_args: Vec<String>,
 }
 
@@ -654,6 +927,7 @@ impl StartJournalctl {
         journal_namespace: Option<String>,
         current_boot_only: bool,
         since_now: bool,
+        filter_args: Vec<String>,
         extra_args: Vec<String>,
     ) -> Self {
         Self {
@@ -662,6 +936,7 @@ impl StartJournalctl {
             journal_namespace,
             current_boot_only,
             since_now,
+            filter_args,
             extra_args,
         }
     }
@@ -695,6 +970,8 @@ impl StartJournalctl {
             command.arg("--since=2000-01-01");
         }
 
+        command.args(&self.filter_args);
+
         if !self.extra_args.is_empty() {
             command.args(&self.extra_args);
         }
@@ -720,6 +997,53 @@ impl StartJournalctl {
     }
 }
 
+/// Streams the journal from the `/entries` endpoint of `systemd-journal-gatewayd`.
+struct StartRemote {
+    client: HttpClient,
+    url: String,
+    current_boot_only: bool,
+    matches: Vec<(&'static str, String)>,
+}
+
+impl StartRemote {
+    fn make_request(&self, checkpoint: Option<&str>) -> crate::Result<Request<Body>> {
+        let mut uri = format!("{}/entries?follow", self.url.trim_end_matches('/'));
+        if self.current_boot_only {
+            uri.push_str("&boot");
+        }
+        for (field, value) in &self.matches {
+            uri.push_str(&format!(
+                "&{}={}",
+                field,
+                utf8_percent_encode(value, NON_ALPHANUMERIC)
+            ));
+        }
+
+        let mut request = Request::get(uri).header(ACCEPT, EXPORT_CONTENT_TYPE);
+        if let Some(cursor) = checkpoint {
+            // Skip the entry at the cursor, as it has been read already.
+            request = request.header(RANGE, format!("entries={}:1:", cursor));
+        }
+
+        Ok(request.body(Body::empty())?)
+    }
+
+    async fn start(&self, checkpoint: Option<&str>) -> crate::Result<JournalStream> {
+        let response = self.client.send(self.make_request(checkpoint)?).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(BuildError::RemoteStatus { status }.into());
+        }
+
+        let body = response
+            .into_body()
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error));
+
+        Ok(FramedRead::new(StreamReader::new(body), ExportFramer::default()).boxed())
+    }
+}
+
 struct RunningJournalctl(Child);
 
 impl Drop for RunningJournalctl {
@@ -867,22 +1191,26 @@ fn decode_array_as_bytes(array: &[JsonValue]) -> Option<JsonValue> {
 }
 
 fn remap_priority(priority: &mut JsonValue) {
-    if let Some(num) = priority.as_str().and_then(|s| usize::from_str(s).ok()) {
-        let text = match num {
-            0 => "EMERG",
-            1 => "ALERT",
-            2 => "CRIT",
-            3 => "ERR",
-            4 => "WARNING",
-            5 => "NOTICE",
-            6 => "INFO",
-            7 => "DEBUG",
-            _ => "UNKNOWN",
-        };
+    if let Some(text) = priority.as_str().and_then(priority_name) {
         *priority = JsonValue::String(text.into());
     }
 }
 
+fn priority_name(priority: &str) -> Option<&'static str> {
+    let text = match usize::from_str(priority).ok()? {
+        0 => "EMERG",
+        1 => "ALERT",
+        2 => "CRIT",
+        3 => "ERR",
+        4 => "WARNING",
+        5 => "NOTICE",
+        6 => "INFO",
+        7 => "DEBUG",
+        _ => "UNKNOWN",
+    };
+    Some(text)
+}
+
 fn filter_matches(record: &Record, includes: &Matches, excludes: &Matches) -> bool {
     match (includes.is_empty(), excludes.is_empty()) {
         (true, true) => false,
@@ -1091,10 +1419,14 @@ mod tests {
     use tokio::time::{sleep, timeout, Duration, Instant};
     use vrl::value::{kind::Collection, Value};
 
+    use warp::Filter;
+
     use super::*;
     use crate::{
-        config::ComponentKey, event::Event, event::EventStatus,
-        test_util::components::assert_source_compliance,
+        config::ComponentKey,
+        event::Event,
+        event::EventStatus,
+        test_util::{components::assert_source_compliance, next_addr, wait_for_tcp},
     };
 
     const TEST_COMPONENT: &str = "journald-test";
@@ -1511,6 +1843,178 @@ mod tests {
         assert!(cmd_line.contains("--merge"));
     }
 
+    #[test]
+    fn parses_include_priority() {
+        let config: JournaldConfig = toml::from_str(r#"include_priority = "warning""#).unwrap();
+        assert_eq!(
+            config.include_priority,
+            Some(IncludePriority::Max(Priority::Warning))
+        );
+
+        let config: JournaldConfig =
+            toml::from_str(r#"include_priority = ["err", "debug"]"#).unwrap();
+        assert_eq!(
+            config.include_priority,
+            Some(IncludePriority::List(vec![Priority::Err, Priority::Debug]))
+        );
+
+        assert!(toml::from_str::<JournaldConfig>(r#"include_priority = "warn""#).is_err());
+        assert!(toml::from_str::<JournaldConfig>(r#"include_priority = ["err", "bad"]"#).is_err());
+    }
+
+    #[test]
+    fn filter_args() {
+        let config = JournaldConfig::default();
+        assert!(config.filter_args().is_empty());
+
+        let config = JournaldConfig {
+            include_priority: Some(IncludePriority::Max(Priority::Warning)),
+            ..Default::default()
+        };
+        assert_eq!(config.filter_args(), vec!["--priority=warning"]);
+
+        let config = JournaldConfig {
+            include_priority: Some(IncludePriority::List(vec![
+                Priority::Debug,
+                Priority::Emerg,
+                Priority::Debug,
+            ])),
+            include_syslog_identifiers: vec!["sshd".into(), "kernel".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.filter_args(),
+            vec![
+                "PRIORITY=0",
+                "PRIORITY=7",
+                "--identifier=sshd",
+                "--identifier=kernel"
+            ]
+        );
+    }
+
+    #[test]
+    fn command_filter_args() {
+        let config = JournaldConfig {
+            include_priority: Some(IncludePriority::Max(Priority::Err)),
+            include_syslog_identifiers: vec!["sshd".into()],
+            ..Default::default()
+        };
+        let command = StartJournalctl::new(
+            PathBuf::from("journalctl"),
+            None,
+            None,
+            false,
+            false,
+            config.filter_args(),
+            vec!["--merge".to_string()],
+        )
+        .make_command(None);
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--priority=err"));
+        assert!(cmd_line.contains("--identifier=sshd"));
+        assert!(cmd_line.find("--identifier=sshd") < cmd_line.find("--merge"));
+    }
+
+    #[tokio::test]
+    async fn reads_remote_journal() {
+        let addr = next_addr();
+        let ranges = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let requests = Arc::clone(&ranges);
+        let entries = warp::path!("entries")
+            .and(warp::header::exact("accept", EXPORT_CONTENT_TYPE))
+            .and(warp::header::optional::<String>("range"))
+            .map(move |range: Option<String>| {
+                requests.lock().unwrap().push(range);
+                "__CURSOR=c1\nMESSAGE=one\nPRIORITY=6\n\n__CURSOR=c2\nMESSAGE\n\x03\0\0\0\0\0\0\0two\n\n"
+            });
+        tokio::spawn(warp::serve(entries).run(addr));
+        wait_for_tcp(addr).await;
+
+        let received = assert_source_compliance(&["protocol"], async move {
+            let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+            let tempdir = tempdir().unwrap();
+
+            let (cx, shutdown) =
+                SourceContext::new_shutdown(&ComponentKey::from(TEST_COMPONENT), tx);
+            let config = JournaldConfig {
+                remote_url: Some(format!("http://{}", addr)),
+                data_dir: Some(tempdir.path().to_path_buf()),
+                remap_priority: true,
+                acknowledgements: false.into(),
+                ..Default::default()
+            };
+            let source = config.build(cx).await.unwrap();
+            tokio::spawn(async move { source.await.unwrap() });
+
+            // Let the source reconnect once the response ends.
+            sleep(BACKOFF_DURATION + Duration::from_millis(500)).await;
+            shutdown
+                .shutdown_all(Some(Instant::now() + Duration::from_secs(1)))
+                .await;
+
+            timeout(Duration::from_secs(1), rx.collect::<Vec<_>>())
+                .await
+                .unwrap()
+        })
+        .await;
+
+        assert!(received.len() >= 2);
+        assert_eq!(message(&received[0]), Value::Bytes("one".into()));
+        assert_eq!(priority(&received[0]), Value::Bytes("INFO".into()));
+        assert_eq!(message(&received[1]), Value::Bytes("two".into()));
+
+        let ranges = ranges.lock().unwrap();
+        assert!(ranges.len() >= 2);
+        assert_eq!(ranges[0], None);
+        assert_eq!(ranges[1].as_deref(), Some("entries=c2:1:"));
+    }
+
+    #[tokio::test]
+    async fn remote_url_rejects_journalctl_options() {
+        let config = JournaldConfig {
+            remote_url: Some("http://localhost:19531".into()),
+            extra_args: vec!["--merge".into()],
+            ..Default::default()
+        };
+        let (cx, _shutdown) = SourceContext::new_shutdown(
+            &ComponentKey::from(TEST_COMPONENT),
+            SourceSender::new_test().0,
+        );
+        let error = config.starter(&cx).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Cannot use `extra_args` together with `remote_url`"
+        );
+    }
+
+    #[test]
+    fn remote_request() {
+        let config = JournaldConfig {
+            include_priority: Some(IncludePriority::Max(Priority::Crit)),
+            include_syslog_identifiers: vec!["my app".into()],
+            ..Default::default()
+        };
+        let starter = StartRemote {
+            client: HttpClient::new(None, &Default::default()).unwrap(),
+            url: "https://journal.example.com:19531/".into(),
+            current_boot_only: true,
+            matches: config.remote_matches(),
+        };
+
+        let request = starter.make_request(None).unwrap();
+        assert_eq!(
+            request.uri(),
+            "https://journal.example.com:19531/entries?follow&boot&PRIORITY=0&PRIORITY=1&PRIORITY=2&SYSLOG_IDENTIFIER=my%20app"
+        );
+        assert_eq!(request.headers()[ACCEPT], EXPORT_CONTENT_TYPE);
+        assert!(!request.headers().contains_key(RANGE));
+
+        let request = starter.make_request(Some("s=1;i=2")).unwrap();
+        assert_eq!(request.headers()[RANGE], "entries=s=1;i=2:1:");
+    }
+
     fn create_command(
         path: &Path,
         journal_dir: Option<PathBuf>,
@@ -1526,6 +2030,7 @@ mod tests {
             journal_namespace,
             current_boot_only,
             since_now,
+            vec![],
             extra_args,
         )
         .make_command(cursor)
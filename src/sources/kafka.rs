@@ -3,6 +3,7 @@ use std::{
     io::Cursor,
     pin::Pin,
     sync::{
+        atomic::{AtomicUsize, Ordering},
         mpsc::{sync_channel, SyncSender},
         Arc, OnceLock, Weak,
     },
@@ -10,6 +11,7 @@ use std::{
 };
 
 use async_stream::stream;
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
 use futures::{Stream, StreamExt};
@@ -20,7 +22,7 @@ use rdkafka::{
         StreamConsumer,
     },
     error::KafkaError,
-    message::{BorrowedMessage, Headers as _, Message},
+    message::{BorrowedMessage, Header, Headers as _, Message},
     types::RDKafkaErrorCode,
     ClientConfig, ClientContext, Statistics, TopicPartitionList,
 };
@@ -30,10 +32,10 @@ use tokio::{
     runtime::Handle,
     sync::{
         mpsc::{self, UnboundedReceiver, UnboundedSender},
-        oneshot,
+        oneshot, watch,
     },
     task::JoinSet,
-    time::Sleep,
+    time::{interval_at, Instant, Sleep},
 };
 use tokio_util::codec::FramedRead;
 use tracing::Span;
@@ -59,8 +61,8 @@ use crate::{
     },
     event::{BatchNotifier, BatchStatus, Event, Value},
     internal_events::{
-        KafkaBytesReceived, KafkaEventsReceived, KafkaOffsetUpdateError, KafkaReadError,
-        StreamClosedError,
+        KafkaBytesReceived, KafkaEventsReceived, KafkaOffsetUpdateError, KafkaPartitionPauseError,
+        KafkaPausedPartitions, KafkaReadError, StreamClosedError,
     },
     kafka,
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
@@ -79,6 +81,15 @@ enum BuildError {
     CreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     SubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("The partitions and group_instance_id options cannot be used together"))]
+    ManualAssignmentWithGroupInstanceId,
+    #[snafu(display(
+        "Topic regular expressions ({}) cannot be used with manually assigned partitions",
+        topic
+    ))]
+    ManualAssignmentWithTopicRegex { topic: String },
+    #[snafu(display("Invalid Kafka partition: {}", partition))]
+    InvalidPartition { partition: i32 },
 }
 
 /// Metrics (beta) configuration.
@@ -119,6 +130,35 @@ pub struct KafkaSourceConfig {
     #[configurable(metadata(docs::examples = "consumer-group-name"))]
     group_id: String,
 
+    /// The static membership identifier of this consumer in the consumer group.
+    ///
+    /// With static membership, the consumer group does not rebalance when the consumer leaves
+    /// and rejoins the group within `session_timeout_ms`, such as during a restart. The
+    /// identifier must be unique within the consumer group. Environment variables such as
+    /// `${HOSTNAME}` are interpolated, so it can be derived from the host Vector runs on.
+    ///
+    /// Cannot be used together with `partitions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = "vector-${HOSTNAME}"))]
+    #[configurable(metadata(docs::advanced))]
+    group_instance_id: Option<String>,
+
+    /// The partitions to consume from each of the topics, bypassing the consumer group protocol.
+    ///
+    /// When set, the partitions are assigned to this consumer directly instead of being
+    /// distributed among the members of the consumer group, so no rebalances take place.
+    /// Offsets are still committed for `group_id`, so consuming resumes where it left off
+    /// after a restart.
+    ///
+    /// Partitions that do not exist yet, and partitions added to the topics while Vector is
+    /// running, are only consumed after the configuration is reloaded.
+    ///
+    /// Cannot be used together with `group_instance_id` or with regular expression topics.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 0, docs::examples = 1))]
+    #[configurable(metadata(docs::advanced))]
+    partitions: Vec<i32>,
+
     /// If offsets for consumer group do not exist, set them using this strategy.
     ///
     /// See the [librdkafka documentation](https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md) for the `auto.offset.reset` option for further clarification.
@@ -217,6 +257,29 @@ pub struct KafkaSourceConfig {
     #[configurable(metadata(docs::examples = "headers"))]
     headers_key: OptionalValuePath,
 
+    /// A list of header names to add to each event.
+    ///
+    /// If empty or not present, all headers are added. Header values that are valid UTF-8 are
+    /// added as strings, and the others are base64 encoded.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "tenant_id", docs::examples = "trace_id"))]
+    include_headers: Vec<String>,
+
+    /// The maximum number of events consumed from Kafka that have not been acknowledged yet.
+    ///
+    /// Once exceeded, fetching the partitions is paused at the broker until enough of the events
+    /// are acknowledged, instead of the fetched messages piling up in memory while the downstream
+    /// components are slow. Offsets are still only committed once the events are delivered.
+    ///
+    /// Events are acknowledged as soon as they are sent to the downstream components, unless
+    /// end-to-end acknowledgements are enabled.
+    ///
+    /// If not set, fetching is never paused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = 10000))]
+    #[configurable(metadata(docs::advanced))]
+    max_in_flight_events: Option<usize>,
+
     /// Advanced options set directly on the underlying `librdkafka` client.
     ///
     /// See the [librdkafka documentation](https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md) for details.
@@ -443,6 +506,8 @@ async fn kafka_source(
     // EOF signal allowing the coordination task to tell the kafka client task when all partitions have reached EOF
     let (eof_tx, eof_rx) = eof.then(oneshot::channel::<()>).unzip();
 
+    let manual_assignment = ManualAssignment::new(&config);
+
     let coordination_task = {
         let span = span.clone();
         let consumer = Arc::clone(&consumer);
@@ -468,7 +533,7 @@ async fn kafka_source(
         let consumer = Arc::clone(&consumer);
         tokio::task::spawn_blocking(move || {
             let _enter = span.enter();
-            drive_kafka_consumer(consumer, shutdown, eof_rx);
+            drive_kafka_consumer(consumer, shutdown, eof_rx, manual_assignment);
         })
     };
 
@@ -604,13 +669,19 @@ impl ConsumerStateInner<Consuming> {
 
             let mut status = PartitionConsumerStatus::NormalExit;
 
+            let mut in_flight_events = PartitionInFlightEvents::new(&consumer, tp.clone());
+
             loop {
+                let paused = in_flight_events
+                    .as_mut()
+                    .map_or(false, PartitionInFlightEvents::sync_paused);
+
                 tokio::select!(
                     // is_some() checks prevent polling end_signal after it completes
                     _ = &mut end_signal, if finalizer.is_some() => {
                         finalizer.take();
                     },
-                    message = messages.next(), if finalizer.is_some() => match message {
+                    message = messages.next(), if finalizer.is_some() && !paused => match message {
                         None => unreachable!("MessageStream never calls Ready(None)"),
                         Some(Err(error)) => match error {
                             rdkafka::error::KafkaError::PartitionEOF(partition) if exit_eof => {
@@ -627,7 +698,10 @@ impl ConsumerStateInner<Consuming> {
                                 topic: msg.topic(),
                                 partition: msg.partition(),
                             });
-                            parse_message(msg, decoder.clone(), &keys, &mut out, acknowledgements, &finalizer, log_namespace).await;
+                            let events = parse_message(msg, decoder.clone(), &keys, &mut out, acknowledgements, &finalizer, log_namespace).await;
+                            if let Some(in_flight_events) = in_flight_events.as_mut() {
+                                in_flight_events.add(events);
+                            }
                         }
                     },
 
@@ -638,6 +712,9 @@ impl ConsumerStateInner<Consuming> {
                                     emit!(KafkaOffsetUpdateError { error });
                                 }
                             }
+                            if let Some(in_flight_events) = in_flight_events.as_mut() {
+                                in_flight_events.remove(entry.events);
+                            }
                         }
                         None if finalizer.is_none() => {
                             debug!("Acknowledgement stream complete for partition {}:{}.", &tp.0, tp.1);
@@ -646,7 +723,11 @@ impl ConsumerStateInner<Consuming> {
                         None => {
                             debug!("Acknowledgement stream empty for {}:{}", &tp.0, tp.1);
                         }
-                    }
+                    },
+
+                    // The loop is woken up to pause or resume the partition when the in-flight
+                    // events limit is crossed, which may be due to the other partitions.
+                    Some(Ok(())) = OptionFuture::from(in_flight_events.as_mut().map(PartitionInFlightEvents::changed)) => {},
                 )
             }
             (tp, status)
@@ -905,10 +986,19 @@ fn drive_kafka_consumer(
     consumer: Arc<StreamConsumer<KafkaSourceContext>>,
     mut shutdown: ShutdownSignal,
     eof: Option<oneshot::Receiver<()>>,
+    manual_assignment: Option<ManualAssignment>,
 ) {
     Handle::current().block_on(async move {
         let mut eof: OptionFuture<_> = eof.into();
         let mut stream = consumer.stream();
+        let mut partition_counts = manual_assignment
+            .as_ref()
+            .map(|assignment| assignment.assign(&consumer))
+            .unwrap_or_default();
+        let mut partition_count_check = interval_at(
+            Instant::now() + PARTITION_COUNT_CHECK_INTERVAL,
+            PARTITION_COUNT_CHECK_INTERVAL,
+        );
         loop {
             tokio::select! {
                 _ = &mut shutdown => {
@@ -921,6 +1011,12 @@ fn drive_kafka_consumer(
                     break
                 },
 
+                _ = partition_count_check.tick(), if manual_assignment.is_some() => {
+                    if let Some(assignment) = &manual_assignment {
+                        assignment.check_partition_counts(&consumer, &mut partition_counts);
+                    }
+                },
+
                 // NB: messages are not received on this thread, however we poll
                 // the consumer to serve client callbacks, such as rebalance notifications
                 message = stream.next() => match message {
@@ -935,6 +1031,114 @@ fn drive_kafka_consumer(
     });
 }
 
+/// How often the partition counts of manually assigned topics are checked for changes.
+const PARTITION_COUNT_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Partitions assigned to the consumer directly, without taking part in the consumer group
+/// protocol.
+///
+/// The assignment is fixed for the lifetime of the source: changes in the partition counts
+/// of the topics are only logged, and picked up when the configuration is reloaded.
+struct ManualAssignment {
+    topics: Vec<String>,
+    partitions: Vec<i32>,
+    metadata_timeout: Duration,
+}
+
+impl ManualAssignment {
+    fn new(config: &KafkaSourceConfig) -> Option<Self> {
+        (!config.partitions.is_empty()).then(|| Self {
+            topics: config.topics.clone(),
+            partitions: config.partitions.clone(),
+            metadata_timeout: config.socket_timeout_ms,
+        })
+    }
+
+    /// Assigns the partitions to the consumer, returning the partition counts of the topics.
+    fn assign(&self, consumer: &StreamConsumer<KafkaSourceContext>) -> HashMap<String, usize> {
+        let partition_counts = self.partition_counts(consumer);
+        let tpl = self.topic_partition_list(&partition_counts);
+
+        // The partition queues have to be split off before the partitions are assigned, in
+        // the same way as during a consumer group rebalance.
+        consumer.context().consume_partitions(&tpl);
+        if let Err(error) = consumer.assign(&tpl) {
+            emit!(KafkaReadError { error });
+        }
+
+        partition_counts
+    }
+
+    /// Fetches the partition counts of the topics. Topics whose metadata is not available
+    /// are left out.
+    fn partition_counts<C: ConsumerContext>(
+        &self,
+        consumer: &StreamConsumer<C>,
+    ) -> HashMap<String, usize> {
+        let mut partition_counts = HashMap::new();
+        for topic in &self.topics {
+            match consumer.fetch_metadata(Some(topic), self.metadata_timeout) {
+                Ok(metadata) => {
+                    if let Some(metadata) = metadata
+                        .topics()
+                        .iter()
+                        .find(|metadata| metadata.name() == topic && metadata.error().is_none())
+                    {
+                        partition_counts.insert(topic.clone(), metadata.partitions().len());
+                    }
+                }
+                Err(error) => emit!(KafkaReadError { error }),
+            }
+        }
+        partition_counts
+    }
+
+    /// Builds the list of partitions to assign. Partitions beyond the partition count of a
+    /// topic are skipped, while all partitions are kept for topics with an unknown partition
+    /// count.
+    fn topic_partition_list(
+        &self,
+        partition_counts: &HashMap<String, usize>,
+    ) -> TopicPartitionList {
+        let mut tpl = TopicPartitionList::new();
+        for topic in &self.topics {
+            for &partition in &self.partitions {
+                match partition_counts.get(topic) {
+                    Some(&count) if partition as usize >= count => warn!(
+                        message = "Kafka partition does not exist and is not consumed until the configuration is reloaded.",
+                        %topic,
+                        partition,
+                    ),
+                    // Without an explicit offset, consuming starts from the committed offset.
+                    _ => {
+                        tpl.add_partition(topic, partition);
+                    }
+                }
+            }
+        }
+        tpl
+    }
+
+    /// Logs the topics whose partition count changed since it was last fetched.
+    fn check_partition_counts<C: ConsumerContext>(
+        &self,
+        consumer: &StreamConsumer<C>,
+        partition_counts: &mut HashMap<String, usize>,
+    ) {
+        for (topic, count) in self.partition_counts(consumer) {
+            match partition_counts.insert(topic.clone(), count) {
+                Some(previous) if previous != count => warn!(
+                    message = "Kafka topic partition count changed, reload the configuration to consume added partitions.",
+                    %topic,
+                    previous,
+                    count,
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
 async fn parse_message(
     msg: BorrowedMessage<'_>,
     decoder: Decoder,
@@ -943,10 +1147,12 @@ async fn parse_message(
     acknowledgements: bool,
     finalizer: &Option<OrderedFinalizer<FinalizerEntry>>,
     log_namespace: LogNamespace,
-) {
+) -> usize {
+    let mut events = 0;
     if let Some((count, stream)) = parse_stream(&msg, decoder, keys, log_namespace) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let mut stream = stream.map(|event| {
+            events += 1;
             // All acknowledgements flow through the normal Finalizer stream so
             // that they can be handled in one place, but are only tied to the
             // batch when acknowledgements are enabled
@@ -965,11 +1171,13 @@ async fn parse_message(
                 // here, when `stream` is dropped and runs the destructor [...]".
                 drop(stream);
                 if let Some(f) = finalizer.as_ref() {
-                    f.add(msg.into(), receiver)
+                    f.add(FinalizerEntry::new(msg, events), receiver);
+                    return events;
                 }
             }
         }
     }
+    0
 }
 
 // Turn the received message into a stream of parsed events.
@@ -981,7 +1189,7 @@ fn parse_stream<'a>(
 ) -> Option<(usize, impl Stream<Item = Event> + 'a)> {
     let payload = msg.payload()?; // skip messages with empty payload
 
-    let rmsg = ReceivedMessage::from(msg);
+    let rmsg = ReceivedMessage::from(msg, keys);
 
     let payload = Cursor::new(Bytes::copy_from_slice(payload));
 
@@ -1024,6 +1232,7 @@ struct Keys {
     partition: Option<OwnedValuePath>,
     offset: Option<OwnedValuePath>,
     headers: Option<OwnedValuePath>,
+    include_headers: HashSet<String>,
 }
 
 impl Keys {
@@ -1035,6 +1244,7 @@ impl Keys {
             partition: config.partition_key.path.clone(),
             offset: config.offset_key.path.clone(),
             headers: config.headers_key.path.clone(),
+            include_headers: config.include_headers.iter().cloned().collect(),
         }
     }
 }
@@ -1049,7 +1259,7 @@ struct ReceivedMessage {
 }
 
 impl ReceivedMessage {
-    fn from(msg: &BorrowedMessage<'_>) -> Self {
+    fn from(msg: &BorrowedMessage<'_>, keys: &Keys) -> Self {
         // Extract timestamp from kafka message
         let timestamp = msg
             .timestamp()
@@ -1061,22 +1271,15 @@ impl ReceivedMessage {
             .map(|key| Value::from(Bytes::from(key.to_owned())))
             .unwrap_or(Value::Null);
 
-        let mut headers_map = ObjectMap::new();
-        if let Some(headers) = msg.headers() {
-            for header in headers.iter() {
-                if let Some(value) = header.value {
-                    headers_map.insert(
-                        header.key.into(),
-                        Value::from(Bytes::from(value.to_owned())),
-                    );
-                }
-            }
-        }
+        let headers = msg
+            .headers()
+            .map(|headers| decode_headers(headers.iter(), &keys.include_headers))
+            .unwrap_or_default();
 
         Self {
             timestamp,
             key,
-            headers: headers_map,
+            headers,
             topic: msg.topic().to_string(),
             partition: msg.partition(),
             offset: msg.offset(),
@@ -1155,19 +1358,170 @@ impl ReceivedMessage {
     }
 }
 
+/// Collects the message headers into a map, limited to the `include_headers` ones if any are set.
+///
+/// Header values that are valid UTF-8 are kept as strings, and the others are base64 encoded.
+fn decode_headers<'a>(
+    headers: impl Iterator<Item = Header<'a, &'a [u8]>>,
+    include_headers: &HashSet<String>,
+) -> ObjectMap {
+    headers
+        .filter(|header| include_headers.is_empty() || include_headers.contains(header.key))
+        .filter_map(|header| {
+            let value = header.value?;
+            let value = match std::str::from_utf8(value) {
+                Ok(value) => value.to_owned(),
+                Err(_) => BASE64_STANDARD.encode(value),
+            };
+            Some((header.key.into(), Value::from(value)))
+        })
+        .collect()
+}
+
 #[derive(Debug, Eq, PartialEq, Hash)]
 struct FinalizerEntry {
     topic: String,
     partition: i32,
     offset: i64,
+    /// The number of events decoded from the message.
+    events: usize,
 }
 
-impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
-    fn from(msg: BorrowedMessage<'a>) -> Self {
+impl FinalizerEntry {
+    fn new(msg: BorrowedMessage<'_>, events: usize) -> Self {
         Self {
             topic: msg.topic().into(),
             partition: msg.partition(),
             offset: msg.offset(),
+            events,
+        }
+    }
+}
+
+/// Counts the events consumed from all the partitions that have not been acknowledged yet, so
+/// that fetching the partitions is paused at the broker while there are more than
+/// `max_in_flight_events` of them.
+struct InFlightEvents {
+    count: watch::Sender<usize>,
+    limit: usize,
+    paused_partitions: AtomicUsize,
+}
+
+impl InFlightEvents {
+    fn new(limit: usize) -> Self {
+        Self {
+            count: watch::Sender::new(0),
+            limit,
+            paused_partitions: AtomicUsize::new(0),
+        }
+    }
+
+    fn add(&self, events: usize) {
+        self.update(|count| *count += events);
+    }
+
+    fn remove(&self, events: usize) {
+        self.update(|count| *count = count.saturating_sub(events));
+    }
+
+    /// Updates the count, only waking the partition consumers up when the limit is crossed.
+    fn update(&self, update: impl FnOnce(&mut usize)) {
+        let limit = self.limit;
+        self.count.send_if_modified(|count| {
+            let was_exceeded = *count > limit;
+            update(count);
+            was_exceeded != (*count > limit)
+        });
+    }
+}
+
+/// The share of a partition consumer in the [`InFlightEvents`].
+///
+/// Its events are removed, and the partition is resumed, when the partition consumer ends,
+/// including when it is aborted at the drain deadline with acknowledgements still pending.
+struct PartitionInFlightEvents<'a> {
+    consumer: &'a StreamConsumer<KafkaSourceContext>,
+    in_flight_events: &'a InFlightEvents,
+    count: watch::Receiver<usize>,
+    tp: TopicPartition,
+    events: usize,
+    paused: bool,
+}
+
+impl<'a> PartitionInFlightEvents<'a> {
+    fn new(consumer: &'a StreamConsumer<KafkaSourceContext>, tp: TopicPartition) -> Option<Self> {
+        let in_flight_events = consumer.context().in_flight_events.as_ref()?;
+        Some(Self {
+            consumer,
+            in_flight_events,
+            count: in_flight_events.count.subscribe(),
+            tp,
+            events: 0,
+            paused: false,
+        })
+    }
+
+    fn add(&mut self, events: usize) {
+        self.events += events;
+        self.in_flight_events.add(events);
+    }
+
+    fn remove(&mut self, events: usize) {
+        self.events = self.events.saturating_sub(events);
+        self.in_flight_events.remove(events);
+    }
+
+    /// Waits for the in-flight events limit to be crossed.
+    async fn changed(&mut self) -> Result<(), watch::error::RecvError> {
+        self.count.changed().await
+    }
+
+    /// Pauses or resumes fetching the partition at the broker, following whether the in-flight
+    /// events limit is exceeded. Returns whether the partition is paused.
+    fn sync_paused(&mut self) -> bool {
+        let exceeded = *self.count.borrow_and_update() > self.in_flight_events.limit;
+        if exceeded != self.paused && self.set_paused(exceeded) {
+            self.paused = exceeded;
+        }
+        self.paused
+    }
+
+    fn set_paused(&self, paused: bool) -> bool {
+        let (topic, partition) = (&self.tp.0, self.tp.1);
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(topic, partition);
+        let result = if paused {
+            self.consumer.pause(&tpl)
+        } else {
+            self.consumer.resume(&tpl)
+        };
+
+        match result {
+            Ok(()) => {
+                let paused_partitions = &self.in_flight_events.paused_partitions;
+                let count = if paused {
+                    debug!("Pausing partition {}:{}.", topic, partition);
+                    paused_partitions.fetch_add(1, Ordering::Relaxed) + 1
+                } else {
+                    debug!("Resuming partition {}:{}.", topic, partition);
+                    paused_partitions.fetch_sub(1, Ordering::Relaxed) - 1
+                };
+                emit!(KafkaPausedPartitions { count });
+                true
+            }
+            Err(error) => {
+                emit!(KafkaPartitionPauseError { error });
+                false
+            }
+        }
+    }
+}
+
+impl Drop for PartitionInFlightEvents<'_> {
+    fn drop(&mut self) {
+        self.in_flight_events.remove(self.events);
+        if self.paused {
+            self.set_paused(false);
         }
     }
 }
@@ -1179,6 +1533,22 @@ fn create_consumer(
     StreamConsumer<KafkaSourceContext>,
     UnboundedReceiver<KafkaCallback>,
 )> {
+    if !config.partitions.is_empty() {
+        snafu::ensure!(
+            config.group_instance_id.is_none(),
+            ManualAssignmentWithGroupInstanceIdSnafu
+        );
+        if let Some(topic) = config.topics.iter().find(|topic| topic.starts_with('^')) {
+            return Err(BuildError::ManualAssignmentWithTopicRegex {
+                topic: topic.clone(),
+            }
+            .into());
+        }
+        if let Some(&partition) = config.partitions.iter().find(|&&partition| partition < 0) {
+            return Err(BuildError::InvalidPartition { partition }.into());
+        }
+    }
+
     let mut client_config = ClientConfig::new();
     client_config
         .set("group.id", &config.group_id)
@@ -1206,6 +1576,10 @@ fn create_consumer(
         .set("statistics.interval.ms", "1000")
         .set("client.id", "vector");
 
+    if let Some(group_instance_id) = &config.group_instance_id {
+        client_config.set("group.instance.id", group_instance_id);
+    }
+
     config.auth.apply(&mut client_config)?;
 
     if let Some(librdkafka_options) = &config.librdkafka_options {
@@ -1219,12 +1593,16 @@ fn create_consumer(
         .create_with_context::<_, StreamConsumer<_>>(KafkaSourceContext::new(
             config.metrics.topic_lag_metric,
             acknowledgements,
+            config.max_in_flight_events,
             callbacks,
             Span::current(),
         ))
         .context(CreateSnafu)?;
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    consumer.subscribe(&topics).context(SubscribeSnafu)?;
+    // Manually assigned partitions are assigned once the source is running.
+    if config.partitions.is_empty() {
+        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        consumer.subscribe(&topics).context(SubscribeSnafu)?;
+    }
 
     Ok((consumer, callback_rx))
 }
@@ -1250,6 +1628,9 @@ struct KafkaSourceContext {
     acknowledgements: bool,
     stats: kafka::KafkaStatisticsContext,
 
+    /// The events not acknowledged yet, if fetching is paused when there are too many of them
+    in_flight_events: Option<InFlightEvents>,
+
     /// A callback channel used to coordinate between the main consumer task and the acknowledgement task
     callbacks: UnboundedSender<KafkaCallback>,
 
@@ -1261,6 +1642,7 @@ impl KafkaSourceContext {
     fn new(
         expose_lag_metrics: bool,
         acknowledgements: bool,
+        max_in_flight_events: Option<usize>,
         callbacks: UnboundedSender<KafkaCallback>,
         span: Span,
     ) -> Self {
@@ -1270,6 +1652,7 @@ impl KafkaSourceContext {
                 span,
             },
             acknowledgements,
+            in_flight_events: max_in_flight_events.map(InFlightEvents::new),
             consumer: OnceLock::default(),
             callbacks,
         }
@@ -1417,6 +1800,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn decodes_headers() {
+        use rdkafka::message::{Header, Headers, OwnedHeaders};
+
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: "tenant_id",
+                value: Some("tenant-1"),
+            })
+            .insert(Header {
+                key: "trace_id",
+                value: Some(&[0xde, 0xad, 0xbe, 0xef][..]),
+            })
+            .insert(Header {
+                key: "other",
+                value: Some("value"),
+            })
+            .insert(Header::<&str> {
+                key: "null",
+                value: None,
+            });
+
+        let decoded = super::decode_headers(headers.iter(), &HashSet::new());
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded["tenant_id"], Value::from("tenant-1"));
+        assert_eq!(decoded["trace_id"], Value::from("3q2+7w=="));
+        assert_eq!(decoded["other"], Value::from("value"));
+
+        let include_headers = ["tenant_id", "trace_id", "null"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let decoded = super::decode_headers(headers.iter(), &include_headers);
+        assert_eq!(decoded.len(), 2);
+        assert!(!decoded.contains_key("other"));
+    }
+
     #[test]
     fn test_output_schema_definition_vector_namespace() {
         let definitions = make_config("topic", "group", LogNamespace::Vector, None)
@@ -1515,6 +1935,89 @@ mod test {
         };
         assert!(create_consumer(&config, true).is_err());
     }
+
+    #[tokio::test]
+    async fn consumer_create_static_membership() {
+        let config = KafkaSourceConfig {
+            group_instance_id: Some("instance".into()),
+            ..make_config("topic", "group", LogNamespace::Legacy, None)
+        };
+        assert!(create_consumer(&config, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_manual_assignment() {
+        let config = KafkaSourceConfig {
+            partitions: vec![0, 1],
+            ..make_config("topic", "group", LogNamespace::Legacy, None)
+        };
+        assert!(create_consumer(&config, true).is_ok());
+
+        for config in [
+            KafkaSourceConfig {
+                group_instance_id: Some("instance".into()),
+                ..config.clone()
+            },
+            KafkaSourceConfig {
+                topics: vec!["^topic-.+".into()],
+                ..config.clone()
+            },
+            KafkaSourceConfig {
+                partitions: vec![0, -1],
+                ..config
+            },
+        ] {
+            assert!(create_consumer(&config, true).is_err());
+        }
+    }
+
+    #[test]
+    fn in_flight_events_notify_on_crossing_limit() {
+        let in_flight_events = InFlightEvents::new(10);
+        let mut count = in_flight_events.count.subscribe();
+
+        in_flight_events.add(10);
+        assert!(!count.has_changed().unwrap());
+        in_flight_events.add(1);
+        assert!(count.has_changed().unwrap());
+        assert_eq!(*count.borrow_and_update(), 11);
+
+        in_flight_events.add(5);
+        in_flight_events.remove(3);
+        assert!(!count.has_changed().unwrap());
+        in_flight_events.remove(20);
+        assert!(count.has_changed().unwrap());
+        assert_eq!(*count.borrow_and_update(), 0);
+    }
+
+    #[test]
+    fn manual_assignment_partitions() {
+        let config = KafkaSourceConfig {
+            topics: vec!["topic-1".into(), "topic-2".into()],
+            partitions: vec![0, 2],
+            ..make_config("topic", "group", LogNamespace::Legacy, None)
+        };
+        let assignment = ManualAssignment::new(&config).unwrap();
+
+        let partition_counts = HashMap::from([("topic-1".to_owned(), 2)]);
+        let tpl = assignment.topic_partition_list(&partition_counts);
+        let partitions: Vec<_> = tpl
+            .elements()
+            .iter()
+            .map(|elem| (elem.topic().to_owned(), elem.partition()))
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![
+                ("topic-1".to_owned(), 0),
+                ("topic-2".to_owned(), 0),
+                ("topic-2".to_owned(), 2),
+            ]
+        );
+
+        let config = make_config("topic", "group", LogNamespace::Legacy, None);
+        assert!(ManualAssignment::new(&config).is_none());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -0,0 +1,397 @@
+//! The `k8s-paths` subcommand, listing the pod log files the source would collect from a pod
+//! logs directory, without collecting them.
+
+#![allow(missing_docs)]
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use serde::Serialize;
+
+use super::{
+    default_path_exclusion,
+    k8s_paths_provider::{is_excluded_path, NameFilter},
+    path_helpers::{
+        is_active_log_file, parse_log_file_path_strict, try_parse_log_file_path_with_delimiter,
+        DEFAULT_POD_DIR_DELIMITER, K8S_LOGS_DIR,
+    },
+    prepare_exclude_paths, prepare_name_patterns, prepare_namespace_filter,
+    prepare_pod_dir_delimiter, Config,
+};
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The pod logs directory to discover the log files in.
+    #[arg(long, default_value = K8S_LOGS_DIR)]
+    root: PathBuf,
+
+    /// The character delimiting the namespace, the name and the UID of the pods in the names of
+    /// their logs directories.
+    #[arg(long, default_value_t = DEFAULT_POD_DIR_DELIMITER)]
+    pod_dir_delimiter: char,
+
+    /// Glob patterns of the log files to exclude. Defaults to the ones of the source.
+    #[arg(long = "exclude-paths-glob-pattern", value_delimiter(','))]
+    exclude_paths_glob_patterns: Option<Vec<PathBuf>>,
+
+    /// Glob patterns of the names of the containers to include.
+    #[arg(long = "include-container-name", value_delimiter(','))]
+    include_container_names: Vec<String>,
+
+    /// Glob patterns of the names of the containers to exclude.
+    #[arg(long = "exclude-container-name", value_delimiter(','))]
+    exclude_container_names: Vec<String>,
+
+    /// Glob patterns of the namespaces to include.
+    #[arg(long = "include-namespace", value_delimiter(','))]
+    include_namespaces: Vec<String>,
+
+    /// Glob patterns of the namespaces to exclude.
+    #[arg(long = "exclude-namespace", value_delimiter(','))]
+    exclude_namespaces: Vec<String>,
+
+    /// Exclude the log files whose pod UID is malformed, like the source does with
+    /// `strict_log_paths`.
+    #[arg(long)]
+    strict_log_paths: bool,
+
+    /// Print the discovered log files as JSON, one object per line.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Opts {
+    /// The configuration of the source with the filters of the options.
+    fn config(&self) -> Config {
+        Config {
+            pod_dir_delimiter: self.pod_dir_delimiter,
+            exclude_paths_glob_patterns: self
+                .exclude_paths_glob_patterns
+                .clone()
+                .unwrap_or_else(default_path_exclusion),
+            include_container_names: self.include_container_names.clone(),
+            exclude_container_names: self.exclude_container_names.clone(),
+            include_namespaces: self.include_namespaces.clone(),
+            exclude_namespaces: self.exclude_namespaces.clone(),
+            strict_log_paths: self.strict_log_paths,
+            ..Default::default()
+        }
+    }
+}
+
+/// A log file found in the pod logs directory.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct DiscoveredFile {
+    path: String,
+    pod_namespace: Option<String>,
+    pod_name: Option<String>,
+    pod_uid: Option<String>,
+    container_name: Option<String>,
+    restart_count: Option<u32>,
+    /// Why the path doesn't parse, if it doesn't.
+    parse_error: Option<String>,
+    /// Whether the log file would be collected.
+    matched: bool,
+    /// Why the log file wouldn't be collected, if it wouldn't.
+    excluded_by: Option<&'static str>,
+}
+
+/// Lists the log files under the pod logs directory, and checks them against the filters the
+/// same way the source does.
+///
+/// The source only collects the log files of the pods it is told about by the Kubernetes API,
+/// so the log files of pods that are gone are listed here, but wouldn't be collected.
+fn discover(root: &Path, config: &Config) -> crate::Result<Vec<DiscoveredFile>> {
+    let pod_dir_delimiter = prepare_pod_dir_delimiter(config.pod_dir_delimiter)?;
+    let exclude_paths = prepare_exclude_paths(config)?;
+    let namespace_filter = prepare_namespace_filter(config)?;
+    let container_name_filter = NameFilter::new(
+        prepare_name_patterns(&config.include_container_names)?,
+        prepare_name_patterns(&config.exclude_container_names)?,
+    );
+
+    let root = root.to_str().ok_or("root is not a valid UTF-8 string")?;
+    let pattern = [glob::Pattern::escape(root).as_str(), "*", "*", "*"].join("/");
+    let paths = glob::glob_with(
+        &pattern,
+        glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(paths
+        .flatten()
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let path_str = path.to_string_lossy();
+            let parsed = try_parse_log_file_path_with_delimiter(&path_str, pod_dir_delimiter);
+            let excluded_by = if !is_active_log_file(&path_str) {
+                Some("rotation")
+            } else if parsed
+                .as_ref()
+                .map_or(false, |info| !namespace_filter.matches(info.pod_namespace))
+            {
+                Some("namespace")
+            } else if parsed.as_ref().map_or(false, |info| {
+                !container_name_filter.matches(info.container_name)
+            }) {
+                Some("container_name")
+            } else if is_excluded_path(&path, &exclude_paths) {
+                Some("exclude_paths_glob_patterns")
+            } else if config.strict_log_paths
+                && parsed.is_ok()
+                && parse_log_file_path_strict(&path_str, pod_dir_delimiter).is_none()
+            {
+                Some("strict_log_paths")
+            } else {
+                None
+            };
+
+            let (info, parse_error) = match parsed {
+                Ok(info) => (Some(info), None),
+                Err(error) => (None, Some(error.to_string())),
+            };
+            DiscoveredFile {
+                path: path_str.to_string(),
+                pod_namespace: info.as_ref().map(|info| info.pod_namespace.to_owned()),
+                pod_name: info.as_ref().map(|info| info.pod_name.to_owned()),
+                pod_uid: info.as_ref().map(|info| info.pod_uid.to_owned()),
+                container_name: info.as_ref().map(|info| info.container_name.to_owned()),
+                restart_count: info.and_then(|info| info.restart_count),
+                parse_error,
+                matched: excluded_by.is_none(),
+                excluded_by,
+            }
+        })
+        .collect())
+}
+
+fn print_table(files: &[DiscoveredFile]) {
+    let header = [
+        "MATCHED",
+        "NAMESPACE",
+        "POD",
+        "UID",
+        "CONTAINER",
+        "RESTART",
+        "PATH",
+        "NOTE",
+    ];
+    let rows: Vec<[String; 8]> = files
+        .iter()
+        .map(|file| {
+            [
+                if file.matched { "yes" } else { "no" }.to_owned(),
+                file.pod_namespace.clone().unwrap_or_default(),
+                file.pod_name.clone().unwrap_or_default(),
+                file.pod_uid.clone().unwrap_or_default(),
+                file.container_name.clone().unwrap_or_default(),
+                file.restart_count
+                    .map(|count| count.to_string())
+                    .unwrap_or_default(),
+                file.path.clone(),
+                match (file.excluded_by, &file.parse_error) {
+                    (Some(excluded_by), _) => format!("excluded by {}", excluded_by),
+                    (None, Some(error)) => error.clone(),
+                    (None, None) => String::new(),
+                },
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[&str]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    #[allow(clippy::print_stdout)]
+    {
+        println!("{}", format_row(&header));
+        for row in &rows {
+            let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+            println!("{}", format_row(&cells));
+        }
+    }
+}
+
+/// Prints the log files that would be collected, exiting with an error if there are none.
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let files = match discover(&opts.root, &opts.config()) {
+        Ok(files) => files,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("Failed to discover the log files: {}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
+
+    if opts.json {
+        for file in &files {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{}", serde_json::to_string(file).unwrap());
+            }
+        }
+    } else {
+        print_table(&files);
+    }
+
+    if files.iter().any(|file| file.matched) {
+        exitcode::OK
+    } else {
+        exitcode::DATAERR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    fn touch(root: &Path, path: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    fn discovered(root: &Path, config: &Config) -> Vec<(String, bool, Option<&'static str>)> {
+        discover(root, config)
+            .unwrap()
+            .into_iter()
+            .map(|file| {
+                let path = file.path.strip_prefix(root.to_str().unwrap()).unwrap();
+                (path.to_owned(), file.matched, file.excluded_by)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        touch(root, "default_app-0_uid-0/app/0.log");
+        touch(root, "default_app-0_uid-0/app/0.log.20240101-120000");
+        touch(root, "default_app-0_uid-0/istio-proxy/0.log");
+        touch(root, "default_app-0_uid-0/app/1.log.tmp");
+        touch(root, "kube-system_dns-0_uid-1/dns/0.log");
+        touch(root, "malformed/app/0.log");
+
+        let config = Config {
+            exclude_container_names: vec!["istio-*".to_owned()],
+            exclude_namespaces: vec!["kube-system".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            discovered(root, &config),
+            vec![
+                ("/default_app-0_uid-0/app/0.log".to_owned(), true, None),
+                (
+                    "/default_app-0_uid-0/app/0.log.20240101-120000".to_owned(),
+                    false,
+                    Some("rotation")
+                ),
+                (
+                    "/default_app-0_uid-0/app/1.log.tmp".to_owned(),
+                    false,
+                    Some("rotation")
+                ),
+                (
+                    "/default_app-0_uid-0/istio-proxy/0.log".to_owned(),
+                    false,
+                    Some("container_name")
+                ),
+                (
+                    "/kube-system_dns-0_uid-1/dns/0.log".to_owned(),
+                    false,
+                    Some("namespace")
+                ),
+                ("/malformed/app/0.log".to_owned(), true, None),
+            ]
+        );
+
+        let files = discover(root, &config).unwrap();
+        let file = files.first().unwrap();
+        assert_eq!(file.pod_namespace.as_deref(), Some("default"));
+        assert_eq!(file.pod_name.as_deref(), Some("app-0"));
+        assert_eq!(file.pod_uid.as_deref(), Some("uid-0"));
+        assert_eq!(file.container_name.as_deref(), Some("app"));
+        assert_eq!(file.restart_count, Some(0));
+        assert!(files.last().unwrap().parse_error.is_some());
+    }
+
+    #[test]
+    fn test_discover_exclude_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        touch(root, "default_app-0_uid-0/app/0.log");
+
+        let config = Config {
+            exclude_paths_glob_patterns: vec![PathBuf::from("**/default_*/**")],
+            ..Default::default()
+        };
+        assert_eq!(
+            discovered(root, &config),
+            vec![(
+                "/default_app-0_uid-0/app/0.log".to_owned(),
+                false,
+                Some("exclude_paths_glob_patterns")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_discover_strict_log_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        touch(
+            root,
+            "default_app-0_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/app/0.log",
+        );
+        touch(root, "kube-system_dns-0_system-7f9c2b1d/dns/0.log");
+        touch(root, "other_tool_state/data/0.log");
+
+        let config = Config {
+            strict_log_paths: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            discovered(root, &config),
+            vec![
+                (
+                    "/default_app-0_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/app/0.log".to_owned(),
+                    true,
+                    None
+                ),
+                (
+                    "/kube-system_dns-0_system-7f9c2b1d/dns/0.log".to_owned(),
+                    true,
+                    None
+                ),
+                (
+                    "/other_tool_state/data/0.log".to_owned(),
+                    false,
+                    Some("strict_log_paths")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Emits an event when a pod log file is first discovered.
+
+#![deny(missing_docs)]
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::channel::mpsc;
+use vector_lib::config::{LegacyKey, LogNamespace};
+use vector_lib::lookup::{lookup_v2::OptionalTargetPath, path, OwnedTargetPath};
+
+use super::{
+    create_event,
+    path_helpers::{LogFileInfo, LogLayout},
+    pod_metadata_annotator::FieldsSpec,
+    Config,
+};
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+};
+
+/// The value of the `event` field marking the events of the discovered files.
+pub const FILE_DISCOVERED_EVENT: &str = "file_discovered";
+
+const MESSAGE: &str = "Pod log file discovered.";
+
+/// Tracks the log files provided by the paths provider, to tell the newly
+/// discovered ones apart.
+pub struct FileDiscovery {
+    known_paths: Mutex<HashSet<PathBuf>>,
+    tx: mpsc::UnboundedSender<PathBuf>,
+}
+
+impl FileDiscovery {
+    /// Creates a new [`FileDiscovery`], and the receiver of the paths of the
+    /// discovered files.
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<PathBuf>) {
+        let (tx, rx) = mpsc::unbounded();
+        let file_discovery = Self {
+            known_paths: Mutex::default(),
+            tx,
+        };
+        (file_discovery, rx)
+    }
+
+    /// Sends the paths that weren't provided before.
+    ///
+    /// The paths no longer provided are forgotten once their files are gone,
+    /// so a file that is only provided again later, like while the metadata
+    /// of the namespace of its Pod is fetched again, isn't discovered twice.
+    pub fn update(&self, paths: &[PathBuf]) {
+        let mut known_paths = self.known_paths.lock().expect("the lock is never poisoned");
+        let provided: HashSet<_> = paths.iter().collect();
+        known_paths.retain(|path| provided.contains(path) || path.exists());
+
+        for path in paths {
+            if known_paths.insert(path.clone()) {
+                // The receiver is only dropped when the source shuts down.
+                _ = self.tx.unbounded_send(path.clone());
+            }
+        }
+    }
+}
+
+/// Creates the events of the discovered files, carrying the information
+/// extracted from their paths.
+pub struct FileDiscoveredEvents {
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    fields_spec: FieldsSpec,
+    ingestion_timestamp_field: Option<OwnedTargetPath>,
+    log_namespace: LogNamespace,
+}
+
+impl FileDiscoveredEvents {
+    /// Creates a new [`FileDiscoveredEvents`].
+    pub const fn new(
+        log_layout: LogLayout,
+        pod_dir_delimiter: char,
+        fields_spec: FieldsSpec,
+        ingestion_timestamp_field: Option<OwnedTargetPath>,
+        log_namespace: LogNamespace,
+    ) -> Self {
+        Self {
+            log_layout,
+            pod_dir_delimiter,
+            fields_spec,
+            ingestion_timestamp_field,
+            log_namespace,
+        }
+    }
+
+    /// Creates the event of the discovered file, if its path parses.
+    pub fn create_event(&self, path: &Path) -> Option<Event> {
+        let file = path.to_str()?;
+        let file_info = self
+            .log_layout
+            .parse_log_file_path(file, self.pod_dir_delimiter)?;
+
+        let mut event = create_event(
+            Bytes::from_static(MESSAGE.as_bytes()),
+            file,
+            false,
+            self.ingestion_timestamp_field.as_ref(),
+            self.log_namespace,
+        );
+        let log = event.as_mut_log();
+        self.log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            log_schema().timestamp_key().map(LegacyKey::Overwrite),
+            path!("timestamp"),
+            Utc::now(),
+        );
+        self.log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            Some(LegacyKey::Overwrite(path!("kubernetes", "event"))),
+            path!("event"),
+            FILE_DISCOVERED_EVENT,
+        );
+        self.annotate(log, &file_info);
+
+        Some(event)
+    }
+
+    fn annotate(&self, log: &mut LogEvent, file_info: &LogFileInfo<'_>) {
+        let fields_spec = &self.fields_spec;
+        for (field, key, value) in [
+            (
+                &fields_spec.pod_namespace,
+                "pod_namespace",
+                file_info.pod_namespace,
+            ),
+            (&fields_spec.pod_name, "pod_name", file_info.pod_name),
+            (&fields_spec.pod_uid, "pod_uid", file_info.pod_uid),
+            (
+                &fields_spec.container_name,
+                "container_name",
+                file_info.container_name,
+            ),
+        ] {
+            // The container log symlinks don't carry the pod UID.
+            if !value.is_empty() {
+                self.insert(log, field, key, value);
+            }
+        }
+
+        if let Some(restart_count) = file_info.restart_count {
+            self.insert(
+                log,
+                &fields_spec.container_restart_count,
+                "container_restart_count",
+                i64::from(restart_count),
+            );
+        }
+
+        if !file_info.extra_segments.is_empty() {
+            self.insert(
+                log,
+                &fields_spec.pod_dir_extra_segments,
+                "pod_dir_extra_segments",
+                file_info
+                    .extra_segments
+                    .iter()
+                    .map(|segment| Value::from(*segment))
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        if let Some(sandbox_attempt) = file_info.sandbox_attempt {
+            self.log_namespace.insert_source_metadata(
+                Config::NAME,
+                log,
+                Some(LegacyKey::Overwrite(path!("kubernetes", "sandbox_attempt"))),
+                path!("sandbox_attempt"),
+                i64::from(sandbox_attempt),
+            );
+        }
+    }
+
+    fn insert(
+        &self,
+        log: &mut LogEvent,
+        field: &OptionalTargetPath,
+        key: &str,
+        value: impl Into<Value>,
+    ) {
+        let legacy_key = field
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+        self.log_namespace
+            .insert_source_metadata(Config::NAME, log, legacy_key, path!(key), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::StreamExt;
+    use vrl::value;
+
+    use super::*;
+    use crate::kubernetes::path_helpers::DEFAULT_POD_DIR_DELIMITER;
+
+    fn file_discovered_events(log_namespace: LogNamespace) -> FileDiscoveredEvents {
+        FileDiscoveredEvents::new(
+            LogLayout::Auto,
+            DEFAULT_POD_DIR_DELIMITER,
+            FieldsSpec::default(),
+            None,
+            log_namespace,
+        )
+    }
+
+    #[tokio::test]
+    async fn one_event_per_discovered_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let pod_dir = dir
+            .path()
+            .join("sandbox0-ns_sandbox0-name_sandbox0-uid")
+            .join("sandbox0-container0-name");
+        std::fs::create_dir_all(&pod_dir).unwrap();
+        let files: Vec<_> = ["0.log", "1.log", "2.log"]
+            .iter()
+            .map(|name| {
+                let path = pod_dir.join(name);
+                std::fs::write(&path, "").unwrap();
+                path
+            })
+            .collect();
+        let unparseable = dir.path().join("unparseable.log");
+
+        let (file_discovery, rx) = FileDiscovery::new();
+        file_discovery.update(&files[..1]);
+        file_discovery.update(&files[..2]);
+        file_discovery.update(&files[..2]);
+        // The first file is still there, so it isn't discovered again once
+        // it is provided again.
+        file_discovery.update(&files[1..2]);
+        file_discovery.update(&[files[0].clone(), files[2].clone(), unparseable.clone()]);
+        // The second file is gone, so it is discovered again once recreated.
+        std::fs::remove_file(&files[1]).unwrap();
+        file_discovery.update(&files[..1]);
+        std::fs::write(&files[1], "").unwrap();
+        file_discovery.update(&files);
+        drop(file_discovery);
+
+        let events = file_discovered_events(LogNamespace::Legacy);
+        let discovered: Vec<_> = rx
+            .filter_map(|path| futures::future::ready(events.create_event(&path)))
+            .collect()
+            .await;
+
+        let mut counts = HashMap::new();
+        for event in &discovered {
+            let log = event.as_log();
+            assert_eq!(log["kubernetes.event"], FILE_DISCOVERED_EVENT.into());
+            *counts
+                .entry(log["file"].to_string_lossy().into_owned())
+                .or_insert(0) += 1;
+        }
+        let file = |index: usize| files[index].to_str().unwrap().to_owned();
+        assert_eq!(
+            counts,
+            HashMap::from([(file(0), 1), (file(1), 2), (file(2), 1)])
+        );
+    }
+
+    #[test]
+    fn create_event_legacy_namespace() {
+        let path = Path::new(
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid_2/sandbox0-container0-name/3.log",
+        );
+        let event = file_discovered_events(LogNamespace::Legacy)
+            .create_event(path)
+            .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["message"], MESSAGE.into());
+        assert_eq!(log["file"], path.to_str().unwrap().into());
+        assert_eq!(log["source_type"], Config::NAME.into());
+        assert!(log.get_timestamp().is_some());
+        assert_eq!(
+            log["kubernetes"],
+            value!({
+                "event": "file_discovered",
+                "pod_namespace": "sandbox0-ns",
+                "pod_name": "sandbox0-name",
+                "pod_uid": "sandbox0-uid",
+                "container_name": "sandbox0-container0-name",
+                "container_restart_count": 3,
+                "sandbox_attempt": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn create_event_vector_namespace() {
+        let path = Path::new(
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-0123abcd.log",
+        );
+        let event = file_discovered_events(LogNamespace::Vector)
+            .create_event(path)
+            .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log.value(), &value!(MESSAGE));
+        let metadata = log.metadata().value();
+        assert_eq!(
+            metadata.get(path!("kubernetes_logs", "event")),
+            Some(&value!("file_discovered"))
+        );
+        assert_eq!(
+            metadata.get(path!("kubernetes_logs", "pod_name")),
+            Some(&value!("sandbox0-name"))
+        );
+        // The container log symlinks don't carry the pod UID.
+        assert_eq!(metadata.get(path!("kubernetes_logs", "pod_uid")), None);
+    }
+
+    #[test]
+    fn create_event_unparseable() {
+        let events = file_discovered_events(LogNamespace::Legacy);
+        assert!(events
+            .create_event(Path::new("/var/log/pods/unparseable.log"))
+            .is_none());
+    }
+}
@@ -0,0 +1,294 @@
+//! Identification of the log files, for checkpointing.
+
+use std::path::Path;
+
+use vector_lib::configurable::configurable_component;
+use vector_lib::file_source::FingerprintStrategy;
+
+use crate::kubernetes::path_helpers::{
+    parse_log_file_path, parse_restart_count, DEFAULT_POD_DIR_DELIMITER,
+};
+
+/// Configuration for how the log files are identified.
+///
+/// This is important for checkpointing when the log files are rotated.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The strategy used to identify the log files.\n\nThis is important for checkpointing when the log files are rotated."
+))]
+pub enum FingerprintConfig {
+    /// Compute a checksum over the first `fingerprint_lines` lines of the log file.
+    #[default]
+    Checksum,
+
+    /// Combine the identity of the container the log file belongs to with a checksum of the
+    /// first bytes of the file.
+    ///
+    /// The identity is made of the pod UID, the container name and the restart count parsed from
+    /// the path of the log file. Files rotated by a rename keep both, so they continue to be read
+    /// from their checkpoint. Files truncated in place by the kubelet hold new first bytes, so
+    /// they are read from the beginning as a new file, and the rest of the old stream is
+    /// discarded. Files whose path doesn't carry the identity are identified by the checksum
+    /// alone.
+    ///
+    /// With a custom `pod_dir_delimiter`, the whole pod logs directory name stands in for the pod
+    /// UID.
+    K8sIdentity {
+        /// The number of bytes from the beginning of the log file to compute the checksum over.
+        ///
+        /// Log files shorter than this aren't read until they grow to this size.
+        #[serde(default = "default_bytes")]
+        #[configurable(metadata(docs::type_unit = "bytes"))]
+        bytes: usize,
+    },
+}
+
+const fn default_bytes() -> usize {
+    256
+}
+
+impl FingerprintConfig {
+    /// Builds the fingerprinting strategy, using `fingerprint_lines` for the
+    /// [`FingerprintConfig::Checksum`] strategy, and `pod_dir_delimiter` to
+    /// pick the identity of the [`FingerprintConfig::K8sIdentity`] one.
+    pub(super) fn strategy(
+        self,
+        fingerprint_lines: usize,
+        pod_dir_delimiter: char,
+    ) -> FingerprintStrategy {
+        match self {
+            Self::Checksum => FingerprintStrategy::FirstLinesChecksum {
+                ignored_header_bytes: 0,
+                lines: fingerprint_lines,
+            },
+            Self::K8sIdentity { bytes } => FingerprintStrategy::PathIdentityChecksum {
+                bytes,
+                identity: if pod_dir_delimiter == DEFAULT_POD_DIR_DELIMITER {
+                    k8s_identity
+                } else {
+                    k8s_pod_dir_identity
+                },
+            },
+        }
+    }
+}
+
+/// Derives the identity of the container a pod log file belongs to from its
+/// path, as `<pod_uid>/<container_name>/<restart_count>`.
+///
+/// Neither of the components can contain a `/`, so the identities of
+/// different containers never collide.
+fn k8s_identity(path: &Path) -> Option<String> {
+    let info = parse_log_file_path(path.to_str()?)?;
+    let restart_count = info.restart_count?;
+    if info.pod_uid.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}/{}/{}",
+        info.pod_uid, info.container_name, restart_count
+    ))
+}
+
+/// Derives the identity of the container a pod log file belongs to from its
+/// path, as `<pod_dir>/<container_name>/<restart_count>`.
+///
+/// Used when the pod logs directory name isn't delimited by the default
+/// delimiter, as the directory name identifies the pod just as well as the
+/// pod UID in it, without having to be split.
+fn k8s_pod_dir_identity(path: &Path) -> Option<String> {
+    let mut components = path.iter().rev().map(|component| component.to_str());
+    let file_name = components.next()??;
+    let container_name = components.next()??;
+    let pod_dir = components.next()??;
+    let restart_count = parse_restart_count(file_name)?;
+    Some(format!("{}/{}/{}", pod_dir, container_name, restart_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, OpenOptions},
+        io::Write,
+        path::PathBuf,
+    };
+
+    use similar_asserts::assert_eq;
+    use vector_lib::file_source::{Compression, Fingerprinter};
+
+    use super::*;
+
+    const POD_DIR: &str = "sandbox0-ns_sandbox0-name_sandbox0-uid";
+
+    fn fingerprinter() -> Fingerprinter {
+        Fingerprinter {
+            strategy: FingerprintConfig::K8sIdentity { bytes: 16 }
+                .strategy(1, DEFAULT_POD_DIR_DELIMITER),
+            max_line_length: 1024,
+            ignore_not_found: false,
+            compression: Compression::Auto,
+        }
+    }
+
+    #[test]
+    fn test_k8s_identity() {
+        let cases = vec![
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+                Some("sandbox0-uid/sandbox0-container0-name/1"),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log.20240101-120000.gz",
+                Some("sandbox0-uid/sandbox0-container0-name/1"),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/app.log",
+                None,
+            ),
+            ("/var/log/containers/name_ns_container-id.log", None),
+            ("/var/log/syslog", None),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                k8s_identity(Path::new(path)).as_deref(),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_k8s_pod_dir_identity() {
+        let cases = vec![
+            (
+                "/var/log/pods/sandbox0-ns-sandbox0-name-sandbox0-uid/sandbox0-container0-name/1.log",
+                Some("sandbox0-ns-sandbox0-name-sandbox0-uid/sandbox0-container0-name/1"),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns-sandbox0-name-sandbox0-uid/sandbox0-container0-name/1.log.20240101-120000.gz",
+                Some("sandbox0-ns-sandbox0-name-sandbox0-uid/sandbox0-container0-name/1"),
+            ),
+            (
+                "/var/log/pods/sandbox0-ns-sandbox0-name-sandbox0-uid/sandbox0-container0-name/app.log",
+                None,
+            ),
+            ("/var/log/syslog", None),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                k8s_pod_dir_identity(Path::new(path)).as_deref(),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_identity_distinguishes_containers() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join(POD_DIR).join("first");
+        let second = dir.path().join(POD_DIR).join("second");
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        // Containers started from the same image commonly log the same banner first.
+        let banner = b"2024-01-01T00:00:00Z stdout F starting up\n";
+        fs::write(first.join("0.log"), banner).unwrap();
+        fs::write(second.join("0.log"), banner).unwrap();
+        fs::write(first.join("1.log"), banner).unwrap();
+
+        let fingerprinter = fingerprinter();
+        let mut buf = Vec::new();
+        let mut fingerprint = |path: PathBuf| {
+            fingerprinter
+                .get_fingerprint_of_file(&path, &mut buf)
+                .unwrap()
+        };
+        let first_0 = fingerprint(first.join("0.log"));
+        assert_ne!(first_0, fingerprint(second.join("0.log")));
+        assert_ne!(first_0, fingerprint(first.join("1.log")));
+    }
+
+    #[test]
+    fn test_rename_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let container = dir.path().join(POD_DIR).join("container");
+        fs::create_dir_all(&container).unwrap();
+        let path = container.join("0.log");
+        let rotated_path = container.join("0.log.20240101-120000");
+
+        fs::write(&path, b"2024-01-01T00:00:00Z stdout F first\n").unwrap();
+        let fingerprinter = fingerprinter();
+        let mut buf = Vec::new();
+        let before = fingerprinter
+            .get_fingerprint_of_file(&path, &mut buf)
+            .unwrap();
+
+        fs::rename(&path, &rotated_path).unwrap();
+        fs::write(&path, b"2024-01-01T12:00:00Z stdout F second\n").unwrap();
+
+        assert_eq!(
+            before,
+            fingerprinter
+                .get_fingerprint_of_file(&rotated_path, &mut buf)
+                .unwrap()
+        );
+        assert_ne!(
+            before,
+            fingerprinter
+                .get_fingerprint_of_file(&path, &mut buf)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_truncate_in_place_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let container = dir.path().join(POD_DIR).join("container");
+        fs::create_dir_all(&container).unwrap();
+        let path = container.join("0.log");
+
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(b"2024-01-01T00:00:00Z stdout F first\n")
+            .unwrap();
+        let fingerprinter = fingerprinter();
+        let mut buf = Vec::new();
+        let before = fingerprinter
+            .get_fingerprint_of_file(&path, &mut buf)
+            .unwrap();
+
+        // Appending to the file keeps its fingerprint.
+        file.write_all(b"2024-01-01T00:00:01Z stdout F more\n")
+            .unwrap();
+        assert_eq!(
+            before,
+            fingerprinter
+                .get_fingerprint_of_file(&path, &mut buf)
+                .unwrap()
+        );
+
+        // Truncating the file in place and writing to it again starts a new
+        // stream of lines, on the same inode and with the same identity.
+        file.set_len(0).unwrap();
+        drop(file);
+        assert!(fingerprinter
+            .get_fingerprint_of_file(&path, &mut buf)
+            .is_err());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"2024-01-01T12:00:00Z stdout F first\n")
+            .unwrap();
+        assert_ne!(
+            before,
+            fingerprinter
+                .get_fingerprint_of_file(&path, &mut buf)
+                .unwrap()
+        );
+    }
+}
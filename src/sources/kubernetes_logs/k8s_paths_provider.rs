@@ -2,25 +2,86 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::{
+    cmp::Ordering as CmpOrdering,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
-use k8s_openapi::api::core::v1::{Namespace, Pod};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{ContainerState, Namespace, Pod};
 use kube::runtime::reflector::{store::Store, ObjectRef};
+use vector_lib::configurable::configurable_component;
 use vector_lib::file_source::paths_provider::PathsProvider;
 
-use super::path_helpers::build_pod_logs_directory;
-use crate::kubernetes::pod_manager_logic::extract_static_pod_config_hashsum;
+use super::{
+    file_discovery::FileDiscovery,
+    path_helpers::{
+        build_pod_logs_directory_with_delimiter, is_active_log_file, parse_container_symlink_path,
+        rotated_log_file_base, LogLayout, DEFAULT_POD_DIR_DELIMITER, K8S_CONTAINER_LOGS_DIR,
+        K8S_LOGS_DIR,
+    },
+};
+use crate::{
+    internal_events::{
+        KubernetesLogsLogPathUnparseable, KubernetesLogsNodeMismatchFileSkipped,
+        KubernetesLogsTerminatedContainerFileClosed,
+    },
+    kubernetes::pod_manager_logic::extract_static_pod_config_hashsum,
+};
 
 /// A paths provider implementation that uses the state obtained from the
 /// the k8s API.
 pub struct K8sPathsProvider {
     pod_state: Store<Pod>,
     namespace_state: Store<Namespace>,
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    pod_logs_root: PathBuf,
+    container_logs_root: PathBuf,
+    extra_log_dirs: Vec<PathBuf>,
+    /// Whether the files found in the extra log directories that follow the
+    /// log layout are skipped when their Pod UID is malformed.
+    strict_log_paths: bool,
     exclude_paths: Vec<glob::Pattern>,
+    container_name_filter: NameFilter,
+    namespace_filter: NameFilter,
+    active_pods: ActivePods,
+    close_terminated_after: Option<Duration>,
+    /// The paths of the terminated containers' log files excluded in the
+    /// latest [`K8sPathsProvider::paths`] call.
+    closed_terminated_paths: Mutex<HashSet<PathBuf>>,
+    /// Whether the log files found in the extra log directories are
+    /// cross-checked against the Pods watched on the Node.
+    self_node_filter: bool,
+    /// The paths of the log files found in the extra log directories of the
+    /// Pods not on the Node skipped in the latest [`K8sPathsProvider::paths`]
+    /// call.
+    node_mismatch_paths: Mutex<HashSet<PathBuf>>,
+    /// The log paths that don't follow the log layout provided in the latest
+    /// [`K8sPathsProvider::paths`] call.
+    unparseable_paths: Mutex<HashSet<PathBuf>>,
+    /// Tracks the provided paths to emit an event for the newly discovered
+    /// log files, if enabled.
+    file_discovery: Option<FileDiscovery>,
+    /// Whether the rotations of the log files are provided along with them.
+    read_rotations: bool,
+    /// The phases of the Pods to provide the log paths of, all of them if
+    /// empty.
+    include_pod_phases: Vec<PodPhase>,
 }
 
 impl K8sPathsProvider {
     /// Create a new [`K8sPathsProvider`].
+    ///
+    /// The log files are looked up in the default pod logs root and container
+    /// logs root, with the default layout, unless set otherwise with the
+    /// `with_*` methods.
     pub fn new(
         pod_state: Store<Pod>,
         namespace_state: Store<Namespace>,
@@ -29,18 +90,313 @@ impl K8sPathsProvider {
         Self {
             pod_state,
             namespace_state,
+            log_layout: LogLayout::default(),
+            pod_dir_delimiter: DEFAULT_POD_DIR_DELIMITER,
+            pod_logs_root: PathBuf::from(K8S_LOGS_DIR),
+            container_logs_root: PathBuf::from(K8S_CONTAINER_LOGS_DIR),
+            extra_log_dirs: Vec::new(),
+            strict_log_paths: false,
             exclude_paths,
+            container_name_filter: NameFilter::default(),
+            namespace_filter: NameFilter::default(),
+            active_pods: ActivePods::default(),
+            close_terminated_after: None,
+            closed_terminated_paths: Mutex::default(),
+            self_node_filter: false,
+            node_mismatch_paths: Mutex::default(),
+            unparseable_paths: Mutex::default(),
+            file_discovery: None,
+            read_rotations: false,
+            include_pod_phases: Vec::new(),
+        }
+    }
+
+    /// Looks the log files up with the given layout, the pod logs directories
+    /// being named with `pod_dir_delimiter` between their parts.
+    pub const fn with_log_layout(mut self, log_layout: LogLayout, pod_dir_delimiter: char) -> Self {
+        self.log_layout = log_layout;
+        self.pod_dir_delimiter = pod_dir_delimiter;
+        self
+    }
+
+    /// Looks the pod log files up under `pod_logs_root`, and the container
+    /// log symlinks under `container_logs_root`.
+    pub fn with_logs_roots(mut self, pod_logs_root: PathBuf, container_logs_root: PathBuf) -> Self {
+        self.pod_logs_root = pod_logs_root;
+        self.container_logs_root = container_logs_root;
+        self
+    }
+
+    /// Also provides the log files found in `extra_log_dirs`, see
+    /// [`list_extra_log_paths`].
+    pub fn with_extra_log_dirs(mut self, extra_log_dirs: Vec<PathBuf>) -> Self {
+        self.extra_log_dirs = extra_log_dirs;
+        self
+    }
+
+    /// Skips the files found in the extra log directories that follow the log
+    /// layout, but with a malformed Pod UID, see
+    /// [`LogLayout::parse_log_file_path_strict`].
+    pub const fn with_strict_log_paths(mut self, strict_log_paths: bool) -> Self {
+        self.strict_log_paths = strict_log_paths;
+        self
+    }
+
+    /// Only provides the log paths of the containers matching
+    /// `container_name_filter`.
+    pub fn with_container_name_filter(mut self, container_name_filter: NameFilter) -> Self {
+        self.container_name_filter = container_name_filter;
+        self
+    }
+
+    /// Only provides the log paths of the Pods in the namespaces matching
+    /// `namespace_filter`.
+    pub fn with_namespace_filter(mut self, namespace_filter: NameFilter) -> Self {
+        self.namespace_filter = namespace_filter;
+        self
+    }
+
+    /// Stores the number of Pods the log paths are provided for in
+    /// `active_pods`.
+    pub fn with_active_pods(mut self, active_pods: ActivePods) -> Self {
+        self.active_pods = active_pods;
+        self
+    }
+
+    /// Stops providing the log files of the containers terminated for at least
+    /// `close_terminated_after`, see [`is_terminated_for`].
+    pub const fn with_close_terminated_after(
+        mut self,
+        close_terminated_after: Option<Duration>,
+    ) -> Self {
+        self.close_terminated_after = close_terminated_after;
+        self
+    }
+
+    /// Only provides the log files found in the extra log directories of the
+    /// Pods watched on the Node, if set, see [`is_local_log_path`].
+    ///
+    /// The Pods are only watched on the Node, so the log files of the Pods
+    /// themselves are always local.
+    pub const fn with_self_node_filter(mut self, self_node_filter: bool) -> Self {
+        self.self_node_filter = self_node_filter;
+        self
+    }
+
+    /// Provides the rotations of the log files along with them, if
+    /// `read_rotations` is set, see [`readable_log_paths`].
+    pub const fn with_rotations(mut self, read_rotations: bool) -> Self {
+        self.read_rotations = read_rotations;
+        self
+    }
+
+    /// Only provides the log paths of the Pods in `include_pod_phases`, see
+    /// [`is_phase_excluded`].
+    pub fn with_pod_phases(mut self, include_pod_phases: Vec<PodPhase>) -> Self {
+        self.include_pod_phases = include_pod_phases;
+        self
+    }
+
+    /// Sends the paths of the newly discovered log files through
+    /// `file_discovery`.
+    pub fn with_file_discovery(mut self, file_discovery: FileDiscovery) -> Self {
+        self.file_discovery = Some(file_discovery);
+        self
+    }
+}
+
+/// The number of Pods the log paths were provided for in the latest
+/// [`K8sPathsProvider::paths`] call.
+#[derive(Clone, Debug, Default)]
+pub struct ActivePods(Arc<AtomicUsize>);
+
+impl ActivePods {
+    /// Returns the number of active Pods.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Filters names, like the ones of the containers or the namespaces the log
+/// paths belong to, with glob patterns.
+///
+/// The patterns support the `*` and `?` wildcards, and are case-sensitive.
+#[derive(Clone, Debug, Default)]
+pub struct NameFilter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl NameFilter {
+    /// Create a new [`NameFilter`].
+    ///
+    /// An empty `include` list includes all the names. The `exclude` list
+    /// takes precedence over the `include` list, so a name matching both is
+    /// excluded.
+    pub fn new(include: Vec<glob::Pattern>, exclude: Vec<glob::Pattern>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Whether the name is included.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.exclude.iter().any(|pattern| pattern.matches(name)) {
+            return false;
+        }
+
+        self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(name))
+    }
+}
+
+/// The phase of a Pod.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PodPhase {
+    /// The Pod is accepted, but not all of its containers are running yet.
+    Pending,
+
+    /// The Pod is bound to the Node, and at least one of its containers is running.
+    Running,
+
+    /// All the containers of the Pod terminated successfully, and won't be restarted.
+    Succeeded,
+
+    /// All the containers of the Pod terminated, and at least one of them failed.
+    Failed,
+
+    /// The state of the Pod couldn't be obtained from its Node.
+    Unknown,
+}
+
+impl PodPhase {
+    /// Returns the phase reported in the status of the Pod, if any.
+    fn of(pod: &Pod) -> Option<Self> {
+        match pod.status.as_ref()?.phase.as_deref()? {
+            "Pending" => Some(Self::Pending),
+            "Running" => Some(Self::Running),
+            "Succeeded" => Some(Self::Succeeded),
+            "Failed" => Some(Self::Failed),
+            "Unknown" => Some(Self::Unknown),
+            _ => None,
         }
     }
 }
 
+/// Checks whether the Pod is in a phase other than `include_pod_phases`.
+///
+/// Pods without a phase yet aren't excluded, so their first logs aren't
+/// missed. An empty `include_pod_phases` includes all the phases.
+fn is_phase_excluded(pod: &Pod, include_pod_phases: &[PodPhase]) -> bool {
+    !include_pod_phases.is_empty()
+        && PodPhase::of(pod).map_or(false, |phase| !include_pod_phases.contains(&phase))
+}
+
+/// Checks whether the log file belongs to one of the Pods with the given
+/// UIDs, see [`pod_logs_uid`].
+///
+/// The log files whose path doesn't carry a Pod UID can't be told apart, so
+/// they are assumed not to.
+fn is_excluded_log_path(
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    excluded_pod_uids: &HashSet<String>,
+    path: &Path,
+) -> bool {
+    path.to_str()
+        .and_then(|path| log_layout.parse_log_file_path(path, pod_dir_delimiter))
+        .map_or(false, |info| excluded_pod_uids.contains(info.pod_uid))
+}
+
+/// Checks whether the path is of a log file that is still written to, see
+/// [`is_active_log_file`].
+fn is_active_log_path(path: &Path) -> bool {
+    path.to_str().map_or(false, is_active_log_file)
+}
+
+/// Keeps the paths of the log files that are still written to and, if
+/// `read_rotations` is set, the paths of their rotations.
+///
+/// The rotations are followed by the file server as they are renamed and
+/// compressed, as long as they are provided, so they are read to the end and
+/// keep their checkpoints across restarts. The rotations of the log files no
+/// longer there are left to the kubelet to remove.
+fn readable_log_paths(paths: Vec<PathBuf>, read_rotations: bool) -> Vec<PathBuf> {
+    let active: HashSet<_> = paths
+        .iter()
+        .filter(|path| is_active_log_path(path))
+        .cloned()
+        .collect();
+    paths
+        .into_iter()
+        .filter(|path| {
+            active.contains(path)
+                || (read_rotations
+                    && path
+                        .to_str()
+                        .and_then(rotated_log_file_base)
+                        .map_or(false, |base| active.contains(Path::new(base))))
+        })
+        .collect()
+}
+
+/// Checks whether the log file belongs to one of the Pods with the given
+/// UIDs, see [`pod_logs_uid`].
+///
+/// The log files whose path doesn't carry a Pod UID can't be told apart, so
+/// they are assumed to.
+fn is_local_log_path(
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    local_pod_uids: &HashSet<String>,
+    path: &Path,
+) -> bool {
+    path.to_str()
+        .and_then(|path| log_layout.parse_log_file_path(path, pod_dir_delimiter))
+        .filter(|info| !info.pod_uid.is_empty())
+        .map_or(true, |info| local_pod_uids.contains(info.pod_uid))
+}
+
+/// Filters the log paths by the namespace and the container they belong to,
+/// before the files are opened.
+///
+/// The paths the names can't be extracted from are kept, and collected into
+/// `unparseable_paths`.
+fn filter_paths<'a>(
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    namespace_filter: &'a NameFilter,
+    container_name_filter: &'a NameFilter,
+    unparseable_paths: &'a mut HashSet<PathBuf>,
+    iter: impl Iterator<Item = PathBuf> + 'a,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    iter.filter(move |path| {
+        match path
+            .to_str()
+            .and_then(|path| log_layout.parse_log_file_path(path, pod_dir_delimiter))
+        {
+            Some(info) => {
+                namespace_filter.matches(info.pod_namespace)
+                    && container_name_filter.matches(info.container_name)
+            }
+            None => {
+                // Paths we can't extract the names from are left for the
+                // annotator to report, but the layout not matching at all
+                // usually means the kubelet changed it, so it is surfaced
+                // here already.
+                unparseable_paths.insert(path.clone());
+                true
+            }
+        }
+    })
+}
+
 impl PathsProvider for K8sPathsProvider {
     type IntoIter = Vec<PathBuf>;
 
     fn paths(&self) -> Vec<PathBuf> {
         let state = self.pod_state.state();
 
-        state
+        let pods: Vec<_> = state
             .into_iter()
             // filter out pods where we haven't fetched the namespace metadata yet
             // they will be picked up on a later run
@@ -54,12 +410,261 @@ impl PathsProvider for K8sPathsProvider {
                     false
                 }
             })
+            .collect();
+
+        // The Pods are only watched on the Node, so their log files are the
+        // local ones, but the extra log directories may be shared with other
+        // Nodes, and the files found there are cross-checked against them.
+        let mut node_mismatch_paths = HashSet::new();
+        let local_pod_uids: HashSet<_> = pods
+            .iter()
+            .filter_map(|pod| pod_logs_uid(pod))
+            .map(str::to_owned)
+            .collect();
+
+        // The log files of the Pods in the excluded phases are skipped, also
+        // when they are found in the extra log directories.
+        let (excluded_pods, pods): (Vec<_>, Vec<_>) = pods
+            .into_iter()
+            .partition(|pod| is_phase_excluded(pod, &self.include_pod_phases));
+        let excluded_pod_uids: HashSet<_> = excluded_pods
+            .iter()
+            .filter_map(|pod| pod_logs_uid(pod))
+            .map(str::to_owned)
+            .collect();
+        self.active_pods.0.store(pods.len(), Ordering::Relaxed);
+
+        let now = Utc::now();
+        let mut closed_terminated_paths = HashSet::new();
+        let mut unparseable_paths = HashSet::new();
+
+        let mut paths: Vec<_> = pods
+            .into_iter()
             .flat_map(|pod| {
                 trace!(message = "Providing log paths for pod.", pod = ?pod.metadata.name);
-                let paths_iter = list_pod_log_paths(real_glob, pod.as_ref());
-                exclude_paths(paths_iter, &self.exclude_paths).collect::<Vec<_>>()
+                let paths = self.list_log_paths(pod.as_ref());
+                let paths_iter = filter_paths(
+                    self.log_layout,
+                    self.pod_dir_delimiter,
+                    &self.namespace_filter,
+                    &self.container_name_filter,
+                    &mut unparseable_paths,
+                    readable_log_paths(paths, self.read_rotations),
+                );
+                let (closed, paths): (Vec<_>, Vec<_>) =
+                    exclude_log_paths(paths_iter, &self.exclude_paths, self.read_rotations)
+                        .partition(|path| {
+                            self.close_terminated_after.map_or(false, |after| {
+                                is_terminated_for(
+                                    self.log_layout,
+                                    self.pod_dir_delimiter,
+                                    pod.as_ref(),
+                                    path,
+                                    after,
+                                    now,
+                                )
+                            })
+                        });
+                closed_terminated_paths.extend(closed);
+                paths
             })
-            .collect()
+            .collect();
+
+        // The excluded files are read to the end and closed by the file
+        // server, and are only counted once, when they are first excluded.
+        let mut previously_closed = self
+            .closed_terminated_paths
+            .lock()
+            .expect("the lock is never poisoned");
+        for path in closed_terminated_paths.difference(&previously_closed) {
+            emit!(KubernetesLogsTerminatedContainerFileClosed {
+                path: &path.to_string_lossy()
+            });
+        }
+        *previously_closed = closed_terminated_paths;
+
+        // The unparseable paths are found again on every call, but are only
+        // counted once, when they are first found.
+        let mut previously_unparseable = self
+            .unparseable_paths
+            .lock()
+            .expect("the lock is never poisoned");
+        for path in unparseable_paths.difference(&previously_unparseable) {
+            emit!(KubernetesLogsLogPathUnparseable {
+                path: &path.to_string_lossy()
+            });
+        }
+        *previously_unparseable = unparseable_paths;
+
+        // The extra log directories may overlap with the pod logs root, the
+        // files found in both are only provided once.
+        let mut known_paths: HashSet<_> = paths
+            .iter()
+            .chain(previously_closed.iter())
+            .cloned()
+            .collect();
+        let extra_paths = list_extra_log_paths(
+            real_glob,
+            &self.extra_log_dirs,
+            self.log_layout,
+            self.pod_dir_delimiter,
+            self.strict_log_paths,
+            &self.namespace_filter,
+            &self.container_name_filter,
+        );
+        paths.extend(
+            exclude_paths(
+                extra_paths.filter(|path| is_active_log_path(path)),
+                &self.exclude_paths,
+            )
+            .filter(|path| {
+                // The Pods of the log files found in the extra log directories
+                // aren't known to be on the Node unless they are watched.
+                let local = !self.self_node_filter
+                    || is_local_log_path(
+                        self.log_layout,
+                        self.pod_dir_delimiter,
+                        &local_pod_uids,
+                        path,
+                    );
+                if !local {
+                    node_mismatch_paths.insert(path.clone());
+                }
+                local
+            })
+            .filter(|path| {
+                !is_excluded_log_path(
+                    self.log_layout,
+                    self.pod_dir_delimiter,
+                    &excluded_pod_uids,
+                    path,
+                )
+            })
+            .filter(|path| known_paths.insert(path.clone())),
+        );
+
+        // The skipped files are only counted once, when they are first
+        // skipped.
+        let mut previously_skipped = self
+            .node_mismatch_paths
+            .lock()
+            .expect("the lock is never poisoned");
+        for path in node_mismatch_paths.difference(&previously_skipped) {
+            emit!(KubernetesLogsNodeMismatchFileSkipped {
+                path: &path.to_string_lossy()
+            });
+        }
+        *previously_skipped = node_mismatch_paths;
+
+        if let Some(file_discovery) = &self.file_discovery {
+            file_discovery.update(&paths);
+        }
+
+        paths
+    }
+}
+
+/// Checks whether the container instance that writes the log file has been
+/// terminated for at least `after`.
+///
+/// The instances are told apart by the restart count in the log file name,
+/// so a restarted container writes to a new file that is read as usual.
+fn is_terminated_for(
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    pod: &Pod,
+    path: &Path,
+    after: Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    path.to_str()
+        .and_then(|path| log_layout.parse_log_file_path(path, pod_dir_delimiter))
+        .and_then(|info| container_terminated_at(pod, info.container_name, info.restart_count?))
+        .map_or(false, |terminated_at| {
+            now.signed_duration_since(terminated_at)
+                .to_std()
+                .map_or(false, |terminated_for| terminated_for >= after)
+        })
+}
+
+/// Returns when the container instance with the given restart count
+/// terminated, if it did.
+///
+/// The current instance is looked up in the state of the container, and the
+/// previous one in its last state. The instances before that are no longer
+/// reported, but have terminated before the last one did.
+fn container_terminated_at(
+    pod: &Pod,
+    container_name: &str,
+    restart_count: u32,
+) -> Option<DateTime<Utc>> {
+    let status = pod.status.as_ref()?;
+    let container_status = status
+        .container_statuses
+        .iter()
+        .chain(&status.init_container_statuses)
+        .chain(&status.ephemeral_container_statuses)
+        .flatten()
+        .find(|container_status| container_status.name == container_name)?;
+
+    let finished_at = |state: Option<&ContainerState>| {
+        state?
+            .terminated
+            .as_ref()?
+            .finished_at
+            .as_ref()
+            .map(|time| time.0)
+    };
+
+    match restart_count.cmp(&u32::try_from(container_status.restart_count).ok()?) {
+        CmpOrdering::Equal => finished_at(container_status.state.as_ref()),
+        CmpOrdering::Less => finished_at(container_status.last_state.as_ref()).or_else(|| {
+            // Without a last state, the current instance starting is the
+            // latest the previous ones could have terminated.
+            let state = container_status.state.as_ref()?;
+            state
+                .running
+                .as_ref()
+                .and_then(|running| running.started_at.as_ref())
+                .or_else(|| state.terminated.as_ref()?.started_at.as_ref())
+                .map(|time| time.0)
+        }),
+        CmpOrdering::Greater => None,
+    }
+}
+
+impl K8sPathsProvider {
+    fn list_log_paths(&self, pod: &Pod) -> Vec<PathBuf> {
+        let list_container_log_paths = || {
+            list_container_log_paths(real_glob, &self.container_logs_root, pod)
+                // The symlinks outlive the files they point to for a while
+                // after the pod is deleted, and a broken setup may even have
+                // them loop. Neither can be read, so they are dropped here
+                // rather than handed to the file server.
+                .filter(|path| path.exists())
+                .collect()
+        };
+
+        match self.log_layout {
+            LogLayout::Pods => {
+                list_pod_log_paths(real_glob, &self.pod_logs_root, self.pod_dir_delimiter, pod)
+                    .collect()
+            }
+            LogLayout::Containers => list_container_log_paths(),
+            LogLayout::Auto => {
+                // The container log symlinks point to the pod log files, so
+                // they are only used if the pod has no log files of its own,
+                // otherwise every file would be read twice.
+                let paths: Vec<_> =
+                    list_pod_log_paths(real_glob, &self.pod_logs_root, self.pod_dir_delimiter, pod)
+                        .collect();
+                if paths.is_empty() {
+                    list_container_log_paths()
+                } else {
+                    paths
+                }
+            }
+        }
     }
 }
 
@@ -82,20 +687,33 @@ impl PathsProvider for K8sPathsProvider {
 /// See <https://github.com/vectordotdev/vector/issues/6001>
 /// See <https://github.com/kubernetes/kubernetes/blob/ef3337a443b402756c9f0bfb1f844b1b45ce289d/pkg/kubelet/pod/pod_manager.go#L30-L44>
 /// See <https://github.com/kubernetes/kubernetes/blob/cea1d4e20b4a7886d8ff65f34c6d4f95efcb4742/pkg/kubelet/pod/mirror_client.go#L80-L81>
-fn extract_pod_logs_directory(pod: &Pod) -> Option<PathBuf> {
+fn extract_pod_logs_directory(
+    pod_logs_root: &Path,
+    pod_dir_delimiter: char,
+    pod: &Pod,
+) -> Option<PathBuf> {
     let metadata = &pod.metadata;
     let namespace = metadata.namespace.as_ref()?;
     let name = metadata.name.as_ref()?;
+    let uid = pod_logs_uid(pod)?;
 
-    let uid = if let Some(static_pod_config_hashsum) = extract_static_pod_config_hashsum(metadata) {
+    // The metadata isn't trusted, the pods it would point outside of the pod logs root for are
+    // skipped.
+    build_pod_logs_directory_with_delimiter(pod_logs_root, namespace, name, uid, pod_dir_delimiter)
+        .ok()
+}
+
+/// Returns the UID the logs directory of the Pod is named after, see
+/// [`extract_pod_logs_directory`].
+fn pod_logs_uid(pod: &Pod) -> Option<&str> {
+    let metadata = &pod.metadata;
+    if let Some(static_pod_config_hashsum) = extract_static_pod_config_hashsum(metadata) {
         // If there's a static pod config hashsum - use it instead of uid.
-        static_pod_config_hashsum
+        Some(static_pod_config_hashsum)
     } else {
         // In the common case - just fallback to the real pod uid.
-        metadata.uid.as_ref()?
-    };
-
-    Some(build_pod_logs_directory(namespace, name, uid))
+        metadata.uid.as_deref()
+    }
 }
 
 const CONTAINER_EXCLUSION_ANNOTATION_KEY: &str = "vector.dev/exclude-containers";
@@ -128,18 +746,23 @@ fn build_container_exclusion_patterns<'a>(
 
 fn list_pod_log_paths<'a, G, GI>(
     mut glob_impl: G,
+    pod_logs_root: &Path,
+    pod_dir_delimiter: char,
     pod: &'a Pod,
 ) -> impl Iterator<Item = PathBuf> + 'a
 where
     G: FnMut(&str) -> GI + 'a,
     GI: Iterator<Item = PathBuf> + 'a,
 {
-    extract_pod_logs_directory(pod)
+    extract_pod_logs_directory(pod_logs_root, pod_dir_delimiter, pod)
         .into_iter()
         .flat_map(move |dir| {
             let dir = dir
                 .to_str()
                 .expect("non-utf8 path to pod logs dir is not supported");
+            // The pod logs root is configurable, so it may contain characters
+            // that have a special meaning in glob patterns.
+            let dir = glob::Pattern::escape(dir);
 
             // Run the glob to get a list of unfiltered paths.
             let path_iter = glob_impl(
@@ -149,20 +772,110 @@ where
                 // architecture.
                 // In some setups, there will also be paths like
                 // `<pod_logs_dir>/<hash>.log` - those we want to skip.
-                &[dir, "*/*.log*"].join("/"),
+                &[dir.as_str(), "*/*.log*"].join("/"),
             );
 
             // Extract the containers to exclude, then build patterns from them
             // and cache the results into a Vec.
             let excluded_containers = extract_excluded_containers_for_pod(pod);
             let exclusion_patterns: Vec<_> =
-                build_container_exclusion_patterns(dir, excluded_containers).collect();
+                build_container_exclusion_patterns(&dir, excluded_containers).collect();
 
             // Return paths filtered with container exclusion.
             exclude_paths(path_iter, exclusion_patterns)
         })
 }
 
+/// Lists the legacy `<container_logs_root>/<pod_name>_<pod_namespace>_<container_name>-<container_id>.log`
+/// symlinks of the pod.
+fn list_container_log_paths<'a, G, GI>(
+    mut glob_impl: G,
+    container_logs_root: &Path,
+    pod: &'a Pod,
+) -> impl Iterator<Item = PathBuf> + 'a
+where
+    G: FnMut(&str) -> GI + 'a,
+    GI: Iterator<Item = PathBuf> + 'a,
+{
+    let metadata = &pod.metadata;
+    let prefix =
+        metadata
+            .namespace
+            .as_ref()
+            .zip(metadata.name.as_ref())
+            .map(|(namespace, name)| {
+                let root = container_logs_root
+                    .to_str()
+                    .expect("non-utf8 path to container logs dir is not supported");
+                format!(
+                    "{}/{}_{}",
+                    glob::Pattern::escape(root),
+                    glob::Pattern::escape(name),
+                    glob::Pattern::escape(namespace)
+                )
+            });
+
+    prefix.into_iter().flat_map(move |prefix| {
+        let path_iter = glob_impl(&format!("{}_*.log", prefix));
+
+        // Container names can contain a `-`, so the excluded containers are
+        // matched against the parsed container name rather than with a glob.
+        let excluded_containers: Vec<_> = extract_excluded_containers_for_pod(pod).collect();
+        path_iter.filter(move |path| {
+            path.to_str()
+                .and_then(parse_container_symlink_path)
+                .map_or(true, |info| {
+                    !excluded_containers.contains(&info.container_name)
+                })
+        })
+    })
+}
+
+/// Lists the log files in the extra log directories.
+///
+/// The files are not tied to the Pods on the Node, so the ones that don't
+/// follow the log layout are expected and are provided as is, while the ones
+/// that do are filtered by their namespace and container like the Pod log
+/// files are. With `strict_log_paths`, the ones that follow the log layout
+/// but have a malformed Pod UID are skipped as stray files.
+fn list_extra_log_paths<'a, G, GI>(
+    mut glob_impl: G,
+    extra_log_dirs: &'a [PathBuf],
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    strict_log_paths: bool,
+    namespace_filter: &'a NameFilter,
+    container_name_filter: &'a NameFilter,
+) -> impl Iterator<Item = PathBuf> + 'a
+where
+    G: FnMut(&str) -> GI + 'a,
+    GI: Iterator<Item = PathBuf> + 'a,
+{
+    extra_log_dirs
+        .iter()
+        .flat_map(move |dir| {
+            let dir = dir
+                .to_str()
+                .expect("non-utf8 path to extra log dir is not supported");
+            glob_impl(&[glob::Pattern::escape(dir).as_str(), "**/*.log*"].join("/"))
+        })
+        .filter(move |path| {
+            let Some(path) = path.to_str() else {
+                return true;
+            };
+            log_layout
+                .parse_log_file_path(path, pod_dir_delimiter)
+                .map_or(true, |info| {
+                    namespace_filter.matches(info.pod_namespace)
+                        && container_name_filter.matches(info.container_name)
+                        && (!strict_log_paths
+                            || log_layout
+                                .parse_log_file_path_strict(path, pod_dir_delimiter)
+                                .is_some())
+                })
+        })
+}
+
 fn real_glob(pattern: &str) -> impl Iterator<Item = PathBuf> {
     glob::glob_with(
         pattern,
@@ -178,29 +891,72 @@ fn real_glob(pattern: &str) -> impl Iterator<Item = PathBuf> {
 fn exclude_paths<'a>(
     iter: impl Iterator<Item = PathBuf> + 'a,
     patterns: impl AsRef<[glob::Pattern]> + 'a,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    iter.filter(move |path| !is_excluded_path(path, patterns.as_ref()))
+}
+
+/// Excludes the log paths matching any of the exclusion patterns.
+///
+/// If `read_rotations` is set, the compressed rotations are checked against
+/// the patterns by their path before compression, so they are still read
+/// with the default `**/*.gz` pattern, and followed once compressed.
+fn exclude_log_paths<'a>(
+    iter: impl Iterator<Item = PathBuf> + 'a,
+    patterns: &'a [glob::Pattern],
+    read_rotations: bool,
 ) -> impl Iterator<Item = PathBuf> + 'a {
     iter.filter(move |path| {
-        !patterns.as_ref().iter().any(|pattern| {
-            pattern.matches_path_with(
-                path,
-                glob::MatchOptions {
-                    require_literal_separator: true,
-                    ..Default::default()
-                },
-            )
-        })
+        let checked_path = path
+            .to_str()
+            .filter(|path| read_rotations && rotated_log_file_base(path).is_some())
+            .and_then(|path| path.strip_suffix(".gz"))
+            .map_or(path.as_path(), Path::new);
+        !is_excluded_path(checked_path, patterns)
+    })
+}
+
+/// Checks whether the path matches any of the exclusion patterns.
+pub(super) fn is_excluded_path(path: &Path, patterns: &[glob::Pattern]) -> bool {
+    patterns.iter().any(|pattern| {
+        pattern.matches_path_with(
+            path,
+            glob::MatchOptions {
+                require_literal_separator: true,
+                ..Default::default()
+            },
+        )
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        time::Duration,
+    };
 
-    use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
+    use chrono::{DateTime, TimeZone, Utc};
+    use k8s_openapi::{
+        api::core::v1::{
+            ContainerState, ContainerStateRunning, ContainerStateTerminated, ContainerStatus,
+            Namespace, Pod, PodSpec, PodStatus,
+        },
+        apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time},
+    };
+    use kube::runtime::{reflector::store, watcher};
+    use vector_lib::file_source::paths_provider::PathsProvider;
 
     use super::{
-        build_container_exclusion_patterns, exclude_paths, extract_excluded_containers_for_pod,
-        extract_pod_logs_directory, list_pod_log_paths,
+        super::path_helpers::{
+            LogLayout, DEFAULT_POD_DIR_DELIMITER, K8S_CONTAINER_LOGS_DIR, K8S_LOGS_DIR,
+        },
+        build_container_exclusion_patterns, container_terminated_at, exclude_paths,
+        extract_excluded_containers_for_pod, extract_pod_logs_directory, filter_paths,
+        is_excluded_log_path, is_local_log_path, is_phase_excluded, is_terminated_for,
+        list_container_log_paths, list_pod_log_paths, pod_logs_uid, readable_log_paths,
+        K8sPathsProvider, NameFilter, PodPhase,
     };
 
     #[test]
@@ -278,11 +1034,36 @@ mod tests {
                 },
                 Some("/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-config-hashsum"),
             ),
+            // Static pod config hashsum escaping the pod logs root.
+            (
+                Pod {
+                    metadata: ObjectMeta {
+                        namespace: Some("sandbox0-ns".to_owned()),
+                        name: Some("sandbox0-name".to_owned()),
+                        uid: Some("sandbox0-uid".to_owned()),
+                        annotations: Some(
+                            vec![(
+                                "kubernetes.io/config.mirror".to_owned(),
+                                "../../../etc".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                        ),
+                        ..ObjectMeta::default()
+                    },
+                    ..Pod::default()
+                },
+                None,
+            ),
         ];
 
         for (pod, expected) in cases {
             assert_eq!(
-                extract_pod_logs_directory(&pod),
+                extract_pod_logs_directory(
+                    Path::new(K8S_LOGS_DIR),
+                    DEFAULT_POD_DIR_DELIMITER,
+                    &pod
+                ),
                 expected.map(PathBuf::from)
             );
         }
@@ -369,6 +1150,7 @@ mod tests {
             // Pod exists and has some containers that write logs, and some of
             // the containers are excluded.
             (
+                K8S_LOGS_DIR,
                 Pod {
                     metadata: ObjectMeta {
                         namespace: Some("sandbox0-ns".to_owned()),
@@ -407,9 +1189,10 @@ mod tests {
                 ],
             ),
             // Pod doesn't have the metadata set.
-            (Pod::default(), vec![], vec![]),
+            (K8S_LOGS_DIR, Pod::default(), vec![], vec![]),
             // Pod has proper metadata, but doesn't have log files.
             (
+                K8S_LOGS_DIR,
                 Pod {
                     metadata: ObjectMeta {
                         namespace: Some("sandbox0-ns".to_owned()),
@@ -425,9 +1208,49 @@ mod tests {
                 )],
                 vec![],
             ),
+            // Pod logs are kept under a custom root directory.
+            (
+                "/data/kubelet/log/pods",
+                Pod {
+                    metadata: ObjectMeta {
+                        namespace: Some("sandbox0-ns".to_owned()),
+                        name: Some("sandbox0-name".to_owned()),
+                        uid: Some("sandbox0-uid".to_owned()),
+                        ..ObjectMeta::default()
+                    },
+                    ..Pod::default()
+                },
+                vec![(
+                    "/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/*/*.log*",
+                    vec![
+                        "/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/container1/0.log",
+                    ],
+                )],
+                vec!["/data/kubelet/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/container1/0.log"],
+            ),
+            // Pod logs root contains characters special to glob patterns.
+            (
+                "/data/[kubelet]/log/pods",
+                Pod {
+                    metadata: ObjectMeta {
+                        namespace: Some("sandbox0-ns".to_owned()),
+                        name: Some("sandbox0-name".to_owned()),
+                        uid: Some("sandbox0-uid".to_owned()),
+                        ..ObjectMeta::default()
+                    },
+                    ..Pod::default()
+                },
+                vec![(
+                    "/data/[[]kubelet[]]/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/*/*.log*",
+                    vec![
+                        "/data/[kubelet]/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/container1/0.log",
+                    ],
+                )],
+                vec!["/data/[kubelet]/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/container1/0.log"],
+            ),
         ];
 
-        for (pod, expected_calls, expected_paths) in cases {
+        for (pod_logs_root, pod, expected_calls, expected_paths) in cases {
             // Prepare the mock fn.
             let mut expected_calls = expected_calls.into_iter();
             let mock_glob = move |pattern: &str| {
@@ -439,7 +1262,143 @@ mod tests {
                 paths_to_return.into_iter().map(PathBuf::from)
             };
 
-            let actual_paths: Vec<_> = list_pod_log_paths(mock_glob, &pod).collect();
+            let actual_paths: Vec<_> = list_pod_log_paths(
+                mock_glob,
+                Path::new(pod_logs_root),
+                DEFAULT_POD_DIR_DELIMITER,
+                &pod,
+            )
+            .collect();
+            let expected_paths: Vec<_> = expected_paths.into_iter().map(PathBuf::from).collect();
+            assert_eq!(actual_paths, expected_paths)
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_list_pod_log_paths_windows() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                namespace: Some("sandbox0-ns".to_owned()),
+                name: Some("sandbox0-name".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                annotations: Some(
+                    vec![(
+                        super::CONTAINER_EXCLUSION_ANNOTATION_KEY.to_owned(),
+                        "excluded1".to_owned(),
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let mock_glob = |pattern: &str| {
+            assert_eq!(
+                pattern,
+                "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid/*/*.log*"
+            );
+            vec![
+                "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid\\container1\\0.log",
+                "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid/container2/0.log",
+                "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid/excluded1/0.log",
+            ]
+            .into_iter()
+            .map(PathBuf::from)
+        };
+
+        let actual_paths: Vec<_> = list_pod_log_paths(
+            mock_glob,
+            Path::new("D:\\var\\log\\pods"),
+            DEFAULT_POD_DIR_DELIMITER,
+            &pod,
+        )
+        .collect();
+        assert_eq!(
+            actual_paths,
+            vec![
+                PathBuf::from(
+                    "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid\\container1\\0.log"
+                ),
+                PathBuf::from(
+                    "D:\\var\\log\\pods\\sandbox0-ns_sandbox0-name_sandbox0-uid/container2/0.log"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_container_log_paths() {
+        let cases = vec![
+            // Pod has some containers that write logs, and one of the
+            // containers is excluded.
+            (
+                K8S_CONTAINER_LOGS_DIR,
+                Pod {
+                    metadata: ObjectMeta {
+                        namespace: Some("sandbox0-ns".to_owned()),
+                        name: Some("sandbox0-name".to_owned()),
+                        uid: Some("sandbox0-uid".to_owned()),
+                        annotations: Some(
+                            vec![(
+                                super::CONTAINER_EXCLUSION_ANNOTATION_KEY.to_owned(),
+                                "excluded1".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                        ),
+                        ..ObjectMeta::default()
+                    },
+                    ..Pod::default()
+                },
+                vec![(
+                    "/var/log/containers/sandbox0-name_sandbox0-ns_*.log",
+                    vec![
+                        "/var/log/containers/sandbox0-name_sandbox0-ns_container1-8a3c1e.log",
+                        "/var/log/containers/sandbox0-name_sandbox0-ns_excluded1-4f2d9b.log",
+                        "/var/log/containers/sandbox0-name_sandbox0-ns_excluded1-sidecar-77ab01.log",
+                    ],
+                )],
+                vec![
+                    "/var/log/containers/sandbox0-name_sandbox0-ns_container1-8a3c1e.log",
+                    "/var/log/containers/sandbox0-name_sandbox0-ns_excluded1-sidecar-77ab01.log",
+                ],
+            ),
+            // Pod doesn't have the metadata set.
+            (K8S_CONTAINER_LOGS_DIR, Pod::default(), vec![], vec![]),
+            // Container logs root contains characters special to glob patterns.
+            (
+                "/data/[containers]",
+                Pod {
+                    metadata: ObjectMeta {
+                        namespace: Some("sandbox0-ns".to_owned()),
+                        name: Some("sandbox0-name".to_owned()),
+                        ..ObjectMeta::default()
+                    },
+                    ..Pod::default()
+                },
+                vec![(
+                    "/data/[[]containers[]]/sandbox0-name_sandbox0-ns_*.log",
+                    vec!["/data/[containers]/sandbox0-name_sandbox0-ns_container1-8a3c1e.log"],
+                )],
+                vec!["/data/[containers]/sandbox0-name_sandbox0-ns_container1-8a3c1e.log"],
+            ),
+        ];
+
+        for (container_logs_root, pod, expected_calls, expected_paths) in cases {
+            let mut expected_calls = expected_calls.into_iter();
+            let mock_glob = move |pattern: &str| {
+                let (expected_pattern, paths_to_return) = expected_calls
+                    .next()
+                    .expect("implementation did a call that wasn't expected");
+
+                assert_eq!(pattern, expected_pattern);
+                paths_to_return.into_iter().map(PathBuf::from)
+            };
+
+            let actual_paths: Vec<_> =
+                list_container_log_paths(mock_glob, Path::new(container_logs_root), &pod).collect();
             let expected_paths: Vec<_> = expected_paths.into_iter().map(PathBuf::from).collect();
             assert_eq!(actual_paths, expected_paths)
         }
@@ -518,6 +1477,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_readable_log_paths() {
+        let dir = "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name";
+        let paths = |names: &[&str]| -> Vec<PathBuf> {
+            names
+                .iter()
+                .map(|name| PathBuf::from(format!("{}/{}", dir, name)))
+                .collect()
+        };
+        let listed = paths(&[
+            "0.log",
+            "0.log.20240101-000000.gz",
+            "0.log.20240101-120000",
+            "0.log.gz",
+            "1.log.20240101-000000.gz",
+            "2.log",
+            "2.logs",
+        ]);
+
+        assert_eq!(
+            readable_log_paths(listed.clone(), false),
+            paths(&["0.log", "2.log"])
+        );
+        // The rotations of the log files no longer there are left out.
+        assert_eq!(
+            readable_log_paths(listed, true),
+            paths(&[
+                "0.log",
+                "0.log.20240101-000000.gz",
+                "0.log.20240101-120000",
+                "0.log.gz",
+                "2.log",
+            ])
+        );
+    }
+
     #[test]
     fn test_build_container_exclusion_patterns() {
         let cases = vec![
@@ -559,4 +1554,681 @@ mod tests {
             )
         }
     }
+
+    fn name_filter(include: &[&str], exclude: &[&str]) -> NameFilter {
+        NameFilter::new(
+            include
+                .iter()
+                .map(|pattern| glob::Pattern::new(pattern).unwrap())
+                .collect(),
+            exclude
+                .iter()
+                .map(|pattern| glob::Pattern::new(pattern).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_name_filter() {
+        let cases = vec![
+            // No patterns allow everything.
+            (
+                vec![],
+                vec![],
+                vec!["default", "kube-system", "team-a", "Team-b"],
+            ),
+            // The `?` wildcard matches a single character.
+            (vec!["team-?"], vec![], vec!["team-a"]),
+            // The patterns are case-sensitive.
+            (vec!["Team-*"], vec![], vec!["Team-b"]),
+            (vec![], vec!["kube-*"], vec!["default", "team-a", "Team-b"]),
+            // Excludes win over includes.
+            (
+                vec!["*"],
+                vec!["team-a"],
+                vec!["default", "kube-system", "Team-b"],
+            ),
+        ];
+
+        for (include, exclude, expected) in cases {
+            let filter = name_filter(&include, &exclude);
+            let actual: Vec<_> = ["default", "kube-system", "team-a", "Team-b"]
+                .into_iter()
+                .filter(|name| filter.matches(name))
+                .collect();
+            assert_eq!(
+                actual, expected,
+                "failed for include {:?} and exclude {:?}",
+                &include, &exclude,
+            );
+        }
+    }
+
+    #[test]
+    fn test_container_name_filter() {
+        let cases = vec![
+            // No patterns allow everything.
+            (vec![], vec![], vec!["app", "istio-proxy", "sidecar"]),
+            // Only the included containers are allowed.
+            (vec!["app*"], vec![], vec!["app"]),
+            // Excluded containers are dropped.
+            (vec![], vec!["istio-*"], vec!["app", "sidecar"]),
+            // Excludes win over includes.
+            (vec!["*"], vec!["istio-proxy"], vec!["app", "sidecar"]),
+            (vec!["istio-*"], vec!["istio-proxy"], vec![]),
+        ];
+
+        let input_paths = vec![
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/app/0.log",
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/istio-proxy/0.log",
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sidecar/0.log",
+        ];
+
+        for (include, exclude, expected_containers) in cases {
+            let namespace_filter = NameFilter::default();
+            let container_name_filter = name_filter(&include, &exclude);
+            let actual_paths: Vec<_> = filter_paths(
+                LogLayout::Pods,
+                DEFAULT_POD_DIR_DELIMITER,
+                &namespace_filter,
+                &container_name_filter,
+                &mut HashSet::new(),
+                input_paths.iter().map(PathBuf::from),
+            )
+            .collect();
+            let expected_paths: Vec<_> = expected_containers
+                .iter()
+                .map(|container| {
+                    PathBuf::from(format!(
+                        "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/{}/0.log",
+                        container
+                    ))
+                })
+                .collect();
+            assert_eq!(
+                actual_paths, expected_paths,
+                "failed for include {:?} and exclude {:?}",
+                &include, &exclude,
+            )
+        }
+    }
+
+    #[test]
+    fn test_filter_paths_by_namespace() {
+        let input_paths = vec![
+            "/var/log/pods/team-a_sandbox0-name_sandbox0-uid/app/0.log",
+            "/var/log/pods/team-b_sandbox1-name_sandbox1-uid/app/0.log",
+            "/var/log/pods/kube-system_sandbox2-name_sandbox2-uid/app/0.log",
+            "/var/log/containers/sandbox3-name_team-a_app-8a3c1e.log",
+            "/var/log/other.log",
+        ];
+
+        let namespace_filter = name_filter(&["team-*"], &["team-b"]);
+        let container_name_filter = NameFilter::default();
+        let mut unparseable_paths = HashSet::new();
+        let actual_paths: Vec<_> = filter_paths(
+            LogLayout::Auto,
+            DEFAULT_POD_DIR_DELIMITER,
+            &namespace_filter,
+            &container_name_filter,
+            &mut unparseable_paths,
+            input_paths.iter().map(PathBuf::from),
+        )
+        .collect();
+
+        assert_eq!(
+            actual_paths,
+            vec![
+                PathBuf::from("/var/log/pods/team-a_sandbox0-name_sandbox0-uid/app/0.log"),
+                PathBuf::from("/var/log/containers/sandbox3-name_team-a_app-8a3c1e.log"),
+                PathBuf::from("/var/log/other.log"),
+            ]
+        );
+        assert_eq!(
+            unparseable_paths,
+            HashSet::from([PathBuf::from("/var/log/other.log")])
+        );
+    }
+
+    #[test]
+    fn test_pod_dir_delimiters() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                namespace: Some("teama".to_owned()),
+                name: Some("sandbox0-name".to_owned()),
+                uid: Some("sandbox0uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+
+        for delimiter in [DEFAULT_POD_DIR_DELIMITER, '-', '+'] {
+            let pod_dir = format!(
+                "/var/log/pods/teama{0}sandbox0-name{0}sandbox0uid",
+                delimiter
+            );
+            let mock_glob = |pattern: &str| {
+                assert_eq!(pattern, format!("{}/*/*.log*", pod_dir));
+                vec![format!("{}/app/0.log", pod_dir)]
+                    .into_iter()
+                    .map(PathBuf::from)
+            };
+            let paths: Vec<_> =
+                list_pod_log_paths(mock_glob, Path::new("/var/log/pods"), delimiter, &pod)
+                    .collect();
+            assert_eq!(paths, vec![PathBuf::from(format!("{}/app/0.log", pod_dir))]);
+
+            // The namespace is parsed with the same delimiter.
+            let namespace_filter = name_filter(&[], &["teama"]);
+            let filtered: Vec<_> = filter_paths(
+                LogLayout::Pods,
+                delimiter,
+                &namespace_filter,
+                &NameFilter::default(),
+                &mut HashSet::new(),
+                paths.into_iter(),
+            )
+            .collect();
+            assert!(filtered.is_empty(), "{:?}", delimiter);
+        }
+    }
+
+    #[test]
+    fn test_list_extra_log_paths() {
+        let extra_log_dirs = vec![
+            PathBuf::from("/mnt/pod-logs"),
+            PathBuf::from("/data/[logs]"),
+        ];
+        let mut expected_calls = vec![
+            (
+                "/mnt/pod-logs/**/*.log*",
+                vec![
+                    "/mnt/pod-logs/team-a_sandbox0-name_sandbox0-uid/app/0.log",
+                    "/mnt/pod-logs/team-b_sandbox1-name_sandbox1-uid/app/0.log",
+                    "/mnt/pod-logs/team-a_sandbox2-name_sandbox2-uid/sidecar/0.log",
+                ],
+            ),
+            ("/data/[[]logs[]]/**/*.log*", vec!["/data/[logs]/app.log"]),
+        ]
+        .into_iter();
+        let mock_glob = move |pattern: &str| {
+            let (expected_pattern, paths_to_return) = expected_calls
+                .next()
+                .expect("implementation did a call that wasn't expected");
+
+            assert_eq!(pattern, expected_pattern);
+            paths_to_return.into_iter().map(PathBuf::from)
+        };
+
+        let namespace_filter = name_filter(&[], &["team-b"]);
+        let container_name_filter = name_filter(&[], &["sidecar"]);
+        let actual_paths: Vec<_> = list_extra_log_paths(
+            mock_glob,
+            &extra_log_dirs,
+            LogLayout::Pods,
+            DEFAULT_POD_DIR_DELIMITER,
+            false,
+            &namespace_filter,
+            &container_name_filter,
+        )
+        .collect();
+
+        assert_eq!(
+            actual_paths,
+            vec![
+                PathBuf::from("/mnt/pod-logs/team-a_sandbox0-name_sandbox0-uid/app/0.log"),
+                PathBuf::from("/data/[logs]/app.log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_extra_log_paths_strict() {
+        let extra_log_dirs = vec![PathBuf::from("/mnt/pod-logs")];
+        let paths = vec![
+            "/mnt/pod-logs/team-a_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/app/0.log",
+            "/mnt/pod-logs/team-a_sandbox1-name_system-7f9c2b1d/app/0.log",
+            "/mnt/pod-logs/other_tool_state/data/0.log",
+            "/mnt/pod-logs/app.log",
+        ];
+        let mock_glob = move |pattern: &str| {
+            assert_eq!(pattern, "/mnt/pod-logs/**/*.log*");
+            paths.clone().into_iter().map(PathBuf::from)
+        };
+
+        let no_filter = NameFilter::default();
+        let actual_paths: Vec<_> = list_extra_log_paths(
+            mock_glob,
+            &extra_log_dirs,
+            LogLayout::Auto,
+            DEFAULT_POD_DIR_DELIMITER,
+            true,
+            &no_filter,
+            &no_filter,
+        )
+        .collect();
+
+        assert_eq!(
+            actual_paths,
+            vec![
+                PathBuf::from(
+                    "/mnt/pod-logs/team-a_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b/app/0.log"
+                ),
+                PathBuf::from("/mnt/pod-logs/team-a_sandbox1-name_system-7f9c2b1d/app/0.log"),
+                PathBuf::from("/mnt/pod-logs/app.log"),
+            ]
+        );
+    }
+
+    fn at(minutes: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, minutes, 0).unwrap()
+    }
+
+    fn terminated(started: u32, finished: u32) -> ContainerState {
+        ContainerState {
+            terminated: Some(ContainerStateTerminated {
+                started_at: Some(Time(at(started))),
+                finished_at: Some(Time(at(finished))),
+                ..ContainerStateTerminated::default()
+            }),
+            ..ContainerState::default()
+        }
+    }
+
+    fn running(started: u32) -> ContainerState {
+        ContainerState {
+            running: Some(ContainerStateRunning {
+                started_at: Some(Time(at(started))),
+            }),
+            ..ContainerState::default()
+        }
+    }
+
+    fn pod_with_container_status(
+        restart_count: i32,
+        state: ContainerState,
+        last_state: Option<ContainerState>,
+    ) -> Pod {
+        Pod {
+            status: Some(PodStatus {
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_owned(),
+                    restart_count,
+                    state: Some(state),
+                    last_state,
+                    ..ContainerStatus::default()
+                }]),
+                ..PodStatus::default()
+            }),
+            ..Pod::default()
+        }
+    }
+
+    #[test]
+    fn test_container_terminated_at() {
+        // The current instance is terminated.
+        let pod = pod_with_container_status(1, terminated(5, 10), Some(terminated(0, 4)));
+        assert_eq!(container_terminated_at(&pod, "app", 1), Some(at(10)));
+        assert_eq!(container_terminated_at(&pod, "app", 0), Some(at(4)));
+        assert_eq!(container_terminated_at(&pod, "app", 2), None);
+        assert_eq!(container_terminated_at(&pod, "other", 1), None);
+
+        // The current instance is running, after a restart.
+        let pod = pod_with_container_status(2, running(5), Some(terminated(3, 4)));
+        assert_eq!(container_terminated_at(&pod, "app", 2), None);
+        assert_eq!(container_terminated_at(&pod, "app", 1), Some(at(4)));
+        assert_eq!(container_terminated_at(&pod, "app", 0), Some(at(4)));
+
+        // The last state is not known.
+        let pod = pod_with_container_status(1, running(5), None);
+        assert_eq!(container_terminated_at(&pod, "app", 1), None);
+        assert_eq!(container_terminated_at(&pod, "app", 0), Some(at(5)));
+
+        assert_eq!(container_terminated_at(&Pod::default(), "app", 0), None);
+    }
+
+    #[test]
+    fn test_is_terminated_for() {
+        let pod = pod_with_container_status(1, terminated(5, 10), Some(terminated(0, 4)));
+        let after = Duration::from_secs(5 * 60);
+        let is_terminated_for = |path: &str, now| {
+            is_terminated_for(
+                LogLayout::Auto,
+                DEFAULT_POD_DIR_DELIMITER,
+                &pod,
+                Path::new(path),
+                after,
+                now,
+            )
+        };
+
+        let dir = "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/app";
+        assert!(!is_terminated_for(&format!("{}/1.log", dir), at(14)));
+        assert!(is_terminated_for(&format!("{}/1.log", dir), at(15)));
+        assert!(is_terminated_for(&format!("{}/0.log", dir), at(14)));
+        assert!(is_terminated_for(
+            &format!("{}/0.log.20240101-120400", dir),
+            at(14)
+        ));
+        // The container symlinks don't carry a restart count.
+        assert!(!is_terminated_for(
+            "/var/log/containers/sandbox0-name_sandbox0-ns_app-8a3c1e.log",
+            at(30)
+        ));
+    }
+
+    fn pod_on_node(uid: &str, node_name: Option<&str>) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                namespace: Some("sandbox0-ns".to_owned()),
+                name: Some(format!("{}-name", uid)),
+                uid: Some(uid.to_owned()),
+                ..ObjectMeta::default()
+            },
+            spec: Some(PodSpec {
+                node_name: node_name.map(str::to_owned),
+                ..PodSpec::default()
+            }),
+            ..Pod::default()
+        }
+    }
+
+    #[test]
+    fn test_is_local_log_path() {
+        let local = pod_on_node("local-uid", Some("node-a"));
+        let local_pod_uids: HashSet<_> = pod_logs_uid(&local)
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+
+        let cases = vec![
+            (
+                LogLayout::Pods,
+                "/mnt/shared/sandbox0-ns_local-uid-name_local-uid/app/0.log",
+                true,
+            ),
+            // The Pod isn't watched on the Node.
+            (
+                LogLayout::Pods,
+                "/mnt/shared/sandbox0-ns_elsewhere-uid-name_elsewhere-uid/app/0.log",
+                false,
+            ),
+            // The path doesn't tell which Pod the log file belongs to.
+            (LogLayout::Pods, "/mnt/shared/app.log", true),
+            (
+                LogLayout::Containers,
+                "/var/log/containers/elsewhere-uid-name_sandbox0-ns_app-0123456789abcdef.log",
+                true,
+            ),
+        ];
+        for (log_layout, path, expected) in cases {
+            assert_eq!(
+                is_local_log_path(
+                    log_layout,
+                    DEFAULT_POD_DIR_DELIMITER,
+                    &local_pod_uids,
+                    Path::new(path)
+                ),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_paths_self_node_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let pod_logs_root = dir.path().join("pods");
+        let extra_log_dir = dir.path().join("shared");
+        let local_path = pod_logs_root.join("sandbox0-ns_local-uid-name_local-uid/app/0.log");
+        let shared_local_path =
+            extra_log_dir.join("sandbox0-ns_local-uid-name_local-uid/app/0.log");
+        let shared_elsewhere_path =
+            extra_log_dir.join("sandbox0-ns_elsewhere-uid-name_elsewhere-uid/app/0.log");
+        let shared_other_path = extra_log_dir.join("app.log");
+        for path in [
+            &local_path,
+            &shared_local_path,
+            &shared_elsewhere_path,
+            &shared_other_path,
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+
+        // Only the Pods on the Node are watched.
+        let mut pods = store::Writer::default();
+        pods.apply_watcher_event(&watcher::Event::Restarted(vec![pod_on_node(
+            "local-uid",
+            Some("node-a"),
+        )]));
+        let mut namespaces = store::Writer::default();
+        namespaces.apply_watcher_event(&watcher::Event::Restarted(vec![Namespace {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-ns".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Namespace::default()
+        }]));
+        let paths = |self_node_filter| {
+            let mut paths = K8sPathsProvider::new(pods.as_reader(), namespaces.as_reader(), vec![])
+                .with_log_layout(LogLayout::Pods, DEFAULT_POD_DIR_DELIMITER)
+                .with_logs_roots(pod_logs_root.clone(), dir.path().join("containers"))
+                .with_extra_log_dirs(vec![extra_log_dir.clone()])
+                .with_self_node_filter(self_node_filter)
+                .paths();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(
+            paths(false),
+            vec![
+                local_path.clone(),
+                shared_other_path.clone(),
+                shared_elsewhere_path,
+                shared_local_path.clone(),
+            ]
+        );
+        // The log file of the Pod not on the Node is filtered out.
+        assert_eq!(
+            paths(true),
+            vec![local_path, shared_other_path, shared_local_path]
+        );
+    }
+
+    #[test]
+    fn test_paths_read_rotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let pod_logs_root = dir.path().join("pods");
+        let container_dir = pod_logs_root.join("sandbox0-ns_local-uid-name_local-uid/app");
+        let log_path = container_dir.join("0.log");
+        let rotated_path = container_dir.join("0.log.20240101-120000");
+        let compressed_path = container_dir.join("0.log.20240101-110000.gz");
+        let tmp_path = container_dir.join("0.log.20240101-100000.gz.tmp");
+        let excluded_dir = pod_logs_root.join("sandbox0-ns_local-uid-name_local-uid/sidecar");
+        let excluded_compressed_path = excluded_dir.join("0.log.20240101-110000.gz");
+        for path in [
+            &log_path,
+            &rotated_path,
+            &compressed_path,
+            &tmp_path,
+            &excluded_dir.join("0.log"),
+            &excluded_compressed_path,
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+
+        let mut pods = store::Writer::default();
+        pods.apply_watcher_event(&watcher::Event::Restarted(vec![pod_on_node(
+            "local-uid",
+            Some("node-a"),
+        )]));
+        let mut namespaces = store::Writer::default();
+        namespaces.apply_watcher_event(&watcher::Event::Restarted(vec![Namespace {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-ns".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Namespace::default()
+        }]));
+        // The default exclusion patterns, along with one excluding a container.
+        let exclude_paths: Vec<_> = crate::sources::kubernetes_logs::default_path_exclusion()
+            .iter()
+            .map(|pattern| glob::Pattern::new(pattern.to_str().unwrap()).unwrap())
+            .chain([glob::Pattern::new("**/sidecar/*").unwrap()])
+            .collect();
+        let paths = |read_rotations| {
+            let mut paths = K8sPathsProvider::new(
+                pods.as_reader(),
+                namespaces.as_reader(),
+                exclude_paths.clone(),
+            )
+            .with_log_layout(LogLayout::Pods, DEFAULT_POD_DIR_DELIMITER)
+            .with_logs_roots(pod_logs_root.clone(), dir.path().join("containers"))
+            .with_rotations(read_rotations)
+            .paths();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(paths(false), vec![log_path.clone()]);
+        // The compressed rotation isn't excluded by the default `**/*.gz`
+        // pattern, but the ones being compressed and the rotations of the
+        // excluded container still are.
+        assert_eq!(paths(true), vec![log_path, compressed_path, rotated_path]);
+    }
+
+    fn pod_in_phase(uid: &str, phase: Option<&str>) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                namespace: Some("sandbox0-ns".to_owned()),
+                name: Some(format!("{}-name", uid)),
+                uid: Some(uid.to_owned()),
+                ..ObjectMeta::default()
+            },
+            status: Some(PodStatus {
+                phase: phase.map(str::to_owned),
+                ..PodStatus::default()
+            }),
+            ..Pod::default()
+        }
+    }
+
+    #[test]
+    fn test_is_phase_excluded() {
+        use PodPhase::*;
+
+        let phases = [
+            (Some("Pending"), Some(Pending)),
+            (Some("Running"), Some(Running)),
+            (Some("Succeeded"), Some(Succeeded)),
+            (Some("Failed"), Some(Failed)),
+            (Some("Unknown"), Some(Unknown)),
+            // The phase isn't known yet, or is one this version doesn't know of.
+            (None, None),
+            (Some("Evicted"), None),
+        ];
+        let includes: Vec<Vec<PodPhase>> = vec![
+            vec![],
+            vec![Running],
+            vec![Running, Failed],
+            vec![Pending, Running, Succeeded, Failed, Unknown],
+        ];
+        for include_pod_phases in &includes {
+            for (phase, expected_phase) in phases {
+                let pod = pod_in_phase("sandbox0-uid", phase);
+                assert_eq!(PodPhase::of(&pod), expected_phase, "{:?}", phase);
+
+                let expected = match expected_phase {
+                    Some(phase) => {
+                        !include_pod_phases.is_empty() && !include_pod_phases.contains(&phase)
+                    }
+                    None => false,
+                };
+                assert_eq!(
+                    is_phase_excluded(&pod, include_pod_phases),
+                    expected,
+                    "{:?} with {:?}",
+                    phase,
+                    include_pod_phases
+                );
+            }
+        }
+
+        // Pods without a status at all are read too.
+        let pod = Pod {
+            status: None,
+            ..pod_in_phase("sandbox0-uid", None)
+        };
+        assert!(!is_phase_excluded(&pod, &[Running]));
+    }
+
+    #[test]
+    fn test_is_excluded_log_path() {
+        let excluded_pod_uids: HashSet<_> = [
+            pod_in_phase("running-uid", Some("Running")),
+            pod_in_phase("succeeded-uid", Some("Succeeded")),
+            pod_in_phase("pending-uid", None),
+        ]
+        .iter()
+        .filter(|pod| is_phase_excluded(pod, &[PodPhase::Running, PodPhase::Failed]))
+        .filter_map(pod_logs_uid)
+        .map(str::to_owned)
+        .collect();
+
+        let cases = vec![
+            (
+                "/mnt/extra/sandbox0-ns_running-uid-name_running-uid/app/0.log",
+                false,
+            ),
+            (
+                "/mnt/extra/sandbox0-ns_succeeded-uid-name_succeeded-uid/app/0.log",
+                true,
+            ),
+            // The phase of the Pod isn't known yet.
+            (
+                "/mnt/extra/sandbox0-ns_pending-uid-name_pending-uid/app/0.log",
+                false,
+            ),
+            // The Pod isn't known at all.
+            (
+                "/mnt/extra/sandbox0-ns_unknown-uid-name_unknown-uid/app/0.log",
+                false,
+            ),
+            // The path doesn't tell which Pod the log file belongs to.
+            ("/mnt/extra/app.log", false),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                is_excluded_log_path(
+                    LogLayout::Pods,
+                    DEFAULT_POD_DIR_DELIMITER,
+                    &excluded_pod_uids,
+                    Path::new(path)
+                ),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_pod_phases() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            include_pod_phases: Vec<PodPhase>,
+        }
+
+        let config: Config =
+            toml::from_str(r#"include_pod_phases = ["running", "failed"]"#).unwrap();
+        assert_eq!(
+            config.include_pod_phases,
+            [PodPhase::Running, PodPhase::Failed]
+        );
+        assert!(toml::from_str::<Config>(r#"include_pod_phases = ["Running"]"#).is_err());
+    }
 }
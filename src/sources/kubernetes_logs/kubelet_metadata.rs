@@ -0,0 +1,252 @@
+//! A fallback source of Pod metadata, backed by the local kubelet.
+//!
+//! The kubelet serves the specs of the Pods scheduled on its Node at the
+//! `/pods` endpoint, which stays reachable while the API server is not. This
+//! is used to annotate events only while the Pod watch stream is
+//! disconnected.
+
+#![deny(missing_docs)]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    runtime::{
+        reflector::{store, ObjectRef, Store},
+        watcher,
+    },
+    Client,
+};
+use serde::Deserialize;
+use serde_with::serde_as;
+use vector_lib::configurable::configurable_component;
+
+use crate::internal_events::{
+    KubernetesLogsKubeletMetadataFallback, KubernetesLogsKubeletMetadataRequestError,
+};
+
+/// The port the kubelet serves its authenticated API on.
+const KUBELET_PORT: u16 = 10250;
+
+/// Configuration for the kubelet metadata fallback.
+///
+/// When enabled, the local kubelet is queried for Pod metadata while the watch stream against
+/// the API server is disconnected. The requests use the same credentials as the API server
+/// connection, so the service account needs access to the `nodes/proxy` resource.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct KubeletMetadataConfig {
+    /// Whether to fall back to the kubelet for Pod metadata.
+    pub enabled: bool,
+
+    /// The URL of the kubelet to query.
+    ///
+    /// If not set, the kubelet is reached at port 10250 of the Node named by `self_node_name`.
+    #[configurable(metadata(docs::examples = "https://10.0.0.1:10250"))]
+    pub endpoint: Option<String>,
+
+    /// How often the kubelet is polled while the watch stream is disconnected.
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    pub refresh_interval_secs: Duration,
+
+    /// Whether to verify the TLS certificate served by the kubelet.
+    ///
+    /// Kubelets commonly serve a self-signed certificate, which can't be verified against the
+    /// cluster CA.
+    pub verify_certificate: bool,
+}
+
+impl Default for KubeletMetadataConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            refresh_interval_secs: default_refresh_interval_secs(),
+            verify_certificate: true,
+        }
+    }
+}
+
+const fn default_refresh_interval_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
+impl KubeletMetadataConfig {
+    /// Builds a client for the kubelet from the API server client config.
+    pub fn build_client(
+        &self,
+        mut client_config: kube::Config,
+        self_node_name: &str,
+    ) -> crate::Result<Client> {
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => format!("https://{}:{}", self_node_name, KUBELET_PORT),
+        };
+
+        client_config.cluster_url = endpoint.parse()?;
+        client_config.accept_invalid_certs = !self.verify_certificate;
+
+        Ok(Client::try_from(client_config)?)
+    }
+}
+
+/// Tracks whether a watch stream is currently connected.
+#[derive(Clone, Default)]
+pub struct WatchConnectivity(Arc<AtomicBool>);
+
+impl WatchConnectivity {
+    /// Wraps the watch stream, so that its items update the connectivity.
+    pub fn track<K, S>(&self, stream: S) -> impl Stream<Item = watcher::Result<watcher::Event<K>>>
+    where
+        S: Stream<Item = watcher::Result<watcher::Event<K>>>,
+    {
+        let connected = Arc::clone(&self.0);
+        stream.inspect(move |result| connected.store(result.is_ok(), Ordering::Relaxed))
+    }
+
+    /// Whether the last item of the watch stream was a successful one.
+    pub fn is_connected(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Lookups into the Pods obtained from the kubelet.
+pub struct KubeletPodsFallback {
+    pods_state_reader: Store<Pod>,
+    watch_connectivity: WatchConnectivity,
+}
+
+impl KubeletPodsFallback {
+    /// Create a new [`KubeletPodsFallback`].
+    pub const fn new(pods_state_reader: Store<Pod>, watch_connectivity: WatchConnectivity) -> Self {
+        Self {
+            pods_state_reader,
+            watch_connectivity,
+        }
+    }
+
+    /// Looks up the Pod, if the watch stream is currently disconnected.
+    pub fn get(&self, obj: &ObjectRef<Pod>) -> Option<Arc<Pod>> {
+        if self.watch_connectivity.is_connected() {
+            return None;
+        }
+
+        let pod = self.pods_state_reader.get(obj)?;
+        emit!(KubernetesLogsKubeletMetadataFallback);
+        Some(pod)
+    }
+}
+
+/// The payload of the kubelet `/pods` endpoint.
+#[derive(Deserialize)]
+struct PodList {
+    items: Vec<Pod>,
+}
+
+async fn fetch_pods(client: &Client) -> kube::Result<Vec<Pod>> {
+    let request = http::Request::get("/pods")
+        .body(Vec::new())
+        .map_err(kube::Error::HttpError)?;
+    let list: PodList = client.request(request).await?;
+    Ok(list.items)
+}
+
+/// Polls the kubelet, refreshing the Pods store while the watch stream is
+/// disconnected.
+pub async fn run_kubelet_poller(
+    client: Client,
+    mut store: store::Writer<Pod>,
+    watch_connectivity: WatchConnectivity,
+    refresh_interval: Duration,
+) {
+    let mut interval = tokio::time::interval(refresh_interval);
+    loop {
+        interval.tick().await;
+
+        if watch_connectivity.is_connected() {
+            continue;
+        }
+
+        match fetch_pods(&client).await {
+            Ok(pods) => store.apply_watcher_event(&watcher::Event::Restarted(pods)),
+            Err(error) => emit!(KubernetesLogsKubeletMetadataRequestError { error }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn watch_connectivity_follows_stream() {
+        let connectivity = WatchConnectivity::default();
+        assert!(!connectivity.is_connected());
+
+        let events: Vec<watcher::Result<watcher::Event<Pod>>> = vec![
+            Ok(watcher::Event::Restarted(vec![])),
+            Err(watcher::Error::TooManyObjects),
+        ];
+        let mut tracked = Box::pin(connectivity.track(stream::iter(events)));
+
+        tracked.next().await;
+        assert!(connectivity.is_connected());
+        tracked.next().await;
+        assert!(!connectivity.is_connected());
+    }
+
+    #[test]
+    fn fallback_only_while_disconnected() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let mut store_w = store::Writer::default();
+        store_w.apply_watcher_event(&watcher::Event::Restarted(vec![pod]));
+
+        let connectivity = WatchConnectivity::default();
+        let fallback = KubeletPodsFallback::new(store_w.as_reader(), connectivity.clone());
+        let obj = ObjectRef::<Pod>::new("sandbox0-name").within("sandbox0-ns");
+
+        assert!(fallback.get(&obj).is_some());
+
+        connectivity.0.store(true, Ordering::Relaxed);
+        assert!(fallback.get(&obj).is_none());
+    }
+
+    #[test]
+    fn parse_kubelet_pod_list() {
+        let list: PodList = serde_json::from_str(
+            r#"{
+                "kind": "PodList",
+                "apiVersion": "v1",
+                "metadata": {},
+                "items": [{"metadata": {"name": "sandbox0-name", "namespace": "sandbox0-ns"}}]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(
+            list.items[0].metadata.name.as_deref(),
+            Some("sandbox0-name")
+        );
+    }
+}
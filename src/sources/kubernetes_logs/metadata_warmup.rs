@@ -0,0 +1,226 @@
+//! Holding the events back at startup, until the metadata they are annotated
+//! with is loaded.
+//!
+//! The Pods, Namespaces and Node are listed before they are watched, and the
+//! stores the events are annotated from stay empty until the lists are
+//! complete. The events read in the meantime would go out without metadata.
+
+#![deny(missing_docs)]
+
+use std::time::Duration;
+
+use futures::{future, stream, Stream, StreamExt};
+use serde_with::serde_as;
+use vector_lib::configurable::configurable_component;
+
+use crate::kubernetes::reflector::InitialList;
+
+/// Configuration for loading the Kubernetes metadata, and annotating the events with it.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct MetadataConfig {
+    /// The maximum time to hold the events back at startup, until the Pods, Namespaces and Node
+    /// are listed.
+    ///
+    /// The events are held back until the lists are complete, or this timeout elapses, whichever
+    /// comes first, so that the first events read from every Pod are annotated. Set to `0` to
+    /// send the events right away, even if the metadata isn't loaded yet.
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Warmup Timeout"))]
+    pub warmup_timeout_ms: Duration,
+
+    /// A list of the keys of the Pod labels to annotate the events with.
+    ///
+    /// If not set, all the Pod labels are added to the events. If empty, none of them are.
+    /// Limiting them keeps the number of distinct fields of the events down.
+    #[configurable(metadata(docs::examples = "app.kubernetes.io/name"))]
+    pub include_label_keys: Option<Vec<String>>,
+
+    /// A list of the keys of the Pod annotations to annotate the events with.
+    ///
+    /// If not set, all the Pod annotations are added to the events. If empty, none of them are.
+    /// Limiting them keeps the number of distinct fields of the events down.
+    #[configurable(metadata(docs::examples = "app.kubernetes.io/owner"))]
+    pub include_annotation_keys: Option<Vec<String>>,
+
+    /// Whether to add the Pod labels and annotations as single dotted fields.
+    ///
+    /// The `app.kubernetes.io/name` label is then added as the `pod_labels.app.kubernetes.io/name`
+    /// field, next to the other Pod fields, rather than as the `app.kubernetes.io/name` field of
+    /// the `pod_labels` object.
+    pub flatten: bool,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            warmup_timeout_ms: default_warmup_timeout_ms(),
+            include_label_keys: None,
+            include_annotation_keys: None,
+            flatten: false,
+        }
+    }
+}
+
+const fn default_warmup_timeout_ms() -> Duration {
+    Duration::from_millis(10_000)
+}
+
+/// Waits until all the initial lists are complete, for at most `timeout`.
+async fn wait_for_initial_lists(initial_lists: Vec<InitialList>, timeout: Duration) {
+    if timeout.is_zero() {
+        return;
+    }
+
+    let complete = future::join_all(initial_lists.iter().map(InitialList::wait));
+    match tokio::time::timeout(timeout, complete).await {
+        Ok(_) => debug!(message = "Kubernetes metadata loaded."),
+        Err(_) => warn!(
+            message = "Timed out waiting for the Kubernetes metadata to load.",
+            timeout_ms = timeout.as_millis() as u64,
+        ),
+    }
+}
+
+/// Holds the events back until all the initial lists are complete, for at
+/// most `timeout`.
+///
+/// The events aren't polled in the meantime, so they are only annotated
+/// once the metadata is loaded.
+pub fn after_warmup<S>(
+    events: S,
+    initial_lists: Vec<InitialList>,
+    timeout: Duration,
+) -> impl Stream<Item = S::Item>
+where
+    S: Stream,
+{
+    stream::once(wait_for_initial_lists(initial_lists, timeout))
+        .filter_map(|()| future::ready(None))
+        .chain(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use futures::{channel::mpsc, SinkExt};
+    use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
+    use kube::runtime::{reflector::store, watcher};
+    use vector_lib::{config::LogNamespace, lookup::event_path};
+
+    use super::*;
+    use crate::{
+        event::{Event, LogEvent},
+        kubernetes::{
+            custom_reflector, meta_cache::MetaCache, path_helpers::LogLayout,
+            reflector::DelayedDeletions,
+        },
+        sources::kubernetes_logs::pod_metadata_annotator::{
+            FieldsSpec, MetadataKeys, PodMetadataAnnotator,
+        },
+    };
+
+    const FILE: &str =
+        "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log";
+
+    fn pod() -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        }
+    }
+
+    #[test]
+    fn parse_config() {
+        let config: MetadataConfig = toml::from_str("").unwrap();
+        assert_eq!(config.warmup_timeout_ms, Duration::from_secs(10));
+        assert_eq!(config.include_label_keys, None);
+        assert!(!config.flatten);
+
+        let config: MetadataConfig = toml::from_str("warmup_timeout_ms = 500").unwrap();
+        assert_eq!(config.warmup_timeout_ms, Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn events_during_warmup_are_annotated() {
+        let store_w = store::Writer::default();
+        let mut annotator = PodMetadataAnnotator::new(
+            store_w.as_reader(),
+            DelayedDeletions::default(),
+            None,
+            LogLayout::Auto,
+            FieldsSpec::default(),
+            MetadataKeys::default(),
+            LogNamespace::Legacy,
+        );
+        let (mut tx, rx) = mpsc::channel::<_>(5);
+        let initial_list = InitialList::default();
+        tokio::spawn(custom_reflector(
+            store_w,
+            MetaCache::new(),
+            rx,
+            Duration::from_secs(1),
+            usize::MAX,
+            DelayedDeletions::default(),
+            initial_list.clone(),
+        ));
+
+        // The lines are read before the Pods are listed.
+        let events = stream::iter(0..3).map(move |line| {
+            let mut event = Event::Log(LogEvent::from(format!("line {}", line)));
+            annotator.annotate(&mut event, FILE);
+            event
+        });
+        let events = tokio::spawn(
+            after_warmup(events, vec![initial_list], Duration::from_secs(30)).collect::<Vec<_>>(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!events.is_finished());
+
+        tx.send(Ok(watcher::Event::Restarted(vec![pod()])))
+            .await
+            .unwrap();
+        let events = events.await.unwrap();
+
+        assert_eq!(events.len(), 3);
+        for event in events {
+            assert_eq!(
+                event.as_log().get(event_path!("kubernetes", "pod_name")),
+                Some(&"sandbox0-name".into())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn warmup_times_out() {
+        let events = stream::iter(0..3);
+        let start = Instant::now();
+        let events: Vec<_> = after_warmup(
+            events,
+            vec![InitialList::default()],
+            Duration::from_millis(100),
+        )
+        .collect()
+        .await;
+        assert_eq!(events, [0, 1, 2]);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Without a timeout, the events aren't held back at all.
+        let events: Vec<_> = after_warmup(
+            stream::iter(0..3),
+            vec![InitialList::default()],
+            Duration::ZERO,
+        )
+        .collect()
+        .await;
+        assert_eq!(events, [0, 1, 2]);
+    }
+}
@@ -1,21 +1,25 @@
 //! This mod implements `kubernetes_logs` source.
 //! The scope of this source is to consume the log files that a kubelet keeps
-//! at "/var/log/pods" on the host of the Kubernetes Node when Vector itself is
-//! running inside the cluster as a DaemonSet.
+//! at "/var/log/pods" (or the configured `pod_logs_root`), or the legacy
+//! symlinks to them at "/var/log/containers", on the host of the Kubernetes
+//! Node when Vector itself is running inside the cluster as a DaemonSet.
 
 #![deny(missing_docs)]
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use bytes::Bytes;
 use chrono::Utc;
 use futures::{future::FutureExt, stream::StreamExt};
 use futures_util::Stream;
 use k8s_openapi::api::core::v1::{Namespace, Node, Pod};
-use k8s_paths_provider::K8sPathsProvider;
+use k8s_paths_provider::{ActivePods, K8sPathsProvider, NameFilter, PodPhase};
 use kube::{
     api::Api,
     config::{self, KubeConfigOptions},
-    runtime::{reflector, watcher, WatchStreamExt},
+    runtime::{reflector, watcher},
     Client, Config as ClientConfig,
 };
 use lifecycle::Lifecycle;
@@ -23,8 +27,8 @@ use serde_with::serde_as;
 use vector_lib::codecs::{BytesDeserializer, BytesDeserializerConfig};
 use vector_lib::configurable::configurable_component;
 use vector_lib::file_source::{
-    calculate_ignore_before, Checkpointer, FileServer, FileServerShutdown, FingerprintStrategy,
-    Fingerprinter, Line, ReadFrom, ReadFromConfig,
+    calculate_ignore_before, Checkpointer, Compression, FileServer, FileServerShutdown,
+    FingerprintStrategy, Fingerprinter, Line, ReadFrom, ReadFromConfig,
 };
 use vector_lib::lookup::{lookup_v2::OptionalTargetPath, owned_value_path, path, OwnedTargetPath};
 use vector_lib::{config::LegacyKey, config::LogNamespace, EstimatedJsonEncodedSizeOf};
@@ -42,33 +46,51 @@ use crate::{
     },
     event::Event,
     internal_events::{
-        FileInternalMetricsConfig, FileSourceInternalEventsEmitter, KubernetesLifecycleError,
+        FileSourceInternalEventsEmitter, KubernetesLifecycleError,
         KubernetesLogsEventAnnotationError, KubernetesLogsEventNamespaceAnnotationError,
         KubernetesLogsEventNodeAnnotationError, KubernetesLogsEventsReceived,
-        KubernetesLogsPodInfo, StreamClosedError,
+        KubernetesLogsInternalMetricsConfig, KubernetesLogsLineRead, KubernetesLogsPodInfo,
+        KubernetesLogsPodTags, StreamClosedError,
+    },
+    kubernetes::{
+        custom_reflector,
+        meta_cache::MetaCache,
+        path_helpers,
+        reflector::{DelayedDeletions, InitialList},
     },
-    kubernetes::{custom_reflector, meta_cache::MetaCache},
     shutdown::ShutdownSignal,
     sources,
     transforms::{FunctionTransform, OutputBuffer},
     SourceSender,
 };
 
+pub mod discover_paths;
+mod file_discovery;
+mod fingerprint;
 mod k8s_paths_provider;
+mod kubelet_metadata;
 mod lifecycle;
+mod metadata_warmup;
 mod namespace_metadata_annotator;
 mod node_metadata_annotator;
 mod parser;
 mod partial_events_merger;
-mod path_helpers;
 mod pod_metadata_annotator;
+mod spill;
 mod transform_utils;
 mod util;
+mod watch_reconnect;
 
+use self::file_discovery::{FileDiscoveredEvents, FileDiscovery};
+use self::fingerprint::FingerprintConfig;
+use self::kubelet_metadata::{KubeletPodsFallback, WatchConnectivity};
+use self::metadata_warmup::after_warmup;
 use self::namespace_metadata_annotator::NamespaceMetadataAnnotator;
 use self::node_metadata_annotator::NodeMetadataAnnotator;
-use self::parser::Parser;
-use self::pod_metadata_annotator::PodMetadataAnnotator;
+use self::parser::{LogFormat, Parser};
+use self::pod_metadata_annotator::{MetadataKeys, PodMetadataAnnotator};
+use self::spill::SpillBufferConfig;
+use self::watch_reconnect::reconnecting;
 
 /// The `self_node_name` value env var key.
 const SELF_NODE_NAME_ENV_KEY: &str = "VECTOR_SELF_NODE_NAME";
@@ -106,7 +128,8 @@ pub struct Config {
     /// The name of the Kubernetes [Node][node] that is running.
     ///
     /// Configured to use an environment variable by default, to be evaluated to a value provided by
-    /// Kubernetes at Pod creation.
+    /// Kubernetes at Pod creation. When running outside of the cluster with a `kube_config_file`,
+    /// set this to the name of the Node the log files are read from.
     ///
     /// [node]: https://kubernetes.io/docs/concepts/architecture/nodes/
     self_node_name: String,
@@ -124,12 +147,52 @@ pub struct Config {
     ))]
     extra_field_selector: String,
 
+    /// Only read the log files found in `extra_log_dirs` of the Pods on the Node.
+    ///
+    /// The Pods are only watched on the Node named by `self_node_name`, so the log files of the
+    /// Pods themselves are always local. The files found in `extra_log_dirs` are not tied to the
+    /// Node though, and with this option the Pod UID in the path of each of them is cross-checked
+    /// against the Pods watched on the Node, and the files of the other Pods are skipped. This
+    /// prevents collecting the logs of other Nodes a second time when `extra_log_dirs` are shared
+    /// between Nodes, for example by a misconfigured `hostPath` volume. The files whose path
+    /// doesn't carry a Pod UID are read as usual.
+    #[serde(default)]
+    self_node_filter: bool,
+
     /// Whether or not to automatically merge partial events.
     ///
     /// Partial events are messages that were split by the Kubernetes Container Runtime
     /// log driver.
     auto_partial_merge: bool,
 
+    #[configurable(derived)]
+    format: LogFormat,
+
+    /// Whether or not to insert the stream the line was written to, `stdout` or `stderr`, into
+    /// the `kubernetes.stream` field.
+    ///
+    /// The stream is taken from the line for the CRI format and from the `stream` field for the
+    /// Docker format, so the field is set the same way for both, before the events are merged
+    /// and sent on.
+    extract_stream: bool,
+
+    /// Whether or not to use the timestamp at the beginning of the CRI lines as the timestamp of
+    /// the events.
+    ///
+    /// The timestamps are written by the container runtime in the RFC 3339 format, with
+    /// nanosecond precision, and are converted to UTC. Timestamps without an offset are assumed
+    /// to be in UTC. When disabled, or when the timestamp of a line can't be parsed, the time the
+    /// line was read at is used instead.
+    use_log_timestamp: bool,
+
+    /// The maximum number of bytes a merged partial event can contain.
+    ///
+    /// When merging the next partial event would exceed this, the accumulated message is emitted
+    /// as its own event, with the `truncated` metadata flag set to `true`, and merging starts
+    /// over with the next partial event. By default, merged partial events are unlimited in size.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_merged_line_bytes: Option<usize>,
+
     /// The directory used to persist file checkpoint positions.
     ///
     /// By default, the global `data_dir` option is used. Make sure the running user has write
@@ -138,6 +201,66 @@ pub struct Config {
     #[configurable(metadata(docs::human_name = "Data Directory"))]
     data_dir: Option<PathBuf>,
 
+    #[configurable(derived)]
+    spill_buffer: Option<SpillBufferConfig>,
+
+    /// The root directory where the kubelet keeps the Pod log files.
+    ///
+    /// Change this if the kubelet is configured with a custom `--root-dir`, or the Pod log
+    /// directory is otherwise relocated on the host, like to another volume on Windows Nodes.
+    ///
+    /// Defaults to `C:\var\log\pods` on Windows.
+    #[serde(default = "default_pod_logs_root")]
+    #[configurable(metadata(docs::examples = "/data/kubelet/log/pods"))]
+    #[configurable(metadata(docs::examples = "D:\\var\\log\\pods"))]
+    #[configurable(metadata(docs::human_name = "Pod Logs Root Directory"))]
+    pod_logs_root: PathBuf,
+
+    #[configurable(derived)]
+    log_layout: path_helpers::LogLayout,
+
+    /// The delimiter between the namespace, the name and the UID of the Pod in the names of the Pod
+    /// log directories.
+    ///
+    /// Change this if the Pod log directories are named like `<namespace>-<name>-<uid>` by a
+    /// customized kubelet. The delimiter may appear in the Pod names, but neither in the
+    /// namespaces nor in the UIDs, so a `-` delimiter only works if those are guaranteed not to
+    /// contain one.
+    ///
+    /// Only used with the `pods` and `auto` log layouts.
+    #[serde(default = "default_pod_dir_delimiter")]
+    #[configurable(metadata(docs::examples = "-"))]
+    pod_dir_delimiter: char,
+
+    /// The directory where the kubelet keeps the legacy symlinks to the container log files.
+    ///
+    /// Only used with the `containers` and `auto` log layouts.
+    #[serde(default = "default_container_logs_root")]
+    #[configurable(metadata(docs::examples = "/data/kubelet/log/containers"))]
+    #[configurable(metadata(docs::human_name = "Container Logs Root Directory"))]
+    container_logs_root: PathBuf,
+
+    /// Additional directories to discover log files in, besides the ones of the Pods on the Node.
+    ///
+    /// The files matching `<dir>/**/*.log` are read, but not their rotations, and rescanned as
+    /// often as the Pod log directories are, see `glob_minimum_cooldown_ms`. The files whose paths end with the
+    /// `<namespace>_<name>_<uid>/<container>/<n>.log` components of the Pod log files are
+    /// enriched with the metadata of their Pod, and are subject to the namespace and container
+    /// filters. The rest are read without any Pod metadata.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/mnt/pod-logs"))]
+    extra_log_dirs: Vec<PathBuf>,
+
+    /// Only take the files for Pod log files if the Pod UID in their path is well-formed.
+    ///
+    /// The Pod UID is well-formed if it is an RFC 4122 UUID, the config hashsum of a static Pod,
+    /// or a `system-<suffix>` UID. The files found in `extra_log_dirs` that follow the Pod log
+    /// layout, but with a malformed Pod UID, are taken for stray files placed there by other
+    /// tooling and are not read, and the events of the Pod log files with a malformed Pod UID are
+    /// not enriched with Pod metadata.
+    #[serde(default)]
+    strict_log_paths: bool,
+
     #[configurable(derived)]
     #[serde(alias = "annotation_fields")]
     pod_annotation_fields: pod_metadata_annotator::FieldsSpec,
@@ -152,6 +275,43 @@ pub struct Config {
     #[configurable(metadata(docs::examples = "**/exclude/**"))]
     exclude_paths_glob_patterns: Vec<PathBuf>,
 
+    /// A list of glob patterns to match the names of the containers to read the logs of.
+    ///
+    /// If empty, the logs of all containers are read. The patterns support the `*` and `?`
+    /// wildcards, and are case-sensitive.
+    #[configurable(metadata(docs::examples = "app-*"))]
+    include_container_names: Vec<String>,
+
+    /// A list of glob patterns to match the names of the containers to not read the logs of.
+    ///
+    /// Takes precedence over `include_container_names`, so a container that matches both is
+    /// excluded.
+    #[configurable(metadata(docs::examples = "istio-proxy"))]
+    exclude_container_names: Vec<String>,
+
+    /// A list of glob patterns to match the namespaces to read the logs of the Pods of.
+    ///
+    /// If empty, the logs of the Pods of all namespaces are read. The patterns support the `*` and
+    /// `?` wildcards, and are case-sensitive.
+    #[configurable(metadata(docs::examples = "team-*"))]
+    include_namespaces: Vec<String>,
+
+    /// A list of glob patterns to match the namespaces to not read the logs of the Pods of.
+    ///
+    /// Takes precedence over `include_namespaces`, so a namespace that matches both is excluded.
+    #[configurable(metadata(docs::examples = "kube-system"))]
+    exclude_namespaces: Vec<String>,
+
+    /// A list of the phases of the Pods to read the logs of.
+    ///
+    /// If empty, the logs of the Pods in all phases are read. The log files of the Pods in the
+    /// other phases are skipped, like the ones of the completed Jobs with `running` and `failed`
+    /// only. The logs of the Pods whose phase isn't known yet are read.
+    ///
+    /// The phase is checked as the log files are discovered, so a file already being read when
+    /// its Pod changes to an excluded phase is read to its end.
+    include_pod_phases: Vec<PodPhase>,
+
     #[configurable(derived)]
     #[serde(default = "default_read_from")]
     read_from: ReadFromConfig,
@@ -163,6 +323,22 @@ pub struct Config {
     #[configurable(metadata(docs::human_name = "Ignore Files Older Than"))]
     ignore_older_secs: Option<u64>,
 
+    /// Stop reading the log files of terminated containers after the specified number of seconds.
+    ///
+    /// The kubelet keeps the log file of a terminated container around, so it is otherwise
+    /// watched until the Pod is removed, even though it never grows. Once the container has been
+    /// terminated for this long, according to its status in the Kubernetes API, its log file is
+    /// read to the end and closed. A restarted container writes to a new log file, which is read
+    /// as usual.
+    ///
+    /// The log files are matched to the containers by the restart count in their name, so this
+    /// only applies to the `pods` log layout.
+    #[serde(default)]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::examples = 300))]
+    #[configurable(metadata(docs::human_name = "Close Terminated Container Files After"))]
+    close_terminated_after_secs: Option<u64>,
+
     /// Max amount of bytes to read from a single file before switching over to the next file.
     /// **Note:** This does not apply when `oldest_first` is `true`.
     ///
@@ -171,13 +347,29 @@ pub struct Config {
     #[configurable(metadata(docs::type_unit = "bytes"))]
     max_read_bytes: usize,
 
+    /// Max amount of bytes to read from each file per read cycle, scheduling the reads fairly
+    /// across the files.
+    ///
+    /// On every cycle, each file is read up to this many bytes, and the next cycle starts from the
+    /// file after the one the previous cycle started from. This keeps a few chatty containers from
+    /// holding back the logs of the other containers of the node. The lines are never split, so
+    /// reading a file stops at the end of the line crossing this limit, and the rest of the file
+    /// is read on the next cycles. When set, this takes precedence over `max_read_bytes` and
+    /// `oldest_first`.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    #[configurable(metadata(docs::examples = 16384))]
+    max_read_bytes_per_file: Option<usize>,
+
     /// Instead of balancing read capacity fairly across all watched files, prioritize draining the oldest files before moving on to read data from more recent files.
     #[serde(default = "default_oldest_first")]
     pub oldest_first: bool,
 
-    /// The maximum number of bytes a line can contain before being discarded.
+    /// The maximum number of bytes a line can contain before being truncated.
     ///
-    /// This protects against malformed lines or tailing incorrect files.
+    /// This protects against malformed lines or tailing incorrect files. The first
+    /// `max_line_bytes` bytes of a longer line are emitted with `kubernetes.truncated` set to
+    /// `true`, and the rest of the line is skipped. If the line is a partial event, the
+    /// remaining partial events of the same message are skipped as well.
     #[configurable(metadata(docs::type_unit = "bytes"))]
     max_line_bytes: usize,
 
@@ -186,9 +378,14 @@ pub struct Config {
     /// If your files share a common header that is not always a fixed size,
     ///
     /// If the file has less than this amount of lines, it won’t be read at all.
+    ///
+    /// Only used by the `checksum` fingerprinting strategy.
     #[configurable(metadata(docs::type_unit = "lines"))]
     fingerprint_lines: usize,
 
+    #[configurable(derived)]
+    fingerprint: FingerprintConfig,
+
     /// The interval at which the file system is polled to identify new files to read from.
     ///
     /// This is quite efficient, yet might still create some load on the
@@ -207,6 +404,31 @@ pub struct Config {
     #[configurable(metadata(docs::examples = ".ingest_timestamp", docs::examples = "ingest_ts"))]
     ingestion_timestamp_field: Option<OptionalTargetPath>,
 
+    /// Also read the rotations of the Pod log files, including the gzip-compressed ones.
+    ///
+    /// The kubelet rotates `<n>.log` to `<n>.log.<timestamp>`, and later compresses the rotations
+    /// to `<n>.log.<timestamp>.gz`, so the lines written while Vector wasn't running may only be
+    /// left in the rotations. The rotations are read as long as their log file is there, and
+    /// followed as they are renamed and compressed, so they keep their checkpoints across restarts
+    /// and their lines aren't read twice. The rotations without a checkpoint, like the ones both
+    /// rotated and compressed while Vector wasn't running, are read according to `read_from`.
+    ///
+    /// The compressed rotations are checked against `exclude_paths_glob_patterns` by their path
+    /// before compression, so they aren't excluded by the default `**/*.gz` pattern.
+    ///
+    /// The events read from the rotations have the metadata of the Pod log file they belong to.
+    #[serde(default)]
+    read_rotated_files: bool,
+
+    /// Emit an event when a Pod log file is first discovered.
+    ///
+    /// The event has `kubernetes.event` set to `file_discovered`, and carries the Pod and
+    /// container information extracted from the path of the file, before any line of the file is
+    /// read. It goes through the same pipeline as the log lines, but isn't annotated with the Pod
+    /// metadata. A file is only discovered again once it was removed and recreated.
+    #[serde(default)]
+    emit_file_discovered_events: bool,
+
     /// The default time zone for timestamps without an explicit zone.
     timezone: Option<TimeZone>,
 
@@ -218,19 +440,43 @@ pub struct Config {
     #[configurable(metadata(docs::examples = "/path/to/.kube/config"))]
     kube_config_file: Option<PathBuf>,
 
+    /// The name of the context in the `kube_config_file` to connect with.
+    ///
+    /// If not set, the current context of the kubeconfig file is used. Only used when
+    /// `kube_config_file` is set.
+    #[configurable(metadata(docs::examples = "dev-cluster"))]
+    kube_config_context: Option<String>,
+
     /// Determines if requests to the kube-apiserver can be served by a cache.
     use_apiserver_cache: bool,
 
+    #[configurable(derived)]
+    kubelet_metadata: kubelet_metadata::KubeletMetadataConfig,
+
+    #[configurable(derived)]
+    metadata: metadata_warmup::MetadataConfig,
+
     /// How long to delay removing metadata entries from the cache when a pod deletion event
     /// event is received from the watch stream.
     ///
     /// A longer delay allows for continued enrichment of logs after the originating Pod is
     /// removed. If relevant metadata has been removed, the log is forwarded un-enriched and a
     /// warning is emitted.
+    ///
+    /// The metadata of a deleted Pod is kept by UID, so its logs are still enriched with it even
+    /// once a Pod was recreated under the same name.
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
     #[configurable(metadata(docs::human_name = "Delay Deletion"))]
     delay_deletion_ms: Duration,
 
+    /// The maximum number of deleted objects of each kind to keep the metadata of for
+    /// `delay_deletion_ms`.
+    ///
+    /// Once more objects are deleted within the delay, like when a Node is drained, the metadata
+    /// of the ones deleted first is removed early, so the memory usage stays bounded.
+    #[configurable(metadata(docs::type_unit = "objects"))]
+    max_delayed_deletions: usize,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -238,7 +484,7 @@ pub struct Config {
 
     #[configurable(derived)]
     #[serde(default)]
-    internal_metrics: FileInternalMetricsConfig,
+    internal_metrics: KubernetesLogsInternalMetricsConfig,
 }
 
 const fn default_read_from() -> ReadFromConfig {
@@ -263,24 +509,50 @@ impl Default for Config {
             extra_namespace_label_selector: "".to_string(),
             self_node_name: default_self_node_name_env_template(),
             extra_field_selector: "".to_string(),
+            self_node_filter: false,
             auto_partial_merge: true,
+            format: LogFormat::default(),
+            extract_stream: true,
+            use_log_timestamp: true,
+            max_merged_line_bytes: None,
             data_dir: None,
+            spill_buffer: None,
+            pod_logs_root: default_pod_logs_root(),
+            log_layout: path_helpers::LogLayout::default(),
+            pod_dir_delimiter: default_pod_dir_delimiter(),
+            container_logs_root: default_container_logs_root(),
+            extra_log_dirs: Vec::new(),
+            strict_log_paths: false,
             pod_annotation_fields: pod_metadata_annotator::FieldsSpec::default(),
             namespace_annotation_fields: namespace_metadata_annotator::FieldsSpec::default(),
             node_annotation_fields: node_metadata_annotator::FieldsSpec::default(),
             exclude_paths_glob_patterns: default_path_exclusion(),
+            include_container_names: Vec::new(),
+            exclude_container_names: Vec::new(),
+            include_namespaces: Vec::new(),
+            exclude_namespaces: Vec::new(),
+            include_pod_phases: Vec::new(),
             read_from: default_read_from(),
             ignore_older_secs: None,
+            close_terminated_after_secs: None,
             max_read_bytes: default_max_read_bytes(),
+            max_read_bytes_per_file: None,
             oldest_first: default_oldest_first(),
             max_line_bytes: default_max_line_bytes(),
             fingerprint_lines: default_fingerprint_lines(),
+            fingerprint: FingerprintConfig::default(),
             glob_minimum_cooldown_ms: default_glob_minimum_cooldown_ms(),
             ingestion_timestamp_field: None,
+            read_rotated_files: false,
+            emit_file_discovered_events: false,
             timezone: None,
             kube_config_file: None,
+            kube_config_context: None,
             use_apiserver_cache: false,
+            kubelet_metadata: Default::default(),
+            metadata: Default::default(),
             delay_deletion_ms: default_delay_deletion_ms(),
+            max_delayed_deletions: default_max_delayed_deletions(),
             log_namespace: None,
             internal_metrics: Default::default(),
         }
@@ -316,6 +588,16 @@ impl SourceConfig for Config {
                 Kind::bytes(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(
+                    "kubernetes",
+                    "truncated"
+                ))),
+                &owned_value_path!("truncated"),
+                Kind::boolean().or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 self.pod_annotation_fields
@@ -340,6 +622,18 @@ impl SourceConfig for Config {
                 Kind::bytes().or_undefined(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                self.pod_annotation_fields
+                    .container_kind
+                    .path
+                    .clone()
+                    .map(|k| k.path)
+                    .map(LegacyKey::Overwrite),
+                &owned_value_path!("container_kind"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 self.pod_annotation_fields
@@ -352,6 +646,30 @@ impl SourceConfig for Config {
                 Kind::bytes().or_undefined(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                self.pod_annotation_fields
+                    .container_restart_count
+                    .path
+                    .clone()
+                    .map(|k| k.path)
+                    .map(LegacyKey::Overwrite),
+                &owned_value_path!("container_restart_count"),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                self.pod_annotation_fields
+                    .pod_dir_extra_segments
+                    .path
+                    .clone()
+                    .map(|k| k.path)
+                    .map(LegacyKey::Overwrite),
+                &owned_value_path!("pod_dir_extra_segments"),
+                Kind::array(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 self.namespace_annotation_fields
@@ -374,6 +692,50 @@ impl SourceConfig for Config {
                 Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
                 None,
             )
+            .with_source_metadata(
+                Self::NAME,
+                self.node_annotation_fields
+                    .node_allocatable_cpu
+                    .path
+                    .clone()
+                    .map(|x| LegacyKey::Overwrite(x.path)),
+                &owned_value_path!("node_allocatable_cpu"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                self.node_annotation_fields
+                    .node_allocatable_memory
+                    .path
+                    .clone()
+                    .map(|x| LegacyKey::Overwrite(x.path)),
+                &owned_value_path!("node_allocatable_memory"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                self.node_annotation_fields
+                    .node_kubelet_version
+                    .path
+                    .clone()
+                    .map(|x| LegacyKey::Overwrite(x.path)),
+                &owned_value_path!("node_kubelet_version"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                self.node_annotation_fields
+                    .node_name
+                    .path
+                    .clone()
+                    .map(|x| LegacyKey::Overwrite(x.path)),
+                &owned_value_path!("node_name"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
             .with_source_metadata(
                 Self::NAME,
                 self.pod_annotation_fields
@@ -501,6 +863,17 @@ impl SourceConfig for Config {
             )
             .with_standard_vector_source_metadata();
 
+        // In the Vector namespace, the stream is already in the `stream` metadata field.
+        let schema_definition = if self.extract_stream && log_namespace == LogNamespace::Legacy {
+            schema_definition.with_event_field(
+                &owned_value_path!("kubernetes", "stream"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+        } else {
+            schema_definition
+        };
+
         vec![SourceOutput::new_logs(DataType::Log, schema_definition)]
     }
 
@@ -512,8 +885,16 @@ impl SourceConfig for Config {
 #[derive(Clone)]
 struct Source {
     client: Client,
+    kubelet_client: Option<Client>,
+    kubelet_refresh_interval: Duration,
+    metadata_warmup_timeout: Duration,
     data_dir: PathBuf,
+    spill_buffer: Option<SpillBufferConfig>,
     auto_partial_merge: bool,
+    format: LogFormat,
+    extract_stream: bool,
+    use_log_timestamp: bool,
+    max_merged_line_bytes: Option<usize>,
     pod_fields_spec: pod_metadata_annotator::FieldsSpec,
     namespace_fields_spec: namespace_metadata_annotator::FieldsSpec,
     node_field_spec: node_metadata_annotator::FieldsSpec,
@@ -522,18 +903,37 @@ struct Source {
     namespace_label_selector: String,
     node_selector: String,
     self_node_name: String,
+    self_node_filter: bool,
+    log_layout: path_helpers::LogLayout,
+    pod_dir_delimiter: char,
+    pod_logs_root: PathBuf,
+    container_logs_root: PathBuf,
+    extra_log_dirs: Vec<PathBuf>,
+    strict_log_paths: bool,
     exclude_paths: Vec<glob::Pattern>,
+    include_container_names: Vec<glob::Pattern>,
+    exclude_container_names: Vec<glob::Pattern>,
+    namespace_filter: NameFilter,
+    include_pod_phases: Vec<PodPhase>,
+    metadata_keys: MetadataKeys,
     read_from: ReadFrom,
     ignore_older_secs: Option<u64>,
+    close_terminated_after: Option<Duration>,
     max_read_bytes: usize,
+    max_read_bytes_per_file: Option<usize>,
     oldest_first: bool,
     max_line_bytes: usize,
-    fingerprint_lines: usize,
+    fingerprint_strategy: FingerprintStrategy,
     glob_minimum_cooldown: Duration,
     use_apiserver_cache: bool,
     ingestion_timestamp_field: Option<OwnedTargetPath>,
+    read_rotated_files: bool,
+    emit_file_discovered_events: bool,
     delay_deletion: Duration,
+    max_delayed_deletions: usize,
     include_file_metric_tag: bool,
+    include_pod_metric_tags: bool,
+    max_tagged_pods: usize,
 }
 
 impl Source {
@@ -542,18 +942,7 @@ impl Source {
         globals: &GlobalOptions,
         key: &ComponentKey,
     ) -> crate::Result<Self> {
-        let self_node_name = if config.self_node_name.is_empty()
-            || config.self_node_name == default_self_node_name_env_template()
-        {
-            std::env::var(SELF_NODE_NAME_ENV_KEY).map_err(|_| {
-                format!(
-                    "self_node_name config value or {} env var is not set",
-                    SELF_NODE_NAME_ENV_KEY
-                )
-            })?
-        } else {
-            config.self_node_name.clone()
-        };
+        let self_node_name = resolve_self_node_name(config)?;
 
         let field_selector = prepare_field_selector(config, self_node_name.as_str())?;
         let label_selector = prepare_label_selector(config.extra_label_selector.as_ref());
@@ -561,25 +950,37 @@ impl Source {
             prepare_label_selector(config.extra_namespace_label_selector.as_ref());
         let node_selector = prepare_node_selector(self_node_name.as_str())?;
 
-        // If the user passed a custom Kubeconfig use it, otherwise
-        // we attempt to load the local kubeconfig, followed by the
-        // in-cluster environment variables
-        let client_config = match &config.kube_config_file {
-            Some(kc) => {
-                ClientConfig::from_custom_kubeconfig(
-                    config::Kubeconfig::read_from(kc)?,
-                    &KubeConfigOptions::default(),
-                )
-                .await?
-            }
-            None => ClientConfig::infer().await?,
+        let client_config = prepare_client_config(config).await?;
+        let kubelet_client = if config.kubelet_metadata.enabled {
+            Some(
+                config
+                    .kubelet_metadata
+                    .build_client(client_config.clone(), self_node_name.as_str())?,
+            )
+        } else {
+            None
         };
         let client = Client::try_from(client_config)?;
 
         let data_dir = globals.resolve_and_make_data_subdir(config.data_dir.as_ref(), key.id())?;
 
+        let pod_logs_root = prepare_logs_root("pod_logs_root", &config.pod_logs_root)?;
+        let pod_dir_delimiter = prepare_pod_dir_delimiter(config.pod_dir_delimiter)?;
+        let container_logs_root =
+            prepare_logs_root("container_logs_root", &config.container_logs_root)?;
+        let extra_log_dirs = config
+            .extra_log_dirs
+            .iter()
+            .map(|dir| prepare_logs_root("extra_log_dirs", dir))
+            .collect::<crate::Result<Vec<_>>>()?;
+
         let exclude_paths = prepare_exclude_paths(config)?;
 
+        let include_container_names = prepare_name_patterns(&config.include_container_names)?;
+        let exclude_container_names = prepare_name_patterns(&config.exclude_container_names)?;
+
+        let namespace_filter = prepare_namespace_filter(config)?;
+
         let glob_minimum_cooldown = config.glob_minimum_cooldown_ms;
 
         let delay_deletion = config.delay_deletion_ms;
@@ -591,8 +992,16 @@ impl Source {
 
         Ok(Self {
             client,
+            kubelet_client,
+            kubelet_refresh_interval: config.kubelet_metadata.refresh_interval_secs,
+            metadata_warmup_timeout: config.metadata.warmup_timeout_ms,
             data_dir,
+            spill_buffer: config.spill_buffer,
             auto_partial_merge: config.auto_partial_merge,
+            format: config.format,
+            extract_stream: config.extract_stream,
+            use_log_timestamp: config.use_log_timestamp,
+            max_merged_line_bytes: config.max_merged_line_bytes,
             pod_fields_spec: config.pod_annotation_fields.clone(),
             namespace_fields_spec: config.namespace_annotation_fields.clone(),
             node_field_spec: config.node_annotation_fields.clone(),
@@ -601,18 +1010,43 @@ impl Source {
             namespace_label_selector,
             node_selector,
             self_node_name,
+            self_node_filter: config.self_node_filter,
+            log_layout: config.log_layout,
+            pod_dir_delimiter,
+            pod_logs_root,
+            container_logs_root,
+            extra_log_dirs,
+            strict_log_paths: config.strict_log_paths,
             exclude_paths,
+            include_container_names,
+            exclude_container_names,
+            namespace_filter,
+            include_pod_phases: config.include_pod_phases.clone(),
+            metadata_keys: MetadataKeys {
+                labels: config.metadata.include_label_keys.clone(),
+                annotations: config.metadata.include_annotation_keys.clone(),
+                flatten: config.metadata.flatten,
+            },
             read_from: ReadFrom::from(config.read_from),
             ignore_older_secs: config.ignore_older_secs,
+            close_terminated_after: config.close_terminated_after_secs.map(Duration::from_secs),
             max_read_bytes: config.max_read_bytes,
+            max_read_bytes_per_file: config.max_read_bytes_per_file,
             oldest_first: config.oldest_first,
             max_line_bytes: config.max_line_bytes,
-            fingerprint_lines: config.fingerprint_lines,
+            fingerprint_strategy: config
+                .fingerprint
+                .strategy(config.fingerprint_lines, pod_dir_delimiter),
             glob_minimum_cooldown,
             use_apiserver_cache: config.use_apiserver_cache,
             ingestion_timestamp_field,
+            read_rotated_files: config.read_rotated_files,
+            emit_file_discovered_events: config.emit_file_discovered_events,
             delay_deletion,
+            max_delayed_deletions: config.max_delayed_deletions,
             include_file_metric_tag: config.internal_metrics.include_file_tag,
+            include_pod_metric_tags: config.internal_metrics.include_pod_tags,
+            max_tagged_pods: config.internal_metrics.max_tagged_pods,
         })
     }
 
@@ -624,8 +1058,16 @@ impl Source {
     ) -> crate::Result<()> {
         let Self {
             client,
+            kubelet_client,
+            kubelet_refresh_interval,
+            metadata_warmup_timeout,
             data_dir,
+            spill_buffer,
             auto_partial_merge,
+            format,
+            extract_stream,
+            use_log_timestamp,
+            max_merged_line_bytes,
             pod_fields_spec,
             namespace_fields_spec,
             node_field_spec,
@@ -634,18 +1076,37 @@ impl Source {
             namespace_label_selector,
             node_selector,
             self_node_name,
+            self_node_filter,
+            log_layout,
+            pod_dir_delimiter,
+            pod_logs_root,
+            container_logs_root,
+            extra_log_dirs,
+            strict_log_paths,
             exclude_paths,
+            include_container_names,
+            exclude_container_names,
+            namespace_filter,
+            include_pod_phases,
+            metadata_keys,
             read_from,
             ignore_older_secs,
+            close_terminated_after,
             max_read_bytes,
+            max_read_bytes_per_file,
             oldest_first,
             max_line_bytes,
-            fingerprint_lines,
+            fingerprint_strategy,
             glob_minimum_cooldown,
             use_apiserver_cache,
             ingestion_timestamp_field,
+            read_rotated_files,
+            emit_file_discovered_events,
             delay_deletion,
+            max_delayed_deletions,
             include_file_metric_tag,
+            include_pod_metric_tags,
+            max_tagged_pods,
         } = self;
 
         let mut reflectors = Vec::new();
@@ -658,76 +1119,144 @@ impl Source {
             watcher::ListSemantic::MostRecent
         };
 
-        let pod_watcher = watcher(
-            pods,
-            watcher::Config {
-                field_selector: Some(field_selector),
-                label_selector: Some(label_selector),
-                list_semantic: list_semantic.clone(),
-                ..Default::default()
-            },
-        )
-        .backoff(watcher::default_backoff());
+        let pod_watch_connectivity = WatchConnectivity::default();
+        let pod_watcher_config = watcher::Config {
+            field_selector: Some(field_selector),
+            label_selector: Some(label_selector),
+            list_semantic: list_semantic.clone(),
+            ..Default::default()
+        };
+        let pod_watcher = pod_watch_connectivity.track(reconnecting("pod", move || {
+            watcher(pods.clone(), pod_watcher_config.clone())
+        }));
         let pod_store_w = reflector::store::Writer::default();
         let pod_state = pod_store_w.as_reader();
         let pod_cacher = MetaCache::new();
+        let pod_delayed_deletions = DelayedDeletions::default();
+        let pod_initial_list = InitialList::default();
 
         reflectors.push(tokio::spawn(custom_reflector(
             pod_store_w,
             pod_cacher,
             pod_watcher,
             delay_deletion,
+            max_delayed_deletions,
+            pod_delayed_deletions.clone(),
+            pod_initial_list.clone(),
         )));
 
+        let kubelet_fallback = kubelet_client.map(|kubelet_client| {
+            let kubelet_pod_store_w = reflector::store::Writer::default();
+            let kubelet_pod_state = kubelet_pod_store_w.as_reader();
+
+            reflectors.push(tokio::spawn(kubelet_metadata::run_kubelet_poller(
+                kubelet_client,
+                kubelet_pod_store_w,
+                pod_watch_connectivity.clone(),
+                kubelet_refresh_interval,
+            )));
+
+            KubeletPodsFallback::new(kubelet_pod_state, pod_watch_connectivity)
+        });
+
         // -----------------------------------------------------------------
 
         let namespaces = Api::<Namespace>::all(client.clone());
-        let ns_watcher = watcher(
-            namespaces,
-            watcher::Config {
-                label_selector: Some(namespace_label_selector),
-                list_semantic: list_semantic.clone(),
-                ..Default::default()
-            },
-        )
-        .backoff(watcher::default_backoff());
+        let ns_watcher_config = watcher::Config {
+            label_selector: Some(namespace_label_selector),
+            list_semantic: list_semantic.clone(),
+            ..Default::default()
+        };
+        let ns_watcher = reconnecting("namespace", move || {
+            watcher(namespaces.clone(), ns_watcher_config.clone())
+        });
         let ns_store_w = reflector::store::Writer::default();
         let ns_state = ns_store_w.as_reader();
         let ns_cacher = MetaCache::new();
+        let ns_initial_list = InitialList::default();
 
         reflectors.push(tokio::spawn(custom_reflector(
             ns_store_w,
             ns_cacher,
             ns_watcher,
             delay_deletion,
+            max_delayed_deletions,
+            DelayedDeletions::default(),
+            ns_initial_list.clone(),
         )));
 
         // -----------------------------------------------------------------
 
         let nodes = Api::<Node>::all(client);
-        let node_watcher = watcher(
-            nodes,
-            watcher::Config {
-                field_selector: Some(node_selector),
-                list_semantic,
-                ..Default::default()
-            },
-        )
-        .backoff(watcher::default_backoff());
+        let node_watcher_config = watcher::Config {
+            field_selector: Some(node_selector),
+            list_semantic,
+            ..Default::default()
+        };
+        let node_watcher = reconnecting("node", move || {
+            watcher(nodes.clone(), node_watcher_config.clone())
+        });
         let node_store_w = reflector::store::Writer::default();
         let node_state = node_store_w.as_reader();
         let node_cacher = MetaCache::new();
+        let node_initial_list = InitialList::default();
 
         reflectors.push(tokio::spawn(custom_reflector(
             node_store_w,
             node_cacher,
             node_watcher,
             delay_deletion,
+            max_delayed_deletions,
+            DelayedDeletions::default(),
+            node_initial_list.clone(),
         )));
 
+        let active_pods = ActivePods::default();
         let paths_provider =
-            K8sPathsProvider::new(pod_state.clone(), ns_state.clone(), exclude_paths);
-        let annotator = PodMetadataAnnotator::new(pod_state, pod_fields_spec, log_namespace);
+            K8sPathsProvider::new(pod_state.clone(), ns_state.clone(), exclude_paths)
+                .with_log_layout(log_layout, pod_dir_delimiter)
+                .with_logs_roots(pod_logs_root, container_logs_root)
+                .with_extra_log_dirs(extra_log_dirs)
+                .with_strict_log_paths(strict_log_paths)
+                .with_container_name_filter(NameFilter::new(
+                    include_container_names,
+                    exclude_container_names,
+                ))
+                .with_namespace_filter(namespace_filter.clone())
+                .with_active_pods(active_pods.clone())
+                .with_close_terminated_after(close_terminated_after)
+                .with_self_node_filter(self_node_filter)
+                .with_rotations(read_rotated_files)
+                .with_pod_phases(include_pod_phases);
+        // The discovered paths end once the paths provider is dropped, or right away if the
+        // events aren't enabled.
+        let (file_discovery, discovered_paths) = FileDiscovery::new();
+        let paths_provider = if emit_file_discovered_events {
+            paths_provider.with_file_discovery(file_discovery)
+        } else {
+            paths_provider
+        };
+        let file_discovered_events = FileDiscoveredEvents::new(
+            log_layout,
+            pod_dir_delimiter,
+            pod_fields_spec.clone(),
+            ingestion_timestamp_field.clone(),
+            log_namespace,
+        );
+        let discovered_events = discovered_paths.filter_map(move |path| {
+            futures::future::ready(file_discovered_events.create_event(&path))
+        });
+        let mut annotator = PodMetadataAnnotator::new(
+            pod_state,
+            pod_delayed_deletions,
+            kubelet_fallback,
+            log_layout,
+            pod_fields_spec,
+            metadata_keys,
+            log_namespace,
+        )
+        .with_pod_dir_delimiter(pod_dir_delimiter)
+        .with_strict_log_paths(strict_log_paths);
         let ns_annotator =
             NamespaceMetadataAnnotator::new(ns_state, namespace_fields_spec, log_namespace);
         let node_annotator = NodeMetadataAnnotator::new(node_state, node_field_spec, log_namespace);
@@ -737,6 +1266,10 @@ impl Source {
         // TODO: maybe more of the parameters have to be configurable.
 
         let checkpointer = Checkpointer::new(&data_dir);
+        let spill_buffer = match spill_buffer {
+            Some(config) => Some(config.build(&data_dir).await?),
+            None => None,
+        };
         let file_server = FileServer {
             // Use our special paths provider.
             paths_provider,
@@ -745,6 +1278,10 @@ impl Source {
             // This allows distributing the reads more or less evenly across
             // the files.
             max_read_bytes,
+            // Schedule the reads fairly across the files, if configured.
+            max_read_bytes_per_file,
+            // The reads are not rate limited per file.
+            max_read_bytes_per_second: None,
             // We want to use checkpointing mechanism, and resume from where we
             // left off.
             ignore_checkpoints: false,
@@ -756,11 +1293,16 @@ impl Source {
             // be other, more sound ways for users considering the use of this
             // option to solve their use case, so take consideration.
             ignore_before,
-            // The maximum number of bytes a line can contain before being discarded. This
+            // The maximum number of bytes a line can contain before being truncated. This
             // protects against malformed lines or tailing incorrect files.
             max_line_bytes,
+            // The longer lines are truncated instead of discarded, the rest of the line is
+            // skipped.
+            truncate_long_lines: true,
             // Delimiter bytes that is used to read the file line-by-line
             line_delimiter: Bytes::from("\n"),
+            // The kubelet compresses the rotated log files with gzip.
+            compression: Compression::Auto,
             // The directory where to keep the checkpoints.
             data_dir,
             // This value specifies not exactly the globbing, but interval
@@ -770,18 +1312,17 @@ impl Source {
             // environment, so we pick the a specially crafted fingerprinter
             // for the log files.
             fingerprinter: Fingerprinter {
-                strategy: FingerprintStrategy::FirstLinesChecksum {
-                    // Max line length to expect during fingerprinting, see the
-                    // explanation above.
-                    ignored_header_bytes: 0,
-                    lines: fingerprint_lines,
-                },
+                strategy: fingerprint_strategy,
+                // Max line length to expect during fingerprinting, see the
+                // explanation above.
                 max_line_length: max_line_bytes,
                 ignore_not_found: true,
+                compression: Compression::Auto,
             },
             oldest_first,
             // We do not remove the log files, `kubelet` is responsible for it.
             remove_after: None,
+            archive_to_dir: None,
             // The standard emitter.
             emitter: FileSourceInternalEventsEmitter {
                 include_file_metric_tag,
@@ -802,12 +1343,37 @@ impl Source {
             let mut event = create_event(
                 line.text,
                 &line.filename,
+                line.truncated,
                 ingestion_timestamp_field.as_ref(),
                 log_namespace,
             );
 
             let file_info = annotator.annotate(&mut event, &line.filename);
 
+            // The pods are filtered by namespace at discovery already, this
+            // guards against the files attributed to another pod there.
+            if file_info
+                .as_ref()
+                .map_or(false, |info| !namespace_filter.matches(info.pod_namespace))
+            {
+                checkpoints.update(line.file_id, line.end_offset);
+                return None;
+            }
+
+            let include_pod_tags = include_pod_metric_tags && active_pods.get() <= max_tagged_pods;
+            emit!(KubernetesLogsLineRead {
+                byte_size,
+                pod_tags: file_info.as_ref().filter(|_| include_pod_tags).map(|info| {
+                    KubernetesLogsPodTags {
+                        pod_namespace: info.pod_namespace,
+                        pod_name: info.pod_name,
+                        container_name: info.container_name,
+                    }
+                }),
+            });
+
+            node_annotator.annotate_node_name(&mut event, self_node_name.as_str());
+
             emit!(KubernetesLogsEventsReceived {
                 file: &line.filename,
                 byte_size: event.estimated_json_encoded_size_of(),
@@ -838,10 +1404,11 @@ impl Source {
             }
 
             checkpoints.update(line.file_id, line.end_offset);
-            event
+            Some(event)
         });
+        let events = events.filter_map(futures::future::ready);
 
-        let mut parser = Parser::new(log_namespace);
+        let mut parser = Parser::new(log_namespace, format, extract_stream, use_log_timestamp);
         let events = events.flat_map(move |event| {
             let mut buf = OutputBuffer::with_capacity(1);
             parser.transform(&mut buf, event);
@@ -851,12 +1418,26 @@ impl Source {
         let (events_count, _) = events.size_hint();
 
         let mut stream = if auto_partial_merge {
-            merge_partial_events(events, log_namespace).left_stream()
+            merge_partial_events(events, log_namespace, max_merged_line_bytes).left_stream()
         } else {
             events.right_stream()
         };
-
-        let event_processing_loop = out.send_event_stream(&mut stream);
+        // The discovery events don't go through the parser, as they aren't lines of the files.
+        let stream = futures::stream::select(stream, discovered_events);
+        // The lines are only annotated as they are pulled from the stream, so holding the stream
+        // back has them annotated once the metadata is loaded.
+        let mut stream = Box::pin(after_warmup(
+            stream,
+            vec![pod_initial_list, ns_initial_list, node_initial_list],
+            metadata_warmup_timeout,
+        ));
+
+        let event_processing_loop = async move {
+            match spill_buffer {
+                Some(buffer) => buffer.send_event_stream(&mut stream, &mut out).await,
+                None => out.send_event_stream(&mut stream).await,
+            }
+        };
 
         let mut lifecycle = Lifecycle::new();
         {
@@ -908,6 +1489,7 @@ impl Source {
 fn create_event(
     line: Bytes,
     file: &str,
+    truncated: bool,
     ingestion_timestamp_field: Option<&OwnedTargetPath>,
     log_namespace: LogNamespace,
 ) -> Event {
@@ -922,6 +1504,16 @@ fn create_event(
         file,
     );
 
+    if truncated {
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            &mut log,
+            Some(LegacyKey::Overwrite(path!("kubernetes", "truncated"))),
+            path!("truncated"),
+            true,
+        );
+    }
+
     log_namespace.insert_vector_metadata(
         &mut log,
         log_schema().source_type_key(),
@@ -946,12 +1538,42 @@ fn create_event(
     log.into()
 }
 
+/// Resolves the name of the Node Vector runs on, either from the
+/// `self_node_name` option or from the `VECTOR_SELF_NODE_NAME` env var.
+fn resolve_self_node_name(config: &Config) -> crate::Result<String> {
+    if config.self_node_name.is_empty()
+        || config.self_node_name == default_self_node_name_env_template()
+    {
+        std::env::var(SELF_NODE_NAME_ENV_KEY).map_err(|_| {
+            format!(
+                "self_node_name config value or {} env var is not set",
+                SELF_NODE_NAME_ENV_KEY
+            )
+            .into()
+        })
+    } else {
+        Ok(config.self_node_name.clone())
+    }
+}
+
 /// This function returns the default value for `self_node_name` variable
 /// as it should be at the generated config file.
 fn default_self_node_name_env_template() -> String {
     format!("${{{}}}", SELF_NODE_NAME_ENV_KEY.to_owned())
 }
 
+fn default_pod_logs_root() -> PathBuf {
+    PathBuf::from(path_helpers::K8S_LOGS_DIR)
+}
+
+const fn default_pod_dir_delimiter() -> char {
+    path_helpers::DEFAULT_POD_DIR_DELIMITER
+}
+
+fn default_container_logs_root() -> PathBuf {
+    PathBuf::from(path_helpers::K8S_CONTAINER_LOGS_DIR)
+}
+
 fn default_path_exclusion() -> Vec<PathBuf> {
     vec![PathBuf::from("**/*.gz"), PathBuf::from("**/*.tmp")]
 }
@@ -991,6 +1613,57 @@ const fn default_delay_deletion_ms() -> Duration {
     Duration::from_millis(60_000)
 }
 
+const fn default_max_delayed_deletions() -> usize {
+    1_000
+}
+
+// This function builds the config of the client to the kube-apiserver.
+//
+// If the user passed a custom Kubeconfig use it, otherwise we attempt to load
+// the local kubeconfig, followed by the in-cluster environment variables.
+// Failing to load the custom Kubeconfig is not retried, so the error names the
+// file to make it clear that the configuration is at fault.
+async fn prepare_client_config(config: &Config) -> crate::Result<ClientConfig> {
+    match &config.kube_config_file {
+        Some(kc) => {
+            let kubeconfig = config::Kubeconfig::read_from(kc)
+                .map_err(|error| format!("Failed to read kubeconfig file {:?}: {}", kc, error))?;
+            let options = KubeConfigOptions {
+                context: config.kube_config_context.clone(),
+                ..KubeConfigOptions::default()
+            };
+            ClientConfig::from_custom_kubeconfig(kubeconfig, &options)
+                .await
+                .map_err(|error| {
+                    format!("Failed to load kubeconfig file {:?}: {}", kc, error).into()
+                })
+        }
+        None => Ok(ClientConfig::infer().await?),
+    }
+}
+
+// This function validates a root directory to discover the log files at.
+// The log file paths are globbed below it, so it has to be absolute.
+fn prepare_logs_root(option: &str, logs_root: &Path) -> crate::Result<PathBuf> {
+    if !logs_root.is_absolute() {
+        return Err(format!("{} must be an absolute path, got {:?}", option, logs_root).into());
+    }
+
+    Ok(logs_root.to_path_buf())
+}
+
+fn prepare_pod_dir_delimiter(delimiter: char) -> crate::Result<char> {
+    if matches!(delimiter, '/' | '\\') {
+        return Err(format!(
+            "pod_dir_delimiter must not be a path separator, got {:?}",
+            delimiter
+        )
+        .into());
+    }
+
+    Ok(delimiter)
+}
+
 // This function constructs the patterns we exclude from file watching, created
 // from the defaults or user provided configuration.
 fn prepare_exclude_paths(config: &Config) -> crate::Result<Vec<glob::Pattern>> {
@@ -1016,6 +1689,23 @@ fn prepare_exclude_paths(config: &Config) -> crate::Result<Vec<glob::Pattern>> {
     Ok(exclude_paths)
 }
 
+// This function constructs the patterns to filter the containers and the
+// namespaces with by name.
+fn prepare_name_patterns(patterns: &[String]) -> crate::Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| Ok(glob::Pattern::new(pattern)?))
+        .collect()
+}
+
+// This function constructs the filter of the pods by namespace.
+fn prepare_namespace_filter(config: &Config) -> crate::Result<NameFilter> {
+    Ok(NameFilter::new(
+        prepare_name_patterns(&config.include_namespaces)?,
+        prepare_name_patterns(&config.exclude_namespaces)?,
+    ))
+}
+
 // This function constructs the effective field selector to use, based on
 // the specified configuration.
 fn prepare_field_selector(config: &Config, self_node_name: &str) -> crate::Result<String> {
@@ -1069,6 +1759,154 @@ mod tests {
         crate::test_util::test_generate_config::<Config>();
     }
 
+    #[test]
+    fn pod_logs_root() {
+        let config = toml::from_str::<Config>("").unwrap();
+        assert_eq!(
+            config.pod_logs_root,
+            std::path::PathBuf::from(super::path_helpers::K8S_LOGS_DIR)
+        );
+
+        let config = toml::from_str::<Config>(
+            r#"
+            pod_logs_root = "/data/kubelet/log/pods"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.pod_logs_root,
+            std::path::PathBuf::from("/data/kubelet/log/pods")
+        );
+    }
+
+    #[tokio::test]
+    async fn prepare_client_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let kube_config_file = dir.path().join("config");
+        std::fs::write(
+            &kube_config_file,
+            r#"
+            apiVersion: v1
+            kind: Config
+            clusters:
+              - name: dev
+                cluster:
+                  server: https://dev.example.com:6443
+              - name: prod
+                cluster:
+                  server: https://prod.example.com:6443
+            users:
+              - name: dev
+                user:
+                  token: dev-token
+              - name: prod
+                user:
+                  token: prod-token
+            contexts:
+              - name: dev
+                context:
+                  cluster: dev
+                  user: dev
+              - name: prod
+                context:
+                  cluster: prod
+                  user: prod
+            current-context: dev
+            "#,
+        )
+        .unwrap();
+
+        let config = Config {
+            kube_config_file: Some(kube_config_file.clone()),
+            ..Default::default()
+        };
+        let client_config = super::prepare_client_config(&config).await.unwrap();
+        assert_eq!(client_config.cluster_url.host(), Some("dev.example.com"));
+
+        let config = Config {
+            kube_config_file: Some(kube_config_file.clone()),
+            kube_config_context: Some("prod".to_owned()),
+            ..Default::default()
+        };
+        let client_config = super::prepare_client_config(&config).await.unwrap();
+        assert_eq!(client_config.cluster_url.host(), Some("prod.example.com"));
+
+        let config = Config {
+            kube_config_file: Some(kube_config_file),
+            kube_config_context: Some("staging".to_owned()),
+            ..Default::default()
+        };
+        assert!(super::prepare_client_config(&config).await.is_err());
+
+        let config = Config {
+            kube_config_file: Some(dir.path().join("missing")),
+            ..Default::default()
+        };
+        let error = super::prepare_client_config(&config).await.unwrap_err();
+        assert!(error.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn log_layout() {
+        let config = toml::from_str::<Config>("").unwrap();
+        assert_eq!(config.log_layout, super::path_helpers::LogLayout::Auto);
+        assert_eq!(
+            config.container_logs_root,
+            std::path::PathBuf::from(super::path_helpers::K8S_CONTAINER_LOGS_DIR)
+        );
+
+        let config = toml::from_str::<Config>(
+            r#"
+            log_layout = "containers"
+            container_logs_root = "/data/kubelet/log/containers"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.log_layout,
+            super::path_helpers::LogLayout::Containers
+        );
+        assert_eq!(
+            config.container_logs_root,
+            std::path::PathBuf::from("/data/kubelet/log/containers")
+        );
+    }
+
+    #[test]
+    fn prepare_logs_root() {
+        let config = Config::default();
+        assert_eq!(
+            super::prepare_logs_root("pod_logs_root", &config.pod_logs_root).unwrap(),
+            std::path::PathBuf::from(super::path_helpers::K8S_LOGS_DIR)
+        );
+        assert_eq!(
+            super::prepare_logs_root("container_logs_root", &config.container_logs_root).unwrap(),
+            std::path::PathBuf::from(super::path_helpers::K8S_CONTAINER_LOGS_DIR)
+        );
+
+        assert!(
+            super::prepare_logs_root("pod_logs_root", std::path::Path::new("var/log/pods"))
+                .is_err()
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn prepare_logs_root_windows() {
+        assert_eq!(
+            super::prepare_logs_root("pod_logs_root", std::path::Path::new("D:\\var\\log\\pods"))
+                .unwrap(),
+            std::path::PathBuf::from("D:\\var\\log\\pods")
+        );
+
+        // Without a drive the path is relative to the current drive.
+        assert!(super::prepare_logs_root(
+            "pod_logs_root",
+            std::path::Path::new("\\var\\log\\pods")
+        )
+        .is_err());
+    }
+
     #[test]
     fn prepare_exclude_paths() {
         let cases = vec![
@@ -1111,6 +1949,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn prepare_name_patterns() {
+        let config = toml::from_str::<Config>(
+            r#"
+            include_container_names = ["app-*"]
+            exclude_container_names = ["istio-proxy", "linkerd-*"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::prepare_name_patterns(&config.include_container_names).unwrap(),
+            vec![glob::Pattern::new("app-*").unwrap()]
+        );
+        assert_eq!(
+            super::prepare_name_patterns(&config.exclude_container_names).unwrap(),
+            vec![
+                glob::Pattern::new("istio-proxy").unwrap(),
+                glob::Pattern::new("linkerd-*").unwrap(),
+            ]
+        );
+        assert!(super::prepare_name_patterns(&["[".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn prepare_namespace_filter() {
+        let filter = super::prepare_namespace_filter(&Config::default()).unwrap();
+        assert!(filter.matches("kube-system"));
+
+        let config = toml::from_str::<Config>(
+            r#"
+            include_namespaces = ["team-*"]
+            exclude_namespaces = ["team-b"]
+            "#,
+        )
+        .unwrap();
+        let filter = super::prepare_namespace_filter(&config).unwrap();
+        assert!(filter.matches("team-a"));
+        assert!(!filter.matches("team-b"));
+        assert!(!filter.matches("kube-system"));
+
+        let config = Config {
+            include_namespaces: vec!["[".to_owned()],
+            ..Default::default()
+        };
+        assert!(super::prepare_namespace_filter(&config).is_err());
+    }
+
     #[test]
     fn prepare_field_selector() {
         let cases = vec![
@@ -1147,6 +2033,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn self_node_name_from_env() {
+        use kube::runtime::reflector::store::Writer;
+        use vrl::event_path;
+
+        use super::node_metadata_annotator::NodeMetadataAnnotator;
+        use crate::event::{Event, LogEvent};
+
+        // This is the only test reading the env var, so it can be set here.
+        std::env::set_var(super::SELF_NODE_NAME_ENV_KEY, "sandbox0-node-name");
+        let node_name = super::resolve_self_node_name(&Config::default());
+        std::env::remove_var(super::SELF_NODE_NAME_ENV_KEY);
+        let node_name = node_name.unwrap();
+        assert_eq!(node_name, "sandbox0-node-name");
+
+        let annotator = NodeMetadataAnnotator::new(
+            Writer::default().as_reader(),
+            Default::default(),
+            LogNamespace::Legacy,
+        );
+        let mut event = Event::Log(LogEvent::default());
+        annotator.annotate_node_name(&mut event, &node_name);
+        assert_eq!(
+            event.as_log().get(event_path!("kubernetes", "node_name")),
+            Some(&"sandbox0-node-name".into())
+        );
+    }
+
     #[test]
     fn prepare_label_selector() {
         let cases = vec![
@@ -1198,6 +2112,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn create_event_truncated() {
+        let event =
+            super::create_event("foo".into(), "/file.log", true, None, LogNamespace::Legacy);
+        assert_eq!(
+            event.as_log().get("kubernetes.truncated"),
+            Some(&vrl::value!(true))
+        );
+
+        let event =
+            super::create_event("foo".into(), "/file.log", false, None, LogNamespace::Legacy);
+        assert_eq!(event.as_log().get("kubernetes.truncated"), None);
+
+        let event =
+            super::create_event("foo".into(), "/file.log", true, None, LogNamespace::Vector);
+        assert_eq!(
+            event.as_log().get("%kubernetes_logs.truncated"),
+            Some(&vrl::value!(true))
+        );
+    }
+
     #[test]
     fn test_output_schema_definition_vector_namespace() {
         let definitions = toml::from_str::<Config>("")
@@ -1215,6 +2150,11 @@ mod tests {
                         Kind::bytes(),
                         None
                     )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "truncated"),
+                        Kind::boolean().or_undefined(),
+                        None
+                    )
                     .with_metadata_field(
                         &owned_value_path!("kubernetes_logs", "container_id"),
                         Kind::bytes().or_undefined(),
@@ -1225,11 +2165,26 @@ mod tests {
                         Kind::bytes().or_undefined(),
                         None
                     )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "container_kind"),
+                        Kind::bytes().or_undefined(),
+                        None
+                    )
                     .with_metadata_field(
                         &owned_value_path!("kubernetes_logs", "container_name"),
                         Kind::bytes().or_undefined(),
                         None
                     )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "container_restart_count"),
+                        Kind::integer().or_undefined(),
+                        None
+                    )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "pod_dir_extra_segments"),
+                        Kind::array(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                        None
+                    )
                     .with_metadata_field(
                         &owned_value_path!("kubernetes_logs", "namespace_labels"),
                         Kind::object(Collection::empty().with_unknown(Kind::bytes()))
@@ -1242,6 +2197,26 @@ mod tests {
                             .or_undefined(),
                         None
                     )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "node_allocatable_cpu"),
+                        Kind::bytes().or_undefined(),
+                        None
+                    )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "node_allocatable_memory"),
+                        Kind::bytes().or_undefined(),
+                        None
+                    )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "node_kubelet_version"),
+                        Kind::bytes().or_undefined(),
+                        None
+                    )
+                    .with_metadata_field(
+                        &owned_value_path!("kubernetes_logs", "node_name"),
+                        Kind::bytes().or_undefined(),
+                        None
+                    )
                     .with_metadata_field(
                         &owned_value_path!("kubernetes_logs", "pod_annotations"),
                         Kind::object(Collection::empty().with_unknown(Kind::bytes()))
@@ -1335,6 +2310,11 @@ mod tests {
                     Kind::bytes(),
                     Some("message")
                 )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "truncated"),
+                    Kind::boolean().or_undefined(),
+                    None
+                )
                 .with_event_field(
                     &owned_value_path!("kubernetes", "container_id"),
                     Kind::bytes().or_undefined(),
@@ -1345,11 +2325,26 @@ mod tests {
                     Kind::bytes().or_undefined(),
                     None
                 )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "container_kind"),
+                    Kind::bytes().or_undefined(),
+                    None
+                )
                 .with_event_field(
                     &owned_value_path!("kubernetes", "container_name"),
                     Kind::bytes().or_undefined(),
                     None
                 )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "container_restart_count"),
+                    Kind::integer().or_undefined(),
+                    None
+                )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "pod_dir_extra_segments"),
+                    Kind::array(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                    None
+                )
                 .with_event_field(
                     &owned_value_path!("kubernetes", "namespace_labels"),
                     Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
@@ -1360,6 +2355,26 @@ mod tests {
                     Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
                     None
                 )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "node_allocatable_cpu"),
+                    Kind::bytes().or_undefined(),
+                    None
+                )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "node_allocatable_memory"),
+                    Kind::bytes().or_undefined(),
+                    None
+                )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "node_kubelet_version"),
+                    Kind::bytes().or_undefined(),
+                    None
+                )
+                .with_event_field(
+                    &owned_value_path!("kubernetes", "node_name"),
+                    Kind::bytes().or_undefined(),
+                    None
+                )
                 .with_event_field(
                     &owned_value_path!("kubernetes", "pod_annotations"),
                     Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
@@ -1411,9 +2426,10 @@ mod tests {
                     Kind::timestamp(),
                     Some("timestamp")
                 )
+                .with_event_field(&owned_value_path!("source_type"), Kind::bytes(), None)
                 .with_event_field(
-                    &owned_value_path!("source_type"),
-                    Kind::bytes(),
+                    &owned_value_path!("kubernetes", "stream"),
+                    Kind::bytes().or_undefined(),
                     None
                 )
             )
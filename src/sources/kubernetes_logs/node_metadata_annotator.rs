@@ -3,7 +3,10 @@
 #![deny(missing_docs)]
 
 use crate::event::{Event, LogEvent};
-use k8s_openapi::{api::core::v1::Node, apimachinery::pkg::apis::meta::v1::ObjectMeta};
+use k8s_openapi::{
+    api::core::v1::{Node, NodeStatus},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
 use kube::runtime::reflector::{store::Store, ObjectRef};
 use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::configurable::configurable_component;
@@ -24,6 +27,41 @@ pub struct FieldsSpec {
     #[configurable(metadata(docs::examples = "k8s.node_labels"))]
     #[configurable(metadata(docs::examples = ""))]
     pub node_labels: OptionalTargetPath,
+
+    /// Event field for the Node's allocatable CPU.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.node_allocatable_cpu"))]
+    #[configurable(metadata(docs::examples = "k8s.node_allocatable_cpu"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub node_allocatable_cpu: OptionalTargetPath,
+
+    /// Event field for the Node's allocatable memory.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.node_allocatable_memory"))]
+    #[configurable(metadata(docs::examples = "k8s.node_allocatable_memory"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub node_allocatable_memory: OptionalTargetPath,
+
+    /// Event field for the version of the kubelet running on the Node.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.node_kubelet_version"))]
+    #[configurable(metadata(docs::examples = "k8s.node_kubelet_version"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub node_kubelet_version: OptionalTargetPath,
+
+    /// Event field for the name of the Node the event was collected on.
+    ///
+    /// The name is the one Vector runs on, as configured by `self_node_name`, so it is added to
+    /// every event, even if the Node metadata isn't available.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.node_name"))]
+    #[configurable(metadata(docs::examples = "k8s.node_name"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub node_name: OptionalTargetPath,
 }
 
 impl Default for FieldsSpec {
@@ -31,6 +69,22 @@ impl Default for FieldsSpec {
         Self {
             node_labels: OwnedTargetPath::event(owned_value_path!("kubernetes", "node_labels"))
                 .into(),
+            node_allocatable_cpu: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "node_allocatable_cpu"
+            ))
+            .into(),
+            node_allocatable_memory: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "node_allocatable_memory"
+            ))
+            .into(),
+            node_kubelet_version: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "node_kubelet_version"
+            ))
+            .into(),
+            node_name: OwnedTargetPath::event(owned_value_path!("kubernetes", "node_name")).into(),
         }
     }
 }
@@ -66,8 +120,43 @@ impl NodeMetadataAnnotator {
         let node: &Node = resource.as_ref();
 
         annotate_from_metadata(log, &self.fields_spec, &node.metadata, self.log_namespace);
+        if let Some(ref node_status) = node.status {
+            annotate_from_node_status(log, &self.fields_spec, node_status, self.log_namespace);
+        }
         Some(())
     }
+
+    /// Annotates an event with the name of the Node it was collected on.
+    pub fn annotate_node_name(&self, event: &mut Event, node: &str) {
+        annotate_node_name(
+            event.as_mut_log(),
+            &self.fields_spec,
+            node,
+            self.log_namespace,
+        );
+    }
+}
+
+fn annotate_node_name(
+    log: &mut LogEvent,
+    fields_spec: &FieldsSpec,
+    node_name: &str,
+    log_namespace: LogNamespace,
+) {
+    let legacy_key = fields_spec
+        .node_name
+        .path
+        .as_ref()
+        .map(|k| &k.path)
+        .map(LegacyKey::Overwrite);
+
+    log_namespace.insert_source_metadata(
+        Config::NAME,
+        log,
+        legacy_key,
+        path!("node_name"),
+        node_name.to_owned(),
+    );
 }
 
 fn annotate_from_metadata(
@@ -93,8 +182,66 @@ fn annotate_from_metadata(
     }
 }
 
+fn annotate_from_node_status(
+    log: &mut LogEvent,
+    fields_spec: &FieldsSpec,
+    node_status: &NodeStatus,
+    log_namespace: LogNamespace,
+) {
+    if let Some(allocatable) = &node_status.allocatable {
+        for (legacy_key, metadata_key, resource) in [
+            (
+                &fields_spec.node_allocatable_cpu,
+                path!("node_allocatable_cpu"),
+                "cpu",
+            ),
+            (
+                &fields_spec.node_allocatable_memory,
+                path!("node_allocatable_memory"),
+                "memory",
+            ),
+        ]
+        .iter()
+        {
+            if let Some(quantity) = allocatable.get(*resource) {
+                let legacy_key = legacy_key
+                    .path
+                    .as_ref()
+                    .map(|k| &k.path)
+                    .map(LegacyKey::Overwrite);
+
+                log_namespace.insert_source_metadata(
+                    Config::NAME,
+                    log,
+                    legacy_key,
+                    *metadata_key,
+                    quantity.0.to_owned(),
+                );
+            }
+        }
+    }
+
+    if let Some(node_info) = &node_status.node_info {
+        let legacy_key = fields_spec
+            .node_kubelet_version
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            legacy_key,
+            path!("node_kubelet_version"),
+            node_info.kubelet_version.to_owned(),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use k8s_openapi::{api::core::v1::NodeSystemInfo, apimachinery::pkg::api::resource::Quantity};
     use similar_asserts::assert_eq;
     use vector_lib::lookup::{event_path, lookup_v2::parse_target_path, metadata_path};
 
@@ -170,6 +317,7 @@ mod tests {
             (
                 FieldsSpec {
                     node_labels: parse_target_path("node_labels").unwrap().into(),
+                    ..Default::default()
                 },
                 ObjectMeta {
                     name: Some("sandbox0-name".to_owned()),
@@ -200,4 +348,135 @@ mod tests {
             assert_eq!(log, expected);
         }
     }
+
+    #[test]
+    fn test_annotate_node_name() {
+        let cases = vec![
+            (FieldsSpec::default(), LogNamespace::Legacy, {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "node_name"), "sandbox0-node-name");
+                log
+            }),
+            (
+                FieldsSpec {
+                    node_name: parse_target_path("node").unwrap().into(),
+                    ..Default::default()
+                },
+                LogNamespace::Legacy,
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(event_path!("node"), "sandbox0-node-name");
+                    log
+                },
+            ),
+            (
+                FieldsSpec {
+                    node_name: OptionalTargetPath::none(),
+                    ..Default::default()
+                },
+                LogNamespace::Legacy,
+                LogEvent::default(),
+            ),
+            (FieldsSpec::default(), LogNamespace::Vector, {
+                let mut log = LogEvent::default();
+                log.insert(
+                    metadata_path!("kubernetes_logs", "node_name"),
+                    "sandbox0-node-name",
+                );
+                log
+            }),
+        ];
+
+        for (fields_spec, log_namespace, expected) in cases.into_iter() {
+            let mut log = LogEvent::default();
+            annotate_node_name(&mut log, &fields_spec, "sandbox0-node-name", log_namespace);
+            assert_eq!(log, expected);
+        }
+    }
+
+    #[test]
+    fn test_annotate_from_node_status() {
+        let node_status = NodeStatus {
+            allocatable: Some(
+                vec![
+                    ("cpu".to_owned(), Quantity("3920m".to_owned())),
+                    ("memory".to_owned(), Quantity("15214Mi".to_owned())),
+                    ("pods".to_owned(), Quantity("110".to_owned())),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            node_info: Some(NodeSystemInfo {
+                kubelet_version: "v1.26.3".to_owned(),
+                ..NodeSystemInfo::default()
+            }),
+            ..NodeStatus::default()
+        };
+
+        let cases = vec![
+            (
+                FieldsSpec::default(),
+                NodeStatus::default(),
+                LogEvent::default(),
+                LogNamespace::Legacy,
+            ),
+            (
+                FieldsSpec::default(),
+                node_status.clone(),
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(event_path!("kubernetes", "node_allocatable_cpu"), "3920m");
+                    log.insert(
+                        event_path!("kubernetes", "node_allocatable_memory"),
+                        "15214Mi",
+                    );
+                    log.insert(event_path!("kubernetes", "node_kubelet_version"), "v1.26.3");
+                    log
+                },
+                LogNamespace::Legacy,
+            ),
+            (
+                FieldsSpec {
+                    node_allocatable_memory: OptionalTargetPath::none(),
+                    node_kubelet_version: parse_target_path("kubelet_version").unwrap().into(),
+                    ..Default::default()
+                },
+                node_status.clone(),
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(event_path!("kubernetes", "node_allocatable_cpu"), "3920m");
+                    log.insert(event_path!("kubelet_version"), "v1.26.3");
+                    log
+                },
+                LogNamespace::Legacy,
+            ),
+            (
+                FieldsSpec::default(),
+                node_status,
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(
+                        metadata_path!("kubernetes_logs", "node_allocatable_cpu"),
+                        "3920m",
+                    );
+                    log.insert(
+                        metadata_path!("kubernetes_logs", "node_allocatable_memory"),
+                        "15214Mi",
+                    );
+                    log.insert(
+                        metadata_path!("kubernetes_logs", "node_kubelet_version"),
+                        "v1.26.3",
+                    );
+                    log
+                },
+                LogNamespace::Vector,
+            ),
+        ];
+
+        for (fields_spec, node_status, expected, log_namespace) in cases.into_iter() {
+            let mut log = LogEvent::default();
+            annotate_from_node_status(&mut log, &fields_spec, &node_status, log_namespace);
+            assert_eq!(log, expected);
+        }
+    }
 }
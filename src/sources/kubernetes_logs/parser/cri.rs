@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, ParseError, Utc};
 use derivative::Derivative;
 use vector_lib::config::{log_schema, LegacyKey, LogNamespace};
 use vector_lib::conversion;
@@ -26,19 +26,64 @@ const TIMESTAMP_KEY: &str = "timestamp";
 ///
 /// Normalizes parsed data for consistency.
 ///
+/// The timestamp at the beginning of the lines is used as the timestamp of the events, unless
+/// `use_log_timestamp` is disabled, in which case the time the line is parsed at is used. The
+/// latter is also used when the timestamp of a line can't be parsed.
+///
 /// [cri_log_format]: https://github.com/kubernetes/community/blob/ee2abbf9dbfa4523b414f99a04ddc97bd38c74b2/contributors/design-proposals/node/kubelet-cri-logging.md
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
 pub(super) struct Cri {
     log_namespace: LogNamespace,
+    use_log_timestamp: bool,
 }
 
 impl Cri {
-    pub const fn new(log_namespace: LogNamespace) -> Self {
-        Self { log_namespace }
+    pub const fn new(log_namespace: LogNamespace, use_log_timestamp: bool) -> Self {
+        Self {
+            log_namespace,
+            use_log_timestamp,
+        }
+    }
+
+    /// The timestamp of the event, either parsed out of the line or the ingestion time.
+    fn timestamp(&self, timestamp: &[u8]) -> DateTime<Utc> {
+        if !self.use_log_timestamp {
+            return Utc::now();
+        }
+
+        let ds = String::from_utf8_lossy(timestamp);
+        match parse_timestamp(&ds) {
+            Ok(timestamp) => timestamp,
+            Err(e) => {
+                emit!(ParserConversionError {
+                    name: TIMESTAMP_KEY,
+                    error: conversion::Error::TimestampParse {
+                        s: ds.to_string(),
+                        source: e,
+                    },
+                });
+                Utc::now()
+            }
+        }
     }
 }
 
+/// Parses the timestamp of a CRI line.
+///
+/// The container runtimes write the timestamps in the RFC 3339 format with nanosecond precision,
+/// usually in UTC. Timestamps with an offset are converted to UTC, and timestamps without one are
+/// assumed to be in UTC.
+fn parse_timestamp(ds: &str) -> Result<DateTime<Utc>, ParseError> {
+    DateTime::parse_from_str(ds, "%+")
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|error| {
+            NaiveDateTime::parse_from_str(ds, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|dt| dt.and_utc())
+                .map_err(|_| error)
+        })
+}
+
 impl FunctionTransform for Cri {
     fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
         let message_path = get_message_path(self.log_namespace);
@@ -85,30 +130,15 @@ impl FunctionTransform for Cri {
                     }
 
                     // TIMESTAMP_TAG
-                    let ds = String::from_utf8_lossy(parsed_log.timestamp);
-                    match DateTime::parse_from_str(&ds, "%+") {
-                        Ok(dt) =>
-                        // Insert the TIMESTAMP_TAG parsed out of the CRI log, this is the timestamp of
-                        // when the runtime processed this message.
-                        {
-                            self.log_namespace.insert_source_metadata(
-                                Config::NAME,
-                                log,
-                                log_schema().timestamp_key().map(LegacyKey::Overwrite),
-                                path!(TIMESTAMP_KEY),
-                                Value::Timestamp(dt.with_timezone(&Utc)),
-                            )
-                        }
-                        Err(e) => {
-                            emit!(ParserConversionError {
-                                name: TIMESTAMP_KEY,
-                                error: conversion::Error::TimestampParse {
-                                    s: ds.to_string(),
-                                    source: e,
-                                },
-                            });
-                        }
-                    }
+                    // Insert the TIMESTAMP_TAG parsed out of the CRI log, this is the timestamp of
+                    // when the runtime processed this message.
+                    self.log_namespace.insert_source_metadata(
+                        Config::NAME,
+                        log,
+                        log_schema().timestamp_key().map(LegacyKey::Overwrite),
+                        path!(TIMESTAMP_KEY),
+                        Value::Timestamp(self.timestamp(parsed_log.timestamp)),
+                    );
 
                     // STREAM_TAG
                     self.log_namespace.insert_source_metadata(
@@ -126,6 +156,11 @@ impl FunctionTransform for Cri {
     }
 }
 
+/// Whether the line is in the CRI log format.
+pub(super) fn is_cri_line(line: &[u8]) -> bool {
+    parse_log_line(line).is_some()
+}
+
 struct ParsedLog<'a> {
     timestamp: &'a [u8],
     stream: &'a [u8],
@@ -284,7 +319,7 @@ pub mod tests {
     fn test_parsing_valid_vector_namespace() {
         trace_init();
         test_util::test_parser(
-            || Cri::new(LogNamespace::Vector),
+            || Cri::new(LogNamespace::Vector, true),
             |bytes| Event::Log(LogEvent::from(value!(bytes))),
             valid_cases(LogNamespace::Vector),
         );
@@ -294,9 +329,69 @@ pub mod tests {
     fn test_parsing_valid_legacy_namespace() {
         trace_init();
         test_util::test_parser(
-            || Cri::new(LogNamespace::Legacy),
+            || Cri::new(LogNamespace::Legacy, true),
             |bytes| Event::Log(LogEvent::from(bytes)),
             valid_cases(LogNamespace::Legacy),
         );
     }
+
+    fn parse_timestamp_of(line: &str, use_log_timestamp: bool) -> Value {
+        let mut parser = Cri::new(LogNamespace::Legacy, use_log_timestamp);
+        let mut output = OutputBuffer::default();
+        parser.transform(&mut output, Event::Log(LogEvent::from(line)));
+        let event = output.into_events().next().unwrap();
+        event
+            .as_log()
+            .get(log_schema().timestamp_key_target_path().unwrap())
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_timestamp_nanoseconds() {
+        let cases = [
+            (
+                "2016-10-06T00:17:09.669794202Z",
+                "2016-10-06T00:17:09.669794202Z",
+            ),
+            (
+                "2016-10-06T02:17:09.000000001+02:00",
+                "2016-10-06T00:17:09.000000001Z",
+            ),
+            // Timestamps without an offset are assumed to be in UTC.
+            (
+                "2016-10-06T00:17:09.669794202",
+                "2016-10-06T00:17:09.669794202Z",
+            ),
+            ("2016-10-06T00:17:09Z", "2016-10-06T00:17:09Z"),
+        ];
+
+        for (timestamp, expected) in cases {
+            let expected = DateTime::parse_from_rfc3339(expected)
+                .unwrap()
+                .with_timezone(&Utc);
+            assert_eq!(parse_timestamp(timestamp), Ok(expected), "{}", timestamp);
+            assert_eq!(
+                parse_timestamp_of(&format!("{} stdout F message", timestamp), true),
+                Value::Timestamp(expected),
+                "{}",
+                timestamp
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_falls_back_to_ingestion_time() {
+        trace_init();
+        let before = Utc::now();
+        for (line, use_log_timestamp) in [
+            ("not-a-timestamp stdout F message", true),
+            ("2016-10-06T00:17:09.669794202Z stdout F message", false),
+        ] {
+            let timestamp = parse_timestamp_of(line, use_log_timestamp);
+            let timestamp = timestamp.as_timestamp().unwrap();
+            assert!(*timestamp >= before, "{}", line);
+            assert!(*timestamp <= Utc::now(), "{}", line);
+        }
+    }
 }
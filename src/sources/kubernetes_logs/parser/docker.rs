@@ -35,6 +35,16 @@ impl Docker {
     }
 }
 
+/// Whether the line is a JSON object with the keys written by the Docker `json-file` log driver.
+pub(super) fn is_docker_line(line: &[u8]) -> bool {
+    match serde_json::from_slice(line) {
+        Ok(JsonValue::Object(object)) => [MESSAGE_KEY, STREAM_KEY, TIMESTAMP_KEY]
+            .iter()
+            .all(|key| object.contains_key(*key)),
+        _ => false,
+    }
+}
+
 impl FunctionTransform for Docker {
     fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
         let log = event.as_mut_log();
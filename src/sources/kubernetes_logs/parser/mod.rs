@@ -2,46 +2,157 @@ mod cri;
 mod docker;
 mod test_util;
 
-use vector_lib::config::LogNamespace;
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use vector_lib::config::{LegacyKey, LogNamespace};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::{owned_value_path, path, OwnedTargetPath};
 
 use crate::sources::kubernetes_logs::transform_utils::get_message_path;
 use crate::{
     event::{Event, Value},
-    internal_events::KubernetesLogsFormatPickerEdgeCase,
+    internal_events::{KubernetesLogsFormatDetectionFallback, KubernetesLogsFormatPickerEdgeCase},
+    sources::kubernetes_logs::Config,
     transforms::{FunctionTransform, OutputBuffer},
 };
 
+/// The key we use for `file` field.
+const FILE_KEY: &str = "file";
+
+/// The key both of the parsers insert the stream of the line in.
+const STREAM_KEY: &str = "stream";
+
+/// The number of files the detected format is remembered for.
+const DETECTED_FORMATS_CAPACITY: usize = 1024;
+
+/// The format of the container log files.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Detect the format of each file from its first non-empty line.
+    ///
+    /// Files whose first line is a JSON object with the `log`, `stream`, and `time` keys are
+    /// parsed in the Docker format, all other files are parsed in the CRI format.
+    #[default]
+    Auto,
+
+    /// Parse all files in the CRI format.
+    Cri,
+
+    /// Parse all files in the Docker `json-file` format.
+    Docker,
+}
+
 #[derive(Clone, Debug)]
 enum ParserState {
-    /// Runtime has not yet been detected.
-    Uninitialized,
+    /// Runtime is detected per file, remembering the format of the files seen recently.
+    Auto(LruCache<String, Runtime>),
+
+    /// The same runtime is used for all files.
+    Fixed(Runtime),
+}
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Runtime {
     /// Docker runtime is being used.
-    Docker(docker::Docker),
+    Docker,
 
     /// CRI is being used.
-    Cri(cri::Cri),
+    Cri,
 }
 
 #[derive(Clone, Debug)]
 pub struct Parser {
     state: ParserState,
+    docker: docker::Docker,
+    cri: cri::Cri,
+    file_path: OwnedTargetPath,
+    stream_path: OwnedTargetPath,
+    extract_stream: bool,
     log_namespace: LogNamespace,
 }
 
 impl Parser {
-    pub const fn new(log_namespace: LogNamespace) -> Self {
+    pub fn new(
+        log_namespace: LogNamespace,
+        format: LogFormat,
+        extract_stream: bool,
+        use_log_timestamp: bool,
+    ) -> Self {
+        let state = match format {
+            LogFormat::Auto => ParserState::Auto(LruCache::new(
+                NonZeroUsize::new(DETECTED_FORMATS_CAPACITY).expect("capacity must be non-zero"),
+            )),
+            LogFormat::Docker => ParserState::Fixed(Runtime::Docker),
+            LogFormat::Cri => ParserState::Fixed(Runtime::Cri),
+        };
+
+        let (file_path, stream_path) = match log_namespace {
+            LogNamespace::Vector => (
+                OwnedTargetPath::metadata(owned_value_path!(Config::NAME, FILE_KEY)),
+                OwnedTargetPath::metadata(owned_value_path!(Config::NAME, STREAM_KEY)),
+            ),
+            LogNamespace::Legacy => (
+                OwnedTargetPath::event(owned_value_path!(FILE_KEY)),
+                OwnedTargetPath::event(owned_value_path!(STREAM_KEY)),
+            ),
+        };
+
         Self {
-            state: ParserState::Uninitialized,
+            state,
+            docker: docker::Docker::new(log_namespace),
+            cri: cri::Cri::new(log_namespace, use_log_timestamp),
+            file_path,
+            stream_path,
+            extract_stream,
             log_namespace,
         }
     }
+
+    /// Inserts the stream the parsed line was written to into the `kubernetes.stream` field,
+    /// the same way for both of the formats.
+    ///
+    /// The CRI format only allows `stdout` and `stderr`, the Docker format carries the stream in
+    /// a JSON field, so other values are left out.
+    fn insert_stream(&self, event: &mut Event) {
+        let log = event.as_mut_log();
+        let stream = match log.get(&self.stream_path).and_then(Value::as_str) {
+            Some(stream) if stream == "stdout" || stream == "stderr" => stream.into_owned(),
+            _ => return,
+        };
+        self.log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            Some(LegacyKey::Overwrite(path!("kubernetes", STREAM_KEY))),
+            path!(STREAM_KEY),
+            stream,
+        );
+    }
+}
+
+/// Detects the format of a log line, if the line is not blank.
+fn detect_runtime(bytes: &[u8]) -> Option<Runtime> {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+
+    if docker::is_docker_line(bytes) {
+        return Some(Runtime::Docker);
+    }
+
+    if !cri::is_cri_line(bytes) {
+        emit!(KubernetesLogsFormatDetectionFallback);
+    }
+    Some(Runtime::Cri)
 }
 
 impl FunctionTransform for Parser {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
-        match &mut self.state {
-            ParserState::Uninitialized => {
+        let runtime = match &mut self.state {
+            ParserState::Fixed(runtime) => *runtime,
+            ParserState::Auto(detected_runtimes) => {
                 let message_field = get_message_path(self.log_namespace);
                 let message = match event.as_log().get(&message_field) {
                     Some(message) => message,
@@ -63,15 +174,38 @@ impl FunctionTransform for Parser {
                     }
                 };
 
-                self.state = if bytes.len() > 1 && bytes[0] == b'{' {
-                    ParserState::Docker(docker::Docker::new(self.log_namespace))
-                } else {
-                    ParserState::Cri(cri::Cri::new(self.log_namespace))
-                };
-                self.transform(output, event)
+                let file = event
+                    .as_log()
+                    .get(&self.file_path)
+                    .and_then(|file| file.as_str())
+                    .map(|file| file.into_owned())
+                    .unwrap_or_default();
+
+                match detected_runtimes.get(&file) {
+                    Some(runtime) => *runtime,
+                    None => match detect_runtime(bytes) {
+                        Some(runtime) => {
+                            detected_runtimes.put(file, runtime);
+                            runtime
+                        }
+                        // Blank lines don't tell the formats apart, so they are left to the CRI
+                        // parser without deciding the format of the file.
+                        None => Runtime::Cri,
+                    },
+                }
+            }
+        };
+
+        let mut parsed = OutputBuffer::with_capacity(1);
+        match runtime {
+            Runtime::Docker => self.docker.transform(&mut parsed, event),
+            Runtime::Cri => self.cri.transform(&mut parsed, event),
+        }
+        for mut event in parsed.into_events() {
+            if self.extract_stream {
+                self.insert_stream(&mut event);
             }
-            ParserState::Docker(t) => t.transform(output, event),
-            ParserState::Cri(t) => t.transform(output, event),
+            output.push(event);
         }
     }
 }
@@ -103,7 +237,7 @@ mod tests {
     fn test_parsing_valid_vector_namespace() {
         trace_init();
         test_util::test_parser(
-            || Parser::new(LogNamespace::Vector),
+            || Parser::new(LogNamespace::Vector, LogFormat::Auto, false, true),
             |bytes| Event::Log(LogEvent::from(value!(bytes))),
             valid_cases(LogNamespace::Vector),
         );
@@ -113,7 +247,7 @@ mod tests {
     fn test_parsing_valid_legacy_namespace() {
         trace_init();
         test_util::test_parser(
-            || Parser::new(LogNamespace::Legacy),
+            || Parser::new(LogNamespace::Legacy, LogFormat::Auto, false, true),
             |bytes| Event::Log(LogEvent::from(bytes)),
             valid_cases(LogNamespace::Legacy),
         );
@@ -126,7 +260,7 @@ mod tests {
         let cases = invalid_cases();
 
         for bytes in cases {
-            let mut parser = Parser::new(LogNamespace::Legacy);
+            let mut parser = Parser::new(LogNamespace::Legacy, LogFormat::Auto, false, true);
             let input = LogEvent::from(bytes);
             let mut output = OutputBuffer::default();
             parser.transform(&mut output, input.into());
@@ -155,11 +289,141 @@ mod tests {
         ];
 
         for (input, log_namespace) in cases {
-            let mut parser = Parser::new(log_namespace);
+            let mut parser = Parser::new(log_namespace, LogFormat::Auto, false, true);
             let mut output = OutputBuffer::default();
             parser.transform(&mut output, input.into());
 
             assert!(output.is_empty(), "Expected no events: {:?}", output);
         }
     }
+
+    #[test]
+    fn test_format_detected_per_file() {
+        trace_init();
+
+        let docker_line =
+            r#"{"log": "docker\n", "stream": "stdout", "time": "2019-01-01T00:00:00Z"}"#;
+        let cri_line = "2019-01-01T00:00:00Z stdout F cri";
+        let cases = vec![
+            ("", "a.log", None),
+            (docker_line, "a.log", Some("docker")),
+            (cri_line, "b.log", Some("cri")),
+            // The format of each file stays the one detected from its first line.
+            (cri_line, "a.log", None),
+            (docker_line, "b.log", None),
+            (docker_line, "a.log", Some("docker")),
+            // JSON other than the Docker format falls back to CRI.
+            (r#"{"message": "hello"}"#, "c.log", None),
+            (cri_line, "c.log", Some("cri")),
+        ];
+
+        let mut parser = Parser::new(LogNamespace::Legacy, LogFormat::Auto, false, true);
+        for (line, file, expected) in cases {
+            let mut input = LogEvent::from(line);
+            input.insert(event_path!("file"), file);
+            let mut output = OutputBuffer::default();
+            parser.transform(&mut output, input.into());
+
+            let messages = output
+                .into_events()
+                .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                messages,
+                expected.into_iter().map(String::from).collect::<Vec<_>>(),
+                "line {:?} of file {:?}",
+                line,
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_stream() {
+        trace_init();
+
+        let cases = vec![
+            ("2019-01-01T00:00:00Z stderr F oops", Some("stderr")),
+            ("2019-01-01T00:00:00Z stdout F fine", Some("stdout")),
+            (
+                r#"{"log": "oops\n", "stream": "stderr", "time": "2019-01-01T00:00:00Z"}"#,
+                Some("stderr"),
+            ),
+            (
+                r#"{"log": "fine\n", "stream": "stdout", "time": "2019-01-01T00:00:00Z"}"#,
+                Some("stdout"),
+            ),
+            (
+                r#"{"log": "odd\n", "stream": "other", "time": "2019-01-01T00:00:00Z"}"#,
+                None,
+            ),
+        ];
+
+        for (line, expected) in cases {
+            let mut parser = Parser::new(LogNamespace::Legacy, LogFormat::Auto, true, true);
+            let mut output = OutputBuffer::default();
+            parser.transform(&mut output, LogEvent::from(line).into());
+            let event = output.into_events().next().expect("line should parse");
+            assert_eq!(
+                event
+                    .as_log()
+                    .get("kubernetes.stream")
+                    .map(|stream| stream.to_string_lossy().into_owned()),
+                expected.map(String::from),
+                "{:?}",
+                line
+            );
+
+            let mut parser = Parser::new(LogNamespace::Vector, LogFormat::Auto, true, true);
+            let mut output = OutputBuffer::default();
+            parser.transform(&mut output, LogEvent::from(value!(line)).into());
+            let event = output.into_events().next().expect("line should parse");
+            let stream = event
+                .as_log()
+                .get(&OwnedTargetPath::metadata(owned_value_path!(
+                    Config::NAME,
+                    STREAM_KEY
+                )))
+                .map(|stream| stream.to_string_lossy().into_owned());
+            assert_eq!(stream.as_deref(), expected.or(Some("other")), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_extract_stream_disabled() {
+        trace_init();
+
+        let mut parser = Parser::new(LogNamespace::Legacy, LogFormat::Auto, false, true);
+        let mut output = OutputBuffer::default();
+        parser.transform(
+            &mut output,
+            LogEvent::from("2019-01-01T00:00:00Z stderr F oops").into(),
+        );
+        let event = output.into_events().next().expect("line should parse");
+        assert_eq!(event.as_log()["stream"], "stderr".into());
+        assert!(event.as_log().get("kubernetes.stream").is_none());
+    }
+
+    #[test]
+    fn test_format_fixed() {
+        trace_init();
+
+        let docker_line =
+            r#"{"log": "docker", "stream": "stdout", "time": "2019-01-01T00:00:00Z"}"#;
+        let cri_line = "2019-01-01T00:00:00Z stdout F cri";
+        let cases = vec![
+            (LogFormat::Docker, docker_line, true),
+            (LogFormat::Docker, cri_line, false),
+            (LogFormat::Cri, cri_line, true),
+            (LogFormat::Cri, docker_line, false),
+        ];
+
+        for (format, line, parsed) in cases {
+            let mut parser = Parser::new(LogNamespace::Legacy, format, false, true);
+            let mut output = OutputBuffer::default();
+            parser.transform(&mut output, LogEvent::from(line).into());
+
+            assert_eq!(!output.is_empty(), parsed, "{:?} {:?}", format, line);
+        }
+    }
 }
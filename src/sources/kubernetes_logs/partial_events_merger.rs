@@ -2,7 +2,7 @@
 
 use bytes::BytesMut;
 use futures::{Stream, StreamExt};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use vector_lib::config::LogNamespace;
 use vector_lib::lookup::OwnedTargetPath;
@@ -11,26 +11,63 @@ use vrl::owned_value_path;
 
 use crate::event;
 use crate::event::{Event, LogEvent, Value};
+use crate::internal_events::KubernetesLogsPartialLineOverflow;
 use crate::sources::kubernetes_logs::transform_utils::get_message_path;
 
 /// The key we use for `file` field.
 const FILE_KEY: &str = "file";
 
+/// The key we use for `stream` field.
+const STREAM_KEY: &str = "stream";
+
+/// The key we use for the metadata flag of the events flushed early by the
+/// `max_merged_line_bytes` cap.
+const TRUNCATED_KEY: &str = "truncated";
+
+/// The key we use for the flag of the lines truncated at `max_line_bytes` in the `Legacy`
+/// namespace.
+const LINE_TRUNCATED_KEY: &str = "kubernetes";
+
 const EXPIRATION_TIME: Duration = Duration::from_secs(30);
 
+// The partial events are merged per file and per stream, as the container runtime writes the
+// `stdout` and `stderr` of a container interleaved into the same file.
+type BucketKey = (String, String);
+
 struct PartialEventMergeState {
-    buckets: HashMap<String, Bucket>,
+    buckets: HashMap<BucketKey, Bucket>,
+    // The keys whose current line was truncated at `max_line_bytes`, so its remaining partial
+    // events are skipped up to and including the final one.
+    skipping: HashSet<BucketKey>,
 }
 
 impl PartialEventMergeState {
+    // Returns the accumulated event if merging the new one into it would exceed
+    // `max_merged_line_bytes`, in which case the new event starts a new bucket.
     fn add_event(
         &mut self,
         event: LogEvent,
-        file: &str,
+        key: &BucketKey,
         message_path: &OwnedTargetPath,
         expiration_time: Duration,
-    ) {
-        if let Some(bucket) = self.buckets.get_mut(file) {
+        max_merged_line_bytes: Option<usize>,
+    ) -> Option<LogEvent> {
+        if let Some(bucket) = self.buckets.get_mut(key) {
+            let overflows = max_merged_line_bytes.map_or(false, |max_merged_line_bytes| {
+                message_len(&bucket.event, message_path) + message_len(&event, message_path)
+                    > max_merged_line_bytes
+            });
+            if overflows {
+                let bucket = std::mem::replace(
+                    bucket,
+                    Bucket {
+                        event,
+                        expiration: Instant::now() + expiration_time,
+                    },
+                );
+                return Some(bucket.event);
+            }
+
             // merging with existing event
 
             if let (Some(Value::Bytes(prev_value)), Some(Value::Bytes(new_value))) =
@@ -41,20 +78,22 @@ impl PartialEventMergeState {
                 bytes_mut.extend_from_slice(new_value);
                 *prev_value = bytes_mut.freeze();
             }
+            None
         } else {
             // new event
             self.buckets.insert(
-                file.to_owned(),
+                key.clone(),
                 Bucket {
                     event,
                     expiration: Instant::now() + expiration_time,
                 },
             );
+            None
         }
     }
 
-    fn remove_event(&mut self, file: &str) -> Option<LogEvent> {
-        self.buckets.remove(file).map(|bucket| bucket.event)
+    fn remove_event(&mut self, key: &BucketKey) -> Option<LogEvent> {
+        self.buckets.remove(key).map(|bucket| bucket.event)
     }
 
     fn emit_expired_events(&mut self, emitter: &mut Emitter<LogEvent>) {
@@ -69,6 +108,7 @@ impl PartialEventMergeState {
     }
 
     fn flush_events(&mut self, emitter: &mut Emitter<LogEvent>) {
+        self.skipping.clear();
         for (_, bucket) in self.buckets.drain() {
             emitter.emit(bucket.event);
         }
@@ -80,11 +120,24 @@ struct Bucket {
     expiration: Instant,
 }
 
+fn message_len(event: &LogEvent, message_path: &OwnedTargetPath) -> usize {
+    match event.get(message_path) {
+        Some(Value::Bytes(value)) => value.len(),
+        _ => 0,
+    }
+}
+
 pub fn merge_partial_events(
     stream: impl Stream<Item = Event> + 'static,
     log_namespace: LogNamespace,
+    max_merged_line_bytes: Option<usize>,
 ) -> impl Stream<Item = Event> {
-    merge_partial_events_with_custom_expiration(stream, log_namespace, EXPIRATION_TIME)
+    merge_partial_events_with_custom_expiration(
+        stream,
+        log_namespace,
+        EXPIRATION_TIME,
+        max_merged_line_bytes,
+    )
 }
 
 // internal function that allows customizing the expiration time (for testing)
@@ -92,6 +145,7 @@ fn merge_partial_events_with_custom_expiration(
     stream: impl Stream<Item = Event> + 'static,
     log_namespace: LogNamespace,
     expiration_time: Duration,
+    max_merged_line_bytes: Option<usize>,
 ) -> impl Stream<Item = Event> {
     let partial_flag_path = match log_namespace {
         LogNamespace::Vector => {
@@ -107,8 +161,28 @@ fn merge_partial_events_with_custom_expiration(
         LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!(FILE_KEY)),
     };
 
+    let stream_path = match log_namespace {
+        LogNamespace::Vector => {
+            OwnedTargetPath::metadata(owned_value_path!(super::Config::NAME, STREAM_KEY))
+        }
+        LogNamespace::Legacy => OwnedTargetPath::event(owned_value_path!(STREAM_KEY)),
+    };
+
+    let truncated_flag_path =
+        OwnedTargetPath::metadata(owned_value_path!(super::Config::NAME, TRUNCATED_KEY));
+
+    // In the `Vector` namespace the lines truncated by the file server share the metadata flag
+    // of the events flushed early.
+    let line_truncated_path = match log_namespace {
+        LogNamespace::Vector => truncated_flag_path.clone(),
+        LogNamespace::Legacy => {
+            OwnedTargetPath::event(owned_value_path!(LINE_TRUNCATED_KEY, TRUNCATED_KEY))
+        }
+    };
+
     let state = PartialEventMergeState {
         buckets: HashMap::new(),
+        skipping: HashSet::new(),
     };
 
     let message_path = get_message_path(log_namespace);
@@ -132,11 +206,52 @@ fn merge_partial_events_with_custom_expiration(
                 .map(|x| x.to_string())
                 .unwrap_or_else(String::new);
 
-            state.add_event(event, &file, &message_path, expiration_time);
-            if !is_partial {
-                if let Some(log_event) = state.remove_event(&file) {
+            let stream = event
+                .get(&stream_path)
+                .and_then(|x| x.as_str())
+                .map(|x| x.to_string())
+                .unwrap_or_else(String::new);
+
+            let is_truncated = event
+                .get(&line_truncated_path)
+                .and_then(|x| x.as_boolean())
+                .unwrap_or(false);
+
+            let key = (file, stream);
+
+            if state.skipping.contains(&key) {
+                // The rest of a truncated line is dropped.
+                if !is_partial {
+                    state.skipping.remove(&key);
+                }
+                return;
+            }
+
+            if let Some(mut log_event) = state.add_event(
+                event,
+                &key,
+                &message_path,
+                expiration_time,
+                max_merged_line_bytes,
+            ) {
+                emit!(KubernetesLogsPartialLineOverflow {
+                    max_merged_line_bytes: max_merged_line_bytes.unwrap_or_default(),
+                });
+                log_event.insert(&truncated_flag_path, true);
+                emitter.emit(log_event);
+            }
+            if !is_partial || is_truncated {
+                if let Some(mut log_event) = state.remove_event(&key) {
+                    // The merged event is complete, so it's no longer partial.
+                    log_event.remove(&partial_flag_path);
+                    if is_truncated {
+                        log_event.insert(&line_truncated_path, true);
+                    }
                     emitter.emit(log_event);
                 }
+                if is_partial {
+                    state.skipping.insert(key);
+                }
             }
         },
         |state: &mut PartialEventMergeState, emitter: &mut Emitter<LogEvent>| {
@@ -155,6 +270,8 @@ fn merge_partial_events_with_custom_expiration(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::sources::kubernetes_logs::parser::{LogFormat, Parser};
+    use crate::transforms::{FunctionTransform, OutputBuffer};
     use vector_lib::event::LogEvent;
     use vrl::value;
 
@@ -164,7 +281,7 @@ mod test {
         e_1.insert("foo", 1);
 
         let input_stream = futures::stream::iter([e_1.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -184,7 +301,7 @@ mod test {
         e_2.insert("foo2", 1);
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -205,7 +322,7 @@ mod test {
         e_1.insert("_partial", true);
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -233,6 +350,7 @@ mod test {
             input_stream,
             LogNamespace::Legacy,
             Duration::from_secs(1),
+            None,
         );
 
         let output: Vec<Event> = output_stream.take(2).collect().await;
@@ -256,7 +374,7 @@ mod test {
         );
 
         let input_stream = futures::stream::iter([e_1.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector, None);
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -286,7 +404,7 @@ mod test {
         );
 
         let input_stream = futures::stream::iter([e_1.into(), e_2.into()]);
-        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Vector, None);
 
         let output: Vec<Event> = output_stream.collect().await;
         assert_eq!(output.len(), 1);
@@ -299,4 +417,142 @@ mod test {
             Some(&value!("foo1"))
         );
     }
+
+    #[tokio::test]
+    async fn merge_multiple_events_overflow() {
+        let mut e_1 = LogEvent::from("aaaa");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("bbbb");
+        e_2.insert("_partial", true);
+
+        let mut e_3 = LogEvent::from("cccc");
+        e_3.insert("_partial", true);
+
+        let e_4 = LogEvent::from("dd");
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into(), e_3.into(), e_4.into()]);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, Some(10));
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("aaaabbbb"))
+        );
+        assert_eq!(
+            output[0].as_log().get("%kubernetes_logs.truncated"),
+            Some(&value!(true))
+        );
+        assert_eq!(output[1].as_log().get(".message"), Some(&value!("ccccdd")));
+        assert_eq!(output[1].as_log().get("%kubernetes_logs.truncated"), None);
+    }
+
+    #[tokio::test]
+    async fn merge_multiple_events_truncated_line_legacy() {
+        let mut e_1 = LogEvent::from("aaaa");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("bbbb");
+        e_2.insert("_partial", true);
+        e_2.insert("kubernetes.truncated", true);
+
+        let mut e_3 = LogEvent::from("cccc");
+        e_3.insert("_partial", true);
+
+        let e_4 = LogEvent::from("dd");
+        let e_5 = LogEvent::from("next");
+
+        let input_stream =
+            futures::stream::iter([e_1.into(), e_2.into(), e_3.into(), e_4.into(), e_5.into()]);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("aaaabbbb"))
+        );
+        assert_eq!(
+            output[0].as_log().get("kubernetes.truncated"),
+            Some(&value!(true))
+        );
+        assert_eq!(output[0].as_log().get("_partial"), None);
+        assert_eq!(output[1].as_log().get(".message"), Some(&value!("next")));
+        assert_eq!(output[1].as_log().get("kubernetes.truncated"), None);
+    }
+
+    #[tokio::test]
+    async fn merge_multiple_events_per_stream_legacy() {
+        let mut e_1 = LogEvent::from("stdout 1");
+        e_1.insert("stream", "stdout");
+        e_1.insert("_partial", true);
+
+        let mut e_2 = LogEvent::from("stderr 1");
+        e_2.insert("stream", "stderr");
+        e_2.insert("_partial", true);
+
+        let mut e_3 = LogEvent::from("stdout 2");
+        e_3.insert("stream", "stdout");
+
+        let mut e_4 = LogEvent::from("stderr 2");
+        e_4.insert("stream", "stderr");
+
+        let input_stream = futures::stream::iter([e_1.into(), e_2.into(), e_3.into(), e_4.into()]);
+        let output_stream = merge_partial_events(input_stream, LogNamespace::Legacy, None);
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get(".message"),
+            Some(&value!("stdout 1stdout 2"))
+        );
+        assert_eq!(output[0].as_log().get(".stream"), Some(&value!("stdout")));
+        assert_eq!(output[0].as_log().get("._partial"), None);
+        assert_eq!(
+            output[1].as_log().get(".message"),
+            Some(&value!("stderr 1stderr 2"))
+        );
+        assert_eq!(output[1].as_log().get(".stream"), Some(&value!("stderr")));
+        assert_eq!(output[1].as_log().get("._partial"), None);
+    }
+
+    #[tokio::test]
+    async fn merge_cri_partial_lines() {
+        let lines = [
+            "2016-10-06T00:17:09.669794202Z stdout P Exception in thread \"main\" ",
+            "2016-10-06T00:17:09.669794203Z stdout P java.lang.RuntimeException: ",
+            "2016-10-06T00:17:09.669794204Z stdout F boom",
+            "2016-10-06T00:17:10.113242941Z stdout F next line",
+        ];
+
+        let mut parser = Parser::new(LogNamespace::Vector, LogFormat::Auto, false, true);
+        let events: Vec<Event> = lines
+            .iter()
+            .flat_map(|line| {
+                let mut event = LogEvent::from(value!(*line));
+                event.insert(
+                    vrl::metadata_path!(super::super::Config::NAME, FILE_KEY),
+                    "foo1",
+                );
+                let mut buf = OutputBuffer::with_capacity(1);
+                parser.transform(&mut buf, event.into());
+                buf.into_events().collect::<Vec<_>>()
+            })
+            .collect();
+
+        let output_stream =
+            merge_partial_events(futures::stream::iter(events), LogNamespace::Vector, None);
+
+        let output: Vec<Event> = output_stream.collect().await;
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].as_log().get("."),
+            Some(&value!(
+                "Exception in thread \"main\" java.lang.RuntimeException: boom"
+            ))
+        );
+        assert_eq!(output[0].as_log().get("%kubernetes_logs._partial"), None);
+        assert_eq!(output[1].as_log().get("."), Some(&value!("next line")));
+    }
 }
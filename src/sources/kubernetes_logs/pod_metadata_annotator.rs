@@ -6,19 +6,32 @@ use k8s_openapi::{
     api::core::v1::{Container, ContainerStatus, Pod, PodSpec, PodStatus},
     apimachinery::pkg::apis::meta::v1::ObjectMeta,
 };
+use std::{collections::BTreeMap, sync::Arc};
+
 use kube::runtime::reflector::{store::Store, ObjectRef};
 use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::{
-    lookup_v2::{OptionalTargetPath, ValuePath},
-    owned_value_path, path, OwnedTargetPath,
+    lookup_v2::{OptionalTargetPath, OwnedSegment, ValuePath},
+    owned_value_path, path, OwnedTargetPath, OwnedValuePath,
 };
 
 use super::{
-    path_helpers::{parse_log_file_path, LogFileInfo},
+    kubelet_metadata::KubeletPodsFallback,
+    path_helpers::{LogFileInfo, LogLayout, DEFAULT_POD_DIR_DELIMITER},
     Config,
 };
-use crate::event::{Event, LogEvent};
+use crate::{
+    event::{Event, LogEvent, Value},
+    internal_events::KubernetesLogsDelayedDeletionLookup,
+    kubernetes::{interner::StringInterner, reflector::DelayedDeletions},
+};
+
+/// The number of distinct metadata strings shared across the events before they are dropped.
+const INTERNED_STRINGS_CAPACITY: usize = 4096;
+
+/// The annotation holding the UID the kubelet assigned to a static Pod, on its mirror Pod.
+const MIRROR_POD_ANNOTATION: &str = "kubernetes.io/config.mirror";
 
 /// Configuration for how the events are enriched with Pod metadata.
 #[configurable_component]
@@ -105,6 +118,17 @@ pub struct FieldsSpec {
     #[configurable(metadata(docs::examples = ""))]
     pub container_name: OptionalTargetPath,
 
+    /// Event field for the Container's restart count.
+    ///
+    /// The restart count is taken from the log file name, and the field is omitted if the file
+    /// name doesn't carry one.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.container_restart_count"))]
+    #[configurable(metadata(docs::examples = "k8s.container_restart_count"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub container_restart_count: OptionalTargetPath,
+
     /// Event field for the Container's ID.
     ///
     /// Set to `""` to suppress this key.
@@ -115,6 +139,9 @@ pub struct FieldsSpec {
 
     /// Event field for the Container's image.
     ///
+    /// The image the Pod status reports the Container to run is used, or the image of the Container
+    /// in the Pod spec until the status reports one.
+    ///
     /// Set to `""` to suppress this key.
     #[configurable(metadata(docs::examples = ".k8s.container_image"))]
     #[configurable(metadata(docs::examples = "k8s.container_image"))]
@@ -123,11 +150,38 @@ pub struct FieldsSpec {
 
     /// Event field for the Container's image ID.
     ///
+    /// This is the digest of the image the Pod status reports the Container to run. It isn't set
+    /// until the status reports one, like while the image is still being pulled.
+    ///
     /// Set to `""` to suppress this key.
     #[configurable(metadata(docs::examples = ".k8s.container_image_id"))]
     #[configurable(metadata(docs::examples = "k8s.container_image_id"))]
     #[configurable(metadata(docs::examples = ""))]
     pub container_image_id: OptionalTargetPath,
+
+    /// Event field for the Container's kind, one of `init`, `ephemeral`, or `regular`.
+    ///
+    /// The kind is taken from the Pod spec, and the field is omitted if the Pod metadata isn't
+    /// available.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.container_kind"))]
+    #[configurable(metadata(docs::examples = "k8s.container_kind"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub container_kind: OptionalTargetPath,
+
+    /// Event field for the segments following the Pod UID in the name of the Pod logs directory.
+    ///
+    /// Some distributions append extra segments to the name of the Pod logs directory. They are
+    /// only told apart from the Pod name when the Pod UID is well-formed, and set in order as an
+    /// array of strings, to be mapped onto other fields with `remap`. The field is omitted if
+    /// there are none.
+    ///
+    /// Set to `""` to suppress this key.
+    #[configurable(metadata(docs::examples = ".k8s.pod_dir_extra_segments"))]
+    #[configurable(metadata(docs::examples = "k8s.pod_dir_extra_segments"))]
+    #[configurable(metadata(docs::examples = ""))]
+    pub pod_dir_extra_segments: OptionalTargetPath,
 }
 
 impl Default for FieldsSpec {
@@ -154,6 +208,11 @@ impl Default for FieldsSpec {
                 "container_name"
             ))
             .into(),
+            container_restart_count: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "container_restart_count"
+            ))
+            .into(),
             container_id: OwnedTargetPath::event(owned_value_path!("kubernetes", "container_id"))
                 .into(),
             container_image: OwnedTargetPath::event(owned_value_path!(
@@ -166,81 +225,299 @@ impl Default for FieldsSpec {
                 "container_image_id"
             ))
             .into(),
+            container_kind: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "container_kind"
+            ))
+            .into(),
+            pod_dir_extra_segments: OwnedTargetPath::event(owned_value_path!(
+                "kubernetes",
+                "pod_dir_extra_segments"
+            ))
+            .into(),
         }
     }
 }
 
+/// The kind of a container within its Pod.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContainerKind {
+    /// A container run to completion before the regular containers are started.
+    Init,
+    /// A container added to a running Pod, like for debugging.
+    Ephemeral,
+    /// A container listed in the `containers` of the Pod spec.
+    Regular,
+}
+
+impl ContainerKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            ContainerKind::Init => "init",
+            ContainerKind::Ephemeral => "ephemeral",
+            ContainerKind::Regular => "regular",
+        }
+    }
+
+    /// Classifies the container of the Pod by its name.
+    fn of(pod_spec: &PodSpec, container_name: &str) -> Option<Self> {
+        if pod_spec.containers.iter().any(|c| c.name == container_name) {
+            Some(ContainerKind::Regular)
+        } else if pod_spec
+            .init_containers
+            .iter()
+            .flatten()
+            .any(|c| c.name == container_name)
+        {
+            Some(ContainerKind::Init)
+        } else if pod_spec
+            .ephemeral_containers
+            .iter()
+            .flatten()
+            .any(|c| c.name == container_name)
+        {
+            Some(ContainerKind::Ephemeral)
+        } else {
+            None
+        }
+    }
+}
+
+/// The Pod labels and annotations the events are annotated with.
+#[derive(Clone, Debug, Default)]
+pub struct MetadataKeys {
+    /// The keys of the labels to add, or `None` to add all of them.
+    pub labels: Option<Vec<String>>,
+    /// The keys of the annotations to add, or `None` to add all of them.
+    pub annotations: Option<Vec<String>>,
+    /// Whether the labels and annotations are added as single dotted fields, rather than nested.
+    pub flatten: bool,
+}
+
 /// Annotate the event with pod metadata.
 pub struct PodMetadataAnnotator {
     pods_state_reader: Store<Pod>,
+    delayed_deletions: DelayedDeletions<Pod>,
+    kubelet_fallback: Option<KubeletPodsFallback>,
+    log_layout: LogLayout,
+    pod_dir_delimiter: char,
+    /// Whether the Pod log files with a malformed Pod UID are left unannotated.
+    strict_log_paths: bool,
     fields_spec: FieldsSpec,
+    metadata_keys: MetadataKeys,
     log_namespace: LogNamespace,
+    /// The metadata strings, shared by the events of the same Pods.
+    interner: StringInterner,
 }
 
 impl PodMetadataAnnotator {
     /// Create a new [`PodMetadataAnnotator`].
-    pub const fn new(
+    pub fn new(
         pods_state_reader: Store<Pod>,
+        delayed_deletions: DelayedDeletions<Pod>,
+        kubelet_fallback: Option<KubeletPodsFallback>,
+        log_layout: LogLayout,
         fields_spec: FieldsSpec,
+        metadata_keys: MetadataKeys,
         log_namespace: LogNamespace,
     ) -> Self {
         Self {
             pods_state_reader,
+            delayed_deletions,
+            kubelet_fallback,
+            log_layout,
+            pod_dir_delimiter: DEFAULT_POD_DIR_DELIMITER,
+            strict_log_paths: false,
             fields_spec,
+            metadata_keys,
             log_namespace,
+            interner: StringInterner::new(INTERNED_STRINGS_CAPACITY),
         }
     }
+
+    /// Sets the delimiter between the components of the Pod logs directory
+    /// names, see [`LogLayout::parse_log_file_path`].
+    pub const fn with_pod_dir_delimiter(mut self, pod_dir_delimiter: char) -> Self {
+        self.pod_dir_delimiter = pod_dir_delimiter;
+        self
+    }
+
+    /// Leaves the Pod log files with a malformed Pod UID unannotated, see
+    /// [`LogLayout::parse_log_file_path_strict`].
+    pub const fn with_strict_log_paths(mut self, strict_log_paths: bool) -> Self {
+        self.strict_log_paths = strict_log_paths;
+        self
+    }
 }
 
 impl PodMetadataAnnotator {
     /// Annotates an event with the information from the [`Pod::metadata`].
-    pub fn annotate<'a>(&self, event: &mut Event, file: &'a str) -> Option<LogFileInfo<'a>> {
+    pub fn annotate<'a>(&mut self, event: &mut Event, file: &'a str) -> Option<LogFileInfo<'a>> {
         let log = event.as_mut_log();
-        let file_info = parse_log_file_path(file)?;
+        let file_info = if self.strict_log_paths {
+            self.log_layout
+                .parse_log_file_path_strict(file, self.pod_dir_delimiter)?
+        } else {
+            self.log_layout
+                .parse_log_file_path(file, self.pod_dir_delimiter)?
+        };
         let obj = ObjectRef::<Pod>::new(file_info.pod_name).within(file_info.pod_namespace);
-        let resource = self.pods_state_reader.get(&obj)?;
+        let resource = match self
+            .pods_state_reader
+            .get(&obj)
+            .filter(|pod| matches_pod_uid(pod, file_info.pod_uid))
+        {
+            Some(resource) => {
+                if resource
+                    .metadata
+                    .uid
+                    .as_deref()
+                    .map_or(false, |uid| self.delayed_deletions.contains(uid))
+                {
+                    emit!(KubernetesLogsDelayedDeletionLookup);
+                }
+                resource
+            }
+            // The Pod may have been deleted and recreated under the same name, while the logs
+            // of the deleted one are still being read.
+            None => match self.delayed_deletions.get(file_info.pod_uid) {
+                Some(resource) => {
+                    emit!(KubernetesLogsDelayedDeletionLookup);
+                    resource
+                }
+                None => self
+                    .kubelet_fallback
+                    .as_ref()
+                    .and_then(|kubelet_fallback| kubelet_fallback.get(&obj))
+                    .filter(|pod| matches_pod_uid(pod, file_info.pod_uid))
+                    .or_else(|| self.get_static_pod(&obj))?,
+            },
+        };
         let pod: &Pod = resource.as_ref();
 
-        annotate_from_file_info(log, &self.fields_spec, &file_info, self.log_namespace);
-        annotate_from_metadata(log, &self.fields_spec, &pod.metadata, self.log_namespace);
+        annotate_from_file_info(
+            log,
+            &self.fields_spec,
+            &file_info,
+            &mut self.interner,
+            self.log_namespace,
+        );
+        annotate_from_metadata(
+            log,
+            &self.fields_spec,
+            &pod.metadata,
+            &self.metadata_keys,
+            &mut self.interner,
+            self.log_namespace,
+        );
 
         let container;
         if let Some(ref pod_spec) = pod.spec {
-            annotate_from_pod_spec(log, &self.fields_spec, pod_spec, self.log_namespace);
+            annotate_from_pod_spec(
+                log,
+                &self.fields_spec,
+                pod_spec,
+                &mut self.interner,
+                self.log_namespace,
+            );
+
+            if let Some(container_kind) = ContainerKind::of(pod_spec, file_info.container_name) {
+                annotate_container_kind(log, &self.fields_spec, container_kind, self.log_namespace);
+            }
 
             container = pod_spec
                 .containers
                 .iter()
+                .chain(pod_spec.init_containers.iter().flatten())
                 .find(|c| c.name == file_info.container_name);
             if let Some(container) = container {
-                annotate_from_container(log, &self.fields_spec, container, self.log_namespace);
+                annotate_from_container(
+                    log,
+                    &self.fields_spec,
+                    container,
+                    &mut self.interner,
+                    self.log_namespace,
+                );
             }
         }
 
         if let Some(ref pod_status) = pod.status {
-            annotate_from_pod_status(log, &self.fields_spec, pod_status, self.log_namespace);
-            if let Some(ref container_statuses) = pod_status.container_statuses {
-                let container_status = container_statuses
-                    .iter()
-                    .find(|c| c.name == file_info.container_name);
-                if let Some(container_status) = container_status {
-                    annotate_from_container_status(
-                        log,
-                        &self.fields_spec,
-                        container_status,
-                        self.log_namespace,
-                    )
-                }
+            annotate_from_pod_status(
+                log,
+                &self.fields_spec,
+                pod_status,
+                &mut self.interner,
+                self.log_namespace,
+            );
+            let container_status = pod_status
+                .container_statuses
+                .iter()
+                .chain(pod_status.init_container_statuses.iter())
+                .chain(pod_status.ephemeral_container_statuses.iter())
+                .flatten()
+                .find(|c| c.name == file_info.container_name);
+            if let Some(container_status) = container_status {
+                annotate_from_container_status(
+                    log,
+                    &self.fields_spec,
+                    container_status,
+                    &mut self.interner,
+                    self.log_namespace,
+                )
             }
         }
         Some(file_info)
     }
+
+    /// Looks a static Pod up by namespace and name only.
+    ///
+    /// The UID of the log files of a static Pod is the one the kubelet assigned from its manifest,
+    /// which the mirror Pod doesn't always carry in its annotation, like once the manifest changed.
+    /// A static Pod can't be recreated through the API, so the namespace and name are enough to
+    /// tell it apart.
+    fn get_static_pod(&self, obj: &ObjectRef<Pod>) -> Option<Arc<Pod>> {
+        self.pods_state_reader
+            .get(obj)
+            .or_else(|| self.kubelet_fallback.as_ref()?.get(obj))
+            .filter(|pod| is_static_pod(pod))
+    }
+}
+
+// Whether the Pod is the mirror of a static Pod, which the kubelet runs from a manifest on the
+// Node rather than from the API.
+fn is_static_pod(pod: &Pod) -> bool {
+    pod.metadata
+        .annotations
+        .as_ref()
+        .map_or(false, |annotations| {
+            annotations.contains_key(MIRROR_POD_ANNOTATION)
+        })
+}
+
+// Whether the Pod is the one the log file belongs to. Pods are looked up by name, so this keeps a
+// Pod recreated with the same name from annotating the logs of its predecessor. The log files of
+// static Pods are named after the UID the kubelet assigned, which their mirror Pod only carries
+// in an annotation.
+fn matches_pod_uid(pod: &Pod, file_pod_uid: &str) -> bool {
+    if file_pod_uid.is_empty() {
+        // The containers layout doesn't have the Pod UID in the log file paths.
+        return true;
+    }
+
+    pod.metadata.uid.as_deref() == Some(file_pod_uid)
+        || pod
+            .metadata
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get(MIRROR_POD_ANNOTATION))
+            .map_or(false, |uid| uid == file_pod_uid)
 }
 
 fn annotate_from_file_info(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     file_info: &LogFileInfo<'_>,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     let legacy_key = fields_spec
@@ -255,14 +532,54 @@ fn annotate_from_file_info(
         log,
         legacy_key,
         path!("container_name"),
-        file_info.container_name.to_owned(),
+        interner.intern(file_info.container_name),
     );
+
+    if let Some(restart_count) = file_info.restart_count {
+        let legacy_key = fields_spec
+            .container_restart_count
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            legacy_key,
+            path!("container_restart_count"),
+            i64::from(restart_count),
+        );
+    }
+
+    if !file_info.extra_segments.is_empty() {
+        let legacy_key = fields_spec
+            .pod_dir_extra_segments
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            legacy_key,
+            path!("pod_dir_extra_segments"),
+            file_info
+                .extra_segments
+                .iter()
+                .map(|segment| Value::from(interner.intern(segment)))
+                .collect::<Vec<_>>(),
+        );
+    }
 }
 
 fn annotate_from_metadata(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     metadata: &ObjectMeta,
+    metadata_keys: &MetadataKeys,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     for (legacy_key, metadata_key, value) in [
@@ -288,7 +605,7 @@ fn annotate_from_metadata(
                 log,
                 legacy_key,
                 *metadata_key,
-                value.to_owned(),
+                interner.intern(value),
             );
         }
     }
@@ -311,48 +628,98 @@ fn annotate_from_metadata(
     }
 
     if let Some(labels) = &metadata.labels {
-        let legacy_key_prefix = fields_spec.pod_labels.path.as_ref().map(|k| &k.path);
+        annotate_from_key_values(
+            log,
+            &fields_spec.pod_labels,
+            "pod_labels",
+            labels,
+            metadata_keys.labels.as_deref(),
+            metadata_keys.flatten,
+            interner,
+            log_namespace,
+        );
+    }
 
-        for (key, value) in labels.iter() {
-            let key_path = path!(key);
-            let legacy_key = legacy_key_prefix
-                .map(|k| k.concat(key_path))
-                .map(LegacyKey::Overwrite);
+    if let Some(annotations) = &metadata.annotations {
+        annotate_from_key_values(
+            log,
+            &fields_spec.pod_annotations,
+            "pod_annotations",
+            annotations,
+            metadata_keys.annotations.as_deref(),
+            metadata_keys.flatten,
+            interner,
+            log_namespace,
+        );
+    }
+}
 
+/// Adds the labels or annotations with the keys in `include_keys`, or all of them if `None`.
+#[allow(clippy::too_many_arguments)]
+fn annotate_from_key_values(
+    log: &mut LogEvent,
+    field: &OptionalTargetPath,
+    metadata_key: &str,
+    key_values: &BTreeMap<String, String>,
+    include_keys: Option<&[String]>,
+    flatten: bool,
+    interner: &mut StringInterner,
+    log_namespace: LogNamespace,
+) {
+    let legacy_key_prefix = field.path.as_ref().map(|k| &k.path);
+
+    for (key, value) in key_values
+        .iter()
+        .filter(|(key, _)| include_keys.map_or(true, |include_keys| include_keys.contains(key)))
+    {
+        let value = interner.intern(value);
+        if flatten {
+            let legacy_key = legacy_key_prefix.map(|prefix| flattened_path(prefix, key));
+            let metadata_key = format!("{}.{}", metadata_key, key);
             log_namespace.insert_source_metadata(
                 Config::NAME,
                 log,
-                legacy_key,
-                path!("pod_labels", key),
-                value.to_owned(),
+                legacy_key.as_ref().map(LegacyKey::Overwrite),
+                path!(metadata_key.as_str()),
+                value,
             )
-        }
-    }
-
-    if let Some(annotations) = &metadata.annotations {
-        let legacy_key_prefix = fields_spec.pod_annotations.path.as_ref().map(|k| &k.path);
-
-        for (key, value) in annotations.iter() {
-            let key_path = path!(key);
+        } else {
             let legacy_key = legacy_key_prefix
-                .map(|k| k.concat(key_path))
+                .map(|k| k.concat(path!(key)))
                 .map(LegacyKey::Overwrite);
-
             log_namespace.insert_source_metadata(
                 Config::NAME,
                 log,
                 legacy_key,
-                path!("pod_annotations", key),
-                value.to_owned(),
+                path!(metadata_key, key),
+                value,
             )
         }
     }
 }
 
+/// The path of the single dotted field of `key`, next to the last field of `prefix`, like
+/// `kubernetes."pod_labels.app"` for `kubernetes.pod_labels` and `app`.
+fn flattened_path(prefix: &OwnedValuePath, key: &str) -> OwnedValuePath {
+    let mut path = prefix.clone();
+    match path.segments.pop() {
+        Some(OwnedSegment::Field(field)) => {
+            path.push_field(&format!("{}.{}", field, key));
+        }
+        // A prefix not ending with a field can't be flattened into.
+        segment => {
+            path.segments.extend(segment);
+            path.push_field(key);
+        }
+    }
+    path
+}
+
 fn annotate_from_pod_spec(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     pod_spec: &PodSpec,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     if let Some(value) = &pod_spec.node_name {
@@ -368,7 +735,7 @@ fn annotate_from_pod_spec(
             log,
             legacy_key,
             path!("pod_node_name"),
-            value.to_owned(),
+            interner.intern(value),
         )
     }
 }
@@ -377,6 +744,7 @@ fn annotate_from_pod_status(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     pod_status: &PodStatus,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     if let Some(value) = &pod_status.pod_ip {
@@ -392,7 +760,7 @@ fn annotate_from_pod_status(
             log,
             legacy_key,
             path!("pod_ip"),
-            value.to_owned(),
+            interner.intern(value),
         )
     }
 
@@ -406,8 +774,9 @@ fn annotate_from_pod_status(
 
         let value = value
             .iter()
-            .filter_map(|k| k.ip.clone())
-            .collect::<Vec<String>>();
+            .filter_map(|k| k.ip.as_deref())
+            .map(|ip| interner.intern(ip))
+            .collect::<Vec<_>>();
 
         log_namespace.insert_source_metadata(Config::NAME, log, legacy_key, path!("pod_ips"), value)
     }
@@ -417,6 +786,7 @@ fn annotate_from_container_status(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     container_status: &ContainerStatus,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     if let Some(value) = &container_status.container_id {
@@ -432,12 +802,55 @@ fn annotate_from_container_status(
             log,
             legacy_key,
             path!("container_id"),
-            value.to_owned(),
+            interner.intern(value),
+        )
+    }
+
+    // The image is only reported once it was pulled, the metadata of the Pod is kept up to date
+    // by the reflector, so the later events get it.
+    if !container_status.image.is_empty() {
+        let legacy_key = fields_spec
+            .container_image
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            legacy_key,
+            path!("container_image"),
+            interner.intern(&container_status.image),
+        )
+    }
+
+    if !container_status.image_id.is_empty() {
+        let legacy_key = fields_spec
+            .container_image_id
+            .path
+            .as_ref()
+            .map(|k| &k.path)
+            .map(LegacyKey::Overwrite);
+
+        log_namespace.insert_source_metadata(
+            Config::NAME,
+            log,
+            legacy_key,
+            path!("container_image_id"),
+            interner.intern(&container_status.image_id),
         )
     }
+}
 
+fn annotate_container_kind(
+    log: &mut LogEvent,
+    fields_spec: &FieldsSpec,
+    container_kind: ContainerKind,
+    log_namespace: LogNamespace,
+) {
     let legacy_key = fields_spec
-        .container_image_id
+        .container_kind
         .path
         .as_ref()
         .map(|k| &k.path)
@@ -447,8 +860,8 @@ fn annotate_from_container_status(
         Config::NAME,
         log,
         legacy_key,
-        path!("container_image_id"),
-        container_status.image_id.to_owned(),
+        path!("container_kind"),
+        container_kind.as_str(),
     )
 }
 
@@ -456,6 +869,7 @@ fn annotate_from_container(
     log: &mut LogEvent,
     fields_spec: &FieldsSpec,
     container: &Container,
+    interner: &mut StringInterner,
     log_namespace: LogNamespace,
 ) {
     if let Some(value) = &container.image {
@@ -471,18 +885,23 @@ fn annotate_from_container(
             log,
             legacy_key,
             path!("container_image"),
-            value.to_owned(),
+            interner.intern(value),
         )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use k8s_openapi::api::core::v1::PodIP;
+    use std::time::Duration;
+
+    use futures::{channel::mpsc, SinkExt};
+    use k8s_openapi::api::core::v1::{EphemeralContainer, PodIP};
+    use kube::runtime::{reflector::store, watcher};
     use similar_asserts::assert_eq;
     use vector_lib::lookup::{event_path, metadata_path};
 
     use super::*;
+    use crate::kubernetes::{custom_reflector, meta_cache::MetaCache, reflector::InitialList};
 
     #[test]
     fn test_annotate_from_metadata() {
@@ -745,11 +1164,252 @@ mod tests {
 
         for (fields_spec, metadata, expected, log_namespace) in cases.into_iter() {
             let mut log = LogEvent::default();
-            annotate_from_metadata(&mut log, &fields_spec, &metadata, log_namespace);
+            annotate_from_metadata(
+                &mut log,
+                &fields_spec,
+                &metadata,
+                &MetadataKeys::default(),
+                &mut StringInterner::new(16),
+                log_namespace,
+            );
+            assert_eq!(log, expected);
+        }
+    }
+
+    #[test]
+    fn test_annotate_from_metadata_include_annotation_keys() {
+        let metadata = ObjectMeta {
+            annotations: Some(
+                vec![
+                    ("team.example.com/owner".to_owned(), "val0".to_owned()),
+                    ("sandbox0-annotation1".to_owned(), "val1".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..ObjectMeta::default()
+        };
+
+        let mut log = LogEvent::default();
+        annotate_from_metadata(
+            &mut log,
+            &FieldsSpec::default(),
+            &metadata,
+            &MetadataKeys {
+                annotations: Some(vec!["team.example.com/owner".to_owned()]),
+                ..MetadataKeys::default()
+            },
+            &mut StringInterner::new(16),
+            LogNamespace::Vector,
+        );
+
+        let mut expected = LogEvent::default();
+        expected.insert(
+            metadata_path!(
+                "kubernetes_logs",
+                "pod_annotations",
+                "team.example.com/owner"
+            ),
+            "val0",
+        );
+        assert_eq!(log, expected);
+    }
+
+    #[test]
+    fn test_annotate_from_metadata_include_keys() {
+        let metadata = ObjectMeta {
+            labels: Some(
+                vec![
+                    ("app.kubernetes.io/name".to_owned(), "val0".to_owned()),
+                    ("pod-template-hash".to_owned(), "val1".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            annotations: Some(
+                vec![("sandbox0-annotation0".to_owned(), "val2".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            ..ObjectMeta::default()
+        };
+
+        let cases = vec![
+            (
+                MetadataKeys {
+                    labels: Some(vec![
+                        "app.kubernetes.io/name".to_owned(),
+                        "missing".to_owned(),
+                    ]),
+                    ..MetadataKeys::default()
+                },
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(
+                        event_path!("kubernetes", "pod_labels", "app.kubernetes.io/name"),
+                        "val0",
+                    );
+                    log.insert(
+                        event_path!("kubernetes", "pod_annotations", "sandbox0-annotation0"),
+                        "val2",
+                    );
+                    log
+                },
+            ),
+            (
+                // Empty lists add none of them.
+                MetadataKeys {
+                    labels: Some(Vec::new()),
+                    annotations: Some(Vec::new()),
+                    flatten: false,
+                },
+                LogEvent::default(),
+            ),
+            (
+                MetadataKeys {
+                    labels: Some(vec!["app.kubernetes.io/name".to_owned()]),
+                    annotations: Some(Vec::new()),
+                    flatten: true,
+                },
+                {
+                    let mut log = LogEvent::default();
+                    log.insert(
+                        event_path!("kubernetes", "pod_labels.app.kubernetes.io/name"),
+                        "val0",
+                    );
+                    log
+                },
+            ),
+        ];
+
+        for (metadata_keys, expected) in cases {
+            let mut log = LogEvent::default();
+            annotate_from_metadata(
+                &mut log,
+                &FieldsSpec::default(),
+                &metadata,
+                &metadata_keys,
+                &mut StringInterner::new(16),
+                LogNamespace::Legacy,
+            );
             assert_eq!(log, expected);
         }
     }
 
+    #[test]
+    fn test_annotate_from_metadata_flatten() {
+        let metadata = ObjectMeta {
+            labels: Some(
+                vec![("app.kubernetes.io/name".to_owned(), "val0".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            annotations: Some(
+                vec![("team.example.com/owner".to_owned(), "val1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            ..ObjectMeta::default()
+        };
+        let metadata_keys = MetadataKeys {
+            flatten: true,
+            ..MetadataKeys::default()
+        };
+
+        let mut log = LogEvent::default();
+        annotate_from_metadata(
+            &mut log,
+            &FieldsSpec {
+                pod_labels: OwnedTargetPath::event(owned_value_path!("labels")).into(),
+                ..FieldsSpec::default()
+            },
+            &metadata,
+            &metadata_keys,
+            &mut StringInterner::new(16),
+            LogNamespace::Legacy,
+        );
+        let mut expected = LogEvent::default();
+        expected.insert(event_path!("labels.app.kubernetes.io/name"), "val0");
+        expected.insert(
+            event_path!("kubernetes", "pod_annotations.team.example.com/owner"),
+            "val1",
+        );
+        assert_eq!(log, expected);
+
+        let mut log = LogEvent::default();
+        annotate_from_metadata(
+            &mut log,
+            &FieldsSpec::default(),
+            &metadata,
+            &metadata_keys,
+            &mut StringInterner::new(16),
+            LogNamespace::Vector,
+        );
+        let mut expected = LogEvent::default();
+        expected.insert(
+            metadata_path!("kubernetes_logs", "pod_labels.app.kubernetes.io/name"),
+            "val0",
+        );
+        expected.insert(
+            metadata_path!("kubernetes_logs", "pod_annotations.team.example.com/owner"),
+            "val1",
+        );
+        assert_eq!(log, expected);
+    }
+
+    #[test]
+    fn test_flattened_path() {
+        assert_eq!(
+            flattened_path(&owned_value_path!("kubernetes", "pod_labels"), "app"),
+            owned_value_path!("kubernetes", "pod_labels.app")
+        );
+        assert_eq!(
+            flattened_path(&owned_value_path!("pod_labels"), "app.kubernetes.io/name"),
+            owned_value_path!("pod_labels.app.kubernetes.io/name")
+        );
+    }
+
+    #[test]
+    fn test_matches_pod_uid() {
+        let pod = |uid: &str, mirror_uid: Option<&str>| Pod {
+            metadata: ObjectMeta {
+                uid: Some(uid.to_owned()),
+                annotations: mirror_uid.map(|mirror_uid| {
+                    vec![(MIRROR_POD_ANNOTATION.to_owned(), mirror_uid.to_owned())]
+                        .into_iter()
+                        .collect()
+                }),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+
+        let cases = vec![
+            (pod("sandbox0-uid", None), "sandbox0-uid", true),
+            (pod("sandbox0-uid", None), "sandbox1-uid", false),
+            (pod("sandbox0-uid", None), "", true),
+            (
+                pod("sandbox0-uid", Some("static0-uid")),
+                "static0-uid",
+                true,
+            ),
+            (
+                pod("sandbox0-uid", Some("static0-uid")),
+                "static1-uid",
+                false,
+            ),
+        ];
+
+        for (pod, file_pod_uid, expected) in cases.into_iter() {
+            assert_eq!(
+                matches_pod_uid(&pod, file_pod_uid),
+                expected,
+                "{}",
+                file_pod_uid
+            );
+        }
+    }
+
     #[test]
     fn test_annotate_from_file_info() {
         let cases = vec![(
@@ -758,27 +1418,121 @@ mod tests {
             {
                 let mut log = LogEvent::default();
                 log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("kubernetes", "container_restart_count"), 1);
                 log
             },
             LogNamespace::Legacy,
         ),(
             FieldsSpec{
                 container_name: OwnedTargetPath::event(owned_value_path!("container_name")).into(),
+                container_restart_count: OwnedTargetPath::event(owned_value_path!("restart_count")).into(),
                 ..Default::default()
             },
             "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
             {
                 let mut log = LogEvent::default();
                 log.insert(event_path!("container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("restart_count"), 1);
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/3.log.20240101-000000",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("kubernetes", "container_restart_count"), 3);
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/2.log.20240101-000000.gz",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("kubernetes", "container_restart_count"), 2);
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/current.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/containers/sandbox0-name_sandbox0-ns_sandbox0-container0-name-8a3c1e.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
                 log
             },
             LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b_zone-a_pool0/sandbox0-container0-name/1.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("kubernetes", "container_restart_count"), 1);
+                log.insert(event_path!("kubernetes", "pod_dir_extra_segments"), vec!["zone-a", "pool0"]);
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec {
+                pod_dir_extra_segments: OptionalTargetPath::none(),
+                ..Default::default()
+            },
+            "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b_zone-a_pool0/sandbox0-container0-name/1.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(event_path!("kubernetes", "container_name"), "sandbox0-container0-name");
+                log.insert(event_path!("kubernetes", "container_restart_count"), 1);
+                log
+            },
+            LogNamespace::Legacy,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_8f7e2c1a-3b4d-4e5f-9a6b-7c8d9e0f1a2b_zone-a_pool0/sandbox0-container0-name/1.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(metadata_path!("kubernetes_logs", "container_name"), "sandbox0-container0-name");
+                log.insert(metadata_path!("kubernetes_logs", "container_restart_count"), 1);
+                log.insert(metadata_path!("kubernetes_logs", "pod_dir_extra_segments"), vec!["zone-a", "pool0"]);
+                log
+            },
+            LogNamespace::Vector,
+        ),(
+            FieldsSpec::default(),
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/1.log",
+            {
+                let mut log = LogEvent::default();
+                log.insert(metadata_path!("kubernetes_logs", "container_name"), "sandbox0-container0-name");
+                log.insert(metadata_path!("kubernetes_logs", "container_restart_count"), 1);
+                log
+            },
+            LogNamespace::Vector,
         )];
 
         for (fields_spec, file, expected, log_namespace) in cases.into_iter() {
             let mut log = LogEvent::default();
-            let file_info = parse_log_file_path(file).unwrap();
-            annotate_from_file_info(&mut log, &fields_spec, &file_info, log_namespace);
+            let file_info = LogLayout::Auto
+                .parse_log_file_path(file, DEFAULT_POD_DIR_DELIMITER)
+                .unwrap();
+            annotate_from_file_info(
+                &mut log,
+                &fields_spec,
+                &file_info,
+                &mut StringInterner::new(16),
+                log_namespace,
+            );
             assert_eq!(log, expected);
         }
     }
@@ -828,7 +1582,13 @@ mod tests {
 
         for (fields_spec, pod_spec, expected, log_namespace) in cases.into_iter() {
             let mut log = LogEvent::default();
-            annotate_from_pod_spec(&mut log, &fields_spec, &pod_spec, log_namespace);
+            annotate_from_pod_spec(
+                &mut log,
+                &fields_spec,
+                &pod_spec,
+                &mut StringInterner::new(16),
+                log_namespace,
+            );
             assert_eq!(log, expected);
         }
     }
@@ -936,7 +1696,13 @@ mod tests {
 
         for (fields_spec, pod_status, expected, log_namespace) in cases.into_iter() {
             let mut log = LogEvent::default();
-            annotate_from_pod_status(&mut log, &fields_spec, &pod_status, log_namespace);
+            annotate_from_pod_status(
+                &mut log,
+                &fields_spec,
+                &pod_status,
+                &mut StringInterner::new(16),
+                log_namespace,
+            );
             assert_eq!(log, expected);
         }
     }
@@ -947,9 +1713,26 @@ mod tests {
             (
                 FieldsSpec::default(),
                 ContainerStatus::default(),
+                LogEvent::default(),
+                LogNamespace::Legacy,
+            ),
+            (
+                FieldsSpec::default(),
+                ContainerStatus {
+                    image: "docker.io/library/nginx:1.25".to_owned(),
+                    image_id: "docker.io/library/nginx@sha256:4c0fdaa8b6341bfdeca5f18f7837462c80cff90527ee35ef185571e1c327beac".to_owned(),
+                    ..ContainerStatus::default()
+                },
                 {
                     let mut log = LogEvent::default();
-                    log.insert(event_path!("kubernetes", "container_image_id"), "");
+                    log.insert(
+                        event_path!("kubernetes", "container_image"),
+                        "docker.io/library/nginx:1.25",
+                    );
+                    log.insert(
+                        event_path!("kubernetes", "container_image_id"),
+                        "docker.io/library/nginx@sha256:4c0fdaa8b6341bfdeca5f18f7837462c80cff90527ee35ef185571e1c327beac",
+                    );
                     log
                 },
                 LogNamespace::Legacy,
@@ -984,6 +1767,7 @@ mod tests {
                 &mut log,
                 &fields_spec,
                 &container_status,
+                &mut StringInterner::new(16),
                 log_namespace,
             );
             assert_eq!(log, expected);
@@ -1036,8 +1820,373 @@ mod tests {
 
         for (fields_spec, container, expected, log_namespace) in cases.into_iter() {
             let mut log = LogEvent::default();
-            annotate_from_container(&mut log, &fields_spec, &container, log_namespace);
+            annotate_from_container(
+                &mut log,
+                &fields_spec,
+                &container,
+                &mut StringInterner::new(16),
+                log_namespace,
+            );
             assert_eq!(log, expected);
         }
     }
+
+    #[test]
+    fn test_container_kind() {
+        let pod_spec = PodSpec {
+            containers: vec![Container {
+                name: "sandbox0-container0-name".to_owned(),
+                ..Default::default()
+            }],
+            init_containers: Some(vec![Container {
+                name: "sandbox0-init0-name".to_owned(),
+                ..Default::default()
+            }]),
+            ephemeral_containers: Some(vec![EphemeralContainer {
+                name: "sandbox0-debug0-name".to_owned(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let cases = vec![
+            ("sandbox0-container0-name", Some(ContainerKind::Regular)),
+            ("sandbox0-init0-name", Some(ContainerKind::Init)),
+            ("sandbox0-debug0-name", Some(ContainerKind::Ephemeral)),
+            ("sandbox0-unknown-name", None),
+        ];
+
+        for (container_name, expected) in cases.into_iter() {
+            assert_eq!(ContainerKind::of(&pod_spec, container_name), expected);
+        }
+    }
+
+    #[test]
+    fn test_annotate_container_image_from_status() {
+        let pod = |container_status: ContainerStatus| Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "sandbox0-container0-name".to_owned(),
+                    image: Some("nginx".to_owned()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "sandbox0-container0-name".to_owned(),
+                    ..container_status
+                }]),
+                ..Default::default()
+            }),
+            ..Pod::default()
+        };
+        let file =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log";
+
+        // The image is still being pulled.
+        let mut store_w = store::Writer::default();
+        store_w.apply_watcher_event(&watcher::Event::Restarted(vec![pod(
+            ContainerStatus::default(),
+        )]));
+        let mut annotator = PodMetadataAnnotator::new(
+            store_w.as_reader(),
+            DelayedDeletions::default(),
+            None,
+            LogLayout::Auto,
+            FieldsSpec::default(),
+            MetadataKeys::default(),
+            LogNamespace::Legacy,
+        );
+
+        let mut event = Event::Log(LogEvent::default());
+        annotator.annotate(&mut event, file).unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_image")),
+            Some(&"nginx".into())
+        );
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_image_id")),
+            None
+        );
+
+        // The status is updated once the image was pulled.
+        store_w.apply_watcher_event(&watcher::Event::Applied(pod(ContainerStatus {
+            image: "docker.io/library/nginx:latest".to_owned(),
+            image_id: "docker.io/library/nginx@sha256:0d17b565c37bcbd895e9d92315a05c1c3c9a29f762b011a10c54a66cd53c9b31".to_owned(),
+            ..ContainerStatus::default()
+        })));
+
+        let mut event = Event::Log(LogEvent::default());
+        annotator.annotate(&mut event, file).unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_image")),
+            Some(&"docker.io/library/nginx:latest".into())
+        );
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_image_id")),
+            Some(&"docker.io/library/nginx@sha256:0d17b565c37bcbd895e9d92315a05c1c3c9a29f762b011a10c54a66cd53c9b31".into())
+        );
+    }
+
+    #[test]
+    fn test_annotate_strict_log_paths() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let file =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log";
+        let annotator = |strict_log_paths| {
+            let mut store_w = store::Writer::default();
+            store_w.apply_watcher_event(&watcher::Event::Restarted(vec![pod.clone()]));
+            PodMetadataAnnotator::new(
+                store_w.as_reader(),
+                DelayedDeletions::default(),
+                None,
+                LogLayout::Auto,
+                FieldsSpec::default(),
+                MetadataKeys::default(),
+                LogNamespace::Legacy,
+            )
+            .with_strict_log_paths(strict_log_paths)
+        };
+
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator(false).annotate(&mut event, file).is_some());
+
+        // The UID isn't well-formed, so the file is taken for a stray one.
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator(true).annotate(&mut event, file).is_none());
+        assert_eq!(
+            event.as_log().get(event_path!("kubernetes", "pod_name")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_annotate_init_container() {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some("sandbox0-uid".to_owned()),
+                ..ObjectMeta::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "sandbox0-container0-name".to_owned(),
+                    image: Some("sandbox0-container-image".to_owned()),
+                    ..Default::default()
+                }],
+                init_containers: Some(vec![Container {
+                    name: "sandbox0-init0-name".to_owned(),
+                    image: Some("sandbox0-init-image".to_owned()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Pod::default()
+        };
+        let mut store_w = store::Writer::default();
+        store_w.apply_watcher_event(&watcher::Event::Restarted(vec![pod]));
+
+        let mut annotator = PodMetadataAnnotator::new(
+            store_w.as_reader(),
+            DelayedDeletions::default(),
+            None,
+            LogLayout::Auto,
+            FieldsSpec::default(),
+            MetadataKeys::default(),
+            LogNamespace::Legacy,
+        );
+
+        let mut event = Event::Log(LogEvent::default());
+        annotator
+            .annotate(
+                &mut event,
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-init0-name/0.log",
+            )
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_kind")),
+            Some(&"init".into())
+        );
+        assert_eq!(
+            log.get(event_path!("kubernetes", "container_image")),
+            Some(&"sandbox0-init-image".into())
+        );
+
+        let mut event = Event::Log(LogEvent::default());
+        annotator
+            .annotate(
+                &mut event,
+                "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log",
+            )
+            .unwrap();
+        assert_eq!(
+            event
+                .as_log()
+                .get(event_path!("kubernetes", "container_kind")),
+            Some(&"regular".into())
+        );
+
+        // Without the Pod metadata, the kind is left out rather than guessed.
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator
+            .annotate(
+                &mut event,
+                "/var/log/pods/sandbox0-ns_sandbox1-name_sandbox1-uid/sandbox0-init0-name/0.log",
+            )
+            .is_none());
+        assert!(event
+            .as_log()
+            .get(event_path!("kubernetes", "container_kind"))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_annotate_after_pod_deletion() {
+        let pod = |uid: &str, label: &str| Pod {
+            metadata: ObjectMeta {
+                name: Some("sandbox0-name".to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                uid: Some(uid.to_owned()),
+                labels: Some(
+                    vec![("sandbox0-label0".to_owned(), label.to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let store_w = store::Writer::default();
+        let store = store_w.as_reader();
+        let delayed_deletions = DelayedDeletions::default();
+        let (mut tx, rx) = mpsc::channel::<_>(5);
+        tokio::spawn(custom_reflector(
+            store_w,
+            MetaCache::new(),
+            rx,
+            Duration::from_secs(2),
+            usize::MAX,
+            delayed_deletions.clone(),
+            InitialList::default(),
+        ));
+
+        // The Pod is deleted, and recreated under the same name before its last lines are read.
+        tx.send(Ok(watcher::Event::Applied(pod("sandbox0-uid", "val0"))))
+            .await
+            .unwrap();
+        tx.send(Ok(watcher::Event::Deleted(pod("sandbox0-uid", "val0"))))
+            .await
+            .unwrap();
+        tx.send(Ok(watcher::Event::Applied(pod("sandbox1-uid", "val1"))))
+            .await
+            .unwrap();
+
+        let mut annotator = PodMetadataAnnotator::new(
+            store,
+            delayed_deletions,
+            None,
+            LogLayout::Auto,
+            FieldsSpec::default(),
+            MetadataKeys::default(),
+            LogNamespace::Legacy,
+        );
+        let file =
+            "/var/log/pods/sandbox0-ns_sandbox0-name_sandbox0-uid/sandbox0-container0-name/0.log";
+
+        // Late events of the deleted Pod are still annotated during the grace period.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator.annotate(&mut event, file).is_some());
+        let log = event.as_log();
+        assert_eq!(
+            log.get(event_path!("kubernetes", "pod_uid")),
+            Some(&"sandbox0-uid".into())
+        );
+        assert_eq!(
+            log.get(event_path!("kubernetes", "pod_labels", "sandbox0-label0")),
+            Some(&"val0".into())
+        );
+
+        // Once the grace period elapsed, the metadata of the deleted Pod is evicted.
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator.annotate(&mut event, file).is_none());
+    }
+
+    #[test]
+    fn test_annotate_static_pod_uid_mismatch() {
+        let pod = |mirror_uid: Option<&str>| Pod {
+            metadata: ObjectMeta {
+                name: Some("kube-apiserver-node0".to_owned()),
+                namespace: Some("kube-system".to_owned()),
+                uid: Some("api-uid".to_owned()),
+                labels: Some(
+                    vec![("component".to_owned(), "kube-apiserver".to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                annotations: mirror_uid.map(|mirror_uid| {
+                    vec![(MIRROR_POD_ANNOTATION.to_owned(), mirror_uid.to_owned())]
+                        .into_iter()
+                        .collect()
+                }),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        };
+        let annotator = |pod: Pod| {
+            let mut store_w = store::Writer::default();
+            store_w.apply_watcher_event(&watcher::Event::Restarted(vec![pod]));
+            PodMetadataAnnotator::new(
+                store_w.as_reader(),
+                DelayedDeletions::default(),
+                None,
+                LogLayout::Auto,
+                FieldsSpec::default(),
+                MetadataKeys::default(),
+                LogNamespace::Legacy,
+            )
+        };
+        // The UID of the directory differs from both the UID of the mirror Pod, and the one in its
+        // annotation.
+        let file = "/var/log/pods/kube-system_kube-apiserver-node0_disk-uid/kube-apiserver/0.log";
+
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator(pod(Some("manifest-uid")))
+            .annotate(&mut event, file)
+            .is_some());
+        let log = event.as_log();
+        assert_eq!(
+            log.get(event_path!("kubernetes", "pod_labels", "component")),
+            Some(&"kube-apiserver".into())
+        );
+        assert_eq!(
+            log.get(event_path!("kubernetes", "pod_uid")),
+            Some(&"api-uid".into())
+        );
+
+        // A Pod that isn't static may have been recreated under the same name, so it still has to
+        // match the UID.
+        let mut event = Event::Log(LogEvent::default());
+        assert!(annotator(pod(None)).annotate(&mut event, file).is_none());
+    }
 }
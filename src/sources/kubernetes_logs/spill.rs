@@ -0,0 +1,273 @@
+//! Spilling of the events of the source to a disk buffer, while the components downstream apply
+//! backpressure.
+
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::Path,
+    time::Instant,
+};
+
+use futures::{Stream, StreamExt};
+use vector_lib::buffers::{
+    topology::channel::{BufferReceiver, BufferSender},
+    BufferConfig, BufferType, WhenFull,
+};
+use vector_lib::configurable::configurable_component;
+
+use crate::{
+    event::{Event, EventArray, LogArray},
+    source_sender::ClosedError,
+    SourceSender,
+};
+
+/// The ID of the disk buffer, under the data directory of the source.
+const BUFFER_ID: &str = "spill";
+
+/// The maximum number of events written to the buffer at once.
+const MAX_BATCH_EVENTS: usize = 1000;
+
+/// Configuration for spilling the events to a disk buffer when the components downstream can't
+/// keep up.
+///
+/// The events are held in memory up to `max_events`, and written to a disk buffer under the
+/// `data_dir` of the source past that, so a burst of logs on a busy Node uses disk space instead
+/// of memory. This is independent of the `buffer` of the sinks.
+///
+/// The source doesn't support end-to-end acknowledgements, so the checkpoints of the log files
+/// still advance as their lines are read. The events on disk are kept across restarts, and sent
+/// once the source starts again. With acknowledgements enabled on the sinks, the events are only
+/// removed from the disk buffer once they are delivered.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SpillBufferConfig {
+    /// The maximum number of events held in memory before they are spilled to disk.
+    #[serde(default = "default_max_events")]
+    #[configurable(metadata(docs::type_unit = "events"))]
+    max_events: NonZeroUsize,
+
+    /// The maximum size of the disk buffer.
+    ///
+    /// Must be at least ~256 megabytes (268435488 bytes). Once the disk buffer is full, the
+    /// source stops reading the log files until the components downstream catch up.
+    #[configurable(
+        validation(range(min = 268435488)),
+        metadata(docs::type_unit = "bytes")
+    )]
+    max_size: NonZeroU64,
+}
+
+fn default_max_events() -> NonZeroUsize {
+    NonZeroUsize::new(10_000).unwrap()
+}
+
+impl SpillBufferConfig {
+    /// The memory stage overflowing into the disk stage.
+    fn buffer_config(&self) -> BufferConfig {
+        BufferConfig::Chained(vec![
+            BufferType::Memory {
+                max_events: self.max_events,
+                when_full: WhenFull::Overflow,
+            },
+            BufferType::DiskV2 {
+                max_size: self.max_size,
+                when_full: WhenFull::Block,
+            },
+        ])
+    }
+
+    /// Builds the buffer, keeping its disk stage under `data_dir`.
+    pub(super) async fn build(&self, data_dir: &Path) -> crate::Result<SpillBuffer> {
+        let (tx, rx) = self
+            .buffer_config()
+            .build(
+                Some(data_dir.to_path_buf()),
+                BUFFER_ID.to_owned(),
+                error_span!("spill_buffer", buffer_type = "disk"),
+            )
+            .await?;
+        Ok(SpillBuffer { tx, rx })
+    }
+}
+
+/// The built buffer the events go through on their way out of the source.
+pub(super) struct SpillBuffer {
+    tx: BufferSender<EventArray>,
+    rx: BufferReceiver<EventArray>,
+}
+
+impl SpillBuffer {
+    /// Sends the events to `out` through the buffer, until the events end and the buffer is
+    /// drained, or `out` is closed.
+    pub(super) async fn send_event_stream<S>(
+        self,
+        events: S,
+        out: &mut SourceSender,
+    ) -> Result<(), ClosedError>
+    where
+        S: Stream<Item = Event> + Unpin,
+    {
+        let Self { mut tx, rx } = self;
+
+        let fill = async move {
+            let mut batches = events.ready_chunks(MAX_BATCH_EVENTS);
+            while let Some(batch) = batches.next().await {
+                let logs: LogArray = batch.into_iter().map(Event::into_log).collect();
+                let sent = tx.send(EventArray::from(logs), Some(Instant::now())).await;
+                if sent.is_err() || tx.flush().await.is_err() {
+                    break;
+                }
+            }
+            // The buffer is closed once the sender is dropped, so the drain below ends once the
+            // buffered events are sent.
+        };
+        let drain = async move {
+            let mut arrays = rx.into_stream();
+            while let Some(array) = arrays.next().await {
+                out.send_event(array).await?;
+            }
+            Ok(())
+        };
+
+        let ((), result) = futures::join!(fill, drain);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{channel::mpsc, stream, SinkExt};
+    use tokio::time::{sleep, timeout};
+
+    use super::*;
+    use crate::{
+        event::{EventContainer, LogEvent},
+        test_util::collect_n,
+    };
+
+    fn config(max_events: usize) -> SpillBufferConfig {
+        SpillBufferConfig {
+            max_events: NonZeroUsize::new(max_events).unwrap(),
+            max_size: NonZeroU64::new(268435488).unwrap(),
+        }
+    }
+
+    fn events(count: usize) -> Vec<Event> {
+        (0..count)
+            .map(|i| Event::from(LogEvent::from(format!("line {}", i))))
+            .collect()
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        let mut messages: Vec<_> = events
+            .iter()
+            .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+            .collect();
+        messages.sort();
+        messages
+    }
+
+    /// The size of the data files of the disk buffer.
+    fn disk_usage(data_dir: &Path) -> u64 {
+        let buffer_dir = data_dir.join("buffer").join("v2").join(BUFFER_ID);
+        std::fs::read_dir(buffer_dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".dat"))
+            .map(|entry| entry.metadata().unwrap().len())
+            .sum()
+    }
+
+    #[test]
+    fn parse_config() {
+        let config: SpillBufferConfig = toml::from_str("max_size = 268435488").unwrap();
+        assert_eq!(config.max_events, default_max_events());
+        assert_eq!(config.buffer_config().stages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn spills_to_disk_when_memory_is_full() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let SpillBuffer { mut tx, rx } = config(10).build(data_dir.path()).await.unwrap();
+
+        // Nothing is read from the buffer, so the events past the first 10 can only be on disk.
+        let sent = events(100);
+        for event in sent.clone() {
+            tx.send(EventArray::from(vec![event.into_log()]), None)
+                .await
+                .unwrap();
+        }
+        tx.flush().await.unwrap();
+        assert!(disk_usage(data_dir.path()) > 0);
+        drop(tx);
+
+        let received: Vec<Event> = rx
+            .into_stream()
+            .flat_map(|array| stream::iter(array.into_events()))
+            .collect()
+            .await;
+        assert_eq!(messages(&received), messages(&sent));
+    }
+
+    #[tokio::test]
+    async fn sends_events_through_buffer() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let buffer = config(10).build(data_dir.path()).await.unwrap();
+        let (mut out, rx) = SourceSender::new_test();
+
+        let sent = events(1000);
+        let events = stream::iter(sent.clone());
+        tokio::spawn(async move { buffer.send_event_stream(events, &mut out).await });
+
+        let received = collect_n(rx, sent.len()).await;
+        assert_eq!(messages(&received), messages(&sent));
+    }
+
+    #[tokio::test]
+    async fn keeps_reading_while_output_is_blocked() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let buffer = config(10).build(data_dir.path()).await.unwrap();
+        // The output holds a single batch, and isn't read from until all the lines are.
+        let (mut out, rx) = SourceSender::new_test_sender_with_buffer(1);
+
+        // The lines come through a bounded channel, like from the file server, so the reads are
+        // held back as soon as the events aren't taken in.
+        let (mut lines_tx, lines_rx) = mpsc::channel::<Event>(2);
+        let source =
+            tokio::spawn(async move { buffer.send_event_stream(lines_rx, &mut out).await });
+
+        let sent = events(5000);
+        timeout(Duration::from_secs(30), async {
+            for event in sent.clone() {
+                lines_tx.send(event).await.unwrap();
+            }
+        })
+        .await
+        .expect("The reads are held back by the blocked output.");
+        drop(lines_tx);
+
+        // The events past the memory stage and the output are on disk.
+        timeout(Duration::from_secs(30), async {
+            while disk_usage(data_dir.path()) == 0 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("The events are not spilled to disk.");
+
+        // The output catches up slowly, and gets every event.
+        let received: Vec<Event> = rx
+            .into_stream()
+            .then(|item| async move {
+                sleep(Duration::from_millis(1)).await;
+                stream::iter(item.events.into_events())
+            })
+            .flatten()
+            .collect()
+            .await;
+        assert_eq!(messages(&received), messages(&sent));
+        assert!(source.await.unwrap().is_ok());
+    }
+}
@@ -0,0 +1,219 @@
+//! Reconnection of the Kubernetes API watch streams feeding the metadata
+//! stores.
+//!
+//! A watch stream yields an error whenever the API server can't be reached,
+//! and could end if its connection is dropped. Either way, the stream is
+//! polled again, or recreated, after a bounded exponential backoff with
+//! jitter, so the metadata stores catch up as soon as the API server is back.
+//! In the meantime, the events are passed through with whatever metadata the
+//! stores still hold, or none.
+
+#![deny(missing_docs)]
+
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use kube::runtime::watcher;
+use rand::Rng;
+
+use crate::internal_events::KubernetesLogsWatchReconnect;
+
+/// The delay before the first reconnection.
+const INITIAL_DELAY: Duration = Duration::from_millis(800);
+
+/// The maximum delay between the reconnections.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Bounded exponential backoff with jitter between the reconnections of a
+/// watch stream.
+///
+/// The delay doubles with each reconnection, up to the maximum, and a random
+/// delay of up to half of it is shaved off, so the agents of a cluster don't
+/// all reconnect at once.
+#[derive(Clone, Debug)]
+pub struct WatchBackoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Default for WatchBackoff {
+    fn default() -> Self {
+        Self::new(INITIAL_DELAY, MAX_DELAY)
+    }
+}
+
+impl WatchBackoff {
+    /// Creates a backoff starting at `initial` and bounded by `max`.
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+
+    /// The delay before the next reconnection.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current.min(self.max);
+        self.current = delay.saturating_mul(2).min(self.max);
+        let jitter = rand::thread_rng().gen_range(0.0..=0.5);
+        delay.mul_f64(1.0 - jitter)
+    }
+
+    /// Starts over from the initial delay, once the stream yields events again.
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+/// Watches the `resource` with the streams created by `connect`, reconnecting
+/// after an error or the end of a stream with the default [`WatchBackoff`].
+///
+/// The errors are still yielded, so the consumers can tell the stream is
+/// disconnected.
+pub fn reconnecting<K, S, F>(
+    resource: &'static str,
+    connect: F,
+) -> impl Stream<Item = watcher::Result<watcher::Event<K>>>
+where
+    S: Stream<Item = watcher::Result<watcher::Event<K>>>,
+    F: FnMut() -> S,
+{
+    reconnecting_with_backoff(resource, connect, WatchBackoff::default())
+}
+
+fn reconnecting_with_backoff<K, S, F>(
+    resource: &'static str,
+    mut connect: F,
+    mut backoff: WatchBackoff,
+) -> impl Stream<Item = watcher::Result<watcher::Event<K>>>
+where
+    S: Stream<Item = watcher::Result<watcher::Event<K>>>,
+    F: FnMut() -> S,
+{
+    async_stream::stream! {
+        loop {
+            let stream = connect();
+            futures::pin_mut!(stream);
+            while let Some(result) = stream.next().await {
+                match result {
+                    Ok(event) => {
+                        backoff.reset();
+                        yield Ok(event);
+                    }
+                    Err(error) => {
+                        yield Err(error);
+                        // The watcher starts over on the next poll.
+                        let delay = backoff.next_delay();
+                        emit!(KubernetesLogsWatchReconnect { resource, delay });
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+
+            // The stream was dropped, so it is recreated.
+            let delay = backoff.next_delay();
+            emit!(KubernetesLogsWatchReconnect { resource, delay });
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use futures::stream::{self, BoxStream};
+    use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::ObjectMeta};
+    use kube::runtime::reflector::{store, ObjectRef};
+
+    use super::*;
+    use crate::kubernetes::{
+        custom_reflector,
+        meta_cache::MetaCache,
+        reflector::{DelayedDeletions, InitialList},
+    };
+
+    fn pod(name: &str) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some("sandbox0-ns".to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..Pod::default()
+        }
+    }
+
+    #[test]
+    fn backoff_is_bounded_with_jitter() {
+        let mut backoff = WatchBackoff::new(Duration::from_millis(100), Duration::from_millis(400));
+
+        for expected in [100, 200, 400, 400] {
+            let delay = backoff.next_delay();
+            assert!(
+                delay >= Duration::from_millis(expected / 2)
+                    && delay <= Duration::from_millis(expected),
+                "{:?} not within the jitter of {}ms",
+                delay,
+                expected
+            );
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay() <= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn reconnects_dropped_watch_stream() {
+        let a = pod("sandbox0-a");
+        let b = pod("sandbox0-b");
+
+        // The first stream is dropped after its initial list, the second one
+        // fails once before resuming, and the last one stays connected.
+        let mut streams: VecDeque<BoxStream<'static, watcher::Result<watcher::Event<Pod>>>> =
+            VecDeque::from([
+                stream::iter(vec![Ok(watcher::Event::Restarted(vec![a.clone()]))]).boxed(),
+                stream::iter(vec![
+                    Err(watcher::Error::TooManyObjects),
+                    Ok(watcher::Event::Applied(b.clone())),
+                ])
+                .boxed(),
+            ]);
+        let connects = Arc::new(AtomicUsize::new(0));
+        let connect = {
+            let connects = Arc::clone(&connects);
+            move || {
+                connects.fetch_add(1, Ordering::Relaxed);
+                streams
+                    .pop_front()
+                    .unwrap_or_else(|| stream::pending().boxed())
+            }
+        };
+        let backoff = WatchBackoff::new(Duration::from_millis(10), Duration::from_millis(20));
+
+        let store_w = store::Writer::default();
+        let store = store_w.as_reader();
+        tokio::spawn(custom_reflector(
+            store_w,
+            MetaCache::new(),
+            reconnecting_with_backoff("pod", connect, backoff),
+            Duration::from_secs(1),
+            usize::MAX,
+            DelayedDeletions::default(),
+            InitialList::default(),
+        ));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        assert_eq!(connects.load(Ordering::Relaxed), 3);
+        assert_eq!(store.get(&ObjectRef::from_obj(&a)).as_deref(), Some(&a));
+        assert_eq!(store.get(&ObjectRef::from_obj(&b)).as_deref(), Some(&b));
+    }
+}
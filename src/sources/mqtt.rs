@@ -0,0 +1,810 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::StreamExt;
+use rumqttc::{
+    AsyncClient, Event as MqttEvent, MqttOptions, Packet, Publish, QoS, SubscribeFilter,
+    SubscribeReasonCode, TlsConfiguration, Transport,
+};
+use snafu::{ResultExt, Snafu};
+use tokio_util::codec::FramedRead;
+use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
+use vector_lib::configurable::configurable_component;
+use vector_lib::finalizer::OrderedFinalizer;
+use vector_lib::internal_event::{
+    ByteSize, BytesReceived, CountByteSize, EventsReceived, InternalEventHandle as _, Protocol,
+    Registered,
+};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path};
+use vector_lib::sensitive_string::SensitiveString;
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::value::Kind;
+
+use crate::{
+    codecs::{Decoder, DecodingConfig},
+    config::{
+        GenerateConfig, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        MqttAckError, MqttConnectionError, MqttConnectionState, MqttSubscribeError,
+        StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based, default_true},
+    shutdown::ShutdownSignal,
+    tls::{TlsEnableableConfig, TlsError, TlsSettings},
+    SourceSender,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one topic must be set"))]
+    NoTopics,
+    #[snafu(display("Invalid topic filter: {:?}", filter))]
+    InvalidTopic { filter: String },
+    #[snafu(display("Invalid client ID: {:?}", client_id))]
+    InvalidClientId { client_id: String },
+    #[snafu(display("`client_id` must be set when `clean_start` is disabled"))]
+    PersistentSessionWithoutClientId,
+    #[snafu(display("`password` can't be set without `user`"))]
+    PasswordWithoutUser,
+    #[snafu(display("MQTT TLS Error: {}", source))]
+    Tls { source: TlsError },
+}
+
+/// Configuration for the `mqtt` source.
+#[configurable_component(source("mqtt", "Read observability data from topics of an MQTT broker."))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct MqttSourceConfig {
+    /// The host of the MQTT broker to connect to.
+    #[configurable(metadata(docs::examples = "mqtt.example.com"))]
+    #[configurable(metadata(docs::examples = "127.0.0.1"))]
+    host: String,
+
+    /// The port of the MQTT broker to connect to.
+    #[serde(default = "default_port")]
+    #[derivative(Default(value = "default_port()"))]
+    #[configurable(metadata(docs::examples = 1883))]
+    #[configurable(metadata(docs::examples = 8883))]
+    port: u16,
+
+    /// The username to authenticate with.
+    #[configurable(metadata(docs::examples = "vector"))]
+    user: Option<String>,
+
+    /// The password to authenticate with.
+    password: Option<SensitiveString>,
+
+    /// The client ID to connect with.
+    ///
+    /// The broker keeps the session of the client, including its subscriptions and the messages
+    /// it hasn't acknowledged yet, under this ID, so it must be set when `clean_start` is
+    /// disabled. Defaults to a random ID of the form `vector-<random>`, which is new each time
+    /// Vector starts.
+    ///
+    /// Some brokers only accept client IDs of up to 23 characters.
+    #[configurable(metadata(docs::examples = "vector-edge-1"))]
+    client_id: Option<String>,
+
+    /// Whether to start a new session each time the source connects to the broker.
+    ///
+    /// When disabled, the broker resumes the session of the `client_id` instead, and delivers the
+    /// messages published to its topics while the source was disconnected, as well as the
+    /// messages it hasn't acknowledged, as long as they were published with a QoS of at least 1.
+    #[serde(default = "default_true")]
+    #[derivative(Default(value = "true"))]
+    clean_start: bool,
+
+    /// The topic filters to subscribe to.
+    ///
+    /// The filters can include the `+` single-level and `#` multi-level wildcards. They are
+    /// subscribed to again each time the source reconnects to the broker.
+    #[configurable(metadata(docs::examples = "sensors/+/temp"))]
+    #[configurable(metadata(docs::examples = "devices/#"))]
+    topics: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    qos: MqttQoS,
+
+    /// The maximum size, in bytes, of the packets received from the broker.
+    ///
+    /// The connection is dropped, and reestablished, when a larger message is received.
+    #[serde(default = "default_max_packet_size")]
+    #[derivative(Default(value = "default_max_packet_size()"))]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_packet_size: usize,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    pub log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+
+    #[configurable(derived)]
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+
+    /// The log field to add the topic of the message to.
+    ///
+    /// This is the concrete topic the message was published to, not the filter it matched.
+    #[serde(default = "default_topic_key")]
+    #[derivative(Default(value = "default_topic_key()"))]
+    topic_key: OptionalValuePath,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+}
+
+/// The quality of service to subscribe to the topics with.
+///
+/// The messages are delivered with the lower of this QoS and the one they were published with.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttQoS {
+    /// QoS 0: the messages are delivered at most once, and lost if the connection drops.
+    AtMostOnce,
+
+    /// QoS 1: the messages are delivered at least once.
+    ///
+    /// The messages are acknowledged once their events are delivered, with end-to-end
+    /// acknowledgements, or once they are sent otherwise. The broker redelivers the messages that
+    /// weren't acknowledged when the session is resumed.
+    #[default]
+    AtLeastOnce,
+}
+
+impl From<MqttQoS> for QoS {
+    fn from(qos: MqttQoS) -> Self {
+        match qos {
+            MqttQoS::AtMostOnce => QoS::AtMostOnce,
+            MqttQoS::AtLeastOnce => QoS::AtLeastOnce,
+        }
+    }
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+const fn default_max_packet_size() -> usize {
+    1024 * 1024
+}
+
+fn default_topic_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("topic"))
+}
+
+/// The capacity of the channel of the requests, like the acknowledgements, sent to the broker.
+const CLIENT_CAPACITY: usize = 1024;
+
+/// The delay before reconnecting to the broker, after the connection failed or dropped.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+impl GenerateConfig for MqttSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            host = "127.0.0.1"
+            port = 1883
+            topics = ["vector/#"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mqtt")]
+impl SourceConfig for MqttSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        let decoder =
+            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+                .build()?;
+        let options = self.options()?;
+        let acknowledgements =
+            cx.do_acknowledgements(self.acknowledgements) && self.qos == MqttQoS::AtLeastOnce;
+
+        Ok(Box::pin(mqtt_source(
+            self.clone(),
+            options,
+            decoder,
+            log_namespace,
+            acknowledgements,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let schema_definition = self
+            .decoding
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                MqttSourceConfig::NAME,
+                self.topic_key.path.clone().map(LegacyKey::InsertIfEmpty),
+                &owned_value_path!("topic"),
+                Kind::bytes(),
+                None,
+            );
+
+        vec![SourceOutput::new_logs(
+            self.decoding.output_type(),
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl MqttSourceConfig {
+    /// The options of the client, with the session, the authentication and the TLS settings of
+    /// the configuration.
+    fn options(&self) -> Result<MqttOptions, BuildError> {
+        if self.topics.is_empty() {
+            return Err(BuildError::NoTopics);
+        }
+        if let Some(filter) = self
+            .topics
+            .iter()
+            .find(|filter| !rumqttc::valid_filter(filter))
+        {
+            return Err(BuildError::InvalidTopic {
+                filter: filter.clone(),
+            });
+        }
+
+        let client_id = match &self.client_id {
+            Some(client_id) if client_id.is_empty() || client_id.starts_with(' ') => {
+                return Err(BuildError::InvalidClientId {
+                    client_id: client_id.clone(),
+                })
+            }
+            Some(client_id) => client_id.clone(),
+            None if !self.clean_start => return Err(BuildError::PersistentSessionWithoutClientId),
+            None => default_client_id(),
+        };
+
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options
+            .set_clean_session(self.clean_start)
+            .set_manual_acks(true)
+            .set_max_packet_size(self.max_packet_size, self.max_packet_size);
+
+        match (&self.user, &self.password) {
+            (Some(user), password) => {
+                let password = password
+                    .as_ref()
+                    .map(|password| password.inner().to_owned())
+                    .unwrap_or_default();
+                options.set_credentials(user, password);
+            }
+            (None, Some(_)) => return Err(BuildError::PasswordWithoutUser),
+            (None, None) => {}
+        }
+
+        if let Some(tls) = self.tls.as_ref().filter(|tls| tls.enabled.unwrap_or(false)) {
+            let settings =
+                TlsSettings::from_options(&Some(tls.options.clone())).context(TlsSnafu)?;
+            let client_auth = settings.identity_pem();
+            let ca: Vec<u8> = settings.authorities_pem().flatten().collect();
+            // Without a CA file or a client certificate, the broker is verified against the
+            // system's root certificates.
+            let configuration =
+                if ca.is_empty() && client_auth.is_none() {
+                    TlsConfiguration::default()
+                } else {
+                    TlsConfiguration::Simple {
+                        ca,
+                        alpn: tls.options.alpn_protocols.clone().map(|protocols| {
+                            protocols.into_iter().map(String::into_bytes).collect()
+                        }),
+                        client_auth,
+                    }
+                };
+            options.set_transport(Transport::tls_with_config(configuration));
+        }
+
+        Ok(options)
+    }
+
+    fn subscribe_filters(&self) -> Vec<SubscribeFilter> {
+        self.topics
+            .iter()
+            .map(|filter| SubscribeFilter::new(filter.clone(), self.qos.into()))
+            .collect()
+    }
+}
+
+/// A random client ID, short enough for the brokers limiting them to 23 characters.
+fn default_client_id() -> String {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    format!("vector-{}", &id[..16])
+}
+
+async fn mqtt_source(
+    config: MqttSourceConfig,
+    options: MqttOptions,
+    decoder: Decoder,
+    log_namespace: LogNamespace,
+    acknowledgements: bool,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let (client, mut eventloop) = AsyncClient::new(options, CLIENT_CAPACITY);
+    // The broker expects the messages to be acknowledged in the order they were received.
+    let (finalizer, mut ack_stream) =
+        OrderedFinalizer::<Publish>::maybe_new(acknowledgements, Some(shutdown.clone()));
+    let filters = config.subscribe_filters();
+    let events_received = register!(EventsReceived);
+    let bytes_received = register!(BytesReceived::from(Protocol::TCP));
+
+    let mut connected = false;
+    emit!(MqttConnectionState { connected });
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => {
+                if let Some((status, publish)) = entry {
+                    handle_ack(&client, status, publish);
+                }
+            },
+            event = eventloop.poll() => match event {
+                Ok(MqttEvent::Incoming(Packet::ConnAck(_))) => {
+                    connected = true;
+                    emit!(MqttConnectionState { connected });
+                    // The subscriptions don't survive a clean session, so they are renewed on
+                    // each connection.
+                    if let Err(error) = client.try_subscribe_many(filters.clone()) {
+                        emit!(MqttSubscribeError {
+                            error: error.to_string()
+                        });
+                    }
+                }
+                Ok(MqttEvent::Incoming(Packet::SubAck(suback))) => {
+                    if suback
+                        .return_codes
+                        .iter()
+                        .any(|code| matches!(code, SubscribeReasonCode::Failure))
+                    {
+                        emit!(MqttSubscribeError {
+                            error: "The broker rejected some of the topic filters.".to_owned()
+                        });
+                    }
+                }
+                Ok(MqttEvent::Incoming(Packet::Publish(publish))) => {
+                    bytes_received.emit(ByteSize(publish.payload.len()));
+                    let events = decode_message(
+                        &config,
+                        &decoder,
+                        log_namespace,
+                        &publish,
+                        &events_received,
+                    )
+                    .await;
+                    send_message_events(events, publish, &client, finalizer.as_ref(), &mut out)
+                        .await?;
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    emit!(MqttConnectionError { error });
+                    if connected {
+                        connected = false;
+                        emit!(MqttConnectionState { connected });
+                    }
+                    // The event loop reconnects on the next poll.
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = tokio::time::sleep(RECONNECT_DELAY) => {},
+                    }
+                }
+            },
+        }
+    }
+
+    if connected {
+        emit!(MqttConnectionState { connected: false });
+    }
+    Ok(())
+}
+
+/// Decodes the events of the message, adding the source metadata to them.
+async fn decode_message(
+    config: &MqttSourceConfig,
+    decoder: &Decoder,
+    log_namespace: LogNamespace,
+    publish: &Publish,
+    events_received: &Registered<EventsReceived>,
+) -> Vec<Event> {
+    let mut decoded = Vec::new();
+    let mut stream = FramedRead::new(publish.payload.as_ref(), decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                let count = events.len();
+                let byte_size = events.estimated_json_encoded_size_of();
+                events_received.emit(CountByteSize(count, byte_size));
+
+                let now = Utc::now();
+
+                decoded.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log_namespace.insert_standard_vector_source_metadata(
+                            log,
+                            MqttSourceConfig::NAME,
+                            now,
+                        );
+                        log_namespace.insert_source_metadata(
+                            MqttSourceConfig::NAME,
+                            log,
+                            config.topic_key.path.as_ref().map(LegacyKey::InsertIfEmpty),
+                            path!("topic"),
+                            publish.topic.as_str(),
+                        );
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+    decoded
+}
+
+/// Sends the events of the message, acknowledging it once they are delivered
+/// with end-to-end acknowledgements, or once they are sent without.
+async fn send_message_events(
+    events: Vec<Event>,
+    publish: Publish,
+    client: &AsyncClient,
+    finalizer: Option<&OrderedFinalizer<Publish>>,
+    out: &mut SourceSender,
+) -> Result<(), ()> {
+    let count = events.len();
+    match finalizer {
+        Some(finalizer) => {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let events = events
+                .into_iter()
+                .map(|event| event.with_batch_notifier(&batch));
+            out.send_batch(events).await.map_err(|_| {
+                emit!(StreamClosedError { count });
+            })?;
+            finalizer.add(publish, receiver);
+        }
+        None => {
+            out.send_batch(events).await.map_err(|_| {
+                emit!(StreamClosedError { count });
+            })?;
+            if let Err(error) = client.try_ack(&publish) {
+                emit!(MqttAckError { error });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Acknowledges the message once its events are delivered.
+///
+/// MQTT has no negative acknowledgements, so the messages whose events weren't delivered are left
+/// unacknowledged, for the broker to redeliver them when the session is resumed.
+fn handle_ack(client: &AsyncClient, status: BatchStatus, publish: Publish) {
+    if status == BatchStatus::Delivered {
+        if let Err(error) = client.try_ack(&publish) {
+            emit!(MqttAckError { error });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::lookup::{owned_value_path, OwnedTargetPath};
+    use vector_lib::schema::Definition;
+    use vrl::value::{kind::Collection, Kind};
+
+    use super::*;
+
+    fn config(toml: &str) -> MqttSourceConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MqttSourceConfig>();
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = config(
+            r#"
+            host = "mqtt.example.com"
+            topics = ["sensors/+/temp", "devices/#"]
+            "#,
+        );
+        assert_eq!(config.port, 1883);
+        assert!(config.clean_start);
+        assert_eq!(config.qos, MqttQoS::AtLeastOnce);
+        assert_eq!(config.topic_key, default_topic_key());
+        assert_eq!(
+            config.subscribe_filters(),
+            vec![
+                SubscribeFilter::new("sensors/+/temp".to_owned(), QoS::AtLeastOnce),
+                SubscribeFilter::new("devices/#".to_owned(), QoS::AtLeastOnce),
+            ]
+        );
+
+        let options = config.options().unwrap();
+        assert!(options.clean_session());
+        assert!(options.client_id().starts_with("vector-"));
+        assert!(options.client_id().len() <= 23);
+    }
+
+    #[test]
+    fn options_persistent_session() {
+        let options = config(
+            r#"
+            host = "mqtt.example.com"
+            topics = ["devices/#"]
+            client_id = "vector-edge-1"
+            clean_start = false
+            user = "vector"
+            password = "secret"
+            "#,
+        )
+        .options()
+        .unwrap();
+        assert!(!options.clean_session());
+        assert_eq!(options.client_id(), "vector-edge-1");
+        assert_eq!(
+            options.credentials(),
+            Some(("vector".to_owned(), "secret".to_owned()))
+        );
+    }
+
+    #[test]
+    fn options_are_validated() {
+        let error = |toml: &str| config(toml).options().unwrap_err().to_string();
+
+        assert_eq!(
+            error(
+                r#"
+                host = "localhost"
+                topics = []
+                "#
+            ),
+            "At least one topic must be set"
+        );
+        assert_eq!(
+            error(
+                r#"
+                host = "localhost"
+                topics = ["devices/#/temp"]
+                "#
+            ),
+            "Invalid topic filter: \"devices/#/temp\""
+        );
+        assert_eq!(
+            error(
+                r#"
+                host = "localhost"
+                topics = ["devices/#"]
+                clean_start = false
+                "#
+            ),
+            "`client_id` must be set when `clean_start` is disabled"
+        );
+        assert_eq!(
+            error(
+                r#"
+                host = "localhost"
+                topics = ["devices/#"]
+                client_id = ""
+                "#
+            ),
+            "Invalid client ID: \"\""
+        );
+        assert_eq!(
+            error(
+                r#"
+                host = "localhost"
+                topics = ["devices/#"]
+                password = "secret"
+                "#
+            ),
+            "`password` can't be set without `user`"
+        );
+    }
+
+    #[test]
+    fn output_schema_definition_vector_namespace() {
+        let config = MqttSourceConfig {
+            log_namespace: Some(true),
+            ..Default::default()
+        };
+
+        let definitions = config
+            .outputs(LogNamespace::Vector)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition =
+            Definition::new_with_default_metadata(Kind::bytes(), [LogNamespace::Vector])
+                .with_meaning(OwnedTargetPath::event_root(), "message")
+                .with_metadata_field(
+                    &owned_value_path!("vector", "source_type"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_metadata_field(
+                    &owned_value_path!("vector", "ingest_timestamp"),
+                    Kind::timestamp(),
+                    None,
+                )
+                .with_metadata_field(&owned_value_path!("mqtt", "topic"), Kind::bytes(), None);
+
+        assert_eq!(definitions, Some(expected_definition));
+    }
+
+    #[test]
+    fn output_schema_definition_legacy_namespace() {
+        let config = MqttSourceConfig::default();
+
+        let definitions = config
+            .outputs(LogNamespace::Legacy)
+            .remove(0)
+            .schema_definition(true);
+
+        let expected_definition = Definition::new_with_default_metadata(
+            Kind::object(Collection::empty()),
+            [LogNamespace::Legacy],
+        )
+        .with_event_field(
+            &owned_value_path!("message"),
+            Kind::bytes(),
+            Some("message"),
+        )
+        .with_event_field(&owned_value_path!("timestamp"), Kind::timestamp(), None)
+        .with_event_field(&owned_value_path!("source_type"), Kind::bytes(), None)
+        .with_event_field(&owned_value_path!("topic"), Kind::bytes(), None);
+
+        assert_eq!(definitions, Some(expected_definition));
+    }
+}
+
+#[cfg(feature = "mqtt-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use bytes::Bytes;
+    use vector_lib::config::log_schema;
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        random_string,
+    };
+
+    fn mqtt_host() -> String {
+        std::env::var("MQTT_HOST").unwrap_or_else(|_| String::from("localhost"))
+    }
+
+    /// Publishes the payloads to the topics once the source is subscribed, and returns the events
+    /// of the source, one per payload.
+    async fn publish_and_collect(
+        config: MqttSourceConfig,
+        messages: Vec<(String, &'static str)>,
+    ) -> Vec<Event> {
+        let options = config.options().unwrap();
+        let count = messages.len();
+
+        assert_source_compliance(&SOURCE_TAGS, async move {
+            let (tx, rx) = SourceSender::new_test();
+            let decoder = DecodingConfig::new(
+                config.framing.clone(),
+                config.decoding.clone(),
+                LogNamespace::Legacy,
+            )
+            .build()
+            .unwrap();
+            tokio::spawn(mqtt_source(
+                config,
+                options,
+                decoder,
+                LogNamespace::Legacy,
+                false,
+                ShutdownSignal::noop(),
+                tx,
+            ));
+            // Gives the source the time to subscribe to the topics.
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let (client, mut eventloop) = AsyncClient::new(
+                MqttOptions::new(format!("publisher-{}", random_string(8)), mqtt_host(), 1883),
+                16,
+            );
+            for (topic, payload) in messages {
+                client
+                    .publish(
+                        topic,
+                        QoS::AtLeastOnce,
+                        false,
+                        Bytes::from_static(payload.as_bytes()),
+                    )
+                    .await
+                    .unwrap();
+            }
+            tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+
+            collect_n(rx, count).await
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn mqtt_wildcard_topics() {
+        let prefix = format!("test-{}", random_string(10));
+        let config = MqttSourceConfig {
+            host: mqtt_host(),
+            topics: vec![
+                format!("{}/+/temp", prefix),
+                format!("{}/devices/#", prefix),
+            ],
+            ..Default::default()
+        };
+
+        let events = publish_and_collect(
+            config,
+            vec![
+                (format!("{}/kitchen/temp", prefix), "21.5"),
+                (format!("{}/devices/a/b", prefix), "online"),
+            ],
+        )
+        .await;
+
+        let messages: Vec<_> = events
+            .iter()
+            .map(|event| {
+                let log = event.as_log();
+                (
+                    log["topic"].to_string_lossy().into_owned(),
+                    log[log_schema().message_key().unwrap().to_string()]
+                        .to_string_lossy()
+                        .into_owned(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (format!("{}/kitchen/temp", prefix), "21.5".to_owned()),
+                (format!("{}/devices/a/b", prefix), "online".to_owned()),
+            ]
+        );
+    }
+}
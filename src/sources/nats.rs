@@ -1,26 +1,40 @@
-use chrono::Utc;
+use std::{collections::BTreeMap, time::Duration};
+
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy, Consumer},
+    AckKind,
+};
+use chrono::{DateTime, Utc};
 use futures::{pin_mut, StreamExt};
+use serde_with::serde_as;
 use snafu::{ResultExt, Snafu};
 use tokio_util::codec::FramedRead;
 use vector_lib::codecs::decoding::{DeserializerConfig, FramingConfig, StreamDecodingError};
 use vector_lib::configurable::configurable_component;
+use vector_lib::finalizer::UnorderedFinalizer;
 use vector_lib::internal_event::{
     ByteSize, BytesReceived, CountByteSize, EventsReceived, InternalEventHandle as _, Protocol,
+    Registered,
 };
-use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path};
 use vector_lib::{
     config::{LegacyKey, LogNamespace},
     EstimatedJsonEncodedSizeOf,
 };
-use vrl::value::Kind;
+use vrl::value::{kind::Collection, Kind, ObjectMap, Value};
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
-    event::Event,
-    internal_events::StreamClosedError,
+    config::{
+        GenerateConfig, SourceAcknowledgementsConfig, SourceConfig, SourceContext, SourceOutput,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        NatsJetStreamAckError, NatsJetStreamNakError, NatsJetStreamReceiveError, StreamClosedError,
+    },
     nats::{from_tls_auth_config, NatsAuthConfig, NatsConfigError},
-    serde::{default_decoding, default_framing_message_based},
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     tls::TlsEnableableConfig,
     SourceSender,
@@ -34,6 +48,18 @@ enum BuildError {
     Connect { source: async_nats::ConnectError },
     #[snafu(display("NATS Subscribe Error: {}", source))]
     Subscribe { source: async_nats::SubscribeError },
+    #[snafu(display("`queue` can't be set together with `jetstream`"))]
+    QueueWithJetStream,
+    #[snafu(display("Invalid JetStream deliver policy start time: {}", source))]
+    StartTime { source: time::error::ComponentRange },
+    #[snafu(display("NATS JetStream Stream Error: {}", source))]
+    Stream {
+        source: jetstream::context::GetStreamError,
+    },
+    #[snafu(display("NATS JetStream Consumer Error: {}", source))]
+    Consumer {
+        source: jetstream::stream::ConsumerError,
+    },
 }
 
 /// Configuration for the `nats` source.
@@ -109,6 +135,111 @@ pub struct NatsSourceConfig {
     #[serde(default = "default_subscription_capacity")]
     #[derivative(Default(value = "default_subscription_capacity()"))]
     subscriber_capacity: usize,
+
+    #[configurable(derived)]
+    jetstream: Option<JetStreamConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+}
+
+/// Configuration for consuming the messages of a NATS [JetStream][jetstream] stream, instead of
+/// subscribing to the subject.
+///
+/// The messages are pulled by a durable consumer, which keeps track of the acknowledged messages
+/// on the server, so the messages published while Vector is down aren't lost. The consumer is
+/// created if it doesn't exist, and resumed otherwise, including after reconnecting. Its messages
+/// are shared by all the sources pulling from it, instead of using a `queue` group.
+///
+/// The consumer only receives the messages of the stream whose subject matches `subject`.
+///
+/// [jetstream]: https://docs.nats.io/nats-concepts/jetstream
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JetStreamConfig {
+    /// The name of the stream to consume the messages of.
+    #[configurable(metadata(docs::examples = "events"))]
+    stream: String,
+
+    /// The name of the durable consumer to pull the messages with.
+    #[configurable(metadata(docs::examples = "vector"))]
+    durable_name: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    deliver_policy: DeliverPolicy,
+
+    /// The time to wait for a message to be acknowledged before it is redelivered.
+    ///
+    /// When end-to-end acknowledgements are enabled, a message is only acknowledged once its
+    /// events are delivered, so this must allow for the time the sinks take to deliver them.
+    ///
+    /// This only applies to newly created consumers.
+    #[serde(default = "default_ack_wait_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Acknowledgement Wait"))]
+    ack_wait_secs: Duration,
+
+    /// The metadata key to add the JetStream metadata of the messages under.
+    ///
+    /// The metadata holds the sequence of the message in the stream (`stream_sequence`) and in
+    /// the consumer (`consumer_sequence`), and the number of times it has been delivered
+    /// (`delivered`).
+    #[serde(default = "default_jetstream_metadata_key")]
+    metadata_key: OptionalValuePath,
+}
+
+/// The messages of the stream the consumer starts from, when it is created.
+///
+/// Resumed consumers continue from their last acknowledged message.
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+#[configurable(metadata(
+    docs::enum_tag_description = "The messages of the stream the consumer starts from."
+))]
+pub enum DeliverPolicy {
+    /// Start from the first message of the stream.
+    #[default]
+    All,
+
+    /// Start from the messages published after the consumer is created.
+    New,
+
+    /// Start from the first message published at or after `start_time`.
+    ByStartTime {
+        /// The time to start from.
+        #[configurable(metadata(docs::examples = "2024-01-01T00:00:00Z"))]
+        start_time: DateTime<Utc>,
+    },
+}
+
+impl DeliverPolicy {
+    fn build(&self) -> Result<jetstream::consumer::DeliverPolicy, BuildError> {
+        Ok(match self {
+            Self::All => jetstream::consumer::DeliverPolicy::All,
+            Self::New => jetstream::consumer::DeliverPolicy::New,
+            Self::ByStartTime { start_time } => {
+                let nanos = i128::from(start_time.timestamp()) * 1_000_000_000
+                    + i128::from(start_time.timestamp_subsec_nanos());
+                jetstream::consumer::DeliverPolicy::ByStartTime {
+                    start_time: time::OffsetDateTime::from_unix_timestamp_nanos(nanos)
+                        .context(StartTimeSnafu)?,
+                }
+            }
+        })
+    }
+}
+
+const fn default_ack_wait_secs() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_jetstream_metadata_key() -> OptionalValuePath {
+    OptionalValuePath::from(owned_value_path!("jetstream"))
 }
 
 fn default_subject_key_field() -> OptionalValuePath {
@@ -136,20 +267,38 @@ impl GenerateConfig for NatsSourceConfig {
 impl SourceConfig for NatsSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
-        let (connection, subscription) = create_subscription(self).await?;
         let decoder =
             DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
                 .build()?;
 
-        Ok(Box::pin(nats_source(
-            self.clone(),
-            connection,
-            subscription,
-            decoder,
-            log_namespace,
-            cx.shutdown,
-            cx.out,
-        )))
+        match &self.jetstream {
+            None => {
+                let (connection, subscription) = create_subscription(self).await?;
+                Ok(Box::pin(nats_source(
+                    self.clone(),
+                    connection,
+                    subscription,
+                    decoder,
+                    log_namespace,
+                    cx.shutdown,
+                    cx.out,
+                )))
+            }
+            Some(jetstream) => {
+                let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+                let (connection, consumer) = create_consumer(self, jetstream).await?;
+                Ok(Box::pin(jetstream_source(
+                    self.clone(),
+                    connection,
+                    consumer,
+                    decoder,
+                    log_namespace,
+                    acknowledgements,
+                    cx.shutdown,
+                    cx.out,
+                )))
+            }
+        }
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
@@ -159,7 +308,7 @@ impl SourceConfig for NatsSourceConfig {
             .clone()
             .path
             .map(LegacyKey::InsertIfEmpty);
-        let schema_definition = self
+        let mut schema_definition = self
             .decoding
             .schema_definition(log_namespace)
             .with_standard_vector_source_metadata()
@@ -170,6 +319,23 @@ impl SourceConfig for NatsSourceConfig {
                 Kind::bytes(),
                 None,
             );
+        if let Some(jetstream) = &self.jetstream {
+            schema_definition = schema_definition.with_source_metadata(
+                NatsSourceConfig::NAME,
+                jetstream
+                    .metadata_key
+                    .clone()
+                    .path
+                    .map(LegacyKey::InsertIfEmpty),
+                &owned_value_path!("jetstream"),
+                Kind::object(Collection::from(BTreeMap::from([
+                    ("consumer_sequence".into(), Kind::integer()),
+                    ("delivered".into(), Kind::integer()),
+                    ("stream_sequence".into(), Kind::integer()),
+                ]))),
+                None,
+            );
+        }
 
         vec![SourceOutput::new_logs(
             self.decoding.output_type(),
@@ -178,7 +344,7 @@ impl SourceConfig for NatsSourceConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        self.jetstream.is_some()
     }
 }
 
@@ -214,57 +380,246 @@ async fn nats_source(
     let bytes_received = register!(BytesReceived::from(Protocol::TCP));
     while let Some(msg) = stream.next().await {
         bytes_received.emit(ByteSize(msg.payload.len()));
-        let mut stream = FramedRead::new(msg.payload.as_ref(), decoder.clone());
-        while let Some(next) = stream.next().await {
-            match next {
-                Ok((events, _byte_size)) => {
-                    let count = events.len();
-                    let byte_size = events.estimated_json_encoded_size_of();
-                    events_received.emit(CountByteSize(count, byte_size));
-
-                    let now = Utc::now();
-
-                    let events = events.into_iter().map(|mut event| {
-                        if let Event::Log(ref mut log) = event {
-                            log_namespace.insert_standard_vector_source_metadata(
-                                log,
-                                NatsSourceConfig::NAME,
-                                now,
-                            );
-
-                            let legacy_subject_key_field = config
-                                .subject_key_field
-                                .path
-                                .as_ref()
-                                .map(LegacyKey::InsertIfEmpty);
-                            log_namespace.insert_source_metadata(
-                                NatsSourceConfig::NAME,
-                                log,
-                                legacy_subject_key_field,
-                                &owned_value_path!("subject"),
-                                msg.subject.as_str(),
-                            )
-                        }
-                        event
-                    });
+        let events = decode_message(&config, &decoder, log_namespace, &msg, &events_received).await;
+        if events.is_empty() {
+            continue;
+        }
+
+        let count = events.len();
+        out.send_batch(events).await.map_err(|_| {
+            emit!(StreamClosedError { count });
+        })?;
+    }
+    Ok(())
+}
 
-                    out.send_batch(events).await.map_err(|_| {
-                        emit!(StreamClosedError { count });
-                    })?;
+/// Decodes the events of the message, adding the source metadata to them.
+async fn decode_message(
+    config: &NatsSourceConfig,
+    decoder: &Decoder,
+    log_namespace: LogNamespace,
+    msg: &async_nats::Message,
+    events_received: &Registered<EventsReceived>,
+) -> Vec<Event> {
+    let mut decoded = Vec::new();
+    let mut stream = FramedRead::new(msg.payload.as_ref(), decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((events, _byte_size)) => {
+                let count = events.len();
+                let byte_size = events.estimated_json_encoded_size_of();
+                events_received.emit(CountByteSize(count, byte_size));
+
+                let now = Utc::now();
+
+                decoded.extend(events.into_iter().map(|mut event| {
+                    if let Event::Log(ref mut log) = event {
+                        log_namespace.insert_standard_vector_source_metadata(
+                            log,
+                            NatsSourceConfig::NAME,
+                            now,
+                        );
+
+                        let legacy_subject_key_field = config
+                            .subject_key_field
+                            .path
+                            .as_ref()
+                            .map(LegacyKey::InsertIfEmpty);
+                        log_namespace.insert_source_metadata(
+                            NatsSourceConfig::NAME,
+                            log,
+                            legacy_subject_key_field,
+                            &owned_value_path!("subject"),
+                            msg.subject.as_str(),
+                        )
+                    }
+                    event
+                }));
+            }
+            Err(error) => {
+                // Error is logged by `crate::codecs`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
                 }
+            }
+        }
+    }
+    decoded
+}
+
+/// The delay before pulling the messages of the consumer again, after the
+/// previous pull stream ended.
+const JETSTREAM_RESUME_DELAY: Duration = Duration::from_secs(1);
+
+#[allow(clippy::too_many_arguments)]
+async fn jetstream_source(
+    config: NatsSourceConfig,
+    // Take ownership of the connection so it doesn't get dropped.
+    _connection: async_nats::Client,
+    consumer: Consumer<pull::Config>,
+    decoder: Decoder,
+    log_namespace: LogNamespace,
+    acknowledgements: bool,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let (finalizer, mut ack_stream) = UnorderedFinalizer::<jetstream::Message>::maybe_new(
+        acknowledgements,
+        Some(shutdown.clone()),
+    );
+    let metadata_key = config
+        .jetstream
+        .as_ref()
+        .and_then(|jetstream| jetstream.metadata_key.path.clone());
+    let events_received = register!(EventsReceived);
+    let bytes_received = register!(BytesReceived::from(Protocol::TCP));
+
+    let mut messages = None;
+    loop {
+        // The pull stream is resumed from the same durable consumer, which
+        // only redelivers the messages that weren't acknowledged.
+        if messages.is_none() {
+            match consumer.messages().await {
+                Ok(stream) => messages = Some(stream),
                 Err(error) => {
-                    // Error is logged by `crate::codecs`, no further
-                    // handling is needed here.
-                    if !error.can_continue() {
-                        break;
+                    emit!(NatsJetStreamReceiveError {
+                        error: error.into()
+                    });
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = tokio::time::sleep(JETSTREAM_RESUME_DELAY) => continue,
                     }
                 }
             }
         }
+        let stream = messages.as_mut().expect("the pull stream was just created");
+
+        tokio::select! {
+            _ = &mut shutdown => break,
+            entry = ack_stream.next() => {
+                if let Some((status, message)) = entry {
+                    handle_ack(status, message).await;
+                }
+            },
+            message = stream.next() => match message {
+                Some(Ok(message)) => {
+                    bytes_received.emit(ByteSize(message.payload.len()));
+                    let mut events = decode_message(
+                        &config,
+                        &decoder,
+                        log_namespace,
+                        &message,
+                        &events_received,
+                    )
+                    .await;
+                    insert_jetstream_metadata(
+                        &mut events,
+                        &message,
+                        metadata_key.as_ref(),
+                        log_namespace,
+                    );
+                    send_message_events(events, message, finalizer.as_ref(), &mut out).await?;
+                }
+                Some(Err(error)) => emit!(NatsJetStreamReceiveError {
+                    error: error.into()
+                }),
+                None => {
+                    messages = None;
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = tokio::time::sleep(JETSTREAM_RESUME_DELAY) => {},
+                    }
+                }
+            },
+        }
     }
     Ok(())
 }
 
+/// Adds the JetStream metadata of the message to its events.
+fn insert_jetstream_metadata(
+    events: &mut [Event],
+    message: &jetstream::Message,
+    metadata_key: Option<&vector_lib::lookup::OwnedValuePath>,
+    log_namespace: LogNamespace,
+) {
+    let Ok(info) = message.info() else {
+        return;
+    };
+    let metadata = Value::from(ObjectMap::from([
+        (
+            "consumer_sequence".into(),
+            Value::from(info.consumer_sequence as i64),
+        ),
+        ("delivered".into(), Value::from(info.delivered)),
+        (
+            "stream_sequence".into(),
+            Value::from(info.stream_sequence as i64),
+        ),
+    ]));
+    for event in events {
+        if let Event::Log(log) = event {
+            log_namespace.insert_source_metadata(
+                NatsSourceConfig::NAME,
+                log,
+                metadata_key.map(LegacyKey::InsertIfEmpty),
+                path!("jetstream"),
+                metadata.clone(),
+            );
+        }
+    }
+}
+
+/// Sends the events of the message, acknowledging it once they are delivered
+/// with end-to-end acknowledgements, or once they are sent without.
+async fn send_message_events(
+    events: Vec<Event>,
+    message: jetstream::Message,
+    finalizer: Option<&UnorderedFinalizer<jetstream::Message>>,
+    out: &mut SourceSender,
+) -> Result<(), ()> {
+    let count = events.len();
+    match finalizer {
+        Some(finalizer) => {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let events = events
+                .into_iter()
+                .map(|event| event.with_batch_notifier(&batch));
+            out.send_batch(events).await.map_err(|_| {
+                emit!(StreamClosedError { count });
+            })?;
+            finalizer.add(message, receiver);
+        }
+        None => {
+            out.send_batch(events).await.map_err(|_| {
+                emit!(StreamClosedError { count });
+            })?;
+            if let Err(error) = message.ack().await {
+                emit!(NatsJetStreamAckError { error });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Acknowledges the message once its events are delivered, or lets JetStream
+/// redeliver it otherwise.
+async fn handle_ack(status: BatchStatus, message: jetstream::Message) {
+    match status {
+        BatchStatus::Delivered => {
+            if let Err(error) = message.ack().await {
+                emit!(NatsJetStreamAckError { error });
+            }
+        }
+        BatchStatus::Errored | BatchStatus::Rejected => {
+            if let Err(error) = message.ack_with(AckKind::Nak(None)).await {
+                emit!(NatsJetStreamNakError { error });
+            }
+        }
+    }
+}
+
 async fn create_subscription(
     config: &NatsSourceConfig,
 ) -> Result<(async_nats::Client, async_nats::Subscriber), BuildError> {
@@ -283,6 +638,38 @@ async fn create_subscription(
     Ok((nc, subscription))
 }
 
+async fn create_consumer(
+    config: &NatsSourceConfig,
+    jetstream: &JetStreamConfig,
+) -> Result<(async_nats::Client, Consumer<pull::Config>), BuildError> {
+    if config.queue.is_some() {
+        return Err(BuildError::QueueWithJetStream);
+    }
+    let deliver_policy = jetstream.deliver_policy.build()?;
+
+    let nc = config.connect().await?;
+    let stream = jetstream::new(nc.clone())
+        .get_stream(&jetstream.stream)
+        .await
+        .context(StreamSnafu)?;
+    let consumer = stream
+        .get_or_create_consumer(
+            &jetstream.durable_name,
+            pull::Config {
+                durable_name: Some(jetstream.durable_name.clone()),
+                deliver_policy,
+                ack_policy: AckPolicy::Explicit,
+                ack_wait: jetstream.ack_wait_secs,
+                filter_subject: config.subject.clone(),
+                ..Default::default()
+            },
+        )
+        .await
+        .context(ConsumerSnafu)?;
+
+    Ok((nc, consumer))
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::print_stdout)] //tests
@@ -355,6 +742,71 @@ mod tests {
 
         assert_eq!(definitions, Some(expected_definition));
     }
+
+    #[test]
+    fn parse_jetstream_config() {
+        let config: NatsSourceConfig = toml::from_str(
+            r#"
+            url = "nats://127.0.0.1:4222"
+            connection_name = "vector"
+            subject = "events.>"
+            acknowledgements = true
+
+            [jetstream]
+            stream = "events"
+            durable_name = "vector"
+            deliver_policy.policy = "by_start_time"
+            deliver_policy.start_time = "2024-01-01T00:00:00.5Z"
+            "#,
+        )
+        .unwrap();
+        assert!(config.can_acknowledge());
+
+        let jetstream = config.jetstream.unwrap();
+        assert_eq!(jetstream.ack_wait_secs, default_ack_wait_secs());
+        assert_eq!(
+            jetstream.metadata_key.path,
+            Some(owned_value_path!("jetstream"))
+        );
+        match jetstream.deliver_policy.build().unwrap() {
+            jetstream::consumer::DeliverPolicy::ByStartTime { start_time } => {
+                assert_eq!(start_time.unix_timestamp(), 1_704_067_200);
+                assert_eq!(start_time.nanosecond(), 500_000_000);
+            }
+            policy => panic!("unexpected deliver policy {:?}", policy),
+        }
+    }
+
+    #[test]
+    fn output_schema_definition_jetstream() {
+        let config = NatsSourceConfig {
+            subject_key_field: default_subject_key_field(),
+            jetstream: Some(JetStreamConfig {
+                stream: "events".to_owned(),
+                durable_name: "vector".to_owned(),
+                deliver_policy: DeliverPolicy::New,
+                ack_wait_secs: default_ack_wait_secs(),
+                metadata_key: default_jetstream_metadata_key(),
+            }),
+            ..Default::default()
+        };
+        let definitions = config
+            .outputs(LogNamespace::Legacy)
+            .remove(0)
+            .schema_definition(true)
+            .unwrap();
+
+        assert_eq!(
+            definitions
+                .event_kind()
+                .at_path(&owned_value_path!("jetstream")),
+            Kind::object(Collection::from(BTreeMap::from([
+                ("consumer_sequence".into(), Kind::integer()),
+                ("delivered".into(), Kind::integer()),
+                ("stream_sequence".into(), Kind::integer()),
+            ])))
+        );
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
@@ -846,4 +1298,89 @@ mod integration_tests {
             r
         );
     }
+
+    #[tokio::test]
+    async fn nats_jetstream_resumes_durable_consumer() {
+        let subject = format!("test-{}", random_string(10));
+        let url =
+            std::env::var("NATS_ADDRESS").unwrap_or_else(|_| String::from("nats://localhost:4222"));
+
+        let conf = NatsSourceConfig {
+            connection_name: "".to_owned(),
+            subject: subject.clone(),
+            url,
+            subject_key_field: default_subject_key_field(),
+            jetstream: Some(JetStreamConfig {
+                stream: subject.clone(),
+                durable_name: "vector".to_owned(),
+                deliver_policy: DeliverPolicy::All,
+                ack_wait_secs: default_ack_wait_secs(),
+                metadata_key: default_jetstream_metadata_key(),
+            }),
+            ..Default::default()
+        };
+        let nc = conf.connect().await.unwrap();
+        let js = jetstream::new(nc);
+        js.create_stream(jetstream::stream::Config {
+            name: subject.clone(),
+            subjects: vec![subject.clone()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let receive = |message: &'static str| {
+            let conf = conf.clone();
+            let js = js.clone();
+            let subject = subject.clone();
+            async move {
+                js.publish(subject, Bytes::from_static(message.as_bytes()))
+                    .await
+                    .unwrap()
+                    .await
+                    .unwrap();
+
+                let (connection, consumer) =
+                    create_consumer(&conf, conf.jetstream.as_ref().unwrap())
+                        .await
+                        .unwrap();
+                let (tx, rx) = SourceSender::new_test();
+                let decoder = DecodingConfig::new(
+                    conf.framing.clone(),
+                    conf.decoding.clone(),
+                    LogNamespace::Legacy,
+                )
+                .build()
+                .unwrap();
+                let (trigger, shutdown, _) = ShutdownSignal::new_wired();
+                let source = tokio::spawn(jetstream_source(
+                    conf.clone(),
+                    connection,
+                    consumer,
+                    decoder,
+                    LogNamespace::Legacy,
+                    false,
+                    shutdown,
+                    tx,
+                ));
+                let events = collect_n(rx, 1).await;
+                drop(trigger);
+                source.await.unwrap().unwrap();
+                events
+            }
+        };
+
+        // The second source resumes the durable consumer after the message
+        // the first one acknowledged.
+        for (message, sequence) in [("first", 1_i64), ("second", 2)] {
+            let events = receive(message).await;
+            let log = events[0].as_log();
+            assert_eq!(
+                log[log_schema().message_key().unwrap().to_string()],
+                message.into()
+            );
+            assert_eq!(log["jetstream.stream_sequence"], Value::from(sequence));
+            assert_eq!(log["jetstream.delivered"], Value::from(1_i64));
+        }
+    }
 }
@@ -1,8 +1,12 @@
 pub(crate) mod parser;
+#[cfg(feature = "sources-prometheus-scrape")]
+mod relabel;
 #[cfg(feature = "sources-prometheus-remote-write")]
 mod remote_write;
 #[cfg(feature = "sources-prometheus-scrape")]
 mod scrape;
+#[cfg(feature = "sources-prometheus-scrape")]
+mod service_discovery;
 
 #[cfg(feature = "sources-prometheus-remote-write")]
 pub use remote_write::PrometheusRemoteWriteConfig;
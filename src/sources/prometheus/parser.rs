@@ -1,9 +1,10 @@
 use std::cmp::Ordering;
 
 use chrono::{DateTime, TimeZone, Utc};
+use vector_lib::lookup::path;
 #[cfg(feature = "sources-prometheus-remote-write")]
 use vector_lib::prometheus::parser::proto;
-use vector_lib::prometheus::parser::{GroupKind, MetricGroup, ParserError};
+use vector_lib::prometheus::parser::{GroupKind, GroupMetadata, MetricGroup, ParserError};
 
 use crate::event::{
     metric::{Bucket, Metric, MetricKind, MetricTags, MetricValue, Quantile},
@@ -29,11 +30,34 @@ pub(super) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>,
     vector_lib::prometheus::parser::parse_request(request).map(reparse_groups)
 }
 
+#[cfg(feature = "sources-prometheus-remote-write")]
+pub(super) fn parse_request_v2(request: proto::v2::Request) -> Result<Vec<Event>, ParserError> {
+    vector_lib::prometheus::parser::parse_request_v2(request).map(reparse_groups)
+}
+
+/// Keeps the help and unit of the metric group in the `prometheus` object of the metadata of its
+/// metrics, where the `prometheus_exporter` and `prometheus_remote_write` sinks find them.
+fn insert_group_metadata(events: &mut [Event], metadata: &GroupMetadata) {
+    for (field, value) in [("help", &metadata.help), ("unit", &metadata.unit)] {
+        let Some(value) = value else {
+            continue;
+        };
+        for event in events.iter_mut() {
+            event
+                .as_mut_metric()
+                .metadata_mut()
+                .value_mut()
+                .insert(path!("prometheus", field), value.as_str());
+        }
+    }
+}
+
 fn reparse_groups(groups: Vec<MetricGroup>) -> Vec<Event> {
     let mut result = Vec::new();
     let start = Utc::now();
 
     for group in groups {
+        let first = result.len();
         match group.metrics {
             GroupKind::Counter(metrics) => {
                 for (key, metric) in metrics {
@@ -127,6 +151,7 @@ fn reparse_groups(groups: Vec<MetricGroup>) -> Vec<Event> {
                 }
             }
         }
+        insert_group_metadata(&mut result[first..], &group.metadata);
     }
 
     result
@@ -0,0 +1,361 @@
+//! Relabeling of the scraped metrics, following Prometheus’ `metric_relabel_configs`.
+
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use vector_lib::configurable::configurable_component;
+
+use crate::event::metric::Metric;
+
+/// The label the name of the metric is available as.
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The action of a relabeling rule.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// Sets `target_label` to `replacement` if `regex` matches the values of the source labels.
+    #[default]
+    Replace,
+
+    /// Drops the metrics for which `regex` doesn't match the values of the source labels.
+    Keep,
+
+    /// Drops the metrics for which `regex` matches the values of the source labels.
+    Drop,
+
+    /// Removes the tags with a name matched by `regex`.
+    Labeldrop,
+
+    /// Removes the tags with a name not matched by `regex`.
+    Labelkeep,
+}
+
+/// A rule relabeling the scraped metrics.
+///
+/// The rules are applied in order, after the `instance_tag` and `endpoint_tag` tags are added.
+/// The name of the metric is available as the `__name__` label.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    /// The action to perform.
+    #[serde(default)]
+    action: RelabelAction,
+
+    /// The labels whose values are joined with `separator` and matched against `regex`.
+    ///
+    /// A missing label has an empty value.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "__name__"))]
+    #[configurable(metadata(docs::examples = "job"))]
+    source_labels: Vec<String>,
+
+    /// The separator placed between the values of the source labels.
+    #[serde(default = "default_separator")]
+    separator: String,
+
+    /// The regular expression matched against the joined values of the source labels, or against
+    /// the tag names with the `labeldrop` and `labelkeep` actions.
+    ///
+    /// The regular expression is anchored at both ends.
+    #[serde(default = "default_regex")]
+    #[configurable(metadata(docs::examples = "go_.*"))]
+    #[configurable(metadata(docs::examples = "(.*):\\d+"))]
+    regex: String,
+
+    /// The label set with the `replace` action.
+    #[configurable(metadata(docs::examples = "instance"))]
+    target_label: Option<String>,
+
+    /// The value set to `target_label` with the `replace` action.
+    ///
+    /// The `$1`, `${1}`, or `$name` references are replaced with the capture groups of `regex`.
+    /// The label is removed if the value is empty.
+    #[serde(default = "default_replacement")]
+    replacement: String,
+}
+
+fn default_separator() -> String {
+    ";".to_owned()
+}
+
+fn default_regex() -> String {
+    "(.*)".to_owned()
+}
+
+fn default_replacement() -> String {
+    "$1".to_owned()
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum RelabelError {
+    #[snafu(display("unable to parse relabel regex from {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("the `replace` relabel action requires a `target_label`"))]
+    MissingTargetLabel,
+}
+
+impl RelabelConfig {
+    pub(super) fn build(&self) -> Result<Relabel, RelabelError> {
+        let regex =
+            Regex::new(&format!("^(?:{})$", self.regex)).with_context(|_| InvalidRegexSnafu {
+                regex: self.regex.clone(),
+            })?;
+        if self.action == RelabelAction::Replace && self.target_label.is_none() {
+            return MissingTargetLabelSnafu.fail();
+        }
+
+        Ok(Relabel {
+            action: self.action,
+            source_labels: self.source_labels.clone(),
+            separator: self.separator.clone(),
+            regex,
+            target_label: self.target_label.clone(),
+            replacement: self.replacement.clone(),
+        })
+    }
+}
+
+/// A relabeling rule, with its regular expression compiled.
+#[derive(Clone, Debug)]
+pub(super) struct Relabel {
+    action: RelabelAction,
+    source_labels: Vec<String>,
+    separator: String,
+    regex: Regex,
+    target_label: Option<String>,
+    replacement: String,
+}
+
+impl Relabel {
+    /// Applies the rule to the metric, returning `None` if the metric is dropped.
+    fn apply(&self, metric: Metric) -> Option<Metric> {
+        match self.action {
+            RelabelAction::Replace => Some(self.replace(metric)),
+            RelabelAction::Keep => self
+                .regex
+                .is_match(&self.source_value(&metric))
+                .then_some(metric),
+            RelabelAction::Drop => {
+                (!self.regex.is_match(&self.source_value(&metric))).then_some(metric)
+            }
+            RelabelAction::Labeldrop => Some(self.retain_tags(metric, false)),
+            RelabelAction::Labelkeep => Some(self.retain_tags(metric, true)),
+        }
+    }
+
+    fn source_value(&self, metric: &Metric) -> String {
+        self.source_labels
+            .iter()
+            .map(|label| label_value(metric, label).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    fn replace(&self, metric: Metric) -> Metric {
+        let source_value = self.source_value(&metric);
+        let (Some(target_label), Some(captures)) =
+            (&self.target_label, self.regex.captures(&source_value))
+        else {
+            return metric;
+        };
+
+        let mut value = String::new();
+        captures.expand(&self.replacement, &mut value);
+        set_label(metric, target_label, value)
+    }
+
+    fn retain_tags(&self, mut metric: Metric, matching: bool) -> Metric {
+        if let Some(tags) = metric.tags_mut() {
+            tags.retain(|name, _| self.regex.is_match(name) == matching);
+        }
+        metric
+    }
+}
+
+fn label_value(metric: &Metric, label: &str) -> Option<String> {
+    if label == METRIC_NAME_LABEL {
+        Some(metric.name().to_owned())
+    } else {
+        metric.tag_value(label)
+    }
+}
+
+fn set_label(mut metric: Metric, label: &str, value: String) -> Metric {
+    match label {
+        // The name of a metric can't be removed.
+        METRIC_NAME_LABEL if value.is_empty() => metric,
+        METRIC_NAME_LABEL => metric.with_name(value),
+        _ if value.is_empty() => {
+            metric.remove_tag(label);
+            metric
+        }
+        _ => {
+            metric.replace_tag(label.to_owned(), value);
+            metric
+        }
+    }
+}
+
+/// Applies the rules to the metric in order, returning `None` once the metric is dropped.
+pub(super) fn relabel(rules: &[Relabel], metric: Metric) -> Option<Metric> {
+    rules
+        .iter()
+        .try_fold(metric, |metric, rule| rule.apply(metric))
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::metric_tags;
+
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricValue};
+
+    fn metric(name: &str) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        )
+        .with_tags(Some(metric_tags!(
+            "instance" => "localhost:9100",
+            "job" => "node",
+            "exported_job" => "app",
+        )))
+    }
+
+    fn rules(config: &str) -> Vec<Relabel> {
+        #[derive(serde::Deserialize)]
+        struct Rules {
+            relabel: Vec<RelabelConfig>,
+        }
+
+        toml::from_str::<Rules>(config)
+            .unwrap()
+            .relabel
+            .iter()
+            .map(|rule| rule.build().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn replace() {
+        let rules = rules(
+            r#"
+            [[relabel]]
+            source_labels = ["instance"]
+            regex = "(.*):\\d+"
+            target_label = "host"
+
+            [[relabel]]
+            source_labels = ["job", "exported_job"]
+            separator = "/"
+            target_label = "service"
+            replacement = "svc-$1"
+
+            [[relabel]]
+            source_labels = ["missing"]
+            target_label = "exported_job"
+            "#,
+        );
+
+        let metric = relabel(&rules, metric("node_load1")).unwrap();
+        assert_eq!(metric.tag_value("host"), Some("localhost".to_owned()));
+        assert_eq!(metric.tag_value("service"), Some("svc-node/app".to_owned()));
+        // The empty replacement removes the label.
+        assert_eq!(metric.tag_value("exported_job"), None);
+    }
+
+    #[test]
+    fn replace_name() {
+        let rules = rules(
+            r#"
+            [[relabel]]
+            source_labels = ["__name__"]
+            regex = "node_(.*)"
+            target_label = "__name__"
+            replacement = "host_$1"
+            "#,
+        );
+
+        assert_eq!(
+            relabel(&rules, metric("node_load1")).unwrap().name(),
+            "host_load1"
+        );
+        assert_eq!(
+            relabel(&rules, metric("go_goroutines")).unwrap().name(),
+            "go_goroutines"
+        );
+    }
+
+    #[test]
+    fn keep_and_drop() {
+        let keep_rules = rules(
+            r#"
+            [[relabel]]
+            action = "keep"
+            source_labels = ["__name__"]
+            regex = "node_.*"
+            "#,
+        );
+        assert!(relabel(&keep_rules, metric("node_load1")).is_some());
+        assert!(relabel(&keep_rules, metric("go_goroutines")).is_none());
+        // The regex is anchored.
+        assert!(relabel(&keep_rules, metric("my_node_load1")).is_none());
+
+        let drop_rules = rules(
+            r#"
+            [[relabel]]
+            action = "drop"
+            source_labels = ["__name__", "job"]
+            regex = "go_.*;node"
+            "#,
+        );
+        assert!(relabel(&drop_rules, metric("node_load1")).is_some());
+        assert!(relabel(&drop_rules, metric("go_goroutines")).is_none());
+    }
+
+    #[test]
+    fn labeldrop_and_labelkeep() {
+        let labeldrop_rules = rules(
+            r#"
+            [[relabel]]
+            action = "labeldrop"
+            regex = "exported_.*"
+            "#,
+        );
+        let relabeled = relabel(&labeldrop_rules, metric("node_load1")).unwrap();
+        let mut tags: Vec<_> = relabeled.tags().unwrap().keys().collect();
+        tags.sort_unstable();
+        assert_eq!(tags, ["instance", "job"]);
+
+        let labelkeep_rules = rules(
+            r#"
+            [[relabel]]
+            action = "labelkeep"
+            regex = "job|instance"
+            "#,
+        );
+        let relabeled = relabel(&labelkeep_rules, metric("node_load1")).unwrap();
+        let mut tags: Vec<_> = relabeled.tags().unwrap().keys().collect();
+        tags.sort_unstable();
+        assert_eq!(tags, ["instance", "job"]);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let config = |config: &str| toml::from_str::<RelabelConfig>(config).unwrap();
+
+        assert!(config(r#"target_label = "a""#).build().is_ok());
+        assert!(matches!(
+            config(r#"regex = "(""#).build(),
+            Err(RelabelError::InvalidRegex { .. })
+        ));
+        assert!(matches!(
+            config(r#"source_labels = ["a"]"#).build(),
+            Err(RelabelError::MissingTargetLabel)
+        ));
+        assert!(config(r#"action = "drop""#).build().is_ok());
+    }
+}
@@ -5,7 +5,7 @@ use prost::Message;
 use vector_lib::config::LogNamespace;
 use vector_lib::configurable::configurable_component;
 use vector_lib::prometheus::parser::proto;
-use warp::http::{HeaderMap, StatusCode};
+use warp::http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
 
 use super::parser;
 use crate::{
@@ -98,21 +98,79 @@ impl SourceConfig for PrometheusRemoteWriteConfig {
     }
 }
 
+/// The `proto` parameter of the `Content-Type` of the Remote Write 1.0 requests.
+const PROTO_V1: &str = "prometheus.WriteRequest";
+
+/// The `proto` parameter of the `Content-Type` of the Remote Write 2.0 requests.
+const PROTO_V2: &str = "io.prometheus.write.v2.Request";
+
+const X_PROMETHEUS_REMOTE_WRITE_VERSION: &str = "X-Prometheus-Remote-Write-Version";
+
+/// The version of the Remote Write protocol of a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RemoteWriteVersion {
+    V1,
+    V2,
+}
+
+impl RemoteWriteVersion {
+    /// Negotiates the version from the `proto` parameter of the `Content-Type` header, falling
+    /// back on the `X-Prometheus-Remote-Write-Version` header, and on 1.0 for the senders setting
+    /// neither.
+    fn from_headers(headers: &HeaderMap) -> Result<Self, ErrorMessage> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let proto = header(CONTENT_TYPE.as_str()).and_then(|content_type| {
+            content_type.split(';').skip(1).find_map(|parameter| {
+                let (name, value) = parameter.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("proto")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+        });
+
+        match proto {
+            Some(PROTO_V1) => Ok(Self::V1),
+            Some(PROTO_V2) => Ok(Self::V2),
+            Some(proto) => Err(ErrorMessage::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported write request message: {}", proto),
+            )),
+            None if header(X_PROMETHEUS_REMOTE_WRITE_VERSION)
+                .map_or(false, |version| version.starts_with("2.")) =>
+            {
+                Ok(Self::V2)
+            }
+            None => Ok(Self::V1),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct RemoteWriteSource;
 
 impl RemoteWriteSource {
-    fn decode_body(&self, body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
-        let request = proto::WriteRequest::decode(body).map_err(|error| {
-            emit!(PrometheusRemoteWriteParseError {
-                error: error.clone()
-            });
+    fn decode_body(
+        &self,
+        version: RemoteWriteVersion,
+        body: Bytes,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let parsed = match version {
+            RemoteWriteVersion::V1 => parser::parse_request(self.decode_message(body)?),
+            RemoteWriteVersion::V2 => parser::parse_request_v2(self.decode_message(body)?),
+        };
+        parsed.map_err(|error| {
             ErrorMessage::new(
                 StatusCode::BAD_REQUEST,
                 format!("Could not decode write request: {}", error),
             )
-        })?;
-        parser::parse_request(request).map_err(|error| {
+        })
+    }
+
+    fn decode_message<M: Message + Default>(&self, body: Bytes) -> Result<M, ErrorMessage> {
+        M::decode(body).map_err(|error| {
+            emit!(PrometheusRemoteWriteParseError {
+                error: error.clone()
+            });
             ErrorMessage::new(
                 StatusCode::BAD_REQUEST,
                 format!("Could not decode write request: {}", error),
@@ -123,18 +181,20 @@ impl RemoteWriteSource {
 
 impl HttpSource for RemoteWriteSource {
     fn decode(&self, encoding_header: Option<&str>, body: Bytes) -> Result<Bytes, ErrorMessage> {
-        // Default to snappy decoding the request body.
+        // Default to snappy decoding the request body, which frames the requests of both
+        // versions the same way.
         decode(encoding_header.or(Some("snappy")), body)
     }
 
     fn build_events(
         &self,
         body: Bytes,
-        _header_map: &HeaderMap,
+        header_map: &HeaderMap,
         _query_parameters: &HashMap<String, String>,
         _full_path: &str,
     ) -> Result<Vec<Event>, ErrorMessage> {
-        let events = self.decode_body(body)?;
+        let version = RemoteWriteVersion::from_headers(header_map)?;
+        let events = self.decode_body(version, body)?;
         Ok(events)
     }
 }
@@ -328,6 +388,151 @@ mod test {
 
         vector_lib::assert_event_data_eq!(expected, output);
     }
+
+    #[test]
+    fn negotiates_version() {
+        let version = |headers: &[(&str, &str)]| {
+            let headers: HeaderMap = headers
+                .iter()
+                .map(|(name, value)| (name.parse().unwrap(), value.parse().unwrap()))
+                .collect();
+            RemoteWriteVersion::from_headers(&headers).map_err(|error| error.code())
+        };
+
+        assert_eq!(version(&[]), Ok(RemoteWriteVersion::V1));
+        assert_eq!(
+            version(&[("content-type", "application/x-protobuf")]),
+            Ok(RemoteWriteVersion::V1)
+        );
+        assert_eq!(
+            version(&[(
+                "content-type",
+                "application/x-protobuf;proto=prometheus.WriteRequest"
+            )]),
+            Ok(RemoteWriteVersion::V1)
+        );
+        assert_eq!(
+            version(&[(
+                "content-type",
+                "application/x-protobuf; proto=io.prometheus.write.v2.Request"
+            )]),
+            Ok(RemoteWriteVersion::V2)
+        );
+        assert_eq!(
+            version(&[("x-prometheus-remote-write-version", "2.0.0")]),
+            Ok(RemoteWriteVersion::V2)
+        );
+        // The `Content-Type` takes precedence over the version.
+        assert_eq!(
+            version(&[
+                (
+                    "content-type",
+                    "application/x-protobuf;proto=prometheus.WriteRequest"
+                ),
+                ("x-prometheus-remote-write-version", "2.0.0"),
+            ]),
+            Ok(RemoteWriteVersion::V1)
+        );
+        assert_eq!(
+            version(&[(
+                "content-type",
+                "application/x-protobuf;proto=io.prometheus.write.v3.Request"
+            )]),
+            Err(StatusCode::UNSUPPORTED_MEDIA_TYPE.as_u16())
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_metrics_v2() {
+        let address = test_util::next_addr();
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+
+        let source = PrometheusRemoteWriteConfig::from_address(address)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+
+        let timestamp = Utc::now().trunc_subsecs(3);
+        let symbols = [
+            "",
+            "__name__",
+            "http_requests_total",
+            "method",
+            "post",
+            "The total number of HTTP requests.",
+        ];
+        let request = proto::v2::Request {
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            timeseries: vec![proto::v2::TimeSeries {
+                labels_refs: vec![1, 2, 3, 4],
+                samples: vec![proto::v2::Sample {
+                    value: 1027.0,
+                    timestamp: timestamp.timestamp_millis(),
+                }],
+                metadata: Some(proto::v2::Metadata {
+                    r#type: proto::v2::MetricType::Counter as i32,
+                    help_ref: 5,
+                    unit_ref: 0,
+                }),
+                created_timestamp: 1395066360000,
+            }],
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .unwrap();
+
+        let output = test_util::spawn_collect_ready(
+            async move {
+                let response = reqwest::Client::new()
+                    .post(format!("http://{}/", address))
+                    .header(
+                        "Content-Type",
+                        "application/x-protobuf;proto=io.prometheus.write.v2.Request",
+                    )
+                    .header("Content-Encoding", "snappy")
+                    .header("X-Prometheus-Remote-Write-Version", "2.0.0")
+                    .body(body)
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            },
+            rx,
+            2,
+        )
+        .await;
+
+        let tags = metric_tags!("method" => "post");
+        let expected = vec![
+            Metric::new(
+                "http_requests_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 1027.0 },
+            )
+            .with_timestamp(Some(timestamp))
+            .with_tags(Some(tags.clone())),
+            Metric::new(
+                "http_requests_created",
+                MetricKind::Absolute,
+                MetricValue::Gauge {
+                    value: 1395066360.0,
+                },
+            )
+            .with_timestamp(Some(timestamp))
+            .with_tags(Some(tags)),
+        ];
+        let output: Vec<_> = output.into_iter().map(Event::into_metric).collect();
+        vector_lib::assert_event_data_eq!(expected, output);
+        assert_eq!(
+            output[0]
+                .metadata()
+                .value()
+                .get(vector_lib::lookup::path!("prometheus", "help")),
+            Some(&"The total number of HTTP requests.".into())
+        );
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
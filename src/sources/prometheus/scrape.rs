@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Bytes;
@@ -9,18 +10,23 @@ use snafu::{ResultExt, Snafu};
 use vector_lib::configurable::configurable_component;
 use vector_lib::{config::LogNamespace, event::Event};
 
-use super::parser;
+use super::{
+    parser,
+    relabel::{relabel, Relabel, RelabelConfig},
+    service_discovery::{schedule, DiscoveredTargets, Labels, ServiceDiscoveryConfig},
+};
 use crate::sources::util::http::HttpMethod;
 use crate::sources::util::http_client::{default_timeout, warn_if_interval_too_low};
 use crate::{
     config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
-    http::Auth,
+    event::metric::Metric,
+    http::{Auth, HttpClient},
     internal_events::PrometheusParseError,
     sources::{
         self,
         util::http_client::{
-            build_url, call, default_interval, GenericHttpClientInputs, HttpClientBuilder,
-            HttpClientContext,
+            build_url, call, call_urls, default_interval, GenericHttpClientInputs,
+            HttpClientBuilder, HttpClientContext,
         },
     },
     tls::{TlsConfig, TlsSettings},
@@ -51,9 +57,12 @@ enum ConfigError {
 pub struct PrometheusScrapeConfig {
     /// Endpoints to scrape metrics from.
     #[configurable(metadata(docs::examples = "http://localhost:9090/metrics"))]
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
 
+    #[configurable(derived)]
+    service_discovery: Option<ServiceDiscoveryConfig>,
+
     /// The interval between scrapes. Requests are run concurrently so if a scrape takes longer
     /// than the interval a new scrape will be started. This can take extra resources, set the timeout
     /// to a value lower than the scrape interval to prevent this from happening.
@@ -85,13 +94,22 @@ pub struct PrometheusScrapeConfig {
     /// Controls how tag conflicts are handled if the scraped source has tags to be added.
     ///
     /// If `true`, the new tag is not added if the scraped metric has the tag already. If `false`, the conflicting tag
-    /// is renamed by prepending `exported_` to the original name.
+    /// is renamed by prepending `exported_` to the original name. This also applies to the labels of the
+    /// discovered targets.
     ///
     /// This matches Prometheus’ `honor_labels` configuration.
     #[serde(default = "crate::serde::default_false")]
     #[configurable(metadata(docs::advanced))]
     honor_labels: bool,
 
+    /// Rules relabeling the scraped metrics, applied in order before the metrics leave the
+    /// source.
+    ///
+    /// This matches Prometheus’ `metric_relabel_configs` configuration.
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    relabel: Vec<RelabelConfig>,
+
     /// Custom parameters for the scrape request query string.
     ///
     /// One or more values for the same parameter key can be provided. The parameters provided in this option are
@@ -123,11 +141,13 @@ impl GenerateConfig for PrometheusScrapeConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             endpoints: vec!["http://localhost:9090/metrics".to_string()],
+            service_discovery: None,
             interval: default_interval(),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            relabel: Vec::new(),
             query: HashMap::new(),
             tls: None,
             auth: None,
@@ -147,11 +167,27 @@ impl SourceConfig for PrometheusScrapeConfig {
             .map(|r| r.map(|uri| build_url(&uri, &self.query)))
             .collect::<std::result::Result<Vec<Uri>, sources::BuildError>>()?;
         let tls = TlsSettings::from_options(&self.tls)?;
+        let relabels = self
+            .relabel
+            .iter()
+            .map(RelabelConfig::build)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let discovery = self
+            .service_discovery
+            .as_ref()
+            .map(|config| {
+                let client = HttpClient::new(tls.clone(), &cx.proxy)?;
+                config.build(client, self.timeout, self.query.clone())
+            })
+            .transpose()?;
 
         let builder = PrometheusScrapeBuilder {
             honor_labels: self.honor_labels,
             instance_tag: self.instance_tag.clone(),
             endpoint_tag: self.endpoint_tag.clone(),
+            relabels: Arc::new(relabels),
+            targets: discovery.as_ref().map(|discovery| discovery.targets()),
         };
 
         warn_if_interval_too_low(self.timeout, self.interval);
@@ -168,7 +204,22 @@ impl SourceConfig for PrometheusScrapeConfig {
             shutdown: cx.shutdown,
         };
 
-        Ok(call(inputs, builder, cx.out, HttpMethod::Get).boxed())
+        let Some(discovery) = discovery else {
+            return Ok(call(inputs, builder, cx.out, HttpMethod::Get).boxed());
+        };
+        let schedule = schedule(inputs.urls.clone(), discovery.targets(), self.interval);
+        let shutdown = inputs.shutdown.clone();
+        let out = cx.out;
+        Ok(async move {
+            // The targets are discovered before the first scrapes.
+            discovery.refresh().await;
+            let (_, result) = futures::join!(
+                discovery.run(shutdown),
+                call_urls(schedule, inputs, builder, out, HttpMethod::Get)
+            );
+            result
+        }
+        .boxed())
     }
 
     fn outputs(&self, _global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
@@ -206,6 +257,8 @@ struct PrometheusScrapeBuilder {
     honor_labels: bool,
     instance_tag: Option<String>,
     endpoint_tag: Option<String>,
+    relabels: Arc<Vec<Relabel>>,
+    targets: Option<DiscoveredTargets>,
 }
 
 impl HttpClientBuilder for PrometheusScrapeBuilder {
@@ -237,6 +290,12 @@ impl HttpClientBuilder for PrometheusScrapeBuilder {
         PrometheusScrapeContext {
             instance_info,
             endpoint_info,
+            target_labels: self
+                .targets
+                .as_ref()
+                .and_then(|targets| targets.labels(url)),
+            honor_labels: self.honor_labels,
+            relabels: Arc::clone(&self.relabels),
         }
     }
 }
@@ -245,28 +304,28 @@ impl HttpClientBuilder for PrometheusScrapeBuilder {
 struct PrometheusScrapeContext {
     instance_info: Option<InstanceInfo>,
     endpoint_info: Option<EndpointInfo>,
+    /// The labels of the discovered target the request is for.
+    target_labels: Option<Arc<Labels>>,
+    honor_labels: bool,
+    relabels: Arc<Vec<Relabel>>,
 }
 
 impl HttpClientContext for PrometheusScrapeContext {
     fn enrich_events(&mut self, events: &mut Vec<Event>) {
         for event in events.iter_mut() {
             let metric = event.as_mut_metric();
+            if let Some(labels) = &self.target_labels {
+                for (tag, value) in labels.iter() {
+                    insert_tag(metric, tag, value, self.honor_labels);
+                }
+            }
             if let Some(InstanceInfo {
                 tag,
                 instance,
                 honor_label,
             }) = &self.instance_info
             {
-                match (honor_label, metric.tag_value(tag)) {
-                    (false, Some(old_instance)) => {
-                        metric.replace_tag(format!("exported_{}", tag), old_instance);
-                        metric.replace_tag(tag.clone(), instance.clone());
-                    }
-                    (true, Some(_)) => {}
-                    (_, None) => {
-                        metric.replace_tag(tag.clone(), instance.clone());
-                    }
-                }
+                insert_tag(metric, tag, instance, *honor_label);
             }
             if let Some(EndpointInfo {
                 tag,
@@ -274,18 +333,17 @@ impl HttpClientContext for PrometheusScrapeContext {
                 honor_label,
             }) = &self.endpoint_info
             {
-                match (honor_label, metric.tag_value(tag)) {
-                    (false, Some(old_endpoint)) => {
-                        metric.replace_tag(format!("exported_{}", tag), old_endpoint);
-                        metric.replace_tag(tag.clone(), endpoint.clone());
-                    }
-                    (true, Some(_)) => {}
-                    (_, None) => {
-                        metric.replace_tag(tag.clone(), endpoint.clone());
-                    }
-                }
+                insert_tag(metric, tag, endpoint, *honor_label);
             }
         }
+
+        if !self.relabels.is_empty() {
+            *events = std::mem::take(events)
+                .into_iter()
+                .filter_map(|event| relabel(&self.relabels, event.into_metric()))
+                .map(Event::Metric)
+                .collect();
+        }
     }
 
     /// Parses the Prometheus HTTP response into metric events
@@ -323,6 +381,21 @@ impl HttpClientContext for PrometheusScrapeContext {
     }
 }
 
+/// Adds the tag to the metric, handling the conflicts with its scraped tags as configured by
+/// `honor_labels`.
+fn insert_tag(metric: &mut Metric, tag: &str, value: &str, honor_label: bool) {
+    match (honor_label, metric.tag_value(tag)) {
+        (false, Some(old_value)) => {
+            metric.replace_tag(format!("exported_{}", tag), old_value);
+            metric.replace_tag(tag.to_owned(), value.to_owned());
+        }
+        (true, Some(_)) => {}
+        (_, None) => {
+            metric.replace_tag(tag.to_owned(), value.to_owned());
+        }
+    }
+}
+
 #[cfg(all(test, feature = "sinks-prometheus"))]
 mod test {
     use hyper::{
@@ -364,11 +437,13 @@ mod test {
 
         let config = PrometheusScrapeConfig {
             endpoints: vec![format!("http://{}/metrics", in_addr)],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
+            relabel: Vec::new(),
             query: HashMap::new(),
             auth: None,
             tls: None,
@@ -398,11 +473,13 @@ mod test {
 
         let config = PrometheusScrapeConfig {
             endpoints: vec![format!("http://{}/metrics", in_addr)],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
+            relabel: Vec::new(),
             query: HashMap::new(),
             auth: None,
             tls: None,
@@ -450,11 +527,13 @@ mod test {
 
         let config = PrometheusScrapeConfig {
             endpoints: vec![format!("http://{}/metrics", in_addr)],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            relabel: Vec::new(),
             query: HashMap::new(),
             auth: None,
             tls: None,
@@ -497,6 +576,120 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_prometheus_relabel() {
+        let in_addr = next_addr();
+
+        let dummy_endpoint = warp::path!("metrics").map(|| {
+                r#"
+                    promhttp_metric_handler_requests_total{instance="localhost:9999", code="200"} 100 1612411516789
+                    go_goroutines{instance="localhost:9999"} 7 1612411516789
+                "#
+        });
+
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+        wait_for_tcp(in_addr).await;
+
+        let relabel: Vec<RelabelConfig> = serde_json::from_value(serde_json::json!([
+            { "action": "drop", "source_labels": ["__name__"], "regex": "go_.*" },
+            { "source_labels": ["exported_instance"], "regex": "(.*):\\d+", "target_label": "host" },
+            { "action": "labeldrop", "regex": "exported_.*|code" },
+        ]))
+        .unwrap();
+        let config = PrometheusScrapeConfig {
+            endpoints: vec![format!("http://{}/metrics", in_addr)],
+            service_discovery: None,
+            interval: Duration::from_secs(1),
+            timeout: default_timeout(),
+            instance_tag: Some("instance".to_string()),
+            endpoint_tag: None,
+            honor_labels: false,
+            relabel,
+            query: HashMap::new(),
+            auth: None,
+            tls: None,
+        };
+
+        let events = run_and_assert_source_compliance(
+            config,
+            Duration::from_secs(3),
+            &HTTP_PULL_SOURCE_TAGS,
+        )
+        .await;
+        assert!(!events.is_empty());
+
+        for event in events {
+            let metric = event.into_metric();
+            assert_eq!(metric.name(), "promhttp_metric_handler_requests_total");
+            assert_eq!(
+                metric.tag_value("instance"),
+                Some(format!("{}:{}", in_addr.ip(), in_addr.port()))
+            );
+            assert_eq!(metric.tag_value("host"), Some(String::from("localhost")));
+            assert_eq!(metric.tag_value("exported_instance"), None);
+            assert_eq!(metric.tag_value("code"), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_service_discovery() {
+        let in_addr = next_addr();
+
+        let targets = format!(
+            r#"[{{"targets": ["{}"], "labels": {{"job": "node", "code": "sd", "__meta_zone": "a"}}}}]"#,
+            in_addr
+        );
+        let dummy_endpoint = warp::path!("metrics")
+            .map(|| {
+                r#"
+                    promhttp_metric_handler_requests_total{code="200"} 100 1612411516789
+                "#
+                .to_owned()
+            })
+            .or(warp::path!("targets").map(move || targets.clone()));
+
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+        wait_for_tcp(in_addr).await;
+
+        let service_discovery: ServiceDiscoveryConfig = serde_json::from_value(serde_json::json!({
+            "http": { "url": format!("http://{}/targets", in_addr) }
+        }))
+        .unwrap();
+        let config = PrometheusScrapeConfig {
+            endpoints: Vec::new(),
+            service_discovery: Some(service_discovery),
+            interval: Duration::from_secs(1),
+            timeout: default_timeout(),
+            instance_tag: Some("instance".to_string()),
+            endpoint_tag: None,
+            honor_labels: false,
+            relabel: Vec::new(),
+            query: HashMap::new(),
+            auth: None,
+            tls: None,
+        };
+
+        let events = run_and_assert_source_compliance(
+            config,
+            Duration::from_secs(3),
+            &HTTP_PULL_SOURCE_TAGS,
+        )
+        .await;
+        assert!(!events.is_empty());
+
+        for event in events {
+            let metric = event.into_metric();
+            assert_eq!(
+                metric.tag_value("instance"),
+                Some(format!("{}:{}", in_addr.ip(), in_addr.port()))
+            );
+            assert_eq!(metric.tag_value("job"), Some(String::from("node")));
+            assert_eq!(metric.tag_value("code"), Some(String::from("sd")));
+            assert_eq!(metric.tag_value("exported_code"), Some(String::from("200")));
+            assert_eq!(metric.tag_value("__meta_zone"), None);
+        }
+    }
+
     /// According to the [spec](https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md?plain=1#L115)
     /// > Label names MUST be unique within a LabelSet.
     /// Prometheus itself will reject the metric with an error. Largely to remain backward compatible with older versions of Vector,
@@ -516,11 +709,13 @@ mod test {
 
         let config = PrometheusScrapeConfig {
             endpoints: vec![format!("http://{}/metrics", in_addr)],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: true,
+            relabel: Vec::new(),
             query: HashMap::new(),
             auth: None,
             tls: None,
@@ -572,11 +767,13 @@ mod test {
 
         let config = PrometheusScrapeConfig {
             endpoints: vec![format!("http://{}/metrics?key1=val1", in_addr)],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: default_timeout(),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            relabel: Vec::new(),
             query: HashMap::from([
                 ("key1".to_string(), vec!["val2".to_string()]),
                 (
@@ -682,9 +879,11 @@ mod test {
             "in",
             PrometheusScrapeConfig {
                 endpoints: vec![format!("http://{}", in_addr)],
+                service_discovery: None,
                 instance_tag: None,
                 endpoint_tag: None,
                 honor_labels: false,
+                relabel: Vec::new(),
                 query: HashMap::new(),
                 interval: Duration::from_secs(1),
                 timeout: default_timeout(),
@@ -772,11 +971,13 @@ mod integration_tests {
     async fn scrapes_metrics() {
         let config = PrometheusScrapeConfig {
             endpoints: vec!["http://prometheus:9090/metrics".into()],
+            service_discovery: None,
             interval: Duration::from_secs(1),
             timeout: Duration::from_secs(1),
             instance_tag: Some("instance".to_string()),
             endpoint_tag: Some("endpoint".to_string()),
             honor_labels: false,
+            relabel: Vec::new(),
             query: HashMap::new(),
             auth: None,
             tls: None,
@@ -0,0 +1,515 @@
+//! Discovery of the scrape targets, following Prometheus’ `http_sd_configs`.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    future::ready,
+    hash::{Hash, Hasher},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use futures_util::{stream::FuturesUnordered, Stream, StreamExt};
+use http::{StatusCode, Uri};
+use hyper::{Body, Request};
+use serde::Deserialize;
+use serde_with::serde_as;
+use snafu::{ResultExt, Snafu};
+use tokio_stream::wrappers::IntervalStream;
+use vector_lib::configurable::configurable_component;
+use vector_lib::shutdown::ShutdownSignal;
+
+use crate::{
+    http::{HttpClient, HttpError},
+    internal_events::PrometheusServiceDiscoveryError,
+    sources::{self, util::http_client::build_url},
+};
+
+/// The label overriding the scheme the target is scraped with.
+const SCHEME_LABEL: &str = "__scheme__";
+
+/// The label overriding the path the target is scraped at.
+const METRICS_PATH_LABEL: &str = "__metrics_path__";
+
+/// The prefix of the labels that aren't attached to the scraped metrics.
+const RESERVED_LABEL_PREFIX: &str = "__";
+
+const DEFAULT_SCHEME: &str = "http";
+
+const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+/// The labels of a discovered target.
+pub(super) type Labels = BTreeMap<String, String>;
+
+/// Configuration for discovering the targets to scrape, in addition to the `endpoints`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceDiscoveryConfig {
+    #[configurable(derived)]
+    http: HttpServiceDiscoveryConfig,
+}
+
+/// Configuration for discovering the targets over HTTP.
+///
+/// This matches Prometheus’ `http_sd_configs` configuration. The URL must return a JSON array of
+/// target groups, like `[{"targets": ["10.0.0.1:9100"], "labels": {"job": "node"}}]`.
+///
+/// The targets are scraped at `/metrics` over HTTP, unless the `__scheme__` or `__metrics_path__`
+/// labels of their group say otherwise. The other labels of the group are added as tags to the
+/// metrics scraped from its targets, except for the ones starting with `__`.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HttpServiceDiscoveryConfig {
+    /// The URL returning the targets to scrape.
+    #[configurable(metadata(docs::examples = "http://localhost:8080/targets"))]
+    url: String,
+
+    /// The interval between the requests for the targets.
+    ///
+    /// The targets no longer returned are no longer scraped after the next request. If a request
+    /// fails, the targets returned by the latest successful one are still scraped.
+    #[serde(default = "default_refresh_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(rename = "refresh_interval_secs")]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    refresh_interval: Duration,
+}
+
+const fn default_refresh_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+impl ServiceDiscoveryConfig {
+    /// Builds the discovery of the targets, requested with `client` within `timeout`.
+    ///
+    /// The `query` parameters are appended to the URLs of the discovered targets.
+    pub(super) fn build(
+        &self,
+        client: HttpClient,
+        timeout: Duration,
+        query: HashMap<String, Vec<String>>,
+    ) -> crate::Result<HttpServiceDiscovery> {
+        let url = self
+            .http
+            .url
+            .parse::<Uri>()
+            .context(sources::UriParseSnafu)?;
+        Ok(HttpServiceDiscovery {
+            url,
+            refresh_interval: self.http.refresh_interval,
+            timeout,
+            query,
+            client,
+            targets: DiscoveredTargets::default(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum ServiceDiscoveryError {
+    #[snafu(display("request timed out after {}s", timeout.as_secs_f64()))]
+    Timeout { timeout: Duration },
+    #[snafu(display("request failed: {}", source))]
+    Request { source: HttpError },
+    #[snafu(display("unexpected response status: {}", status))]
+    Status { status: StatusCode },
+    #[snafu(display("failed to read the response: {}", source))]
+    Body { source: hyper::Error },
+    #[snafu(display("invalid target groups: {}", source))]
+    Document { source: serde_json::Error },
+    #[snafu(display("invalid target {:?}: {}", target, source))]
+    InvalidTarget {
+        target: String,
+        source: http::uri::InvalidUri,
+    },
+}
+
+/// A group of targets sharing the same labels.
+#[derive(Debug, Deserialize)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: Labels,
+}
+
+/// Parses the target groups, into the URLs the targets are scraped at, and their labels.
+fn parse_targets(
+    body: &[u8],
+    query: &HashMap<String, Vec<String>>,
+) -> Result<HashMap<Uri, Arc<Labels>>, ServiceDiscoveryError> {
+    let groups: Vec<TargetGroup> = serde_json::from_slice(body).context(DocumentSnafu)?;
+
+    let mut targets = HashMap::new();
+    for group in groups {
+        let scheme = group
+            .labels
+            .get(SCHEME_LABEL)
+            .map_or(DEFAULT_SCHEME, String::as_str);
+        let metrics_path = group
+            .labels
+            .get(METRICS_PATH_LABEL)
+            .map_or(DEFAULT_METRICS_PATH, String::as_str);
+        let labels: Arc<Labels> = Arc::new(
+            group
+                .labels
+                .iter()
+                .filter(|(name, _)| !name.starts_with(RESERVED_LABEL_PREFIX))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        );
+
+        for target in group.targets {
+            let url = format!("{}://{}{}", scheme, target, metrics_path)
+                .parse::<Uri>()
+                .context(InvalidTargetSnafu { target })?;
+            targets.insert(build_url(&url, query), Arc::clone(&labels));
+        }
+    }
+    Ok(targets)
+}
+
+/// The discovered targets, by the URL they're scraped at, shared between the discovery and the
+/// scrapes.
+#[derive(Clone, Debug, Default)]
+pub(super) struct DiscoveredTargets(Arc<RwLock<HashMap<Uri, Arc<Labels>>>>);
+
+impl DiscoveredTargets {
+    fn replace(&self, targets: HashMap<Uri, Arc<Labels>>) {
+        *self.0.write().expect("the lock is never poisoned") = targets;
+    }
+
+    fn urls(&self) -> Vec<Uri> {
+        self.0
+            .read()
+            .expect("the lock is never poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn contains(&self, url: &Uri) -> bool {
+        self.0
+            .read()
+            .expect("the lock is never poisoned")
+            .contains_key(url)
+    }
+
+    /// Returns the labels of the target scraped at `url`, if it is discovered.
+    pub(super) fn labels(&self, url: &Uri) -> Option<Arc<Labels>> {
+        self.0
+            .read()
+            .expect("the lock is never poisoned")
+            .get(url)
+            .map(Arc::clone)
+    }
+}
+
+/// Discovers the targets by requesting them over HTTP, every refresh interval.
+pub(super) struct HttpServiceDiscovery {
+    url: Uri,
+    refresh_interval: Duration,
+    timeout: Duration,
+    query: HashMap<String, Vec<String>>,
+    client: HttpClient,
+    targets: DiscoveredTargets,
+}
+
+impl HttpServiceDiscovery {
+    /// Returns the targets discovered by the latest successful request.
+    pub(super) fn targets(&self) -> DiscoveredTargets {
+        self.targets.clone()
+    }
+
+    async fn fetch(&self) -> Result<HashMap<Uri, Arc<Labels>>, ServiceDiscoveryError> {
+        let request = Request::get(&self.url)
+            .header(http::header::ACCEPT, "application/json")
+            .body(Body::empty())
+            .expect("error creating request");
+        let response = self.client.send(request).await.context(RequestSnafu)?;
+
+        let (parts, body) = response.into_parts();
+        if parts.status != StatusCode::OK {
+            return StatusSnafu {
+                status: parts.status,
+            }
+            .fail();
+        }
+        let body = hyper::body::to_bytes(body).await.context(BodySnafu)?;
+        parse_targets(&body, &self.query)
+    }
+
+    /// Requests the targets, keeping the ones discovered before if the request fails.
+    pub(super) async fn refresh(&self) {
+        let result = tokio::time::timeout(self.timeout, self.fetch())
+            .await
+            .unwrap_or(Err(ServiceDiscoveryError::Timeout {
+                timeout: self.timeout,
+            }));
+        match result {
+            Ok(targets) => {
+                debug!(
+                    message = "Discovered targets.",
+                    url = %self.url,
+                    count = targets.len(),
+                );
+                self.targets.replace(targets);
+            }
+            Err(error) => emit!(PrometheusServiceDiscoveryError {
+                error: error.into(),
+                url: self.url.clone(),
+            }),
+        }
+    }
+
+    /// Refreshes the targets every refresh interval, starting one interval from now, until
+    /// `shutdown`.
+    pub(super) async fn run(self, shutdown: ShutdownSignal) {
+        let start = tokio::time::Instant::now() + self.refresh_interval;
+        let mut ticks = IntervalStream::new(tokio::time::interval_at(start, self.refresh_interval))
+            .take_until(shutdown);
+        while ticks.next().await.is_some() {
+            self.refresh().await;
+        }
+    }
+}
+
+/// The offset of the scrapes of the target within the scrape interval.
+///
+/// The offset is derived from the URL of the target, so each target is scraped at a regular
+/// interval, while the scrapes of the targets are spread across the interval.
+fn scrape_offset(url: &Uri, interval: Duration) -> Duration {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let interval = u64::try_from(interval.as_nanos())
+        .unwrap_or(u64::MAX)
+        .max(1);
+    Duration::from_nanos(hasher.finish() % interval)
+}
+
+/// Schedules the scrapes of the static and the discovered targets, every `interval`.
+///
+/// Each target is scraped at its offset within the interval. The discovered targets gone by the
+/// time of their scrape are skipped.
+pub(super) fn schedule(
+    static_urls: Vec<Uri>,
+    targets: DiscoveredTargets,
+    interval: Duration,
+) -> impl Stream<Item = Uri> + Send {
+    IntervalStream::new(tokio::time::interval(interval))
+        .map(move |_| {
+            let discovered = targets
+                .urls()
+                .into_iter()
+                .filter(|url| !static_urls.contains(url))
+                .map(|url| (url, false));
+            static_urls
+                .iter()
+                .map(|url| (url.clone(), true))
+                .chain(discovered)
+                .map(|(url, is_static)| {
+                    let targets = targets.clone();
+                    async move {
+                        tokio::time::sleep(scrape_offset(&url, interval)).await;
+                        (is_static || targets.contains(&url)).then_some(url)
+                    }
+                })
+                .collect::<FuturesUnordered<_>>()
+                .filter_map(ready)
+        })
+        .flatten_unordered(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use warp::Filter;
+
+    use super::*;
+    use crate::{
+        test_util::{next_addr, wait_for_tcp},
+        tls::TlsSettings,
+    };
+
+    fn targets(urls: &[&str]) -> HashMap<Uri, Arc<Labels>> {
+        urls.iter()
+            .map(|url| (url.parse().unwrap(), Arc::default()))
+            .collect()
+    }
+
+    fn sorted_urls(targets: &DiscoveredTargets) -> Vec<String> {
+        let mut urls: Vec<_> = targets.urls().iter().map(Uri::to_string).collect();
+        urls.sort();
+        urls
+    }
+
+    #[test]
+    fn parse_target_groups() {
+        let body = br#"[
+            {"targets": ["10.0.0.1:9100", "10.0.0.2:9100"], "labels": {"job": "node", "__meta_zone": "a"}},
+            {"targets": ["10.0.0.3:8443"], "labels": {"__scheme__": "https", "__metrics_path__": "/federate"}},
+            {"targets": ["10.0.0.4:9090"]}
+        ]"#;
+        let query = HashMap::from([("format".to_owned(), vec!["text".to_owned()])]);
+
+        let targets = parse_targets(body, &query).unwrap();
+        let labels = |url: &str| {
+            targets
+                .get(&url.parse::<Uri>().unwrap())
+                .map(|labels| labels.as_ref().clone())
+        };
+        let node_labels = Labels::from([("job".to_owned(), "node".to_owned())]);
+
+        assert_eq!(targets.len(), 4);
+        assert_eq!(
+            labels("http://10.0.0.1:9100/metrics?format=text"),
+            Some(node_labels.clone())
+        );
+        assert_eq!(
+            labels("http://10.0.0.2:9100/metrics?format=text"),
+            Some(node_labels)
+        );
+        assert_eq!(
+            labels("https://10.0.0.3:8443/federate?format=text"),
+            Some(Labels::new())
+        );
+        assert_eq!(
+            labels("http://10.0.0.4:9090/metrics?format=text"),
+            Some(Labels::new())
+        );
+    }
+
+    #[test]
+    fn parse_invalid_target_groups() {
+        let query = HashMap::new();
+
+        assert!(matches!(
+            parse_targets(br#"{"targets": []}"#, &query),
+            Err(ServiceDiscoveryError::Document { .. })
+        ));
+        assert!(matches!(
+            parse_targets(br#"[{"targets": ["not a host"]}]"#, &query),
+            Err(ServiceDiscoveryError::InvalidTarget { .. })
+        ));
+        assert!(parse_targets(b"[]", &query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scrape_offset_is_within_interval() {
+        let interval = Duration::from_secs(15);
+        for i in 0..100 {
+            let url: Uri = format!("http://10.0.0.{}:9100/metrics", i).parse().unwrap();
+            let offset = scrape_offset(&url, interval);
+            assert!(offset < interval);
+            assert_eq!(offset, scrape_offset(&url, interval));
+        }
+        assert_eq!(
+            scrape_offset(&Uri::from_static("http://a/metrics"), Duration::ZERO),
+            Duration::ZERO
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn schedule_follows_discovered_targets() {
+        let discovered = DiscoveredTargets::default();
+        discovered.replace(targets(&[
+            "http://10.0.0.1:9100/metrics",
+            "http://10.0.0.2:9100/metrics",
+        ]));
+        let static_url = Uri::from_static("http://localhost:9090/metrics");
+        let mut schedule = Box::pin(schedule(
+            vec![static_url.clone()],
+            discovered.clone(),
+            Duration::from_secs(15),
+        ));
+
+        let mut scraped: Vec<_> = (&mut schedule)
+            .take(3)
+            .map(|url| url.to_string())
+            .collect()
+            .await;
+        scraped.sort();
+        assert_eq!(
+            scraped,
+            [
+                "http://10.0.0.1:9100/metrics",
+                "http://10.0.0.2:9100/metrics",
+                "http://localhost:9090/metrics",
+            ]
+        );
+
+        // The removed target is no longer scraped from the next interval on.
+        discovered.replace(targets(&["http://10.0.0.2:9100/metrics"]));
+        let mut scraped: Vec<_> = (&mut schedule)
+            .take(4)
+            .map(|url| url.to_string())
+            .collect()
+            .await;
+        scraped.sort();
+        assert_eq!(
+            scraped,
+            [
+                "http://10.0.0.2:9100/metrics",
+                "http://10.0.0.2:9100/metrics",
+                "http://localhost:9090/metrics",
+                "http://localhost:9090/metrics",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_keeps_targets_on_failure() {
+        let in_addr = next_addr();
+        let response = Arc::new(Mutex::new(Some(
+            r#"[{"targets": ["10.0.0.1:9100", "10.0.0.2:9100"]}]"#.to_owned(),
+        )));
+
+        let served = Arc::clone(&response);
+        let dummy_endpoint =
+            warp::path!("targets").map(move || match served.lock().unwrap().clone() {
+                Some(body) => warp::http::Response::builder().status(200).body(body),
+                None => warp::http::Response::builder()
+                    .status(500)
+                    .body(String::new()),
+            });
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+        wait_for_tcp(in_addr).await;
+
+        let config: ServiceDiscoveryConfig = toml::from_str(&format!(
+            r#"
+            [http]
+            url = "http://{}/targets"
+            "#,
+            in_addr
+        ))
+        .unwrap();
+        let client = HttpClient::new(TlsSettings::default(), &Default::default()).unwrap();
+        let discovery = config
+            .build(client, Duration::from_secs(5), HashMap::new())
+            .unwrap();
+        let targets = discovery.targets();
+
+        discovery.refresh().await;
+        assert_eq!(
+            sorted_urls(&targets),
+            [
+                "http://10.0.0.1:9100/metrics",
+                "http://10.0.0.2:9100/metrics"
+            ]
+        );
+
+        *response.lock().unwrap() = None;
+        discovery.refresh().await;
+        assert_eq!(sorted_urls(&targets).len(), 2);
+
+        *response.lock().unwrap() = Some("not a target group".to_owned());
+        discovery.refresh().await;
+        assert_eq!(sorted_urls(&targets).len(), 2);
+
+        *response.lock().unwrap() = Some(r#"[{"targets": ["10.0.0.2:9100"]}]"#.to_owned());
+        discovery.refresh().await;
+        assert_eq!(sorted_urls(&targets), ["http://10.0.0.2:9100/metrics"]);
+    }
+}
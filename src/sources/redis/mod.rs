@@ -16,23 +16,39 @@ use vector_lib::{
     config::{LegacyKey, LogNamespace},
     EstimatedJsonEncodedSizeOf,
 };
-use vrl::value::Kind;
+use vrl::value::{kind::Collection, Kind};
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{log_schema, GenerateConfig, SourceConfig, SourceContext, SourceOutput},
+    config::{
+        log_schema, DataType, GenerateConfig, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput,
+    },
     event::Event,
     internal_events::{EventsReceived, StreamClosedError},
-    serde::{default_decoding, default_framing_message_based},
+    schema::Definition,
+    serde::{bool_or_struct, default_decoding, default_framing_message_based, default_true},
+    template::Template,
 };
 
 mod channel;
 mod list;
+mod stream;
 
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Failed to build redis client: {}", source))]
     Client { source: redis::RedisError },
+    #[snafu(display("`stream` options are required with the `stream` data type"))]
+    MissingStreamOptions,
+    #[snafu(display("Invalid consumer name: {}", source))]
+    ConsumerName {
+        source: crate::template::TemplateParseError,
+    },
+    #[snafu(display("Failed to render the consumer name: {}", source))]
+    ConsumerNameRender {
+        source: crate::template::TemplateRenderingError,
+    },
 }
 
 /// Data type to use for reading messages from Redis.
@@ -49,6 +65,12 @@ pub enum DataTypeConfig {
     ///
     /// This is based on Redis' Pub/Sub capabilities.
     Channel,
+
+    /// The `stream` data type.
+    ///
+    /// The entries of the stream are read as a member of a consumer group, so that the
+    /// consumers of the group share them.
+    Stream,
 }
 
 /// Options for the Redis `list` data type.
@@ -74,6 +96,81 @@ pub enum Method {
     Rpop,
 }
 
+/// Options for the Redis `stream` data type.
+///
+/// When end-to-end acknowledgements are enabled, the entries are only acknowledged with `XACK`
+/// once their events are delivered, and otherwise stay pending to be claimed again. Without, they
+/// are acknowledged once their events are sent.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// The consumer group to read the entries of the stream as.
+    #[configurable(metadata(docs::examples = "vector"))]
+    group: String,
+
+    /// The name of the consumer within the group.
+    ///
+    /// The `{{ hostname }}` template is replaced by the hostname of the machine Vector runs on,
+    /// so that each instance reads as a distinct consumer, and reclaims its own pending entries
+    /// after a restart.
+    #[serde(default = "default_consumer_name")]
+    #[configurable(metadata(docs::examples = "vector-{{ hostname }}"))]
+    consumer_name: String,
+
+    /// How long to block waiting for new entries, in milliseconds.
+    #[serde(default = "default_block_ms")]
+    #[configurable(metadata(docs::type_unit = "milliseconds"))]
+    block_ms: u64,
+
+    /// The time after which the pending entries of a consumer are claimed, in milliseconds.
+    ///
+    /// The entries delivered to a consumer stay pending until they are acknowledged. When set,
+    /// the entries pending for longer than this, like the ones of a consumer that is gone, are
+    /// reclaimed with `XAUTOCLAIM` and read again.
+    ///
+    /// By default, the pending entries of other consumers aren't claimed.
+    #[configurable(metadata(docs::type_unit = "milliseconds"))]
+    #[configurable(metadata(docs::examples = 60000))]
+    claim_idle_ms: Option<u64>,
+
+    /// Whether to create the consumer group, and the stream, if they don't exist.
+    ///
+    /// The group is created with `MKSTREAM`, so that it reads the entries added to the stream
+    /// from then on.
+    #[serde(default = "default_true")]
+    create_group: bool,
+
+    /// Sets the name of the log field to use to add the ID of the stream entry to each event.
+    ///
+    /// By default, this is not set and the field is not automatically added.
+    #[configurable(metadata(docs::examples = "id"))]
+    id_key: Option<OptionalValuePath>,
+}
+
+fn default_consumer_name() -> String {
+    "{{ hostname }}".to_owned()
+}
+
+const fn default_block_ms() -> u64 {
+    5000
+}
+
+impl StreamOption {
+    /// Renders the name of the consumer, with the hostname.
+    fn consumer_name(&self) -> crate::Result<String> {
+        let template =
+            Template::try_from(self.consumer_name.as_str()).context(ConsumerNameSnafu)?;
+        let mut event = vector_lib::event::LogEvent::default();
+        if let Ok(hostname) = crate::get_hostname() {
+            event.insert("hostname", hostname);
+        }
+        Ok(template
+            .render_string(&event)
+            .context(ConsumerNameRenderSnafu)?)
+    }
+}
+
 pub struct ConnectionInfo {
     protocol: &'static str,
     endpoint: String,
@@ -98,13 +195,16 @@ impl From<&redis::ConnectionInfo> for ConnectionInfo {
 #[derive(Clone, Debug, Derivative)]
 #[serde(deny_unknown_fields)]
 pub struct RedisSourceConfig {
-    /// The Redis data type (`list` or `channel`) to use.
+    /// The Redis data type (`list`, `channel` or `stream`) to use.
     #[serde(default)]
     data_type: DataTypeConfig,
 
     #[configurable(derived)]
     list: Option<ListOption>,
 
+    #[configurable(derived)]
+    stream: Option<StreamOption>,
+
     /// The Redis URL to connect to.
     ///
     /// The URL must take the form of `protocol://server:port/db` where the `protocol` can either be `redis` or `rediss` for connections secured using TLS.
@@ -137,6 +237,10 @@ pub struct RedisSourceConfig {
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
     log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
 }
 
 impl GenerateConfig for RedisSourceConfig {
@@ -165,6 +269,7 @@ impl SourceConfig for RedisSourceConfig {
             return Err("`key` cannot be empty.".into());
         }
         let redis_key = self.redis_key.clone().and_then(|k| k.path);
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
 
         let client = redis::Client::open(self.url.as_str()).context(ClientSnafu {})?;
         let connection_info = ConnectionInfo::from(client.get_connection_info());
@@ -193,6 +298,16 @@ impl SourceConfig for RedisSourceConfig {
                 handler.watch(method).await
             }
             DataTypeConfig::Channel => handler.subscribe(connection_info).await,
+            DataTypeConfig::Stream => {
+                let options = self
+                    .stream
+                    .as_ref()
+                    .ok_or(BuildError::MissingStreamOptions)?;
+                let consumer_name = options.consumer_name()?;
+                handler
+                    .read_stream(options, consumer_name, acknowledgements)
+                    .await
+            }
         }
     }
 
@@ -205,9 +320,42 @@ impl SourceConfig for RedisSourceConfig {
             .and_then(|k| k.path)
             .map(LegacyKey::InsertIfEmpty);
 
-        let schema_definition = self
-            .decoding
-            .schema_definition(log_namespace)
+        let (schema_definition, output_type) = match (self.data_type, &self.stream) {
+            // The fields of the stream entries are the fields of the events.
+            (DataTypeConfig::Stream, Some(options)) => (
+                Definition::new_with_default_metadata(
+                    Kind::object(Collection::empty().with_unknown(Kind::bytes())),
+                    [log_namespace],
+                )
+                .with_source_metadata(
+                    Self::NAME,
+                    options
+                        .id_key
+                        .clone()
+                        .and_then(|k| k.path)
+                        .map(LegacyKey::InsertIfEmpty),
+                    &owned_value_path!("id"),
+                    Kind::bytes(),
+                    None,
+                )
+                .with_source_metadata(
+                    Self::NAME,
+                    log_schema()
+                        .timestamp_key()
+                        .cloned()
+                        .map(LegacyKey::InsertIfEmpty),
+                    &owned_value_path!("timestamp"),
+                    Kind::timestamp(),
+                    Some("timestamp"),
+                ),
+                DataType::Log,
+            ),
+            _ => (
+                self.decoding.schema_definition(log_namespace),
+                self.decoding.output_type(),
+            ),
+        };
+        let schema_definition = schema_definition
             .with_source_metadata(
                 Self::NAME,
                 redis_key_path,
@@ -217,14 +365,11 @@ impl SourceConfig for RedisSourceConfig {
             )
             .with_standard_vector_source_metadata();
 
-        vec![SourceOutput::new_logs(
-            self.decoding.output_type(),
-            schema_definition,
-        )]
+        vec![SourceOutput::new_logs(output_type, schema_definition)]
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        matches!(self.data_type, DataTypeConfig::Stream)
     }
 }
 
@@ -315,6 +460,7 @@ mod integration_test {
     use super::*;
     use crate::{
         config::log_schema,
+        event::EventStatus,
         test_util::{
             collect_n,
             components::{run_and_assert_source_compliance_n, SOURCE_TAGS},
@@ -345,12 +491,14 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Rpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace: Some(false),
+            acknowledgements: Default::default(),
         };
 
         let events = run_and_assert_source_compliance_n(config, 3, &SOURCE_TAGS).await;
@@ -386,12 +534,14 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Rpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: Some(OptionalValuePath::from(owned_value_path!("remapped_key"))),
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace: Some(true),
+            acknowledgements: Default::default(),
         };
 
         let events = run_and_assert_source_compliance_n(config, 1, &SOURCE_TAGS).await;
@@ -427,12 +577,14 @@ mod integration_test {
             list: Some(ListOption {
                 method: Method::Lpop,
             }),
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace: Some(false),
+            acknowledgements: Default::default(),
         };
 
         let events = run_and_assert_source_compliance_n(config, 3, &SOURCE_TAGS).await;
@@ -451,6 +603,83 @@ mod integration_test {
         );
     }
 
+    #[tokio::test]
+    async fn redis_source_stream_consumer_group() {
+        let client = redis::Client::open(REDIS_SERVER).unwrap();
+        let mut conn = client.get_tokio_connection_manager().await.unwrap();
+
+        let key = format!("test-stream-{}", random_string(10));
+        debug!("Test key name: {}.", key);
+
+        // The group reads the stream from its beginning, and already exists
+        // when the source starts.
+        let _: () = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&key)
+            .arg("vector")
+            .arg("0")
+            .arg("MKSTREAM")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        for i in 0..3 {
+            let _: String = conn
+                .xadd(
+                    &key,
+                    "*",
+                    &[("message", i.to_string()), ("level", "info".to_owned())],
+                )
+                .await
+                .unwrap();
+        }
+
+        let config = RedisSourceConfig {
+            data_type: DataTypeConfig::Stream,
+            list: None,
+            stream: Some(StreamOption {
+                group: "vector".to_owned(),
+                consumer_name: "vector-{{ hostname }}".to_owned(),
+                block_ms: 100,
+                claim_idle_ms: None,
+                create_group: true,
+                id_key: Some(OptionalValuePath::from(owned_value_path!("id"))),
+            }),
+            url: REDIS_SERVER.to_owned(),
+            key: key.clone(),
+            redis_key: None,
+            framing: default_framing_message_based(),
+            decoding: default_decoding(),
+            log_namespace: Some(false),
+            acknowledgements: true.into(),
+        };
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let context = SourceContext::new_test(tx, None);
+        let source = config
+            .build(context)
+            .await
+            .expect("source should not fail to build");
+        tokio::spawn(source);
+
+        let events = collect_n(rx, 3).await;
+        for (i, event) in events.iter().enumerate() {
+            let log = event.as_log();
+            assert_eq!(log["message"], i.to_string().into());
+            assert_eq!(log["level"], "info".into());
+            assert!(log.contains("id"));
+        }
+
+        // The entries are acknowledged once their events are delivered.
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        let pending: (usize, redis::Value, redis::Value, redis::Value) = redis::cmd("XPENDING")
+            .arg(&key)
+            .arg("vector")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(pending.0, 0);
+    }
+
     #[tokio::test]
     async fn redis_source_channel_consume_event() {
         let key = format!("test-channel-{}", random_string(10));
@@ -460,12 +689,14 @@ mod integration_test {
         let config = RedisSourceConfig {
             data_type: DataTypeConfig::Channel,
             list: None,
+            stream: None,
             url: REDIS_SERVER.to_owned(),
             key: key.clone(),
             redis_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace: Some(false),
+            acknowledgements: Default::default(),
         };
 
         let (tx, rx) = SourceSender::new_test();
@@ -0,0 +1,502 @@
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::StreamExt;
+use redis::{aio::ConnectionManager, ErrorKind, RedisError, RedisResult, Value};
+use snafu::{ResultExt, Snafu};
+use vector_lib::{
+    config::LegacyKey,
+    finalizer::UnorderedFinalizer,
+    internal_event::{ByteSize, CountByteSize, InternalEventHandle as _},
+    lookup::OwnedValuePath,
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::{path, value::ObjectMap};
+
+use super::{list::backoff_exponential, InputHandler, RedisSourceConfig, StreamOption};
+use crate::{
+    config::log_schema,
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
+    internal_events::{RedisReceiveEventError, RedisStreamAckError, StreamClosedError},
+    sources::Source,
+};
+
+/// The maximum number of entries read at once.
+const READ_COUNT: usize = 100;
+
+/// The cursor `XAUTOCLAIM` starts from, and returns once it went through all
+/// the pending entries.
+const CLAIM_START: &str = "0-0";
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to create connection: {}", source))]
+    Connection { source: RedisError },
+    #[snafu(display("Failed to create consumer group: {}", source))]
+    CreateGroup { source: RedisError },
+}
+
+/// An entry of a stream.
+#[derive(Debug, PartialEq)]
+struct StreamEntry {
+    id: String,
+    fields: Vec<(String, Bytes)>,
+}
+
+/// Reads the entries of a stream as a consumer of a group.
+struct StreamReader {
+    key: String,
+    group: String,
+    consumer_name: String,
+    block_ms: u64,
+    claim_idle_ms: Option<u64>,
+    /// The ID to read the entries still pending for the consumer from, until
+    /// they were all read again after a restart.
+    pending_from: Option<String>,
+    claim_cursor: String,
+    next_claim: Instant,
+}
+
+impl StreamReader {
+    fn new(key: String, options: &StreamOption, consumer_name: String) -> Self {
+        Self {
+            key,
+            group: options.group.clone(),
+            consumer_name,
+            block_ms: options.block_ms,
+            claim_idle_ms: options.claim_idle_ms,
+            pending_from: Some("0".to_owned()),
+            claim_cursor: CLAIM_START.to_owned(),
+            next_claim: Instant::now(),
+        }
+    }
+
+    /// Reads the next entries: the ones claimed from idle consumers first, then
+    /// the ones pending for this consumer, then the new ones.
+    async fn read(&mut self, conn: &mut ConnectionManager) -> RedisResult<Vec<StreamEntry>> {
+        if let Some(min_idle_ms) = self.claim_idle_ms {
+            if Instant::now() >= self.next_claim {
+                let reply = redis::cmd("XAUTOCLAIM")
+                    .arg(&self.key)
+                    .arg(&self.group)
+                    .arg(&self.consumer_name)
+                    .arg(min_idle_ms)
+                    .arg(&self.claim_cursor)
+                    .arg("COUNT")
+                    .arg(READ_COUNT)
+                    .query_async(conn)
+                    .await?;
+                let (cursor, entries) = parse_autoclaim_reply(&reply)?;
+                if cursor == CLAIM_START {
+                    self.next_claim = Instant::now() + Duration::from_millis(min_idle_ms);
+                }
+                self.claim_cursor = cursor;
+                if !entries.is_empty() {
+                    return Ok(entries);
+                }
+            }
+        }
+
+        if let Some(pending_from) = self.pending_from.take() {
+            let reply = self.read_group(conn, &pending_from, None).await?;
+            let entries = parse_read_reply(&reply)?;
+            if let Some(last) = entries.last() {
+                self.pending_from = Some(last.id.clone());
+                return Ok(entries);
+            }
+        }
+
+        let reply = self.read_group(conn, ">", Some(self.block_ms)).await?;
+        parse_read_reply(&reply)
+    }
+
+    async fn read_group(
+        &self,
+        conn: &mut ConnectionManager,
+        id: &str,
+        block_ms: Option<u64>,
+    ) -> RedisResult<Value> {
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP")
+            .arg(&self.group)
+            .arg(&self.consumer_name)
+            .arg("COUNT")
+            .arg(READ_COUNT);
+        if let Some(block_ms) = block_ms {
+            cmd.arg("BLOCK").arg(block_ms);
+        }
+        cmd.arg("STREAMS").arg(&self.key).arg(id);
+        cmd.query_async(conn).await
+    }
+}
+
+impl InputHandler {
+    pub(super) async fn read_stream(
+        mut self,
+        options: &StreamOption,
+        consumer_name: String,
+        acknowledgements: bool,
+    ) -> crate::Result<Source> {
+        let mut conn = self
+            .client
+            .get_tokio_connection_manager()
+            .await
+            .context(ConnectionSnafu {})?;
+        // A blocking read holds the connection it is sent on, so the entries
+        // are acknowledged on another one.
+        let mut ack_conn = self
+            .client
+            .get_tokio_connection_manager()
+            .await
+            .context(ConnectionSnafu {})?;
+
+        if options.create_group {
+            create_group(&mut conn, &self.key, &options.group)
+                .await
+                .context(CreateGroupSnafu {})?;
+        }
+
+        let group = options.group.clone();
+        let id_key = options.id_key.clone().and_then(|k| k.path);
+        let mut reader = StreamReader::new(self.key.clone(), options, consumer_name);
+        trace!(key = %self.key, group = %group, consumer = %reader.consumer_name, "Reading stream.");
+
+        Ok(Box::pin(async move {
+            let mut shutdown = self.cx.shutdown.clone();
+            let (finalizer, mut ack_stream) = UnorderedFinalizer::<Vec<String>>::maybe_new(
+                acknowledgements,
+                Some(shutdown.clone()),
+            );
+            let mut retry: u32 = 0;
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    entry = ack_stream.next() => {
+                        if let Some((status, ids)) = entry {
+                            // The entries of undelivered events stay pending, to
+                            // be claimed again.
+                            if status == BatchStatus::Delivered {
+                                ack(&mut ack_conn, &self.key, &group, &ids).await;
+                            }
+                        }
+                    },
+                    res = reader.read(&mut conn) => match res {
+                        Err(error) => {
+                            let kind = error.kind();
+
+                            emit!(RedisReceiveEventError::from(error));
+
+                            if kind == ErrorKind::IoError {
+                                retry += 1;
+                                backoff_exponential(retry).await
+                            }
+                        }
+                        Ok(entries) => {
+                            retry = 0;
+                            if entries.is_empty() {
+                                continue;
+                            }
+
+                            let ids: Vec<String> =
+                                entries.iter().map(|entry| entry.id.clone()).collect();
+                            let events: Vec<Event> = entries
+                                .into_iter()
+                                .map(|entry| self.entry_event(entry, id_key.as_ref()))
+                                .collect();
+                            if let Err(()) = self.send_entry_events(events, ids.clone(), finalizer.as_ref()).await {
+                                break;
+                            }
+                            if finalizer.is_none() {
+                                ack(&mut ack_conn, &self.key, &group, &ids).await;
+                            }
+                        }
+                    },
+                }
+            }
+            Ok(())
+        }))
+    }
+
+    /// Creates the event of the entry, with a field for each field of the entry.
+    fn entry_event(&self, entry: StreamEntry, id_key: Option<&OwnedValuePath>) -> Event {
+        let now = Utc::now();
+        let byte_size = entry
+            .fields
+            .iter()
+            .map(|(field, value)| field.len() + value.len())
+            .sum();
+        self.bytes_received.emit(ByteSize(byte_size));
+
+        let timestamp = entry_timestamp(&entry.id);
+        let fields: ObjectMap = entry
+            .fields
+            .into_iter()
+            .map(|(field, value)| (field.into(), value.into()))
+            .collect();
+        let mut log = LogEvent::from(fields);
+
+        self.log_namespace.insert_source_metadata(
+            RedisSourceConfig::NAME,
+            &mut log,
+            id_key.map(LegacyKey::InsertIfEmpty),
+            path!("id"),
+            entry.id,
+        );
+        if let Some(timestamp) = timestamp {
+            self.log_namespace.insert_source_metadata(
+                RedisSourceConfig::NAME,
+                &mut log,
+                log_schema().timestamp_key().map(LegacyKey::InsertIfEmpty),
+                path!("timestamp"),
+                timestamp,
+            );
+        }
+        self.log_namespace.insert_source_metadata(
+            RedisSourceConfig::NAME,
+            &mut log,
+            self.redis_key.as_ref().map(LegacyKey::InsertIfEmpty),
+            path!("key"),
+            self.key.as_str(),
+        );
+        self.log_namespace.insert_standard_vector_source_metadata(
+            &mut log,
+            RedisSourceConfig::NAME,
+            now,
+        );
+
+        Event::Log(log)
+    }
+
+    /// Sends the events of the entries, which are acknowledged once the events
+    /// are delivered with end-to-end acknowledgements.
+    async fn send_entry_events(
+        &mut self,
+        events: Vec<Event>,
+        ids: Vec<String>,
+        finalizer: Option<&UnorderedFinalizer<Vec<String>>>,
+    ) -> Result<(), ()> {
+        let count = events.len();
+        self.events_received.emit(CountByteSize(
+            count,
+            events.estimated_json_encoded_size_of(),
+        ));
+
+        match finalizer {
+            Some(finalizer) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                let events = events
+                    .into_iter()
+                    .map(|event| event.with_batch_notifier(&batch));
+                self.cx.out.send_batch(events).await.map_err(|_| {
+                    emit!(StreamClosedError { count });
+                })?;
+                finalizer.add(ids, receiver);
+            }
+            None => {
+                self.cx.out.send_batch(events).await.map_err(|_| {
+                    emit!(StreamClosedError { count });
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creates the consumer group, and the stream if it doesn't exist, unless the
+/// group already exists.
+async fn create_group(conn: &mut ConnectionManager, key: &str, group: &str) -> RedisResult<()> {
+    let result: RedisResult<()> = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(key)
+        .arg(group)
+        .arg("$")
+        .arg("MKSTREAM")
+        .query_async(conn)
+        .await;
+    match result {
+        Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+        result => result,
+    }
+}
+
+async fn ack(conn: &mut ConnectionManager, key: &str, group: &str, ids: &[String]) {
+    let result: RedisResult<usize> = redis::cmd("XACK")
+        .arg(key)
+        .arg(group)
+        .arg(ids)
+        .query_async(conn)
+        .await;
+    if let Err(error) = result {
+        emit!(RedisStreamAckError { error });
+    }
+}
+
+/// The time the entry was added at, from the milliseconds part of its ID.
+fn entry_timestamp(id: &str) -> Option<DateTime<Utc>> {
+    let (millis, _) = id.split_once('-')?;
+    Utc.timestamp_millis_opt(millis.parse().ok()?).single()
+}
+
+fn unexpected_reply(command: &'static str) -> RedisError {
+    RedisError::from((ErrorKind::TypeError, "Unexpected reply", command.to_owned()))
+}
+
+/// Parses an entry, as `[id, [field, value, ...]]`.
+///
+/// The entries deleted while they were pending are replied as nil, or with nil
+/// fields, depending on the version of Redis, and are skipped.
+fn parse_entry(value: &Value) -> RedisResult<Option<StreamEntry>> {
+    let items = match value {
+        Value::Nil => return Ok(None),
+        Value::Bulk(items) => items,
+        _ => return Err(unexpected_reply("stream entry")),
+    };
+    let (id, fields) = match items.as_slice() {
+        [_, Value::Nil] => return Ok(None),
+        [id, Value::Bulk(fields)] => (id, fields),
+        _ => return Err(unexpected_reply("stream entry")),
+    };
+
+    let id: String = redis::from_redis_value(id)?;
+    let fields = fields
+        .chunks_exact(2)
+        .map(|pair| {
+            let field: String = redis::from_redis_value(&pair[0])?;
+            let value: Vec<u8> = redis::from_redis_value(&pair[1])?;
+            Ok((field, Bytes::from(value)))
+        })
+        .collect::<RedisResult<_>>()?;
+    Ok(Some(StreamEntry { id, fields }))
+}
+
+fn parse_entries(value: &Value) -> RedisResult<Vec<StreamEntry>> {
+    match value {
+        Value::Bulk(entries) => entries
+            .iter()
+            .filter_map(|entry| parse_entry(entry).transpose())
+            .collect(),
+        _ => Err(unexpected_reply("stream entries")),
+    }
+}
+
+/// Parses the reply of `XREADGROUP` for a single stream, as
+/// `[[key, [entry, ...]]]`, or nil once the read timed out.
+fn parse_read_reply(value: &Value) -> RedisResult<Vec<StreamEntry>> {
+    match value {
+        Value::Nil => Ok(Vec::new()),
+        Value::Bulk(streams) => {
+            let mut entries = Vec::new();
+            for stream in streams {
+                match stream {
+                    Value::Bulk(items) if items.len() == 2 => {
+                        entries.extend(parse_entries(&items[1])?)
+                    }
+                    _ => return Err(unexpected_reply("XREADGROUP")),
+                }
+            }
+            Ok(entries)
+        }
+        _ => Err(unexpected_reply("XREADGROUP")),
+    }
+}
+
+/// Parses the reply of `XAUTOCLAIM`, as `[cursor, [entry, ...]]`, followed by
+/// the IDs of the deleted entries since Redis 7.
+fn parse_autoclaim_reply(value: &Value) -> RedisResult<(String, Vec<StreamEntry>)> {
+    match value {
+        Value::Bulk(items) if items.len() >= 2 => {
+            let cursor: String = redis::from_redis_value(&items[0])?;
+            Ok((cursor, parse_entries(&items[1])?))
+        }
+        _ => Err(unexpected_reply("XAUTOCLAIM")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(value: &str) -> Value {
+        Value::Data(value.as_bytes().to_vec())
+    }
+
+    fn entry(id: &str, fields: &[(&str, &str)]) -> Value {
+        Value::Bulk(vec![
+            data(id),
+            Value::Bulk(
+                fields
+                    .iter()
+                    .flat_map(|(field, value)| [data(field), data(value)])
+                    .collect(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn parses_read_reply() {
+        let reply = Value::Bulk(vec![Value::Bulk(vec![
+            data("vector"),
+            Value::Bulk(vec![
+                entry(
+                    "1700000000000-0",
+                    &[("message", "hello"), ("level", "info")],
+                ),
+                entry("1700000000000-1", &[]),
+            ]),
+        ])]);
+
+        assert_eq!(
+            parse_read_reply(&reply).unwrap(),
+            vec![
+                StreamEntry {
+                    id: "1700000000000-0".to_owned(),
+                    fields: vec![
+                        ("message".to_owned(), Bytes::from("hello")),
+                        ("level".to_owned(), Bytes::from("info")),
+                    ],
+                },
+                StreamEntry {
+                    id: "1700000000000-1".to_owned(),
+                    fields: vec![],
+                },
+            ]
+        );
+        assert!(parse_read_reply(&Value::Nil).unwrap().is_empty());
+        assert!(parse_read_reply(&data("vector")).is_err());
+    }
+
+    #[test]
+    fn parses_autoclaim_reply() {
+        // Redis 6.2 replies the deleted entries as nil.
+        let reply = Value::Bulk(vec![
+            data("1700000000001-0"),
+            Value::Bulk(vec![
+                entry("1700000000000-0", &[("message", "hello")]),
+                Value::Nil,
+            ]),
+        ]);
+        let (cursor, entries) = parse_autoclaim_reply(&reply).unwrap();
+        assert_eq!(cursor, "1700000000001-0");
+        assert_eq!(entries.len(), 1);
+
+        // Redis 7 replies the IDs of the deleted entries separately.
+        let reply = Value::Bulk(vec![
+            data(CLAIM_START),
+            Value::Bulk(vec![]),
+            Value::Bulk(vec![data("1700000000000-1")]),
+        ]);
+        let (cursor, entries) = parse_autoclaim_reply(&reply).unwrap();
+        assert_eq!(cursor, CLAIM_START);
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn timestamp_from_entry_id() {
+        assert_eq!(
+            entry_timestamp("1700000000123-4"),
+            Utc.timestamp_millis_opt(1_700_000_000_123).single()
+        );
+        assert_eq!(entry_timestamp("invalid"), None);
+    }
+}
@@ -270,13 +270,32 @@ impl SourceConfig for SocketConfig {
             Mode::UnixDatagram(config) => {
                 let legacy_host_key = config.host_key().clone().path.map(LegacyKey::InsertIfEmpty);
 
-                schema_definition.with_source_metadata(
-                    Self::NAME,
-                    legacy_host_key,
-                    &owned_value_path!("host"),
-                    Kind::bytes(),
-                    None,
-                )
+                let legacy_peer_credentials_key = config
+                    .peer_credentials_key()
+                    .clone()
+                    .path
+                    .map(LegacyKey::Overwrite);
+
+                schema_definition
+                    .with_source_metadata(
+                        Self::NAME,
+                        legacy_host_key,
+                        &owned_value_path!("host"),
+                        Kind::bytes(),
+                        None,
+                    )
+                    .with_source_metadata(
+                        Self::NAME,
+                        legacy_peer_credentials_key,
+                        &owned_value_path!("peer_credentials"),
+                        Kind::object(std::collections::BTreeMap::from([
+                            ("peer_pid".into(), Kind::integer()),
+                            ("peer_uid".into(), Kind::integer()),
+                            ("peer_gid".into(), Kind::integer()),
+                        ]))
+                        .or_undefined(),
+                        None,
+                    )
             }
             #[cfg(unix)]
             Mode::UnixStream(config) => {
@@ -1227,6 +1246,17 @@ mod test {
             config.log_namespace = Some(true);
         }
 
+        init_unix_with_config(sender, stream, config).await
+    }
+
+    #[cfg(unix)]
+    async fn init_unix_with_config(
+        sender: SourceSender,
+        stream: bool,
+        config: UnixConfig,
+    ) -> PathBuf {
+        let in_path = config.path.clone();
+
         let mode = if stream {
             Mode::UnixStream(config)
         } else {
@@ -1451,6 +1481,54 @@ mod test {
         .await;
     }
 
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn unix_datagram_peer_credentials() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
+            let mut config = UnixConfig::new(in_path);
+            config.peer_credentials_key = OptionalValuePath::from(owned_value_path!("peer"));
+            let path = init_unix_with_config(tx, false, config).await;
+
+            send_lines_unix_datagram(path, &["test"]).await;
+            let events = collect_n(rx, 1).await;
+
+            let log = events[0].as_log();
+            assert_eq!(log["peer.peer_pid"], (std::process::id() as i64).into());
+            // SAFETY: Getting the user and group IDs of the process always succeeds.
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            assert_eq!(log["peer.peer_uid"], i64::from(uid).into());
+            assert_eq!(log["peer.peer_gid"], i64::from(gid).into());
+        })
+        .await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_datagram_truncates_oversized() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
+            let mut config = UnixConfig::new(in_path);
+            config.framing = Some(NewlineDelimitedDecoderConfig::new_with_max_length(10).into());
+            let path = init_unix_with_config(tx, false, config).await;
+
+            send_lines_unix_datagram(path, &["0123456789abcdef", "short"]).await;
+            let events = collect_n(rx, 2).await;
+
+            assert_eq!(
+                events[0].as_log()[log_schema().message_key().unwrap().to_string()],
+                "0123456789".into()
+            );
+            assert_eq!(
+                events[1].as_log()[log_schema().message_key().unwrap().to_string()],
+                "short".into()
+            );
+        })
+        .await;
+    }
+
     #[cfg(unix)]
     #[test]
     fn parses_unix_datagram_config() {
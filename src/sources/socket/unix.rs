@@ -10,10 +10,10 @@ use vector_lib::shutdown::ShutdownSignal;
 
 use crate::{
     codecs::Decoder,
-    event::Event,
+    event::{Event, ObjectMap, Value},
     serde::default_decoding,
     sources::{
-        util::{build_unix_datagram_source, build_unix_stream_source},
+        util::{build_unix_datagram_source, build_unix_stream_source, PeerCredentials},
         Source,
     },
     SourceSender,
@@ -53,6 +53,18 @@ pub struct UnixConfig {
     #[serde(default = "default_host_key")]
     pub host_key: OptionalValuePath,
 
+    /// Overrides the name of the log field used to add the credentials of the peer process to
+    /// each event.
+    ///
+    /// The value is an object with the `peer_pid`, `peer_uid` and `peer_gid` fields of the
+    /// process that sent the message. The credentials are only available in the `unix_datagram`
+    /// mode, on Linux.
+    ///
+    /// By default, the credentials aren't added to the events.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "peer_credentials"))]
+    pub peer_credentials_key: OptionalValuePath,
+
     #[configurable(derived)]
     #[serde(default)]
     pub framing: Option<FramingConfig>,
@@ -73,6 +85,7 @@ impl UnixConfig {
             path,
             socket_file_mode: None,
             host_key: default_host_key(),
+            peer_credentials_key: OptionalValuePath::none(),
             framing: None,
             decoding: default_decoding(),
             log_namespace: None,
@@ -86,14 +99,19 @@ impl UnixConfig {
     pub const fn host_key(&self) -> &OptionalValuePath {
         &self.host_key
     }
+
+    pub const fn peer_credentials_key(&self) -> &OptionalValuePath {
+        &self.peer_credentials_key
+    }
 }
 
 /// Function to pass to `build_unix_*_source`, specific to the basic unix source
 /// Takes a single line of a received message and handles an `Event` object.
 fn handle_events(
     events: &mut [Event],
-    host_key: &OptionalValuePath,
+    config: &UnixConfig,
     received_from: Option<Bytes>,
+    credentials: Option<PeerCredentials>,
     log_namespace: LogNamespace,
 ) {
     let now = Utc::now();
@@ -104,7 +122,7 @@ fn handle_events(
         log_namespace.insert_standard_vector_source_metadata(log, SocketConfig::NAME, now);
 
         if let Some(ref host) = received_from {
-            let legacy_host_key = host_key.clone().path;
+            let legacy_host_key = config.host_key.clone().path;
 
             log_namespace.insert_source_metadata(
                 SocketConfig::NAME,
@@ -114,6 +132,24 @@ fn handle_events(
                 host.clone(),
             );
         }
+
+        if let Some(credentials) = credentials {
+            let legacy_peer_credentials_key = config.peer_credentials_key.clone().path;
+
+            log_namespace.insert_source_metadata(
+                SocketConfig::NAME,
+                log,
+                legacy_peer_credentials_key
+                    .as_ref()
+                    .map(LegacyKey::Overwrite),
+                path!("peer_credentials"),
+                ObjectMap::from([
+                    ("peer_pid".into(), Value::Integer(credentials.pid.into())),
+                    ("peer_uid".into(), Value::Integer(credentials.uid.into())),
+                    ("peer_gid".into(), Value::Integer(credentials.gid.into())),
+                ]),
+            );
+        }
     }
 }
 
@@ -126,6 +162,7 @@ pub(super) fn unix_datagram(
 ) -> crate::Result<Source> {
     let max_length = config
         .framing
+        .as_ref()
         .and_then(|framing| match framing {
             FramingConfig::CharacterDelimited(config) => config.character_delimited.max_length,
             FramingConfig::NewlineDelimited(config) => config.newline_delimited.max_length,
@@ -135,12 +172,12 @@ pub(super) fn unix_datagram(
         .unwrap_or_else(crate::serde::default_max_length);
 
     build_unix_datagram_source(
-        config.path,
+        config.path.clone(),
         config.socket_file_mode,
         max_length,
         decoder,
-        move |events, received_from| {
-            handle_events(events, &config.host_key, received_from, log_namespace)
+        move |events, received_from, credentials| {
+            handle_events(events, &config, received_from, credentials, log_namespace)
         },
         shutdown,
         out,
@@ -155,11 +192,11 @@ pub(super) fn unix_stream(
     log_namespace: LogNamespace,
 ) -> crate::Result<Source> {
     build_unix_stream_source(
-        config.path,
+        config.path.clone(),
         config.socket_file_mode,
         decoder,
         move |events, received_from| {
-            handle_events(events, &config.host_key, received_from, log_namespace)
+            handle_events(events, &config, received_from, None, log_namespace)
         },
         shutdown,
         out,
@@ -12,7 +12,7 @@ use flate2::read::MultiGzDecoder;
 use futures::FutureExt;
 use http::StatusCode;
 use hyper::{service::make_service_fn, Server};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{de::Read as JsonRead, Deserializer, Value as JsonValue};
 use snafu::Snafu;
 use tower::ServiceBuilder;
@@ -59,6 +59,7 @@ pub const CHANNEL: &str = "splunk_channel";
 pub const INDEX: &str = "splunk_index";
 pub const SOURCE: &str = "splunk_source";
 pub const SOURCETYPE: &str = "splunk_sourcetype";
+pub const TOKEN_NAME: &str = "splunk_hec_token_name";
 
 /// Configuration for the `splunk_hec` source.
 #[configurable_component(source("splunk_hec", "Receive logs from Splunk."))]
@@ -85,9 +86,12 @@ pub struct SplunkConfig {
     /// If supplied, incoming requests must supply one of these tokens in the `Authorization` header, just as a client
     /// would if it was communicating with the Splunk HEC endpoint directly.
     ///
+    /// The tokens can also be named, with a map of the names to the tokens. The name of the token a request was
+    /// authorized with is then added to its events, in the `splunk_hec_token_name` field.
+    ///
     /// If _not_ supplied, the `Authorization` header is ignored and requests are not authenticated.
     #[configurable(metadata(docs::examples = "A94A8FE5CCB19BA61C4C08"))]
-    valid_tokens: Option<Vec<SensitiveString>>,
+    valid_tokens: Option<ValidTokens>,
 
     /// Whether or not to forward the Splunk HEC authentication token with events.
     ///
@@ -108,6 +112,31 @@ pub struct SplunkConfig {
     log_namespace: Option<bool>,
 }
 
+/// The valid authorization tokens.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(untagged)]
+pub enum ValidTokens {
+    /// A list of tokens.
+    List(Vec<SensitiveString>),
+
+    /// A map of the names of the tokens to the tokens.
+    Named(HashMap<String, SensitiveString>),
+}
+
+impl ValidTokens {
+    /// The tokens, along with their names.
+    fn tokens(&self) -> Vec<(Option<&str>, &SensitiveString)> {
+        match self {
+            Self::List(tokens) => tokens.iter().map(|token| (None, token)).collect(),
+            Self::Named(tokens) => tokens
+                .iter()
+                .map(|(name, token)| (Some(name.as_str()), token))
+                .collect(),
+        }
+    }
+}
+
 impl_generate_config_from_default!(SplunkConfig);
 
 impl Default for SplunkConfig {
@@ -257,6 +286,13 @@ impl SourceConfig for SplunkConfig {
             &owned_value_path!("sourcetype"),
             Kind::bytes(),
             None,
+        )
+        .with_source_metadata(
+            SplunkConfig::NAME,
+            Some(LegacyKey::Overwrite(owned_value_path!(TOKEN_NAME))),
+            &owned_value_path!("token_name"),
+            Kind::bytes(),
+            None,
         );
 
         vec![SourceOutput::new_logs(DataType::Log, schema_definition)]
@@ -274,6 +310,8 @@ impl SourceConfig for SplunkConfig {
 /// Shared data for responding to requests.
 struct SplunkSource {
     valid_credentials: Vec<String>,
+    /// The names of the named tokens, by token.
+    token_names: HashMap<String, Arc<str>>,
     protocol: &'static str,
     idx_ack: Option<Arc<IndexerAcknowledgement>>,
     store_hec_token: bool,
@@ -289,8 +327,16 @@ impl SplunkSource {
         let valid_tokens = config
             .valid_tokens
             .iter()
-            .flatten()
-            .chain(config.token.iter());
+            .flat_map(ValidTokens::tokens)
+            .chain(config.token.iter().map(|token| (None, token)));
+        let mut valid_credentials = Vec::new();
+        let mut token_names = HashMap::new();
+        for (name, token) in valid_tokens {
+            valid_credentials.push(format!("Splunk {}", token.inner()));
+            if let Some(name) = name {
+                token_names.insert(token.inner().to_owned(), Arc::from(name));
+            }
+        }
 
         let idx_ack = acknowledgements.then(|| {
             Arc::new(IndexerAcknowledgement::new(
@@ -300,9 +346,8 @@ impl SplunkSource {
         });
 
         SplunkSource {
-            valid_credentials: valid_tokens
-                .map(|token| format!("Splunk {}", token.inner()))
-                .collect(),
+            valid_credentials,
+            token_names,
             protocol,
             idx_ack,
             store_hec_token: config.store_hec_token,
@@ -323,6 +368,7 @@ impl SplunkSource {
         let protocol = self.protocol;
         let idx_ack = self.idx_ack.clone();
         let store_hec_token = self.store_hec_token;
+        let token_names = self.token_names.clone();
         let log_namespace = self.log_namespace;
         let events_received = self.events_received.clone();
 
@@ -351,6 +397,10 @@ impl SplunkSource {
                     let mut out = out.clone();
                     let idx_ack = idx_ack.clone();
                     let events_received = events_received.clone();
+                    let token_name = token
+                        .as_ref()
+                        .and_then(|token| token_names.get(token))
+                        .cloned();
 
                     async move {
                         if idx_ack.is_some() && channel.is_none() {
@@ -393,6 +443,7 @@ impl SplunkSource {
                             xff,
                             batch,
                             token.filter(|_| store_hec_token).map(Into::into),
+                            token_name,
                             log_namespace,
                         );
                         for result in iter {
@@ -432,6 +483,7 @@ impl SplunkSource {
         let protocol = self.protocol;
         let idx_ack = self.idx_ack.clone();
         let store_hec_token = self.store_hec_token;
+        let token_names = self.token_names.clone();
         let events_received = self.events_received.clone();
         let log_namespace = self.log_namespace;
 
@@ -439,6 +491,7 @@ impl SplunkSource {
             .and(path!("raw" / "1.0").or(path!("raw")))
             .and(self.authorization())
             .and(SplunkSource::required_channel())
+            .and(warp::query::<RawQuery>())
             .and(warp::addr::remote())
             .and(warp::header::optional::<String>("X-Forwarded-For"))
             .and(self.gzip())
@@ -448,6 +501,7 @@ impl SplunkSource {
                 move |_,
                       token: Option<String>,
                       channel_id: String,
+                      query: RawQuery,
                       remote: Option<SocketAddr>,
                       xff: Option<String>,
                       gzip: bool,
@@ -456,6 +510,10 @@ impl SplunkSource {
                     let mut out = out.clone();
                     let idx_ack = idx_ack.clone();
                     let events_received = events_received.clone();
+                    let token_name = token
+                        .as_ref()
+                        .and_then(|token| token_names.get(token))
+                        .cloned();
                     emit!(HttpBytesReceived {
                         byte_size: body.len(),
                         http_path: path.as_str(),
@@ -483,6 +541,10 @@ impl SplunkSource {
                             log_namespace,
                             &events_received,
                         )?;
+                        query.insert(event.as_mut_log(), log_namespace);
+                        if let Some(token_name) = token_name {
+                            insert_token_name(event.as_mut_log(), &token_name, log_namespace);
+                        }
                         if let Some(token) = token.filter(|_| store_hec_token) {
                             event.metadata_mut().set_splunk_hec_token(token.into());
                         }
@@ -631,11 +693,14 @@ struct EventIterator<'de, R: JsonRead<'de>> {
     batch: Option<BatchNotifier>,
     /// Splunk HEC Token for passthrough
     token: Option<Arc<str>>,
+    /// Name of the token the request was authorized with
+    token_name: Option<Arc<str>>,
     /// Lognamespace to put the events in
     log_namespace: LogNamespace,
 }
 
 impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         deserializer: serde_json::StreamDeserializer<'de, R, JsonValue>,
         channel: Option<String>,
@@ -643,6 +708,7 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
         remote_addr: Option<String>,
         batch: Option<BatchNotifier>,
         token: Option<Arc<str>>,
+        token_name: Option<Arc<str>>,
         log_namespace: LogNamespace,
     ) -> Self {
         EventIterator {
@@ -673,6 +739,7 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
             ],
             batch,
             token,
+            token_name,
             log_namespace,
         }
     }
@@ -780,6 +847,11 @@ impl<'de, R: JsonRead<'de>> EventIterator<'de, R> {
             log.metadata_mut().set_splunk_hec_token(Arc::clone(token));
         }
 
+        // Add the name of the token if it's named
+        if let Some(token_name) = &self.token_name {
+            insert_token_name(&mut log, token_name, self.log_namespace);
+        }
+
         if let Some(batch) = self.batch.clone() {
             log = log.with_batch_notifier(&batch);
         }
@@ -964,7 +1036,7 @@ impl DefaultExtractor {
                     SplunkConfig::NAME,
                     log,
                     Some(LegacyKey::Overwrite(metadata_key)),
-                    &self.to_field.path.clone().unwrap_or(owned_value_path!("")),
+                    lookup::path!(self.field),
                     index.clone(),
                 )
             }
@@ -972,6 +1044,46 @@ impl DefaultExtractor {
     }
 }
 
+/// The fields of the events of a raw request, given as query parameters.
+#[derive(Debug, Default, Deserialize)]
+struct RawQuery {
+    index: Option<String>,
+    source: Option<String>,
+    sourcetype: Option<String>,
+}
+
+impl RawQuery {
+    /// Inserts the fields where the event endpoint inserts the fields of the JSON envelope.
+    fn insert(self, log: &mut LogEvent, log_namespace: LogNamespace) {
+        for (field, to_field, value) in [
+            ("index", INDEX, self.index),
+            ("source", SOURCE, self.source),
+            ("sourcetype", SOURCETYPE, self.sourcetype),
+        ] {
+            if let Some(value) = value {
+                log_namespace.insert_source_metadata(
+                    SplunkConfig::NAME,
+                    log,
+                    Some(LegacyKey::Overwrite(&owned_value_path!(to_field))),
+                    lookup::path!(field),
+                    value,
+                );
+            }
+        }
+    }
+}
+
+/// Adds the name of the token the request was authorized with
+fn insert_token_name(log: &mut LogEvent, token_name: &str, log_namespace: LogNamespace) {
+    log_namespace.insert_source_metadata(
+        SplunkConfig::NAME,
+        log,
+        Some(LegacyKey::Overwrite(&owned_value_path!(TOKEN_NAME))),
+        lookup::path!("token_name"),
+        token_name,
+    );
+}
+
 /// For tracking origin of the timestamp
 #[derive(Clone, Debug)]
 enum Time {
@@ -1269,14 +1381,12 @@ mod tests {
 
     async fn source_with(
         token: Option<SensitiveString>,
-        valid_tokens: Option<&[&str]>,
+        valid_tokens: Option<ValidTokens>,
         acknowledgements: Option<HecAcknowledgementsConfig>,
         store_hec_token: bool,
     ) -> (impl Stream<Item = Event> + Unpin, SocketAddr) {
         let (sender, recv) = SourceSender::new_test_finalize(EventStatus::Delivered);
         let address = next_addr();
-        let valid_tokens =
-            valid_tokens.map(|tokens| tokens.iter().map(|v| v.to_string().into()).collect());
         let cx = SourceContext::new_test(sender, None);
         tokio::spawn(async move {
             SplunkConfig {
@@ -1298,6 +1408,17 @@ mod tests {
         (recv, address)
     }
 
+    fn valid_tokens() -> ValidTokens {
+        ValidTokens::List(VALID_TOKENS.iter().map(|v| v.to_string().into()).collect())
+    }
+
+    fn named_tokens() -> ValidTokens {
+        ValidTokens::Named(HashMap::from([
+            ("team_a".to_owned(), TOKEN.to_owned().into()),
+            ("team_b".to_owned(), VALID_TOKENS[1].to_owned().into()),
+        ]))
+    }
+
     async fn sink(
         address: SocketAddr,
         encoding: EncodingConfig,
@@ -1853,7 +1974,7 @@ mod tests {
     async fn secondary_token() {
         assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
             let message = r#"{"event":"first", "color": "blue"}"#;
-            let (_source, address) = source_with(None, Some(VALID_TOKENS), None, false).await;
+            let (_source, address) = source_with(None, Some(valid_tokens()), None, false).await;
             let options = SendWithOpts {
                 channel: None,
                 forwarded_for: None,
@@ -1874,11 +1995,127 @@ mod tests {
         .await;
     }
 
+    #[test]
+    fn parse_valid_tokens() {
+        let config: SplunkConfig = toml::from_str(r#"valid_tokens = ["a", "b"]"#).unwrap();
+        let mut tokens: Vec<_> = config
+            .valid_tokens
+            .as_ref()
+            .unwrap()
+            .tokens()
+            .into_iter()
+            .map(|(name, token)| (name, token.inner()))
+            .collect();
+        tokens.sort_unstable();
+        assert_eq!(tokens, [(None, "a"), (None, "b")]);
+
+        let config: SplunkConfig =
+            toml::from_str(r#"valid_tokens = { team_a = "a", team_b = "b" }"#).unwrap();
+        let mut tokens: Vec<_> = config
+            .valid_tokens
+            .as_ref()
+            .unwrap()
+            .tokens()
+            .into_iter()
+            .map(|(name, token)| (name, token.inner()))
+            .collect();
+        tokens.sort_unstable();
+        assert_eq!(tokens, [(Some("team_a"), "a"), (Some("team_b"), "b")]);
+    }
+
+    #[tokio::test]
+    async fn event_service_token_name() {
+        assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let message = r#"{"event":"first","index":"team_b_index","sourcetype":"_json"}"#;
+            let (source, address) = source_with(None, Some(named_tokens()), None, false).await;
+            let options = SendWithOpts::default();
+
+            assert_eq!(
+                200,
+                send_with(
+                    address,
+                    "services/collector/event",
+                    message,
+                    VALID_TOKENS[1],
+                    &options
+                )
+                .await
+            );
+
+            let event = collect_n(source, 1).await.remove(0);
+            assert_eq!(event.as_log()[&super::TOKEN_NAME], "team_b".into());
+            assert_eq!(event.as_log()[&super::INDEX], "team_b_index".into());
+            assert_eq!(event.as_log()[&super::SOURCETYPE], "_json".into());
+            assert!(event.metadata().splunk_hec_token().is_none());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn raw_service_token_name_and_query_fields() {
+        assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let message = "raw";
+            let (source, address) = source_with(None, Some(named_tokens()), None, false).await;
+
+            assert_eq!(
+                200,
+                post(
+                    address,
+                    "services/collector/raw?index=team_a_index&source=app&sourcetype=_raw",
+                    message
+                )
+                .await
+            );
+
+            let event = collect_n(source, 1).await.remove(0);
+            assert_eq!(event.as_log()[&super::TOKEN_NAME], "team_a".into());
+            assert_eq!(event.as_log()[&super::INDEX], "team_a_index".into());
+            assert_eq!(event.as_log()[&super::SOURCE], "app".into());
+            assert_eq!(event.as_log()[&super::SOURCETYPE], "_raw".into());
+            assert_eq!(event.as_log()[&super::CHANNEL], "channel".into());
+        })
+        .await;
+    }
+
+    #[test]
+    fn event_iterator_vector_namespace_metadata() {
+        let body = r#"{"event":"first","index":"main","source":"app"}{"event":"second"}"#;
+        let events: Vec<_> = EventIterator::new(
+            Deserializer::from_str(body).into_iter::<JsonValue>(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Arc::from("team_a")),
+            LogNamespace::Vector,
+        )
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        assert_eq!(events.len(), 2);
+        for event in events {
+            let metadata = event.as_log().metadata().value();
+            assert_eq!(
+                metadata.get(lookup::path!("splunk_hec", "index")),
+                Some(&Value::from("main"))
+            );
+            assert_eq!(
+                metadata.get(lookup::path!("splunk_hec", "source")),
+                Some(&Value::from("app"))
+            );
+            assert_eq!(
+                metadata.get(lookup::path!("splunk_hec", "token_name")),
+                Some(&Value::from("team_a"))
+            );
+        }
+    }
+
     #[tokio::test]
     async fn event_service_token_passthrough_enabled() {
         assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
             let message = "passthrough_token_enabled";
-            let (source, address) = source_with(None, Some(VALID_TOKENS), None, true).await;
+            let (source, address) = source_with(None, Some(valid_tokens()), None, true).await;
             let (sink, health) = sink(
                 address,
                 TextSerializerConfig::default().into(),
@@ -1905,7 +2142,7 @@ mod tests {
     async fn raw_service_token_passthrough_enabled() {
         assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
             let message = "raw";
-            let (source, address) = source_with(None, Some(VALID_TOKENS), None, true).await;
+            let (source, address) = source_with(None, Some(valid_tokens()), None, true).await;
 
             assert_eq!(200, post(address, "services/collector/raw", message).await);
 
@@ -2535,6 +2772,11 @@ mod tests {
             &owned_value_path!("splunk_hec", "sourcetype"),
             Kind::bytes(),
             None,
+        )
+        .with_metadata_field(
+            &owned_value_path!("splunk_hec", "token_name"),
+            Kind::bytes(),
+            None,
         );
 
         assert_eq!(definition, Some(expected_definition));
@@ -2574,6 +2816,11 @@ mod tests {
             Some("service"),
         )
         .with_event_field(&owned_value_path!("splunk_sourcetype"), Kind::bytes(), None)
+        .with_event_field(
+            &owned_value_path!("splunk_hec_token_name"),
+            Kind::bytes(),
+            None,
+        )
         .with_event_field(&owned_value_path!("timestamp"), Kind::timestamp(), None);
 
         assert_eq!(definitions, Some(expected_definition));
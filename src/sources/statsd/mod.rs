@@ -17,7 +17,7 @@ use vector_lib::configurable::configurable_component;
 use vector_lib::internal_event::{CountByteSize, InternalEventHandle as _, Registered};
 use vector_lib::EstimatedJsonEncodedSizeOf;
 
-use self::parser::ParseError;
+use self::parser::{ParseError, Parser, DEFAULT_CONTAINER_ID_TAG};
 use super::util::net::{try_bind_udp_socket, SocketListenAddr, TcpNullAcker, TcpSource};
 use crate::{
     codecs::Decoder,
@@ -38,7 +38,6 @@ pub mod parser;
 #[cfg(unix)]
 mod unix;
 
-use parser::parse;
 #[cfg(unix)]
 use unix::{statsd_unix, UnixConfig};
 use vector_lib::config::LogNamespace;
@@ -70,6 +69,13 @@ pub struct UdpConfig {
 
     /// The size of the receive buffer used for each connection.
     receive_buffer_bytes: Option<usize>,
+
+    /// The name of the tag to add the container ID of the DogStatsD metrics under.
+    ///
+    /// The container ID is sent by the DogStatsD clients in the `|c:<container-id>` section.
+    #[serde(default = "default_container_id_tag")]
+    #[configurable(metadata(docs::examples = "container_id"))]
+    container_id_tag: String,
 }
 
 impl UdpConfig {
@@ -77,6 +83,7 @@ impl UdpConfig {
         Self {
             address,
             receive_buffer_bytes: None,
+            container_id_tag: default_container_id_tag(),
         }
     }
 }
@@ -109,6 +116,13 @@ pub struct TcpConfig {
     /// The maximum number of TCP connections that are allowed at any given time.
     #[configurable(metadata(docs::type_unit = "connections"))]
     connection_limit: Option<u32>,
+
+    /// The name of the tag to add the container ID of the DogStatsD metrics under.
+    ///
+    /// The container ID is sent by the DogStatsD clients in the `|c:<container-id>` section.
+    #[serde(default = "default_container_id_tag")]
+    #[configurable(metadata(docs::examples = "container_id"))]
+    container_id_tag: String,
 }
 
 impl TcpConfig {
@@ -121,6 +135,7 @@ impl TcpConfig {
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
             connection_limit: None,
+            container_id_tag: default_container_id_tag(),
         }
     }
 }
@@ -129,6 +144,10 @@ const fn default_shutdown_timeout_secs() -> Duration {
     Duration::from_secs(30)
 }
 
+fn default_container_id_tag() -> String {
+    DEFAULT_CONTAINER_ID_TAG.to_owned()
+}
+
 impl GenerateConfig for StatsdConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self::Udp(UdpConfig::from_address(
@@ -157,7 +176,10 @@ impl SourceConfig for StatsdConfig {
                     .and_then(|tls| tls.client_metadata_key.clone())
                     .and_then(|k| k.path);
                 let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
-                StatsdTcpSource.run(
+                StatsdTcpSource {
+                    parser: Parser::new(config.container_id_tag.clone()),
+                }
+                .run(
                     config.address,
                     config.keepalive,
                     config.shutdown_timeout_secs,
@@ -199,29 +221,33 @@ impl SourceConfig for StatsdConfig {
 pub(crate) struct StatsdDeserializer {
     socket_mode: Option<SocketMode>,
     events_received: Option<Registered<EventsReceived>>,
+    parser: Parser,
 }
 
 impl StatsdDeserializer {
-    pub fn udp() -> Self {
+    pub fn udp(parser: Parser) -> Self {
         Self {
             socket_mode: Some(SocketMode::Udp),
             // The other modes emit a different `EventsReceived`.
             events_received: Some(register!(EventsReceived)),
+            parser,
         }
     }
 
-    pub const fn tcp() -> Self {
+    pub const fn tcp(parser: Parser) -> Self {
         Self {
             socket_mode: None,
             events_received: None,
+            parser,
         }
     }
 
     #[cfg(unix)]
-    pub const fn unix() -> Self {
+    pub const fn unix(parser: Parser) -> Self {
         Self {
             socket_mode: Some(SocketMode::Unix),
             events_received: None,
+            parser,
         }
     }
 }
@@ -244,7 +270,7 @@ impl decoding::format::Deserializer for StatsdDeserializer {
 
         match std::str::from_utf8(&bytes)
             .map_err(ParseError::InvalidUtf8)
-            .and_then(parse)
+            .and_then(|packet| self.parser.parse(packet))
         {
             Ok(metric) => {
                 let event = Event::Metric(metric);
@@ -288,7 +314,9 @@ async fn statsd_udp(
 
     let codec = Decoder::new(
         Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-        Deserializer::Boxed(Box::new(StatsdDeserializer::udp())),
+        Deserializer::Boxed(Box::new(StatsdDeserializer::udp(Parser::new(
+            config.container_id_tag.clone(),
+        )))),
     );
     let mut stream = UdpFramed::new(socket, codec).take_until(shutdown);
     while let Some(frame) = stream.next().await {
@@ -312,7 +340,9 @@ async fn statsd_udp(
 }
 
 #[derive(Clone)]
-struct StatsdTcpSource;
+struct StatsdTcpSource {
+    parser: Parser,
+}
 
 impl TcpSource for StatsdTcpSource {
     type Error = vector_lib::codecs::decoding::Error;
@@ -323,7 +353,7 @@ impl TcpSource for StatsdTcpSource {
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
             Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-            Deserializer::Boxed(Box::new(StatsdDeserializer::tcp())),
+            Deserializer::Boxed(Box::new(StatsdDeserializer::tcp(self.parser.clone()))),
         )
     }
 
@@ -431,6 +461,7 @@ mod test {
             let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
             let config = StatsdConfig::Unix(UnixConfig {
                 path: in_path.clone(),
+                container_id_tag: default_container_id_tag(),
             });
             let (sender, mut receiver) = mpsc::channel(200);
             tokio::spawn(async move {
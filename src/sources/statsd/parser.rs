@@ -4,6 +4,7 @@ use std::{
     str::Utf8Error,
 };
 
+use chrono::{DateTime, TimeZone, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -15,7 +16,67 @@ use crate::{
 static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
 static NONALPHANUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z_\-0-9\.]").unwrap());
 
+/// The name of the tag the container ID of the DogStatsD metrics is added under, by default.
+pub const DEFAULT_CONTAINER_ID_TAG: &str = "container_id";
+
+/// Parses the StatsD lines, including the DogStatsD extensions.
+#[derive(Clone, Debug)]
+pub struct Parser {
+    container_id_tag: String,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONTAINER_ID_TAG.to_owned())
+    }
+}
+
+impl Parser {
+    /// Creates a parser adding the container ID of the metrics under the `container_id_tag` tag.
+    pub const fn new(container_id_tag: String) -> Self {
+        Self { container_id_tag }
+    }
+
+    pub fn parse(&self, packet: &str) -> Result<Metric, ParseError> {
+        parse_packet(packet, &self.container_id_tag)
+    }
+}
+
+/// Parses the line with the default [`Parser`].
 pub fn parse(packet: &str) -> Result<Metric, ParseError> {
+    Parser::default().parse(packet)
+}
+
+/// The optional sections following the metric type.
+#[derive(Default)]
+struct Extensions<'a> {
+    sampling: Option<&'a str>,
+    tags: Option<&'a str>,
+    container_id: Option<&'a str>,
+    timestamp: Option<&'a str>,
+}
+
+impl<'a> Extensions<'a> {
+    /// Picks the known sections out of `parts`, in any order. The unknown ones, like the
+    /// `e:<external data>` section of the newer DogStatsD clients, are ignored.
+    fn new(parts: &[&'a str]) -> Self {
+        let mut extensions = Self::default();
+        for &part in parts {
+            if part.starts_with('@') {
+                extensions.sampling.get_or_insert(part);
+            } else if part.starts_with('#') {
+                extensions.tags.get_or_insert(part);
+            } else if let Some(container_id) = part.strip_prefix("c:") {
+                extensions.container_id.get_or_insert(container_id);
+            } else if let Some(timestamp) = part.strip_prefix('T') {
+                extensions.timestamp.get_or_insert(timestamp);
+            }
+        }
+        extensions
+    }
+}
+
+fn parse_packet(packet: &str, container_id_tag: &str) -> Result<Metric, ParseError> {
     // https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/#datagram-format
     let key_and_body = packet.splitn(2, ':').collect::<Vec<_>>();
     if key_and_body.len() != 2 {
@@ -35,22 +96,16 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     let name = sanitize_key(key);
     let metric_type = parts[1];
 
-    // sampling part is optional and comes after metric type part
-    let sampling = parts.get(2).filter(|s| s.starts_with('@'));
-    let sample_rate = if let Some(s) = sampling {
+    // The sampling, the tags, the container ID and the timestamp are optional, and come in any
+    // order after the metric type.
+    let extensions = Extensions::new(&parts[2..]);
+    let sample_rate = if let Some(s) = extensions.sampling {
         1.0 / sanitize_sampling(parse_sampling(s)?)
     } else {
         1.0
     };
-
-    // tags are optional and could be found either after sampling of after metric type part
-    let tags = if sampling.is_none() {
-        parts.get(2)
-    } else {
-        parts.get(3)
-    };
-    let tags = tags.filter(|s| s.starts_with('#'));
-    let tags = tags.map(parse_tags).transpose()?;
+    let tags = extensions.tags.map(parse_tags).transpose()?;
+    let timestamp = extensions.timestamp.map(parse_timestamp).transpose()?;
 
     let metric = match metric_type {
         "c" => {
@@ -108,6 +163,11 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
         .with_tags(tags),
         other => return Err(ParseError::UnknownMetricType(other.into())),
     };
+
+    let mut metric = metric.with_timestamp(timestamp);
+    if let Some(container_id) = extensions.container_id.filter(|id| !id.is_empty()) {
+        metric.replace_tag(container_id_tag.to_owned(), container_id.to_owned());
+    }
     Ok(metric)
 }
 
@@ -127,7 +187,7 @@ fn parse_sampling(input: &str) -> Result<f64, ParseError> {
 }
 
 /// Statsd (and dogstatsd) support bare, single and multi-value tags.
-fn parse_tags(input: &&str) -> Result<MetricTags, ParseError> {
+fn parse_tags(input: &str) -> Result<MetricTags, ParseError> {
     if !input.starts_with('#') || input.len() < 2 {
         return Err(ParseError::Malformed(
             "expected non empty '#'-prefixed tags component",
@@ -140,6 +200,14 @@ fn parse_tags(input: &&str) -> Result<MetricTags, ParseError> {
         .collect())
 }
 
+/// DogStatsD timestamps are in seconds since the Unix epoch.
+fn parse_timestamp(input: &str) -> Result<DateTime<Utc>, ParseError> {
+    let seconds: i64 = input.parse()?;
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .ok_or(ParseError::Malformed("timestamp out of range"))
+}
+
 fn parse_direction(input: &str) -> Result<Option<f64>, ParseError> {
     match input
         .chars()
@@ -215,10 +283,11 @@ impl From<ParseFloatError> for ParseError {
 
 #[cfg(test)]
 mod test {
+    use chrono::{TimeZone, Utc};
     use vector_lib::assert_event_data_eq;
     use vector_lib::{event::metric::TagValue, metric_tags};
 
-    use super::{parse, sanitize_key, sanitize_sampling};
+    use super::{parse, sanitize_key, sanitize_sampling, ParseError, Parser};
     use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
 
     #[test]
@@ -394,6 +463,109 @@ mod test {
         );
     }
 
+    #[test]
+    fn container_id() {
+        assert_event_data_eq!(
+            parse("foo:1|c|c:abc123"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(metric_tags!("container_id" => "abc123")))),
+        );
+    }
+
+    #[test]
+    fn container_id_custom_tag() {
+        let parser = Parser::new("container".to_owned());
+        assert_event_data_eq!(
+            parser.parse("foo:1|c|#tag1:value|c:abc123"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(metric_tags!(
+                "tag1" => "value",
+                "container" => "abc123",
+            )))),
+        );
+    }
+
+    #[test]
+    fn empty_container_id() {
+        assert_event_data_eq!(
+            parse("foo:1|c|c:"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )),
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_event_data_eq!(
+            parse("foo:1|g|T1656581400"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 1.0 },
+            )
+            .with_timestamp(Some(Utc.timestamp_opt(1656581400, 0).unwrap()))),
+        );
+    }
+
+    #[test]
+    fn invalid_timestamp() {
+        assert!(matches!(
+            parse("foo:1|g|Tnow"),
+            Err(ParseError::InvalidInteger(_))
+        ));
+        assert!(matches!(
+            parse(&format!("foo:1|g|T{}", i64::MAX)),
+            Err(ParseError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_extension() {
+        assert_event_data_eq!(
+            parse("foo:1|c|e:it-false,cn-nginx,pu-1234"),
+            Ok(Metric::new(
+                "foo",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )),
+        );
+    }
+
+    #[test]
+    fn combined_extensions() {
+        let expected = Metric::new(
+            "foo",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 2.0 },
+        )
+        .with_tags(Some(metric_tags!(
+            "a" => "b",
+            "container_id" => "abc",
+        )))
+        .with_timestamp(Some(Utc.timestamp_opt(1656581400, 0).unwrap()));
+
+        assert_event_data_eq!(
+            parse("foo:1|c|@0.5|#a:b|c:abc|T1656581400|e:xyz"),
+            Ok(expected.clone()),
+        );
+        // The sections are accepted in any order after the metric type.
+        assert_event_data_eq!(
+            parse("foo:1|c|e:xyz|T1656581400|c:abc|#a:b|@0.5"),
+            Ok(expected),
+        );
+    }
+
     #[test]
     fn sanitizing_keys() {
         assert_eq!("foo-bar-baz", sanitize_key("foo/bar/baz"));
@@ -6,7 +6,7 @@ use vector_lib::codecs::{
 };
 use vector_lib::configurable::configurable_component;
 
-use super::StatsdDeserializer;
+use super::{default_container_id_tag, parser::Parser, StatsdDeserializer};
 use crate::{
     codecs::Decoder,
     shutdown::ShutdownSignal,
@@ -23,6 +23,13 @@ pub struct UnixConfig {
     /// This should be an absolute path.
     #[configurable(metadata(docs::examples = "/path/to/socket"))]
     pub path: PathBuf,
+
+    /// The name of the tag to add the container ID of the DogStatsD metrics under.
+    ///
+    /// The container ID is sent by the DogStatsD clients in the `|c:<container-id>` section.
+    #[serde(default = "default_container_id_tag")]
+    #[configurable(metadata(docs::examples = "container_id"))]
+    pub container_id_tag: String,
 }
 
 pub fn statsd_unix(
@@ -32,7 +39,9 @@ pub fn statsd_unix(
 ) -> crate::Result<Source> {
     let decoder = Decoder::new(
         Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
-        Deserializer::Boxed(Box::new(StatsdDeserializer::unix())),
+        Deserializer::Boxed(Box::new(StatsdDeserializer::unix(Parser::new(
+            config.container_id_tag,
+        )))),
     );
 
     build_unix_stream_source(
@@ -1,20 +1,24 @@
 #[cfg(unix)]
 use std::path::PathBuf;
-use std::{net::SocketAddr, time::Duration};
+use std::{io, net::SocketAddr, time::Duration};
 
-use bytes::Bytes;
+use bytes::{Buf, Bytes, BytesMut};
 use chrono::Utc;
 use futures::StreamExt;
 use listenfd::ListenFd;
 use smallvec::SmallVec;
+use tokio_util::codec::{Decoder as _, LinesCodecError};
 use tokio_util::udp::UdpFramed;
+#[cfg(unix)]
+use vector_lib::codecs::OctetCountingDecoder;
 use vector_lib::codecs::{
-    decoding::{Deserializer, Framer},
-    BytesDecoder, OctetCountingDecoder, SyslogDeserializerConfig,
+    decoding::{BoxedFramingError, Deserializer, Framer},
+    BytesDecoder, NewlineDelimitedDecoder, SyslogDeserializerConfig,
 };
 use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::{lookup_v2::OptionalValuePath, path, OwnedValuePath};
+use vector_lib::TimeZone;
 use vrl::event_path;
 
 #[cfg(unix)]
@@ -59,6 +63,18 @@ pub struct SyslogConfig {
     /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
     host_key: Option<OptionalValuePath>,
 
+    /// The name of the timezone to apply to the timestamps that do not contain an explicit time
+    /// zone, like the RFC 3164 ones.
+    ///
+    /// This overrides the [global `timezone`][global_timezone] option. The time zone name may be
+    /// any name in the [TZ database][tz_database], or `local` to indicate system local time.
+    ///
+    /// [global_timezone]: https://vector.dev/docs/reference/configuration//global-options#timezone
+    /// [tz_database]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
+    #[serde(default)]
+    #[configurable(metadata(docs::advanced))]
+    pub timezone: Option<TimeZone>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -90,6 +106,10 @@ pub enum Mode {
 
         /// The maximum number of TCP connections that are allowed at any given time.
         connection_limit: Option<u32>,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        framing: TcpFraming,
     },
 
     /// Listen on UDP.
@@ -121,6 +141,27 @@ pub enum Mode {
     },
 }
 
+/// Framing of the messages received over TCP, as described in [RFC 6587][rfc6587].
+///
+/// [rfc6587]: https://datatracker.ietf.org/doc/html/rfc6587#section-3.4
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TcpFraming {
+    /// Detect the framing of each connection from its first bytes.
+    ///
+    /// A run of digits followed by a space selects octet counting for the connection, anything
+    /// else selects non-transparent framing.
+    #[default]
+    Auto,
+
+    /// Octet counting, each message is prefixed with its length in bytes and a space.
+    OctetCounting,
+
+    /// Non-transparent framing, each message is terminated by a newline.
+    NonTransparent,
+}
+
 impl SyslogConfig {
     #[cfg(test)]
     pub fn from_mode(mode: Mode) -> Self {
@@ -128,6 +169,7 @@ impl SyslogConfig {
             mode,
             host_key: None,
             max_length: crate::serde::default_max_length(),
+            timezone: None,
             log_namespace: None,
         }
     }
@@ -142,9 +184,11 @@ impl Default for SyslogConfig {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: TcpFraming::Auto,
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
+            timezone: None,
             log_namespace: None,
         }
     }
@@ -166,6 +210,7 @@ impl SourceConfig for SyslogConfig {
             .clone()
             .and_then(|k| k.path)
             .or(log_schema().host_key().cloned());
+        let timezone = self.timezone.unwrap_or_else(|| cx.globals.timezone());
 
         match self.mode.clone() {
             Mode::Tcp {
@@ -174,9 +219,12 @@ impl SourceConfig for SyslogConfig {
                 tls,
                 receive_buffer_bytes,
                 connection_limit,
+                framing,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
+                    framing,
+                    timezone,
                     host_key,
                     log_namespace,
                 };
@@ -207,9 +255,12 @@ impl SourceConfig for SyslogConfig {
                 receive_buffer_bytes,
             } => Ok(udp(
                 address,
-                self.max_length,
                 host_key,
                 receive_buffer_bytes,
+                Decoder::new(
+                    Framer::Bytes(BytesDecoder::new()),
+                    syslog_deserializer(timezone),
+                ),
                 cx.shutdown,
                 log_namespace,
                 cx.out,
@@ -223,9 +274,7 @@ impl SourceConfig for SyslogConfig {
                     Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(
                         self.max_length,
                     )),
-                    Deserializer::Syslog(
-                        SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build(),
-                    ),
+                    syslog_deserializer(timezone),
                 );
 
                 build_unix_stream_source(
@@ -266,6 +315,8 @@ impl SourceConfig for SyslogConfig {
 #[derive(Debug, Clone)]
 struct SyslogTcpSource {
     max_length: usize,
+    framing: TcpFraming,
+    timezone: TimeZone,
     host_key: Option<OwnedValuePath>,
     log_namespace: LogNamespace,
 }
@@ -278,8 +329,11 @@ impl TcpSource for SyslogTcpSource {
 
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
-            Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(self.max_length)),
-            Deserializer::Syslog(SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build()),
+            Framer::Boxed(Box::new(SyslogTcpFramer::new(
+                self.framing,
+                self.max_length,
+            ))),
+            syslog_deserializer(self.timezone),
         )
     }
 
@@ -297,11 +351,176 @@ impl TcpSource for SyslogTcpSource {
     }
 }
 
+fn syslog_deserializer(timezone: TimeZone) -> Deserializer {
+    Deserializer::Syslog(
+        SyslogDeserializerConfig::from_source(SyslogConfig::NAME)
+            .with_timezone(timezone)
+            .build(),
+    )
+}
+
+/// The maximum number of digits of an octet count, which is plenty for any sensible
+/// `max_length`.
+const MAX_OCTET_COUNT_DIGITS: usize = 10;
+
+/// The framing of a single TCP connection.
+#[derive(Clone, Debug)]
+enum ConnectionFraming {
+    /// Octet counting, with the number of bytes of an oversized message still to be skipped.
+    OctetCounting {
+        discarding: usize,
+    },
+    NonTransparent(NewlineDelimitedDecoder),
+}
+
+/// Frames the messages of a single TCP connection.
+///
+/// With the `auto` framing, the framing is picked from the first bytes of the connection and kept
+/// for all of its messages.
+#[derive(Clone, Debug)]
+struct SyslogTcpFramer {
+    max_length: usize,
+    framing: Option<ConnectionFraming>,
+}
+
+impl SyslogTcpFramer {
+    fn new(framing: TcpFraming, max_length: usize) -> Self {
+        let framing = match framing {
+            TcpFraming::Auto => None,
+            TcpFraming::OctetCounting => Some(ConnectionFraming::OctetCounting { discarding: 0 }),
+            TcpFraming::NonTransparent => Some(ConnectionFraming::NonTransparent(
+                NewlineDelimitedDecoder::new_with_max_length(max_length),
+            )),
+        };
+        Self {
+            max_length,
+            framing,
+        }
+    }
+
+    /// Picks the framing from the first bytes of the connection, or returns `None` if more bytes
+    /// are needed to tell.
+    fn detect(&self, src: &[u8]) -> Option<ConnectionFraming> {
+        let digits = src.iter().take_while(|b| b.is_ascii_digit()).count();
+        let octet_counting = match src.get(digits) {
+            Some(&b) => digits > 0 && b == b' ',
+            None if digits <= MAX_OCTET_COUNT_DIGITS => return None,
+            None => false,
+        };
+        Some(if octet_counting {
+            ConnectionFraming::OctetCounting { discarding: 0 }
+        } else {
+            ConnectionFraming::NonTransparent(NewlineDelimitedDecoder::new_with_max_length(
+                self.max_length,
+            ))
+        })
+    }
+}
+
+/// Decodes an octet counted message, `<length> <message>`.
+///
+/// A malformed length is an error that closes the connection, as the start of the next message
+/// can't be found anymore.
+fn decode_octet_counted(
+    src: &mut BytesMut,
+    discarding: &mut usize,
+    max_length: usize,
+) -> Result<Option<Bytes>, BoxedFramingError> {
+    if *discarding > 0 {
+        let skipped = (*discarding).min(src.len());
+        src.advance(skipped);
+        *discarding -= skipped;
+        if *discarding > 0 {
+            return Ok(None);
+        }
+    }
+
+    let Some(space) = src
+        .iter()
+        .take(MAX_OCTET_COUNT_DIGITS + 1)
+        .position(|&b| b == b' ')
+    else {
+        if src.len() > MAX_OCTET_COUNT_DIGITS || !src.iter().all(u8::is_ascii_digit) {
+            return Err(invalid_octet_count());
+        }
+        return Ok(None);
+    };
+
+    let digits = &src[..space];
+    let length = match digits.first() {
+        Some(b'1'..=b'9') if digits.iter().all(u8::is_ascii_digit) => std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| digits.parse::<usize>().ok())
+            .ok_or_else(invalid_octet_count)?,
+        _ => return Err(invalid_octet_count()),
+    };
+
+    if length > max_length {
+        // The message is skipped, the connection can go on with the next one.
+        src.advance(space + 1);
+        *discarding = length;
+        return Err(LinesCodecError::MaxLineLengthExceeded.into());
+    }
+
+    if src.len() < space + 1 + length {
+        // The rest of the message is yet to be received.
+        return Ok(None);
+    }
+
+    src.advance(space + 1);
+    Ok(Some(src.split_to(length).freeze()))
+}
+
+fn invalid_octet_count() -> BoxedFramingError {
+    io::Error::new(io::ErrorKind::InvalidData, "Invalid octet count.").into()
+}
+
+impl tokio_util::codec::Decoder for SyslogTcpFramer {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.framing.is_none() {
+            self.framing = self.detect(src);
+        }
+
+        match &mut self.framing {
+            None => Ok(None),
+            Some(ConnectionFraming::OctetCounting { discarding }) => {
+                decode_octet_counted(src, discarding, self.max_length)
+            }
+            Some(ConnectionFraming::NonTransparent(decoder)) => decoder.decode(src),
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.framing.is_none() && !src.is_empty() {
+            // The connection is closing before its framing is known, it can't be octet counting.
+            self.framing = Some(ConnectionFraming::NonTransparent(
+                NewlineDelimitedDecoder::new_with_max_length(self.max_length),
+            ));
+        }
+
+        if let Some(ConnectionFraming::NonTransparent(decoder)) = &mut self.framing {
+            return decoder.decode_eof(src);
+        }
+
+        match self.decode(src)? {
+            None if !src.is_empty() => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Connection closed in the middle of a message.",
+            )
+            .into()),
+            frame => Ok(frame),
+        }
+    }
+}
+
 pub fn udp(
     addr: SocketListenAddr,
-    _max_length: usize,
     host_key: Option<OwnedValuePath>,
     receive_buffer_bytes: Option<usize>,
+    decoder: Decoder,
     shutdown: ShutdownSignal,
     log_namespace: LogNamespace,
     mut out: SourceSender,
@@ -327,36 +546,33 @@ pub fn udp(
             r#type = "udp"
         );
 
-        let mut stream = UdpFramed::new(
-            socket,
-            Decoder::new(
-                Framer::Bytes(BytesDecoder::new()),
-                Deserializer::Syslog(
-                    SyslogDeserializerConfig::from_source(SyslogConfig::NAME).build(),
-                ),
-            ),
-        )
-        .take_until(shutdown)
-        .filter_map(|frame| {
-            let host_key = host_key.clone();
-            async move {
-                match frame {
-                    Ok(((mut events, _byte_size), received_from)) => {
-                        let received_from = received_from.ip().to_string().into();
-                        handle_events(&mut events, &host_key, Some(received_from), log_namespace);
-                        Some(events.remove(0))
-                    }
-                    Err(error) => {
-                        emit!(SocketReceiveError {
-                            mode: SocketMode::Udp,
-                            error: &error,
-                        });
-                        None
+        let mut stream = UdpFramed::new(socket, decoder)
+            .take_until(shutdown)
+            .filter_map(|frame| {
+                let host_key = host_key.clone();
+                async move {
+                    match frame {
+                        Ok(((mut events, _byte_size), received_from)) => {
+                            let received_from = received_from.ip().to_string().into();
+                            handle_events(
+                                &mut events,
+                                &host_key,
+                                Some(received_from),
+                                log_namespace,
+                            );
+                            Some(events.remove(0))
+                        }
+                        Err(error) => {
+                            emit!(SocketReceiveError {
+                                mode: SocketMode::Udp,
+                                error: &error,
+                            });
+                            None
+                        }
                     }
                 }
-            }
-        })
-        .boxed();
+            })
+            .boxed();
 
         match out.send_event_stream(&mut stream).await {
             Ok(()) => {
@@ -442,9 +658,9 @@ mod test {
     use rand::{thread_rng, Rng};
     use serde::Deserialize;
     use tokio::time::{sleep, Duration, Instant};
-    use tokio_util::codec::BytesCodec;
+    use tokio_util::codec::{BytesCodec, Decoder as _};
     use vector_lib::assert_event_data_eq;
-    use vector_lib::codecs::decoding::format::Deserializer;
+    use vector_lib::codecs::{decoding::format::Deserializer, StreamDecodingError};
     use vector_lib::lookup::PathPrefix;
     use vector_lib::{config::ComponentKey, schema::Definition};
     use vrl::value::{kind::Collection, Kind, ObjectMap, Value};
@@ -649,6 +865,22 @@ mod test {
         assert!(matches!(config.mode, Mode::Tcp { .. }));
     }
 
+    #[test]
+    fn config_timezone() {
+        let config: SyslogConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:1235"
+            timezone = "America/Chicago"
+          "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.timezone,
+            Some(TimeZone::Named(chrono_tz::America::Chicago))
+        );
+    }
+
     #[test]
     fn config_tcp_with_receive_buffer_size() {
         let config: SyslogConfig = toml::from_str(
@@ -1116,6 +1348,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: TcpFraming::Auto,
             });
 
             let key = ComponentKey::from("in");
@@ -1259,6 +1492,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                framing: TcpFraming::Auto,
             });
 
             let key = ComponentKey::from("in");
@@ -1323,6 +1557,189 @@ mod test {
         .await;
     }
 
+    fn decode_all(framer: &mut SyslogTcpFramer, chunks: &[&[u8]]) -> Vec<Bytes> {
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        for chunk in chunks {
+            src.extend_from_slice(chunk);
+            while let Some(frame) = framer.decode(&mut src).unwrap() {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    #[test]
+    fn tcp_framing_auto_octet_counting_partial_frames() {
+        let mut framer = SyslogTcpFramer::new(TcpFraming::Auto, 1024);
+
+        // The length prefix and the messages are split across segments.
+        let frames = decode_all(
+            &mut framer,
+            &[
+                b"1",
+                b"1 <13>fo",
+                b"o bar",
+                b"9 <13>a\nb\nc",
+                b"9 <13>baz\n",
+            ],
+        );
+        assert_eq!(frames, ["<13>foo bar", "<13>a\nb\nc", "<13>baz\n"]);
+        assert!(matches!(
+            framer.framing,
+            Some(ConnectionFraming::OctetCounting { .. })
+        ));
+
+        // The framing is picked once per connection, not per message.
+        let mut src = BytesMut::from(&b"<13>qux\n"[..]);
+        let error = framer.decode(&mut src).unwrap_err();
+        assert!(!error.can_continue());
+    }
+
+    #[test]
+    fn tcp_framing_auto_non_transparent() {
+        let mut framer = SyslogTcpFramer::new(TcpFraming::Auto, 1024);
+
+        let frames = decode_all(&mut framer, &[b"<13>fo", b"o\n12 b", b"ar\n"]);
+        assert_eq!(frames, ["<13>foo", "12 bar"]);
+
+        let mut src = BytesMut::from(&b"<13>baz"[..]);
+        assert_eq!(framer.decode(&mut src).unwrap(), None);
+        assert_eq!(framer.decode_eof(&mut src).unwrap().unwrap(), "<13>baz");
+    }
+
+    #[test]
+    fn tcp_framing_auto_waits_for_length_prefix() {
+        let framer = SyslogTcpFramer::new(TcpFraming::Auto, 1024);
+
+        assert!(framer.detect(b"").is_none());
+        assert!(framer.detect(b"123").is_none());
+        assert!(matches!(
+            framer.detect(b"123 "),
+            Some(ConnectionFraming::OctetCounting { .. })
+        ));
+        assert!(matches!(
+            framer.detect(b"123<"),
+            Some(ConnectionFraming::NonTransparent(_))
+        ));
+        assert!(matches!(
+            framer.detect(b"<13>"),
+            Some(ConnectionFraming::NonTransparent(_))
+        ));
+        assert!(matches!(
+            framer.detect(b"12345678901"),
+            Some(ConnectionFraming::NonTransparent(_))
+        ));
+    }
+
+    #[test]
+    fn tcp_framing_octet_counting_rejects_malformed_count() {
+        for input in [&b"0 <13>foo"[..], b"01 x", b"1x2 <13>foo", b"123456789012"] {
+            let mut framer = SyslogTcpFramer::new(TcpFraming::OctetCounting, 1024);
+            let mut src = BytesMut::from(input);
+            let error = framer.decode(&mut src).unwrap_err();
+            assert!(!error.can_continue(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn tcp_framing_octet_counting_skips_oversized_frame() {
+        let mut framer = SyslogTcpFramer::new(TcpFraming::OctetCounting, 8);
+        let mut src = BytesMut::from(&b"10 <13>foo ba"[..]);
+
+        let error = framer.decode(&mut src).unwrap_err();
+        assert!(error.can_continue());
+        assert_eq!(framer.decode(&mut src).unwrap(), None);
+
+        src.extend_from_slice(b"r7 <13>baz");
+        assert_eq!(framer.decode(&mut src).unwrap().unwrap(), "<13>baz");
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn tcp_framing_octet_counting_truncated_at_eof() {
+        let mut framer = SyslogTcpFramer::new(TcpFraming::OctetCounting, 1024);
+        let mut src = BytesMut::from(&b"11 <13>foo"[..]);
+
+        assert_eq!(framer.decode(&mut src).unwrap(), None);
+        assert!(framer.decode_eof(&mut src).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_syslog_mixed_framing() {
+        use tokio::io::AsyncWriteExt;
+        use tokio::net::TcpStream;
+
+        let in_addr = next_addr();
+
+        let config = SyslogConfig::from_mode(Mode::Tcp {
+            address: in_addr.into(),
+            keepalive: None,
+            tls: None,
+            receive_buffer_bytes: None,
+            connection_limit: None,
+            framing: TcpFraming::Auto,
+        });
+
+        let key = ComponentKey::from("in");
+        let (tx, rx) = SourceSender::new_test();
+        let (context, shutdown) = SourceContext::new_shutdown(&key, tx);
+        let shutdown_complete = shutdown.shutdown_tripwire();
+
+        let source = config
+            .build(context)
+            .await
+            .expect("source should not fail to build");
+        tokio::spawn(source);
+        wait_for_tcp(in_addr).await;
+
+        let output_events = CountReceiver::receive_events(rx);
+
+        let octet_counted = "<13>1 2019-02-13T19:48:34+00:00 host app 1 - - octet\ncounted";
+        let framed = format!("{} {}", octet_counted.len(), octet_counted);
+        let mut octet_counting = TcpStream::connect(in_addr).await.unwrap();
+        // The device splits the frame across TCP segments, in the middle of the length prefix.
+        for segment in [&framed[..1], &framed[1..20], &framed[20..]] {
+            octet_counting.write_all(segment.as_bytes()).await.unwrap();
+            octet_counting.flush().await.unwrap();
+            sleep(Duration::from_millis(50)).await;
+        }
+
+        let mut non_transparent = TcpStream::connect(in_addr).await.unwrap();
+        non_transparent
+            .write_all(b"<13>1 2019-02-13T19:48:34+00:00 host app 1 - - non transparent\n")
+            .await
+            .unwrap();
+
+        let mut malformed = TcpStream::connect(in_addr).await.unwrap();
+        malformed
+            .write_all(b"0 <13>1 2019-02-13T19:48:34+00:00 host app 1 - - dropped\n")
+            .await
+            .unwrap();
+
+        sleep(Duration::from_secs(1)).await;
+
+        shutdown
+            .shutdown_all(Some(Instant::now() + Duration::from_millis(100)))
+            .await;
+        shutdown_complete.await;
+
+        let mut messages = output_events
+            .await
+            .into_iter()
+            .map(|event| {
+                event
+                    .as_log()
+                    .get("message")
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<_>>();
+        messages.sort();
+        assert_eq!(messages, ["non transparent", "octet\ncounted"]);
+    }
+
     #[derive(Deserialize, PartialEq, Clone, Debug)]
     struct SyslogMessageRfc5424 {
         msgid: String,
@@ -0,0 +1,245 @@
+//! Limits on the connections to the gRPC servers.
+//!
+//! A connection beyond the limits is still accepted, as gRPC has no way of telling the client why
+//! the connection is refused, but all its requests are answered with `RESOURCE_EXHAUSTED`.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+};
+use tonic::{transport::server::Connected, Request, Status};
+
+use crate::{
+    internal_events::{ConnectionOpen, GrpcConnectionRejected},
+    tls::MaybeTlsIncomingStream,
+};
+
+/// The limits on the open connections to a gRPC server.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionLimits {
+    max_connections: Option<u32>,
+    max_connections_per_peer: Option<u32>,
+    counts: Arc<Mutex<ConnectionCounts>>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectionCounts {
+    total: usize,
+    per_peer: HashMap<IpAddr, usize>,
+}
+
+impl ConnectionLimits {
+    /// Creates the limits on the number of open connections, in total and from the same IP address.
+    pub fn new(max_connections: Option<u32>, max_connections_per_peer: Option<u32>) -> Self {
+        Self {
+            max_connections,
+            max_connections_per_peer,
+            counts: Arc::default(),
+        }
+    }
+
+    /// Counts the new connection as open, if it is within the limits.
+    pub(super) fn accept(&self, stream: MaybeTlsIncomingStream<TcpStream>) -> LimitedStream {
+        let peer_addr = stream.peer_addr();
+        let guard = self.acquire(peer_addr.ip());
+        if let Err(reason) = guard {
+            emit!(GrpcConnectionRejected { peer_addr, reason });
+        }
+        LimitedStream {
+            inner: stream,
+            peer_addr,
+            guard,
+        }
+    }
+
+    fn acquire(&self, peer_ip: IpAddr) -> Result<ConnectionGuard, &'static str> {
+        let mut counts = self.counts.lock().expect("the lock is never poisoned");
+        let peer_count = counts.per_peer.get(&peer_ip).copied().unwrap_or_default();
+        if self
+            .max_connections
+            .map_or(false, |max| counts.total >= max as usize)
+        {
+            return Err("too many open connections");
+        }
+        if self
+            .max_connections_per_peer
+            .map_or(false, |max| peer_count >= max as usize)
+        {
+            return Err("too many open connections from the peer");
+        }
+
+        counts.total += 1;
+        counts.per_peer.insert(peer_ip, peer_count + 1);
+        emit!(ConnectionOpen {
+            count: counts.total
+        });
+        Ok(ConnectionGuard {
+            counts: Arc::clone(&self.counts),
+            peer_ip,
+        })
+    }
+}
+
+/// Counts the connection as closed once dropped.
+#[derive(Debug)]
+struct ConnectionGuard {
+    counts: Arc<Mutex<ConnectionCounts>>,
+    peer_ip: IpAddr,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().expect("the lock is never poisoned");
+        counts.total -= 1;
+        if let Some(peer_count) = counts.per_peer.get_mut(&self.peer_ip) {
+            *peer_count -= 1;
+            if *peer_count == 0 {
+                counts.per_peer.remove(&self.peer_ip);
+            }
+        }
+        emit!(ConnectionOpen {
+            count: counts.total
+        });
+    }
+}
+
+/// A connection, with whether it is within the limits.
+pub struct LimitedStream {
+    inner: MaybeTlsIncomingStream<TcpStream>,
+    peer_addr: SocketAddr,
+    guard: Result<ConnectionGuard, &'static str>,
+}
+
+/// The information about the connection a request is received on.
+#[derive(Clone, Debug)]
+pub struct LimitedConnectInfo {
+    /// The address of the peer.
+    pub remote_addr: SocketAddr,
+
+    /// Why the connection is beyond the limits, if it is.
+    pub rejection: Option<&'static str>,
+}
+
+impl Connected for LimitedStream {
+    type ConnectInfo = LimitedConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        LimitedConnectInfo {
+            remote_addr: self.peer_addr,
+            rejection: self.guard.as_ref().err().copied(),
+        }
+    }
+}
+
+impl AsyncRead for LimitedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for LimitedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Fails with `RESOURCE_EXHAUSTED` if the request is received on a connection beyond the limits.
+pub fn check_connection_limits<T>(request: &Request<T>) -> Result<(), Status> {
+    match request
+        .extensions()
+        .get::<LimitedConnectInfo>()
+        .and_then(|info| info.rejection)
+    {
+        Some(reason) => Err(Status::resource_exhausted(reason)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::from([10, 0, 0, last])
+    }
+
+    #[test]
+    fn max_connections() {
+        let limits = ConnectionLimits::new(Some(2), None);
+
+        let first = limits.acquire(ip(1)).unwrap();
+        let _second = limits.acquire(ip(1)).unwrap();
+        assert_eq!(
+            limits.acquire(ip(2)).unwrap_err(),
+            "too many open connections"
+        );
+
+        // The connection is counted as closed once dropped.
+        drop(first);
+        assert!(limits.acquire(ip(2)).is_ok());
+    }
+
+    #[test]
+    fn max_connections_per_peer() {
+        let limits = ConnectionLimits::new(Some(3), Some(1));
+
+        let first = limits.acquire(ip(1)).unwrap();
+        assert_eq!(
+            limits.acquire(ip(1)).unwrap_err(),
+            "too many open connections from the peer"
+        );
+        let _other_peer = limits.acquire(ip(2)).unwrap();
+
+        drop(first);
+        assert!(limits.acquire(ip(1)).is_ok());
+        assert!(limits.counts.lock().unwrap().per_peer.get(&ip(1)).is_none());
+    }
+
+    #[test]
+    fn unlimited() {
+        let limits = ConnectionLimits::default();
+        let guards: Vec<_> = (0..100).map(|_| limits.acquire(ip(1)).unwrap()).collect();
+        assert_eq!(limits.counts.lock().unwrap().total, 100);
+        drop(guards);
+        assert_eq!(limits.counts.lock().unwrap().total, 0);
+    }
+
+    #[test]
+    fn check_rejected_connection() {
+        let mut request = Request::new(());
+        assert!(check_connection_limits(&request).is_ok());
+
+        request.extensions_mut().insert(LimitedConnectInfo {
+            remote_addr: "10.0.0.1:6000".parse().unwrap(),
+            rejection: Some("too many open connections"),
+        });
+        let status = check_connection_limits(&request).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.message(), "too many open connections");
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     shutdown::{ShutdownSignal, ShutdownSignalToken},
     tls::MaybeTlsSettings,
 };
-use futures::FutureExt;
+use futures::{FutureExt, StreamExt};
 use http::{Request, Response};
 use hyper::Body;
 use std::{convert::Infallible, net::SocketAddr, time::Duration};
@@ -21,12 +21,44 @@ use tracing::Span;
 mod decompression;
 pub use self::decompression::{DecompressionAndMetrics, DecompressionAndMetricsLayer};
 
+mod limits;
+pub use self::limits::{check_connection_limits, ConnectionLimits, LimitedConnectInfo};
+
 pub async fn run_grpc_server<S>(
     address: SocketAddr,
     tls_settings: MaybeTlsSettings,
     service: S,
     shutdown: ShutdownSignal,
 ) -> crate::Result<()>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
+        + NamedService
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    run_grpc_server_with_limits(
+        address,
+        tls_settings,
+        service,
+        shutdown,
+        ConnectionLimits::default(),
+    )
+    .await
+}
+
+/// Runs the gRPC server, with limits on its open connections.
+///
+/// The service is expected to reject the requests received on the connections beyond the limits,
+/// with [`check_connection_limits`].
+pub async fn run_grpc_server_with_limits<S>(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    service: S,
+    shutdown: ShutdownSignal,
+    limits: ConnectionLimits,
+) -> crate::Result<()>
 where
     S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
         + NamedService
@@ -38,7 +70,9 @@ where
     let span = Span::current();
     let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
     let listener = tls_settings.bind(&address).await?;
-    let stream = listener.accept_stream();
+    let stream = listener
+        .accept_stream()
+        .map(move |result| result.map(|stream| limits.accept(stream)));
 
     info!(%address, "Building gRPC server.");
 
@@ -1,9 +1,17 @@
 use std::convert::TryFrom;
 
 use headers::{Authorization, HeaderMapExt};
+use openssl::{
+    hash::MessageDigest,
+    memcmp,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
 use vector_lib::configurable::configurable_component;
 use vector_lib::sensitive_string::SensitiveString;
-use warp::http::HeaderMap;
+use warp::http::{header::HeaderName, HeaderMap};
+
+use crate::internal_events::HttpSignatureRejected;
 
 #[cfg(any(
     feature = "sources-utils-http-prelude",
@@ -83,3 +91,204 @@ impl HttpSourceAuth {
         }
     }
 }
+
+/// The hash algorithm of an HMAC signature.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HmacAlgorithm {
+    /// SHA-1.
+    Sha1,
+
+    /// SHA-256.
+    #[default]
+    Sha256,
+
+    /// SHA-512.
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    fn message_digest(self) -> MessageDigest {
+        match self {
+            Self::Sha1 => MessageDigest::sha1(),
+            Self::Sha256 => MessageDigest::sha256(),
+            Self::Sha512 => MessageDigest::sha512(),
+        }
+    }
+}
+
+/// HMAC signature verification configuration.
+///
+/// The signature is computed over the raw body of the request, before it is decompressed or
+/// decoded, and is sent hex encoded in a header, like the `X-Hub-Signature-256` header of GitHub
+/// webhooks.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct HmacAuthConfig {
+    /// The name of the header holding the signature.
+    #[configurable(metadata(docs::examples = "X-Hub-Signature-256"))]
+    pub header: String,
+
+    /// The secret the signature is computed with.
+    #[configurable(metadata(docs::examples = "${WEBHOOK_SECRET}"))]
+    #[configurable(metadata(docs::examples = "SECRET[secret_backend.webhook_secret]"))]
+    pub secret: SensitiveString,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub algorithm: HmacAlgorithm,
+
+    /// The prefix of the signature in the header, which is stripped before the signature is
+    /// verified.
+    #[configurable(metadata(docs::examples = "sha256="))]
+    pub prefix: Option<String>,
+}
+
+impl TryFrom<&HmacAuthConfig> for HmacAuth {
+    type Error = String;
+
+    fn try_from(config: &HmacAuthConfig) -> Result<Self, Self::Error> {
+        let header = HeaderName::from_bytes(config.header.as_bytes())
+            .map_err(|error| format!("Invalid HMAC signature header name: {}", error))?;
+        let key = PKey::hmac(config.secret.inner().as_bytes())
+            .map_err(|error| format!("Invalid HMAC secret: {}", error))?;
+        Ok(HmacAuth {
+            header,
+            key,
+            algorithm: config.algorithm,
+            prefix: config.prefix.clone().unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HmacAuth {
+    header: HeaderName,
+    key: PKey<Private>,
+    algorithm: HmacAlgorithm,
+    prefix: String,
+}
+
+impl HmacAuth {
+    /// Verifies the signature of the raw request body, comparing it in constant time.
+    #[allow(unused)] // triggered by check-component-features
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), ErrorMessage> {
+        use warp::http::StatusCode;
+
+        let reject = |reason, message: &str| {
+            emit!(HttpSignatureRejected { reason });
+            Err(ErrorMessage::new(
+                StatusCode::UNAUTHORIZED,
+                message.to_owned(),
+            ))
+        };
+
+        let Some(header) = headers.get(&self.header) else {
+            return reject("missing_signature", "No signature header");
+        };
+        let Some(signature) = header
+            .to_str()
+            .ok()
+            .and_then(|header| header.strip_prefix(self.prefix.as_str()))
+            .and_then(|signature| hex::decode(signature.trim()).ok())
+        else {
+            return reject("malformed_signature", "Malformed signature");
+        };
+
+        let expected = Signer::new(self.algorithm.message_digest(), &self.key)
+            .and_then(|mut signer| {
+                signer.update(body)?;
+                signer.sign_to_vec()
+            })
+            .map_err(|error| {
+                ErrorMessage::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed computing signature: {}", error),
+                )
+            })?;
+
+        // `memcmp::eq` requires slices of the same length, the length of the signature is no
+        // secret.
+        if signature.len() == expected.len() && memcmp::eq(&signature, &expected) {
+            Ok(())
+        } else {
+            reject("invalid_signature", "Invalid signature")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example of https://docs.github.com/en/webhooks/using-webhooks/validating-webhook-deliveries.
+    const GITHUB_SECRET: &str = "It's a Secret to Everybody";
+    const GITHUB_PAYLOAD: &[u8] = b"Hello, World!";
+    const GITHUB_SIGNATURE: &str =
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    fn github_auth(algorithm: HmacAlgorithm, prefix: &str) -> HmacAuth {
+        HmacAuth::try_from(&HmacAuthConfig {
+            header: "X-Hub-Signature-256".to_owned(),
+            secret: GITHUB_SECRET.to_owned().into(),
+            algorithm,
+            prefix: Some(prefix.to_owned()),
+        })
+        .unwrap()
+    }
+
+    fn signature_headers(signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Hub-Signature-256", signature.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn verifies_github_signature() {
+        let auth = github_auth(HmacAlgorithm::Sha256, "sha256=");
+        let headers = signature_headers(GITHUB_SIGNATURE);
+        assert!(auth.verify(&headers, GITHUB_PAYLOAD).is_ok());
+        assert!(auth.verify(&headers, b"Hello, World?").is_err());
+    }
+
+    #[test]
+    fn verifies_algorithms() {
+        let cases = [
+            (
+                HmacAlgorithm::Sha1,
+                "01dc10d0c83e72ed246219cdd91669667fe2ca59",
+            ),
+            (
+                HmacAlgorithm::Sha512,
+                "11ed355a617e98134e842012a7944ccf59c10256cb182357bd7e3a42013ff07c376f8c14cf5cc1923da20b51d64256b2fb8ebbf100aa67a61326f61fea8111bc",
+            ),
+        ];
+        for (algorithm, signature) in cases {
+            let auth = github_auth(algorithm, "");
+            assert!(auth
+                .verify(&signature_headers(signature), GITHUB_PAYLOAD)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        let auth = github_auth(HmacAlgorithm::Sha256, "sha256=");
+        for signature in [
+            // Missing the prefix.
+            &GITHUB_SIGNATURE["sha256=".len()..],
+            "sha256=not-hex",
+            "sha256=757107ea",
+            "sha256=857107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+        ] {
+            let error = auth
+                .verify(&signature_headers(signature), GITHUB_PAYLOAD)
+                .unwrap_err();
+            assert_eq!(error.status_code(), warp::http::StatusCode::UNAUTHORIZED);
+        }
+
+        let error = auth.verify(&HeaderMap::new(), GITHUB_PAYLOAD).unwrap_err();
+        assert_eq!(error.status_code(), warp::http::StatusCode::UNAUTHORIZED);
+    }
+}
@@ -8,32 +8,41 @@ use warp::http::StatusCode;
 use super::error::ErrorMessage;
 use crate::internal_events::HttpDecompressError;
 
-pub fn decode(header: Option<&str>, mut body: Bytes) -> Result<Bytes, ErrorMessage> {
+pub fn decode(header: Option<&str>, body: Bytes) -> Result<Bytes, ErrorMessage> {
+    decode_with_limit(header, body, None)
+}
+
+/// Decodes the body according to the `Content-Encoding` header, rejecting it
+/// once any of the decoding steps produces more than `max_size` bytes.
+pub fn decode_with_limit(
+    header: Option<&str>,
+    mut body: Bytes,
+    max_size: Option<usize>,
+) -> Result<Bytes, ErrorMessage> {
     if let Some(encodings) = header {
         for encoding in encodings.rsplit(',').map(str::trim) {
             body = match encoding {
                 "identity" => body,
-                "gzip" => {
-                    let mut decoded = Vec::new();
-                    MultiGzDecoder::new(body.reader())
-                        .read_to_end(&mut decoded)
-                        .map_err(|error| handle_decode_error(encoding, error))?;
-                    decoded.into()
+                "gzip" => read_limited(encoding, MultiGzDecoder::new(body.reader()), max_size)?,
+                "deflate" => read_limited(encoding, ZlibDecoder::new(body.reader()), max_size)?,
+                "snappy" => {
+                    if let Some(max_size) = max_size {
+                        let size = snap::raw::decompress_len(&body)
+                            .map_err(|error| handle_decode_error(encoding, error))?;
+                        if size > max_size {
+                            return Err(too_large_error(encoding, max_size));
+                        }
+                    }
+                    SnappyDecoder::new()
+                        .decompress_vec(&body)
+                        .map_err(|error| handle_decode_error(encoding, error))?
+                        .into()
                 }
-                "deflate" => {
-                    let mut decoded = Vec::new();
-                    ZlibDecoder::new(body.reader())
-                        .read_to_end(&mut decoded)
+                "zstd" => {
+                    let decoder = zstd::stream::read::Decoder::new(body.reader())
                         .map_err(|error| handle_decode_error(encoding, error))?;
-                    decoded.into()
+                    read_limited(encoding, decoder, max_size)?
                 }
-                "snappy" => SnappyDecoder::new()
-                    .decompress_vec(&body)
-                    .map_err(|error| handle_decode_error(encoding, error))?
-                    .into(),
-                "zstd" => zstd::decode_all(body.reader())
-                    .map_err(|error| handle_decode_error(encoding, error))?
-                    .into(),
                 encoding => {
                     return Err(ErrorMessage::new(
                         StatusCode::UNSUPPORTED_MEDIA_TYPE,
@@ -47,6 +56,35 @@ pub fn decode(header: Option<&str>, mut body: Bytes) -> Result<Bytes, ErrorMessa
     Ok(body)
 }
 
+fn read_limited(
+    encoding: &str,
+    reader: impl Read,
+    max_size: Option<usize>,
+) -> Result<Bytes, ErrorMessage> {
+    // Read one byte past the limit to tell a body of exactly `max_size` bytes
+    // apart from a larger one.
+    let limit = max_size.map_or(u64::MAX, |max_size| max_size as u64 + 1);
+    let mut decoded = Vec::new();
+    reader
+        .take(limit)
+        .read_to_end(&mut decoded)
+        .map_err(|error| handle_decode_error(encoding, error))?;
+    match max_size {
+        Some(max_size) if decoded.len() > max_size => Err(too_large_error(encoding, max_size)),
+        _ => Ok(decoded.into()),
+    }
+}
+
+fn too_large_error(encoding: &str, max_size: usize) -> ErrorMessage {
+    ErrorMessage::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!(
+            "Payload decompressed with {} decoder exceeds the maximum size of {} bytes.",
+            encoding, max_size
+        ),
+    )
+}
+
 fn handle_decode_error(encoding: &str, error: impl std::error::Error) -> ErrorMessage {
     emit!(HttpDecompressError {
         encoding,
@@ -6,6 +6,8 @@ mod encoding;
 mod error;
 mod method;
 #[cfg(feature = "sources-utils-http-prelude")]
+mod path;
+#[cfg(feature = "sources-utils-http-prelude")]
 mod prelude;
 #[cfg(any(
     feature = "sources-http_server",
@@ -15,13 +17,15 @@ mod prelude;
 mod query;
 
 #[cfg(feature = "sources-utils-http-auth")]
-pub use auth::{HttpSourceAuth, HttpSourceAuthConfig};
+pub use auth::{HmacAuth, HmacAuthConfig, HttpSourceAuth, HttpSourceAuthConfig};
 #[cfg(feature = "sources-utils-http-encoding")]
-pub use encoding::decode;
+pub use encoding::{decode, decode_with_limit};
 #[cfg(feature = "sources-utils-http-error")]
 pub use error::ErrorMessage;
 pub use method::HttpMethod;
 #[cfg(feature = "sources-utils-http-prelude")]
+pub use path::PathTemplate;
+#[cfg(feature = "sources-utils-http-prelude")]
 pub use prelude::HttpSource;
 #[cfg(feature = "sources-utils-http-query")]
 pub use query::add_query_parameters;
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use percent_encoding::percent_decode_str;
+
+/// Returns the name of the capture, if the path segment is a `{name}` placeholder.
+pub fn capture_name(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Capture(String),
+}
+
+/// A URL path with named captures, like `/ingest/{tenant}/{dataset}`.
+///
+/// Each `{name}` placeholder matches a single path segment, which is captured under `name`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathTemplate {
+    segments: Vec<Segment>,
+}
+
+impl PathTemplate {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let mut names = HashSet::new();
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| match capture_name(segment) {
+                Some("") => Err(format!("Empty capture name in path `{}`.", path)),
+                Some(name) if !names.insert(name) => Err(format!(
+                    "Duplicate capture name `{}` in path `{}`.",
+                    name, path
+                )),
+                Some(name) => Ok(Segment::Capture(name.to_owned())),
+                None => Ok(Segment::Literal(segment.to_owned())),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { segments })
+    }
+
+    pub fn has_captures(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Capture(_)))
+    }
+
+    /// Matches the request path against the template and returns the captured segments,
+    /// percent-decoded.
+    ///
+    /// Segments of the request path past the end of the template are ignored, as those are only
+    /// accepted if the path isn't strict.
+    pub fn captures(&self, request_path: &str) -> Option<Vec<(&str, String)>> {
+        let mut request_segments = request_path
+            .split('/')
+            .filter(|segment| !segment.is_empty());
+        let mut captures = Vec::new();
+        for segment in &self.segments {
+            let request_segment = request_segments.next()?;
+            match segment {
+                Segment::Literal(literal) if literal != request_segment => return None,
+                Segment::Literal(_) => {}
+                Segment::Capture(name) => captures.push((
+                    name.as_str(),
+                    percent_decode_str(request_segment)
+                        .decode_utf8_lossy()
+                        .into_owned(),
+                )),
+            }
+        }
+        Some(captures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_templates() {
+        let template = PathTemplate::parse("/ingest/{tenant}/{dataset}").unwrap();
+        assert!(template.has_captures());
+        assert!(!PathTemplate::parse("/ingest/logs").unwrap().has_captures());
+
+        assert!(PathTemplate::parse("/ingest/{}").is_err());
+        assert!(PathTemplate::parse("/{tenant}/{tenant}").is_err());
+    }
+
+    #[test]
+    fn captures_segments() {
+        let template = PathTemplate::parse("/ingest/{tenant}/{dataset}").unwrap();
+        assert_eq!(
+            template.captures("/ingest/acme/web%20logs"),
+            Some(vec![
+                ("tenant", "acme".to_owned()),
+                ("dataset", "web logs".to_owned())
+            ])
+        );
+        assert_eq!(
+            template.captures("/ingest/acme/web/extra"),
+            Some(vec![
+                ("tenant", "acme".to_owned()),
+                ("dataset", "web".to_owned())
+            ])
+        );
+        assert_eq!(template.captures("/ingest/acme"), None);
+        assert_eq!(template.captures("/other/acme/web"), None);
+    }
+}
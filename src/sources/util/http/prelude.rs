@@ -41,6 +41,7 @@ use super::{
     auth::{HttpSourceAuth, HttpSourceAuthConfig},
     encoding::decode,
     error::ErrorMessage,
+    path::capture_name,
 };
 
 #[async_trait]
@@ -69,6 +70,18 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         decode(encoding_header, body)
     }
 
+    // This function can be defined to verify the request against its raw body,
+    // before the body is decompressed or decoded.
+    fn verify(&self, _headers: &HeaderMap, _body: &Bytes) -> Result<(), ErrorMessage> {
+        Ok(())
+    }
+
+    // This function can be defined to return a body with the response to
+    // successfully handled requests, which otherwise have an empty body.
+    fn response_body(&self) -> Option<Bytes> {
+        None
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn run(
         self,
@@ -100,7 +113,16 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
             // https://github.com/rust-lang/rust-clippy/issues/8148
             #[allow(clippy::unnecessary_to_owned)]
             for s in path.split('/').filter(|&x| !x.is_empty()) {
-                filter = filter.and(warp::path(s.to_string())).boxed()
+                filter = match capture_name(s) {
+                    // Captures match any single segment, the implementors extract them from
+                    // the full request path.
+                    Some(_) => filter
+                        .and(warp::path::param::<String>())
+                        .map(|_: String| ())
+                        .untuple_one()
+                        .boxed(),
+                    None => filter.and(warp::path(s.to_string())).boxed(),
+                };
             }
             let svc = filter
                 .and(warp::path::tail())
@@ -136,6 +158,7 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
 
                         let events = auth
                             .is_valid(&auth_header)
+                            .and_then(|()| self.verify(&headers, &body))
                             .and_then(|()| self.decode(encoding_header.as_deref(), body))
                             .and_then(|body| {
                                 emit!(HttpBytesReceived {
@@ -163,7 +186,13 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                                 events
                             });
 
-                        handle_request(events, acknowledgements, response_code, cx.out.clone())
+                        handle_request(
+                            events,
+                            acknowledgements,
+                            response_code,
+                            self.response_body(),
+                            cx.out.clone(),
+                        )
                     },
                 );
 
@@ -172,6 +201,11 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 if let Some(e_msg) = r.find::<ErrorMessage>() {
                     let json = warp::reply::json(e_msg);
                     Ok(warp::reply::with_status(json, e_msg.status_code()))
+                } else if r.is_not_found() {
+                    emit!(HttpInternalError {
+                        message: "Path not found."
+                    });
+                    Err(r)
                 } else {
                     //other internal error - will return 500 internal server error
                     emit!(HttpInternalError {
@@ -222,6 +256,7 @@ async fn handle_request(
     events: Result<Vec<Event>, ErrorMessage>,
     acknowledgements: bool,
     response_code: StatusCode,
+    response_body: Option<Bytes>,
     mut out: SourceSender,
 ) -> Result<impl warp::Reply, Rejection> {
     match events {
@@ -236,7 +271,7 @@ async fn handle_request(
                     emit!(StreamClosedError { count });
                     warp::reject::custom(RejectShuttingDown)
                 })
-                .and_then(|_| handle_batch_status(response_code, receiver))
+                .and_then(|_| handle_batch_status(response_code, response_body, receiver))
                 .await
         }
         Err(error) => {
@@ -248,12 +283,19 @@ async fn handle_request(
 
 async fn handle_batch_status(
     success_response_code: StatusCode,
+    success_response_body: Option<Bytes>,
     receiver: Option<BatchStatusReceiver>,
 ) -> Result<impl warp::Reply, Rejection> {
     match receiver {
-        None => Ok(success_response_code),
+        None => Ok(success_response(
+            success_response_code,
+            success_response_body,
+        )),
         Some(receiver) => match receiver.await {
-            BatchStatus::Delivered => Ok(success_response_code),
+            BatchStatus::Delivered => Ok(success_response(
+                success_response_code,
+                success_response_body,
+            )),
             BatchStatus::Errored => Err(warp::reject::custom(ErrorMessage::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Error delivering contents to sink".into(),
@@ -265,3 +307,9 @@ async fn handle_batch_status(
         },
     }
 }
+
+fn success_response(status_code: StatusCode, body: Option<Bytes>) -> warp::reply::Response {
+    let mut response = warp::reply::Response::new(body.map_or_else(hyper::Body::empty, Into::into));
+    *response.status_mut() = status_code;
+    response
+}
@@ -9,7 +9,7 @@
 //!     context.
 
 use bytes::Bytes;
-use futures_util::{stream, FutureExt, StreamExt, TryFutureExt};
+use futures_util::{stream, FutureExt, Stream, StreamExt, TryFutureExt};
 use http::{response::Parts, Uri};
 use hyper::{Body, Request};
 use std::time::{Duration, Instant};
@@ -127,6 +127,28 @@ pub(crate) async fn call<
     B: HttpClientBuilder<Context = C> + Send + Clone,
     C: HttpClientContext + Send,
 >(
+    inputs: GenericHttpClientInputs,
+    context_builder: B,
+    out: SourceSender,
+    http_method: HttpMethod,
+) -> Result<(), ()> {
+    let urls = inputs.urls.clone();
+    let schedule = IntervalStream::new(tokio::time::interval(inputs.interval))
+        .map(move |_| stream::iter(urls.clone()))
+        .flatten();
+    call_urls(schedule, inputs, context_builder, out, http_method).await
+}
+
+/// Calls each url as it is yielded by `schedule`, instead of calling the urls of the inputs at
+/// their interval, until the shutdown signal of the inputs.
+///
+/// This allows the sources to discover the urls to call, and to schedule the calls themselves.
+pub(crate) async fn call_urls<
+    S: Stream<Item = Uri> + Send + 'static,
+    B: HttpClientBuilder<Context = C> + Send + Clone,
+    C: HttpClientContext + Send,
+>(
+    schedule: S,
     inputs: GenericHttpClientInputs,
     context_builder: B,
     mut out: SourceSender,
@@ -136,10 +158,8 @@ pub(crate) async fn call<
     // proxy and tls settings.
     let client =
         HttpClient::new(inputs.tls.clone(), &inputs.proxy).expect("Building HTTP client failed");
-    let mut stream = IntervalStream::new(tokio::time::interval(inputs.interval))
+    let mut stream = schedule
         .take_until(inputs.shutdown)
-        .map(move |_| stream::iter(inputs.urls.clone()))
-        .flatten()
         .map(move |url| {
             let client = client.clone();
             let endpoint = url.to_string();
@@ -41,6 +41,8 @@ pub use multiline_config::MultilineConfig;
 ))]
 pub use unix::change_socket_permissions;
 #[cfg(all(unix, feature = "sources-socket",))]
+pub use unix::PeerCredentials;
+#[cfg(all(unix, feature = "sources-socket",))]
 pub use unix_datagram::build_unix_datagram_source;
 #[cfg(all(unix, feature = "sources-utils-net-unix",))]
 pub use unix_stream::build_unix_stream_source;
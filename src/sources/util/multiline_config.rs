@@ -5,7 +5,10 @@ use serde_with::serde_as;
 use snafu::{ResultExt, Snafu};
 use vector_lib::configurable::configurable_component;
 
-use crate::line_agg;
+use crate::{
+    conditions::{ConditionalConfig, VrlConfig},
+    line_agg::{self, LineCondition},
+};
 
 /// Configuration of multi-line aggregation.
 #[serde_as]
@@ -22,16 +25,30 @@ pub struct MultilineConfig {
 
     /// Regular expression pattern that is used to determine whether or not more lines should be read.
     ///
-    /// This setting must be configured in conjunction with `mode`.
+    /// This setting must be configured in conjunction with `mode`, and can't be configured
+    /// together with `condition`.
     #[configurable(metadata(docs::examples = "^[\\s]+"))]
     #[configurable(metadata(docs::examples = "\\\\$"))]
     #[configurable(metadata(docs::examples = "^(INFO|ERROR) "))]
     #[configurable(metadata(docs::examples = ";$"))]
-    pub condition_pattern: String,
+    pub condition_pattern: Option<String>,
+
+    /// [Vector Remap Language][vrl] (VRL) boolean expression that is used to determine whether or
+    /// not more lines should be read.
+    ///
+    /// The expression is evaluated against each line, which is exposed as `.message`. It can tell
+    /// lines apart where a regular expression can't, like JSON lines without a `timestamp` key.
+    ///
+    /// This setting must be configured in conjunction with `mode`, and can't be configured
+    /// together with `condition_pattern`.
+    ///
+    /// [vrl]: https://vector.dev/docs/reference/vrl
+    #[configurable(metadata(docs::examples = "(parse_json(.message) ?? {}).timestamp == null"))]
+    pub condition: Option<String>,
 
     /// Aggregation mode.
     ///
-    /// This setting must be configured in conjunction with `condition_pattern`.
+    /// This setting must be configured in conjunction with `condition_pattern` or `condition`.
     #[configurable(derived)]
     pub mode: line_agg::Mode,
 
@@ -52,19 +69,39 @@ impl TryFrom<&MultilineConfig> for line_agg::Config {
         let MultilineConfig {
             start_pattern,
             condition_pattern,
+            condition,
             mode,
             timeout_ms,
         } = config;
 
         let start_pattern = Regex::new(start_pattern)
             .with_context(|_| InvalidMultilineStartPatternSnafu { start_pattern })?;
-        let condition_pattern = Regex::new(condition_pattern)
-            .with_context(|_| InvalidMultilineConditionPatternSnafu { condition_pattern })?;
+        let condition = match (condition_pattern, condition) {
+            (Some(condition_pattern), None) => Regex::new(condition_pattern)
+                .with_context(|_| InvalidMultilineConditionPatternSnafu { condition_pattern })?
+                .into(),
+            (None, Some(condition)) => {
+                // The condition is compiled like the VRL conditions, so that compilation errors
+                // carry the same diagnostics.
+                let vrl = VrlConfig {
+                    source: condition.clone(),
+                    runtime: Default::default(),
+                };
+                let condition = vrl.build(&Default::default()).map_err(|error| {
+                    Error::InvalidMultilineCondition {
+                        error: error.to_string(),
+                    }
+                })?;
+                LineCondition::Vrl(condition)
+            }
+            (Some(_), Some(_)) => return ConflictingMultilineConditionSnafu.fail(),
+            (None, None) => return MissingMultilineConditionSnafu.fail(),
+        };
         let timeout = *timeout_ms;
 
         Ok(Self {
             start_pattern,
-            condition_pattern,
+            condition,
             mode: *mode,
             timeout,
         })
@@ -91,4 +128,60 @@ pub enum Error {
         condition_pattern: String,
         source: regex::Error,
     },
+    #[snafu(display("unable to compile multiline condition: {}", error))]
+    InvalidMultilineCondition { error: String },
+    #[snafu(display("only one of multiline condition pattern and condition can be configured"))]
+    ConflictingMultilineCondition,
+    #[snafu(display("either multiline condition pattern or condition must be configured"))]
+    MissingMultilineCondition,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(condition_pattern: Option<&str>, condition: Option<&str>) -> MultilineConfig {
+        MultilineConfig {
+            start_pattern: "^[^\\s]".to_owned(),
+            condition_pattern: condition_pattern.map(ToOwned::to_owned),
+            condition: condition.map(ToOwned::to_owned),
+            mode: line_agg::Mode::ContinueThrough,
+            timeout_ms: Duration::from_millis(1000),
+        }
+    }
+
+    #[test]
+    fn condition_pattern() {
+        let config = line_agg::Config::try_from(&config(Some("^[\\s]+"), None)).unwrap();
+        assert!(matches!(config.condition, LineCondition::Pattern(_)));
+    }
+
+    #[test]
+    fn vrl_condition() {
+        let config = line_agg::Config::try_from(&config(
+            None,
+            Some("(parse_json(.message) ?? {}).timestamp == null"),
+        ))
+        .unwrap();
+        assert!(matches!(config.condition, LineCondition::Vrl(_)));
+    }
+
+    #[test]
+    fn invalid_vrl_condition() {
+        let error =
+            line_agg::Config::try_from(&config(None, Some("starts_with(.message,"))).unwrap_err();
+        assert!(matches!(error, Error::InvalidMultilineCondition { .. }));
+    }
+
+    #[test]
+    fn conflicting_conditions() {
+        let error = line_agg::Config::try_from(&config(Some("^[\\s]+"), Some("true"))).unwrap_err();
+        assert!(matches!(error, Error::ConflictingMultilineCondition));
+    }
+
+    #[test]
+    fn missing_condition() {
+        let error = line_agg::Config::try_from(&config(None, None)).unwrap_err();
+        assert!(matches!(error, Error::MissingMultilineCondition));
+    }
 }
@@ -2,8 +2,8 @@ mod request_limiter;
 
 use std::{io, mem::drop, net::SocketAddr, time::Duration};
 
-use bytes::Bytes;
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use bytes::{Bytes, BytesMut};
+use futures::{future, future::BoxFuture, FutureExt, StreamExt};
 use futures_util::future::OptionFuture;
 use listenfd::ListenFd;
 use smallvec::SmallVec;
@@ -103,6 +103,19 @@ where
 
     fn handle_events(&self, _events: &mut [Event], _host: std::net::SocketAddr) {}
 
+    /// Performs the handshake of the protocol on a new connection, before its frames are decoded,
+    /// like authenticating the client. The connection is closed if the handshake fails.
+    ///
+    /// The bytes read past the handshake are left in `buffer`, to be decoded.
+    fn handshake<'a>(
+        &'a self,
+        _socket: &'a mut MaybeTlsIncomingStream<TcpStream>,
+        _buffer: &'a mut BytesMut,
+        _peer_addr: SocketAddr,
+    ) -> BoxFuture<'a, crate::Result<()>> {
+        Box::pin(future::ok(()))
+    }
+
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
 
     #[allow(clippy::too_many_arguments)]
@@ -274,6 +287,19 @@ async fn handle_stream<T>(
         }
     }
 
+    let mut handshake_buffer = BytesMut::new();
+    tokio::select! {
+        result = source.handshake(&mut socket, &mut handshake_buffer, peer_addr) => {
+            if let Err(error) = result {
+                debug!(message = "Handshake failed, closing the connection.", %error, %peer_addr);
+                return;
+            }
+        },
+        _ = &mut shutdown_signal => {
+            return;
+        }
+    };
+
     let socket = socket.after_read(move |byte_size| {
         emit!(TcpBytesReceived {
             byte_size,
@@ -287,7 +313,8 @@ async fn handle_stream<T>(
         .and_then(|stream| stream.ssl().peer_certificate())
         .map(CertificateMetadata::from);
 
-    let reader = FramedRead::new(socket, source.decoder());
+    let mut reader = FramedRead::new(socket, source.decoder());
+    reader.read_buffer_mut().unsplit(handshake_buffer);
     let mut reader = ReadyFrames::new(reader);
 
     let connection_close_timeout = OptionFuture::from(
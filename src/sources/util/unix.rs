@@ -19,3 +19,11 @@ pub fn change_socket_permissions(path: &Path, perms: Option<u32>) -> crate::Resu
     }
     Ok(())
 }
+
+/// The credentials of the process that sent a message over a Unix domain socket.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PeerCredentials {
+    pub pid: i32,
+    pub uid: u32,
+    pub gid: u32,
+}
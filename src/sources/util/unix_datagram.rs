@@ -1,8 +1,16 @@
-use std::{fs::remove_file, path::PathBuf};
+use std::{
+    fs::remove_file,
+    io::{self, IoSliceMut},
+    os::unix::io::{AsRawFd, RawFd},
+    path::PathBuf,
+};
 
 use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
-use tokio::net::UnixDatagram;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use nix::sys::socket::ControlMessageOwned;
+use nix::sys::socket::{recvmsg, MsgFlags, UnixAddr};
+use tokio::{io::Interest, net::UnixDatagram};
 use tokio_util::codec::FramedRead;
 use tracing::field;
 use vector_lib::codecs::StreamDecodingError;
@@ -14,11 +22,11 @@ use crate::{
     event::Event,
     internal_events::{
         SocketEventsReceived, SocketMode, SocketReceiveError, StreamClosedError,
-        UnixSocketFileDeleteError,
+        UnixSocketDatagramTruncated, UnixSocketFileDeleteError,
     },
     shutdown::ShutdownSignal,
     sources::util::change_socket_permissions,
-    sources::util::unix::UNNAMED_SOCKET_HOST,
+    sources::util::unix::{PeerCredentials, UNNAMED_SOCKET_HOST},
     sources::Source,
     SourceSender,
 };
@@ -27,12 +35,19 @@ use crate::{
 /// Passing in different functions for `decoder` and `handle_events` can allow
 /// for different source-specific logic (such as decoding syslog messages in the
 /// syslog source).
+///
+/// On Linux, the credentials of the process that sent each datagram are passed
+/// to `handle_events` as well.
 pub fn build_unix_datagram_source(
     listen_path: PathBuf,
     socket_file_mode: Option<u32>,
     max_length: usize,
     decoder: Decoder,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut [Event], Option<Bytes>, Option<PeerCredentials>)
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> crate::Result<Source> {
@@ -43,6 +58,14 @@ pub fn build_unix_datagram_source(
         change_socket_permissions(&listen_path, socket_file_mode)
             .expect("Failed to set socket permissions");
 
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        nix::sys::socket::setsockopt(
+            socket.as_raw_fd(),
+            nix::sys::socket::sockopt::PassCred,
+            &true,
+        )
+        .expect("Failed to enable receiving credentials on datagram socket");
+
         let result = listen(socket, max_length, decoder, shutdown, handle_events, out).await;
 
         // Delete socket file.
@@ -57,12 +80,24 @@ pub fn build_unix_datagram_source(
     }))
 }
 
+/// A datagram received on the socket.
+struct Datagram {
+    byte_size: usize,
+    truncated: bool,
+    peer_path: Option<PathBuf>,
+    credentials: Option<PeerCredentials>,
+}
+
 async fn listen(
     socket: UnixDatagram,
     max_length: usize,
     decoder: Decoder,
     mut shutdown: ShutdownSignal,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut [Event], Option<Bytes>, Option<PeerCredentials>)
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     mut out: SourceSender,
 ) -> Result<(), ()> {
     let mut buf = BytesMut::with_capacity(max_length);
@@ -70,8 +105,8 @@ async fn listen(
     loop {
         buf.resize(max_length, 0);
         tokio::select! {
-            recv = socket.recv_from(&mut buf) => {
-                let (byte_size, address) = recv.map_err(|error| {
+            recv = recv_datagram(&socket, &mut buf) => {
+                let datagram = recv.map_err(|error| {
                     let error = vector_lib::codecs::decoding::Error::FramingError(error.into());
                     emit!(SocketReceiveError {
                         mode: SocketMode::Unix,
@@ -80,25 +115,28 @@ async fn listen(
                 })?;
 
                 let span = info_span!("datagram");
-                let received_from = if !address.is_unnamed() {
-                    let path = address.as_pathname().map(|e| e.to_owned()).map(|path| {
+                let received_from = match datagram.peer_path {
+                    Some(path) => {
                         span.record("peer_path", &field::debug(&path));
-                        path
-                    });
-
-                    path.map(|p| p.to_string_lossy().into_owned().into())
-                } else {
-                    // In most cases, we'll be connecting to this
-                    // socket from an unnamed socket (a socket not
-                    // bound to a file). Instead of a filename, we'll
-                    // surface a specific host value.
-                    span.record("peer_path", &field::debug(UNNAMED_SOCKET_HOST));
-                    Some(UNNAMED_SOCKET_HOST.into())
+                        Some(path.to_string_lossy().into_owned().into())
+                    }
+                    None => {
+                        // In most cases, we'll be connecting to this
+                        // socket from an unnamed socket (a socket not
+                        // bound to a file). Instead of a filename, we'll
+                        // surface a specific host value.
+                        span.record("peer_path", &field::debug(UNNAMED_SOCKET_HOST));
+                        Some(UNNAMED_SOCKET_HOST.into())
+                    }
                 };
 
-                bytes_received.emit(ByteSize(byte_size));
+                if datagram.truncated {
+                    emit!(UnixSocketDatagramTruncated { max_length });
+                }
+
+                bytes_received.emit(ByteSize(datagram.byte_size));
 
-                let payload = buf.split_to(byte_size);
+                let payload = buf.split_to(datagram.byte_size);
 
                 let mut stream = FramedRead::new(payload.as_ref(), decoder.clone());
 
@@ -111,7 +149,7 @@ async fn listen(
                                 count: events.len()
                             });
 
-                            handle_events(&mut events, received_from.clone());
+                            handle_events(&mut events, received_from.clone(), datagram.credentials);
 
                             let count = events.len();
                             if (out.send_batch(events).await).is_err() {
@@ -135,3 +173,51 @@ async fn listen(
         }
     }
 }
+
+/// Receives a datagram into `buf`, along with the credentials of its sender.
+///
+/// Datagrams longer than `buf` are truncated to its length.
+async fn recv_datagram(socket: &UnixDatagram, buf: &mut [u8]) -> io::Result<Datagram> {
+    loop {
+        socket.readable().await?;
+        match socket.try_io(Interest::READABLE, || {
+            try_recv_datagram(socket.as_raw_fd(), buf)
+        }) {
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => continue,
+            result => return result,
+        }
+    }
+}
+
+fn try_recv_datagram(fd: RawFd, buf: &mut [u8]) -> io::Result<Datagram> {
+    let mut iov = [IoSliceMut::new(buf)];
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let mut cmsg_buffer = Some(nix::cmsg_space!(nix::libc::ucred));
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let mut cmsg_buffer: Option<Vec<u8>> = None;
+
+    let message = recvmsg::<UnixAddr>(fd, &mut iov, cmsg_buffer.as_mut(), MsgFlags::empty())?;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let credentials = message.cmsgs().find_map(|cmsg| match cmsg {
+        ControlMessageOwned::ScmCredentials(credentials) => Some(PeerCredentials {
+            pid: credentials.pid(),
+            uid: credentials.uid(),
+            gid: credentials.gid(),
+        }),
+        _ => None,
+    });
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let credentials = None;
+
+    Ok(Datagram {
+        byte_size: message.bytes,
+        truncated: message.flags.contains(MsgFlags::MSG_TRUNC),
+        peer_path: message
+            .address
+            .as_ref()
+            .and_then(UnixAddr::path)
+            .map(|path| path.to_owned()),
+        credentials,
+    })
+}
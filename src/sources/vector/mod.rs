@@ -3,13 +3,14 @@ use std::net::SocketAddr;
 
 use chrono::Utc;
 use futures::TryFutureExt;
+use prost::Message;
 use tonic::{Request, Response, Status};
 use vector_lib::codecs::NativeDeserializerConfig;
 use vector_lib::configurable::configurable_component;
 use vector_lib::internal_event::{CountByteSize, InternalEventHandle as _};
 use vector_lib::{
     config::LogNamespace,
-    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
+    event::{proto::EventWrapper, BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
     EstimatedJsonEncodedSizeOf,
 };
 
@@ -18,10 +19,13 @@ use crate::{
         DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
         SourceContext, SourceOutput,
     },
-    internal_events::{EventsReceived, StreamClosedError},
+    internal_events::{EventsReceived, GrpcOversizedEventsError, StreamClosedError},
     proto::vector as proto,
     serde::bool_or_struct,
-    sources::{util::grpc::run_grpc_server, Source},
+    sources::{
+        util::grpc::{check_connection_limits, run_grpc_server_with_limits, ConnectionLimits},
+        Source,
+    },
     tls::{MaybeTlsSettings, TlsEnableableConfig},
     SourceSender,
 };
@@ -40,6 +44,34 @@ struct Service {
     pipeline: SourceSender,
     acknowledgements: bool,
     log_namespace: LogNamespace,
+    max_event_size_bytes: Option<usize>,
+}
+
+impl Service {
+    /// Fails if any of the events is larger than `max_event_size_bytes`, before they are converted.
+    fn check_event_sizes(&self, events: &[EventWrapper]) -> Result<(), Status> {
+        let Some(max_event_size_bytes) = self.max_event_size_bytes else {
+            return Ok(());
+        };
+
+        let count = events
+            .iter()
+            .filter(|event| event.encoded_len() > max_event_size_bytes)
+            .count();
+        if count == 0 {
+            return Ok(());
+        }
+
+        emit!(GrpcOversizedEventsError {
+            count,
+            max_event_size_bytes
+        });
+        // Not retried by the `vector` sink, as the batch would be rejected again.
+        Err(Status::invalid_argument(format!(
+            "{} events are larger than the maximum size of {} bytes",
+            count, max_event_size_bytes
+        )))
+    }
 }
 
 #[tonic::async_trait]
@@ -48,12 +80,11 @@ impl proto::Service for Service {
         &self,
         request: Request<proto::PushEventsRequest>,
     ) -> Result<Response<proto::PushEventsResponse>, Status> {
-        let mut events: Vec<Event> = request
-            .into_inner()
-            .events
-            .into_iter()
-            .map(Event::from)
-            .collect();
+        check_connection_limits(&request)?;
+
+        let events = request.into_inner().events;
+        self.check_event_sizes(&events)?;
+        let mut events: Vec<Event> = events.into_iter().map(Event::from).collect();
 
         let now = Utc::now();
         for event in &mut events {
@@ -90,8 +121,10 @@ impl proto::Service for Service {
     // TODO: figure out a way to determine if the current Vector instance is "healthy".
     async fn health_check(
         &self,
-        _: Request<proto::HealthCheckRequest>,
+        request: Request<proto::HealthCheckRequest>,
     ) -> Result<Response<proto::HealthCheckResponse>, Status> {
+        check_connection_limits(&request)?;
+
         let message = proto::HealthCheckResponse {
             status: proto::ServingStatus::Serving.into(),
         };
@@ -134,6 +167,30 @@ pub struct VectorConfig {
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
 
+    /// The maximum number of connections open at any given time.
+    ///
+    /// The requests on the connections opened beyond the limit are rejected with the
+    /// `RESOURCE_EXHAUSTED` gRPC status, until the connection is closed.
+    #[configurable(metadata(docs::type_unit = "connections"))]
+    #[configurable(metadata(docs::examples = 1000))]
+    max_connections: Option<u32>,
+
+    /// The maximum number of connections open from the same IP address at any given time.
+    ///
+    /// The requests on the connections opened beyond the limit are rejected with the
+    /// `RESOURCE_EXHAUSTED` gRPC status, until the connection is closed.
+    #[configurable(metadata(docs::type_unit = "connections"))]
+    #[configurable(metadata(docs::examples = 10))]
+    max_connections_per_peer: Option<u32>,
+
+    /// The maximum size of an event, as encoded by the sending Vector instance.
+    ///
+    /// The batches with events larger than the limit are rejected as a whole with the
+    /// `INVALID_ARGUMENT` gRPC status, before the events are converted.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    #[configurable(metadata(docs::examples = 1048576))]
+    max_event_size_bytes: Option<usize>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[serde(default)]
     #[configurable(metadata(docs::hidden))]
@@ -157,6 +214,9 @@ impl Default for VectorConfig {
             address: "0.0.0.0:6000".parse().unwrap(),
             tls: None,
             acknowledgements: Default::default(),
+            max_connections: None,
+            max_connections_per_peer: None,
+            max_event_size_bytes: None,
             log_namespace: None,
         }
     }
@@ -180,15 +240,18 @@ impl SourceConfig for VectorConfig {
             pipeline: cx.out,
             acknowledgements,
             log_namespace,
+            max_event_size_bytes: self.max_event_size_bytes,
         })
         .accept_compressed(tonic::codec::CompressionEncoding::Gzip)
         // Tonic added a default of 4MB in 0.9. This replaces the old behavior.
         .max_decoding_message_size(usize::MAX);
 
+        let limits = ConnectionLimits::new(self.max_connections, self.max_connections_per_peer);
         let source =
-            run_grpc_server(self.address, tls_settings, service, cx.shutdown).map_err(|error| {
-                error!(message = "Source future failed.", %error);
-            });
+            run_grpc_server_with_limits(self.address, tls_settings, service, cx.shutdown, limits)
+                .map_err(|error| {
+                    error!(message = "Source future failed.", %error);
+                });
 
         Ok(Box::pin(source))
     }
@@ -218,15 +281,40 @@ mod test {
     use vector_lib::{config::LogNamespace, schema::Definition};
     use vrl::value::{kind::Collection, Kind};
 
-    use crate::config::SourceConfig;
+    use vector_lib::event::{proto::EventWrapper, Event, LogEvent};
+
+    use crate::{config::SourceConfig, SourceSender};
 
-    use super::VectorConfig;
+    use super::{Service, VectorConfig};
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<super::VectorConfig>();
     }
 
+    #[test]
+    fn reject_oversized_events() {
+        let service = Service {
+            pipeline: SourceSender::new_test().0,
+            acknowledgements: false,
+            log_namespace: LogNamespace::Legacy,
+            max_event_size_bytes: Some(100),
+        };
+        let small = EventWrapper::from(Event::Log(LogEvent::from("small")));
+        let large = EventWrapper::from(Event::Log(LogEvent::from("x".repeat(200))));
+
+        assert!(service.check_event_sizes(&[small.clone()]).is_ok());
+        let status = service.check_event_sizes(&[small, large]).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let unlimited = Service {
+            max_event_size_bytes: None,
+            ..service
+        };
+        let large = EventWrapper::from(Event::Log(LogEvent::from("x".repeat(200))));
+        assert!(unlimited.check_event_sizes(&[large]).is_ok());
+    }
+
     #[test]
     fn output_schema_definition_vector_namespace() {
         let config = VectorConfig::default();
@@ -234,6 +234,56 @@ cli: {
 			description: "Prints this message or the help of the given subcommand(s)"
 		}
 
+		"k8s-paths": {
+			description: """
+				List the pod log files the `kubernetes_logs` source would collect from a pod
+				logs directory, without collecting them, then exit. Each file is printed with
+				the pod and container parsed from its path, and whether the filters exclude
+				it. Exits with an error if none of the log files would be collected.
+				"""
+
+			example: "vector k8s-paths --root /var/log/pods --exclude-namespace kube-system --json"
+
+			flags: _default_flags & {
+				"json": {
+					description: "Print the discovered log files as JSON, one object per line"
+				}
+			}
+
+			options: {
+				"root": {
+					description: "The pod logs directory to discover the log files in"
+					type:        "string"
+					default:     "/var/log/pods"
+				}
+				"pod-dir-delimiter": {
+					description: "The character delimiting the namespace, the name and the UID of the pods in the names of their logs directories"
+					type:        "string"
+					default:     "_"
+				}
+				"exclude-paths-glob-pattern": {
+					description: "Glob patterns of the log files to exclude. Defaults to the ones of the source"
+					type:        "list"
+				}
+				"include-container-name": {
+					description: "Glob patterns of the names of the containers to include"
+					type:        "list"
+				}
+				"exclude-container-name": {
+					description: "Glob patterns of the names of the containers to exclude"
+					type:        "list"
+				}
+				"include-namespace": {
+					description: "Glob patterns of the namespaces to include"
+					type:        "list"
+				}
+				"exclude-namespace": {
+					description: "Glob patterns of the namespaces to exclude"
+					type:        "list"
+				}
+			}
+		}
+
 		"list": {
 			description: "List available components, then exit"
 
@@ -77,5 +77,9 @@ components: sources: amqp: {
 		}
 	}
 
+	telemetry: metrics: {
+		amqp_failed_messages_total: components.sources.internal_metrics.output.metrics.amqp_failed_messages_total
+	}
+
 	how_it_works: components._amqp.how_it_works
 }
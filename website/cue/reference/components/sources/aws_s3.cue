@@ -129,6 +129,23 @@ components: sources: aws_s3: components._aws & {
 				```
 				"""
 		}
+
+		polling: {
+			title: "Polling without bucket notifications"
+			body:  """
+				With the `polling` strategy, the objects under the `prefix` are listed
+				every `poll_secs`, and consumed in the lexicographic order of their keys.
+				The key of the last processed object is checkpointed in the `data_dir`,
+				and the next listings start after it, so that the processed objects are
+				not listed again, even in buckets with millions of keys.
+
+				Objects written with keys sorting before the checkpointed one are never
+				consumed, so this strategy suits keys starting with a timestamp. An
+				object failing to be processed is retried on the next listing, holding
+				back the objects after it. An object being processed when Vector stops
+				is processed again on the next start.
+				"""
+		}
 	}
 
 	permissions: iam: [
@@ -142,6 +159,14 @@ components: sources: aws_s3: components._aws & {
 				{
 					_action: "GetObject"
 				},
+				{
+					_action:       "ListBucket"
+					required_when: "the `polling` [`strategy`](#strategy) is used"
+				},
+				{
+					_action:       "DeleteObject"
+					required_when: "[`delete_object`](#polling.delete_object) is set to `true`"
+				},
 			]
 		},
 		{
@@ -69,6 +69,28 @@ components: sources: aws_sqs: components._aws & {
 					syntax: "literal"
 				}
 			}
+			message_deduplication_id: {
+				description: "The deduplication ID of the message, for FIFO queues."
+				required:    false
+				type: string: {
+					examples: ["7b1d6c2e"]
+				}
+			}
+			message_group_id: {
+				description: "The message group of the message, for FIFO queues."
+				required:    false
+				type: string: {
+					examples: ["orders"]
+				}
+			}
+			receive_count: {
+				description: "The approximate number of times the message was received from the queue."
+				required:    true
+				type: uint: {
+					examples: [1]
+					unit: null
+				}
+			}
 			source_type: {
 				description: "The name of the source type."
 				required:    true
@@ -94,5 +116,20 @@ components: sources: aws_sqs: components._aws & {
 				`acknowledgements` setting.
 				"""
 		}
+		fifo_queues: {
+			title: "FIFO queues"
+			body: """
+				Queues whose URL ends with `.fifo` are handled as FIFO queues. The events of the messages
+				of each message group are sent in order, one message after the other, while the message
+				groups are processed concurrently. With `acknowledgements` enabled, the next message of a
+				group is only sent once the events of the previous one are delivered, and a message is
+				only deleted after all the messages before it in its group were. A message that fails
+				to be delivered is received again, along with the ones following it in its group, once
+				its visibility timeout expires.
+
+				The `receive_count` field can be used to route the messages that repeatedly fail to be
+				processed, for example to drop them after a number of attempts.
+				"""
+		}
 	}
 }
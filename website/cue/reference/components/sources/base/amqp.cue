@@ -284,6 +284,29 @@ base: components: sources: amqp: configuration: {
 		required:    false
 		type: string: default: "offset"
 	}
+	on_decode_error: {
+		description: """
+			What to do with the messages that fail to be decoded, and with the messages whose events
+			fail to be delivered when end-to-end acknowledgements are enabled.
+
+			Rejecting the messages without requeueing them lets a [dead letter exchange][dlx] capture
+			them for inspection.
+
+			When unset, the messages that fail to be decoded are acknowledged, and the messages whose
+			events fail to be delivered are rejected without being requeued.
+
+			[dlx]: https://www.rabbitmq.com/docs/dlx
+			"""
+		required: false
+		type: string: enum: {
+			ack:    "Acknowledge the message, removing it from the queue."
+			reject: """
+				Reject the message without requeueing it, dead lettering it if the queue has a dead letter
+				exchange.
+				"""
+			reject_requeue: "Reject the message and requeue it, so it is delivered again."
+		}
+	}
 	queue: {
 		description: "The name of the queue to consume."
 		required:    false
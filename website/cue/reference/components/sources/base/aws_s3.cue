@@ -105,7 +105,76 @@ base: components: sources: aws_s3: configuration: {
 					examples: ["develop"]
 				}
 			}
-			region: {
+			polling: {
+		description: "Configuration options for polling."
+		required:    false
+		type: object: options: {
+			bucket: {
+				description: "The name of the bucket to list the objects from."
+				required:    true
+				type: string: examples: ["my-bucket"]
+			}
+			data_dir: {
+				description: """
+					The directory used to persist the checkpoint of the last processed object.
+
+					By default, the global `data_dir` option is used. Make sure the running user has write
+					permissions to this directory.
+					"""
+				required: false
+				type: string: examples: ["/var/lib/vector"]
+			}
+			delete_object: {
+				description: """
+					Whether to delete the objects once they are processed.
+
+					When acknowledgements are enabled, the objects are only deleted once their events are
+					delivered.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			poll_secs: {
+				description: """
+					How long to wait between the listings of the objects, in seconds.
+
+					Each listing consumes all the new objects before waiting again.
+					"""
+				required: false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			prefix: {
+				description: """
+					The prefix of the keys of the objects to consume.
+
+					The objects are consumed in the lexicographic order of their keys, so they are expected to
+					be written under this prefix in that order, as with keys starting with a timestamp. Objects
+					written with keys sorting before the last processed one are never consumed.
+					"""
+				required: false
+				type: string: {
+					default: ""
+					examples: ["logs/"]
+				}
+			}
+			start_from: {
+				description: """
+					Where to start consuming the objects from.
+
+					Objects last modified before the timestamp are skipped.
+					"""
+				required: false
+				type: string: {
+					default: "beginning"
+					examples: ["beginning", "2024-01-01T00:00:00Z"]
+				}
+			}
+		}
+	}
+	region: {
 				description: """
 					The [AWS region][aws_region] to send STS requests to.
 
@@ -125,8 +194,14 @@ base: components: sources: aws_s3: configuration: {
 		}
 	}
 	compression: {
-		description: "The compression scheme used for decompressing objects retrieved from S3."
-		required:    false
+		description: """
+			The compression scheme used for decompressing objects retrieved from S3.
+
+			Objects made of multiple GZIP members or ZSTD frames are decompressed to their end. A
+			corrupt member ends the object: the events of the members before it are still sent, and
+			the error is reported.
+			"""
+		required: false
 		type: string: {
 			default: "auto"
 			enum: {
@@ -385,20 +460,37 @@ base: components: sources: aws_s3: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			condition: {
+				description: """
+					[Vector Remap Language][vrl] (VRL) boolean expression that is used to determine whether or
+					not more lines should be read.
+
+					The expression is evaluated against each line, which is exposed as `.message`. It can tell
+					lines apart where a regular expression can't, like JSON lines without a `timestamp` key.
+
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition_pattern`.
+
+					[vrl]: https://vector.dev/docs/reference/vrl
+					"""
+				required: false
+				type: string: examples: ["(parse_json(.message) ?? {}).timestamp == null"]
+			}
 			condition_pattern: {
 				description: """
 					Regular expression pattern that is used to determine whether or not more lines should be read.
 
-					This setting must be configured in conjunction with `mode`.
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition`.
 					"""
-				required: true
+				required: false
 				type: string: examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
 			}
 			mode: {
 				description: """
 					Aggregation mode.
 
-					This setting must be configured in conjunction with `condition_pattern`.
+					This setting must be configured in conjunction with `condition_pattern` or `condition`.
 					"""
 				required: true
 				type: string: enum: {
@@ -505,7 +597,28 @@ base: components: sources: aws_s3: configuration: {
 				required:    true
 				type: string: examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
 			}
-			tls_options: {
+			strategy: {
+		description: "The strategy to use to consume objects from S3."
+		required:    false
+		type: string: {
+			default: "sqs"
+			enum: {
+				polling: """
+					Consumes objects by listing the objects under a prefix of the bucket on an interval.
+
+					This doesn't require bucket notifications. The objects are consumed in the lexicographic
+					order of their keys, and the last processed one is checkpointed, so that the objects are
+					not consumed again after a restart.
+					"""
+				sqs: """
+					Consumes objects by processing bucket notification events sent to an [AWS SQS queue][aws_sqs].
+
+					[aws_sqs]: https://aws.amazon.com/sqs/
+					"""
+			}
+		}
+	}
+	tls_options: {
 				description: "TLS configuration."
 				required:    false
 				type: object: options: {
@@ -281,6 +281,10 @@ base: components: sources: aws_sqs: configuration: {
 			Whether to delete the message once it is processed.
 
 			It can be useful to set this to `false` for debugging or during the initial setup.
+
+			For FIFO queues, a message is only deleted once it and all the messages received before it
+			from its message group were processed, so that a message that fails to be processed is
+			received again before the messages following it in its message group.
 			"""
 		required: false
 		type: bool: default: true
@@ -381,6 +385,35 @@ base: components: sources: aws_sqs: configuration: {
 			}
 		}
 	}
+	message_deduplication_id_key: {
+		description: """
+			Overrides the name of the log field used to add the message deduplication ID to each
+			event.
+
+			The value is the `MessageDeduplicationId` of the SQS message, only set for FIFO queues.
+
+			By default, `"message_deduplication_id"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "message_deduplication_id"
+			examples: ["message_deduplication_id"]
+		}
+	}
+	message_group_id_key: {
+		description: """
+			Overrides the name of the log field used to add the message group ID to each event.
+
+			The value is the `MessageGroupId` of the SQS message, only set for FIFO queues.
+
+			By default, `"message_group_id"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "message_group_id"
+			examples: ["message_group_id"]
+		}
+	}
 	poll_secs: {
 		description: """
 			How long to wait while polling the queue for new messages, in seconds.
@@ -408,6 +441,22 @@ base: components: sources: aws_sqs: configuration: {
 		required: false
 		type: string: examples: ["us-east-1"]
 	}
+	receive_count_key: {
+		description: """
+			Overrides the name of the log field used to add the receive count to each event.
+
+			The value is the `ApproximateReceiveCount` of the SQS message, the number of times it was
+			received from the queue, which can be used to route the messages that repeatedly fail to
+			be processed.
+
+			By default, `"receive_count"` is used.
+			"""
+		required: false
+		type: string: {
+			default: "receive_count"
+			examples: ["receive_count"]
+		}
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -36,6 +36,20 @@ base: components: sources: docker_logs: configuration: {
 		required: false
 		type: array: items: type: string: examples: ["exclude_", "exclude_me_0", "ad08cc418cf9"]
 	}
+	exclude_labels: {
+		description: """
+			A list of container object labels to exclude containers by.
+
+			Each label is either a `key`, matching the containers with the label whatever its value, or a
+			`key=value`, matching the containers with the label set to `value`. Containers matching any of
+			the labels are excluded even if they are also included with `include_labels`.
+
+			The labels are evaluated whenever a container starts, so a container recreated with
+			different labels is included or excluded by its new labels.
+			"""
+		required: false
+		type: array: items: type: string: examples: ["logging=verbose", "com.mycorp.internal.debug"]
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to add the current hostname to each event.
@@ -88,20 +102,37 @@ base: components: sources: docker_logs: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			condition: {
+				description: """
+					[Vector Remap Language][vrl] (VRL) boolean expression that is used to determine whether or
+					not more lines should be read.
+
+					The expression is evaluated against each line, which is exposed as `.message`. It can tell
+					lines apart where a regular expression can't, like JSON lines without a `timestamp` key.
+
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition_pattern`.
+
+					[vrl]: https://vector.dev/docs/reference/vrl
+					"""
+				required: false
+				type: string: examples: ["(parse_json(.message) ?? {}).timestamp == null"]
+			}
 			condition_pattern: {
 				description: """
 					Regular expression pattern that is used to determine whether or not more lines should be read.
 
-					This setting must be configured in conjunction with `mode`.
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition`.
 					"""
-				required: true
+				required: false
 				type: string: examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
 			}
 			mode: {
 				description: """
 					Aggregation mode.
 
-					This setting must be configured in conjunction with `condition_pattern`.
+					This setting must be configured in conjunction with `condition_pattern` or `condition`.
 					"""
 				required: true
 				type: string: enum: {
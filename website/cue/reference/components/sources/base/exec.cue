@@ -148,6 +148,18 @@ base: components: sources: exec: configuration: {
 			}
 		}
 	}
+	emit_exit_status: {
+		description: """
+			Whether or not to emit an event with the exit status of the command when it exits.
+
+			The event is emitted after all of the output of the command, and holds the `exit_status`
+			of the command, the `signal` it was killed by, if any, and its execution time in
+			`duration_seconds`. In scheduled mode this records the outcome of every run, and in
+			streaming mode it tells when the command exited.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	environment: {
 		description: """
 			Custom environment variables to set or update when running the command.
@@ -256,8 +268,13 @@ base: components: sources: exec: configuration: {
 		}
 	}
 	include_stderr: {
-		description: "Whether or not the output from stderr should be included when generating events."
-		required:    false
+		description: """
+			Whether or not the output from stderr should be included when generating events.
+
+			The stream the output was read from, `stdout` or `stderr`, is set in the `stream` field of
+			the events.
+			"""
+		required: false
 		type: bool: default: true
 	}
 	maximum_buffer_size_bytes: {
@@ -276,14 +293,47 @@ base: components: sources: exec: configuration: {
 	scheduled: {
 		description: "Configuration options for scheduled commands."
 		required:    false
-		type: object: options: exec_interval_secs: {
-			description: """
-				The interval, in seconds, between scheduled command runs.
-
-				If the command takes longer than `exec_interval_secs` to run, it is killed.
-				"""
-			required: false
-			type: uint: default: 60
+		type: object: options: {
+			exec_interval_secs: {
+				description: """
+					The interval, in seconds, between scheduled command runs.
+
+					If the command takes longer than `exec_interval_secs` to run, it is killed.
+
+					Defaults to 60 seconds, unless `schedule` is set. Can't be set together with `schedule`.
+					"""
+				required: false
+				type: uint: {}
+			}
+			schedule: {
+				description: """
+					Configuration for running scheduled commands at the instants matching a cron expression.
+
+					Unlike with `exec_interval_secs`, the runs don't drift over time. A command still running when
+					the next run is due isn't killed, and the next run is skipped instead.
+					"""
+				required: false
+				type: object: options: {
+					cron: {
+						description: """
+							The cron expression the command runs on.
+
+							Both the standard five fields and six fields, with leading seconds, are supported.
+							"""
+						required: true
+						type: string: examples: ["5 * * * *", "*/30 * * * * *"]
+					}
+					timezone: {
+						description: """
+							The time zone the cron expression is evaluated in.
+
+							Defaults to the global `timezone` option.
+							"""
+						required: false
+						type: string: examples: ["local", "America/New_York", "EST5EDT"]
+					}
+				}
+			}
 		}
 	}
 	streaming: {
@@ -22,6 +22,43 @@ base: components: sources: file: configuration: {
 			type: bool: {}
 		}
 	}
+	archive_to_dir: {
+		description: """
+			The directory to move files to instead of deleting them once `remove_after_secs` elapsed.
+
+			Files keep their path relative to the root within the directory. They are renamed when
+			possible, and otherwise copied and removed, like when the directory is on another
+			filesystem. Files within the directory are never read, even if they match `include`.
+
+			If not specified, files are deleted.
+			"""
+		required: false
+		type: string: examples: ["/var/log/archive"]
+	}
+	compression: {
+		description: """
+			Compression of the files to read.
+
+			Compressed files are read once, from the beginning of the uncompressed data to its end, and
+			are not expected to be appended to. Their checkpoints refer to the offset in the uncompressed
+			data.
+			"""
+		required: false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					Detect the compression of each file from its first bytes.
+
+					Files starting with the gzip or zstd magic bytes are decompressed, all other files are
+					read as-is.
+					"""
+				gzip: "Decompress all files as gzip."
+				none: "Read all files as-is."
+				zstd: "Decompress all files as zstd."
+			}
+		}
+	}
 	data_dir: {
 		description: """
 			The directory used to persist file checkpoint positions.
@@ -85,6 +122,18 @@ base: components: sources: file: configuration: {
 			]
 		}
 	}
+	file_size_key: {
+		description: """
+			Enables adding the file size to each event and sets the name of the log field used.
+
+			The value is the size of the file in bytes once the line was read. It's not added for
+			compressed files.
+
+			Off by default, the file size is only added to the event if this is set.
+			"""
+		required: false
+		type: string: examples: ["file_size"]
+	}
 	fingerprint: {
 		description: """
 			Configuration for how files should be identified.
@@ -135,6 +184,15 @@ base: components: sources: file: configuration: {
 						device_and_inode: """
 															Use the [device and inode][inode] as the identifier.
 
+															Files renamed by a rotation keep their device and inode, so they continue to be read from
+															their checkpoint under the new name. Files truncated in place, like by a copy-truncate
+															rotation, hold a new stream of lines on the same inode, so they are read from the
+															beginning again once they are found to be shorter than the offset read up to. The same
+															check catches an inode reused by a new file, as long as the new file is shorter than the
+															checkpoint of the removed one.
+
+															This strategy can also be configured as `inode`.
+
 															[inode]: https://en.wikipedia.org/wiki/Inode
 															"""
 					}
@@ -255,6 +313,19 @@ base: components: sources: file: configuration: {
 			unit:    "bytes"
 		}
 	}
+	max_read_bytes_per_second: {
+		description: """
+			The maximum number of bytes to read from a single file per second.
+
+			Each file gets its own budget, so that a file being written to very quickly can't starve
+			the reads of the other files. Lines are never split to stay within the budget, so a file
+			may exceed it by up to one line, which is made up for before the file is read again.
+
+			By default, the reads are not rate limited.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
 	multiline: {
 		description: """
 			Multiline aggregation configuration.
@@ -263,20 +334,37 @@ base: components: sources: file: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			condition: {
+				description: """
+					[Vector Remap Language][vrl] (VRL) boolean expression that is used to determine whether or
+					not more lines should be read.
+
+					The expression is evaluated against each line, which is exposed as `.message`. It can tell
+					lines apart where a regular expression can't, like JSON lines without a `timestamp` key.
+
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition_pattern`.
+
+					[vrl]: https://vector.dev/docs/reference/vrl
+					"""
+				required: false
+				type: string: examples: ["(parse_json(.message) ?? {}).timestamp == null"]
+			}
 			condition_pattern: {
 				description: """
 					Regular expression pattern that is used to determine whether or not more lines should be read.
 
-					This setting must be configured in conjunction with `mode`.
+					This setting must be configured in conjunction with `mode`, and can't be configured
+					together with `condition`.
 					"""
-				required: true
+				required: false
 				type: string: examples: ["^[\\s]+", "\\\\$", "^(INFO|ERROR) ", ";$"]
 			}
 			mode: {
 				description: """
 					Aggregation mode.
 
-					This setting must be configured in conjunction with `condition_pattern`.
+					This setting must be configured in conjunction with `condition_pattern` or `condition`.
 					"""
 				required: true
 				type: string: enum: {
@@ -329,7 +417,8 @@ base: components: sources: file: configuration: {
 		description: """
 			Enables adding the file offset to each event and sets the name of the log field used.
 
-			The value is the byte offset of the start of the line within the file.
+			The value is the byte offset of the start of the line within the file. For lines aggregated
+			by `multiline`, it's the offset of their first line.
 
 			Off by default, the offset is only added to the event if this is set.
 			"""
@@ -350,7 +439,13 @@ base: components: sources: file: configuration: {
 			default: "beginning"
 			enum: {
 				beginning: "Read from the beginning of the file."
-				end:       "Start reading from the current end of the file."
+				checkpoint_or_end: """
+					Start reading from the current end of the file on the first startup only.
+
+					Files with a checkpoint resume from it. Once checkpoints were persisted by a previous run,
+					files without a checkpoint are new to Vector, so they are read from the beginning.
+					"""
+				end: "Start reading from the current end of the file."
 			}
 		}
 	}
@@ -60,6 +60,17 @@ base: components: sources: fluent: configuration: {
 			unit: "bytes"
 		}
 	}
+	shared_key: {
+		description: """
+			The key shared with the clients, to authenticate them.
+
+			When set, the clients must perform the handshake of the forward protocol, proving they know
+			the key, before sending any message. This is the `shared_key` of the `<security>` section of
+			the Fluentd configuration.
+			"""
+		required: false
+		type: string: examples: ["${FLUENT_SHARED_KEY}"]
+	}
 	tls: {
 		description: "TlsEnableableConfig for `sources`, adding metadata from the client certificate."
 		required:    false
@@ -160,4 +171,28 @@ base: components: sources: fluent: configuration: {
 			}
 		}
 	}
+	users: {
+		description: """
+			The users allowed to send to the source.
+
+			When set, the clients must also authenticate with the username and password of one of
+			them during the handshake. Requires `shared_key`.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				password: {
+					description: "The password of the user."
+					required:    true
+					type: string: examples: ["${FLUENT_PASSWORD}"]
+				}
+				username: {
+					description: "The name of the user."
+					required:    true
+					type: string: examples: ["fluentd"]
+				}
+			}
+		}
+	}
 }
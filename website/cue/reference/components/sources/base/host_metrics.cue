@@ -68,7 +68,7 @@ base: components: sources: host_metrics: configuration: {
 		description: """
 			The list of host metric collector services to use.
 
-			Defaults to all collectors.
+			Defaults to all collectors, except `process`.
 			"""
 		required: false
 		type: array: {
@@ -87,8 +87,13 @@ base: components: sources: host_metrics: configuration: {
 					load:       "Metrics related to the system load average."
 					memory:     "Metrics related to memory utilization."
 					network:    "Metrics related to network utilization."
+					process: """
+						Metrics related to the resource usage of the processes.
+
+						Only available on Linux, and skipped on the other platforms. Not enabled by default.
+						"""
 				}
-				examples: ["cgroups", "cpu", "disk", "filesystem", "load", "host", "memory", "network"]
+				examples: ["cgroups", "cpu", "disk", "filesystem", "load", "host", "memory", "network", "process"]
 			}
 		}
 	}
@@ -291,6 +296,76 @@ base: components: sources: host_metrics: configuration: {
 			}
 		}
 	}
+	process: {
+		description: """
+			Options for the process metrics collector.
+
+			This collector is only available on Linux, and is skipped on the other platforms.
+			"""
+		required: false
+		type: object: options: {
+			include_cmdline_patterns: {
+				description: """
+					Patterns matched against the command lines of the processes, with the arguments
+					separated by spaces.
+
+					When set, only the processes with a matching command line are reported.
+
+					The patterns are matched using globbing.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["*--config /etc/vector/*"]
+				}
+			}
+			include_process_names: {
+				description: """
+					Patterns matched against the names of the processes.
+
+					When set, only the processes with a matching name are reported. The name is the one the
+					kernel reports, truncated to 15 characters.
+
+					The patterns are matched using globbing.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["vector", "postgres*"]
+				}
+			}
+			pid_tag: {
+				description: """
+					Whether the metrics are tagged with the PID of the process.
+
+					The PIDs change as the processes restart, so disabling the tag bounds the cardinality of
+					the metrics. The metrics of the processes with the same name are then summed.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			top_n: {
+				description: "The number of processes reported when `top_n_by` is set."
+				required:    false
+				type: uint: {
+					default: 10
+					examples: [5]
+				}
+			}
+			top_n_by: {
+				description: """
+					The resource the processes are ranked by, to only report the `top_n` first ones.
+
+					All the included processes are reported when unset.
+					"""
+				required: false
+				type: string: enum: {
+					cpu:    "The CPU time used since the previous scrape."
+					memory: "The resident memory."
+				}
+			}
+		}
+	}
 	scrape_interval_secs: {
 		description: "The interval between metric gathering, in seconds."
 		required:    false
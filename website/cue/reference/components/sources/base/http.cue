@@ -32,9 +32,51 @@ base: components: sources: http: configuration: {
 		type: string: examples: ["0.0.0.0:80", "localhost:80"]
 	}
 	auth: {
-		description: "HTTP Basic authentication configuration."
+		description: "HTTP authentication configuration."
 		required:    false
 		type: object: options: {
+			hmac: {
+				description: """
+					HMAC signature verification configuration.
+
+					The signature is computed over the raw body of the request, before it is decompressed or
+					decoded, and is sent hex encoded in a header, like the `X-Hub-Signature-256` header of GitHub
+					webhooks.
+					"""
+				required: true
+				type: object: options: {
+					algorithm: {
+						description: "The hash algorithm of an HMAC signature."
+						required:    false
+						type: string: {
+							default: "sha256"
+							enum: {
+								sha1:   "SHA-1."
+								sha256: "SHA-256."
+								sha512: "SHA-512."
+							}
+						}
+					}
+					header: {
+						description: "The name of the header holding the signature."
+						required:    true
+						type: string: examples: ["X-Hub-Signature-256"]
+					}
+					prefix: {
+						description: """
+							The prefix of the signature in the header, which is stripped before the signature is
+							verified.
+							"""
+						required: false
+						type: string: examples: ["sha256="]
+					}
+					secret: {
+						description: "The secret the signature is computed with."
+						required:    true
+						type: string: examples: ["${WEBHOOK_SECRET}", "SECRET[secret_backend.webhook_secret]"]
+					}
+				}
+			}
 			password: {
 				description: "The password for basic authentication."
 				required:    true
@@ -234,7 +276,21 @@ base: components: sources: http: configuration: {
 					}
 				}
 			}
-			method: {
+			max_decompressed_size: {
+		description: """
+			The maximum size, in bytes, of a request body after decompressing it according to its
+			`Content-Encoding` header.
+
+			Requests whose decompressed body exceeds this size are rejected with a `413 Payload Too
+			Large` response. By default, the size of decompressed bodies is not limited.
+			"""
+		required: false
+		type: uint: {
+			examples: [10485760]
+			unit: "bytes"
+		}
+	}
+	method: {
 				description: "The framing method."
 				required:    true
 				type: string: enum: {
@@ -311,11 +367,17 @@ base: components: sources: http: configuration: {
 		}
 	}
 	path: {
-		description: "The URL path on which log event POST requests are sent."
-		required:    false
+		description: """
+			The URL path on which log event POST requests are sent.
+
+			Segments of the form `{name}` match any single path segment, which is included in the log
+			event in the `name` field. Requests to paths that don't match are rejected with a `404
+			Not Found` response.
+			"""
+		required: false
 		type: string: {
 			default: "/"
-			examples: ["/event/path", "/logs"]
+			examples: ["/event/path", "/logs", "/ingest/{tenant}/{dataset}"]
 		}
 	}
 	path_key: {
@@ -331,15 +393,58 @@ base: components: sources: http: configuration: {
 			A list of URL query parameters to include in the log event.
 
 			These override any values included in the body with conflicting names.
+
+			The `*` entry includes all of the query parameters of the request as a map, in the
+			`query_parameters_key` field.
 			"""
 		required: false
 		type: array: {
 			default: []
-			items: type: string: examples: ["application", "source"]
+			items: type: string: examples: ["application", "source", "*"]
+		}
+	}
+	query_parameters_key: {
+		description: """
+			The event key in which all of the query parameters of the request are stored, when
+			`query_parameters` contains `*`.
+			"""
+		required: false
+		type: string: {
+			default: "query_parameters"
+			examples: ["query_parameters"]
+		}
+	}
+	response: {
+		description: "The response returned to successful requests."
+		required:    false
+		type: object: options: {
+			body: {
+				description: """
+					The body returned with successful requests.
+
+					By default, the response body is empty. It can't be set together with a status code that
+					doesn't allow a body, such as `204`.
+					"""
+				required: false
+				type: string: examples: ["{\"status\":\"ok\"}"]
+			}
+			status_code: {
+				description: """
+					The HTTP status code returned on successful requests.
+
+					Overrides `response_code` when set.
+					"""
+				required: false
+				type: uint: examples: [204]
+			}
 		}
 	}
 	response_code: {
-		description: "Specifies the HTTP response status code that will be returned on successful requests."
+		description: """
+			Specifies the HTTP response status code that will be returned on successful requests.
+
+			If `response.status_code` is set, it takes precedence over this option.
+			"""
 		required:    false
 		type: uint: {
 			default: 200
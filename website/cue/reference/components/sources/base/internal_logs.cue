@@ -1,6 +1,18 @@
 package metadata
 
 base: components: sources: internal_logs: configuration: {
+	exclude_components: {
+		description: """
+			The IDs of the components whose internal logs are dropped.
+
+			Matched against the `vector.component_id` field of the internal logs.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["my_sink"]
+		}
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to add the current hostname to each event.
@@ -14,6 +26,25 @@ base: components: sources: internal_logs: configuration: {
 		required: false
 		type: string: default: "host"
 	}
+	min_level: {
+		description: """
+			The minimum level of the internal logs to forward.
+
+			The internal logs below this level are dropped by the source. Only the internal logs
+			enabled for Vector itself, as with `VECTOR_LOG`, can be forwarded.
+			"""
+		required: false
+		type: string: {
+			default: "trace"
+			enum: {
+				debug: "The `DEBUG` level, and above."
+				error: "The `ERROR` level."
+				info:  "The `INFO` level, and above."
+				trace: "The `TRACE` level, and above."
+				warn:  "The `WARN` level, and above."
+			}
+		}
+	}
 	pid_key: {
 		description: """
 			Overrides the name of the log field used to add the current process ID to each event.
@@ -25,4 +56,17 @@ base: components: sources: internal_logs: configuration: {
 		required: false
 		type: string: default: "pid"
 	}
+	rate_limit_per_component: {
+		description: """
+			The maximum number of internal logs forwarded per second for each component.
+
+			Once a component goes over the limit, the rest of its internal logs are dropped until the
+			second ends, and an event reporting how many of them were suppressed is sent then. The
+			internal logs emitted outside of any component share a single limit.
+
+			By default, the internal logs aren't rate limited.
+			"""
+		required: false
+		type: uint: unit: "events"
+	}
 }
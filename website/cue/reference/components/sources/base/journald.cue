@@ -125,6 +125,57 @@ base: components: sources: journald: configuration: {
 			}
 		}
 	}
+	include_priority: {
+		description: """
+			The priorities of the journal entries to include.
+
+			Either the name of the least severe priority to include, such as `warning` to include
+			entries at `warning` or worse, or an explicit list of priority names.
+
+			This is passed to `journalctl`, so entries are filtered before they are read by Vector.
+			If not present, all priorities are accepted.
+			"""
+		required: false
+		type: {
+			array: items: type: string: enum: {
+				alert:   "Action must be taken immediately."
+				crit:    "Critical conditions."
+				debug:   "Debug-level messages."
+				emerg:   "System is unusable."
+				err:     "Error conditions."
+				info:    "Informational messages."
+				notice:  "Normal but significant conditions."
+				warning: "Warning conditions."
+			}
+			string: {
+				enum: {
+					alert:   "Action must be taken immediately."
+					crit:    "Critical conditions."
+					debug:   "Debug-level messages."
+					emerg:   "System is unusable."
+					err:     "Error conditions."
+					info:    "Informational messages."
+					notice:  "Normal but significant conditions."
+					warning: "Warning conditions."
+				}
+				examples: ["warning"]
+			}
+		}
+	}
+	include_syslog_identifiers: {
+		description: """
+			A list of syslog identifiers to monitor.
+
+			If empty or not present, all syslog identifiers are accepted.
+
+			This is passed to `journalctl`, so entries are filtered before they are read by Vector.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["sshd", "kernel"]
+		}
+	}
 	include_units: {
 		description: """
 			A list of unit names to monitor.
@@ -181,9 +232,108 @@ base: components: sources: journald: configuration: {
 		required: false
 		type: bool: default: false
 	}
+	remote_url: {
+		description: """
+			The URL of a [`systemd-journal-gatewayd`][gatewayd] instance to read the journal from.
+
+			If set, the journal entries are streamed from the `/entries` endpoint of the gateway in the
+			journal export format, instead of being read from a local `journalctl`. The options that
+			only apply to `journalctl`, such as `journalctl_path`, `journal_directory`,
+			`journal_namespace`, `extra_args` and `since_now`, can't be used together with this option.
+
+			[gatewayd]: https://www.freedesktop.org/software/systemd/man/systemd-journal-gatewayd.service.html
+			"""
+		required: false
+		type: string: examples: ["https://journal.example.com:19531"]
+	}
 	since_now: {
 		description: "Only include entries that appended to the journal after the entries have been read."
 		required:    false
 		type: bool: default: false
 	}
+	tls: {
+		description: "TLS configuration."
+		required:    false
+		type: object: options: {
+			alpn_protocols: {
+				description: """
+					Sets the list of supported ALPN protocols.
+
+					Declare the supported ALPN protocols, which are used during negotiation with peer. They are prioritized in the order
+					that they are defined.
+					"""
+				required: false
+				type: array: items: type: string: examples: ["h2"]
+			}
+			ca_file: {
+				description: """
+					Absolute path to an additional CA certificate file.
+
+					The certificate must be in the DER or PEM (X.509) format. Additionally, the certificate can be provided as an inline string in PEM format.
+					"""
+				required: false
+				type: string: examples: ["/path/to/certificate_authority.crt"]
+			}
+			crt_file: {
+				description: """
+					Absolute path to a certificate file used to identify this server.
+
+					The certificate must be in DER, PEM (X.509), or PKCS#12 format. Additionally, the certificate can be provided as
+					an inline string in PEM format.
+
+					If this is set, and is not a PKCS#12 archive, `key_file` must also be set.
+					"""
+				required: false
+				type: string: examples: ["/path/to/host_certificate.crt"]
+			}
+			key_file: {
+				description: """
+					Absolute path to a private key file used to identify this server.
+
+					The key must be in DER or PEM (PKCS#8) format. Additionally, the key can be provided as an inline string in PEM format.
+					"""
+				required: false
+				type: string: examples: ["/path/to/host_certificate.key"]
+			}
+			key_pass: {
+				description: """
+					Passphrase used to unlock the encrypted key file.
+
+					This has no effect unless `key_file` is set.
+					"""
+				required: false
+				type: string: examples: ["${KEY_PASS_ENV_VAR}", "PassWord1"]
+			}
+			verify_certificate: {
+				description: """
+					Enables certificate verification.
+
+					If enabled, certificates must not be expired and must be issued by a trusted
+					issuer. This verification operates in a hierarchical manner, checking that the leaf certificate (the
+					certificate presented by the client/server) is not only valid, but that the issuer of that certificate is also valid, and
+					so on until the verification process reaches a root certificate.
+
+					Relevant for both incoming and outgoing connections.
+
+					Do NOT set this to `false` unless you understand the risks of not verifying the validity of certificates.
+					"""
+				required: false
+				type: bool: {}
+			}
+			verify_hostname: {
+				description: """
+					Enables hostname verification.
+
+					If enabled, the hostname used to connect to the remote host must be present in the TLS certificate presented by
+					the remote host, either as the Common Name or as an entry in the Subject Alternative Name extension.
+
+					Only relevant for outgoing connections.
+
+					Do NOT set this to `false` unless you understand the risks of not verifying the remote hostname.
+					"""
+				required: false
+				type: bool: {}
+			}
+		}
+	}
 }
@@ -312,6 +312,20 @@ base: components: sources: kafka: configuration: {
 		required:    true
 		type: string: examples: ["consumer-group-name"]
 	}
+	group_instance_id: {
+		description: """
+			The static membership identifier of this consumer in the consumer group.
+
+			With static membership, the consumer group does not rebalance when the consumer leaves
+			and rejoins the group within `session_timeout_ms`, such as during a restart. The
+			identifier must be unique within the consumer group. Environment variables such as
+			`${HOSTNAME}` are interpolated, so it can be derived from the host Vector runs on.
+
+			Cannot be used together with `partitions`.
+			"""
+		required: false
+		type: string: examples: ["vector-${HOSTNAME}"]
+	}
 	headers_key: {
 		description: """
 			Overrides the name of the log field used to add the headers to each event.
@@ -326,6 +340,19 @@ base: components: sources: kafka: configuration: {
 			examples: ["headers"]
 		}
 	}
+	include_headers: {
+		description: """
+			A list of header names to add to each event.
+
+			If empty or not present, all headers are added. Header values that are valid UTF-8 are
+			added as strings, and the others are base64 encoded.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["tenant_id", "trace_id"]
+		}
+	}
 	key_field: {
 		description: """
 			Overrides the name of the log field used to add the message key to each event.
@@ -360,6 +387,22 @@ base: components: sources: kafka: configuration: {
 			}
 		}
 	}
+	max_in_flight_events: {
+		description: """
+			The maximum number of events consumed from Kafka that have not been acknowledged yet.
+
+			Once exceeded, fetching the partitions is paused at the broker until enough of the events
+			are acknowledged, instead of the fetched messages piling up in memory while the downstream
+			components are slow. Offsets are still only committed once the events are delivered.
+
+			Events are acknowledged as soon as they are sent to the downstream components, unless
+			end-to-end acknowledgements are enabled.
+
+			If not set, fetching is never paused.
+			"""
+		required: false
+		type: uint: examples: [10000]
+	}
 	metrics: {
 		description: "Metrics (beta) configuration."
 		required:    false
@@ -399,6 +442,26 @@ base: components: sources: kafka: configuration: {
 			examples: ["partition"]
 		}
 	}
+	partitions: {
+		description: """
+			The partitions to consume from each of the topics, bypassing the consumer group protocol.
+
+			When set, the partitions are assigned to this consumer directly instead of being
+			distributed among the members of the consumer group, so no rebalances take place.
+			Offsets are still committed for `group_id`, so consuming resumes where it left off
+			after a restart.
+
+			Partitions that do not exist yet, and partitions added to the topics while Vector is
+			running, are only consumed after the configuration is reloaded.
+
+			Cannot be used together with `group_instance_id` or with regular expression topics.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: int: examples: [0, 1]
+		}
+	}
 	sasl: {
 		description: "Configuration for SASL authentication when interacting with Kafka."
 		required:    false
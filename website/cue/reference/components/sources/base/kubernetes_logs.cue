@@ -11,6 +11,39 @@ base: components: sources: kubernetes_logs: configuration: {
 		required: false
 		type: bool: default: true
 	}
+	close_terminated_after_secs: {
+		description: """
+			Stop reading the log files of terminated containers after the specified number of seconds.
+
+			The kubelet keeps the log file of a terminated container around, so it is otherwise
+			watched until the Pod is removed, even though it never grows. Once the container has been
+			terminated for this long, according to its status in the Kubernetes API, its log file is
+			read to the end and closed. A restarted container writes to a new log file, which is read
+			as usual.
+
+			The log files are matched to the containers by the restart count in their name, so this
+			only applies to the `pods` log layout.
+			"""
+		required: false
+		type: uint: {
+			examples: [
+				300,
+			]
+			unit: "seconds"
+		}
+	}
+	container_logs_root: {
+		description: """
+			The directory where the kubelet keeps the legacy symlinks to the container log files.
+
+			Only used with the `containers` and `auto` log layouts.
+			"""
+		required: false
+		type: string: {
+			default: "/var/log/containers"
+			examples: ["/data/kubelet/log/containers"]
+		}
+	}
 	data_dir: {
 		description: """
 			The directory used to persist file checkpoint positions.
@@ -29,6 +62,9 @@ base: components: sources: kubernetes_logs: configuration: {
 			A longer delay allows for continued enrichment of logs after the originating Pod is
 			removed. If relevant metadata has been removed, the log is forwarded un-enriched and a
 			warning is emitted.
+
+			The metadata of a deleted Pod is kept by UID, so its logs are still enriched with it even
+			once a Pod was recreated under the same name.
 			"""
 		required: false
 		type: uint: {
@@ -36,6 +72,43 @@ base: components: sources: kubernetes_logs: configuration: {
 			unit:    "milliseconds"
 		}
 	}
+	emit_file_discovered_events: {
+		description: """
+			Emit an event when a Pod log file is first discovered.
+
+			The event has `kubernetes.event` set to `file_discovered`, and carries the Pod and
+			container information extracted from the path of the file, before any line of the file is
+			read. It goes through the same pipeline as the log lines, but isn't annotated with the Pod
+			metadata. A file is only discovered again once it was removed and recreated.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	exclude_container_names: {
+		description: """
+			A list of glob patterns to match the names of the containers to not read the logs of.
+
+			Takes precedence over `include_container_names`, so a container that matches both is
+			excluded.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["istio-proxy"]
+		}
+	}
+	exclude_namespaces: {
+		description: """
+			A list of glob patterns to match the namespaces to not read the logs of the Pods of.
+
+			Takes precedence over `include_namespaces`, so a namespace that matches both is excluded.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["kube-system"]
+		}
+	}
 	exclude_paths_glob_patterns: {
 		description: "A list of glob patterns to exclude from reading the files."
 		required:    false
@@ -75,6 +148,22 @@ base: components: sources: kubernetes_logs: configuration: {
 			examples: ["my_custom_label!=my_value", "my_custom_label!=my_value,my_other_custom_label=my_value"]
 		}
 	}
+	extra_log_dirs: {
+		description: """
+			Additional directories to discover log files in, besides the ones of the Pods on the Node.
+
+			The files matching `<dir>/**/*.log` are read, but not their rotations, and rescanned as
+			often as the Pod log directories are, see `glob_minimum_cooldown_ms`. The files whose paths end with the
+			`<namespace>_<name>_<uid>/<container>/<n>.log` components of the Pod log files are
+			enriched with the metadata of their Pod, and are subject to the namespace and container
+			filters. The rest are read without any Pod metadata.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["/mnt/pod-logs"]
+		}
+	}
 	extra_namespace_label_selector: {
 		description: """
 			Specifies the [label selector][label_selector] to filter [Namespaces][namespaces] with, to
@@ -90,6 +179,66 @@ base: components: sources: kubernetes_logs: configuration: {
 			examples: ["my_custom_label!=my_value", "my_custom_label!=my_value,my_other_custom_label=my_value"]
 		}
 	}
+	extract_stream: {
+		description: """
+			Whether or not to insert the stream the line was written to, `stdout` or `stderr`, into
+			the `kubernetes.stream` field.
+
+			The stream is taken from the line for the CRI format and from the `stream` field for the
+			Docker format, so the field is set the same way for both, before the events are merged
+			and sent on.
+			"""
+		required: false
+		type: bool: default: true
+	}
+	fingerprint: {
+		description: """
+			Configuration for how the log files are identified.
+
+			This is important for checkpointing when the log files are rotated.
+			"""
+		required: false
+		type: object: options: {
+			bytes: {
+				description: """
+					The number of bytes from the beginning of the log file to compute the checksum over.
+
+					Log files shorter than this aren't read until they grow to this size.
+					"""
+				relevant_when: "strategy = \"k8s_identity\""
+				required:      false
+				type: uint: {
+					default: 256
+					unit:    "bytes"
+				}
+			}
+			strategy: {
+				description: """
+					The strategy used to identify the log files.
+
+					This is important for checkpointing when the log files are rotated.
+					"""
+				required: false
+				type: string: {
+					default: "checksum"
+					enum: {
+						checksum: "Compute a checksum over the first `fingerprint_lines` lines of the log file."
+						k8s_identity: """
+															Combine the identity of the container the log file belongs to with a checksum of the
+															first bytes of the file.
+
+															The identity is made of the pod UID, the container name and the restart count parsed from
+															the path of the log file. Files rotated by a rename keep both, so they continue to be read
+															from their checkpoint. Files truncated in place by the kubelet hold new first bytes, so
+															they are read from the beginning as a new file, and the rest of the old stream is
+															discarded. Files whose path doesn't carry the identity are identified by the checksum
+															alone.
+															"""
+					}
+				}
+			}
+		}
+	}
 	fingerprint_lines: {
 		description: """
 			The number of lines to read for generating the checksum.
@@ -97,6 +246,8 @@ base: components: sources: kubernetes_logs: configuration: {
 			If your files share a common header that is not always a fixed size,
 
 			If the file has less than this amount of lines, it won’t be read at all.
+
+			Only used by the `checksum` fingerprinting strategy.
 			"""
 		required: false
 		type: uint: {
@@ -104,6 +255,23 @@ base: components: sources: kubernetes_logs: configuration: {
 			unit:    "lines"
 		}
 	}
+	format: {
+		description: "The format of the container log files."
+		required:    false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					Detect the format of each file from its first non-empty line.
+
+					Files whose first line is a JSON object with the `log`, `stream`, and `time` keys are
+					parsed in the Docker format, all other files are parsed in the CRI format.
+					"""
+				cri:    "Parse all files in the CRI format."
+				docker: "Parse all files in the Docker `json-file` format."
+			}
+		}
+	}
 	glob_minimum_cooldown_ms: {
 		description: """
 			The interval at which the file system is polled to identify new files to read from.
@@ -129,6 +297,55 @@ base: components: sources: kubernetes_logs: configuration: {
 			unit: "seconds"
 		}
 	}
+	include_container_names: {
+		description: """
+			A list of glob patterns to match the names of the containers to read the logs of.
+
+			If empty, the logs of all containers are read. The patterns support the `*` and `?`
+			wildcards, and are case-sensitive.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["app-*"]
+		}
+	}
+	include_namespaces: {
+		description: """
+			A list of glob patterns to match the namespaces to read the logs of the Pods of.
+
+			If empty, the logs of the Pods of all namespaces are read. The patterns support the `*` and
+			`?` wildcards, and are case-sensitive.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["team-*"]
+		}
+	}
+	include_pod_phases: {
+		description: """
+			A list of the phases of the Pods to read the logs of.
+
+			If empty, the logs of the Pods in all phases are read. The log files of the Pods in the
+			other phases are skipped, like the ones of the completed Jobs with `running` and `failed`
+			only. The logs of the Pods whose phase isn't known yet are read.
+
+			The phase is checked as the log files are discovered, so a file already being read when
+			its Pod changes to an excluded phase is read to its end.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: enum: {
+				failed:    "All the containers of the Pod terminated, and at least one of them failed."
+				pending:   "The Pod is accepted, but not all of its containers are running yet."
+				running:   "The Pod is bound to the Node, and at least one of its containers is running."
+				succeeded: "All the containers of the Pod terminated successfully, and won't be restarted."
+				unknown:   "The state of the Pod couldn't be obtained from its Node."
+			}
+		}
+	}
 	ingestion_timestamp_field: {
 		description: """
 			Overrides the name of the log field used to add the ingestion timestamp to each event.
@@ -141,19 +358,47 @@ base: components: sources: kubernetes_logs: configuration: {
 		type: string: examples: [".ingest_timestamp", "ingest_ts"]
 	}
 	internal_metrics: {
-		description: "Configuration of internal metrics for file-based components."
+		description: "Configuration of internal metrics for the `kubernetes_logs` source."
 		required:    false
-		type: object: options: include_file_tag: {
-			description: """
-				Whether or not to include the "file" tag on the component's corresponding internal metrics.
+		type: object: options: {
+			include_file_tag: {
+				description: """
+					Whether or not to include the "file" tag on the component's corresponding internal metrics.
 
-				This is useful for distinguishing between different files while monitoring. However, the tag's
-				cardinality is unbounded.
-				"""
-			required: false
-			type: bool: default: false
+					This is useful for distinguishing between different files while monitoring. However, the tag's
+					cardinality is unbounded.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			include_pod_tags: {
+				description: """
+					Whether or not to include the "pod_namespace", "pod_name", and "container_name" tags on the
+					`k8s_logs_read_bytes_total` and `k8s_logs_read_lines_total` internal metrics.
+
+					This is useful for finding the Pods that produce the most logs. The tags are dropped while
+					more than `max_tagged_pods` Pods are active on the Node, to bound their cardinality.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			max_tagged_pods: {
+				description: "The maximum number of active Pods on the Node to include the Pod tags for."
+				required:    false
+				type: uint: default: 250
+			}
 		}
 	}
+	kube_config_context: {
+		description: """
+			The name of the context in the `kube_config_file` to connect with.
+
+			If not set, the current context of the kubeconfig file is used. Only used when
+			`kube_config_file` is set.
+			"""
+		required: false
+		type: string: examples: ["dev-cluster"]
+	}
 	kube_config_file: {
 		description: """
 			Optional path to a readable [kubeconfig][kubeconfig] file.
@@ -165,11 +410,93 @@ base: components: sources: kubernetes_logs: configuration: {
 		required: false
 		type: string: examples: ["/path/to/.kube/config"]
 	}
+	kubelet_metadata: {
+		description: """
+			Configuration for the kubelet metadata fallback.
+
+			When enabled, the local kubelet is queried for Pod metadata while the watch stream against
+			the API server is disconnected. The requests use the same credentials as the API server
+			connection, so the service account needs access to the `nodes/proxy` resource.
+			"""
+		required: false
+		type: object: options: {
+			enabled: {
+				description: "Whether to fall back to the kubelet for Pod metadata."
+				required:    false
+				type: bool: default: false
+			}
+			endpoint: {
+				description: """
+					The URL of the kubelet to query.
+
+					If not set, the kubelet is reached at port 10250 of the Node named by `self_node_name`.
+					"""
+				required: false
+				type: string: examples: ["https://10.0.0.1:10250"]
+			}
+			refresh_interval_secs: {
+				description: "How often the kubelet is polled while the watch stream is disconnected."
+				required:    false
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+			}
+			verify_certificate: {
+				description: """
+					Whether to verify the TLS certificate served by the kubelet.
+
+					Kubelets commonly serve a self-signed certificate, which can't be verified against the
+					cluster CA.
+					"""
+				required: false
+				type: bool: default: true
+			}
+		}
+	}
+	log_layout: {
+		description: "The layout the log files are discovered in."
+		required:    false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					The `pods` layout is used for the Pods that have log files in it, and the `containers`
+					layout for the rest.
+					"""
+				containers: """
+					The legacy `<container_logs_root>/<name>_<namespace>_<container>-<id>.log`
+					layout of symlinks to the log files.
+					"""
+				pods: """
+					The `<pod_logs_root>/<namespace>_<name>_<uid>/<container>/<n>.log`
+					layout of the log files managed by the kubelet.
+					"""
+			}
+		}
+	}
+	max_delayed_deletions: {
+		description: """
+			The maximum number of deleted objects of each kind to keep the metadata of for
+			`delay_deletion_ms`.
+
+			Once more objects are deleted within the delay, like when a Node is drained, the metadata
+			of the ones deleted first is removed early, so the memory usage stays bounded.
+			"""
+		required: false
+		type: uint: {
+			default: 1000
+			unit:    "objects"
+		}
+	}
 	max_line_bytes: {
 		description: """
-			The maximum number of bytes a line can contain before being discarded.
+			The maximum number of bytes a line can contain before being truncated.
 
-			This protects against malformed lines or tailing incorrect files.
+			This protects against malformed lines or tailing incorrect files. The first
+			`max_line_bytes` bytes of a longer line are emitted with `kubernetes.truncated` set to
+			`true`, and the rest of the line is skipped. If the line is a partial event, the
+			remaining partial events of the same message are skipped as well.
 			"""
 		required: false
 		type: uint: {
@@ -177,6 +504,17 @@ base: components: sources: kubernetes_logs: configuration: {
 			unit:    "bytes"
 		}
 	}
+	max_merged_line_bytes: {
+		description: """
+			The maximum number of bytes a merged partial event can contain.
+
+			When merging the next partial event would exceed this, the accumulated message is emitted
+			as its own event, with the `truncated` metadata flag set to `true`, and merging starts
+			over with the next partial event. By default, merged partial events are unlimited in size.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
 	max_read_bytes: {
 		description: """
 			Max amount of bytes to read from a single file before switching over to the next file.
@@ -191,6 +529,78 @@ base: components: sources: kubernetes_logs: configuration: {
 			unit:    "bytes"
 		}
 	}
+	max_read_bytes_per_file: {
+		description: """
+			Max amount of bytes to read from each file per read cycle, scheduling the reads fairly
+			across the files.
+
+			On every cycle, each file is read up to this many bytes, and the next cycle starts from the
+			file after the one the previous cycle started from. This keeps a few chatty containers from
+			holding back the logs of the other containers of the node. The lines are never split, so
+			reading a file stops at the end of the line crossing this limit, and the rest of the file
+			is read on the next cycles. When set, this takes precedence over `max_read_bytes` and
+			`oldest_first`.
+			"""
+		required: false
+		type: uint: {
+			examples: [
+				16384,
+			]
+			unit: "bytes"
+		}
+	}
+	metadata: {
+		description: "Configuration for loading the Kubernetes metadata, and annotating the events with it."
+		required:    false
+		type: object: options: {
+			flatten: {
+				description: """
+					Whether to add the Pod labels and annotations as single dotted fields.
+
+					The `app.kubernetes.io/name` label is then added as the `pod_labels.app.kubernetes.io/name`
+					field, next to the other Pod fields, rather than as the `app.kubernetes.io/name` field of
+					the `pod_labels` object.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			include_annotation_keys: {
+				description: """
+					A list of the keys of the Pod annotations to annotate the events with.
+
+					If not set, all the Pod annotations are added to the events. If empty, none of them are.
+					Limiting them keeps the number of distinct fields of the events down.
+					"""
+				required: false
+				type: array: items: type: string: examples: ["app.kubernetes.io/owner"]
+			}
+			include_label_keys: {
+				description: """
+					A list of the keys of the Pod labels to annotate the events with.
+
+					If not set, all the Pod labels are added to the events. If empty, none of them are.
+					Limiting them keeps the number of distinct fields of the events down.
+					"""
+				required: false
+				type: array: items: type: string: examples: ["app.kubernetes.io/name"]
+			}
+			warmup_timeout_ms: {
+				description: """
+					The maximum time to hold the events back at startup, until the Pods, Namespaces and Node
+					are listed.
+
+					The events are held back until the lists are complete, or this timeout elapses, whichever
+					comes first, so that the first events read from every Pod are annotated. Set to `0` to
+					send the events right away, even if the metadata isn't loaded yet.
+					"""
+				required: false
+				type: uint: {
+					default: 10000
+					unit:    "milliseconds"
+				}
+			}
+		}
+	}
 	namespace_annotation_fields: {
 		description: "Configuration for how the events are enriched with Namespace metadata."
 		required:    false
@@ -210,16 +620,69 @@ base: components: sources: kubernetes_logs: configuration: {
 	node_annotation_fields: {
 		description: "Configuration for how the events are enriched with Node metadata."
 		required:    false
-		type: object: options: node_labels: {
-			description: """
-				Event field for the Node's labels.
+		type: object: options: {
+			node_allocatable_cpu: {
+				description: """
+					Event field for the Node's allocatable CPU.
 
-				Set to `""` to suppress this key.
-				"""
-			required: false
-			type: string: {
-				default: ".kubernetes.node_labels"
-				examples: [".k8s.node_labels", "k8s.node_labels", ""]
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.node_allocatable_cpu"
+					examples: [".k8s.node_allocatable_cpu", "k8s.node_allocatable_cpu", ""]
+				}
+			}
+			node_allocatable_memory: {
+				description: """
+					Event field for the Node's allocatable memory.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.node_allocatable_memory"
+					examples: [".k8s.node_allocatable_memory", "k8s.node_allocatable_memory", ""]
+				}
+			}
+			node_kubelet_version: {
+				description: """
+					Event field for the version of the kubelet running on the Node.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.node_kubelet_version"
+					examples: [".k8s.node_kubelet_version", "k8s.node_kubelet_version", ""]
+				}
+			}
+			node_labels: {
+				description: """
+					Event field for the Node's labels.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.node_labels"
+					examples: [".k8s.node_labels", "k8s.node_labels", ""]
+				}
+			}
+			node_name: {
+				description: """
+					Event field for the name of the Node the event was collected on.
+
+					The name is the one Vector runs on, as configured by `self_node_name`, so it is added to
+					every event, even if the Node metadata isn't available.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.node_name"
+					examples: [".k8s.node_name", "k8s.node_name", ""]
+				}
 			}
 		}
 	}
@@ -248,6 +711,9 @@ base: components: sources: kubernetes_logs: configuration: {
 				description: """
 					Event field for the Container's image.
 
+					The image the Pod status reports the Container to run is used, or the image of the Container
+					in the Pod spec until the status reports one.
+
 					Set to `""` to suppress this key.
 					"""
 				required: false
@@ -260,6 +726,9 @@ base: components: sources: kubernetes_logs: configuration: {
 				description: """
 					Event field for the Container's image ID.
 
+					This is the digest of the image the Pod status reports the Container to run. It isn't set
+					until the status reports one, like while the image is still being pulled.
+
 					Set to `""` to suppress this key.
 					"""
 				required: false
@@ -268,6 +737,21 @@ base: components: sources: kubernetes_logs: configuration: {
 					examples: [".k8s.container_image_id", "k8s.container_image_id", ""]
 				}
 			}
+			container_kind: {
+				description: """
+					Event field for the Container's kind, one of `init`, `ephemeral`, or `regular`.
+
+					The kind is taken from the Pod spec, and the field is omitted if the Pod metadata isn't
+					available.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.container_kind"
+					examples: [".k8s.container_kind", "k8s.container_kind", ""]
+				}
+			}
 			container_name: {
 				description: """
 					Event field for the Container's name.
@@ -280,6 +764,21 @@ base: components: sources: kubernetes_logs: configuration: {
 					examples: [".k8s.container_name", "k8s.container_name", ""]
 				}
 			}
+			container_restart_count: {
+				description: """
+					Event field for the Container's restart count.
+
+					The restart count is taken from the log file name, and the field is omitted if the file
+					name doesn't carry one.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.container_restart_count"
+					examples: [".k8s.container_restart_count", "k8s.container_restart_count", ""]
+				}
+			}
 			pod_annotations: {
 				description: """
 					Event field for the Pod's annotations.
@@ -292,6 +791,23 @@ base: components: sources: kubernetes_logs: configuration: {
 					examples: [".k8s.pod_annotations", "k8s.pod_annotations", ""]
 				}
 			}
+			pod_dir_extra_segments: {
+				description: """
+					Event field for the segments following the Pod UID in the name of the Pod logs directory.
+
+					Some distributions append extra segments to the name of the Pod logs directory. They are
+					only told apart from the Pod name when the Pod UID is well-formed, and set in order as an
+					array of strings, to be mapped onto other fields with `remap`. The field is omitted if
+					there are none.
+
+					Set to `""` to suppress this key.
+					"""
+				required: false
+				type: string: {
+					default: ".kubernetes.pod_dir_extra_segments"
+					examples: [".k8s.pod_dir_extra_segments", "k8s.pod_dir_extra_segments", ""]
+				}
+			}
 			pod_ip: {
 				description: """
 					Event field for the Pod's IPv4 address.
@@ -390,6 +906,39 @@ base: components: sources: kubernetes_logs: configuration: {
 			}
 		}
 	}
+	pod_dir_delimiter: {
+		description: """
+			The delimiter between the namespace, the name and the UID of the Pod in the names of the Pod
+			log directories.
+
+			Change this if the Pod log directories are named like `<namespace>-<name>-<uid>` by a
+			customized kubelet. The delimiter may appear in the Pod names, but neither in the
+			namespaces nor in the UIDs, so a `-` delimiter only works if those are guaranteed not to
+			contain one.
+
+			Only used with the `pods` and `auto` log layouts.
+			"""
+		required: false
+		type: string: {
+			default: "_"
+			examples: ["-"]
+		}
+	}
+	pod_logs_root: {
+		description: """
+			The root directory where the kubelet keeps the Pod log files.
+
+			Change this if the kubelet is configured with a custom `--root-dir`, or the Pod log
+			directory is otherwise relocated on the host, like to another volume on Windows Nodes.
+
+			Defaults to `C:\\var\\log\\pods` on Windows.
+			"""
+		required: false
+		type: string: {
+			default: "/var/log/pods"
+			examples: ["/data/kubelet/log/pods", "D:\\var\\log\\pods"]
+		}
+	}
 	read_from: {
 		description: "File position to use when reading a new file."
 		required:    false
@@ -397,22 +946,112 @@ base: components: sources: kubernetes_logs: configuration: {
 			default: "beginning"
 			enum: {
 				beginning: "Read from the beginning of the file."
-				end:       "Start reading from the current end of the file."
+				checkpoint_or_end: """
+					Start reading from the current end of the file on the first startup only.
+
+					Files with a checkpoint resume from it. Once checkpoints were persisted by a previous run,
+					files without a checkpoint are new to Vector, so they are read from the beginning.
+					"""
+				end: "Start reading from the current end of the file."
 			}
 		}
 	}
+	read_rotated_files: {
+		description: """
+			Also read the rotations of the Pod log files, including the gzip-compressed ones.
+
+			The kubelet rotates `<n>.log` to `<n>.log.<timestamp>`, and later compresses the rotations
+			to `<n>.log.<timestamp>.gz`, so the lines written while Vector wasn't running may only be
+			left in the rotations. The rotations are read as long as their log file is there, and
+			followed as they are renamed and compressed, so they keep their checkpoints across restarts
+			and their lines aren't read twice. The rotations without a checkpoint, like the ones both
+			rotated and compressed while Vector wasn't running, are read according to `read_from`.
+
+			The compressed rotations are checked against `exclude_paths_glob_patterns` by their path
+			before compression, so they aren't excluded by the default `**/*.gz` pattern.
+
+			The events read from the rotations have the metadata of the Pod log file they belong to.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	self_node_filter: {
+		description: """
+			Only read the log files found in `extra_log_dirs` of the Pods on the Node.
+
+			The Pods are only watched on the Node named by `self_node_name`, so the log files of the
+			Pods themselves are always local. The files found in `extra_log_dirs` are not tied to the
+			Node though, and with this option the Pod UID in the path of each of them is cross-checked
+			against the Pods watched on the Node, and the files of the other Pods are skipped. This
+			prevents collecting the logs of other Nodes a second time when `extra_log_dirs` are shared
+			between Nodes, for example by a misconfigured `hostPath` volume. The files whose path
+			doesn't carry a Pod UID are read as usual.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	self_node_name: {
 		description: """
 			The name of the Kubernetes [Node][node] that is running.
 
 			Configured to use an environment variable by default, to be evaluated to a value provided by
-			Kubernetes at Pod creation.
+			Kubernetes at Pod creation. When running outside of the cluster with a `kube_config_file`,
+			set this to the name of the Node the log files are read from.
 
 			[node]: https://kubernetes.io/docs/concepts/architecture/nodes/
 			"""
 		required: false
 		type: string: default: "${VECTOR_SELF_NODE_NAME}"
 	}
+	spill_buffer: {
+		description: """
+			Configuration for spilling the events to a disk buffer when the components downstream can't
+			keep up.
+
+			The events are held in memory up to `max_events`, and written to a disk buffer under the
+			`data_dir` of the source past that, so a burst of logs on a busy Node uses disk space instead
+			of memory. This is independent of the `buffer` of the sinks.
+
+			The source doesn't support end-to-end acknowledgements, so the checkpoints of the log files
+			still advance as their lines are read. The events on disk are kept across restarts, and sent
+			once the source starts again. With acknowledgements enabled on the sinks, the events are only
+			removed from the disk buffer once they are delivered.
+			"""
+		required: false
+		type: object: options: {
+			max_events: {
+				description: "The maximum number of events held in memory before they are spilled to disk."
+				required:    false
+				type: uint: {
+					default: 10000
+					unit:    "events"
+				}
+			}
+			max_size: {
+				description: """
+					The maximum size of the disk buffer.
+
+					Must be at least ~256 megabytes (268435488 bytes). Once the disk buffer is full, the
+					source stops reading the log files until the components downstream catch up.
+					"""
+				required: true
+				type: uint: unit: "bytes"
+			}
+		}
+	}
+	strict_log_paths: {
+		description: """
+			Only take the files for Pod log files if the Pod UID in their path is well-formed.
+
+			The Pod UID is well-formed if it is an RFC 4122 UUID, the config hashsum of a static Pod,
+			or a `system-<suffix>` UID. The files found in `extra_log_dirs` that follow the Pod log
+			layout, but with a malformed Pod UID, are taken for stray files placed there by other
+			tooling and are not read, and the events of the Pod log files with a malformed Pod UID are
+			not enriched with Pod metadata.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	timezone: {
 		description: "The default time zone for timestamps without an explicit zone."
 		required:    false
@@ -423,4 +1062,17 @@ base: components: sources: kubernetes_logs: configuration: {
 		required:    false
 		type: bool: default: false
 	}
+	use_log_timestamp: {
+		description: """
+			Whether or not to use the timestamp at the beginning of the CRI lines as the timestamp of
+			the events.
+
+			The timestamps are written by the container runtime in the RFC 3339 format, with
+			nanosecond precision, and are converted to UTC. Timestamps without an offset are assumed
+			to be in UTC. When disabled, or when the timestamp of a line can't be parsed, the time the
+			line was read at is used instead.
+			"""
+		required: false
+		type: bool: default: true
+	}
 }
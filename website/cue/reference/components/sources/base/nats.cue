@@ -1,6 +1,27 @@
 package metadata
 
 base: components: sources: nats: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	auth: {
 		description: "Configuration of the authentication strategy when interacting with NATS."
 		required:    false
@@ -326,6 +347,85 @@ base: components: sources: nats: configuration: {
 			}
 		}
 	}
+	jetstream: {
+		description: """
+			Configuration for consuming the messages of a NATS [JetStream][jetstream] stream, instead of
+			subscribing to the subject.
+
+			The messages are pulled by a durable consumer, which keeps track of the acknowledged messages
+			on the server, so the messages published while Vector is down aren't lost. The consumer is
+			created if it doesn't exist, and resumed otherwise, including after reconnecting. Its messages
+			are shared by all the sources pulling from it, instead of using a `queue` group.
+
+			The consumer only receives the messages of the stream whose subject matches `subject`.
+
+			[jetstream]: https://docs.nats.io/nats-concepts/jetstream
+			"""
+		required: false
+		type: object: options: {
+			ack_wait_secs: {
+				description: """
+					The time to wait for a message to be acknowledged before it is redelivered.
+
+					When end-to-end acknowledgements are enabled, a message is only acknowledged once its
+					events are delivered, so this must allow for the time the sinks take to deliver them.
+
+					This only applies to newly created consumers.
+					"""
+				required: false
+				type: uint: default: 30
+			}
+			deliver_policy: {
+				description: """
+					The messages of the stream the consumer starts from, when it is created.
+
+					Resumed consumers continue from their last acknowledged message.
+					"""
+				required: false
+				type: object: options: {
+					policy: {
+						description: "The messages of the stream the consumer starts from."
+						required:    false
+						type: string: {
+							default: "all"
+							enum: {
+								all:           "Start from the first message of the stream."
+								by_start_time: "Start from the first message published at or after `start_time`."
+								new:           "Start from the messages published after the consumer is created."
+							}
+						}
+					}
+					start_time: {
+						description:   "The time to start from."
+						relevant_when: "policy = \"by_start_time\""
+						required:      true
+						type: string: examples: ["2024-01-01T00:00:00Z"]
+					}
+				}
+			}
+			durable_name: {
+				description: "The name of the durable consumer to pull the messages with."
+				required:    true
+				type: string: examples: ["vector"]
+			}
+			metadata_key: {
+				description: """
+					The metadata key to add the JetStream metadata of the messages under.
+
+					The metadata holds the sequence of the message in the stream (`stream_sequence`) and in
+					the consumer (`consumer_sequence`), and the number of times it has been delivered
+					(`delivered`).
+					"""
+				required: false
+				type: string: default: "jetstream"
+			}
+			stream: {
+				description: "The name of the stream to consume the messages of."
+				required:    true
+				type: string: examples: ["events"]
+			}
+		}
+	}
 	queue: {
 		description: "The NATS queue group to join."
 		required:    false
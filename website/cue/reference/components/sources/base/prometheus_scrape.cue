@@ -59,15 +59,19 @@ base: components: sources: prometheus_scrape: configuration: {
 	}
 	endpoints: {
 		description: "Endpoints to scrape metrics from."
-		required:    true
-		type: array: items: type: string: examples: ["http://localhost:9090/metrics"]
+		required:    false
+		type: array: {
+			default: []
+			items: type: string: examples: ["http://localhost:9090/metrics"]
+		}
 	}
 	honor_labels: {
 		description: """
 			Controls how tag conflicts are handled if the scraped source has tags to be added.
 
 			If `true`, the new tag is not added if the scraped metric has the tag already. If `false`, the conflicting tag
-			is renamed by prepending `exported_` to the original name.
+			is renamed by prepending `exported_` to the original name. This also applies to the labels of the
+			discovered targets.
 
 			This matches Prometheus’ `honor_labels` configuration.
 			"""
@@ -103,6 +107,79 @@ base: components: sources: prometheus_scrape: configuration: {
 			}
 		}
 	}
+	relabel: {
+		description: """
+			Rules relabeling the scraped metrics, applied in order before the metrics leave the
+			source.
+
+			This matches Prometheus’ `metric_relabel_configs` configuration.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				action: {
+					description: "The action to perform."
+					required:    false
+					type: string: {
+						default: "replace"
+						enum: {
+							drop:      "Drops the metrics for which `regex` matches the values of the source labels."
+							keep:      "Drops the metrics for which `regex` doesn't match the values of the source labels."
+							labeldrop: "Removes the tags with a name matched by `regex`."
+							labelkeep: "Removes the tags with a name not matched by `regex`."
+							replace:   "Sets `target_label` to `replacement` if `regex` matches the values of the source labels."
+						}
+					}
+				}
+				regex: {
+					description: """
+						The regular expression matched against the joined values of the source labels, or against
+						the tag names with the `labeldrop` and `labelkeep` actions.
+
+						The regular expression is anchored at both ends.
+						"""
+					required: false
+					type: string: {
+						default: "(.*)"
+						examples: ["go_.*", "(.*):\\d+"]
+					}
+				}
+				replacement: {
+					description: """
+						The value set to `target_label` with the `replace` action.
+
+						The `$1`, `${1}`, or `$name` references are replaced with the capture groups of `regex`.
+						The label is removed if the value is empty.
+						"""
+					required: false
+					type: string: default: "$1"
+				}
+				separator: {
+					description: "The separator placed between the values of the source labels."
+					required:    false
+					type: string: default: ";"
+				}
+				source_labels: {
+					description: """
+						The labels whose values are joined with `separator` and matched against `regex`.
+
+						A missing label has an empty value.
+						"""
+					required: false
+					type: array: {
+						default: []
+						items: type: string: examples: ["__name__", "job"]
+					}
+				}
+				target_label: {
+					description: "The label set with the `replace` action."
+					required:    false
+					type: string: examples: ["instance"]
+				}
+			}
+		}
+	}
 	scrape_interval_secs: {
 		description: """
 			The interval between scrapes. Requests are run concurrently so if a scrape takes longer
@@ -123,6 +200,43 @@ base: components: sources: prometheus_scrape: configuration: {
 			unit:    "seconds"
 		}
 	}
+	service_discovery: {
+		description: "Configuration for discovering the targets to scrape, in addition to the `endpoints`."
+		required:    false
+		type: object: options: http: {
+			description: """
+				Configuration for discovering the targets over HTTP.
+
+				This matches Prometheus’ `http_sd_configs` configuration. The URL must return a JSON array of
+				target groups, like `[{"targets": ["10.0.0.1:9100"], "labels": {"job": "node"}}]`.
+
+				The targets are scraped at `/metrics` over HTTP, unless the `__scheme__` or `__metrics_path__`
+				labels of their group say otherwise. The other labels of the group are added as tags to the
+				metrics scraped from its targets, except for the ones starting with `__`.
+				"""
+			required: true
+			type: object: options: {
+				refresh_interval_secs: {
+					description: """
+						The interval between the requests for the targets.
+
+						The targets no longer returned are no longer scraped after the next request. If a request
+						fails, the targets returned by the latest successful one are still scraped.
+						"""
+					required: false
+					type: uint: {
+						default: 60
+						unit:    "seconds"
+					}
+				}
+				url: {
+					description: "The URL returning the targets to scrape."
+					required:    true
+					type: string: examples: ["http://localhost:8080/targets"]
+				}
+			}
+		}
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -1,8 +1,29 @@
 package metadata
 
 base: components: sources: redis: configuration: {
+	acknowledgements: {
+		deprecated: true
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level.
+
+			Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how event acknowledgement is handled.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	data_type: {
-		description: "The Redis data type (`list` or `channel`) to use."
+		description: "The Redis data type (`list`, `channel` or `stream`) to use."
 		required:    false
 		type: string: {
 			default: "list"
@@ -13,6 +34,12 @@ base: components: sources: redis: configuration: {
 					This is based on Redis' Pub/Sub capabilities.
 					"""
 				list: "The `list` data type."
+				stream: """
+					The `stream` data type.
+
+					The entries of the stream are read as a member of a consumer group, so that the
+					consumers of the group share them.
+					"""
 			}
 		}
 	}
@@ -274,6 +301,80 @@ base: components: sources: redis: configuration: {
 		required: false
 		type: string: examples: ["redis_key"]
 	}
+	stream: {
+		description: """
+			Options for the Redis `stream` data type.
+
+			When end-to-end acknowledgements are enabled, the entries are only acknowledged with `XACK`
+			once their events are delivered, and otherwise stay pending to be claimed again. Without, they
+			are acknowledged once their events are sent.
+			"""
+		required: false
+		type: object: options: {
+			block_ms: {
+				description: "How long to block waiting for new entries, in milliseconds."
+				required:    false
+				type: uint: {
+					default: 5000
+					unit:    "milliseconds"
+				}
+			}
+			claim_idle_ms: {
+				description: """
+					The time after which the pending entries of a consumer are claimed, in milliseconds.
+
+					The entries delivered to a consumer stay pending until they are acknowledged. When set,
+					the entries pending for longer than this, like the ones of a consumer that is gone, are
+					reclaimed with `XAUTOCLAIM` and read again.
+
+					By default, the pending entries of other consumers aren't claimed.
+					"""
+				required: false
+				type: uint: {
+					examples: [60000]
+					unit: "milliseconds"
+				}
+			}
+			consumer_name: {
+				description: """
+					The name of the consumer within the group.
+
+					The `{{ hostname }}` template is replaced by the hostname of the machine Vector runs on,
+					so that each instance reads as a distinct consumer, and reclaims its own pending entries
+					after a restart.
+					"""
+				required: false
+				type: string: {
+					default: "{{ hostname }}"
+					examples: ["vector-{{ hostname }}"]
+				}
+			}
+			create_group: {
+				description: """
+					Whether to create the consumer group, and the stream, if they don't exist.
+
+					The group is created with `MKSTREAM`, so that it reads the entries added to the stream
+					from then on.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			group: {
+				description: "The consumer group to read the entries of the stream as."
+				required:    true
+				type: string: examples: ["vector"]
+			}
+			id_key: {
+				description: """
+					Sets the name of the log field to use to add the ID of the stream entry to each event.
+
+					By default, this is not set and the field is not automatically added.
+					"""
+				required: false
+				type: string: examples: ["id"]
+			}
+		}
+	}
 	url: {
 		description: """
 			The Redis URL to connect to.
@@ -311,6 +311,21 @@ base: components: sources: socket: configuration: {
 		required:      true
 		type: string: examples: ["/path/to/socket"]
 	}
+	peer_credentials_key: {
+		description: """
+			Overrides the name of the log field used to add the credentials of the peer process to
+			each event.
+
+			The value is an object with the `peer_pid`, `peer_uid` and `peer_gid` fields of the
+			process that sent the message. The credentials are only available in the `unix_datagram`
+			mode, on Linux.
+
+			By default, the credentials aren't added to the events.
+			"""
+		relevant_when: "mode = \"unix_datagram\" or mode = \"unix_stream\""
+		required:      false
+		type: string: examples: ["peer_credentials"]
+	}
 	port_key: {
 		description: """
 			Overrides the name of the log field used to add the peer host's port to each event.
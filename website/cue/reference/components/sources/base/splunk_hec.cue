@@ -198,9 +198,25 @@ base: components: sources: splunk_hec: configuration: {
 			If supplied, incoming requests must supply one of these tokens in the `Authorization` header, just as a client
 			would if it was communicating with the Splunk HEC endpoint directly.
 
+			The tokens can also be named, with a map of the names to the tokens. The name of the token a request was
+			authorized with is then added to its events, in the `splunk_hec_token_name` field.
+
 			If _not_ supplied, the `Authorization` header is ignored and requests are not authenticated.
 			"""
 		required: false
-		type: array: items: type: string: examples: ["A94A8FE5CCB19BA61C4C08"]
+		type: {
+			array: items: type: string: examples: ["A94A8FE5CCB19BA61C4C08"]
+			object: {
+				examples: [{
+					team_a: "A94A8FE5CCB19BA61C4C08"
+					team_b: "B85B9FE6DDC20CB72D5D19"
+				}]
+				options: "*": {
+					description: "A named token."
+					required:    true
+					type: string: {}
+				}
+			}
+		}
 	}
 }
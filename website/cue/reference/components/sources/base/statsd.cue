@@ -18,6 +18,18 @@ base: components: sources: statsd: configuration: {
 		required:      false
 		type: uint: unit: "connections"
 	}
+	container_id_tag: {
+		description: """
+			The name of the tag to add the container ID of the DogStatsD metrics under.
+
+			The container ID is sent by the DogStatsD clients in the `|c:<container-id>` section.
+			"""
+		required: false
+		type: string: {
+			default: "container_id"
+			examples: ["container_id"]
+		}
+	}
 	keepalive: {
 		description:   "TCP keepalive settings for socket-based components."
 		relevant_when: "mode = \"tcp\""
@@ -18,6 +18,28 @@ base: components: sources: syslog: configuration: {
 		required:      false
 		type: uint: {}
 	}
+	framing: {
+		description: """
+			Framing of the messages received over TCP, as described in [RFC 6587][rfc6587].
+
+			[rfc6587]: https://datatracker.ietf.org/doc/html/rfc6587#section-3.4
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					Detect the framing of each connection from its first bytes.
+
+					A run of digits followed by a space selects octet counting for the connection, anything
+					else selects non-transparent framing.
+					"""
+				non_transparent: "Non-transparent framing, each message is terminated by a newline."
+				octet_counting:  "Octet counting, each message is prefixed with its length in bytes and a space."
+			}
+		}
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to add the peer host to each event.
@@ -94,6 +116,20 @@ base: components: sources: syslog: configuration: {
 		required:      false
 		type: uint: {}
 	}
+	timezone: {
+		description: """
+			The name of the timezone to apply to the timestamps that do not contain an explicit time
+			zone, like the RFC 3164 ones.
+
+			This overrides the [global `timezone`][global_timezone] option. The time zone name may be
+			any name in the [TZ database][tz_database], or `local` to indicate system local time.
+
+			[global_timezone]: https://vector.dev/docs/reference/configuration//global-options#timezone
+			[tz_database]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
+			"""
+		required: false
+		type: string: examples: ["local", "America/New_York", "EST5EDT"]
+	}
 	tls: {
 		description:   "TlsEnableableConfig for `sources`, adding metadata from the client certificate."
 		relevant_when: "mode = \"tcp\""
@@ -31,6 +31,45 @@ base: components: sources: vector: configuration: {
 		required: true
 		type: string: {}
 	}
+	max_connections: {
+		description: """
+			The maximum number of connections open at any given time.
+
+			The requests on the connections opened beyond the limit are rejected with the
+			`RESOURCE_EXHAUSTED` gRPC status, until the connection is closed.
+			"""
+		required: false
+		type: uint: {
+			examples: [1000]
+			unit: "connections"
+		}
+	}
+	max_connections_per_peer: {
+		description: """
+			The maximum number of connections open from the same IP address at any given time.
+
+			The requests on the connections opened beyond the limit are rejected with the
+			`RESOURCE_EXHAUSTED` gRPC status, until the connection is closed.
+			"""
+		required: false
+		type: uint: {
+			examples: [10]
+			unit: "connections"
+		}
+	}
+	max_event_size_bytes: {
+		description: """
+			The maximum size of an event, as encoded by the sending Vector instance.
+
+			The batches with events larger than the limit are rejected as a whole with the
+			`INVALID_ARGUMENT` gRPC status, before the events are converted.
+			"""
+		required: false
+		type: uint: {
+			examples: [1048576]
+			unit: "bytes"
+		}
+	}
 	tls: {
 		description: "Configures the TLS options for incoming/outgoing connections."
 		required:    false
@@ -94,6 +94,13 @@ components: sources: docker_logs: {
 		log: {
 			description: "A Docker log event"
 			fields: {
+				compose: {
+					description: "The Docker Compose project and service of the container, set from its `com.docker.compose.project` and `com.docker.compose.service` labels."
+					required:    false
+					type: object: {
+						examples: [{"project": "shop", "service": "web"}]
+					}
+				}
 				container_created_at: {
 					description: "A UTC timestamp representing when the container was created."
 					required:    true
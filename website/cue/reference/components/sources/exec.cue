@@ -83,6 +83,63 @@ components: sources: exec: {
 		}
 	}
 
+	output: logs: exit_status: {
+		description: "The exit status of the command, emitted after its output if `emit_exit_status` is enabled."
+		fields: {
+			host:      fields._local_host
+			timestamp: fields._current_timestamp
+			exit_status: {
+				description: "The exit code of the command, or `null` if it was killed by a signal."
+				required:    true
+				type: uint: {
+					examples: [0, 1]
+					unit: null
+				}
+			}
+			signal: {
+				description: "The number of the signal the command was killed by, on *nix platforms."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [9, 15]
+					unit: null
+				}
+			}
+			duration_seconds: {
+				description: "The execution time of the command."
+				required:    true
+				type: float: {
+					examples: [0.25, 12.5]
+					unit: "seconds"
+				}
+			}
+			pid: {
+				description: "The process ID of the command."
+				required:    true
+				type: uint: {
+					examples: [60085, 668]
+					unit: null
+				}
+			}
+			command: {
+				required:    true
+				description: "The command that was run."
+				type: array: {
+					items: type: string: {
+						examples: ["echo", "Hello World!", "ls", "-la"]
+					}
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["exec"]
+				}
+			}
+		}
+	}
+
 	examples: [
 		{
 			_line:      "64 bytes from 127.0.0.1: icmp_seq=0 ttl=64 time=0.060 ms"
@@ -129,5 +186,6 @@ components: sources: exec: {
 	telemetry: metrics: {
 		command_executed_total:             components.sources.internal_metrics.output.metrics.command_executed_total
 		command_execution_duration_seconds: components.sources.internal_metrics.output.metrics.command_execution_duration_seconds
+		skipped_runs_total:                 components.sources.internal_metrics.output.metrics.skipped_runs_total
 	}
 }
@@ -123,19 +123,23 @@ components: sources: file: {
 			title: "Compressed Files"
 			body: """
 				Vector will transparently detect files which have been compressed
-				using Gzip and decompress them for reading. This detection process
-				looks for the unique sequence of bytes in the Gzip header and does
-				not rely on the compressed files adhering to any kind of naming
-				convention.
-
-				One caveat with reading compressed files is that Vector is not able
-				to efficiently seek into them. Rather than implement a
-				potentially-expensive full scan as a seek mechanism, Vector
-				currently will not attempt to make further reads from a file for
-				which it has already stored a checkpoint in a previous run. For
-				this reason, users should take care to allow Vector to fully
-				process any compressed files before shutting the process down or moving the
-				files to another location on disk.
+				using Gzip or Zstandard and decompress them for reading. This
+				detection process looks for the unique sequence of bytes in the
+				header of each format and does not rely on the compressed files
+				adhering to any kind of naming convention. The `compression` option
+				can be used to disable the detection, or to decompress all files
+				with a given format.
+
+				Compressed files are fingerprinted by their uncompressed contents,
+				so a file that is compressed by `logrotate` after being rotated is
+				identified as the file Vector was already reading, and the lines
+				written between the last read and the rotation are still read.
+				Checkpoints of compressed files refer to the offset in the
+				uncompressed data. Since Vector is not able to efficiently seek
+				into compressed files, resuming from a checkpoint decompresses and
+				skips the data up to it. Compressed files are not expected to be
+				appended to, so once the end of one is reached, it is not read
+				again.
 				"""
 		}
 
@@ -224,6 +228,13 @@ components: sources: file: {
 				This strategy avoids the common pitfalls associated with using device and inode
 				names since inode names can be reused across files. This enables Vector to properly
 				tail files across various rotation strategies.
+
+				When files share identical leading lines, like a common preamble header, their
+				checksums collide. In that case, setting `fingerprint.strategy` to `device_and_inode`
+				(or its alias `inode`) identifies files by their inode instead. Files renamed by a
+				rotation are then followed to their new name, and files truncated in place by a
+				`copytruncate` rotation are read from the beginning again once they are found to be
+				shorter than the offset read up to.
 				"""
 		}
 
@@ -423,11 +434,14 @@ components: sources: file: {
 	}
 
 	telemetry: metrics: {
-		checkpoints_total:     components.sources.internal_metrics.output.metrics.checkpoints_total
-		checksum_errors_total: components.sources.internal_metrics.output.metrics.checksum_errors_total
-		files_added_total:     components.sources.internal_metrics.output.metrics.files_added_total
-		files_deleted_total:   components.sources.internal_metrics.output.metrics.files_deleted_total
-		files_resumed_total:   components.sources.internal_metrics.output.metrics.files_resumed_total
-		files_unwatched_total: components.sources.internal_metrics.output.metrics.files_unwatched_total
+		checkpoints_total:         components.sources.internal_metrics.output.metrics.checkpoints_total
+		checksum_errors_total:     components.sources.internal_metrics.output.metrics.checksum_errors_total
+		file_archive_errors_total: components.sources.internal_metrics.output.metrics.file_archive_errors_total
+		files_added_total:         components.sources.internal_metrics.output.metrics.files_added_total
+		files_archived_total:      components.sources.internal_metrics.output.metrics.files_archived_total
+		files_deleted_total:       components.sources.internal_metrics.output.metrics.files_deleted_total
+		files_resumed_total:       components.sources.internal_metrics.output.metrics.files_resumed_total
+		files_throttled:           components.sources.internal_metrics.output.metrics.files_throttled
+		files_unwatched_total:     components.sources.internal_metrics.output.metrics.files_unwatched_total
 	}
 }
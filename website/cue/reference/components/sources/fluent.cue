@@ -163,15 +163,37 @@ components: sources: fluent: {
 		secure_mode: {
 			title: "Secure forward mode support"
 			body:  """
-				The `fluent` source currently supports using TLS, but does not support the authentication part of the
-				Fluent protocol including:
+				The `fluent` source supports TLS, and the authentication part of the Fluent protocol, with the
+				`shared_key` and `users` options. When `shared_key` is set, the clients must perform the handshake of the
+				forward protocol before sending any message, proving they know the key, and also authenticate with the
+				username and password of one of the `users` if set. The connections of the clients failing to
+				authenticate are closed.
 
-				- Shared key
-				- Username and password
+				For example, to authenticate Fluentd with a shared key:
 
-				And so these options of the secure forward output plugins for Fluent and Fluent Bit cannot be used.
+				```text
+					<match *>
+					  @type forward
+					  transport tls
+					  <security>
+						self_hostname fluentd
+						shared_key    secret
+					  </security>
+					  <server>
+						host 127.0.0.1
+						port 24224
+					  </server>
+					</match>
+				```
+				"""
+		}
 
-				If you would find this useful, [please let us know](\(urls.vector_repo)/issues/7532).
+		compression: {
+			title: "Compression"
+			body:  """
+				The gzip compressed messages of the `compress gzip` option of Fluentd are decompressed. A message
+				failing to decompress is counted as an error and skipped, and the following messages of the connection
+				are still received.
 				"""
 		}
 	}
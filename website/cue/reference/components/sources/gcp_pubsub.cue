@@ -127,5 +127,22 @@ components: sources: gcp_pubsub: {
 					without opening up extra connections at startup.
 				"""
 		}
+		exactly_once_delivery: {
+			title: "Exactly-once delivery"
+			body: """
+				At startup, the `gcp_pubsub` source fetches the subscription to detect whether it has
+				[exactly-once delivery](https://cloud.google.com/pubsub/docs/exactly-once-delivery)
+				enabled, and logs which delivery mode is active. This requires the
+				`pubsub.subscriptions.get` permission; without it, the source falls back to at-least-once
+				delivery until the first streaming pull response reports the subscription properties.
+
+				With exactly-once delivery enabled, the acknowledgement deadline of the messages whose
+				events are still being processed is extended halfway through the `ack_deadline_secs`
+				deadline, so slow sinks don't cause them to be redelivered. The acknowledgements the
+				server doesn't confirm are retried with an exponential backoff until their deadline
+				expires. Acknowledgements the server rejects as invalid are reported as errors and not
+				retried, as the messages are redelivered.
+				"""
+		}
 	}
 }
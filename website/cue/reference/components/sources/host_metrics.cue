@@ -116,6 +116,32 @@ components: sources: host_metrics: {
 		cgroup_memory_anon_bytes:        _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup in anonymous mappings (normal program allocation), in bytes."}
 		cgroup_memory_file_bytes:        _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup to cache filesystem data, including tmpfs and shared memory, in bytes."}
 
+		// Host cgroups pressure stall information
+		cgroup_cpu_pressure_some_avg10:            _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on CPU, as a percentage averaged over 10 seconds."}
+		cgroup_cpu_pressure_some_avg60:            _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on CPU, as a percentage averaged over 60 seconds."}
+		cgroup_cpu_pressure_some_avg300:           _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on CPU, as a percentage averaged over 300 seconds."}
+		cgroup_cpu_pressure_some_seconds_total:    _host & _cgroup_pressure_counter & {description: "The total amount of time at least some tasks of this cgroup stalled on CPU, in seconds."}
+		cgroup_cpu_pressure_full_avg10:            _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on CPU, as a percentage averaged over 10 seconds."}
+		cgroup_cpu_pressure_full_avg60:            _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on CPU, as a percentage averaged over 60 seconds."}
+		cgroup_cpu_pressure_full_avg300:           _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on CPU, as a percentage averaged over 300 seconds."}
+		cgroup_cpu_pressure_full_seconds_total:    _host & _cgroup_pressure_counter & {description: "The total amount of time all non-idle tasks of this cgroup stalled on CPU, in seconds."}
+		cgroup_io_pressure_some_avg10:             _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on IO, as a percentage averaged over 10 seconds."}
+		cgroup_io_pressure_some_avg60:             _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on IO, as a percentage averaged over 60 seconds."}
+		cgroup_io_pressure_some_avg300:            _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on IO, as a percentage averaged over 300 seconds."}
+		cgroup_io_pressure_some_seconds_total:     _host & _cgroup_pressure_counter & {description: "The total amount of time at least some tasks of this cgroup stalled on IO, in seconds."}
+		cgroup_io_pressure_full_avg10:             _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on IO, as a percentage averaged over 10 seconds."}
+		cgroup_io_pressure_full_avg60:             _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on IO, as a percentage averaged over 60 seconds."}
+		cgroup_io_pressure_full_avg300:            _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on IO, as a percentage averaged over 300 seconds."}
+		cgroup_io_pressure_full_seconds_total:     _host & _cgroup_pressure_counter & {description: "The total amount of time all non-idle tasks of this cgroup stalled on IO, in seconds."}
+		cgroup_memory_pressure_some_avg10:         _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on memory, as a percentage averaged over 10 seconds."}
+		cgroup_memory_pressure_some_avg60:         _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on memory, as a percentage averaged over 60 seconds."}
+		cgroup_memory_pressure_some_avg300:        _host & _cgroup_pressure_gauge & {description:   "The share of time at least some tasks of this cgroup stalled on memory, as a percentage averaged over 300 seconds."}
+		cgroup_memory_pressure_some_seconds_total: _host & _cgroup_pressure_counter & {description: "The total amount of time at least some tasks of this cgroup stalled on memory, in seconds."}
+		cgroup_memory_pressure_full_avg10:         _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on memory, as a percentage averaged over 10 seconds."}
+		cgroup_memory_pressure_full_avg60:         _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on memory, as a percentage averaged over 60 seconds."}
+		cgroup_memory_pressure_full_avg300:        _host & _cgroup_pressure_gauge & {description:   "The share of time all non-idle tasks of this cgroup stalled on memory, as a percentage averaged over 300 seconds."}
+		cgroup_memory_pressure_full_seconds_total: _host & _cgroup_pressure_counter & {description: "The total amount of time all non-idle tasks of this cgroup stalled on memory, in seconds."}
+
 		// Host disk
 		disk_read_bytes_total:       _host & _disk_counter & {description: "The accumulated number of bytes read in."}
 		disk_reads_completed_total:  _host & _disk_counter & {description: "The accumulated number of read operations completed."}
@@ -167,6 +193,12 @@ components: sources: host_metrics: {
 		network_transmit_packets_drop_total: _host & _network_nomac & {description: "The number of packets dropped during transmits on this interface."}
 		network_transmit_packets_total:      _host & _network_nomac & {description: "The number of packets transmitted on this interface."}
 
+		// Host processes
+		process_cpu_seconds_total:     _host & _process_counter & {description: "The total amount of CPU time used by the process, in seconds."}
+		process_open_fds:              _host & _process_gauge & {description:   "The number of file descriptors open by the process, reported when they can be listed."}
+		process_resident_memory_bytes: _host & _process_gauge & {description:   "The amount of resident memory used by the process, in bytes."}
+		process_threads:               _host & _process_gauge & {description:   "The number of threads of the process."}
+
 		// Helpers
 		_host: {
 			default_namespace: "host"
@@ -186,6 +218,14 @@ components: sources: host_metrics: {
 				cgroup: _cgroup_name
 			}
 		}
+		_cgroup_pressure: {
+			tags: _host_metrics_tags & {
+				collector: examples: ["cgroups"]
+				cgroup: _cgroup_name
+			}
+		}
+		_cgroup_pressure_counter: _cgroup_pressure & {type: "counter"}
+		_cgroup_pressure_gauge:   _cgroup_pressure & {type: "gauge"}
 		_cgroup_name: {
 			description: "The control group name."
 			required:    true
@@ -256,5 +296,23 @@ components: sources: host_metrics: {
 			}
 		}
 		_network_nomac: _network_gauge & {relevant_when: "OS is not macOS"}
+		_process: {
+			tags: _host_metrics_tags & {
+				collector: examples: ["process"]
+				name: {
+					description: "The name of the process."
+					required:    true
+					examples: ["vector", "postgres"]
+				}
+				pid: {
+					description: "The PID of the process, unless `process.pid_tag` is disabled."
+					required:    false
+					examples: ["1234"]
+				}
+			}
+			relevant_when: "OS is Linux"
+		}
+		_process_counter: _process & {type: "counter"}
+		_process_gauge:   _process & {type: "gauge"}
 	}
 }
@@ -178,9 +178,10 @@ components: sources: http_server: {
 	]
 
 	telemetry: metrics: {
-		http_server_handler_duration_seconds: components.sources.internal_metrics.output.metrics.http_server_handler_duration_seconds
-		http_server_requests_received_total:  components.sources.internal_metrics.output.metrics.http_server_requests_received_total
-		http_server_responses_sent_total:     components.sources.internal_metrics.output.metrics.http_server_responses_sent_total
+		http_server_handler_duration_seconds:   components.sources.internal_metrics.output.metrics.http_server_handler_duration_seconds
+		http_server_requests_received_total:    components.sources.internal_metrics.output.metrics.http_server_requests_received_total
+		http_server_responses_sent_total:       components.sources.internal_metrics.output.metrics.http_server_responses_sent_total
+		http_server_signature_rejections_total: components.sources.internal_metrics.output.metrics.http_server_signature_rejections_total
 	}
 
 	how_it_works: {
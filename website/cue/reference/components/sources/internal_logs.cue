@@ -131,5 +131,17 @@ components: sources: internal_logs: {
 				command-line options. The `internal_logs` source only receives logs that are generated by these options.
 				"""
 		}
+
+		filtering: {
+			title: "Filtering and rate limiting"
+			body: """
+				The `min_level`, `exclude_components`, and `rate_limit_per_component` options drop
+				internal logs in the source itself, before they are sent to the components
+				downstream, so a noisy component doesn't load the pipeline processing the internal
+				logs. When the rate limit of a component drops internal logs, an event with the
+				`WARN` level reports how many were suppressed, in the `suppressed` field, once the
+				second ends.
+				"""
+		}
 	}
 }
@@ -72,6 +72,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		amqp_failed_messages_total: {
+			description:       "The total number of AMQP messages that failed to be decoded or delivered, by how they were disposed of."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				disposition: {
+					description: "How the message was disposed of, as configured with `on_decode_error`."
+					required:    true
+					enum: {
+						ack:            "The message was acknowledged."
+						reject:         "The message was rejected without being requeued."
+						reject_requeue: "The message was rejected and requeued."
+					}
+				}
+				reason: {
+					description: "Why the message was disposed of."
+					required:    true
+					enum: {
+						decode_error:     "The message failed to be decoded."
+						delivery_failure: "The events of the message failed to be delivered."
+					}
+				}
+			}
+		}
 		api_started_total: {
 			description:       "The number of times the Vector GraphQL API has been started."
 			type:              "counter"
@@ -217,18 +241,109 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		k8s_format_detection_fallbacks_total: {
+			description:       "The total number of Kubernetes log files whose format could not be detected, and which are parsed in the CRI format."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		k8s_format_picker_edge_cases_total: {
 			description:       "The total number of edge cases encountered while picking format of the Kubernetes log message."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		k8s_kubelet_metadata_fallback_lookups_total: {
+			description:       "The total number of events annotated with Pod metadata obtained from the kubelet, while the Pod watch stream was disconnected."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		k8s_metadata_watch_reconnects_total: {
+			description:       "The total number of times a Kubernetes API watch stream of the metadata was reconnected, after an error or after it was dropped."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				resource: {
+					description: "The kind of the watched resource, one of `pod`, `namespace` or `node`."
+					required:    true
+				}
+			}
+		}
+		k8s_delayed_deletion_lookups_total: {
+			description:       "The total number of events annotated with the metadata of a Pod whose deletion from the metadata cache was delayed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		k8s_docker_format_parse_failures_total: {
 			description:       "The total number of failures to parse a message as a JSON object."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		k8s_log_paths_unparseable_total: {
+			description:       "The total number of discovered Kubernetes log file paths that did not match the expected layout, so that their events could not be attributed to a Pod. Each path is counted once, when it is first discovered."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		k8s_logs_read_bytes_total: {
+			description:       "The total number of bytes read from the Pod log files."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				pod_namespace: {
+					description: "The namespace of the pod the log file belongs to."
+					required:    false
+				}
+				pod_name: {
+					description: "The name of the pod the log file belongs to."
+					required:    false
+				}
+				container_name: {
+					description: "The name of the container the log file belongs to."
+					required:    false
+				}
+			}
+		}
+		k8s_logs_read_lines_total: {
+			description:       "The total number of lines read from the Pod log files."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				pod_namespace: {
+					description: "The namespace of the pod the log file belongs to."
+					required:    false
+				}
+				pod_name: {
+					description: "The name of the pod the log file belongs to."
+					required:    false
+				}
+				container_name: {
+					description: "The name of the container the log file belongs to."
+					required:    false
+				}
+			}
+		}
+		k8s_node_mismatch_files_skipped_total: {
+			description:       "The total number of Kubernetes log files found in `extra_log_dirs` skipped by `self_node_filter` because their Pod is not on the Node."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		k8s_partial_line_overflows_total: {
+			description:       "The total number of merged partial events emitted early, because they exceeded `max_merged_line_bytes`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		k8s_terminated_container_files_closed_total: {
+			description:       "The total number of Kubernetes log files closed because their container has been terminated for longer than `close_terminated_after_secs`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		k8s_reflector_desyncs_total: {
 			description:       "The total number of desyncs for the reflector."
 			type:              "counter"
@@ -510,6 +625,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              internal_metrics_cardinality.tags
 		}
+		kafka_paused_partitions: {
+			description:       "Current number of partitions whose fetching is paused, because `max_in_flight_events` is exceeded."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		kafka_queue_messages: {
 			description:       "Current number of messages in producer queues."
 			type:              "gauge"
@@ -585,6 +706,14 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		file_archive_errors_total: {
+			description:       "The total number of errors encountered when moving files to the `archive_to_dir` directory."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _file
+			}
+		}
 		files_added_total: {
 			description:       "The total number of files Vector has found to watch."
 			type:              "counter"
@@ -593,6 +722,14 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		files_archived_total: {
+			description:       "The total number of files moved to the `archive_to_dir` directory."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _file
+			}
+		}
 		files_deleted_total: {
 			description:       "The total number of files deleted."
 			type:              "counter"
@@ -609,6 +746,12 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		files_throttled: {
+			description:       "The number of files whose reads were throttled by `max_read_bytes_per_second` in the last read pass."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
 		files_unwatched_total: {
 			description:       "The total number of times Vector has stopped watching a file."
 			type:              "counter"
@@ -701,6 +844,22 @@ components: sources: internal_metrics: {
 				status: _status
 			}
 		}
+		http_server_signature_rejections_total: {
+			description:       "The total number of HTTP requests rejected by the signature verification."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "The reason the request was rejected."
+					required:    true
+					enum: {
+						"invalid_signature":   "The signature doesn't match the body of the request."
+						"malformed_signature": "The signature isn't prefixed as configured or isn't hex encoded."
+						"missing_signature":   "The request has no signature header."
+					}
+				}
+			}
+		}
 		http_server_handler_duration_seconds: {
 			description:       "The duration spent handling a HTTP request."
 			type:              "histogram"
@@ -735,18 +894,36 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		mqtt_connected: {
+			description:       "Whether the `mqtt` source is connected to the broker, `1` when connected and `0` otherwise."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		open_connections: {
 			description:       "The number of current open connections to Vector."
 			type:              "gauge"
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		oversized_events_total: {
+			description:       "The total number of events rejected for being larger than the maximum size."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		protobuf_decode_errors_total: {
 			description:       "The total number of [Protocol Buffers](\(urls.protobuf)) errors thrown during communication between Vector instances."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		rejected_connections_total: {
+			description:       "The total number of connections rejected for being beyond the connection limits."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		request_duration_seconds: {
 			description:       "The total request duration in seconds."
 			type:              "histogram"
@@ -771,6 +948,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		skipped_runs_total: {
+			description:       "The total number of scheduled command runs skipped, as the previous run of the command was still executing."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		source_lag_time_seconds: {
 			description:       "The difference between the timestamp recorded in each event and the time when it was ingested, expressed as fractional seconds."
 			type:              "histogram"
@@ -908,6 +1091,12 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		unix_socket_datagrams_truncated_total: {
+			description:       "The total number of datagrams truncated because they were longer than the maximum length."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		value_limit_reached_total: {
 			description: """
 				The total number of times new values for a key have been rejected because the
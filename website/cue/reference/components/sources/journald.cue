@@ -148,5 +148,16 @@ components: sources: journald: {
 				are replaced with the Unicode replacement character, `�`.
 				"""
 		}
+		remote_journal: {
+			title: "Remote Journals"
+			body: """
+				Instead of spawning `journalctl`, Vector can stream the journal from a
+				[`systemd-journal-gatewayd`](https://www.freedesktop.org/software/systemd/man/systemd-journal-gatewayd.service.html)
+				instance, set with the `remote_url` option. The entries are read in the journal
+				export format and decoded into the same events as the local ones. The cursor of
+				the last entry read is checkpointed in the `data_dir`, and Vector resumes from it
+				when it reconnects after the connection is lost.
+				"""
+		}
 	}
 }
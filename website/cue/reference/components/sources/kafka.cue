@@ -98,6 +98,7 @@ components: sources: kafka: {
 		kafka_consumed_messages_total:       components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
 		kafka_consumed_messages_bytes_total: components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
 		kafka_consumer_lag:                  components.sources.internal_metrics.output.metrics.kafka_consumer_lag
+		kafka_paused_partitions:             components.sources.internal_metrics.output.metrics.kafka_paused_partitions
 	}
 
 	how_it_works: components._kafka.how_it_works
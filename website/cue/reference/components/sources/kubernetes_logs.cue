@@ -96,6 +96,15 @@ components: sources: kubernetes_logs: {
 					examples: ["busybox@sha256:1e7b63c09af457b93c17d25ef4e6aee96b5bb95f087840cffd7c4bb2fe8ae5c6"]
 				}
 			}
+			"kubernetes.container_kind": {
+				description: "Container kind, one of `init`, `ephemeral`, or `regular`, as found in the Pod spec."
+				required:    false
+				common:      false
+				type: string: {
+					default: null
+					examples: ["init", "regular"]
+				}
+			}
 			"kubernetes.container_name": {
 				description: "Container name."
 				required:    false
@@ -105,6 +114,16 @@ components: sources: kubernetes_logs: {
 					examples: ["coredns"]
 				}
 			}
+			"kubernetes.container_restart_count": {
+				description: "Container restart count, as encoded in the log file name."
+				required:    false
+				common:      false
+				type: uint: {
+					default: null
+					unit:    null
+					examples: [0, 3]
+				}
+			}
 			"kubernetes.namespace_labels": {
 				description: "Set of labels attached to the Namespace."
 				required:    false
@@ -114,6 +133,24 @@ components: sources: kubernetes_logs: {
 					options: {}
 				}
 			}
+			"kubernetes.node_name": {
+				description: "The name of the Node the event was collected on."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["minikube"]
+				}
+			}
+			"kubernetes.pod_dir_extra_segments": {
+				description: "The segments following the Pod UID in the name of the Pod logs directory, appended by some distributions."
+				required:    false
+				common:      false
+				type: array: {
+					default: null
+					items: type: string: examples: ["zone-a", "pool0"]
+				}
+			}
 			"kubernetes.pod_ip": {
 				description: "Pod IPv4 address."
 				required:    false
@@ -195,6 +232,21 @@ components: sources: kubernetes_logs: {
 					examples: ["ba46d8c9-9541-4f6b-bbf9-d23b36f2f136"]
 				}
 			}
+			"kubernetes.stream": {
+				description: "The name of the stream the log line was submitted to, set unless `extract_stream` is disabled."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["stdout", "stderr"]
+				}
+			}
+			"kubernetes.truncated": {
+				description: "Set to `true` if the line was truncated at `max_line_bytes`."
+				required:    false
+				common:      false
+				type: bool: default: null
+			}
 			message: {
 				description: "The raw line from the Pod log file."
 				required:    true
@@ -269,6 +321,21 @@ components: sources: kubernetes_logs: {
 					Vector will enrich data with Kubernetes context. A comprehensive
 					list of fields can be found in the
 					[`kubernetes_logs` source output docs](\(urls.vector_kubernetes_logs_source)#output-data).
+
+					The metadata is only available once the Pods, Namespaces and Node are listed from
+					the Kubernetes API. At startup, the events are held back until the lists are
+					complete, for at most `metadata.warmup_timeout_ms`, so the first events read from
+					every Pod are enriched too.
+
+					All the Pod labels and annotations are added by default. They can be limited to
+					the keys listed in `metadata.include_label_keys` and
+					`metadata.include_annotation_keys`, to keep the number of distinct fields down,
+					and added as single dotted fields, like `pod_labels.app.kubernetes.io/name`,
+					with `metadata.flatten`.
+
+					The log files are matched to their Pods by namespace, name and UID. The static
+					Pods, like the control plane components, are matched by namespace and name only
+					when their UID differs from the one of their mirror Pod in the Kubernetes API.
 					"""
 		}
 
@@ -286,6 +353,8 @@ components: sources: kubernetes_logs: {
 				* The `extra_label_selector` option specifies the label selector to
 				  filter Pods with, to be used in addition to the [built-in
 				  `vector.dev/exclude` filter](#pod-exclusion).
+				* The `include_pod_phases` option skips the log files of the Pods
+				  in the other phases, like the completed Jobs.
 				"""
 		}
 
@@ -361,7 +430,12 @@ components: sources: kubernetes_logs: {
 			title: "Partial message merging"
 			body:  """
 					Vector, by default, will merge partial messages that are
-					split due to the Docker size limit. For everything else, it
+					split due to the Docker size limit, or flagged as partial
+					(`P`) by a CRI container runtime, with the lines that follow
+					them up to the one that completes them. The lines are merged
+					separately for the `stdout` and `stderr` streams of a
+					container, and the size of a merged message can be capped
+					with `max_merged_line_bytes`. For everything else, it
 					is recommended to use the [`reduce`
 					transform](\(urls.vector_reduce_transform)) which offers
 					the ability to handle custom merging of things like
@@ -369,6 +443,18 @@ components: sources: kubernetes_logs: {
 					"""
 		}
 
+		file_discovery_events: {
+			title: "File discovery events"
+			body: """
+				With `emit_file_discovered_events` enabled, an event with `kubernetes.event` set to
+				`file_discovered` is emitted when a Pod log file is first discovered, before any of
+				its lines are read. The event carries the namespace, name, and UID of the Pod, the
+				name and restart count of the container, and the sandbox attempt extracted from the
+				path of the file, in the same fields as the log events. This allows tracking when the
+				containers start logging, even if they don't log anything for a while.
+				"""
+		}
+
 		pod_removal: {
 			title: "Pod removal"
 			body: """
@@ -406,6 +492,23 @@ components: sources: kubernetes_logs: {
 						have a more straightforward pipeline, you may need less.
 						"""
 				},
+				{
+					title: "Spilling to disk"
+					body: """
+						When the sinks can't keep up with a burst of logs, the events waiting to be sent
+						take up memory until the memory limit is reached. With `spill_buffer` configured,
+						the source holds up to `spill_buffer.max_events` events in memory, and writes the
+						rest to a disk buffer under its `data_dir`, up to `spill_buffer.max_size`, so the
+						burst uses disk space instead. Once the disk buffer is full, the source stops
+						reading the log files until the sinks catch up.
+
+						The source doesn't support end-to-end acknowledgements: the checkpoints of the log
+						files advance as their lines are read, whether the events are in memory or on disk.
+						The events on disk are kept across restarts, and sent once the source starts again,
+						while the events in memory are lost if Vector crashes. With acknowledgements enabled
+						on the sinks, the events are only removed from the disk buffer once delivered.
+						"""
+				},
 			]
 		}
 
@@ -467,16 +570,26 @@ components: sources: kubernetes_logs: {
 	}
 
 	telemetry: metrics: {
-		k8s_format_picker_edge_cases_total:     components.sources.internal_metrics.output.metrics.k8s_format_picker_edge_cases_total
-		k8s_docker_format_parse_failures_total: components.sources.internal_metrics.output.metrics.k8s_docker_format_parse_failures_total
-		k8s_reflector_desyncs_total:            components.sources.internal_metrics.output.metrics.k8s_reflector_desyncs_total
-		k8s_state_ops_total:                    components.sources.internal_metrics.output.metrics.k8s_state_ops_total
-		k8s_stream_chunks_processed_total:      components.sources.internal_metrics.output.metrics.k8s_stream_chunks_processed_total
-		k8s_stream_processed_bytes_total:       components.sources.internal_metrics.output.metrics.k8s_stream_processed_bytes_total
-		k8s_watch_requests_invoked_total:       components.sources.internal_metrics.output.metrics.k8s_watch_requests_invoked_total
-		k8s_watch_requests_failed_total:        components.sources.internal_metrics.output.metrics.k8s_watch_requests_failed_total
-		k8s_watch_stream_failed_total:          components.sources.internal_metrics.output.metrics.k8s_watch_stream_failed_total
-		k8s_watch_stream_items_obtained_total:  components.sources.internal_metrics.output.metrics.k8s_watch_stream_items_obtained_total
-		k8s_watcher_http_error_total:           components.sources.internal_metrics.output.metrics.k8s_watcher_http_error_total
+		k8s_format_detection_fallbacks_total:        components.sources.internal_metrics.output.metrics.k8s_format_detection_fallbacks_total
+		k8s_format_picker_edge_cases_total:          components.sources.internal_metrics.output.metrics.k8s_format_picker_edge_cases_total
+		k8s_delayed_deletion_lookups_total:          components.sources.internal_metrics.output.metrics.k8s_delayed_deletion_lookups_total
+		k8s_docker_format_parse_failures_total:      components.sources.internal_metrics.output.metrics.k8s_docker_format_parse_failures_total
+		k8s_kubelet_metadata_fallback_lookups_total: components.sources.internal_metrics.output.metrics.k8s_kubelet_metadata_fallback_lookups_total
+		k8s_log_paths_unparseable_total:             components.sources.internal_metrics.output.metrics.k8s_log_paths_unparseable_total
+		k8s_logs_read_bytes_total:                   components.sources.internal_metrics.output.metrics.k8s_logs_read_bytes_total
+		k8s_logs_read_lines_total:                   components.sources.internal_metrics.output.metrics.k8s_logs_read_lines_total
+		k8s_metadata_watch_reconnects_total:         components.sources.internal_metrics.output.metrics.k8s_metadata_watch_reconnects_total
+		k8s_node_mismatch_files_skipped_total:       components.sources.internal_metrics.output.metrics.k8s_node_mismatch_files_skipped_total
+		k8s_partial_line_overflows_total:            components.sources.internal_metrics.output.metrics.k8s_partial_line_overflows_total
+		k8s_reflector_desyncs_total:                 components.sources.internal_metrics.output.metrics.k8s_reflector_desyncs_total
+		k8s_state_ops_total:                         components.sources.internal_metrics.output.metrics.k8s_state_ops_total
+		k8s_stream_chunks_processed_total:           components.sources.internal_metrics.output.metrics.k8s_stream_chunks_processed_total
+		k8s_stream_processed_bytes_total:            components.sources.internal_metrics.output.metrics.k8s_stream_processed_bytes_total
+		k8s_terminated_container_files_closed_total: components.sources.internal_metrics.output.metrics.k8s_terminated_container_files_closed_total
+		k8s_watch_requests_invoked_total:            components.sources.internal_metrics.output.metrics.k8s_watch_requests_invoked_total
+		k8s_watch_requests_failed_total:             components.sources.internal_metrics.output.metrics.k8s_watch_requests_failed_total
+		k8s_watch_stream_failed_total:               components.sources.internal_metrics.output.metrics.k8s_watch_stream_failed_total
+		k8s_watch_stream_items_obtained_total:       components.sources.internal_metrics.output.metrics.k8s_watch_stream_items_obtained_total
+		k8s_watcher_http_error_total:                components.sources.internal_metrics.output.metrics.k8s_watcher_http_error_total
 	}
 }
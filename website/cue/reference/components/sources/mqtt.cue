@@ -0,0 +1,128 @@
+package metadata
+
+components: sources: mqtt: {
+	title: "MQTT"
+
+	features: {
+		auto_generated:   true
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.mqtt
+				interface: {
+					socket: {
+						api: {
+							title: "MQTT protocol"
+							url:   urls.mqtt
+						}
+						direction: "incoming"
+						port:      1883
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+			tls: {
+				enabled:                true
+				can_verify_certificate: false
+				can_verify_hostname:    false
+				enabled_default:        false
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	support: {
+		requirements: []
+		notices: []
+		warnings: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: base.components.sources.mqtt.configuration
+
+	output: logs: record: {
+		description: "An individual MQTT message."
+		fields: {
+			message: {
+				description: "The payload of the MQTT message."
+				required:    true
+				type: string: {
+					examples: ["21.5"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["mqtt"]
+				}
+			}
+			timestamp: fields._current_timestamp
+			topic: {
+				description: "The topic the MQTT message was published to."
+				required:    true
+				type: string: {
+					examples: ["sensors/kitchen/temp"]
+				}
+			}
+		}
+	}
+
+	telemetry: metrics: {
+		mqtt_connected: components.sources.internal_metrics.output.metrics.mqtt_connected
+	}
+
+	how_it_works: {
+		topics: {
+			title: "Topic filters"
+			body: """
+				The source subscribes to each of the [topic filters](\(urls.mqtt_topics)) of `topics`,
+				which can include the `+` single-level wildcard, as in `sensors/+/temp`, and the `#`
+				multi-level wildcard, as in `devices/#`. The concrete topic each message was published
+				to is added to its events under `topic_key`.
+				"""
+		}
+		sessions: {
+			title: "Sessions and reconnection"
+			body: """
+				When the connection to the broker drops, the source reconnects and subscribes to the
+				topics again, so no configuration change is needed when the broker restarts. The
+				`mqtt_connected` gauge tracks whether the source is currently connected.
+
+				With `clean_start` enabled, the default, each connection starts a new session, and the
+				messages published while the source is disconnected are lost. With `clean_start`
+				disabled, the broker keeps the session of the `client_id` across the connections, and
+				delivers the messages published with a QoS of at least 1 in the meantime once the
+				source reconnects.
+				"""
+		}
+		acknowledgements: {
+			title: "Acknowledgements"
+			body: """
+				With `qos` set to `at_least_once`, the default, each message is acknowledged once its
+				events are sent, or with end-to-end acknowledgements enabled, once they are delivered.
+				MQTT has no negative acknowledgements, so the messages whose events fail to be
+				delivered are left unacknowledged, and redelivered by the broker when the session is
+				resumed.
+				"""
+		}
+	}
+}
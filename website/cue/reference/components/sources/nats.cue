@@ -5,7 +5,7 @@ components: sources: nats: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			from: components._nats.features.collect.from
@@ -58,6 +58,13 @@ components: sources: nats: {
 					examples: ["nats"]
 				}
 			}
+			jetstream: {
+				description: "The JetStream metadata of the message, when consuming a `jetstream` stream."
+				required:    false
+				type: object: {
+					examples: [{"stream_sequence": 42, "consumer_sequence": 7, "delivered": 1}]
+				}
+			}
 			subject: {
 				description: "The subject from the NATS message."
 				required:    true
@@ -68,5 +75,22 @@ components: sources: nats: {
 		}
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body: """
+				With `jetstream` configured, the `nats` source pulls the messages of the stream with a
+				durable consumer, so the messages published while Vector is down are received once it
+				is back. The pull consumer only fetches more messages as Vector processes them.
+
+				Each message is acknowledged once its events are sent, or with end-to-end
+				acknowledgements enabled, once they are delivered. Messages whose events fail to be
+				delivered are negatively acknowledged, so JetStream redelivers them. Messages that
+				aren't acknowledged within `ack_wait_secs` are redelivered as well.
+
+				On reconnection, the source resumes the same durable consumer, which only delivers
+				the messages that haven't been acknowledged yet.
+				"""
+		}
+	}
 }
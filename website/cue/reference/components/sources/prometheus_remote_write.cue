@@ -73,6 +73,28 @@ components: sources: prometheus_remote_write: {
 				"""
 		}
 
+		protocol_versions: {
+			title: "Remote Write versions"
+			body: """
+				This source accepts both the Remote Write 1.0 and 2.0 requests, snappy
+				compressed in both cases. The version of a request is negotiated from the
+				`proto` parameter of its `Content-Type` header, set to `prometheus.WriteRequest`
+				for 1.0 and to `io.prometheus.write.v2.Request` for 2.0, falling back on the
+				`X-Prometheus-Remote-Write-Version` header. The requests setting neither are
+				decoded as 1.0 requests, and the requests for any other message are rejected with
+				a `415 Unsupported Media Type` response.
+
+				The type of the metrics is given by the metadata of each series in the 2.0
+				requests. Their help and unit, given by the metadata of both versions, are kept
+				in the `prometheus.help` and `prometheus.unit` fields of the metadata of the
+				metric events, and exposed again by the `prometheus_exporter` and
+				`prometheus_remote_write` sinks. The created timestamps of the 2.0 series are
+				emitted as `<name>_created` gauges, in seconds, as in the OpenMetrics exposition
+				format. The native histograms and the exemplars aren't supported, and are
+				dropped.
+				"""
+		}
+
 		duplicate_tag_names: {
 			title: "Duplicate tag names"
 			body: """
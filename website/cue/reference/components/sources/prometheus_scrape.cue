@@ -66,6 +66,37 @@ components: sources: prometheus_scrape: {
 				but will only take the last value for each tag name specified.
 				"""
 		}
+		relabeling: {
+			title: "Relabeling"
+			body: """
+				The `relabel` rules follow Prometheus’ `metric_relabel_configs`. They are applied
+				to every scraped metric in order, after the `instance_tag` and `endpoint_tag` tags
+				are added, so they can rewrite or remove those too. The name of the metric is
+				available as the `__name__` label.
+
+				The `replace` action sets `target_label`, the `keep` and `drop` actions filter the
+				metrics, and the `labeldrop` and `labelkeep` actions remove tags by name. A metric
+				dropped by a rule isn't passed to the following rules.
+				"""
+		}
+
+		service_discovery: {
+			title: "Service discovery"
+			body: """
+				With `service_discovery.http` set, the targets are requested from its `url` every
+				`refresh_interval_secs`, and scraped along with the `endpoints`. The URL must return
+				the target groups of Prometheus’ HTTP service discovery, and the labels of the groups
+				are added as tags to the metrics scraped from their targets.
+
+				The targets missing from a response stop being scraped from the next scrape interval
+				on. If a request fails, the targets of the latest successful response are still
+				scraped, and the `component_errors_total` internal metric is incremented.
+
+				With service discovery, the scrapes are spread across the scrape interval: each target
+				is scraped at a fixed offset within the interval, derived from its URL, instead of all
+				the targets being scraped at once.
+				"""
+		}
 	}
 
 	output: metrics: {
@@ -5,7 +5,7 @@ components: sources: redis: {
 
 	features: {
 		auto_generated:   true
-		acknowledgements: false
+		acknowledgements: true
 		collect: {
 			checkpoint: enabled: false
 			tls: enabled:        false
@@ -86,6 +86,22 @@ components: sources: redis: {
 	}
 
 	how_it_works: {
+		streams: {
+			title: "Redis Streams"
+			body:  """
+				With the `stream` data type, the entries of the stream are read with `XREADGROUP` as a
+				consumer of the `stream.group` consumer group, which is created with `MKSTREAM` if it
+				doesn't exist. Each field of an entry becomes a field of its event, and the
+				timestamp of the event is taken from the ID of the entry. The `framing` and `decoding`
+				options don't apply to the entries.
+
+				The entries read stay pending until they are acknowledged with `XACK`, once their events
+				are delivered when end-to-end acknowledgements are enabled. On start, the entries still
+				pending for the consumer are read again first. With `stream.claim_idle_ms`, the entries
+				pending for longer than that for any consumer of the group, like one that is gone, are
+				claimed with `XAUTOCLAIM` and read again.
+				"""
+		}
 		redis_rs: {
 			title: "redis-rs"
 			body:  """
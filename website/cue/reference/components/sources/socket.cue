@@ -111,8 +111,9 @@ components: sources: socket: {
 	]
 
 	telemetry: metrics: {
-		connection_established_total: components.sources.internal_metrics.output.metrics.connection_established_total
-		connection_send_errors_total: components.sources.internal_metrics.output.metrics.connection_send_errors_total
-		connection_shutdown_total:    components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		connection_established_total:          components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_send_errors_total:          components.sources.internal_metrics.output.metrics.connection_send_errors_total
+		connection_shutdown_total:             components.sources.internal_metrics.output.metrics.connection_shutdown_total
+		unix_socket_datagrams_truncated_total: components.sources.internal_metrics.output.metrics.unix_socket_datagrams_truncated_total
 	}
 }
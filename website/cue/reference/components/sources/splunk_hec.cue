@@ -74,6 +74,34 @@ components: sources: splunk_hec: {
 					examples: ["splunk_hec"]
 				}
 			}
+			splunk_hec_token_name: {
+				description: "The name of the token the request was authorized with, if it's named in `valid_tokens`."
+				required:    false
+				type: string: {
+					examples: ["team_a"]
+				}
+			}
+			splunk_index: {
+				description: "The Splunk index, from the `index` field of the event, or the `index` query parameter of the raw endpoint."
+				required:    false
+				type: string: {
+					examples: ["main"]
+				}
+			}
+			splunk_source: {
+				description: "The Splunk source, from the `source` field of the event, or the `source` query parameter of the raw endpoint."
+				required:    false
+				type: string: {
+					examples: ["/var/log/syslog"]
+				}
+			}
+			splunk_sourcetype: {
+				description: "The Splunk sourcetype, from the `sourcetype` field of the event, or the `sourcetype` query parameter of the raw endpoint."
+				required:    false
+				type: string: {
+					examples: ["_json"]
+				}
+			}
 			timestamp: fields._current_timestamp
 		}
 	}
@@ -93,5 +121,18 @@ components: sources: splunk_hec: {
 				To summarize the protocol, each request to the source is associated with an integer identifier (an ack id) that the client is given and can use to query for the status of the request.
 				"""
 		}
+		routing: {
+			title: "Routing by token and index"
+			body: """
+				The tokens in `valid_tokens` can be named, with a map of the names to the tokens, such as
+				`valid_tokens = { team_a = "xxxx", team_b = "yyyy" }`. The name of the token a request was authorized
+				with is added to its events, in the `splunk_hec_token_name` field, to route the events of each token.
+
+				The `index`, `source`, and `sourcetype` fields of the events are kept in the `splunk_index`,
+				`splunk_source`, and `splunk_sourcetype` fields, or in the `splunk_hec` metadata with the Vector log
+				namespace. The raw endpoint takes them from the query parameters of the same names. They are passed on
+				to a `splunk_hec_logs` sink with its templates, such as `index = "{{ splunk_index }}"`.
+				"""
+		}
 	}
 }
@@ -74,6 +74,20 @@ components: sources: statsd: {
 				`null` timestamps are substituted with the current time by downstream sinks or
 				third-party services during sending/ingestion. See the
 				[metric data model](\(urls.vector_metric)) page for more info.
+
+				The metrics sent by the DogStatsD clients with a `|T<timestamp>` section, in seconds
+				since the Unix epoch, are assigned that timestamp instead.
+				"""
+		}
+		dogstatsd_extensions: {
+			title: "DogStatsD extensions"
+			body:  """
+				Besides the sample rate and the tags, the DogStatsD clients can send the ID of the
+				container the metric originates from, in a `|c:<container-id>` section. The ID is
+				added to the metric as a tag, named with the `container_id_tag` option.
+
+				The sections following the metric type are accepted in any order, and the ones the
+				source doesn't know about, like the `|e:<external-data>` section, are ignored.
 				"""
 		}
 	}
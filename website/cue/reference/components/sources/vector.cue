@@ -103,6 +103,9 @@ components: sources: vector: {
 		grpc_server_handler_duration_seconds: components.sources.internal_metrics.output.metrics.grpc_server_handler_duration_seconds
 		grpc_server_messages_received_total:  components.sources.internal_metrics.output.metrics.grpc_server_messages_received_total
 		grpc_server_messages_sent_total:      components.sources.internal_metrics.output.metrics.grpc_server_messages_sent_total
+		open_connections:                     components.sources.internal_metrics.output.metrics.open_connections
+		oversized_events_total:               components.sources.internal_metrics.output.metrics.oversized_events_total
 		protobuf_decode_errors_total:         components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
+		rejected_connections_total:           components.sources.internal_metrics.output.metrics.rejected_connections_total
 	}
 }
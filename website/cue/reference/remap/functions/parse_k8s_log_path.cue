@@ -0,0 +1,83 @@
+package metadata
+
+remap: functions: parse_k8s_log_path: {
+	category:    "Parse"
+	description: """
+		Parses the path of a Kubernetes pod log file, as written by the kubelet below
+		`/var/log/pods`, into the pod and container it belongs to. The path is parsed the same way
+		as by the `kubernetes_logs` source.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The path of the pod log file."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "delimiter"
+			description: "The delimiter between the components of the pod logs directory name."
+			required:    false
+			type: ["string"]
+			default: "_"
+		},
+		{
+			name:        "layout"
+			description: "The layout the log file is located in."
+			required:    false
+			type: ["string"]
+			default: "pods"
+			enum: {
+				pods:       "The `<pod_logs_root>/<namespace>_<name>_<uid>/<container>/<n>.log` pod log files."
+				containers: "The `<container_logs_root>/<name>_<namespace>_<container>-<id>.log` container log symlinks."
+				auto:       "The pod log files, falling back to the container log symlinks."
+			}
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is not the path of a log file in the `layout`.",
+		"`delimiter` is not a single character.",
+	]
+	return: {
+		types: ["object"]
+		rules: [
+			"The `pod_uid` is empty for the container log symlinks, which don't carry it.",
+			"The `restart_count` is `null` if the log file name doesn't carry one.",
+			"The `sandbox_attempt` is `null` if the pod logs directory name doesn't carry one.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Parse a pod log file path"
+			source: #"""
+				parse_k8s_log_path!("/var/log/pods/default_nginx_b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d/nginx/0.log")
+				"""#
+			return: {
+				pod_namespace:   "default"
+				pod_name:        "nginx"
+				pod_uid:         "b7c2d5a0-3f1e-4d2a-9c8b-6e5f4a3b2c1d"
+				container_name:  "nginx"
+				file_name:       "0.log"
+				restart_count:   0
+				sandbox_attempt: null
+			}
+		},
+		{
+			title: "Parse a container log symlink path"
+			source: #"""
+				parse_k8s_log_path!("/var/log/containers/nginx_default_nginx-8a3c1e4f.log", layout: "containers")
+				"""#
+			return: {
+				pod_namespace:   "default"
+				pod_name:        "nginx"
+				pod_uid:         ""
+				container_name:  "nginx"
+				file_name:       "nginx_default_nginx-8a3c1e4f.log"
+				restart_count:   null
+				sandbox_attempt: null
+			}
+		},
+	]
+}